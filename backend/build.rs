@@ -0,0 +1,55 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// tapd's gRPC services, generated from `proto/taprpc`
+const PROTOS: &[&str] = &[
+    "proto/taprpc/taprootassets.proto",
+    "proto/taprpc/mintrpc/mint.proto",
+    "proto/taprpc/assetwalletrpc/assetwallet.proto",
+];
+
+/// Stamps the binary with the commit it was built from and when, for
+/// `GET /api/version`. `GIT_COMMIT` overrides the checkout's commit, for
+/// builds without a `.git` directory such as Docker's.
+fn main() {
+    compile_protos();
+
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+
+    let commit = std::env::var("GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.trim().is_empty())
+        .or_else(|| {
+            let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+        });
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=GIT_COMMIT={}", commit.trim());
+    }
+
+    // Reproducible builds pin the timestamp
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .or_else(|| Some(SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs()));
+    if let Some(timestamp) = timestamp {
+        println!("cargo:rustc-env=BUILD_TIMESTAMP={timestamp}");
+    }
+}
+
+/// Generates tapd's gRPC clients with the vendored `protoc`, so builds need no
+/// system protobuf compiler
+fn compile_protos() {
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("No vendored protoc for this host");
+    std::env::set_var("PROTOC", protoc);
+    tonic_build::configure()
+        .build_server(false)
+        .compile_protos(PROTOS, &["proto/taprpc"])
+        .expect("Failed to compile tapd protos");
+}
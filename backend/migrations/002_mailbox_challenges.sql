@@ -0,0 +1,9 @@
+-- Create mailbox_challenges table for shared auth challenge storage
+CREATE TABLE IF NOT EXISTS mailbox_challenges (
+    challenge_id VARCHAR(255) PRIMARY KEY,
+    timestamp BIGINT NOT NULL,
+    nonce TEXT NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_mailbox_challenges_expires_at ON mailbox_challenges(expires_at);
@@ -0,0 +1,10 @@
+-- Create mailbox_receivers table for registered mailbox receivers
+CREATE TABLE IF NOT EXISTS mailbox_receivers (
+    receiver_id VARCHAR(255) PRIMARY KEY,
+    public_key VARCHAR(130) NOT NULL,
+    address TEXT,
+    created_at BIGINT NOT NULL,
+    last_seen BIGINT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    metadata TEXT
+);
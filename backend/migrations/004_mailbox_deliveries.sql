@@ -0,0 +1,7 @@
+-- Track mailbox messages acknowledged by their receivers
+CREATE TABLE IF NOT EXISTS mailbox_deliveries (
+    receiver_id VARCHAR(255) NOT NULL,
+    message_id VARCHAR(255) NOT NULL,
+    delivered_at BIGINT NOT NULL,
+    PRIMARY KEY (receiver_id, message_id)
+);
@@ -0,0 +1,3 @@
+-- Optional webhook delivery for offline mailbox receivers
+ALTER TABLE mailbox_receivers ADD COLUMN IF NOT EXISTS webhook_url TEXT;
+ALTER TABLE mailbox_receivers ADD COLUMN IF NOT EXISTS webhook_secret TEXT;
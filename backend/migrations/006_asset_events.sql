@@ -0,0 +1,9 @@
+-- Persist asset mint/receive/send events so reconnecting clients can backfill
+CREATE TABLE IF NOT EXISTS asset_events (
+    cursor BIGSERIAL PRIMARY KEY,
+    event_type VARCHAR(32) NOT NULL,
+    payload JSONB NOT NULL,
+    received_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_events_type_cursor ON asset_events(event_type, cursor);
@@ -0,0 +1,4 @@
+-- Taproot Assets addresses can exceed 255 characters; index them for event correlation
+ALTER TABLE transactions ALTER COLUMN destination TYPE TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_type_destination ON transactions(tx_type, destination);
@@ -0,0 +1,9 @@
+-- Mobile push tokens and the Taproot Assets addresses each device watches
+CREATE TABLE IF NOT EXISTS notification_devices (
+    token TEXT PRIMARY KEY,
+    platform VARCHAR(16) NOT NULL,
+    addresses TEXT[] NOT NULL,
+    registered_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_devices_addresses ON notification_devices USING GIN (addresses);
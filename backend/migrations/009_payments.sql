@@ -0,0 +1,17 @@
+-- Outgoing asset payments initiated through the gateway
+CREATE TABLE IF NOT EXISTS payments (
+    id UUID PRIMARY KEY,
+    payment_request TEXT,
+    payment_hash VARCHAR(64),
+    asset_id VARCHAR(64) NOT NULL,
+    asset_amount BIGINT NOT NULL,
+    rfq_id TEXT,
+    status VARCHAR(16) NOT NULL,
+    fee_msat BIGINT NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_payments_created_at ON payments(created_at);
+CREATE INDEX IF NOT EXISTS idx_payments_asset_status ON payments(asset_id, status);
@@ -0,0 +1,16 @@
+-- RFQ buy/sell offers and orders submitted through the gateway
+CREATE TABLE IF NOT EXISTS rfq_orders (
+    id UUID PRIMARY KEY,
+    kind VARCHAR(16) NOT NULL,
+    asset_id VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    rfq_id TEXT,
+    peer VARCHAR(66),
+    request JSONB NOT NULL,
+    response JSONB,
+    failure_reason TEXT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rfq_orders_created_at ON rfq_orders(created_at);
+CREATE INDEX IF NOT EXISTS idx_rfq_orders_asset_status ON rfq_orders(asset_id, status);
@@ -0,0 +1,2 @@
+-- Amount delivered by each payment, used to derive effective exchange rates
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS value_msat BIGINT NOT NULL DEFAULT 0;
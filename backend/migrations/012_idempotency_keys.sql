@@ -0,0 +1,13 @@
+-- Results of sends, mints and burns keyed by the client's Idempotency-Key
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    scope VARCHAR(32) NOT NULL,
+    key VARCHAR(255) NOT NULL,
+    request_hash CHAR(64) NOT NULL,
+    -- NULL while the first request is still running
+    status INTEGER,
+    response JSONB,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (scope, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,4 @@
+-- Free-text labels attached to sends and invoices, filterable in history
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS label TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_label ON transactions(label);
@@ -0,0 +1,9 @@
+-- Assets a profile marked as favorite or hidden in asset listings
+CREATE TABLE IF NOT EXISTS asset_preferences (
+    profile VARCHAR(64) NOT NULL,
+    asset_id CHAR(64) NOT NULL,
+    favorite BOOLEAN NOT NULL DEFAULT FALSE,
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (profile, asset_id)
+);
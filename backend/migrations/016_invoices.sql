@@ -0,0 +1,11 @@
+-- Invoices created through the gateway, by payment hash
+CREATE TABLE IF NOT EXISTS invoices (
+    payment_hash CHAR(64) PRIMARY KEY,
+    payment_request TEXT NOT NULL,
+    asset_id VARCHAR(64) NOT NULL,
+    asset_amount BIGINT NOT NULL,
+    label TEXT,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_created_at ON invoices(created_at);
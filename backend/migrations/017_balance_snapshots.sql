@@ -0,0 +1,10 @@
+-- Per-asset balances sampled into hourly and daily buckets; the latest sample
+-- in a bucket wins
+CREATE TABLE IF NOT EXISTS balance_snapshots (
+    asset_id CHAR(64) NOT NULL,
+    granularity VARCHAR(8) NOT NULL,
+    bucket_start BIGINT NOT NULL,
+    balance BIGINT NOT NULL,
+    recorded_at BIGINT NOT NULL,
+    PRIMARY KEY (asset_id, granularity, bucket_start)
+);
@@ -0,0 +1,6 @@
+-- App and user preferences kept server-side, by key
+CREATE TABLE IF NOT EXISTS settings (
+    key VARCHAR(64) PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_at BIGINT NOT NULL
+);
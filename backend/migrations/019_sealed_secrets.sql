@@ -0,0 +1,6 @@
+-- Secrets sealed with SECRETS_MASTER_KEY outgrow the hex-sized column
+ALTER TABLE webhooks ALTER COLUMN secret TYPE TEXT;
+
+-- With encryption enabled, push tokens are keyed by their HMAC and the token
+-- itself is kept sealed
+ALTER TABLE notification_devices ADD COLUMN IF NOT EXISTS sealed_token TEXT;
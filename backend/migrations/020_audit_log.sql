@@ -0,0 +1,27 @@
+-- Sends, mints, burns, channel changes and admin actions, as requested and
+-- answered; rows can only be added
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID PRIMARY KEY,
+    occurred_at BIGINT NOT NULL,
+    actor VARCHAR(32) NOT NULL,
+    ip TEXT,
+    forwarded_for TEXT,
+    action VARCHAR(32) NOT NULL,
+    method VARCHAR(16) NOT NULL,
+    path TEXT NOT NULL,
+    summary JSONB NOT NULL,
+    status INTEGER NOT NULL,
+    result VARCHAR(16) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,17 @@
+-- One-shot alerts on an asset's oracle rate registered through /rfq/alerts
+CREATE TABLE IF NOT EXISTS price_alerts (
+    id UUID PRIMARY KEY,
+    asset_id VARCHAR(64) NOT NULL,
+    direction VARCHAR(8) NOT NULL,
+    threshold DOUBLE PRECISION NOT NULL,
+    webhook_url TEXT,
+    -- Sealed with SECRETS_MASTER_KEY when encryption is enabled
+    webhook_secret TEXT,
+    created_at BIGINT NOT NULL,
+    triggered_at BIGINT,
+    triggered_rate DOUBLE PRECISION
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_alerts_asset_id ON price_alerts(asset_id);
+CREATE INDEX IF NOT EXISTS idx_price_alerts_pending
+    ON price_alerts(created_at) WHERE triggered_at IS NULL;
@@ -0,0 +1,150 @@
+-- Schema for single-file SQLite deployments, matching the Postgres migrations
+-- in the parent directory. UUIDs are stored as 16-byte BLOBs, JSON values and
+-- arrays as TEXT, and timestamps as TEXT or Unix seconds like their Postgres
+-- counterparts.
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id BLOB PRIMARY KEY,
+    tx_type TEXT NOT NULL,
+    asset_id TEXT,
+    amount INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    destination TEXT,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    label TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_asset_id ON transactions(asset_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_created_at ON transactions(created_at);
+CREATE INDEX IF NOT EXISTS idx_transactions_type_destination ON transactions(tx_type, destination);
+CREATE INDEX IF NOT EXISTS idx_transactions_label ON transactions(label);
+
+-- Expiry in Unix milliseconds
+CREATE TABLE IF NOT EXISTS mailbox_challenges (
+    challenge_id TEXT PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    nonce TEXT NOT NULL,
+    expires_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_mailbox_challenges_expires_at ON mailbox_challenges(expires_at);
+
+CREATE TABLE IF NOT EXISTS mailbox_receivers (
+    receiver_id TEXT PRIMARY KEY,
+    public_key TEXT NOT NULL,
+    address TEXT,
+    created_at INTEGER NOT NULL,
+    last_seen INTEGER NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    metadata TEXT,
+    webhook_url TEXT,
+    webhook_secret TEXT
+);
+
+CREATE TABLE IF NOT EXISTS mailbox_deliveries (
+    receiver_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    delivered_at INTEGER NOT NULL,
+    PRIMARY KEY (receiver_id, message_id)
+);
+
+CREATE TABLE IF NOT EXISTS asset_events (
+    cursor INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    received_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_events_type_cursor ON asset_events(event_type, cursor);
+
+-- Watched addresses as a JSON array
+CREATE TABLE IF NOT EXISTS notification_devices (
+    token TEXT PRIMARY KEY,
+    platform TEXT NOT NULL,
+    addresses TEXT NOT NULL,
+    registered_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS payments (
+    id BLOB PRIMARY KEY,
+    payment_request TEXT,
+    payment_hash TEXT,
+    asset_id TEXT NOT NULL,
+    asset_amount INTEGER NOT NULL,
+    rfq_id TEXT,
+    status TEXT NOT NULL,
+    value_msat INTEGER NOT NULL DEFAULT 0,
+    fee_msat INTEGER NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_payments_created_at ON payments(created_at);
+CREATE INDEX IF NOT EXISTS idx_payments_asset_status ON payments(asset_id, status);
+
+CREATE TABLE IF NOT EXISTS rfq_orders (
+    id BLOB PRIMARY KEY,
+    kind TEXT NOT NULL,
+    asset_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    rfq_id TEXT,
+    peer TEXT,
+    request TEXT NOT NULL,
+    response TEXT,
+    failure_reason TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rfq_orders_created_at ON rfq_orders(created_at);
+CREATE INDEX IF NOT EXISTS idx_rfq_orders_asset_status ON rfq_orders(asset_id, status);
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    -- NULL while the first request is still running
+    status INTEGER,
+    response TEXT,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (scope, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+-- Subscribed events as a JSON array
+CREATE TABLE IF NOT EXISTS webhooks (
+    id BLOB PRIMARY KEY,
+    url TEXT NOT NULL,
+    events TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id BLOB PRIMARY KEY,
+    webhook_id BLOB NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL,
+    last_error TEXT,
+    created_at INTEGER NOT NULL,
+    delivered_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
+
+CREATE TABLE IF NOT EXISTS asset_preferences (
+    profile TEXT NOT NULL,
+    asset_id TEXT NOT NULL,
+    favorite BOOLEAN NOT NULL DEFAULT FALSE,
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (profile, asset_id)
+);
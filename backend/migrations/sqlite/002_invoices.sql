@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS invoices (
+    payment_hash TEXT PRIMARY KEY,
+    payment_request TEXT NOT NULL,
+    asset_id TEXT NOT NULL,
+    asset_amount INTEGER NOT NULL,
+    label TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_created_at ON invoices(created_at);
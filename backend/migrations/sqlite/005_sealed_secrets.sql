@@ -0,0 +1,3 @@
+-- With encryption enabled, push tokens are keyed by their HMAC and the token
+-- itself is kept sealed
+ALTER TABLE notification_devices ADD COLUMN sealed_token TEXT;
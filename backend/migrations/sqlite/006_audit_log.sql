@@ -0,0 +1,27 @@
+-- Sends, mints, burns, channel changes and admin actions, as requested and
+-- answered; rows can only be added
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BLOB PRIMARY KEY,
+    occurred_at INTEGER NOT NULL,
+    actor TEXT NOT NULL,
+    ip TEXT,
+    forwarded_for TEXT,
+    action TEXT NOT NULL,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    result TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS idx_mailbox_deliveries_delivered_at
+    ON mailbox_deliveries(delivered_at);
+CREATE INDEX IF NOT EXISTS idx_asset_events_received_at ON asset_events(received_at);
@@ -0,0 +1,14 @@
+-- One-shot alerts on an asset's oracle rate registered through /rfq/alerts
+CREATE TABLE IF NOT EXISTS price_alerts (
+    id BLOB PRIMARY KEY,
+    asset_id TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    threshold REAL NOT NULL,
+    webhook_url TEXT,
+    webhook_secret TEXT,
+    created_at INTEGER NOT NULL,
+    triggered_at INTEGER,
+    triggered_rate REAL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_alerts_asset_id ON price_alerts(asset_id);
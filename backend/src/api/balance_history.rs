@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::error::AppError;
+use crate::storage::balance_snapshots::{
+    BalanceHistoryQuery, BalanceSnapshot, SnapshotGranularity,
+};
+use crate::types::{asset_id_hex, ApiResponse, AppState};
+
+pub const BALANCE_HISTORY_DEFAULT_LIMIT: usize = 168;
+pub const BALANCE_HISTORY_MAX_LIMIT: usize = 2000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BalanceHistoryParams {
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub granularity: SnapshotGranularity,
+    /// Inclusive lower bound on the bucket start, in unix seconds
+    pub from: Option<i64>,
+    /// Inclusive upper bound on the bucket start, in unix seconds
+    pub to: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl BalanceHistoryParams {
+    fn query(self) -> Result<BalanceHistoryQuery, AppError> {
+        let asset_id = self
+            .asset_id
+            .as_deref()
+            .and_then(asset_id_hex)
+            .ok_or_else(|| {
+                AppError::InvalidInput("asset_id must be 32 bytes of hex or base64".to_string())
+            })?;
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(AppError::InvalidInput("from must not be after to".to_string()));
+            }
+        }
+        Ok(BalanceHistoryQuery {
+            asset_id,
+            granularity: self.granularity,
+            from: self.from,
+            to: self.to,
+            limit: self
+                .limit
+                .unwrap_or(BALANCE_HISTORY_DEFAULT_LIMIT)
+                .clamp(1, BALANCE_HISTORY_MAX_LIMIT),
+        })
+    }
+}
+
+/// An asset's sampled balances, oldest first, for portfolio charts
+pub async fn get_balance_history(
+    State(app_state): State<AppState>,
+    Query(params): Query<BalanceHistoryParams>,
+) -> Result<Json<ApiResponse<Vec<BalanceSnapshot>>>, ApiError> {
+    let message = "Failed to retrieve balance history";
+    let query = params.query().map_err(|e| ApiError::new(e, message))?;
+    match app_state.balance_snapshots.history(&query).await {
+        Ok(snapshots) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(snapshots),
+            error: None,
+            message: Some("Balance history retrieved successfully".to_string()),
+        })),
+        Err(e) => Err(ApiError::new(e, message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_query() {
+        let params = BalanceHistoryParams {
+            asset_id: Some("AB".repeat(32)),
+            limit: Some(0),
+            ..Default::default()
+        };
+        let query = params.query().unwrap();
+        assert_eq!(query.asset_id, "ab".repeat(32));
+        assert_eq!(query.granularity, SnapshotGranularity::Hourly);
+        assert_eq!(query.limit, 1);
+
+        assert!(BalanceHistoryParams::default().query().is_err());
+        let backwards = BalanceHistoryParams {
+            asset_id: Some("ab".repeat(32)),
+            from: Some(10),
+            to: Some(5),
+            ..Default::default()
+        };
+        assert!(backwards.query().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::gateway::upstream;
+use crate::types::ApiResponse;
+
+/// A failed `/api` call: the usual `ApiResponse` envelope with
+/// `success: false`, sent with the status of the underlying error. Errors
+/// that are not an `AppError` answer 500.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub error: String,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(error: impl Into<anyhow::Error>, message: &str) -> Self {
+        let error = error.into();
+        Self {
+            status: upstream::error_status(&error),
+            error: error.to_string(),
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Keeps a partial result in the envelope, such as per-recipient outcomes
+    pub fn with_data(mut self, data: impl serde::Serialize) -> Self {
+        self.data = serde_json::to_value(data).ok();
+        self
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiResponse {
+            success: false,
+            data: self.data,
+            error: Some(self.error),
+            message: Some(self.message),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+
+    #[test]
+    fn test_api_error_keeps_app_error_status() {
+        let missing = ApiError::new(AppError::NotFound("asset".to_string()), "Failed");
+        assert_eq!(missing.into_response().status(), StatusCode::NOT_FOUND);
+
+        let unknown = ApiError::new(anyhow::anyhow!("boom"), "Failed");
+        assert_eq!(unknown.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(unknown.error, "boom");
+    }
+}
@@ -0,0 +1,146 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A problem with one field of a request body; `field` is absent when the
+/// problem is with the body as a whole
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks a deserialized request body, returning every problem found
+pub trait Validate {
+    fn validate_fields(&self) -> Vec<FieldError>;
+}
+
+/// A JSON body deserialized into `T` and validated. Bodies that are missing
+/// fields, have mistyped ones, or fail validation are answered with 422 and
+/// a message per field.
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(body) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let value: T = serde_path_to_error::deserialize(body)
+            .map_err(|e| rejection(vec![deserialize_error(&e)]))?;
+        let errors = value.validate_fields();
+        if !errors.is_empty() {
+            return Err(rejection(errors));
+        }
+        Ok(ValidJson(value))
+    }
+}
+
+/// Names the field serde stopped at; a missing field is reported at the
+/// object holding it, so its name is taken from the message
+fn deserialize_error(error: &serde_path_to_error::Error<serde_json::Error>) -> FieldError {
+    let message = error.inner().to_string();
+    let path = error.path().to_string();
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next());
+    let field = match (path.as_str(), missing) {
+        (".", Some(name)) => Some(name.to_string()),
+        (parent, Some(name)) => Some(format!("{parent}.{name}")),
+        (".", None) => None,
+        (path, None) => Some(path.to_string()),
+    };
+    FieldError { field, message }
+}
+
+fn rejection(fields: Vec<FieldError>) -> Response {
+    let summary = fields
+        .iter()
+        .map(|error| match &error.field {
+            Some(field) => format!("{field}: {}", error.message),
+            None => error.message.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    let body = serde_json::json!({
+        "success": false,
+        "data": null,
+        "error": summary,
+        "message": "Invalid request body",
+        "fields": fields,
+    });
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Payment {
+        amount: u64,
+        #[allow(dead_code)]
+        memo: String,
+    }
+
+    impl Validate for Payment {
+        fn validate_fields(&self) -> Vec<FieldError> {
+            if self.amount == 0 {
+                return vec![FieldError::new("amount", "must be greater than 0")];
+            }
+            Vec::new()
+        }
+    }
+
+    async fn extract(body: &str) -> Result<Payment, (StatusCode, serde_json::Value)> {
+        let request = Request::builder()
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        match ValidJson::<Payment>::from_request(request, &()).await {
+            Ok(ValidJson(payment)) => Ok(payment),
+            Err(response) => {
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                Err((status, serde_json::from_slice(&bytes).unwrap()))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_valid_json_reports_fields() {
+        assert_eq!(extract(r#"{"amount": 5, "memo": ""}"#).await.unwrap().amount, 5);
+
+        let (status, body) = extract(r#"{"memo": ""}"#).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["fields"][0]["field"], "amount");
+
+        let (_, body) = extract(r#"{"amount": "five", "memo": ""}"#).await.unwrap_err();
+        assert_eq!(body["fields"][0]["field"], "amount");
+
+        let (_, body) = extract(r#"{"amount": 0, "memo": ""}"#).await.unwrap_err();
+        assert_eq!(body["error"], "amount: must be greater than 0");
+    }
+}
@@ -1,135 +1,590 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
-    response::Json,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::Utc;
+use futures::StreamExt;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::extract::{Validate, ValidJson};
+use crate::api::idempotency::{run_once, IdempotencyKey};
+use crate::api::preferences::load_preferences;
+use crate::error::AppError;
+use crate::gateway::addresses::{check_address_encoding, decode_address, parse_decoded_address};
+use crate::gateway::burn::asset_balance;
+use crate::gateway::fees::{sat_per_kw_to_vbyte, DEFAULT_TARGET_CONF};
+use crate::storage::transactions::TransactionRepo;
+use crate::taproot::amounts::{annotate_balances, parse_amount};
+use crate::types::{
+    ApiResponse, AssetGroup, AssetListParams, AssetPage, TaprootAsset, AssetTransfer,
+    asset_id_hex, BalanceGrouping, BatchTransfer, BatchTransferResult, CreateAddressRequest,
+    DisplayedTransaction, MintAssetRequest, RecipientStatus, SearchParams, SearchResults,
+    SendAssetParams, SendFeePreview,
+    SendPreview, Transaction, TransactionPage, TransactionQuery, TransactionStatus,
+    TransactionType, TransferBatchResult, TransferOutcome, AppState, VersionInfo,
+    ASSET_LIST_MAX_LIMIT, MAX_BATCH_RECIPIENTS, SEND_BATCH_CONCURRENCY,
 };
-use crate::types::{ApiResponse, TaprootAsset, AssetTransfer, Transaction, AppState};
 
 pub async fn list_assets(
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<TaprootAsset>>>, StatusCode> {
-    match app_state.tapd_client.list_assets().await {
+    Query(mut params): Query<AssetListParams>,
+) -> Result<Json<ApiResponse<AssetPage<TaprootAsset>>>, ApiError> {
+    load_preferences(&app_state, &mut params)
+        .await
+        .map_err(|e| ApiError::new(e, "Failed to retrieve assets"))?;
+    match app_state.tapd_client.list_assets(&params).await {
         Ok(assets) => Ok(Json(ApiResponse {
             success: true,
             data: Some(assets),
             error: None,
             message: Some("Assets retrieved successfully".to_string()),
         })),
-        Err(e) => Ok(Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-            message: Some("Failed to retrieve assets".to_string()),
-        }))
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve assets")),
     }
 }
 
-pub async fn get_asset_balance(
+pub async fn list_asset_groups(
     State(app_state): State<AppState>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
-    match app_state.tapd_client.get_balance().await {
-        Ok(balance) => Ok(Json(ApiResponse {
+) -> Result<Json<ApiResponse<Vec<AssetGroup>>>, ApiError> {
+    match app_state.tapd_client.list_groups().await {
+        Ok(groups) => Ok(Json(ApiResponse {
             success: true,
-            data: Some(balance),
+            data: Some(groups),
             error: None,
-            message: Some("Balance retrieved successfully".to_string()),
+            message: Some("Asset groups retrieved successfully".to_string()),
         })),
-        Err(e) => Ok(Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-            message: Some("Failed to retrieve balance".to_string()),
-        }))
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve asset groups")),
+    }
+}
+
+/// Balances by asset ID, each with its `balance_display` in display units
+/// when the asset's decimal places are known
+pub async fn get_asset_balance(
+    State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (balance, decimals) = futures::join!(
+        app_state.tapd_client.get_balance(BalanceGrouping::AssetId),
+        asset_decimals(&app_state)
+    );
+    match balance {
+        Ok(mut balance) => {
+            annotate_balances(&mut balance, &decimals);
+            Ok(Json(ApiResponse {
+                success: true,
+                data: Some(balance),
+                error: None,
+                message: Some("Balance retrieved successfully".to_string()),
+            }))
+        }
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve balance")),
+    }
+}
+
+/// Decimal places by hex asset ID; empty when tapd cannot list assets, so
+/// responses fall back to base units alone
+async fn asset_decimals(app_state: &AppState) -> HashMap<String, u8> {
+    app_state.tapd_client.asset_decimals().await.unwrap_or_else(|e| {
+        warn!("Showing amounts without decimal places: {}", e);
+        HashMap::new()
+    })
+}
+
+/// Fills `amount` from `amount_display`, read in the asset's decimal places
+async fn resolve_amount(
+    app_state: &AppState,
+    transfer: &mut AssetTransfer,
+) -> Result<(), AppError> {
+    let Some(display) = &transfer.amount_display else {
+        return Ok(());
+    };
+    let asset_id = asset_id_hex(&transfer.asset_id).unwrap_or_default();
+    let decimals = app_state.tapd_client.asset_decimals().await.map_err(|e| {
+        AppError::ServiceUnavailable(format!("Could not look up decimal places: {e}"))
+    })?;
+    let places = decimals.get(&asset_id).copied().ok_or_else(|| {
+        AppError::NotFound(format!("Decimal places of asset {asset_id} are unknown"))
+    })?;
+    let amount = parse_amount(display, places)?;
+    if transfer.amount != 0 && transfer.amount != amount {
+        return Err(AppError::InvalidInput(format!(
+            "amount {} does not match amount_display {display} ({amount} base units)",
+            transfer.amount
+        )));
     }
+    transfer.amount = amount;
+    Ok(())
 }
 
+/// Sends an asset, or with `dry_run=true` reports what the send would do
 pub async fn send_asset(
     State(app_state): State<AppState>,
-    Json(transfer): Json<AssetTransfer>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    match app_state.tapd_client.send_asset(&transfer).await {
-        Ok(tx_id) => Ok(Json(ApiResponse {
-            success: true,
-            data: Some(tx_id),
-            error: None,
-            message: Some("Asset transfer initiated".to_string()),
-        })),
-        Err(e) => Ok(Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-            message: Some("Failed to send asset".to_string()),
-        }))
+    Query(params): Query<SendAssetParams>,
+    key: IdempotencyKey,
+    ValidJson(mut transfer): ValidJson<AssetTransfer>,
+) -> Response {
+    if let Err(e) = resolve_amount(&app_state, &mut transfer).await {
+        return ApiError::new(e, "Invalid amount").into_response();
+    }
+    if params.dry_run {
+        return preview_send(&app_state, &transfer).await.into_response();
+    }
+    run_once(&app_state, "api.send", key, &transfer, || send_asset_once(&app_state, &transfer))
+        .await
+}
+
+async fn send_asset_once(
+    app_state: &AppState,
+    transfer: &AssetTransfer,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    match app_state.tapd_client.send_asset(transfer).await {
+        Ok(tx_id) => {
+            // Recorded as pending; asset-send events move it to its final status
+            let now = Utc::now();
+            let transaction = Transaction {
+                id: Uuid::new_v4(),
+                tx_type: TransactionType::Send,
+                asset_id: Some(transfer.asset_id.clone()),
+                amount: transfer.amount,
+                status: TransactionStatus::Pending,
+                created_at: now,
+                updated_at: now,
+                label: transfer.label.clone(),
+            };
+            if let Err(e) = app_state
+                .transaction_store
+                .insert(transaction, Some(transfer.destination.clone()))
+                .await
+            {
+                warn!("Failed to record send transaction: {}", e);
+            }
+
+            Ok(Json(ApiResponse {
+                success: true,
+                data: Some(tx_id),
+                error: None,
+                message: Some("Asset transfer initiated".to_string()),
+            }))
+        }
+        Err(e) => Err(ApiError::new(e, "Failed to send asset")),
+    }
+}
+
+/// Decodes the destination, checks the balance and estimates the anchor fee
+/// without broadcasting anything
+async fn preview_send(
+    app_state: &AppState,
+    transfer: &AssetTransfer,
+) -> Result<Json<ApiResponse<SendPreview>>, ApiError> {
+    let destination = transfer.destination.trim();
+    let network = check_address_encoding(destination)
+        .map_err(|e| ApiError::new(e, "Invalid destination"))?;
+    let (decoded, balances, fee) = futures::join!(
+        decode_address(
+            &app_state.http_client,
+            &app_state.base_url.0,
+            &app_state.macaroon_hex.0,
+            destination,
+        ),
+        app_state.tapd_client.get_balance(BalanceGrouping::AssetId),
+        send_fee(app_state, transfer.fee_rate),
+    );
+
+    let decoded = decoded.map_err(|e| match e {
+        // tapd refuses addresses for another network or with bad contents
+        AppError::RequestError(_) => ApiError::new(e, "Failed to decode destination")
+            .with_status(StatusCode::BAD_REQUEST),
+        e => ApiError::new(e, "Failed to decode destination"),
+    })?;
+    let destination = parse_decoded_address(network, &decoded);
+    let balances = balances.map_err(|e| ApiError::new(e, "Failed to check balance"))?;
+    let balance = asset_balance(&balances, &destination.asset_id);
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(SendPreview::new(transfer, destination, balance, fee)),
+        error: None,
+        message: Some("Dry run; nothing was sent".to_string()),
+    }))
+}
+
+/// The requested fee rate, which tapd takes in sat/kw, or the current
+/// estimate; an unreachable fee source leaves the fee out of the preview
+async fn send_fee(app_state: &AppState, fee_rate: Option<u32>) -> Option<SendFeePreview> {
+    if let Some(sat_per_kw) = fee_rate {
+        return Some(SendFeePreview::new(sat_per_kw_to_vbyte(sat_per_kw.into()), None));
+    }
+    match app_state.fee_estimator.estimate(DEFAULT_TARGET_CONF).await {
+        Ok(estimate) => Some(SendFeePreview::new(estimate.sat_per_vbyte, Some(estimate.source))),
+        Err(e) => {
+            warn!("Fee estimate for send preview failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Sends to several addresses in one anchor transaction, reporting an outcome
+/// per address
+pub async fn send_batch(
+    State(app_state): State<AppState>,
+    key: IdempotencyKey,
+    Json(transfer): Json<BatchTransfer>,
+) -> Response {
+    run_once(&app_state, "api.send_batch", key, &transfer, || {
+        send_batch_once(&app_state, &transfer)
+    })
+    .await
+}
+
+async fn send_batch_once(
+    app_state: &AppState,
+    transfer: &BatchTransfer,
+) -> Result<Json<ApiResponse<BatchTransferResult>>, ApiError> {
+    if let Err(e) = transfer.validate() {
+        return Err(ApiError::new(e, "Invalid batch transfer"));
+    }
+    let errors = transfer.check_recipients();
+    if errors.iter().any(Option::is_some) {
+        let rejected = AppError::InvalidInput(
+            "Some recipients were rejected; nothing was sent".to_string(),
+        );
+        return Err(ApiError::new(rejected, "Invalid batch transfer")
+            .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+            .with_data(BatchTransferResult::rejected(transfer, errors)));
+    }
+
+    let result = app_state.tapd_client.send_batch(transfer).await;
+    let sent = result.anchor_tx_hash.is_some();
+    if sent {
+        let now = Utc::now();
+        for (recipient, outcome) in transfer.recipients.iter().zip(&result.recipients) {
+            if outcome.status != RecipientStatus::Sent {
+                continue;
+            }
+            let transaction = Transaction {
+                id: Uuid::new_v4(),
+                tx_type: TransactionType::Send,
+                asset_id: Some(recipient.asset_id.clone()),
+                amount: recipient.amount,
+                status: TransactionStatus::Pending,
+                created_at: now,
+                updated_at: now,
+                label: transfer.label.clone(),
+            };
+            if let Err(e) = app_state
+                .transaction_store
+                .insert(transaction, Some(recipient.destination.trim().to_string()))
+                .await
+            {
+                warn!("Failed to record send transaction: {}", e);
+            }
+        }
+    }
+
+    if !sent {
+        // tapd's failure only survives as text in the per-recipient outcomes
+        let error = result.recipients.iter().find_map(|outcome| outcome.error.clone());
+        let error = anyhow::anyhow!(error.unwrap_or_else(|| "Batch was not sent".to_string()));
+        return Err(ApiError::new(error, "Failed to send batch")
+            .with_status(StatusCode::BAD_GATEWAY)
+            .with_data(result));
+    }
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        message: Some("Batch transfer initiated".to_string()),
+    }))
+}
+
+/// Makes each transfer as its own send, a few at a time, reporting an
+/// outcome per transfer. Unlike `send_batch`, one failure does not stop
+/// the others.
+pub async fn send_many(
+    State(app_state): State<AppState>,
+    key: IdempotencyKey,
+    Json(transfers): Json<Vec<AssetTransfer>>,
+) -> Response {
+    run_once(&app_state, "api.send_many", key, &transfers, || {
+        send_many_once(&app_state, &transfers)
+    })
+    .await
+}
+
+async fn send_many_once(
+    app_state: &AppState,
+    transfers: &[AssetTransfer],
+) -> Result<Json<ApiResponse<TransferBatchResult>>, ApiError> {
+    if transfers.is_empty() || transfers.len() > MAX_BATCH_RECIPIENTS {
+        let error = AppError::InvalidInput(format!(
+            "A batch needs between 1 and {MAX_BATCH_RECIPIENTS} transfers"
+        ));
+        return Err(ApiError::new(error, "Invalid batch transfer")
+            .with_status(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    let sends: Vec<_> = transfers
+        .iter()
+        .enumerate()
+        .map(|(index, transfer)| send_one(app_state, index, transfer))
+        .collect();
+    let results: Vec<TransferOutcome> = futures::stream::iter(sends)
+        .buffered(SEND_BATCH_CONCURRENCY)
+        .collect()
+        .await;
+    let result = TransferBatchResult::new(results);
+
+    if result.summary.succeeded == 0 {
+        let error = anyhow::anyhow!("None of the {} transfers was sent", result.summary.total);
+        return Err(ApiError::new(error, "Failed to send batch")
+            .with_status(StatusCode::BAD_GATEWAY)
+            .with_data(result));
+    }
+    let message = format!(
+        "{} of {} transfers initiated",
+        result.summary.succeeded, result.summary.total
+    );
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        message: Some(message),
+    }))
+}
+
+async fn send_one(
+    app_state: &AppState,
+    index: usize,
+    transfer: &AssetTransfer,
+) -> TransferOutcome {
+    let errors = transfer.validate_fields();
+    let sent = if errors.is_empty() {
+        let mut transfer = transfer.clone();
+        match resolve_amount(app_state, &mut transfer).await {
+            Ok(()) => send_asset_once(app_state, &transfer).await,
+            Err(e) => Err(ApiError::new(e, "Invalid amount")),
+        }
+            .map(|Json(response)| response.data.unwrap_or_default())
+            .map_err(|e| e.error)
+    } else {
+        let problems: Vec<String> = errors
+            .into_iter()
+            .map(|error| format!("{}: {}", error.field.unwrap_or_default(), error.message))
+            .collect();
+        Err(problems.join("; "))
+    };
+    TransferOutcome {
+        index,
+        destination: transfer.destination.clone(),
+        success: sent.is_ok(),
+        tx_id: sent.as_ref().ok().cloned(),
+        error: sent.err(),
     }
 }
 
 pub async fn create_asset_address(
     State(app_state): State<AppState>,
-    Json(request): Json<serde_json::Value>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let asset_id = request["asset_id"].as_str().unwrap_or("");
-    let amount = request["amount"].as_u64().unwrap_or(0);
-    
-    match app_state.tapd_client.create_address(asset_id, amount).await {
+    ValidJson(request): ValidJson<CreateAddressRequest>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    match app_state.tapd_client.create_address(&request.asset_id, request.amount).await {
         Ok(address) => Ok(Json(ApiResponse {
             success: true,
             data: Some(address),
             error: None,
             message: Some("Asset address created".to_string()),
         })),
-        Err(e) => Ok(Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-            message: Some("Failed to create address".to_string()),
-        }))
+        Err(e) => Err(ApiError::new(e, "Failed to create address")),
     }
 }
 
 pub async fn mint_asset(
     State(app_state): State<AppState>,
-    Json(request): Json<serde_json::Value>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let name = request["name"].as_str().unwrap_or("");
-    let amount = request["amount"].as_u64().unwrap_or(0);
-    let asset_type = request["asset_type"].as_str().unwrap_or("NORMAL");
-    
-    match app_state.tapd_client.mint_asset(name, amount, asset_type).await {
+    key: IdempotencyKey,
+    ValidJson(request): ValidJson<MintAssetRequest>,
+) -> Response {
+    run_once(&app_state, "api.mint", key, &request, || mint_asset_once(&app_state, &request)).await
+}
+
+async fn mint_asset_once(
+    app_state: &AppState,
+    request: &MintAssetRequest,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    match app_state.tapd_client.mint_asset(request).await {
         Ok(batch_key) => Ok(Json(ApiResponse {
             success: true,
             data: Some(batch_key),
             error: None,
             message: Some("Asset minting initiated".to_string()),
         })),
-        Err(e) => Ok(Json(ApiResponse {
-            success: false,
-            data: None,
-            error: Some(e.to_string()),
-            message: Some("Failed to mint asset".to_string()),
-        }))
+        Err(e) => Err(ApiError::new(e, "Failed to mint asset")),
     }
 }
 
-pub async fn get_transactions() -> Result<Json<ApiResponse<Vec<Transaction>>>, StatusCode> {
-    // TODO: Implement actual transaction history from database
-    let transactions = vec![];
-    
+/// Searches held assets, the wallet's addresses and the transaction history
+/// for one piece of text
+pub async fn search(
+    State(app_state): State<AppState>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<ApiResponse<SearchResults>>, ApiError> {
+    let text = params.text().map_err(|e| ApiError::new(e, "Invalid search"))?;
+    let limit = params.limit();
+    let asset_params = AssetListParams {
+        limit: Some(ASSET_LIST_MAX_LIMIT),
+        ..Default::default()
+    };
+    let (assets, addresses, transactions) = futures::join!(
+        app_state.tapd_client.list_assets(&asset_params),
+        app_state.tapd_client.list_addresses(),
+        app_state.transaction_store.search(text, limit),
+    );
+
+    let mut warnings = Vec::new();
+    let assets = match assets {
+        Ok(page) => page
+            .assets
+            .into_iter()
+            .filter(|asset| params.matches_asset(asset))
+            .take(limit)
+            .collect(),
+        Err(e) => {
+            warnings.push(format!("Assets unavailable: {e}"));
+            Vec::new()
+        }
+    };
+    let addresses = match addresses {
+        Ok(listing) => listing["addrs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|addr| {
+                let encoded = addr["encoded"].as_str().unwrap_or_default();
+                let network = check_address_encoding(encoded).ok()?;
+                Some(parse_decoded_address(network, addr))
+            })
+            .filter(|address| params.matches_address(address))
+            .take(limit)
+            .collect(),
+        Err(e) => {
+            warnings.push(format!("Addresses unavailable: {e}"));
+            Vec::new()
+        }
+    };
+    let transactions = transactions.unwrap_or_else(|e| {
+        warnings.push(format!("Transactions unavailable: {e}"));
+        Vec::new()
+    });
+
     Ok(Json(ApiResponse {
         success: true,
-        data: Some(transactions),
+        data: Some(SearchResults {
+            query: text.to_string(),
+            assets,
+            addresses,
+            transactions,
+            warnings,
+        }),
         error: None,
-        message: Some("Transactions retrieved successfully".to_string()),
+        message: Some("Search completed".to_string()),
     }))
 }
 
+/// The gateway's build and the versions of the nodes behind it
+pub async fn get_version(State(app_state): State<AppState>) -> Json<ApiResponse<VersionInfo>> {
+    let lnd_info = async {
+        match &app_state.lnd_client {
+            Some(lnd) => Some(lnd.get::<serde_json::Value>("/v1/getinfo").await),
+            None => None,
+        }
+    };
+    let (tapd_info, lnd_info) = futures::join!(app_state.tapd_client.get_info(), lnd_info);
+
+    let version = |info: &serde_json::Value| info["version"].as_str().map(str::to_string);
+    let tapd_version = match tapd_info {
+        Ok(info) => version(&info),
+        Err(e) => {
+            warn!("Version check could not reach tapd: {}", e);
+            None
+        }
+    };
+    let lnd_version = match lnd_info {
+        Some(Ok(info)) => version(&info),
+        Some(Err(e)) => {
+            warn!("Version check could not reach LND: {}", e);
+            None
+        }
+        None => None,
+    };
+
+    let features = [
+        ("tapd_grpc", app_state.tapd_client.grpc_client().is_some()),
+        ("lnd", app_state.lnd_client.is_some()),
+        ("rfq_simulator", app_state.rfq_simulator.is_some()),
+        ("fiat_reference", app_state.fiat_reference.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+
+    Json(ApiResponse {
+        success: true,
+        data: Some(VersionInfo {
+            features,
+            tapd_version,
+            lnd_version,
+            ..VersionInfo::build()
+        }),
+        error: None,
+        message: Some("Version retrieved successfully".to_string()),
+    })
+}
+
+/// Transaction history, filtered by asset, type, status and creation time,
+/// one page at a time
+pub async fn get_transactions(
+    State(app_state): State<AppState>,
+    Query(query): Query<TransactionQuery>,
+) -> Result<Json<ApiResponse<TransactionPage<DisplayedTransaction>>>, ApiError> {
+    let decimals = asset_decimals(&app_state).await;
+    list_transactions(app_state.transaction_store.as_ref(), &query, &decimals).await
+}
+
+async fn list_transactions(
+    store: &dyn TransactionRepo,
+    query: &TransactionQuery,
+    decimals: &HashMap<String, u8>,
+) -> Result<Json<ApiResponse<TransactionPage<DisplayedTransaction>>>, ApiError> {
+    let page = match query.validate() {
+        Ok(()) => store.query(query).await,
+        Err(e) => Err(e),
+    };
+    match page {
+        Ok(page) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(page.map(|tx| DisplayedTransaction::new(tx, decimals))),
+            error: None,
+            message: Some("Transactions retrieved successfully".to_string()),
+        })),
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve transactions")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::transactions::InMemoryTransactionRepo;
 
     #[test]
     fn test_get_transactions() {
         // Simple test that doesn't require async or complex mocking
-        let result = tokio::runtime::Runtime::new().unwrap().block_on(get_transactions());
+        let store = InMemoryTransactionRepo::new();
+        let query = TransactionQuery::default();
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(list_transactions(&store, &query, &HashMap::new()));
         assert!(result.is_ok());
 
         let response = result.unwrap();
@@ -140,7 +595,8 @@ mod tests {
         assert!(response_data.error.is_none());
         assert_eq!(response_data.message, Some("Transactions retrieved successfully".to_string()));
 
-        let transactions = response_data.data.unwrap();
-        assert_eq!(transactions.len(), 0); // Currently returns empty vector
+        let page = response_data.data.unwrap();
+        assert_eq!(page.transactions.len(), 0); // Nothing recorded yet
+        assert_eq!((page.total, page.next_offset), (0, None));
     }
 }
\ No newline at end of file
@@ -0,0 +1,155 @@
+use std::future::Future;
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::api::error::ApiError;
+use crate::error::AppError;
+use crate::storage::idempotency::IdempotencyClaim;
+use crate::types::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses answered from a stored result
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+
+/// The optional `Idempotency-Key` request header. Keys are 1 to 255
+/// printable ASCII characters; anything else is answered with 400.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyKey(pub Option<String>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IdempotencyKey
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(IDEMPOTENCY_KEY_HEADER) else {
+            return Ok(IdempotencyKey(None));
+        };
+        let key = value
+            .to_str()
+            .ok()
+            .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+            .filter(|key| key.chars().all(|c| c.is_ascii_graphic()))
+            .ok_or_else(|| {
+                let error = AppError::InvalidInput(format!(
+                    "Idempotency-Key must be 1 to {MAX_KEY_LEN} printable ASCII characters"
+                ));
+                ApiError::new(error, "Invalid Idempotency-Key")
+            })?;
+        Ok(IdempotencyKey(Some(key.to_string())))
+    }
+}
+
+/// Runs `run` once per key within `scope`. Retries with the same key and
+/// body replay the first response, whatever its status, so a retried send,
+/// mint or burn cannot happen twice. A retry while the first request is
+/// still running gets 409; reusing a key for another body gets 422.
+pub async fn run_once<T, F, Fut, R>(
+    state: &AppState,
+    scope: &str,
+    key: IdempotencyKey,
+    request: &T,
+    run: F,
+) -> Response
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = R>,
+    R: IntoResponse,
+{
+    let Some(key) = key.0 else {
+        return run().await.into_response();
+    };
+    let request_hash = match serde_json::to_vec(request) {
+        Ok(bytes) => hex::encode(Sha256::digest(bytes)),
+        Err(e) => return ApiError::new(e, "Failed to read request").into_response(),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let expired_before = now.saturating_sub(state.idempotency_ttl_secs as i64);
+    let store = &state.idempotency_store;
+    let claim = store.claim(scope, &key, &request_hash, now, expired_before).await;
+    match claim {
+        Ok(IdempotencyClaim::Claimed) => {}
+        Ok(IdempotencyClaim::Completed { status, body }) => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+            // Responses that had no body, like bare status errors, replay without one
+            let mut response = match body {
+                serde_json::Value::Null => status.into_response(),
+                body => (status, axum::Json(body)).into_response(),
+            };
+            response.headers_mut().insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+            return response;
+        }
+        Ok(IdempotencyClaim::InProgress) => {
+            let error = AppError::Conflict(format!(
+                "A request with Idempotency-Key {key} is still in progress"
+            ));
+            return ApiError::new(error, "Request already in progress").into_response();
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            let error = AppError::InvalidInput(format!(
+                "Idempotency-Key {key} was already used for a different request"
+            ));
+            return ApiError::new(error, "Idempotency-Key reused")
+                .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                .into_response();
+        }
+        Err(e) => {
+            // Running without a claim could repeat the request on retry
+            warn!("Idempotency store unavailable: {}", e);
+            let error = AppError::ServiceUnavailable(e.to_string());
+            return ApiError::new(error, "Idempotency store unavailable").into_response();
+        }
+    }
+
+    let (parts, body) = run().await.into_response().into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => return ApiError::new(e, "Failed to read response").into_response(),
+    };
+    let stored = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    if let Err(e) = store.complete(scope, &key, parts.status.as_u16(), &stored).await {
+        // The key stays in progress until it expires, so retries get 409
+        // rather than a second attempt
+        warn!("Failed to store result for Idempotency-Key {}: {}", key, e);
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(value: &str) -> Result<IdempotencyKey, StatusCode> {
+        let request = Request::builder().header(IDEMPOTENCY_KEY_HEADER, value).body(()).unwrap();
+        let (mut parts, _) = request.into_parts();
+        IdempotencyKey::from_request_parts(&mut parts, &())
+            .await
+            .map_err(|e| e.status)
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_header_validation() {
+        assert_eq!(
+            extract("send-7f3a").await,
+            Ok(IdempotencyKey(Some("send-7f3a".to_string())))
+        );
+        assert_eq!(extract("").await, Err(StatusCode::BAD_REQUEST));
+        assert_eq!(extract("two words").await, Err(StatusCode::BAD_REQUEST));
+        assert_eq!(extract(&"k".repeat(256)).await, Err(StatusCode::BAD_REQUEST));
+    }
+}
@@ -1,2 +1,9 @@
+pub mod balance_history;
+pub mod error;
+pub mod extract;
+pub mod idempotency;
+pub mod preferences;
 pub mod routes;
-pub mod handlers;
\ No newline at end of file
+pub mod settings;
+pub mod handlers;
+pub mod webhooks;
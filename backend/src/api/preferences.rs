@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::api::extract::{FieldError, Validate, ValidJson};
+use crate::error::AppError;
+use crate::storage::asset_preferences::AssetPreference;
+use crate::types::{asset_id_hex, ApiResponse, AppState, AssetListParams};
+
+pub const DEFAULT_PROFILE: &str = "default";
+const MAX_PROFILE_LEN: usize = 64;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProfileParams {
+    pub profile: Option<String>,
+}
+
+/// Changes to an asset's preference; flags left out keep their value
+#[derive(Debug, Deserialize)]
+pub struct UpdateAssetPreference {
+    pub favorite: Option<bool>,
+    pub hidden: Option<bool>,
+}
+
+impl Validate for UpdateAssetPreference {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        if self.favorite.is_none() && self.hidden.is_none() {
+            return vec![FieldError {
+                field: None,
+                message: "set favorite, hidden or both".to_string(),
+            }];
+        }
+        Vec::new()
+    }
+}
+
+/// Profiles are 1 to 64 letters, digits, `-` or `_`; unset means `default`
+pub fn profile_name(profile: Option<&str>) -> Result<&str, AppError> {
+    let profile = profile.unwrap_or(DEFAULT_PROFILE);
+    let valid = !profile.is_empty()
+        && profile.len() <= MAX_PROFILE_LEN
+        && profile.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::InvalidInput(format!(
+            "profile must be 1 to {MAX_PROFILE_LEN} letters, digits, '-' or '_'"
+        )));
+    }
+    Ok(profile)
+}
+
+/// Loads the listing profile's preferences into `params`, so hidden assets
+/// are filtered out before paging
+pub async fn load_preferences(
+    state: &AppState,
+    params: &mut AssetListParams,
+) -> Result<(), AppError> {
+    let profile = profile_name(params.profile.as_deref())?;
+    params.preferences = state
+        .asset_preferences
+        .list(profile)
+        .await?
+        .into_iter()
+        .map(|preference| (preference.asset_id.clone(), preference))
+        .collect();
+    Ok(())
+}
+
+pub async fn list_preferences(
+    State(app_state): State<AppState>,
+    Query(params): Query<ProfileParams>,
+) -> Result<Json<ApiResponse<Vec<AssetPreference>>>, ApiError> {
+    let message = "Failed to retrieve asset preferences";
+    let profile = profile_name(params.profile.as_deref()).map_err(|e| ApiError::new(e, message))?;
+    match app_state.asset_preferences.list(profile).await {
+        Ok(preferences) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(preferences),
+            error: None,
+            message: Some("Asset preferences retrieved successfully".to_string()),
+        })),
+        Err(e) => Err(ApiError::new(e, message)),
+    }
+}
+
+/// Marks an asset as a favorite or hidden for one profile
+pub async fn update_preference(
+    State(app_state): State<AppState>,
+    Path(asset_id): Path<String>,
+    Query(params): Query<ProfileParams>,
+    ValidJson(update): ValidJson<UpdateAssetPreference>,
+) -> Result<Json<ApiResponse<AssetPreference>>, ApiError> {
+    let message = "Failed to update asset preference";
+    let profile = profile_name(params.profile.as_deref()).map_err(|e| ApiError::new(e, message))?;
+    let asset_id = asset_id_hex(&asset_id).ok_or_else(|| {
+        let error = AppError::InvalidInput("asset_id must be 32 bytes of hex or base64".into());
+        ApiError::new(error, message)
+    })?;
+
+    let store = &app_state.asset_preferences;
+    let current = store
+        .get(profile, &asset_id)
+        .await
+        .map_err(|e| ApiError::new(e, message))?;
+    let preference = AssetPreference {
+        favorite: update
+            .favorite
+            .unwrap_or_else(|| current.as_ref().is_some_and(|p| p.favorite)),
+        hidden: update
+            .hidden
+            .unwrap_or_else(|| current.as_ref().is_some_and(|p| p.hidden)),
+        asset_id,
+        updated_at: Utc::now().timestamp(),
+    };
+    store
+        .save(profile, &preference)
+        .await
+        .map_err(|e| ApiError::new(e, message))?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(preference),
+        error: None,
+        message: Some("Asset preference updated".to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_name() {
+        assert_eq!(profile_name(None).unwrap(), DEFAULT_PROFILE);
+        assert_eq!(profile_name(Some("alice_01")).unwrap(), "alice_01");
+        assert!(profile_name(Some("")).is_err());
+        assert!(profile_name(Some("bob smith")).is_err());
+        assert!(profile_name(Some(&"p".repeat(65))).is_err());
+    }
+}
@@ -1,16 +1,29 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
-use crate::api::handlers;
+use crate::api::{balance_history, handlers, preferences, settings, webhooks};
 use crate::types::AppState;
 
 pub fn create_routes() -> Router<AppState> {
     Router::new()
         .route("/assets", get(handlers::list_assets))
         .route("/assets/balance", get(handlers::get_asset_balance))
+        .route("/assets/balance/history", get(balance_history::get_balance_history))
+        .route("/assets/groups", get(handlers::list_asset_groups))
+        .route("/assets/preferences", get(preferences::list_preferences))
+        .route("/assets/:asset_id/preferences", put(preferences::update_preference))
         .route("/assets/send", post(handlers::send_asset))
+        .route("/assets/send/multi", post(handlers::send_batch))
+        .route("/assets/send-batch", post(handlers::send_many))
         .route("/assets/address", post(handlers::create_asset_address))
         .route("/assets/mint", post(handlers::mint_asset))
         .route("/transactions", get(handlers::get_transactions))
+        .route("/webhooks", post(webhooks::create_webhook).get(webhooks::list_webhooks))
+        .route("/webhooks/:id", delete(webhooks::delete_webhook))
+        .route("/webhooks/:id/test", post(webhooks::test_webhook))
+        .route("/settings", get(settings::list_settings))
+        .route("/settings/:key", get(settings::get_setting).put(settings::put_setting))
+        .route("/search", get(handlers::search))
+        .route("/version", get(handlers::get_version))
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::api::extract::{FieldError, Validate, ValidJson};
+use crate::error::AppError;
+use crate::storage::settings::Setting;
+use crate::types::{ApiResponse, AppState};
+
+const MAX_KEY_LEN: usize = 64;
+/// Largest accepted value, as serialized JSON
+pub const MAX_SETTING_BYTES: usize = 4096;
+
+#[derive(Debug, Deserialize)]
+pub struct PutSettingRequest {
+    /// Any JSON value: a number, string, flag or object
+    pub value: serde_json::Value,
+}
+
+impl Validate for PutSettingRequest {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        if self.value.to_string().len() > MAX_SETTING_BYTES {
+            return vec![FieldError::new(
+                "value",
+                format!("must be at most {MAX_SETTING_BYTES} bytes of JSON"),
+            )];
+        }
+        Vec::new()
+    }
+}
+
+/// Keys are 1 to 64 lowercase letters, digits, `.`, `-` or `_`
+pub fn setting_key(key: &str) -> Result<&str, AppError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        return Err(AppError::InvalidInput(format!(
+            "key must be 1 to {MAX_KEY_LEN} lowercase letters, digits, '.', '-' or '_'"
+        )));
+    }
+    Ok(key)
+}
+
+pub async fn list_settings(
+    State(app_state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<Setting>>>, ApiError> {
+    match app_state.settings.list().await {
+        Ok(settings) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(settings),
+            error: None,
+            message: Some("Settings retrieved successfully".to_string()),
+        })),
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve settings")),
+    }
+}
+
+pub async fn get_setting(
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<Json<ApiResponse<Setting>>, ApiError> {
+    let message = "Failed to retrieve setting";
+    let key = setting_key(&key).map_err(|e| ApiError::new(e, message))?;
+    let setting = app_state
+        .settings
+        .get(key)
+        .await
+        .map_err(|e| ApiError::new(e, message))?
+        .ok_or_else(|| ApiError::new(AppError::NotFound(format!("Setting {key}")), message))?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(setting),
+        error: None,
+        message: Some("Setting retrieved successfully".to_string()),
+    }))
+}
+
+/// Stores a setting, replacing any earlier value for its key
+pub async fn put_setting(
+    State(app_state): State<AppState>,
+    Path(key): Path<String>,
+    ValidJson(request): ValidJson<PutSettingRequest>,
+) -> Result<Json<ApiResponse<Setting>>, ApiError> {
+    let message = "Failed to update setting";
+    let key = setting_key(&key).map_err(|e| ApiError::new(e, message))?;
+    let setting = Setting {
+        key: key.to_string(),
+        value: request.value,
+        updated_at: Utc::now().timestamp(),
+    };
+    app_state
+        .settings
+        .put(&setting)
+        .await
+        .map_err(|e| ApiError::new(e, message))?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(setting),
+        error: None,
+        message: Some("Setting updated".to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setting_key() {
+        assert_eq!(setting_key("notifications.push").unwrap(), "notifications.push");
+        assert!(setting_key("").is_err());
+        assert!(setting_key("Display_Currency").is_err());
+        assert!(setting_key("fee rate").is_err());
+        assert!(setting_key(&"k".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_put_request_caps_value_size() {
+        let request = |value| PutSettingRequest { value };
+        assert!(request(serde_json::json!({"sat_per_vbyte": 12})).validate_fields().is_empty());
+        let oversized = request(serde_json::json!("x".repeat(MAX_SETTING_BYTES)));
+        assert_eq!(oversized.validate_fields().len(), 1);
+    }
+}
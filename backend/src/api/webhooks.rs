@@ -0,0 +1,228 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::extract::{FieldError, Validate, ValidJson};
+use crate::error::AppError;
+use crate::gateway::admin::authorize;
+use crate::gateway::mailbox_webhooks::{generate_webhook_secret, validate_webhook_url};
+use crate::gateway::webhooks::WebhookMessage;
+use crate::storage::webhooks::{Webhook, WebhookEvent};
+use crate::types::{ApiResponse, AppState};
+
+/// Webhooks one gateway may have registered
+pub const MAX_WEBHOOKS: usize = 20;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+impl Validate for CreateWebhookRequest {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if let Err(e) = validate_webhook_url(&self.url) {
+            errors.push(FieldError::new("url", e.to_string()));
+        }
+        if self.events.is_empty() {
+            errors.push(FieldError::new("events", "must name at least one event"));
+        }
+        if self.events.contains(&WebhookEvent::Test) {
+            errors.push(FieldError::new("events", "webhook.test cannot be subscribed to"));
+        }
+        errors
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    /// Verifies the `X-Webhook-Signature` header; shown only once
+    pub secret: String,
+}
+
+/// Outcome of a test delivery
+#[derive(Debug, Serialize)]
+pub struct WebhookTestResult {
+    pub delivered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub async fn create_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CreatedWebhook>>), ApiError> {
+    authorize(&app_state, &headers)
+        .map_err(|e| ApiError::new(e, "Failed to register webhook"))?;
+    let store = app_state.webhooks.store();
+    let registered = store
+        .list()
+        .await
+        .map_err(|e| ApiError::new(e, "Failed to register webhook"))?
+        .len();
+    if registered >= MAX_WEBHOOKS {
+        let error = AppError::Conflict(format!("At most {MAX_WEBHOOKS} webhooks"));
+        return Err(ApiError::new(error, "Failed to register webhook"));
+    }
+
+    let mut events = Vec::new();
+    for event in request.events {
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    let webhook = Webhook {
+        id: Uuid::new_v4(),
+        url: request.url,
+        events,
+        secret: generate_webhook_secret(),
+        created_at: Utc::now().timestamp(),
+    };
+    store
+        .create(&webhook)
+        .await
+        .map_err(|e| ApiError::new(e, "Failed to register webhook"))?;
+
+    info!("Registered webhook {} for {:?}", webhook.id, webhook.events);
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse {
+            success: true,
+            data: Some(CreatedWebhook {
+                secret: webhook.secret.clone(),
+                webhook,
+            }),
+            error: None,
+            message: Some("Webhook registered".to_string()),
+        }),
+    ))
+}
+
+pub async fn list_webhooks(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<Vec<Webhook>>>, ApiError> {
+    authorize(&app_state, &headers)
+        .map_err(|e| ApiError::new(e, "Failed to retrieve webhooks"))?;
+    match app_state.webhooks.store().list().await {
+        Ok(webhooks) => Ok(Json(ApiResponse {
+            success: true,
+            data: Some(webhooks),
+            error: None,
+            message: Some("Webhooks retrieved successfully".to_string()),
+        })),
+        Err(e) => Err(ApiError::new(e, "Failed to retrieve webhooks")),
+    }
+}
+
+pub async fn delete_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    authorize(&app_state, &headers)
+        .map_err(|e| ApiError::new(e, "Failed to delete webhook"))?;
+    let deleted = app_state
+        .webhooks
+        .store()
+        .delete(id)
+        .await
+        .map_err(|e| ApiError::new(e, "Failed to delete webhook"))?;
+    if !deleted {
+        let error = AppError::NotFound(format!("Webhook {id}"));
+        return Err(ApiError::new(error, "Failed to delete webhook"));
+    }
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        error: None,
+        message: Some("Webhook deleted".to_string()),
+    }))
+}
+
+/// Sends a signed `webhook.test` event right away, bypassing the queue, so
+/// integrators can check their endpoint and signature verification
+pub async fn test_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<WebhookTestResult>>, ApiError> {
+    authorize(&app_state, &headers)
+        .map_err(|e| ApiError::new(e, "Failed to test webhook"))?;
+    let webhook = app_state
+        .webhooks
+        .store()
+        .get(id)
+        .await
+        .map_err(|e| ApiError::new(e, "Failed to test webhook"))?
+        .ok_or_else(|| {
+            ApiError::new(AppError::NotFound(format!("Webhook {id}")), "Failed to test webhook")
+        })?;
+
+    let data = serde_json::json!({"webhook_id": webhook.id});
+    let message = WebhookMessage {
+        id: Uuid::new_v4(),
+        event: WebhookEvent::Test,
+        created_at: Utc::now().timestamp(),
+        data: &data,
+    };
+    let (result, message) = match app_state.webhooks.send(&webhook, &message).await {
+        Ok(status) => (
+            WebhookTestResult {
+                delivered: true,
+                status_code: Some(status),
+                error: None,
+            },
+            "Test event delivered",
+        ),
+        Err(e) => (
+            WebhookTestResult {
+                delivered: false,
+                status_code: None,
+                error: Some(e.to_string()),
+            },
+            "Test event could not be delivered",
+        ),
+    };
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        message: Some(message.to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_request_validation() {
+        let request = |url: &str, events: Vec<WebhookEvent>| CreateWebhookRequest {
+            url: url.to_string(),
+            events,
+        };
+        let confirmed = vec![WebhookEvent::TransactionConfirmed];
+        assert!(request("https://example.com/hooks", confirmed).validate_fields().is_empty());
+
+        let errors = request("http://example.com/hooks", Vec::new()).validate_fields();
+        let fields: Vec<_> = errors.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(fields, ["url", "events"]);
+
+        let test_only = request("https://example.com/hooks", vec![WebhookEvent::Test]);
+        assert_eq!(test_only.validate_fields().len(), 1);
+    }
+}
@@ -0,0 +1,30 @@
+//! Seals a tapd or LND macaroon under `SECRETS_MASTER_KEY`, so it can be
+//! kept encrypted in `TAPROOT_MACAROON_HEX`/`LND_MACAROON_HEX` or in the file
+//! at `TAPD_MACAROON_PATH`/`LND_MACAROON_PATH`:
+//!
+//! ```text
+//! cargo run --bin seal-macaroon -- ~/.lnd/data/chain/bitcoin/mainnet/admin.macaroon
+//! ```
+//!
+//! The gateway opens it at startup with the same master key.
+
+use anyhow::{bail, Context};
+use taproot_backend::config::SecretsSettings;
+use taproot_backend::secrets::SecretBox;
+
+fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+
+    let Some(path) = std::env::args().nth(1) else {
+        bail!("usage: seal-macaroon <path to .macaroon file>");
+    };
+    let settings = SecretsSettings::from_env();
+    if settings.master_key.is_none() {
+        bail!("SECRETS_MASTER_KEY must be set to seal a macaroon");
+    }
+
+    let macaroon = std::fs::read(&path).with_context(|| format!("Failed to read {path}"))?;
+    let secrets = SecretBox::from_settings(&settings)?;
+    println!("{}", secrets.seal(&hex::encode(macaroon))?);
+    Ok(())
+}
@@ -1,6 +1,1352 @@
 use crate::error::AppError;
+use crate::secrets::SecretBox;
 use serde::Deserialize;
 use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Backend used to persist mailbox authentication challenges
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeStoreBackend {
+    Memory,
+    Redis,
+    Postgres,
+}
+
+impl FromStr for ChallengeStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(ChallengeStoreBackend::Memory),
+            "redis" => Ok(ChallengeStoreBackend::Redis),
+            "postgres" => Ok(ChallengeStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown CHALLENGE_STORE_BACKEND: {other}. Expected memory, redis or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct ChallengeStoreSettings {
+    pub backend: ChallengeStoreBackend,
+    pub redis_url: String,
+}
+
+impl ChallengeStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("CHALLENGE_STORE_BACKEND")
+            .unwrap_or_else(|_| "memory".to_string())
+            .parse::<ChallengeStoreBackend>()?;
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+
+        Ok(Self { backend, redis_url })
+    }
+}
+
+impl Default for ChallengeStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: ChallengeStoreBackend::Memory,
+            redis_url: "redis://127.0.0.1:6379".to_string(),
+        }
+    }
+}
+
+/// Backend used to persist asset events for history replay
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for EventStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(EventStoreBackend::Memory),
+            "postgres" => Ok(EventStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown EVENT_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct EventStoreSettings {
+    pub backend: EventStoreBackend,
+}
+
+impl EventStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("EVENT_STORE_BACKEND")
+            .unwrap_or_else(|_| "memory".to_string())
+            .parse::<EventStoreBackend>()?;
+
+        Ok(Self { backend })
+    }
+}
+
+impl Default for EventStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: EventStoreBackend::Memory,
+        }
+    }
+}
+
+/// Backend used to persist outgoing payment history
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for PaymentStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(PaymentStoreBackend::Memory),
+            "postgres" => Ok(PaymentStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown PAYMENT_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct PaymentStoreSettings {
+    pub backend: PaymentStoreBackend,
+}
+
+impl PaymentStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("PAYMENT_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<PaymentStoreBackend>()?;
+
+        Ok(Self { backend })
+    }
+}
+
+impl Default for PaymentStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: PaymentStoreBackend::Postgres,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RfqOrderStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for RfqOrderStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(RfqOrderStoreBackend::Memory),
+            "postgres" => Ok(RfqOrderStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown RFQ_ORDER_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct RfqOrderStoreSettings {
+    pub backend: RfqOrderStoreBackend,
+}
+
+impl RfqOrderStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("RFQ_ORDER_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<RfqOrderStoreBackend>()?;
+
+        Ok(Self { backend })
+    }
+}
+
+impl Default for RfqOrderStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: RfqOrderStoreBackend::Postgres,
+        }
+    }
+}
+
+/// Backend used to remember `Idempotency-Key` results
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdempotencyStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for IdempotencyStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(IdempotencyStoreBackend::Memory),
+            "postgres" => Ok(IdempotencyStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown IDEMPOTENCY_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct IdempotencyStoreSettings {
+    pub backend: IdempotencyStoreBackend,
+    /// How long a key's result is replayed; afterwards the key can be reused
+    pub ttl_secs: u64,
+}
+
+impl IdempotencyStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("IDEMPOTENCY_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<IdempotencyStoreBackend>()?;
+
+        Ok(Self {
+            backend,
+            ttl_secs: env_or("IDEMPOTENCY_KEY_TTL_SECS", Self::default().ttl_secs),
+        })
+    }
+}
+
+impl Default for IdempotencyStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: IdempotencyStoreBackend::Postgres,
+            ttl_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+/// Backend used to store favorite and hidden asset preferences
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetPreferenceStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for AssetPreferenceStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(AssetPreferenceStoreBackend::Memory),
+            "postgres" => Ok(AssetPreferenceStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown ASSET_PREFERENCE_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct AssetPreferenceStoreSettings {
+    pub backend: AssetPreferenceStoreBackend,
+}
+
+impl AssetPreferenceStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("ASSET_PREFERENCE_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<AssetPreferenceStoreBackend>()?;
+        Ok(Self { backend })
+    }
+}
+
+impl Default for AssetPreferenceStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: AssetPreferenceStoreBackend::Postgres,
+        }
+    }
+}
+
+/// Backend used to store registered `/api/webhooks` and their delivery queue
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for WebhookStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(WebhookStoreBackend::Memory),
+            "postgres" => Ok(WebhookStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown WEBHOOK_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+/// Storage and delivery of integrator webhooks
+#[derive(Clone, Deserialize, Debug)]
+pub struct WebhookSettings {
+    pub backend: WebhookStoreBackend,
+    /// How often the delivery worker looks for due deliveries
+    pub poll_interval_secs: u64,
+    /// Attempts per delivery before it is marked failed
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles with each further attempt
+    pub initial_backoff_ms: u64,
+}
+
+impl WebhookSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let backend = std::env::var("WEBHOOK_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<WebhookStoreBackend>()?;
+
+        Ok(Self {
+            backend,
+            // Zero would panic the delivery loop's tokio interval
+            poll_interval_secs: env_or("WEBHOOK_POLL_INTERVAL_SECS", defaults.poll_interval_secs)
+                .max(1),
+            max_attempts: env_or("WEBHOOK_MAX_ATTEMPTS", defaults.max_attempts),
+            initial_backoff_ms: env_or("WEBHOOK_INITIAL_BACKOFF_MS", defaults.initial_backoff_ms),
+        })
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            backend: WebhookStoreBackend::Postgres,
+            poll_interval_secs: 5,
+            max_attempts: 8,
+            initial_backoff_ms: 5_000,
+        }
+    }
+}
+
+/// Backend used to store `/api/settings`
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for SettingsStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(SettingsStoreBackend::Memory),
+            "postgres" => Ok(SettingsStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown SETTINGS_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct SettingsStoreSettings {
+    pub backend: SettingsStoreBackend,
+}
+
+impl SettingsStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("SETTINGS_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<SettingsStoreBackend>()?;
+        Ok(Self { backend })
+    }
+}
+
+impl Default for SettingsStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: SettingsStoreBackend::Postgres,
+        }
+    }
+}
+
+/// Backend used to store the audit log of sensitive operations
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLogBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for AuditLogBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(AuditLogBackend::Memory),
+            "postgres" => Ok(AuditLogBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown AUDIT_LOG_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct AuditLogSettings {
+    pub backend: AuditLogBackend,
+}
+
+impl AuditLogSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let backend = std::env::var("AUDIT_LOG_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<AuditLogBackend>()?;
+        Ok(Self { backend })
+    }
+}
+
+impl Default for AuditLogSettings {
+    fn default() -> Self {
+        Self {
+            backend: AuditLogBackend::Postgres,
+        }
+    }
+}
+
+/// Backend used to store hourly and daily balance snapshots
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BalanceSnapshotStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for BalanceSnapshotStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(BalanceSnapshotStoreBackend::Memory),
+            "postgres" => Ok(BalanceSnapshotStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown BALANCE_SNAPSHOT_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+/// Storage and sampling of per-asset balance history
+#[derive(Clone, Deserialize, Debug)]
+pub struct BalanceSnapshotSettings {
+    pub backend: BalanceSnapshotStoreBackend,
+    /// How often balances are sampled into the current hour and day
+    pub interval_secs: u64,
+}
+
+impl BalanceSnapshotSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let backend = std::env::var("BALANCE_SNAPSHOT_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<BalanceSnapshotStoreBackend>()?;
+
+        Ok(Self {
+            backend,
+            interval_secs: env_or("BALANCE_SNAPSHOT_INTERVAL_SECS", defaults.interval_secs),
+        })
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+}
+
+impl Default for BalanceSnapshotSettings {
+    fn default() -> Self {
+        Self {
+            backend: BalanceSnapshotStoreBackend::Postgres,
+            interval_secs: 900,
+        }
+    }
+}
+
+/// Backend used to store uploaded collectible images
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageStoreBackend {
+    Filesystem,
+    S3,
+}
+
+impl FromStr for ImageStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "filesystem" => Ok(ImageStoreBackend::Filesystem),
+            "s3" => Ok(ImageStoreBackend::S3),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown IMAGE_STORE_BACKEND: {other}. Expected filesystem or s3."
+            ))),
+        }
+    }
+}
+
+/// tapd rejects asset meta blobs larger than 1 MiB
+pub const MAX_ASSET_META_BYTES: usize = 1024 * 1024;
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct ImageStoreSettings {
+    pub backend: ImageStoreBackend,
+    /// Directory for the filesystem backend
+    pub dir: String,
+    /// Largest accepted upload; images become the asset's meta blob
+    pub max_bytes: usize,
+    pub s3_bucket: String,
+    pub s3_region: String,
+    /// S3-compatible endpoint; AWS's regional endpoint when unset
+    pub s3_endpoint: Option<String>,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+}
+
+impl ImageStoreSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let backend = std::env::var("IMAGE_STORE_BACKEND")
+            .unwrap_or_else(|_| "filesystem".to_string())
+            .parse::<ImageStoreBackend>()?;
+        let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+
+        Ok(Self {
+            backend,
+            dir: var("IMAGE_STORE_DIR").unwrap_or(defaults.dir),
+            max_bytes: env_or("IMAGE_MAX_BYTES", defaults.max_bytes),
+            s3_bucket: var("IMAGE_S3_BUCKET").unwrap_or_default(),
+            s3_region: var("IMAGE_S3_REGION").unwrap_or(defaults.s3_region),
+            s3_endpoint: var("IMAGE_S3_ENDPOINT"),
+            s3_access_key: var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            s3_secret_key: var("AWS_SECRET_ACCESS_KEY").unwrap_or_default(),
+        })
+    }
+}
+
+impl Default for ImageStoreSettings {
+    fn default() -> Self {
+        Self {
+            backend: ImageStoreBackend::Filesystem,
+            dir: "./data/images".to_string(),
+            max_bytes: MAX_ASSET_META_BYTES,
+            s3_bucket: String::new(),
+            s3_region: "us-east-1".to_string(),
+            s3_endpoint: None,
+            s3_access_key: String::new(),
+            s3_secret_key: String::new(),
+        }
+    }
+}
+
+/// LND REST access used for chain notifications, channel listings and the BTC
+/// wallet endpoints; disabled when no URL is set
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct LndSettings {
+    pub rest_url: Option<String>,
+    pub macaroon_hex: String,
+    /// LND's `tls.cert`, trusted when verifying its certificate
+    pub tls_cert_path: Option<String>,
+}
+
+impl LndSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        Ok(Self {
+            rest_url: std::env::var("LND_REST_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            // Falls back to the macaroon file the rest of the config points at
+            macaroon_hex: macaroon_from_env("LND_MACAROON_HEX", "LND_MACAROON_PATH")?,
+            tls_cert_path: std::env::var("LND_TLS_CERT_PATH")
+                .ok()
+                .filter(|path| !path.is_empty()),
+        })
+    }
+}
+
+/// How `TapdClient` reaches tapd
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TapdTransport {
+    /// The grpc-gateway REST proxy
+    #[default]
+    Rest,
+    /// tapd's native gRPC port, for nodes that do not expose the REST proxy
+    Grpc,
+}
+
+impl FromStr for TapdTransport {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rest" => Ok(TapdTransport::Rest),
+            "grpc" => Ok(TapdTransport::Grpc),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown TAPD_TRANSPORT: {other}. Expected rest or grpc."
+            ))),
+        }
+    }
+}
+
+/// Authentication for tapd's REST gateway, shared by `TapdClient` and the
+/// handlers that call tapd directly
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct TapdSettings {
+    pub macaroon_hex: String,
+    /// Sends no macaroon, for an unauthenticated local proxy
+    pub macaroon_disabled: bool,
+    pub transport: TapdTransport,
+    /// tapd's gRPC endpoint, used when `transport` is gRPC
+    pub grpc_url: String,
+    /// tapd's REST gateway, `TAPROOT_GATEWAY_URL`
+    pub gateway_url: Option<String>,
+}
+
+impl TapdSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let transport = std::env::var("TAPD_TRANSPORT")
+            .unwrap_or_else(|_| "rest".to_string())
+            .parse::<TapdTransport>()?;
+
+        let settings = Self {
+            macaroon_hex: macaroon_from_env("TAPROOT_MACAROON_HEX", "TAPD_MACAROON_PATH")?,
+            macaroon_disabled: env_or("TAPD_MACAROON_DISABLED", false),
+            transport,
+            grpc_url: std::env::var("TAPD_GRPC_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| "https://127.0.0.1:10029".to_string()),
+            gateway_url: std::env::var("TAPROOT_GATEWAY_URL").ok().filter(|url| !url.is_empty()),
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Channels, RFQ, the mailbox, universe sync and address decoding only
+    /// exist on the REST gateway, so gRPC does not stand in for it
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.transport == TapdTransport::Grpc && self.gateway_url.is_none() {
+            return Err(AppError::ValidationError(
+                "TAPD_TRANSPORT=grpc still needs TAPROOT_GATEWAY_URL for the calls tapd's \
+                 gRPC client does not cover"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The REST gateway, defaulting to tapd's local REST port
+    pub fn gateway_url(&self) -> String {
+        self.gateway_url.clone().unwrap_or_else(|| "http://127.0.0.1:8080".to_string())
+    }
+
+    /// The hex macaroon to send with tapd requests, if any
+    pub fn macaroon(&self) -> Option<&str> {
+        (!self.macaroon_disabled && !self.macaroon_hex.is_empty())
+            .then_some(self.macaroon_hex.as_str())
+    }
+}
+
+/// Transport to tapd, shared by `TapdClient` and the gateway's handlers
+#[derive(Clone, Deserialize, Debug)]
+pub struct HttpClientSettings {
+    pub tls_verify: bool,
+    /// PEM certificate to trust, such as tapd's self-signed `tls.cert`
+    pub ca_cert_path: Option<String>,
+    /// Bound on each unary call; streaming calls are only bounded while connecting
+    pub request_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    /// `socks5://` or `socks5h://` proxy, e.g. Tor for an onion-hosted node
+    pub socks5_proxy: Option<String>,
+}
+
+impl HttpClientSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+        let socks5_proxy = var("TAPD_SOCKS5_PROXY");
+        if let Some(proxy) = &socks5_proxy {
+            if !proxy.starts_with("socks5://") && !proxy.starts_with("socks5h://") {
+                return Err(AppError::ValidationError(format!(
+                    "TAPD_SOCKS5_PROXY must be a socks5:// or socks5h:// URL, got {proxy}"
+                )));
+            }
+        }
+
+        Ok(Self {
+            tls_verify: env_or("TLS_VERIFY", defaults.tls_verify),
+            ca_cert_path: var("TAPD_TLS_CERT_PATH"),
+            request_timeout_secs: env_or("REQUEST_TIMEOUT_SECS", defaults.request_timeout_secs),
+            connect_timeout_secs: env_or("CONNECT_TIMEOUT_SECS", defaults.connect_timeout_secs),
+            socks5_proxy,
+        })
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+}
+
+impl Default for HttpClientSettings {
+    fn default() -> Self {
+        Self {
+            // TLS verification defaults to true for production safety
+            tls_verify: true,
+            ca_cert_path: None,
+            request_timeout_secs: 30,
+            connect_timeout_secs: 10,
+            socks5_proxy: None,
+        }
+    }
+}
+
+/// Connection pool for `DATABASE_URL`
+#[derive(Clone, Deserialize, Debug)]
+pub struct DatabasePoolSettings {
+    pub max_connections: u32,
+    /// How long a query waits for a free connection before failing
+    pub acquire_timeout_secs: u64,
+    /// Idle connections are closed after this long; 0 keeps them open
+    pub idle_timeout_secs: u64,
+    /// Postgres `statement_timeout`; 0 lets statements run indefinitely.
+    /// SQLite has no equivalent and ignores it.
+    pub statement_timeout_ms: u64,
+}
+
+impl DatabasePoolSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_connections: env_or("DATABASE_MAX_CONNECTIONS", defaults.max_connections),
+            acquire_timeout_secs: env_or(
+                "DATABASE_ACQUIRE_TIMEOUT_SECS",
+                defaults.acquire_timeout_secs,
+            ),
+            idle_timeout_secs: env_or("DATABASE_IDLE_TIMEOUT_SECS", defaults.idle_timeout_secs),
+            statement_timeout_ms: env_or(
+                "DATABASE_STATEMENT_TIMEOUT_MS",
+                defaults.statement_timeout_ms,
+            ),
+        }
+    }
+
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_secs(self.acquire_timeout_secs)
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        (self.idle_timeout_secs > 0).then(|| Duration::from_secs(self.idle_timeout_secs))
+    }
+
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        (self.statement_timeout_ms > 0).then(|| Duration::from_millis(self.statement_timeout_ms))
+    }
+}
+
+impl Default for DatabasePoolSettings {
+    fn default() -> Self {
+        // sqlx's own defaults, apart from the statement timeout it does not set
+        Self {
+            max_connections: 10,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            statement_timeout_ms: 0,
+        }
+    }
+}
+
+/// Master keys sealing webhook secrets and push tokens in the database;
+/// they are stored unencrypted when no key is set
+#[derive(Clone, Default)]
+pub struct SecretsSettings {
+    pub master_key: Option<String>,
+    /// Keys replaced by a rotation, still accepted for values sealed under them
+    pub previous_master_keys: Vec<String>,
+}
+
+impl SecretsSettings {
+    pub fn from_env() -> Self {
+        Self {
+            master_key: std::env::var("SECRETS_MASTER_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+            previous_master_keys: std::env::var("SECRETS_PREVIOUS_MASTER_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Push relay that forwards notifications to FCM/APNs; pushes are only logged when unset
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct PushSettings {
+    pub relay_url: Option<String>,
+    pub relay_token: Option<String>,
+}
+
+impl PushSettings {
+    pub fn from_env() -> Self {
+        Self {
+            relay_url: std::env::var("PUSH_RELAY_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            relay_token: std::env::var("PUSH_RELAY_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+/// Fallback fee source used when LND cannot provide an estimate
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct FeeSettings {
+    /// Base URL of a mempool.space-compatible API
+    pub mempool_url: Option<String>,
+}
+
+impl FeeSettings {
+    pub fn from_env() -> Self {
+        Self {
+            mempool_url: std::env::var("FEE_ESTIMATE_MEMPOOL_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+        }
+    }
+}
+
+/// Operator limits on asset burns, on top of the checks every burn gets
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct BurnSettings {
+    /// Largest amount a single burn may destroy; unlimited when unset
+    pub max_amount: Option<u64>,
+}
+
+impl BurnSettings {
+    pub fn from_env() -> Self {
+        Self {
+            max_amount: std::env::var("BURN_MAX_AMOUNT")
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok()),
+        }
+    }
+}
+
+/// Retries and circuit breaking for calls to tapd, LND and other upstreams
+#[derive(Clone, Deserialize, Debug)]
+pub struct UpstreamSettings {
+    /// Tries per call, including the first
+    pub max_attempts: u32,
+    /// Backoff before the first retry; doubles on each further attempt
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Consecutive failed calls that open an upstream's circuit
+    pub breaker_failure_threshold: u32,
+    /// How long an open circuit fails fast before letting a trial call through
+    pub breaker_open_secs: u64,
+}
+
+impl UpstreamSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_attempts: env_or("UPSTREAM_MAX_ATTEMPTS", defaults.max_attempts).max(1),
+            initial_backoff_ms: env_or("UPSTREAM_INITIAL_BACKOFF_MS", defaults.initial_backoff_ms),
+            max_backoff_ms: env_or("UPSTREAM_MAX_BACKOFF_MS", defaults.max_backoff_ms),
+            breaker_failure_threshold: env_or(
+                "UPSTREAM_BREAKER_FAILURE_THRESHOLD",
+                defaults.breaker_failure_threshold,
+            )
+            .max(1),
+            breaker_open_secs: env_or("UPSTREAM_BREAKER_OPEN_SECS", defaults.breaker_open_secs),
+        }
+    }
+}
+
+impl Default for UpstreamSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 2000,
+            breaker_failure_threshold: 5,
+            breaker_open_secs: 30,
+        }
+    }
+}
+
+/// How long read-heavy tapd responses are served from memory; 0 disables
+/// caching for that response
+#[derive(Clone, Deserialize, Debug)]
+pub struct CacheSettings {
+    pub assets_ttl_secs: u64,
+    pub balance_ttl_secs: u64,
+    pub info_ttl_secs: u64,
+    pub universe_roots_ttl_secs: u64,
+}
+
+impl CacheSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            assets_ttl_secs: env_or("CACHE_ASSETS_TTL_SECS", defaults.assets_ttl_secs),
+            balance_ttl_secs: env_or("CACHE_BALANCE_TTL_SECS", defaults.balance_ttl_secs),
+            info_ttl_secs: env_or("CACHE_INFO_TTL_SECS", defaults.info_ttl_secs),
+            universe_roots_ttl_secs: env_or(
+                "CACHE_UNIVERSE_ROOTS_TTL_SECS",
+                defaults.universe_roots_ttl_secs,
+            ),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            assets_ttl_secs: 0,
+            balance_ttl_secs: 0,
+            info_ttl_secs: 0,
+            universe_roots_ttl_secs: 0,
+        }
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            assets_ttl_secs: 5,
+            balance_ttl_secs: 5,
+            info_ttl_secs: 30,
+            universe_roots_ttl_secs: 60,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceOracleBackend {
+    Tapd,
+    Http,
+}
+
+impl FromStr for PriceOracleBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tapd" => Ok(PriceOracleBackend::Tapd),
+            "http" => Ok(PriceOracleBackend::Http),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown PRICE_ORACLE_BACKEND: {other}. Expected tapd or http."
+            ))),
+        }
+    }
+}
+
+/// Where `/rfq/rates` gets asset prices from
+#[derive(Clone, Deserialize, Debug)]
+pub struct PriceOracleSettings {
+    pub backend: PriceOracleBackend,
+    /// HTTP feed URL with an `{asset_id}` placeholder, for the http backend
+    pub feed_url: Option<String>,
+    /// Rates older than this are reported as stale
+    pub max_age_secs: u64,
+    /// Currency that balances are valued in, e.g. `USD`
+    pub fiat_currency: Option<String>,
+    /// Oracle asset whose rate is read as whole fiat units per BTC
+    pub fiat_asset_id: Option<String>,
+}
+
+/// Prices balances in a fiat currency through the configured oracle
+#[derive(Clone, Debug, PartialEq)]
+pub struct FiatReference {
+    pub currency: String,
+    pub asset_id: String,
+}
+
+impl PriceOracleSettings {
+    /// `None` unless both the currency and its reference asset are set
+    pub fn fiat_reference(&self) -> Option<FiatReference> {
+        Some(FiatReference {
+            currency: self.fiat_currency.clone()?,
+            asset_id: self.fiat_asset_id.clone()?,
+        })
+    }
+
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let backend = std::env::var("PRICE_ORACLE_BACKEND")
+            .unwrap_or_else(|_| "tapd".to_string())
+            .parse::<PriceOracleBackend>()?;
+        let feed_url = std::env::var("PRICE_ORACLE_FEED_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let max_age_secs = std::env::var("PRICE_ORACLE_MAX_AGE_SECS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(defaults.max_age_secs);
+        let non_empty = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        Ok(Self {
+            backend,
+            feed_url,
+            max_age_secs,
+            fiat_currency: non_empty("PRICE_ORACLE_FIAT_CURRENCY"),
+            fiat_asset_id: non_empty("PRICE_ORACLE_FIAT_ASSET_ID"),
+        })
+    }
+}
+
+impl Default for PriceOracleSettings {
+    fn default() -> Self {
+        Self {
+            backend: PriceOracleBackend::Tapd,
+            feed_url: None,
+            max_age_secs: 300,
+            fiat_currency: None,
+            fiat_asset_id: None,
+        }
+    }
+}
+
+/// Backend used to store RFQ price alerts registered through `/rfq/alerts`
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceAlertStoreBackend {
+    Memory,
+    Postgres,
+}
+
+impl FromStr for PriceAlertStoreBackend {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(PriceAlertStoreBackend::Memory),
+            "postgres" => Ok(PriceAlertStoreBackend::Postgres),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown PRICE_ALERT_STORE_BACKEND: {other}. Expected memory or postgres."
+            ))),
+        }
+    }
+}
+
+/// Storage of RFQ price alerts and how often pending ones are checked
+/// against the oracle
+#[derive(Clone, Deserialize, Debug)]
+pub struct PriceAlertSettings {
+    pub backend: PriceAlertStoreBackend,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for PriceAlertSettings {
+    fn default() -> Self {
+        Self {
+            backend: PriceAlertStoreBackend::Postgres,
+            poll_interval_secs: 60,
+        }
+    }
+}
+
+impl PriceAlertSettings {
+    pub fn from_env() -> Result<Self, AppError> {
+        let defaults = Self::default();
+        let backend = std::env::var("PRICE_ALERT_STORE_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse::<PriceAlertStoreBackend>()?;
+
+        Ok(Self {
+            backend,
+            // Zero would panic the monitor's tokio interval
+            poll_interval_secs: env_or(
+                "PRICE_ALERT_POLL_INTERVAL_SECS",
+                defaults.poll_interval_secs,
+            )
+            .max(1),
+        })
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+}
+
+/// How often persisted transactions are reconciled against tapd
+#[derive(Clone, Deserialize, Debug)]
+pub struct ReconcileSettings {
+    /// 0 disables the background pass; `/admin/reconcile` still runs one
+    pub interval_secs: u64,
+}
+
+impl Default for ReconcileSettings {
+    fn default() -> Self {
+        Self { interval_secs: 300 }
+    }
+}
+
+impl ReconcileSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            interval_secs: env_or("RECONCILE_INTERVAL_SECS", defaults.interval_secs),
+        }
+    }
+
+    /// `None` when the background pass is disabled
+    pub fn interval(&self) -> Option<Duration> {
+        (self.interval_secs > 0).then(|| Duration::from_secs(self.interval_secs))
+    }
+}
+
+/// How long recorded asset events are kept, and how often expired rows are
+/// pruned
+#[derive(Clone, Deserialize, Debug)]
+pub struct RetentionSettings {
+    /// 0 disables the background job
+    pub interval_secs: u64,
+    /// Days a recorded asset event is kept for backfill; 0 keeps it forever
+    pub event_days: u64,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            event_days: 30,
+        }
+    }
+}
+
+impl RetentionSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            interval_secs: env_or("RETENTION_INTERVAL_SECS", defaults.interval_secs),
+            event_days: env_or("EVENT_RETENTION_DAYS", defaults.event_days),
+        }
+    }
+
+    /// `None` when the background job is disabled
+    pub fn interval(&self) -> Option<Duration> {
+        (self.interval_secs > 0).then(|| Duration::from_secs(self.interval_secs))
+    }
+
+    /// Events received before this unix time are pruned
+    pub fn event_cutoff(&self, now: i64) -> Option<i64> {
+        retention_cutoff(self.event_days, now)
+    }
+}
+
+fn retention_cutoff(days: u64, now: i64) -> Option<i64> {
+    let days = i64::try_from(days).ok().filter(|days| *days > 0)?;
+    Some(now.saturating_sub(days.saturating_mul(86_400)))
+}
+
+/// Timing of the RFQ notification WebSocket
+#[derive(Clone, Deserialize, Debug)]
+pub struct RfqSettings {
+    /// Used only when tapd cannot stream notifications
+    pub poll_interval_secs: u64,
+    /// Keepalive pings sent to connected clients
+    pub ping_interval_secs: u64,
+    /// Payments re-request quotes expiring within this many seconds; 0 disables
+    pub quote_refresh_secs: u64,
+    /// Answer RFQ offers and orders with simulated quotes instead of calling tapd
+    pub simulate: bool,
+    /// Asset units per BTC quoted in simulation mode
+    pub simulated_rate: String,
+}
+
+impl Default for RfqSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            ping_interval_secs: 30,
+            quote_refresh_secs: 0,
+            simulate: false,
+            simulated_rate: "100000".to_string(),
+        }
+    }
+}
+
+impl RfqSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            // Both feed tokio intervals, which panic on a zero period
+            poll_interval_secs: secs("RFQ_POLL_INTERVAL_SECS", defaults.poll_interval_secs).max(1),
+            ping_interval_secs: secs("RFQ_PING_INTERVAL_SECS", defaults.ping_interval_secs).max(1),
+            quote_refresh_secs: secs("RFQ_QUOTE_REFRESH_SECS", defaults.quote_refresh_secs),
+            simulate: std::env::var("RFQ_SIMULATE")
+                .ok()
+                .and_then(|value| value.parse::<bool>().ok())
+                .unwrap_or(defaults.simulate),
+            simulated_rate: std::env::var("RFQ_SIMULATED_RATE")
+                .unwrap_or(defaults.simulated_rate),
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+
+    pub fn ping_interval(&self) -> Duration {
+        Duration::from_secs(self.ping_interval_secs)
+    }
+}
+
+/// Per-receiver limits enforced by the mailbox, plus operator access settings
+#[derive(Clone, Deserialize, Debug)]
+pub struct MailboxSettings {
+    pub messages_per_minute: u32,
+    pub byte_quota_per_minute: usize,
+    pub max_message_size_bytes: usize,
+    /// Largest payload that may be relayed as a sequence of chunked frames
+    pub max_payload_size_bytes: usize,
+    pub max_connections_per_receiver: usize,
+    /// Delivery attempts per webhook before giving up
+    pub webhook_max_attempts: u32,
+    /// Delay before the first webhook retry; doubles on each further attempt
+    pub webhook_initial_backoff_ms: u64,
+    /// Bearer token for the admin endpoints (mailbox and debug level); they are disabled when unset
+    pub admin_token: Option<String>,
+}
+
+impl MailboxSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            messages_per_minute: env_or(
+                "MAILBOX_MESSAGES_PER_MINUTE",
+                defaults.messages_per_minute,
+            ),
+            byte_quota_per_minute: env_or(
+                "MAILBOX_BYTE_QUOTA_PER_MINUTE",
+                defaults.byte_quota_per_minute,
+            ),
+            max_message_size_bytes: env_or(
+                "MAILBOX_MAX_MESSAGE_SIZE_BYTES",
+                defaults.max_message_size_bytes,
+            ),
+            max_payload_size_bytes: env_or(
+                "MAILBOX_MAX_PAYLOAD_SIZE_BYTES",
+                defaults.max_payload_size_bytes,
+            ),
+            max_connections_per_receiver: env_or(
+                "MAILBOX_MAX_CONNECTIONS_PER_RECEIVER",
+                defaults.max_connections_per_receiver,
+            ),
+            webhook_max_attempts: env_or(
+                "MAILBOX_WEBHOOK_MAX_ATTEMPTS",
+                defaults.webhook_max_attempts,
+            ),
+            webhook_initial_backoff_ms: env_or(
+                "MAILBOX_WEBHOOK_INITIAL_BACKOFF_MS",
+                defaults.webhook_initial_backoff_ms,
+            ),
+            admin_token: std::env::var("MAILBOX_ADMIN_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }
+    }
+}
+
+impl Default for MailboxSettings {
+    fn default() -> Self {
+        Self {
+            messages_per_minute: 60,
+            byte_quota_per_minute: 1024 * 1024, // 1MB
+            max_message_size_bytes: 64 * 1024,  // 64KB
+            max_payload_size_bytes: 4 * 1024 * 1024, // 4MB
+            max_connections_per_receiver: 3,
+            webhook_max_attempts: 5,
+            webhook_initial_backoff_ms: 1000,
+            admin_token: None,
+        }
+    }
+}
+
+/// Reads and parses an environment variable, falling back to `default` when unset or invalid
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+/// Reads a hex macaroon from `hex_key`, or else from the macaroon file at
+/// `path_key`; empty when neither is set. Either may hold the macaroon sealed
+/// under `SECRETS_MASTER_KEY` by the `seal-macaroon` binary, in which case it
+/// is opened here and only kept in plaintext in memory.
+fn macaroon_from_env(hex_key: &str, path_key: &str) -> Result<String, AppError> {
+    let stored = std::env::var(hex_key)
+        .ok()
+        .filter(|hex| !hex.is_empty())
+        .or_else(|| {
+            let bytes = std::fs::read(std::env::var(path_key).ok()?).ok()?;
+            // A sealed file holds text; a plain one is the node's binary macaroon
+            Some(match std::str::from_utf8(&bytes).map(str::trim) {
+                Ok(text) if SecretBox::is_sealed(text) => text.to_string(),
+                _ => hex::encode(bytes),
+            })
+        })
+        .unwrap_or_default();
+    if !SecretBox::is_sealed(&stored) {
+        return Ok(stored);
+    }
+
+    SecretBox::from_settings(&SecretsSettings::from_env())?
+        .open(&stored)
+        .map_err(|e| {
+            AppError::ValidationError(format!("Sealed macaroon in {hex_key}/{path_key}: {e}"))
+        })
+}
 
 #[derive(Clone, Deserialize, Debug)]
 pub struct Config {
@@ -12,7 +1358,27 @@ pub struct Config {
     pub server_address: String,
     pub request_timeout_secs: u64,
     pub rate_limit_per_minute: usize,
-    pub rfq_poll_interval_secs: u64,
+    pub http_client: HttpClientSettings,
+    pub upstream: UpstreamSettings,
+    pub cache: CacheSettings,
+    pub rfq: RfqSettings,
+    pub price_oracle: PriceOracleSettings,
+    pub price_alerts: PriceAlertSettings,
+    pub challenge_store: ChallengeStoreSettings,
+    pub mailbox: MailboxSettings,
+    pub event_store: EventStoreSettings,
+    pub payment_store: PaymentStoreSettings,
+    pub rfq_order_store: RfqOrderStoreSettings,
+    pub idempotency_store: IdempotencyStoreSettings,
+    pub webhooks: WebhookSettings,
+    pub asset_preferences: AssetPreferenceStoreSettings,
+    pub image_store: ImageStoreSettings,
+    pub tapd: TapdSettings,
+    pub lnd: LndSettings,
+    pub push: PushSettings,
+    pub fees: FeeSettings,
+    pub burn: BurnSettings,
+    pub database_pool: DatabasePoolSettings,
 }
 
 impl Config {
@@ -26,11 +1392,9 @@ impl Config {
         let macaroon_path = std::env::var("TAPD_MACAROON_PATH")?;
         let lnd_macaroon_path = std::env::var("LND_MACAROON_PATH")?;
 
-        // Security settings - TLS verification defaults to true for production safety
-        let tls_verify = std::env::var("TLS_VERIFY")
-            .unwrap_or_else(|_| "true".to_string())
-            .parse::<bool>()
-            .unwrap_or(true);
+        // Transport to tapd: TLS, timeouts and proxy
+        let http_client = HttpClientSettings::from_env()?;
+        let tls_verify = http_client.tls_verify;
 
         // CORS configuration
         let cors_origins = std::env::var("CORS_ORIGINS")
@@ -44,10 +1408,13 @@ impl Config {
             std::env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
 
         // Request timeout configuration
-        let request_timeout_secs = std::env::var("REQUEST_TIMEOUT_SECS")
-            .unwrap_or_else(|_| "30".to_string())
-            .parse::<u64>()
-            .unwrap_or(30);
+        let request_timeout_secs = http_client.request_timeout_secs;
+
+        // Upstream retry and circuit breaker configuration
+        let upstream = UpstreamSettings::from_env();
+
+        // Response cache TTL configuration
+        let cache = CacheSettings::from_env();
 
         // Rate limiting configuration
         let rate_limit_per_minute = std::env::var("RATE_LIMIT_PER_MINUTE")
@@ -55,11 +1422,59 @@ impl Config {
             .parse::<usize>()
             .unwrap_or(100);
 
-        // RFQ polling interval configuration
-        let rfq_poll_interval_secs = std::env::var("RFQ_POLL_INTERVAL_SECS")
-            .unwrap_or_else(|_| "5".to_string())
-            .parse::<u64>()
-            .unwrap_or(5);
+        // RFQ WebSocket polling and ping interval configuration
+        let rfq = RfqSettings::from_env();
+
+        // RFQ rate source configuration
+        let price_oracle = PriceOracleSettings::from_env()?;
+
+        // RFQ price alert polling configuration
+        let price_alerts = PriceAlertSettings::from_env()?;
+
+        // Mailbox challenge store configuration
+        let challenge_store = ChallengeStoreSettings::from_env()?;
+
+        // Mailbox rate limit and quota configuration
+        let mailbox = MailboxSettings::from_env();
+
+        // Asset event history configuration
+        let event_store = EventStoreSettings::from_env()?;
+
+        // Payment history configuration
+        let payment_store = PaymentStoreSettings::from_env()?;
+
+        // RFQ order book configuration
+        let rfq_order_store = RfqOrderStoreSettings::from_env()?;
+
+        // Idempotency-Key results for sends, mints and burns
+        let idempotency_store = IdempotencyStoreSettings::from_env()?;
+
+        // Integrator webhook storage and delivery
+        let webhooks = WebhookSettings::from_env()?;
+
+        // Favorite and hidden asset preferences
+        let asset_preferences = AssetPreferenceStoreSettings::from_env()?;
+
+        // Collectible image storage configuration
+        let image_store = ImageStoreSettings::from_env()?;
+
+        // Tapd macaroon and transport configuration
+        let tapd = TapdSettings::from_env()?;
+
+        // LND chain notification configuration
+        let lnd = LndSettings::from_env()?;
+
+        // Mobile push notification configuration
+        let push = PushSettings::from_env();
+
+        // Fee estimation fallback configuration
+        let fees = FeeSettings::from_env();
+
+        // Burn size limit configuration
+        let burn = BurnSettings::from_env();
+
+        // Database connection pool configuration
+        let database_pool = DatabasePoolSettings::from_env();
 
         // Validate paths exist
         if !Path::new(&macaroon_path).exists() {
@@ -82,7 +1497,27 @@ impl Config {
             server_address,
             request_timeout_secs,
             rate_limit_per_minute,
-            rfq_poll_interval_secs,
+            http_client,
+            upstream,
+            cache,
+            rfq,
+            price_oracle,
+            price_alerts,
+            challenge_store,
+            mailbox,
+            event_store,
+            payment_store,
+            rfq_order_store,
+            idempotency_store,
+            webhooks,
+            asset_preferences,
+            image_store,
+            tapd,
+            lnd,
+            push,
+            fees,
+            burn,
+            database_pool,
         };
 
         // Validate configuration
@@ -133,12 +1568,112 @@ impl Config {
             ));
         }
 
-        // Validate RFQ polling interval
-        if self.rfq_poll_interval_secs == 0 {
+        // Validate RFQ polling and ping intervals
+        if self.rfq.poll_interval_secs == 0 {
             return Err(AppError::ValidationError(
                 "RFQ_POLL_INTERVAL_SECS must be greater than 0".to_string(),
             ));
         }
+        if self.rfq.ping_interval_secs == 0 {
+            return Err(AppError::ValidationError(
+                "RFQ_PING_INTERVAL_SECS must be greater than 0".to_string(),
+            ));
+        }
+        let simulated_rate = self.rfq.simulated_rate.parse::<f64>().unwrap_or(0.0);
+        if self.rfq.simulate && !(simulated_rate > 0.0 && simulated_rate.is_finite()) {
+            return Err(AppError::ValidationError(
+                "RFQ_SIMULATED_RATE must be a positive decimal".to_string(),
+            ));
+        }
+
+        // Validate price oracle configuration
+        if self.price_oracle.backend == PriceOracleBackend::Http
+            && self.price_oracle.feed_url.is_none()
+        {
+            return Err(AppError::ValidationError(
+                "PRICE_ORACLE_FEED_URL must be set when PRICE_ORACLE_BACKEND=http".to_string(),
+            ));
+        }
+
+        if self.price_oracle.fiat_currency.is_some() != self.price_oracle.fiat_asset_id.is_some() {
+            return Err(AppError::ValidationError(
+                "PRICE_ORACLE_FIAT_CURRENCY and PRICE_ORACLE_FIAT_ASSET_ID must be set together"
+                    .to_string(),
+            ));
+        }
+
+        if self.price_alerts.poll_interval_secs == 0 {
+            return Err(AppError::ValidationError(
+                "PRICE_ALERT_POLL_INTERVAL_SECS must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.webhooks.poll_interval_secs == 0 || self.webhooks.max_attempts == 0 {
+            return Err(AppError::ValidationError(
+                "WEBHOOK_POLL_INTERVAL_SECS and WEBHOOK_MAX_ATTEMPTS must be greater than 0"
+                    .to_string(),
+            ));
+        }
+
+        // Validate challenge store configuration
+        if self.challenge_store.backend == ChallengeStoreBackend::Redis
+            && self.challenge_store.redis_url.is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "REDIS_URL cannot be empty when CHALLENGE_STORE_BACKEND=redis".to_string(),
+            ));
+        }
+
+        // Validate mailbox limits
+        if self.mailbox.messages_per_minute == 0
+            || self.mailbox.byte_quota_per_minute == 0
+            || self.mailbox.max_message_size_bytes == 0
+            || self.mailbox.max_connections_per_receiver == 0
+            || self.mailbox.webhook_max_attempts == 0
+        {
+            return Err(AppError::ValidationError(
+                "MAILBOX_* limits must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.mailbox.max_payload_size_bytes < self.mailbox.max_message_size_bytes {
+            return Err(AppError::ValidationError(
+                "MAILBOX_MAX_PAYLOAD_SIZE_BYTES cannot be smaller than MAILBOX_MAX_MESSAGE_SIZE_BYTES"
+                    .to_string(),
+            ));
+        }
+
+        // Validate image storage configuration
+        if self.image_store.max_bytes == 0 || self.image_store.max_bytes > MAX_ASSET_META_BYTES {
+            return Err(AppError::ValidationError(format!(
+                "IMAGE_MAX_BYTES must be between 1 and {MAX_ASSET_META_BYTES}"
+            )));
+        }
+        if self.image_store.backend == ImageStoreBackend::S3
+            && (self.image_store.s3_bucket.is_empty()
+                || self.image_store.s3_access_key.is_empty()
+                || self.image_store.s3_secret_key.is_empty())
+        {
+            return Err(AppError::ValidationError(
+                "IMAGE_S3_BUCKET, AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set when IMAGE_STORE_BACKEND=s3"
+                    .to_string(),
+            ));
+        }
+
+        // Validate burn limits
+        if self.burn.max_amount == Some(0) {
+            return Err(AppError::ValidationError(
+                "BURN_MAX_AMOUNT must be greater than 0 when set".to_string(),
+            ));
+        }
+
+        // Validate database pool limits
+        if self.database_pool.max_connections == 0 || self.database_pool.acquire_timeout_secs == 0 {
+            return Err(AppError::ValidationError(
+                "DATABASE_MAX_CONNECTIONS and DATABASE_ACQUIRE_TIMEOUT_SECS must be greater than 0"
+                    .to_string(),
+            ));
+        }
 
         Ok(())
     }
@@ -155,12 +1690,33 @@ impl Config {
             server_address: "127.0.0.1:8080".to_string(),
             request_timeout_secs: 30,
             rate_limit_per_minute: 100,
-            rfq_poll_interval_secs: 5,
+            http_client: HttpClientSettings::default(),
+            upstream: UpstreamSettings::default(),
+            cache: CacheSettings::default(),
+            rfq: RfqSettings::default(),
+            price_oracle: PriceOracleSettings::default(),
+            price_alerts: PriceAlertSettings::default(),
+            challenge_store: ChallengeStoreSettings::default(),
+            mailbox: MailboxSettings::default(),
+            event_store: EventStoreSettings::default(),
+            payment_store: PaymentStoreSettings::default(),
+            rfq_order_store: RfqOrderStoreSettings::default(),
+            idempotency_store: IdempotencyStoreSettings::default(),
+            webhooks: WebhookSettings::default(),
+            asset_preferences: AssetPreferenceStoreSettings::default(),
+            image_store: ImageStoreSettings::default(),
+            tapd: TapdSettings::default(),
+            lnd: LndSettings::default(),
+            push: PushSettings::default(),
+            fees: FeeSettings::default(),
+            burn: BurnSettings::default(),
+            database_pool: DatabasePoolSettings::default(),
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use std::env;
@@ -190,6 +1746,20 @@ mod tests {
         assert!(matches!(result.unwrap_err(), AppError::ValidationError(_)));
     }
 
+    #[test]
+    fn test_grpc_transport_requires_the_rest_gateway() {
+        let mut tapd = TapdSettings {
+            transport: TapdTransport::Grpc,
+            ..TapdSettings::default()
+        };
+        assert!(matches!(tapd.validate(), Err(AppError::ValidationError(_))));
+
+        tapd.gateway_url = Some("https://tapd.local:8089".to_string());
+        assert!(tapd.validate().is_ok());
+        assert_eq!(tapd.gateway_url(), "https://tapd.local:8089");
+        assert_eq!(TapdSettings::default().gateway_url(), "http://127.0.0.1:8080");
+    }
+
     #[test]
     fn test_config_validation_empty_lnd_macaroon_path() {
         let mut config = Config::test_config();
@@ -229,12 +1799,274 @@ mod tests {
     #[test]
     fn test_config_validation_zero_rfq_interval() {
         let mut config = Config::test_config();
-        config.rfq_poll_interval_secs = 0;
+        config.rfq.poll_interval_secs = 0;
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), AppError::ValidationError(_)));
+
+        let mut config = Config::test_config();
+        config.rfq.ping_interval_secs = 0;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+
+        env::set_var("RFQ_PING_INTERVAL_SECS", "0");
+        assert_eq!(RfqSettings::from_env().ping_interval(), Duration::from_secs(1));
+        env::remove_var("RFQ_PING_INTERVAL_SECS");
+    }
+
+    #[test]
+    fn test_config_validation_rfq_simulation_rate() {
+        let mut config = Config::test_config();
+        config.rfq.simulated_rate = "0".to_string();
+        assert!(config.validate().is_ok());
+
+        config.rfq.simulate = true;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+
+        config.rfq.simulated_rate = "64250.5".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_validation_redis_store_without_url() {
+        let mut config = Config::test_config();
+        config.challenge_store.backend = ChallengeStoreBackend::Redis;
+        config.challenge_store.redis_url = "".to_string();
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), AppError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_config_validation_zero_mailbox_limit() {
+        let mut config = Config::test_config();
+        config.mailbox.max_connections_per_receiver = 0;
         let result = config.validate();
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), AppError::ValidationError(_)));
     }
 
+    #[test]
+    fn test_config_validation_payload_smaller_than_message() {
+        let mut config = Config::test_config();
+        config.mailbox.max_payload_size_bytes = config.mailbox.max_message_size_bytes - 1;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_config_validation_image_store() {
+        let mut config = Config::test_config();
+        config.image_store.max_bytes = MAX_ASSET_META_BYTES + 1;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+
+        let mut config = Config::test_config();
+        config.image_store.backend = ImageStoreBackend::S3;
+        config.image_store.s3_bucket = "collectibles".to_string();
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+
+        config.image_store.s3_access_key = "AKID".to_string();
+        config.image_store.s3_secret_key = "secret".to_string();
+        assert!(config.validate().is_ok());
+        assert_eq!("S3".parse::<ImageStoreBackend>().unwrap(), ImageStoreBackend::S3);
+    }
+
+    #[test]
+    fn test_config_validation_fiat_reference() {
+        let mut config = Config::test_config();
+        config.price_oracle.fiat_currency = Some("USD".to_string());
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+        assert_eq!(config.price_oracle.fiat_reference(), None);
+
+        config.price_oracle.fiat_asset_id = Some("usd".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.price_oracle.fiat_reference().unwrap().currency, "USD");
+    }
+
+    #[test]
+    fn test_config_validation_burn_limit() {
+        let mut config = Config::test_config();
+        config.burn.max_amount = Some(0);
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+        config.burn.max_amount = Some(1_000);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_validation_database_pool() {
+        let mut config = Config::test_config();
+        assert_eq!(config.database_pool.idle_timeout(), Some(Duration::from_secs(600)));
+        assert_eq!(config.database_pool.statement_timeout(), None);
+        config.database_pool.max_connections = 0;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+        config.database_pool.max_connections = 1;
+        config.database_pool.idle_timeout_secs = 0;
+        assert!(config.validate().is_ok());
+        assert_eq!(config.database_pool.idle_timeout(), None);
+    }
+
+    #[test]
+    fn test_env_or_falls_back_on_invalid_value() {
+        env::set_var("TEST_ENV_OR_INVALID", "not-a-number");
+        assert_eq!(env_or("TEST_ENV_OR_INVALID", 42u32), 42);
+        env::set_var("TEST_ENV_OR_INVALID", "7");
+        assert_eq!(env_or("TEST_ENV_OR_INVALID", 42u32), 7);
+        env::remove_var("TEST_ENV_OR_INVALID");
+    }
+
+    #[test]
+    fn test_challenge_store_backend_parsing() {
+        assert_eq!("memory".parse::<ChallengeStoreBackend>().unwrap(), ChallengeStoreBackend::Memory);
+        assert_eq!("Redis".parse::<ChallengeStoreBackend>().unwrap(), ChallengeStoreBackend::Redis);
+        assert_eq!("postgres".parse::<ChallengeStoreBackend>().unwrap(), ChallengeStoreBackend::Postgres);
+        assert!(matches!(
+            "mysql".parse::<ChallengeStoreBackend>().unwrap_err(),
+            AppError::ValidationError(_)
+        ));
+    }
+
+    #[test]
+    fn test_event_store_backend_parsing() {
+        assert_eq!("Postgres".parse::<EventStoreBackend>().unwrap(), EventStoreBackend::Postgres);
+        assert_eq!("memory".parse::<EventStoreBackend>().unwrap(), EventStoreBackend::Memory);
+        assert!("redis".parse::<EventStoreBackend>().is_err());
+    }
+
+    #[test]
+    fn test_payment_store_backend_parsing() {
+        assert_eq!(
+            "postgres".parse::<PaymentStoreBackend>().unwrap(),
+            PaymentStoreBackend::Postgres
+        );
+        assert_eq!(
+            " Memory ".parse::<PaymentStoreBackend>().unwrap(),
+            PaymentStoreBackend::Memory
+        );
+        assert!("sqlite".parse::<PaymentStoreBackend>().is_err());
+    }
+
+    #[test]
+    fn test_price_oracle_settings() {
+        assert_eq!(" HTTP ".parse::<PriceOracleBackend>().unwrap(), PriceOracleBackend::Http);
+        assert!("coingecko".parse::<PriceOracleBackend>().is_err());
+
+        let mut config = Config::test_config();
+        config.price_oracle.backend = PriceOracleBackend::Http;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+        config.price_oracle.feed_url = Some("https://prices.example/{asset_id}".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rfq_order_store_backend_parsing() {
+        assert_eq!(
+            "postgres".parse::<RfqOrderStoreBackend>().unwrap(),
+            RfqOrderStoreBackend::Postgres
+        );
+        assert_eq!(
+            "MEMORY".parse::<RfqOrderStoreBackend>().unwrap(),
+            RfqOrderStoreBackend::Memory
+        );
+        assert!("redis".parse::<RfqOrderStoreBackend>().is_err());
+    }
+
+    #[test]
+    fn test_idempotency_store_backend_parsing() {
+        assert_eq!(
+            "Postgres".parse::<IdempotencyStoreBackend>().unwrap(),
+            IdempotencyStoreBackend::Postgres
+        );
+        assert_eq!(
+            "memory".parse::<IdempotencyStoreBackend>().unwrap(),
+            IdempotencyStoreBackend::Memory
+        );
+        assert!("sqlite".parse::<IdempotencyStoreBackend>().is_err());
+    }
+
+    #[test]
+    fn test_asset_preference_store_backend_parsing() {
+        assert_eq!(
+            "postgres".parse::<AssetPreferenceStoreBackend>().unwrap(),
+            AssetPreferenceStoreBackend::Postgres
+        );
+        assert_eq!(
+            "Memory".parse::<AssetPreferenceStoreBackend>().unwrap(),
+            AssetPreferenceStoreBackend::Memory
+        );
+        assert!("sqlite".parse::<AssetPreferenceStoreBackend>().is_err());
+    }
+
+    #[test]
+    fn test_webhook_settings() {
+        assert_eq!(
+            "postgres".parse::<WebhookStoreBackend>().unwrap(),
+            WebhookStoreBackend::Postgres
+        );
+        assert_eq!(" Memory ".parse::<WebhookStoreBackend>().unwrap(), WebhookStoreBackend::Memory);
+        assert!("redis".parse::<WebhookStoreBackend>().is_err());
+
+        let mut config = Config::test_config();
+        config.webhooks.max_attempts = 0;
+        assert!(matches!(config.validate(), Err(AppError::ValidationError(_))));
+
+        env::set_var("WEBHOOK_POLL_INTERVAL_SECS", "0");
+        assert_eq!(WebhookSettings::from_env().unwrap().poll_interval(), Duration::from_secs(1));
+        env::remove_var("WEBHOOK_POLL_INTERVAL_SECS");
+    }
+
+    #[test]
+    fn test_sealed_macaroons_are_opened() {
+        let master_key = "macaroon-master-key-with-at-least-32-chars";
+        let sealed = SecretBox::new(master_key, &[]).unwrap().seal("0201abcd").unwrap();
+        let sealed_file = NamedTempFile::new().unwrap();
+        std::fs::write(sealed_file.path(), format!("{sealed}\n")).unwrap();
+        let plain_file = NamedTempFile::new().unwrap();
+        std::fs::write(plain_file.path(), [0x02, 0x01, 0xab, 0xcd]).unwrap();
+
+        env::set_var("SECRETS_MASTER_KEY", master_key);
+        env::set_var("TEST_SEALED_MACAROON_HEX", &sealed);
+        env::set_var("TEST_SEALED_MACAROON_PATH", sealed_file.path());
+        env::set_var("TEST_PLAIN_MACAROON_PATH", plain_file.path());
+        let from_hex = macaroon_from_env("TEST_SEALED_MACAROON_HEX", "TEST_UNSET_PATH");
+        let from_file = macaroon_from_env("TEST_UNSET_HEX", "TEST_SEALED_MACAROON_PATH");
+        let plain = macaroon_from_env("TEST_UNSET_HEX", "TEST_PLAIN_MACAROON_PATH");
+        env::remove_var("SECRETS_MASTER_KEY");
+        let without_key = macaroon_from_env("TEST_SEALED_MACAROON_HEX", "TEST_UNSET_PATH");
+        env::remove_var("TEST_SEALED_MACAROON_HEX");
+        env::remove_var("TEST_SEALED_MACAROON_PATH");
+        env::remove_var("TEST_PLAIN_MACAROON_PATH");
+
+        assert_eq!(from_hex.unwrap(), "0201abcd");
+        assert_eq!(from_file.unwrap(), "0201abcd");
+        assert_eq!(plain.unwrap(), "0201abcd");
+        assert!(matches!(without_key, Err(AppError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_price_alert_settings() {
+        assert_eq!(
+            " Memory ".parse::<PriceAlertStoreBackend>().unwrap(),
+            PriceAlertStoreBackend::Memory
+        );
+        assert!("redis".parse::<PriceAlertStoreBackend>().is_err());
+
+        env::set_var("PRICE_ALERT_POLL_INTERVAL_SECS", "0");
+        let settings = PriceAlertSettings::from_env().unwrap();
+        assert_eq!(settings.backend, PriceAlertStoreBackend::Postgres);
+        assert_eq!(settings.poll_interval(), Duration::from_secs(1));
+        env::remove_var("PRICE_ALERT_POLL_INTERVAL_SECS");
+    }
+
+    #[test]
+    fn test_retention_cutoffs() {
+        let settings = RetentionSettings {
+            interval_secs: 0,
+            event_days: 2,
+        };
+        assert_eq!(settings.interval(), None);
+        assert_eq!(settings.event_cutoff(1_000_000), Some(1_000_000 - 2 * 86_400));
+        let kept = RetentionSettings { event_days: 0, ..settings };
+        assert_eq!(kept.event_cutoff(1_000_000), None);
+    }
+
     #[test]
     fn test_config_load_with_valid_env_vars() {
         // Create temporary files for macaroons
@@ -260,7 +2092,7 @@ mod tests {
         assert_eq!(config.server_address, "test.server:8080");
         assert_eq!(config.request_timeout_secs, 60);
         assert_eq!(config.rate_limit_per_minute, 200);
-        assert_eq!(config.rfq_poll_interval_secs, 10);
+        assert_eq!(config.rfq.poll_interval_secs, 10);
         assert_eq!(config.tls_verify, false);
         assert_eq!(config.cors_origins, vec!["http://test.com", "https://test.com"]);
 
@@ -316,7 +2148,8 @@ mod tests {
         assert_eq!(config.server_address, "127.0.0.1:8080");
         assert_eq!(config.request_timeout_secs, 30);
         assert_eq!(config.rate_limit_per_minute, 100);
-        assert_eq!(config.rfq_poll_interval_secs, 5);
+        assert_eq!(config.rfq.poll_interval_secs, 5);
+        assert_eq!(config.rfq.ping_interval_secs, 30);
         assert_eq!(config.tls_verify, true);
         assert_eq!(config.cors_origins, vec!["http://localhost:5173", "http://127.0.0.1:5173"]);
 
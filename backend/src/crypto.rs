@@ -1,7 +1,11 @@
 use crate::error::AppError;
 use base64::Engine;
 use bitcoin::hashes::{sha256, Hash};
-use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use secp256k1::{ecdh::SharedSecret, ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey};
+use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
 use std::str::FromStr;
 use tracing::{debug, error, info};
@@ -153,7 +157,97 @@ pub fn derive_public_key_from_receiver_id(receiver_id: &str) -> Result<Option<St
     Ok(None)
 }
 
+const ECIES_INFO: &[u8] = b"taproot-mailbox-ecies-v1";
+const ECIES_PUBKEY_LEN: usize = 33;
+const ECIES_NONCE_LEN: usize = 12;
+
+/// Parses a hex encoded compressed, uncompressed or x-only (even parity) public key
+fn parse_public_key(public_key_hex: &str) -> Result<PublicKey, AppError> {
+    if public_key_hex.len() == 64 {
+        let x_only = secp256k1::XOnlyPublicKey::from_str(public_key_hex)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid public key format: {e}")))?;
+        return Ok(x_only.public_key(secp256k1::Parity::Even));
+    }
+
+    PublicKey::from_str(public_key_hex)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid public key format: {e}")))
+}
+
+/// Derives the AES-256 key for an ECIES exchange from the ECDH secret and ephemeral key
+fn ecies_key(shared_secret: &SharedSecret, ephemeral_public_key: &[u8]) -> [u8; 32] {
+    let hkdf = Hkdf::<Sha256>::new(Some(ephemeral_public_key), &shared_secret.secret_bytes());
+    let mut key = [0u8; 32];
+    hkdf.expand(ECIES_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+/// Encrypts `plaintext` to a secp256k1 public key using ECIES
+/// (ECDH + HKDF-SHA256 + AES-256-GCM).
+///
+/// Output layout: `ephemeral_pubkey (33) || nonce (12) || ciphertext || tag (16)`.
+pub fn ecies_encrypt(public_key_hex: &str, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+    let recipient = parse_public_key(public_key_hex)?;
+
+    let secp = Secp256k1::new();
+    let (ephemeral_secret, ephemeral_public) =
+        secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+    let ephemeral_bytes = ephemeral_public.serialize();
+
+    let key = ecies_key(&SharedSecret::new(&recipient, &ephemeral_secret), &ephemeral_bytes);
+    let nonce_bytes: [u8; ECIES_NONCE_LEN] = secp256k1::rand::random();
+
+    let ciphertext = Aes256Gcm::new(&key.into())
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|_| AppError::RequestError("ECIES encryption failed".to_string()))?;
+
+    let mut output = Vec::with_capacity(ECIES_PUBKEY_LEN + ECIES_NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(&ephemeral_bytes);
+    output.extend_from_slice(&nonce_bytes);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// Decrypts data produced by [`ecies_encrypt`] with the recipient's hex encoded secret key
+pub fn ecies_decrypt(secret_key_hex: &str, data: &[u8]) -> Result<Vec<u8>, AppError> {
+    let secret_key = SecretKey::from_str(secret_key_hex)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid secret key: {e}")))?;
+
+    if data.len() < ECIES_PUBKEY_LEN + ECIES_NONCE_LEN + 16 {
+        return Err(AppError::InvalidInput("ECIES payload too short".to_string()));
+    }
+    let (ephemeral_bytes, rest) = data.split_at(ECIES_PUBKEY_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(ECIES_NONCE_LEN);
+
+    let ephemeral_public = PublicKey::from_slice(ephemeral_bytes)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid ephemeral public key: {e}")))?;
+
+    let key = ecies_key(&SharedSecret::new(&ephemeral_public, &secret_key), ephemeral_bytes);
+
+    Aes256Gcm::new(&key.into())
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| {
+            AppError::InvalidInput(
+                "ECIES decryption failed: wrong key or corrupted payload".to_string(),
+            )
+        })
+}
+
+/// Computes the HMAC-SHA256 of `message` under `key`
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+/// Computes a hex encoded HMAC-SHA256 of `message` under `key`
+pub fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    hex::encode(hmac_sha256(key, message))
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use secp256k1::{Secp256k1, SecretKey};
@@ -470,4 +564,47 @@ mod tests {
             "Should return Ok(false) for invalid signature"
         );
     }
+
+    #[test]
+    fn test_ecies_roundtrip() {
+        let secp = Secp256k1::new();
+        let (secret_key, public_key) = secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+        let plaintext = b"encrypted mailbox payload";
+
+        let encrypted = ecies_encrypt(&public_key.to_string(), plaintext).unwrap();
+        assert_ne!(&encrypted[ECIES_PUBKEY_LEN + ECIES_NONCE_LEN..], plaintext);
+
+        let decrypted = ecies_decrypt(&hex::encode(secret_key.secret_bytes()), &encrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_ecies_x_only_key_and_wrong_key() {
+        let secp = Secp256k1::new();
+        let (secret_key, public_key) = secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+        let (x_only, parity) = public_key.x_only_public_key();
+        // x-only keys are interpreted with even parity, as in BIP340
+        let secret_key = if parity == secp256k1::Parity::Odd {
+            secret_key.negate()
+        } else {
+            secret_key
+        };
+
+        let encrypted = ecies_encrypt(&x_only.to_string(), b"hello").unwrap();
+        let decrypted = ecies_decrypt(&hex::encode(secret_key.secret_bytes()), &encrypted).unwrap();
+        assert_eq!(decrypted, b"hello");
+
+        let (other_key, _) = secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+        assert!(ecies_decrypt(&hex::encode(other_key.secret_bytes()), &encrypted).is_err());
+        assert!(ecies_decrypt(&hex::encode(secret_key.secret_bytes()), &encrypted[..20]).is_err());
+    }
+
+    #[test]
+    fn test_hmac_sha256_hex_known_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
 }
@@ -14,6 +14,36 @@ pub enum AppError {
 
     #[error("Request error: {0}")]
     RequestError(String),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("Upstream unavailable: {0}")]
+    UpstreamUnavailable(String),
+
+    /// A failed tapd or LND call, with the status to answer the client with
+    #[error("Upstream error ({status}): {message}")]
+    Upstream {
+        status: StatusCode,
+        /// gRPC status code from the REST proxy's error body
+        code: Option<u32>,
+        message: String,
+    },
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
 }
 
 impl AppError {
@@ -23,10 +53,76 @@ impl AppError {
             AppError::ValidationError(_) => StatusCode::BAD_REQUEST,
             AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
             AppError::RequestError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::StorageError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::UpstreamUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Upstream { status, .. } => *status,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
         }
     }
 }
 
+impl AppError {
+    /// Reads a failed response from tapd or LND. Their REST proxies answer
+    /// with a gRPC `{"code", "message"}` body, whose code picks the status;
+    /// other bodies keep the upstream's own status.
+    pub fn upstream(status: StatusCode, body: &str) -> Self {
+        let json = serde_json::from_str::<serde_json::Value>(body).unwrap_or_default();
+        // Streaming calls wrap the status in an `error` object
+        let error = if json["error"].is_object() { &json["error"] } else { &json };
+        let code = error["code"].as_u64().map(|code| code as u32);
+        let message = error["message"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| body.trim().to_string());
+        AppError::Upstream {
+            status: code.and_then(grpc_status).unwrap_or(status),
+            code,
+            message,
+        }
+    }
+
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Self::upstream(status, &body)
+    }
+
+    /// Reads a failed call on tapd's gRPC port, with the status grpc-gateway
+    /// would have answered the same failure with
+    pub fn from_grpc_status(status: &tonic::Status) -> Self {
+        let code = status.code() as u32;
+        AppError::Upstream {
+            status: grpc_status(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            code: Some(code),
+            message: status.message().to_string(),
+        }
+    }
+}
+
+/// The HTTP status matching a gRPC code, as grpc-gateway maps them; `None`
+/// for OK and codes it does not know
+fn grpc_status(code: u32) -> Option<StatusCode> {
+    Some(match code {
+        1 => StatusCode::REQUEST_TIMEOUT,
+        2 | 13 | 15 => StatusCode::INTERNAL_SERVER_ERROR,
+        3 | 11 => StatusCode::BAD_REQUEST,
+        4 => StatusCode::GATEWAY_TIMEOUT,
+        5 => StatusCode::NOT_FOUND,
+        6 | 10 => StatusCode::CONFLICT,
+        7 => StatusCode::FORBIDDEN,
+        8 => StatusCode::TOO_MANY_REQUESTS,
+        9 => StatusCode::PRECONDITION_FAILED,
+        12 => StatusCode::NOT_IMPLEMENTED,
+        14 => StatusCode::SERVICE_UNAVAILABLE,
+        16 => StatusCode::UNAUTHORIZED,
+        _ => return None,
+    })
+}
+
 impl From<std::env::VarError> for AppError {
     fn from(err: std::env::VarError) -> Self {
         AppError::EnvVarError(err.to_string())
@@ -45,7 +141,20 @@ impl From<serde_json::Error> for AppError {
     }
 }
 
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::StorageError(err.to_string())
+    }
+}
+
+impl From<redis::RedisError> for AppError {
+    fn from(err: redis::RedisError) -> Self {
+        AppError::StorageError(err.to_string())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
     use std::env;
@@ -81,6 +190,61 @@ mod tests {
         assert!(app_error.to_string().contains("Environment variable error"));
     }
 
+    #[test]
+    fn test_storage_error_status_code() {
+        let error = AppError::StorageError("connection refused".to_string());
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error.to_string(), "Storage error: connection refused");
+    }
+
+    #[test]
+    fn test_client_facing_error_status_codes() {
+        assert_eq!(AppError::NotFound("x".to_string()).status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(AppError::Conflict("x".to_string()).status_code(), StatusCode::CONFLICT);
+        assert_eq!(
+            AppError::ServiceUnavailable("x".to_string()).status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            AppError::UpstreamUnavailable("x".to_string()).status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            AppError::Unauthorized("x".to_string()).status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            AppError::NotImplemented("x".to_string()).status_code(),
+            StatusCode::NOT_IMPLEMENTED
+        );
+    }
+
+    #[test]
+    fn test_upstream_errors_map_grpc_codes() {
+        let not_found = AppError::upstream(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"code": 5, "message": "asset not found", "details": []}"#,
+        );
+        assert_eq!(not_found.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(not_found.to_string(), "Upstream error (404 Not Found): asset not found");
+
+        let precondition = AppError::upstream(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"error": {"code": 9, "message": "insufficient balance"}}"#,
+        );
+        assert!(matches!(
+            precondition,
+            AppError::Upstream { status: StatusCode::PRECONDITION_FAILED, code: Some(9), .. }
+        ));
+
+        let plain = AppError::upstream(StatusCode::BAD_GATEWAY, "proxy down\n");
+        assert!(matches!(
+            plain,
+            AppError::Upstream { status: StatusCode::BAD_GATEWAY, code: None, ref message }
+                if message == "proxy down"
+        ));
+    }
+
     #[test]
     fn test_error_debug_formatting() {
         let error = AppError::ValidationError("Test error".to_string());
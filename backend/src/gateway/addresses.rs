@@ -1,5 +1,11 @@
-use axum::{response::Json, http::StatusCode, extract::State};
+use axum::{response::Json, http::StatusCode, extract::{Path, State}};
+use base64::Engine;
+use bitcoin::bech32::{self, primitives::decode::CheckedHrpstring};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::{error, info, warn};
+use super::upstream::{self, SendUpstream};
+use crate::error::AppError;
 use crate::types::AppState;
 
 pub async fn new_address(
@@ -8,7 +14,7 @@ pub async fn new_address(
 ) -> Result<Json<Value>, StatusCode> {
     match state.tapd_client.new_address(payload).await {
         Ok(address) => Ok(Json(address)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(upstream::error_status(&e)),
     }
 }
 
@@ -17,6 +23,344 @@ pub async fn list_addresses(
 ) -> Result<Json<Value>, StatusCode> {
     match state.tapd_client.list_addresses().await {
         Ok(addresses) => Ok(Json(addresses)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(upstream::error_status(&e)),
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeAddressRequest {
+    pub addr: String,
+}
+
+/// What a Taproot Assets address pays, for previewing a send
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedAddress {
+    pub encoded: String,
+    /// From the address prefix: mainnet, testnet, regtest or simnet
+    pub network: &'static str,
+    /// Hex
+    pub asset_id: String,
+    pub asset_type: Option<String>,
+    pub amount: u64,
+    /// Hex
+    pub group_key: Option<String>,
+    /// Hex
+    pub script_key: String,
+    /// Hex
+    pub internal_key: String,
+    pub proof_courier_addr: Option<String>,
+    pub asset_version: Option<String>,
+    pub address_version: Option<String>,
+}
+
+/// Checks the bech32m encoding of a Taproot Assets address and returns the
+/// network its prefix belongs to
+pub fn check_address_encoding(addr: &str) -> Result<&'static str, AppError> {
+    let checked = CheckedHrpstring::new::<bech32::Bech32m>(addr.trim()).map_err(|e| {
+        AppError::InvalidInput(format!("Not a bech32m Taproot Assets address: {e}"))
+    })?;
+    match checked.hrp().as_str() {
+        "tapbc" => Ok("mainnet"),
+        "taptb" => Ok("testnet"),
+        "taprt" => Ok("regtest"),
+        "tapsb" => Ok("simnet"),
+        other => Err(AppError::InvalidInput(format!(
+            "Unknown Taproot Assets address prefix: {other}"
+        ))),
+    }
+}
+
+/// Reads tapd's `Addr`, whose byte fields are base64
+pub fn parse_decoded_address(network: &'static str, addr: &Value) -> DecodedAddress {
+    let hex_field = |field: &str| {
+        addr[field]
+            .as_str()
+            .and_then(|value| base64::engine::general_purpose::STANDARD.decode(value).ok())
+            .filter(|bytes| !bytes.is_empty())
+            .map(hex::encode)
+    };
+    let text_field = |field: &str| {
+        addr[field]
+            .as_str()
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    DecodedAddress {
+        encoded: text_field("encoded").unwrap_or_default(),
+        network,
+        asset_id: hex_field("asset_id").unwrap_or_default(),
+        asset_type: text_field("asset_type"),
+        amount: match &addr["amount"] {
+            Value::String(amount) => amount.parse().unwrap_or(0),
+            amount => amount.as_u64().unwrap_or(0),
+        },
+        group_key: hex_field("group_key"),
+        script_key: hex_field("script_key").unwrap_or_default(),
+        internal_key: hex_field("internal_key").unwrap_or_default(),
+        proof_courier_addr: text_field("proof_courier_addr"),
+        asset_version: text_field("asset_version"),
+        address_version: text_field("address_version"),
+    }
+}
+
+pub async fn decode_address(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    addr: &str,
+) -> Result<Value, AppError> {
+    let url = format!("{base_url}/v1/taproot-assets/addrs/decode");
+    let response = client
+        .post(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .json(&serde_json::json!({ "addr": addr }))
+        .send_upstream()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+/// Decodes an address through tapd after a local encoding check, so malformed
+/// input is rejected without a round trip
+pub async fn decode_address_handler(
+    State(state): State<AppState>,
+    Json(request): Json<DecodeAddressRequest>,
+) -> Result<Json<DecodedAddress>, StatusCode> {
+    let addr = request.addr.trim();
+    let network = match check_address_encoding(addr) {
+        Ok(network) => network,
+        Err(e) => {
+            warn!("Rejected address decode: {}", e);
+            return Err(e.status_code());
+        }
+    };
+    info!("Decoding {} Taproot Assets address", network);
+
+    let decoded =
+        decode_address(&state.http_client, &state.base_url.0, &state.macaroon_hex.0, addr).await;
+    match decoded {
+        Ok(decoded) => Ok(Json(parse_decoded_address(network, &decoded))),
+        Err(e) => {
+            error!("Decode address failed: {}", e);
+            // tapd refuses addresses for another network or with bad contents
+            Err(match e {
+                AppError::RequestError(_) => StatusCode::BAD_REQUEST,
+                other => other.status_code(),
+            })
+        }
+    }
+}
+
+/// How far an inbound transfer to an address has progressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveStatus {
+    /// The anchor transaction is in the mempool
+    Detected,
+    Confirmed,
+    ProofReceived,
+    /// The proof is imported and the asset is spendable
+    Completed,
+}
+
+impl ReceiveStatus {
+    /// Maps tapd's `AddrEventStatus`
+    pub fn from_upstream(status: &str) -> Option<Self> {
+        match status {
+            "ADDR_EVENT_STATUS_TRANSACTION_DETECTED" => Some(ReceiveStatus::Detected),
+            "ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED" => Some(ReceiveStatus::Confirmed),
+            "ADDR_EVENT_STATUS_PROOF_RECEIVED" => Some(ReceiveStatus::ProofReceived),
+            "ADDR_EVENT_STATUS_COMPLETED" => Some(ReceiveStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AddressEvent {
+    pub status: ReceiveStatus,
+    /// Anchor output, `txid:vout`
+    pub outpoint: String,
+    pub utxo_amt_sat: u64,
+    pub confirmation_height: Option<u32>,
+    pub has_proof: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddressEventsResponse {
+    pub addr: String,
+    /// Furthest status any inbound transfer has reached; `None` until one is seen
+    pub status: Option<ReceiveStatus>,
+    /// Newest first
+    pub events: Vec<AddressEvent>,
+}
+
+/// Reads tapd's `AddrReceivesResponse`, skipping events with an unknown status
+pub fn parse_address_events(addr: &str, response: &Value) -> AddressEventsResponse {
+    let number = |value: &Value| match value {
+        Value::String(text) => text.parse::<i64>().ok(),
+        other => other.as_i64(),
+    };
+    let mut events: Vec<AddressEvent> = response["events"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|event| {
+            Some(AddressEvent {
+                status: ReceiveStatus::from_upstream(event["status"].as_str()?)?,
+                outpoint: event["outpoint"].as_str().unwrap_or_default().to_string(),
+                utxo_amt_sat: number(&event["utxo_amt_sat"]).unwrap_or(0).max(0) as u64,
+                confirmation_height: number(&event["confirmation_height"])
+                    .filter(|height| *height > 0)
+                    .map(|height| height as u32),
+                has_proof: event["has_proof"].as_bool().unwrap_or(false),
+                created_at: number(&event["creation_time_unix_seconds"]).unwrap_or(0),
+            })
+        })
+        .collect();
+    events.sort_by_key(|event| std::cmp::Reverse(event.created_at));
+
+    AddressEventsResponse {
+        addr: addr.to_string(),
+        status: events.iter().map(|event| event.status).max(),
+        events,
+    }
+}
+
+/// Receive events for one address, or for every address when `addr` is `None`
+pub async fn address_receives(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    addr: Option<&str>,
+) -> Result<Value, AppError> {
+    let url = format!("{base_url}/v1/taproot-assets/addrs/receives");
+    let response = client
+        .post(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .json(&serde_json::json!({ "filter_addr": addr.unwrap_or_default() }))
+        .send_upstream()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+/// Reports whether inbound transfers to one address were detected, confirmed
+/// or completed
+pub async fn address_events_handler(
+    State(state): State<AppState>,
+    Path(addr): Path<String>,
+) -> Result<Json<AddressEventsResponse>, StatusCode> {
+    let addr = addr.trim();
+    if let Err(e) = check_address_encoding(addr) {
+        warn!("Rejected address events query: {}", e);
+        return Err(e.status_code());
+    }
+
+    let receives = address_receives(
+        &state.http_client,
+        &state.base_url.0,
+        &state.macaroon_hex.0,
+        Some(addr),
+    )
+    .await;
+    match receives {
+        Ok(receives) => Ok(Json(parse_address_events(addr, &receives))),
+        Err(e) => {
+            error!("Get address events failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_address_encoding() {
+        let encode = |hrp: &str| {
+            let hrp = bech32::Hrp::parse(hrp).unwrap();
+            bech32::encode::<bech32::Bech32m>(hrp, &[7u8; 120]).unwrap()
+        };
+        assert_eq!(check_address_encoding(&encode("tapbc")).unwrap(), "mainnet");
+        assert_eq!(check_address_encoding(&encode("taprt")).unwrap(), "regtest");
+        assert!(check_address_encoding(&encode("bc")).is_err());
+
+        let hrp = bech32::Hrp::parse("taptb").unwrap();
+        let bech32 = bech32::encode::<bech32::Bech32>(hrp, &[7u8; 60]).unwrap();
+        assert!(check_address_encoding(&bech32).is_err());
+        let mut corrupted = encode("taptb");
+        corrupted.pop();
+        corrupted.push('q');
+        assert!(check_address_encoding(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_parse_address_events() {
+        let response = serde_json::json!({"events": [
+            {
+                "creation_time_unix_seconds": "100",
+                "status": "ADDR_EVENT_STATUS_TRANSACTION_DETECTED",
+                "outpoint": "aa:0",
+                "utxo_amt_sat": "1000",
+                "confirmation_height": 0,
+                "has_proof": false
+            },
+            {
+                "creation_time_unix_seconds": "200",
+                "status": "ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED",
+                "outpoint": "bb:1",
+                "utxo_amt_sat": "1000",
+                "confirmation_height": 812_000,
+                "has_proof": true
+            },
+            {"creation_time_unix_seconds": "300", "status": "ADDR_EVENT_STATUS_UNKNOWN"}
+        ]});
+        let parsed = parse_address_events("taprt1abc", &response);
+        assert_eq!(parsed.status, Some(ReceiveStatus::Confirmed));
+        assert_eq!(parsed.events.len(), 2);
+        assert_eq!(parsed.events[0].outpoint, "bb:1");
+        assert_eq!(parsed.events[0].confirmation_height, Some(812_000));
+        assert_eq!(parsed.events[1].confirmation_height, None);
+
+        let empty = parse_address_events("taprt1abc", &serde_json::json!({}));
+        assert_eq!(empty.status, None);
+        assert!(empty.events.is_empty());
+    }
+
+    #[test]
+    fn test_parse_decoded_address() {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let addr = serde_json::json!({
+            "encoded": "taprt1abc",
+            "asset_id": base64.encode([0xab; 32]),
+            "asset_type": "NORMAL",
+            "amount": "250",
+            "group_key": "",
+            "script_key": base64.encode([0x02; 33]),
+            "internal_key": base64.encode([0x03; 33]),
+            "proof_courier_addr": "universerpc://universe.example.com:10029",
+            "address_version": "ADDR_VERSION_V1"
+        });
+        let decoded = parse_decoded_address("regtest", &addr);
+        assert_eq!(decoded.asset_id, "ab".repeat(32));
+        assert_eq!(decoded.amount, 250);
+        assert_eq!(decoded.group_key, None);
+        assert_eq!(decoded.script_key, "02".repeat(33));
+        assert_eq!(
+            decoded.proof_courier_addr.as_deref(),
+            Some("universerpc://universe.example.com:10029")
+        );
+        assert_eq!(decoded.asset_version, None);
+    }
+}
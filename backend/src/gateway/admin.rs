@@ -0,0 +1,114 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::error::AppError;
+use crate::types::AppState;
+use super::backup::{export_handler, import_handler, MAX_IMPORT_BYTES};
+use super::mailbox_registry::ConnectionSummary;
+
+pub async fn list_connections_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ConnectionSummary>>, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+    Ok(Json(state.mailbox_registry.snapshot()))
+}
+
+pub async fn close_connection_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+
+    if state.mailbox_registry.close(&connection_id) {
+        info!("Operator requested close of mailbox connection {}", connection_id);
+        Ok(StatusCode::ACCEPTED)
+    } else {
+        Err(error_response(AppError::NotFound(format!(
+            "Connection not found: {connection_id}"
+        ))))
+    }
+}
+
+/// Checks the request's bearer token against the configured admin token
+pub(crate) fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), AppError> {
+    if state.mailbox_limiter.settings().admin_token.is_none() {
+        return Err(AppError::ServiceUnavailable(
+            "Admin API is disabled".to_string(),
+        ));
+    }
+
+    if is_admin(state, headers) {
+        Ok(())
+    } else {
+        warn!("Rejected admin request with invalid token");
+        Err(AppError::Unauthorized("Invalid admin token".to_string()))
+    }
+}
+
+/// Whether the request carries the configured admin token
+pub(crate) fn is_admin(state: &AppState, headers: &HeaderMap) -> bool {
+    let Some(expected) = state.mailbox_limiter.settings().admin_token.as_deref() else {
+        return false;
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    constant_time_eq(provided.as_bytes(), expected.as_bytes())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub(crate) fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    (
+        status,
+        Json(serde_json::json!({
+            "error": error.to_string(),
+            "type": format!("{:?}", error)
+        })),
+    )
+}
+
+pub fn create_admin_router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/mailbox/connections", get(list_connections_handler))
+        .route(
+            "/admin/mailbox/connections/:id/close",
+            post(close_connection_handler),
+        )
+        .route("/admin/reconcile", post(super::reconcile::reconcile_handler))
+        .route("/admin/audit", get(super::audit::list_audit_handler))
+        .route("/admin/export", post(export_handler))
+        .route(
+            "/admin/import",
+            post(import_handler).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret-longer"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
@@ -1,22 +1,747 @@
-use axum::{response::Json, http::StatusCode, extract::State};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use base64::Engine;
+use serde::Serialize;
 use serde_json::Value;
-use crate::types::AppState;
+use tracing::{error, info, warn};
+use super::upstream::{self, SendUpstream};
+use crate::api::idempotency::{run_once, IdempotencyKey};
+use crate::api::preferences::load_preferences;
+use crate::error::AppError;
+use crate::storage::images::{image_content_type, image_hash, StoredImage};
+use crate::types::{parse_asset_groups, AppState, AssetGroup, AssetListParams, AssetMetaData};
+
+/// Transfers shown on the asset detail view
+const DETAIL_TRANSFER_LIMIT: usize = 20;
 
 pub async fn list_assets(
-    State(state): State<AppState>
+    State(state): State<AppState>,
+    Query(mut params): Query<AssetListParams>,
 ) -> Result<Json<Value>, StatusCode> {
-    match state.tapd_client.list_assets().await {
+    load_preferences(&state, &mut params)
+        .await
+        .map_err(|e| e.status_code())?;
+    match state.tapd_client.list_assets(&params).await {
         Ok(assets) => Ok(Json(serde_json::to_value(assets).unwrap_or_default())),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(upstream::error_status(&e)),
     }
 }
 
+/// Mints through tapd's `MintAsset`. A top-level `image_hash` naming an
+/// uploaded image becomes the asset's opaque meta blob.
 pub async fn mint_asset(
     State(state): State<AppState>,
+    key: IdempotencyKey,
     Json(payload): Json<Value>
-) -> Result<Json<Value>, StatusCode> {
+) -> Response {
+    run_once(&state, "mint", key, &payload, || mint_asset_once(&state, payload.clone())).await
+}
+
+async fn mint_asset_once(state: &AppState, mut payload: Value) -> Result<Json<Value>, StatusCode> {
+    if let Some(hash) = payload.as_object_mut().and_then(|p| p.remove("image_hash")) {
+        let hash = hash.as_str().unwrap_or_default().to_ascii_lowercase();
+        let image = match state.image_store.get(&hash).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                warn!("Mint references unknown image {}", hash);
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(e) => {
+                error!("Loading image {} failed: {}", hash, e);
+                return Err(e.status_code());
+            }
+        };
+        if let Err(e) = inject_image_meta(&mut payload, &image) {
+            warn!("Rejected mint with image {}: {}", hash, e);
+            return Err(e.status_code());
+        }
+    }
+
     match state.tapd_client.mint_asset_raw(payload).await {
         Ok(result) => Ok(Json(result)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(upstream::error_status(&e)),
+    }
+}
+
+/// Sets `asset.asset_meta` of a `MintAssetRequest` to the image as an
+/// opaque blob, so tapd's meta hash is the image's hash
+pub fn inject_image_meta(payload: &mut Value, image: &StoredImage) -> Result<(), AppError> {
+    let asset = payload
+        .get_mut("asset")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| AppError::InvalidInput("Mint request has no asset".to_string()))?;
+    if asset.get("asset_meta").is_some_and(|meta| !meta.is_null()) {
+        return Err(AppError::InvalidInput(
+            "Mint request sets both image_hash and asset_meta".to_string(),
+        ));
+    }
+    asset.insert(
+        "asset_meta".to_string(),
+        serde_json::json!({
+            "data": base64::engine::general_purpose::STANDARD.encode(&image.data),
+            "type": "META_TYPE_OPAQUE",
+        }),
+    );
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageUploadResponse {
+    /// Hex; pass as `image_hash` when minting
+    pub meta_hash: String,
+    pub content_type: &'static str,
+    pub size_bytes: usize,
+}
+
+/// Stores a PNG, JPEG, GIF or WebP sent as the raw request body
+pub async fn upload_image_handler(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ImageUploadResponse>), StatusCode> {
+    if body.len() > state.image_max_bytes {
+        warn!("Rejected {} byte image upload", body.len());
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    let Some(image) = StoredImage::new(body.to_vec()) else {
+        return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    };
+
+    match state.image_store.put(&image).await {
+        Ok(meta_hash) => {
+            info!("Stored {} image {}", image.content_type, meta_hash);
+            Ok((
+                StatusCode::CREATED,
+                Json(ImageUploadResponse {
+                    meta_hash,
+                    content_type: image.content_type,
+                    size_bytes: image.data.len(),
+                }),
+            ))
+        }
+        Err(e) => {
+            error!("Image upload failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
+/// An asset's meta blob as tapd stores it, decoded for display
+#[derive(Debug, Serialize)]
+pub struct AssetMetaResponse {
+    pub asset_id: String,
+    /// `json` or `opaque`
+    pub meta_type: String,
+    /// Hex
+    pub meta_hash: Option<String>,
+    pub meta_data: AssetMetaData,
+    /// The parsed blob when it holds JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    pub size_bytes: usize,
+}
+
+/// First string among `keys` in a JSON meta object
+fn meta_field(json: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| json.get(*key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Decodes tapd's `AssetMeta` (`data`, `type`, `meta_hash`, base64 bytes).
+/// JSON blobs are detected by type or content; opaque blobs are shown as
+/// text when they are UTF-8, or as a data URI when they are an image.
+pub fn decode_asset_meta(asset_id: &str, meta: &Value) -> Result<AssetMetaResponse, AppError> {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let data = base64
+        .decode(meta["data"].as_str().unwrap_or_default())
+        .map_err(|e| AppError::RequestError(format!("Invalid asset meta data: {e}")))?;
+    let meta_hash = meta["meta_hash"]
+        .as_str()
+        .and_then(|hash| base64.decode(hash).ok())
+        .map(hex::encode);
+
+    let declared_json = meta["type"].as_str() == Some("META_TYPE_JSON");
+    let json = serde_json::from_slice::<Value>(&data)
+        .ok()
+        .filter(|json| declared_json || json.is_object());
+    let meta_data = match &json {
+        Some(json) => AssetMetaData {
+            description: meta_field(json, &["description", "desc", "name"]),
+            image_url: meta_field(json, &["image_url", "imageUrl", "image", "icon"]),
+            issuer: meta_field(json, &["issuer", "issuer_name", "creator"]),
+        },
+        None => AssetMetaData {
+            description: image_content_type(&data)
+                .is_none()
+                .then(|| String::from_utf8(data.clone()).ok())
+                .flatten()
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty()),
+            image_url: image_content_type(&data)
+                .map(|mime| format!("data:{mime};base64,{}", base64.encode(&data))),
+            issuer: None,
+        },
+    };
+
+    Ok(AssetMetaResponse {
+        asset_id: asset_id.to_string(),
+        meta_type: if json.is_some() { "json" } else { "opaque" }.to_string(),
+        meta_hash,
+        meta_data,
+        json,
+        size_bytes: data.len(),
+    })
+}
+
+pub async fn fetch_asset_meta(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    asset_id: &str,
+) -> Result<Value, AppError> {
+    info!("Fetching meta for asset ID: {}", asset_id);
+    let url = format!("{base_url}/v1/taproot-assets/assets/meta/asset-id/{asset_id}");
+    let response = client
+        .get(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .send_upstream()
+        .await?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(AppError::NotFound(format!("No meta for asset {asset_id}")));
+    }
+    if !status.is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+pub async fn asset_meta_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetMetaResponse>, StatusCode> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let meta = fetch_asset_meta(
+        &state.http_client,
+        &state.base_url.0,
+        &state.macaroon_hex.0,
+        &asset_id,
+    )
+    .await;
+    match meta.and_then(|meta| decode_asset_meta(&asset_id, &meta)) {
+        Ok(result) => Ok(Json(result)),
+        Err(e) => {
+            error!("Get asset meta failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
+/// Everything the asset detail screen shows, gathered from several tapd calls
+#[derive(Debug, Serialize)]
+pub struct AssetDetailResponse {
+    pub asset_id: String,
+    pub name: Option<String>,
+    /// tapd's `AssetType`, e.g. `NORMAL`
+    pub asset_type: Option<String>,
+    pub balance: u64,
+    /// Unspent leaves as tapd lists them
+    pub leaves: Vec<Value>,
+    pub group: Option<AssetGroup>,
+    /// Addresses created for this asset
+    pub addresses: Vec<Value>,
+    /// Most recent transfers spending or creating this asset, newest first
+    pub transfers: Vec<Value>,
+}
+
+fn base64_hex(value: &Value) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(value.as_str()?).ok()?;
+    Some(hex::encode(bytes))
+}
+
+fn string_u64(value: &Value) -> u64 {
+    match value {
+        Value::String(text) => text.parse().unwrap_or(0),
+        other => other.as_u64().unwrap_or(0),
+    }
+}
+
+/// Merges tapd's `ListAssets`, `ListBalances` (by asset ID), `ListGroups`,
+/// `QueryAddrs` and `ListTransfers` responses for one asset; `None` when
+/// the node holds no leaves or balance for it. The last three are optional
+/// so one failing call does not hide the rest of the view.
+pub fn assemble_asset_detail(
+    asset_id: &str,
+    assets: &Value,
+    balances: &Value,
+    groups: Option<&Value>,
+    addresses: Option<&Value>,
+    transfers: Option<&Value>,
+) -> Option<AssetDetailResponse> {
+    let leaves: Vec<Value> = assets["assets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|leaf| {
+            base64_hex(&leaf["asset_genesis"]["asset_id"]).as_deref() == Some(asset_id)
+        })
+        .cloned()
+        .collect();
+    let balance_entry = &balances["asset_balances"][asset_id];
+    if leaves.is_empty() && balance_entry.is_null() {
+        return None;
     }
-}
\ No newline at end of file
+
+    let genesis = leaves
+        .first()
+        .map(|leaf| &leaf["asset_genesis"])
+        .unwrap_or(&balance_entry["asset_genesis"]);
+    let group_key = leaves
+        .iter()
+        .find_map(|leaf| base64_hex(&leaf["asset_group"]["tweaked_group_key"]));
+    let group = group_key.and_then(|key| {
+        parse_asset_groups(groups?)
+            .into_iter()
+            .find(|group| group.group_key == key)
+    });
+
+    let addresses = addresses
+        .and_then(|addresses| addresses["addrs"].as_array())
+        .into_iter()
+        .flatten()
+        .filter(|addr| base64_hex(&addr["asset_id"]).as_deref() == Some(asset_id))
+        .cloned()
+        .collect();
+
+    let touches_asset = |transfer: &Value, side: &str| {
+        transfer[side].as_array().into_iter().flatten().any(|entry| {
+            base64_hex(&entry["asset_id"]).as_deref() == Some(asset_id)
+        })
+    };
+    let mut transfers: Vec<Value> = transfers
+        .and_then(|transfers| transfers["transfers"].as_array())
+        .into_iter()
+        .flatten()
+        .filter(|transfer| touches_asset(transfer, "inputs") || touches_asset(transfer, "outputs"))
+        .cloned()
+        .collect();
+    transfers.sort_by_key(|transfer| {
+        std::cmp::Reverse(string_u64(&transfer["transfer_timestamp"]))
+    });
+    transfers.truncate(DETAIL_TRANSFER_LIMIT);
+
+    Some(AssetDetailResponse {
+        asset_id: asset_id.to_string(),
+        name: genesis["name"].as_str().map(str::to_string),
+        asset_type: genesis["asset_type"].as_str().map(str::to_string),
+        balance: string_u64(&balance_entry["balance"]),
+        leaves,
+        group,
+        addresses,
+        transfers,
+    })
+}
+
+async fn tapd_get(state: &AppState, path: &str) -> Result<Value, AppError> {
+    let url = format!("{}/v1/taproot-assets/{path}", state.base_url.0);
+    let response = state
+        .http_client
+        .get(&url)
+        .header("Grpc-Metadata-macaroon", &state.macaroon_hex.0)
+        .send_upstream()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+fn optional_section(name: &str, result: &Result<Value, AppError>) -> Option<Value> {
+    match result {
+        Ok(value) => Some(value.clone()),
+        Err(e) => {
+            warn!("Asset response is missing {}: {}", name, e);
+            None
+        }
+    }
+}
+
+pub async fn asset_detail_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetDetailResponse>, StatusCode> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    info!("Getting asset detail for {}", asset_id);
+
+    let (assets, balances, groups, addresses, transfers) = futures::join!(
+        tapd_get(&state, "assets"),
+        tapd_get(&state, "assets/balance?asset_id=true"),
+        tapd_get(&state, "assets/groups"),
+        tapd_get(&state, "addrs"),
+        tapd_get(&state, "assets/transfers"),
+    );
+    let (assets, balances) = match (assets, balances) {
+        (Ok(assets), Ok(balances)) => (assets, balances),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Get asset detail failed: {}", e);
+            return Err(e.status_code());
+        }
+    };
+
+    assemble_asset_detail(
+        &asset_id,
+        &assets,
+        &balances,
+        optional_section("groups", &groups).as_ref(),
+        optional_section("addresses", &addresses).as_ref(),
+        optional_section("transfers", &transfers).as_ref(),
+    )
+    .map(Json)
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Issuance and distribution of one asset: universe supply and sync figures
+/// next to what this node still holds
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AssetStatsResponse {
+    pub asset_id: String,
+    pub name: Option<String>,
+    /// From the universe; `None` if it has not seen the asset
+    pub total_supply: Option<u64>,
+    pub decimal_display: Option<u32>,
+    pub genesis_height: Option<u32>,
+    /// Times the asset's universe was synced from this node's universe server
+    pub total_syncs: u64,
+    /// Issuance and transfer proofs the universe stores for the asset
+    pub total_proofs: u64,
+    pub local_balance: u64,
+    /// Unspent leaves the local balance is split across
+    pub local_leaves: usize,
+    /// Supply held outside this node
+    pub distributed: Option<u64>,
+}
+
+/// Merges tapd's `QueryAssetStats` (filtered to the asset), `ListBalances`
+/// (by asset ID) and `ListAssets`; `None` when neither the universe nor the
+/// wallet knows the asset
+pub fn assemble_asset_stats(
+    asset_id: &str,
+    stats: Option<&Value>,
+    balances: &Value,
+    assets: &Value,
+) -> Option<AssetStatsResponse> {
+    let snapshot = stats
+        .and_then(|stats| stats["asset_stats"].as_array())
+        .into_iter()
+        .flatten()
+        .map(|snapshot| {
+            // Grouped assets report their figures on the group anchor
+            match &snapshot["asset"] {
+                asset if asset["asset_id"].is_string() => (snapshot, asset),
+                _ => (snapshot, &snapshot["group_anchor"]),
+            }
+        })
+        .find(|(_, asset)| base64_hex(&asset["asset_id"]).as_deref() == Some(asset_id));
+    let balance_entry = &balances["asset_balances"][asset_id];
+    let local_leaves = assets["assets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|leaf| {
+            base64_hex(&leaf["asset_genesis"]["asset_id"]).as_deref() == Some(asset_id)
+        })
+        .count();
+    if snapshot.is_none() && balance_entry.is_null() && local_leaves == 0 {
+        return None;
+    }
+
+    let local_balance = string_u64(&balance_entry["balance"]);
+    let total_supply = snapshot
+        .map(|(_, asset)| string_u64(&asset["total_supply"]))
+        .filter(|supply| *supply > 0);
+    let name = snapshot
+        .and_then(|(_, asset)| asset["asset_name"].as_str())
+        .or_else(|| balance_entry["asset_genesis"]["name"].as_str())
+        .map(str::to_string);
+    Some(AssetStatsResponse {
+        asset_id: asset_id.to_string(),
+        name,
+        total_supply,
+        decimal_display: snapshot.map(|(_, asset)| string_u64(&asset["decimal_display"]) as u32),
+        genesis_height: snapshot
+            .map(|(_, asset)| string_u64(&asset["genesis_height"]) as u32)
+            .filter(|height| *height > 0),
+        total_syncs: snapshot.map_or(0, |(snapshot, _)| string_u64(&snapshot["total_syncs"])),
+        total_proofs: snapshot.map_or(0, |(snapshot, _)| string_u64(&snapshot["total_proofs"])),
+        local_balance,
+        local_leaves,
+        distributed: total_supply.map(|supply| supply.saturating_sub(local_balance)),
+    })
+}
+
+pub async fn asset_stats_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetStatsResponse>, StatusCode> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    info!("Getting asset stats for {}", asset_id);
+
+    let stats_path = format!("universe/stats/assets?asset_id_filter={asset_id}");
+    let (stats, balances, assets) = futures::join!(
+        tapd_get(&state, &stats_path),
+        tapd_get(&state, "assets/balance?asset_id=true"),
+        tapd_get(&state, "assets"),
+    );
+    let (balances, assets) = match (balances, assets) {
+        (Ok(balances), Ok(assets)) => (balances, assets),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Get asset stats failed: {}", e);
+            return Err(e.status_code());
+        }
+    };
+
+    assemble_asset_stats(
+        &asset_id,
+        optional_section("universe stats", &stats).as_ref(),
+        &balances,
+        &assets,
+    )
+    .map(Json)
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Serves a collectible's image: the uploaded original when its meta hash is
+/// in the image store, otherwise the meta blob itself if it is an image
+pub async fn asset_image_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let meta = fetch_asset_meta(
+        &state.http_client,
+        &state.base_url.0,
+        &state.macaroon_hex.0,
+        &asset_id,
+    )
+    .await
+    .map_err(|e| {
+        error!("Get asset image failed: {}", e);
+        e.status_code()
+    })?;
+
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let meta_hash = meta["meta_hash"]
+        .as_str()
+        .and_then(|hash| base64.decode(hash).ok())
+        .map(hex::encode);
+    let stored = match &meta_hash {
+        Some(hash) => state.image_store.get(hash).await.unwrap_or_else(|e| {
+            warn!("Image store lookup for {} failed: {}", asset_id, e);
+            None
+        }),
+        None => None,
+    };
+    let image = stored.or_else(|| {
+        let data = base64.decode(meta["data"].as_str()?).ok()?;
+        StoredImage::new(data)
+    });
+
+    match image {
+        Some(image) => Ok((
+            [
+                (header::CONTENT_TYPE, image.content_type.to_string()),
+                (header::ETAG, format!("\"{}\"", image_hash(&image.data))),
+                (header::CACHE_CONTROL, "public, max-age=31536000, immutable".to_string()),
+            ],
+            image.data,
+        )
+            .into_response()),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET_ID: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    fn meta(data: &[u8], meta_type: &str) -> Value {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        serde_json::json!({
+            "data": base64.encode(data),
+            "type": meta_type,
+            "meta_hash": base64.encode([0xab; 32]),
+        })
+    }
+
+    #[test]
+    fn test_decode_json_meta() {
+        let blob = serde_json::json!({
+            "description": "Stable dollar",
+            "image": "https://x/usd.png",
+            "issuer": "ACME"
+        });
+        let blob = serde_json::to_vec(&blob).unwrap();
+        let decoded = decode_asset_meta(ASSET_ID, &meta(&blob, "META_TYPE_OPAQUE")).unwrap();
+        assert_eq!(decoded.meta_type, "json");
+        assert_eq!(decoded.meta_hash, Some("ab".repeat(32)));
+        assert_eq!(decoded.meta_data.description.as_deref(), Some("Stable dollar"));
+        assert_eq!(decoded.meta_data.image_url.as_deref(), Some("https://x/usd.png"));
+        assert_eq!(decoded.meta_data.issuer.as_deref(), Some("ACME"));
+        assert_eq!(decoded.json.unwrap()["issuer"], "ACME");
+    }
+
+    #[test]
+    fn test_decode_opaque_meta() {
+        let text = decode_asset_meta(ASSET_ID, &meta(b" a plain note \n", "META_TYPE_OPAQUE"))
+            .unwrap();
+        assert_eq!(text.meta_type, "opaque");
+        assert_eq!(text.meta_data.description.as_deref(), Some("a plain note"));
+        assert!(text.json.is_none());
+
+        let png = b"\x89PNG\r\n\x1a\nrest";
+        let image = decode_asset_meta(ASSET_ID, &meta(png, "META_TYPE_OPAQUE")).unwrap();
+        assert_eq!(image.meta_data.description, None);
+        assert!(image.meta_data.image_url.unwrap().starts_with("data:image/png;base64,"));
+
+        let invalid = serde_json::json!({"data": "not base64!"});
+        assert!(decode_asset_meta(ASSET_ID, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_assemble_asset_detail() {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let id = base64.encode(hex::decode(ASSET_ID).unwrap());
+        let other = base64.encode([0x11; 32]);
+        let group_key = hex::encode([0x02; 33]);
+        let assets = serde_json::json!({"assets": [
+            {
+                "asset_genesis": {"asset_id": id, "name": "USD", "asset_type": "NORMAL"},
+                "asset_group": {"tweaked_group_key": base64.encode([0x02; 33])},
+                "amount": "600"
+            },
+            {"asset_genesis": {"asset_id": other, "name": "EUR"}, "amount": "5"},
+            {"asset_genesis": {"asset_id": id, "name": "USD"}, "amount": "400"}
+        ]});
+        let balances = serde_json::json!({"asset_balances": {
+            ASSET_ID: {"asset_genesis": {"name": "USD"}, "balance": "1000"}
+        }});
+        let groups = serde_json::json!({"groups": {
+            group_key.clone(): {"assets": [{"id": id, "tag": "USD", "amount": "1000"}]}
+        }});
+        let addresses = serde_json::json!({"addrs": [
+            {"encoded": "taprt1usd", "asset_id": id},
+            {"encoded": "taprt1eur", "asset_id": other}
+        ]});
+        let transfers = serde_json::json!({"transfers": [
+            {"transfer_timestamp": "100", "inputs": [{"asset_id": id}]},
+            {"transfer_timestamp": "300", "inputs": [], "outputs": [{"asset_id": id}]},
+            {"transfer_timestamp": "200", "inputs": [{"asset_id": other}]}
+        ]});
+
+        let detail = assemble_asset_detail(
+            ASSET_ID,
+            &assets,
+            &balances,
+            Some(&groups),
+            Some(&addresses),
+            Some(&transfers),
+        )
+        .unwrap();
+        assert_eq!(detail.name.as_deref(), Some("USD"));
+        assert_eq!(detail.asset_type.as_deref(), Some("NORMAL"));
+        assert_eq!((detail.balance, detail.leaves.len()), (1000, 2));
+        assert_eq!(detail.group.unwrap().group_key, group_key);
+        assert_eq!(detail.addresses.len(), 1);
+        let timestamps: Vec<_> =
+            detail.transfers.iter().map(|t| &t["transfer_timestamp"]).collect();
+        assert_eq!(timestamps, ["300", "100"]);
+
+        let partial = assemble_asset_detail(ASSET_ID, &assets, &balances, None, None, None)
+            .unwrap();
+        assert!(partial.group.is_none() && partial.transfers.is_empty());
+
+        let unknown = hex::encode([0x33; 32]);
+        assert!(assemble_asset_detail(&unknown, &assets, &balances, None, None, None).is_none());
+    }
+
+    #[test]
+    fn test_assemble_asset_stats() {
+        let asset_id = "cd".repeat(32);
+        let encoded_id = base64::engine::general_purpose::STANDARD.encode([0xcd; 32]);
+        let stats = serde_json::json!({"asset_stats": [{
+            "asset": {
+                "asset_id": encoded_id,
+                "asset_name": "beefbux",
+                "total_supply": "1000",
+                "decimal_display": 2,
+                "genesis_height": 800000,
+            },
+            "total_syncs": "4",
+            "total_proofs": "9",
+        }]});
+        let balances = serde_json::json!({"asset_balances": {
+            asset_id.clone(): {"balance": "250"}
+        }});
+        let assets = serde_json::json!({"assets": [
+            {"asset_genesis": {"asset_id": encoded_id}},
+            {"asset_genesis": {"asset_id": encoded_id}},
+        ]});
+
+        let response = assemble_asset_stats(&asset_id, Some(&stats), &balances, &assets).unwrap();
+        assert_eq!(response.name.as_deref(), Some("beefbux"));
+        assert_eq!(response.total_supply, Some(1000));
+        assert_eq!(response.decimal_display, Some(2));
+        assert_eq!(response.genesis_height, Some(800000));
+        assert_eq!((response.total_syncs, response.total_proofs), (4, 9));
+        assert_eq!((response.local_balance, response.local_leaves), (250, 2));
+        assert_eq!(response.distributed, Some(750));
+
+        // Without universe stats only the local holdings are known
+        let local = assemble_asset_stats(&asset_id, None, &balances, &assets).unwrap();
+        assert_eq!((local.total_supply, local.distributed), (None, None));
+        let empty = serde_json::json!({});
+        assert!(assemble_asset_stats(&"ab".repeat(32), Some(&stats), &empty, &empty).is_none());
+    }
+
+    #[test]
+    fn test_inject_image_meta() {
+        let image = StoredImage::new(b"\x89PNG\r\n\x1a\nrest".to_vec()).unwrap();
+        let mut payload = serde_json::json!({
+            "asset": {"name": "punk-1", "amount": "1", "asset_type": "COLLECTIBLE"}
+        });
+        inject_image_meta(&mut payload, &image).unwrap();
+        let meta = &payload["asset"]["asset_meta"];
+        assert_eq!(meta["type"], "META_TYPE_OPAQUE");
+        let decoded = decode_asset_meta(ASSET_ID, meta).unwrap();
+        assert_eq!(decoded.size_bytes, image.data.len());
+
+        assert!(inject_image_meta(&mut payload, &image).is_err());
+        assert!(inject_image_meta(&mut serde_json::json!({}), &image).is_err());
+    }
+}
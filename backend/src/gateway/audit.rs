@@ -0,0 +1,269 @@
+//! Records sends, mints, burns, channel changes, payments and admin actions
+//! into the audit log, and serves it to operators at `GET /admin/audit`
+
+use std::net::SocketAddr;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Query, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::error;
+use uuid::Uuid;
+
+use super::admin::{authorize, error_response, is_admin};
+use crate::error::AppError;
+use crate::storage::audit::{AuditAction, AuditEntry, AuditQuery, AuditResult};
+use crate::types::AppState;
+
+/// Matches axum's default body limit, which the handlers would apply anyway
+const MAX_AUDITED_BODY_BYTES: usize = 2 * 1024 * 1024;
+/// Requests whose bodies are passed through unread and left out of the log:
+/// imported archives are sealed, and may exceed the limit above
+const UNSUMMARIZED_PATHS: &[&str] = &["/admin/import"];
+/// Longer strings, such as minted asset metadata, are cut down in summaries
+const MAX_SUMMARY_STRING_CHARS: usize = 128;
+/// Body fields whose names contain these are never written to the log
+const REDACTED_FIELDS: &[&str] =
+    &["secret", "macaroon", "token", "password", "passphrase", "preimage", "seed", "mnemonic"];
+
+pub const AUDIT_DEFAULT_LIMIT: usize = 100;
+pub const AUDIT_MAX_LIMIT: usize = 1000;
+
+/// The audited action a request performs, if any. Admin reads are not
+/// recorded; the WebSocket variants of payments and channel closes are.
+pub fn audit_action(method: &Method, path: &str) -> Option<AuditAction> {
+    if path.starts_with("/admin/") || path == "/events/debuglevel" {
+        return (method != Method::GET).then_some(AuditAction::Admin);
+    }
+
+    let action = match path {
+        "/api/assets/send" | "/api/assets/send/multi" | "/api/assets/send-batch" => {
+            AuditAction::AssetSend
+        }
+        "/api/assets/mint" | "/v1/taproot-assets/assets/mint" => AuditAction::AssetMint,
+        "/v1/taproot-assets/burn" => AuditAction::AssetBurn,
+        "/v1/taproot-assets/channels/channels/fund" => AuditAction::ChannelFund,
+        "/v1/taproot-assets/channels/channels/close" => {
+            return (method == Method::POST || method == Method::GET)
+                .then_some(AuditAction::ChannelClose);
+        }
+        "/v1/taproot-assets/channels/channels/send-payment" => {
+            return (method == Method::POST || method == Method::GET)
+                .then_some(AuditAction::PaymentSend);
+        }
+        "/v1/taproot-assets/channels/channels/keysend"
+        | "/v1/taproot-assets/channels/channels/pay-address"
+        | "/v1/lnd/payments"
+        | "/v1/lnd/offers/pay" => AuditAction::PaymentSend,
+        _ => return None,
+    };
+    (method == Method::POST).then_some(action)
+}
+
+/// Middleware appending an entry for each audited request once it has been
+/// answered. A failure to write the entry is logged; the response is still
+/// returned, since the operation has already run.
+pub async fn record_audit_entry(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(action) = audit_action(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let mut entry = AuditEntry {
+        id: Uuid::new_v4(),
+        occurred_at: Utc::now().timestamp(),
+        actor: if is_admin(&state, &parts.headers) { "admin" } else { "anonymous" }.to_string(),
+        ip: parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string()),
+        forwarded_for: parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        action,
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        summary: Value::Null,
+        status: 0,
+        result: AuditResult::Failure,
+    };
+
+    let response = if UNSUMMARIZED_PATHS.contains(&entry.path.as_str()) {
+        next.run(Request::from_parts(parts, body)).await
+    } else {
+        match to_bytes(body, MAX_AUDITED_BODY_BYTES).await {
+            Ok(bytes) => {
+                entry.summary = summarize_body(&bytes);
+                next.run(Request::from_parts(parts, Body::from(bytes))).await
+            }
+            Err(_) => StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+        }
+    };
+
+    entry.status = response.status().as_u16();
+    entry.result = if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+        AuditResult::Success
+    } else {
+        AuditResult::from_status(entry.status)
+    };
+    if let Err(e) = state.audit_log.append(&entry).await {
+        error!("Failed to record audit entry for {} {}: {}", entry.method, entry.path, e);
+    }
+
+    response
+}
+
+/// The request body as logged: JSON with secrets redacted and long strings
+/// shortened, or just its size when it is not JSON
+fn summarize_body(body: &[u8]) -> Value {
+    if body.is_empty() {
+        return Value::Null;
+    }
+    match serde_json::from_slice::<Value>(body) {
+        Ok(value) => summarize(value),
+        Err(_) => serde_json::json!({ "bytes": body.len() }),
+    }
+}
+
+fn summarize(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => fields
+            .into_iter()
+            .map(|(name, value)| {
+                let lowercase = name.to_ascii_lowercase();
+                let value = if REDACTED_FIELDS.iter().any(|field| lowercase.contains(field)) {
+                    Value::String("[redacted]".to_string())
+                } else {
+                    summarize(value)
+                };
+                (name, value)
+            })
+            .collect(),
+        Value::Array(items) => items.into_iter().map(summarize).collect(),
+        Value::String(text) if text.chars().count() > MAX_SUMMARY_STRING_CHARS => {
+            let kept: String = text.chars().take(MAX_SUMMARY_STRING_CHARS).collect();
+            Value::String(format!("{kept}... ({} chars)", text.chars().count()))
+        }
+        other => other,
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditParams {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    /// `success` or `failure`
+    pub result: Option<String>,
+    /// Inclusive bounds on when the request was made, in unix seconds
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl AuditParams {
+    fn query(self) -> Result<AuditQuery, AppError> {
+        let result = match self.result.as_deref() {
+            None => None,
+            Some("success") => Some(AuditResult::Success),
+            Some("failure") => Some(AuditResult::Failure),
+            Some(other) => {
+                return Err(AppError::InvalidInput(format!(
+                    "result must be success or failure, got {other}"
+                )))
+            }
+        };
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(AppError::InvalidInput("from must not be after to".to_string()));
+            }
+        }
+        Ok(AuditQuery {
+            actor: self.actor,
+            action: self.action.as_deref().map(AuditAction::parse).transpose()?,
+            result,
+            from: self.from,
+            to: self.to,
+            limit: self.limit.unwrap_or(AUDIT_DEFAULT_LIMIT).clamp(1, AUDIT_MAX_LIMIT),
+        })
+    }
+}
+
+/// Audit entries matching the filters, newest first
+pub async fn list_audit_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AuditParams>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+    let query = params.query().map_err(error_response)?;
+    let entries = state.audit_log.list(&query).await.map_err(error_response)?;
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_action() {
+        let send = audit_action(&Method::POST, "/api/assets/send");
+        assert_eq!(send, Some(AuditAction::AssetSend));
+        let batch = audit_action(&Method::POST, "/api/assets/send-batch");
+        assert_eq!(batch, Some(AuditAction::AssetSend));
+        let close = audit_action(&Method::GET, "/v1/taproot-assets/channels/channels/close");
+        assert_eq!(close, Some(AuditAction::ChannelClose));
+        assert_eq!(audit_action(&Method::POST, "/admin/reconcile"), Some(AuditAction::Admin));
+        assert_eq!(audit_action(&Method::GET, "/admin/audit"), None);
+        assert_eq!(audit_action(&Method::GET, "/api/assets/mint"), None);
+        assert_eq!(audit_action(&Method::POST, "/api/assets/address"), None);
+    }
+
+    #[test]
+    fn test_summary_redacts_secrets_and_shortens_long_values() {
+        let body = serde_json::json!({
+            "asset_id": "ab",
+            "amount": 10,
+            "outputs": [{"webhook_secret": "whsec", "addr": "taprt1"}],
+            "meta_data": "a".repeat(200),
+        });
+        let summary = summarize_body(body.to_string().as_bytes());
+        assert_eq!(summary["amount"], 10);
+        assert_eq!(summary["outputs"][0]["webhook_secret"], "[redacted]");
+        assert_eq!(summary["outputs"][0]["addr"], "taprt1");
+        assert!(summary["meta_data"].as_str().unwrap().ends_with("... (200 chars)"));
+
+        assert_eq!(summarize_body(b""), Value::Null);
+        assert_eq!(summarize_body(b"not json"), serde_json::json!({"bytes": 8}));
+    }
+
+    #[test]
+    fn test_params_query() {
+        let params = AuditParams {
+            action: Some("asset.burn".to_string()),
+            result: Some("failure".to_string()),
+            limit: Some(5_000),
+            ..Default::default()
+        };
+        let query = params.query().unwrap();
+        assert_eq!(query.action, Some(AuditAction::AssetBurn));
+        assert_eq!(query.result, Some(AuditResult::Failure));
+        assert_eq!(query.limit, AUDIT_MAX_LIMIT);
+
+        let unknown = AuditParams { action: Some("asset.steal".to_string()), ..Default::default() };
+        assert!(unknown.query().is_err());
+        let reversed = AuditParams { from: Some(2), to: Some(1), ..Default::default() };
+        assert!(reversed.query().is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Encrypted export and import of the gateway's local state, for moving it
+//! to another instance.
+//!
+//! An archive holds the persisted transactions, each with the address it
+//! paid or was received on, and the server-side settings; nothing else is
+//! carried over (see [`NOT_ARCHIVED`]). Asset proofs stay in tapd; a
+//! transaction's address is the reference that finds them there.
+//!
+//! The archive is JSON encrypted with AES-256-GCM under a key derived from
+//! an operator-chosen passphrase with Argon2id, so it can be carried between
+//! hosts without exposing history. It is stored as
+//! `archive:v1:<base64 salt>:<base64 nonce (12) || ciphertext || tag (16)>`,
+//! with a fresh random salt for every export.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use base64::Engine;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use super::admin::{authorize, error_response};
+use crate::error::AppError;
+use crate::storage::settings::{Setting, SettingsStore};
+use crate::storage::transactions::TransactionRepo;
+use crate::types::{AppState, Transaction};
+
+const ARCHIVE_VERSION: u32 = 1;
+const ARCHIVE_PREFIX: &str = "archive:v1:";
+const MIN_PASSPHRASE_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Argon2id cost: 19 MiB of memory, two passes, one lane. Part of the
+/// archive format, so changing it needs a new prefix.
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_PASSES: u32 = 2;
+
+/// What an export carries
+pub const ARCHIVED: [&str; 2] = ["transactions", "settings"];
+/// Stored state an export leaves behind; it has to be recreated on the new
+/// gateway (webhooks and receivers re-registered, and so on)
+pub const NOT_ARCHIVED: [&str; 9] = [
+    "payments",
+    "rfq_orders",
+    "webhooks",
+    "mailbox_receivers",
+    "devices",
+    "asset_preferences",
+    "invoices",
+    "balance_snapshots",
+    "price_alerts",
+];
+/// Imports may exceed axum's default body limit, since archives grow with history
+pub const MAX_IMPORT_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    exported_at: i64,
+    transactions: Vec<ArchivedTransaction>,
+    settings: Vec<Setting>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedTransaction {
+    #[serde(flatten)]
+    transaction: Transaction,
+    /// The Taproot Assets address paid or received on
+    destination: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportRequest {
+    /// At least 32 characters; needed again to import the archive
+    pub passphrase: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportResponse {
+    /// The sealed archive, to be passed to `POST /admin/import` as is
+    pub archive: String,
+    pub transactions: usize,
+    pub settings: usize,
+    /// Always [`ARCHIVED`]
+    pub includes: [&'static str; 2],
+    /// Always [`NOT_ARCHIVED`]
+    pub excludes: [&'static str; 9],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    pub passphrase: String,
+    pub archive: String,
+}
+
+/// What an import restored
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ImportReport {
+    pub transactions_imported: usize,
+    /// Transactions whose ID this gateway already holds; they are left as is
+    pub transactions_skipped: usize,
+    /// Settings written over any existing value for their key
+    pub settings_restored: usize,
+}
+
+/// Derives the archive key from the passphrase and the archive's salt
+fn archive_cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, AppError> {
+    if passphrase.len() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "passphrase must be at least {MIN_PASSPHRASE_LEN} characters"
+        )));
+    }
+
+    let params = Params::new(KDF_MEMORY_KIB, KDF_PASSES, 1, Some(32))
+        .expect("archive KDF parameters are valid");
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::StorageError(format!("Failed to derive archive key: {e}")))?;
+    Ok(Aes256Gcm::new(&key.into()))
+}
+
+fn seal_archive(passphrase: &str, json: &str) -> Result<String, AppError> {
+    let salt: [u8; SALT_LEN] = secp256k1::rand::random();
+    let nonce: [u8; NONCE_LEN] = secp256k1::rand::random();
+    let ciphertext = archive_cipher(passphrase, &salt)?
+        .encrypt(Nonce::from_slice(&nonce), json.as_bytes())
+        .map_err(|_| AppError::StorageError("Failed to seal archive".to_string()))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    let base64 = base64::engine::general_purpose::STANDARD;
+    Ok(format!("{ARCHIVE_PREFIX}{}:{}", base64.encode(salt), base64.encode(sealed)))
+}
+
+fn open_archive(passphrase: &str, archive: &str) -> Result<String, AppError> {
+    let not_an_archive =
+        || AppError::InvalidInput("archive is not an exported archive".to_string());
+    let (salt, sealed) = archive
+        .strip_prefix(ARCHIVE_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(not_an_archive)?;
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let salt = base64.decode(salt).map_err(|_| not_an_archive())?;
+    let sealed = base64.decode(sealed).map_err(|_| not_an_archive())?;
+    if salt.len() != SALT_LEN || sealed.len() < NONCE_LEN {
+        return Err(not_an_archive());
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let json = archive_cipher(passphrase, &salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            AppError::InvalidInput("archive could not be opened with this passphrase".to_string())
+        })?;
+    String::from_utf8(json).map_err(|_| not_an_archive())
+}
+
+/// Runs archive sealing or opening on the blocking pool, since the key
+/// derivation would otherwise hold a runtime worker for its whole duration
+async fn on_blocking_pool<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::StorageError(format!("Archive key derivation failed: {e}")))?
+}
+
+/// Seals every transaction and setting into an archive
+pub async fn export_archive(
+    transactions: &dyn TransactionRepo,
+    settings: &dyn SettingsStore,
+    passphrase: &str,
+) -> Result<ExportResponse, AppError> {
+    let archive = Archive {
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now().timestamp(),
+        transactions: transactions
+            .export()
+            .await?
+            .into_iter()
+            .map(|(transaction, destination)| ArchivedTransaction { transaction, destination })
+            .collect(),
+        settings: settings.list().await?,
+    };
+
+    let json = serde_json::to_string(&archive)
+        .map_err(|e| AppError::StorageError(format!("Failed to encode archive: {e}")))?;
+    let passphrase = passphrase.to_string();
+    Ok(ExportResponse {
+        archive: on_blocking_pool(move || seal_archive(&passphrase, &json)).await?,
+        transactions: archive.transactions.len(),
+        settings: archive.settings.len(),
+        includes: ARCHIVED,
+        excludes: NOT_ARCHIVED,
+    })
+}
+
+/// Opens an archive from [`export_archive`] and restores it. Transactions
+/// already present are kept, so importing the same archive twice is harmless.
+pub async fn import_archive(
+    transactions: &dyn TransactionRepo,
+    settings: &dyn SettingsStore,
+    passphrase: &str,
+    sealed: &str,
+) -> Result<ImportReport, AppError> {
+    let (passphrase, sealed) = (passphrase.to_string(), sealed.to_string());
+    let json = on_blocking_pool(move || open_archive(&passphrase, &sealed)).await?;
+    let archive: Archive = serde_json::from_str(&json)
+        .map_err(|e| AppError::InvalidInput(format!("archive is malformed: {e}")))?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "archive version {} is not supported",
+            archive.version
+        )));
+    }
+
+    let mut report = ImportReport::default();
+    for ArchivedTransaction { transaction, destination } in archive.transactions {
+        if transactions.import(transaction, destination).await? {
+            report.transactions_imported += 1;
+        } else {
+            report.transactions_skipped += 1;
+        }
+    }
+    for setting in &archive.settings {
+        settings.put(setting).await?;
+        report.settings_restored += 1;
+    }
+    Ok(report)
+}
+
+/// `POST /admin/export`: seals transactions and settings only. The response
+/// lists what was included and what was left behind.
+pub async fn export_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ExportRequest>,
+) -> Result<Json<ExportResponse>, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+    let export = export_archive(
+        state.transaction_store.as_ref(),
+        state.settings.as_ref(),
+        &request.passphrase,
+    )
+    .await
+    .map_err(error_response)?;
+
+    info!(
+        "Exported {} transactions and {} settings",
+        export.transactions, export.settings
+    );
+    Ok(Json(export))
+}
+
+pub async fn import_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ImportRequest>,
+) -> Result<Json<ImportReport>, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+    let report = import_archive(
+        state.transaction_store.as_ref(),
+        state.settings.as_ref(),
+        &request.passphrase,
+        &request.archive,
+    )
+    .await
+    .map_err(error_response)?;
+
+    info!(
+        "Imported {} transactions ({} already present) and {} settings",
+        report.transactions_imported, report.transactions_skipped, report.settings_restored
+    );
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::settings::InMemorySettingsStore;
+    use crate::storage::transactions::InMemoryTransactionRepo;
+    use crate::types::{TransactionStatus, TransactionType};
+
+    const PASSPHRASE: &str = "correct horse battery staple, twice over";
+
+    fn transaction() -> Transaction {
+        Transaction {
+            id: uuid::Uuid::new_v4(),
+            tx_type: TransactionType::Receive,
+            asset_id: Some("ab".repeat(32)),
+            amount: 42,
+            status: TransactionStatus::Confirmed,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            label: Some("rent".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let transactions = InMemoryTransactionRepo::new();
+        let settings = InMemorySettingsStore::new();
+        let received = transaction();
+        transactions.insert(received.clone(), Some("taprt1alice".to_string())).await.unwrap();
+        let setting = Setting {
+            key: "display_currency".to_string(),
+            value: serde_json::json!("EUR"),
+            updated_at: 1_700_000_000,
+        };
+        settings.put(&setting).await.unwrap();
+
+        let export = export_archive(&transactions, &settings, PASSPHRASE).await.unwrap();
+        assert_eq!((export.transactions, export.settings), (1, 1));
+        assert_eq!(export.includes, ARCHIVED);
+        assert!(export.excludes.contains(&"webhooks"));
+        assert!(export.archive.starts_with(ARCHIVE_PREFIX));
+        assert!(!export.archive.contains("taprt1alice"));
+
+        let restored = InMemoryTransactionRepo::new();
+        let restored_settings = InMemorySettingsStore::new();
+        let report = import_archive(&restored, &restored_settings, PASSPHRASE, &export.archive)
+            .await
+            .unwrap();
+        assert_eq!(report.transactions_imported, 1);
+        assert_eq!(report.settings_restored, 1);
+        let exported = restored.export().await.unwrap();
+        assert_eq!(exported[0].0.id, received.id);
+        assert_eq!(exported[0].1.as_deref(), Some("taprt1alice"));
+        assert_eq!(restored_settings.get("display_currency").await.unwrap(), Some(setting));
+
+        let again = import_archive(&restored, &restored_settings, PASSPHRASE, &export.archive)
+            .await
+            .unwrap();
+        assert_eq!((again.transactions_imported, again.transactions_skipped), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_wrong_passphrase_and_plain_archives() {
+        let transactions = InMemoryTransactionRepo::new();
+        let settings = InMemorySettingsStore::new();
+        let export = export_archive(&transactions, &settings, PASSPHRASE).await.unwrap();
+
+        let wrong = "another passphrase of 32 or more characters";
+        let result = import_archive(&transactions, &settings, wrong, &export.archive).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        let plain = r#"{"version":1,"exported_at":0,"transactions":[],"settings":[]}"#;
+        let result = import_archive(&transactions, &settings, PASSPHRASE, plain).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        let result = export_archive(&transactions, &settings, "short").await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_key_derivation_leaves_the_runtime_free() {
+        // On this single-threaded runtime the export only yields back here
+        // if its key derivation runs somewhere else
+        let export = tokio::spawn(async {
+            let transactions = InMemoryTransactionRepo::new();
+            let settings = InMemorySettingsStore::new();
+            export_archive(&transactions, &settings, PASSPHRASE).await
+        });
+        tokio::task::yield_now().await;
+        assert!(!export.is_finished());
+        assert!(export.await.unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_each_archive_gets_its_own_salt() {
+        let first = seal_archive(PASSPHRASE, "{}").unwrap();
+        let second = seal_archive(PASSPHRASE, "{}").unwrap();
+        let salt = |archive: &str| archive.split(':').nth(2).unwrap().to_string();
+        assert_ne!(salt(&first), salt(&second));
+        assert_eq!(open_archive(PASSPHRASE, &second).unwrap(), "{}");
+
+        let sealed = second.rsplit(':').next().unwrap();
+        let tampered = format!("{ARCHIVE_PREFIX}{}:{sealed}", salt(&first));
+        assert!(matches!(open_archive(PASSPHRASE, &tampered), Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use super::events::{EventBroker, EventQueryParams};
+use super::lnd::{display_txid, LndClient};
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// Confirmations after which a watched transaction is considered final
+pub const CONFIRMATION_TARGET: u32 = 6;
+/// Watched transactions that never show up in the wallet are dropped after this many blocks
+const MAX_WATCH_BLOCKS: u32 = 144;
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const CHANNEL_CAPACITY: usize = 64;
+
+/// A chain update streamed on `/events/blocks`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainEvent {
+    Block {
+        height: u32,
+        hash: String,
+    },
+    Confirmations {
+        txid: String,
+        confirmations: u32,
+        target: u32,
+        block_height: Option<u32>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct LndInfo {
+    block_height: u32,
+    block_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndTransaction {
+    tx_hash: String,
+    #[serde(default)]
+    num_confirmations: u32,
+    #[serde(default)]
+    block_height: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndTransactions {
+    #[serde(default)]
+    transactions: Vec<LndTransaction>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct WatchedTx {
+    confirmations: Option<u32>,
+    added_at_height: u32,
+}
+
+/// Follows the chain tip through LND and reports confirmation progress of
+/// anchor transactions seen in asset send and receive events
+pub struct ChainWatcher {
+    lnd: Arc<LndClient>,
+    sender: broadcast::Sender<Arc<ChainEvent>>,
+    watched: Mutex<HashMap<String, WatchedTx>>,
+    tip: Mutex<Option<Arc<ChainEvent>>>,
+}
+
+impl ChainWatcher {
+    pub fn new(lnd: Arc<LndClient>) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self {
+            lnd,
+            sender,
+            watched: Mutex::new(HashMap::new()),
+            tip: Mutex::new(None),
+        }
+    }
+
+    /// Returns the latest known block, if any, and a feed of further updates
+    pub fn subscribe(&self) -> (Option<Arc<ChainEvent>>, broadcast::Receiver<Arc<ChainEvent>>) {
+        let tip = self.tip.lock().unwrap().clone();
+        (tip, self.sender.subscribe())
+    }
+
+    /// Starts tracking confirmations of `txid`
+    pub fn watch(&self, txid: String) {
+        let height = self.tip_height().unwrap_or_default();
+        self.watched
+            .lock()
+            .unwrap()
+            .entry(txid)
+            .or_insert_with(|| WatchedTx {
+                confirmations: None,
+                added_at_height: height,
+            });
+    }
+
+    fn tip_height(&self) -> Option<u32> {
+        match self.tip.lock().unwrap().as_deref() {
+            Some(ChainEvent::Block { height, .. }) => Some(*height),
+            _ => None,
+        }
+    }
+
+    /// Polls LND for new blocks and watches anchor transactions from broker events
+    pub fn spawn(self: &Arc<Self>, broker: &Arc<EventBroker>) {
+        for event_type in ["asset-send", "asset-receive"] {
+            let watcher = Arc::clone(self);
+            let request = EventQueryParams::default().subscription_request(event_type);
+            let mut events = broker.subscribe(event_type, request);
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            if let Some(txid) = anchor_txid(&event.payload) {
+                                debug!("Watching anchor transaction {}", txid);
+                                watcher.watch(txid);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
+        let watcher = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = watcher.poll().await {
+                    warn!("Failed to poll LND for chain updates: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn poll(&self) -> Result<(), AppError> {
+        let info: LndInfo = self.lnd.get("/v1/getinfo").await?;
+        if self.tip_height() == Some(info.block_height) {
+            return Ok(());
+        }
+
+        info!("New block {} ({})", info.block_height, info.block_hash);
+        let block = Arc::new(ChainEvent::Block {
+            height: info.block_height,
+            hash: info.block_hash,
+        });
+        *self.tip.lock().unwrap() = Some(Arc::clone(&block));
+        let _ = self.sender.send(block);
+
+        if self.watched.lock().unwrap().is_empty() {
+            return Ok(());
+        }
+
+        // Unconfirmed transactions are included by passing end_height=-1
+        let start_height = info.block_height.saturating_sub(CONFIRMATION_TARGET);
+        let transactions: LndTransactions = self
+            .lnd
+            .get(&format!("/v1/transactions?start_height={start_height}&end_height=-1"))
+            .await?;
+        let confirmations: HashMap<String, (u32, u32)> = transactions
+            .transactions
+            .into_iter()
+            .map(|tx| (tx.tx_hash, (tx.num_confirmations, tx.block_height)))
+            .collect();
+
+        let updates = update_confirmations(
+            &mut self.watched.lock().unwrap(),
+            &confirmations,
+            info.block_height,
+        );
+        for update in updates {
+            let _ = self.sender.send(Arc::new(update));
+        }
+        Ok(())
+    }
+
+}
+
+/// Applies the wallet's view of confirmations to the watch list, returning
+/// an update for every transaction whose confirmation count changed
+fn update_confirmations(
+    watched: &mut HashMap<String, WatchedTx>,
+    wallet: &HashMap<String, (u32, u32)>,
+    tip_height: u32,
+) -> Vec<ChainEvent> {
+    let mut updates = Vec::new();
+
+    watched.retain(|txid, tx| {
+        let Some(&(confirmations, block_height)) = wallet.get(txid) else {
+            // Outside the queried window: either buried deeper than the
+            // target already or unknown to this wallet
+            return tip_height.saturating_sub(tx.added_at_height) < MAX_WATCH_BLOCKS;
+        };
+
+        if tx.confirmations != Some(confirmations) {
+            tx.confirmations = Some(confirmations);
+            updates.push(ChainEvent::Confirmations {
+                txid: txid.clone(),
+                confirmations,
+                target: CONFIRMATION_TARGET,
+                block_height: (block_height > 0).then_some(block_height),
+            });
+        }
+        confirmations < CONFIRMATION_TARGET
+    });
+
+    updates
+}
+
+/// Extracts the anchor transaction id from an asset send or receive event
+fn anchor_txid(event: &serde_json::Value) -> Option<String> {
+    // Receive events carry the anchor outpoint as "txid:vout"
+    if let Some(outpoint) = event.get("outpoint").and_then(|o| o.as_str()) {
+        let txid = outpoint.split(':').next()?;
+        return is_txid(txid).then(|| txid.to_string());
+    }
+
+    // Send events carry the transfer's anchor hash as base64 bytes in
+    // internal byte order, so it is reversed to get the displayed txid
+    let encoded = event.get("transfer")?.get("anchor_tx_hash")?.as_str()?;
+    display_txid(encoded)
+}
+
+fn is_txid(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub async fn blocks_ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    let Some(watcher) = state.chain_watcher.clone() else {
+        let error = AppError::ServiceUnavailable("LND chain notifications are not configured".to_string());
+        return (
+            error.status_code(),
+            Json(serde_json::json!({
+                "error": error.to_string(),
+                "type": format!("{:?}", error)
+            })),
+        )
+            .into_response();
+    };
+
+    ws.on_upgrade(move |socket| forward_chain_events(socket, watcher))
+}
+
+async fn forward_chain_events(mut socket: WebSocket, watcher: Arc<ChainWatcher>) {
+    info!("Opening block event stream");
+    let (tip, mut events) = watcher.subscribe();
+
+    if let Some(tip) = tip {
+        let Ok(text) = serde_json::to_string(tip.as_ref()) else {
+            return;
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(event.as_ref()) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Block event subscriber lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use serde_json::json;
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    #[test]
+    fn test_anchor_txid_from_events() {
+        let receive = json!({"outpoint": format!("{TXID}:1")});
+        assert_eq!(anchor_txid(&receive).as_deref(), Some(TXID));
+
+        let mut internal = hex::decode(TXID).unwrap();
+        internal.reverse();
+        let send = json!({
+            "transfer": {
+                "anchor_tx_hash": base64::engine::general_purpose::STANDARD.encode(internal)
+            }
+        });
+        assert_eq!(anchor_txid(&send).as_deref(), Some(TXID));
+
+        assert_eq!(anchor_txid(&json!({"outpoint": "not-a-txid:0"})), None);
+        assert_eq!(anchor_txid(&json!({"send_state": "SEND_STATE_BROADCAST"})), None);
+    }
+
+    #[test]
+    fn test_update_confirmations_reports_changes_and_drops_final() {
+        let mut watched = HashMap::new();
+        watched.insert(
+            TXID.to_string(),
+            WatchedTx {
+                confirmations: None,
+                added_at_height: 100,
+            },
+        );
+
+        let mut wallet = HashMap::new();
+        wallet.insert(TXID.to_string(), (0, 0));
+        let updates = update_confirmations(&mut watched, &wallet, 100);
+        assert_eq!(
+            updates,
+            vec![ChainEvent::Confirmations {
+                txid: TXID.to_string(),
+                confirmations: 0,
+                target: CONFIRMATION_TARGET,
+                block_height: None,
+            }]
+        );
+
+        // Unchanged counts produce no update
+        assert!(update_confirmations(&mut watched, &wallet, 100).is_empty());
+
+        wallet.insert(TXID.to_string(), (CONFIRMATION_TARGET, 101));
+        let updates = update_confirmations(&mut watched, &wallet, 106);
+        assert_eq!(updates.len(), 1);
+        assert!(watched.is_empty());
+    }
+
+    #[test]
+    fn test_update_confirmations_expires_unknown_transactions() {
+        let mut watched = HashMap::new();
+        watched.insert(
+            TXID.to_string(),
+            WatchedTx {
+                confirmations: None,
+                added_at_height: 100,
+            },
+        );
+
+        update_confirmations(&mut watched, &HashMap::new(), 150);
+        assert_eq!(watched.len(), 1);
+        update_confirmations(&mut watched, &HashMap::new(), 100 + MAX_WATCH_BLOCKS);
+        assert!(watched.is_empty());
+    }
+
+    #[test]
+    fn test_chain_event_serialization() {
+        let block = ChainEvent::Block {
+            height: 840_000,
+            hash: "00ab".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&block).unwrap(),
+            json!({"type": "block", "height": 840000, "hash": "00ab"})
+        );
+    }
+}
@@ -1,15 +1,21 @@
+use super::upstream::SendUpstream;
+use crate::api::idempotency::{run_once, IdempotencyKey};
 use crate::error::AppError;
-use crate::types::AppState;
+use crate::types::{AppState, BalanceGrouping};
 use axum::{
     extract::State,
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::{IntoResponse, Json, Response},
 };
+use base64::Engine;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The phrase tapd requires before it destroys assets
+pub const BURN_CONFIRMATION_TEXT: &str = "assets will be destroyed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BurnRequest {
     pub asset_id: String,
     pub asset_id_str: Option<String>,
@@ -18,6 +24,74 @@ pub struct BurnRequest {
     pub note: Option<String>,
 }
 
+impl BurnRequest {
+    /// Hex asset ID, from `asset_id_str` or from `asset_id` as hex or base64
+    pub fn asset_id_hex(&self) -> Option<String> {
+        let is_hex = |id: &str| id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit());
+        if let Some(id) = self.asset_id_str.as_deref().filter(|id| !id.is_empty()) {
+            return is_hex(id).then(|| id.to_ascii_lowercase());
+        }
+        if is_hex(&self.asset_id) {
+            return Some(self.asset_id.to_ascii_lowercase());
+        }
+        let engines = [
+            base64::engine::general_purpose::STANDARD,
+            base64::engine::general_purpose::URL_SAFE,
+        ];
+        engines
+            .iter()
+            .find_map(|engine| engine.decode(&self.asset_id).ok())
+            .filter(|bytes| bytes.len() == 32)
+            .map(hex::encode)
+    }
+
+    /// Checks everything that does not need tapd: the confirmation phrase, a
+    /// positive amount and the operator's cap. Returns the amount to burn.
+    pub fn validate(&self, max_amount: Option<u64>) -> Result<u64, AppError> {
+        if self.confirmation_text != BURN_CONFIRMATION_TEXT {
+            return Err(AppError::InvalidInput(format!(
+                "confirmation_text must be exactly '{BURN_CONFIRMATION_TEXT}'"
+            )));
+        }
+        let amount = self
+            .amount_to_burn
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|amount| *amount > 0)
+            .ok_or_else(|| {
+                AppError::InvalidInput("amount_to_burn must be a positive integer".to_string())
+            })?;
+        if let Some(max) = max_amount.filter(|max| amount > *max) {
+            return Err(AppError::InvalidInput(format!(
+                "amount_to_burn {amount} exceeds the configured limit of {max}"
+            )));
+        }
+        Ok(amount)
+    }
+}
+
+/// The spendable balance of one asset in a `ListBalances` response grouped by
+/// asset ID; assets the wallet does not hold have none
+pub fn asset_balance(balances: &serde_json::Value, asset_id_hex: &str) -> u64 {
+    let balance = &balances["asset_balances"][asset_id_hex]["balance"];
+    balance
+        .as_str()
+        .and_then(|balance| balance.parse().ok())
+        .or_else(|| balance.as_u64())
+        .unwrap_or(0)
+}
+
+/// Rejects a burn larger than the wallet's balance of the asset
+pub fn check_burn_balance(amount: u64, balance: u64) -> Result<(), AppError> {
+    if amount > balance {
+        return Err(AppError::InvalidInput(format!(
+            "amount_to_burn {amount} exceeds the asset balance of {balance}"
+        )));
+    }
+    Ok(())
+}
+
 #[instrument(skip(client, macaroon_hex, request))]
 pub async fn burn_assets(
     client: &Client,
@@ -31,7 +105,7 @@ pub async fn burn_assets(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await?;
     Ok(response
         .json::<serde_json::Value>()
@@ -49,25 +123,45 @@ pub async fn list_burns(
     let response = client
         .get(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .send()
+        .send_upstream()
         .await?;
     Ok(response
         .json::<serde_json::Value>()
         .await?)
 }
 
+/// Validates the request and the wallet's balance before asking tapd to burn
+async fn checked_burn(state: &AppState, req: BurnRequest) -> Result<serde_json::Value, AppError> {
+    let amount = req.validate(state.burn_settings.max_amount)?;
+    let asset_id = req.asset_id_hex().ok_or_else(|| {
+        AppError::InvalidInput("asset_id must be 32 bytes of hex or base64".to_string())
+    })?;
+    let balances = state.tapd_client.get_balance(BalanceGrouping::AssetId).await.map_err(|e| {
+        warn!("Balance check before burn failed: {}", e);
+        AppError::RequestError(format!("Could not check the asset balance: {e}"))
+    })?;
+    check_burn_balance(amount, asset_balance(&balances, &asset_id))?;
+
+    let burned = match state.tapd_client.grpc_client() {
+        Some(grpc) => grpc.burn_asset(&serde_json::to_value(&req)?).await?,
+        None => {
+            burn_assets(&state.http_client, &state.base_url.0, &state.macaroon_hex.0, req).await?
+        }
+    };
+    state.response_cache.invalidate_holdings().await;
+    Ok(burned)
+}
+
 pub async fn burn(
     State(state): State<AppState>,
+    key: IdempotencyKey,
     Json(req): Json<BurnRequest>,
 ) -> impl IntoResponse {
-    match burn_assets(
-        &state.http_client,
-        &state.base_url.0,
-        &state.macaroon_hex.0,
-        req,
-    )
-    .await
-    {
+    run_once(&state, "burn", key, &req, || burn_once(&state, req.clone())).await
+}
+
+async fn burn_once(state: &AppState, req: BurnRequest) -> Response {
+    match checked_burn(state, req).await {
         Ok(value) => (StatusCode::OK, Json(value)).into_response(),
         Err(e) => {
             let status = e.status_code();
@@ -86,7 +180,11 @@ pub async fn burn(
 pub async fn list(
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    match list_burns(&state.http_client, &state.base_url.0, &state.macaroon_hex.0).await {
+    let burns = match state.tapd_client.grpc_client() {
+        Some(grpc) => grpc.list_burns().await,
+        None => list_burns(&state.http_client, &state.base_url.0, &state.macaroon_hex.0).await,
+    };
+    match burns {
         Ok(value) => (StatusCode::OK, Json(value)).into_response(),
         Err(e) => {
             let status = e.status_code();
@@ -146,4 +244,59 @@ mod tests {
         assert_eq!(deserialized.confirmation_text, "I understand this action cannot be undone");
         assert_eq!(deserialized.note, None);
     }
+
+    fn burn_request(amount: &str, confirmation: &str) -> BurnRequest {
+        BurnRequest {
+            asset_id: String::new(),
+            asset_id_str: Some("ab".repeat(32)),
+            amount_to_burn: amount.to_string(),
+            confirmation_text: confirmation.to_string(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_burn_validation() {
+        assert_eq!(burn_request("100", BURN_CONFIRMATION_TEXT).validate(None).unwrap(), 100);
+        assert_eq!(burn_request("100", BURN_CONFIRMATION_TEXT).validate(Some(100)).unwrap(), 100);
+
+        let rejected = [
+            burn_request("100", "Assets will be destroyed"),
+            burn_request("100", "I understand this action cannot be undone"),
+            burn_request("0", BURN_CONFIRMATION_TEXT),
+            burn_request("-5", BURN_CONFIRMATION_TEXT),
+            burn_request("1.5", BURN_CONFIRMATION_TEXT),
+            burn_request("101", BURN_CONFIRMATION_TEXT),
+        ];
+        for request in rejected {
+            let result = request.validate(Some(100));
+            assert!(matches!(result, Err(AppError::InvalidInput(_))), "{request:?}");
+        }
+    }
+
+    #[test]
+    fn test_burn_balance_check() {
+        let asset_id = "ab".repeat(32);
+        let balances = serde_json::json!({"asset_balances": {
+            asset_id.clone(): {"balance": "250"}
+        }});
+        assert_eq!(asset_balance(&balances, &asset_id), 250);
+        assert_eq!(asset_balance(&balances, &"cd".repeat(32)), 0);
+        assert!(check_burn_balance(250, 250).is_ok());
+        assert!(check_burn_balance(251, 250).is_err());
+    }
+
+    #[test]
+    fn test_burn_asset_id_hex() {
+        let mut request = burn_request("1", BURN_CONFIRMATION_TEXT);
+        assert_eq!(request.asset_id_hex(), Some("ab".repeat(32)));
+
+        request.asset_id_str = None;
+        request.asset_id = base64::engine::general_purpose::STANDARD.encode([0xcd; 32]);
+        assert_eq!(request.asset_id_hex(), Some("cd".repeat(32)));
+        request.asset_id = "CD".repeat(32);
+        assert_eq!(request.asset_id_hex(), Some("cd".repeat(32)));
+        request.asset_id = "not an asset".to_string();
+        assert_eq!(request.asset_id_hex(), None);
+    }
 }
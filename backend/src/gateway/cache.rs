@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::config::CacheSettings;
+
+/// The upstream reads dashboards poll, each with its own TTL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    Assets,
+    Balance,
+    Info,
+    UniverseRoots,
+}
+
+impl CacheKind {
+    /// Reads that sends, mints and burns make stale
+    pub const HOLDINGS: [CacheKind; 3] =
+        [CacheKind::Assets, CacheKind::Balance, CacheKind::UniverseRoots];
+}
+
+/// Caches raw tapd responses for a short time. A TTL of zero disables
+/// caching for that kind.
+pub struct ResponseCache {
+    ttls: HashMap<CacheKind, Duration>,
+    entries: RwLock<HashMap<(CacheKind, String), (Instant, Value)>>,
+}
+
+impl ResponseCache {
+    pub fn new(settings: &CacheSettings) -> Self {
+        let ttls = [
+            (CacheKind::Assets, settings.assets_ttl_secs),
+            (CacheKind::Balance, settings.balance_ttl_secs),
+            (CacheKind::Info, settings.info_ttl_secs),
+            (CacheKind::UniverseRoots, settings.universe_roots_ttl_secs),
+        ]
+        .into_iter()
+        .map(|(kind, secs)| (kind, Duration::from_secs(secs)))
+        .collect();
+        Self {
+            ttls,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Never caches anything
+    pub fn disabled() -> Self {
+        Self::new(&CacheSettings::disabled())
+    }
+
+    fn ttl(&self, kind: CacheKind) -> Duration {
+        self.ttls.get(&kind).copied().unwrap_or_default()
+    }
+
+    pub async fn get(&self, kind: CacheKind, key: &str) -> Option<Value> {
+        let entries = self.entries.read().await;
+        let (stored_at, value) = entries.get(&(kind, key.to_string()))?;
+        (stored_at.elapsed() < self.ttl(kind)).then(|| value.clone())
+    }
+
+    pub async fn insert(&self, kind: CacheKind, key: &str, value: Value) {
+        if self.ttl(kind).is_zero() {
+            return;
+        }
+        let mut entries = self.entries.write().await;
+        // Expired entries go on every write, so keys that stop being asked
+        // for do not pile up
+        entries.retain(|(kind, _), (stored_at, _)| stored_at.elapsed() < self.ttl(*kind));
+        entries.insert((kind, key.to_string()), (Instant::now(), value));
+    }
+
+    /// Answers from the cache while fresh, otherwise fetches and stores the
+    /// result; errors are never cached
+    pub async fn get_or_fetch<F, Fut, E>(
+        &self,
+        kind: CacheKind,
+        key: &str,
+        fetch: F,
+    ) -> Result<Value, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value, E>>,
+    {
+        if let Some(value) = self.get(kind, key).await {
+            return Ok(value);
+        }
+        let value = fetch().await?;
+        self.insert(kind, key, value.clone()).await;
+        Ok(value)
+    }
+
+    pub async fn invalidate(&self, kinds: &[CacheKind]) {
+        self.entries.write().await.retain(|(kind, _), _| !kinds.contains(kind));
+    }
+
+    /// Drops what a send, mint or burn changes
+    pub async fn invalidate_holdings(&self) {
+        self.invalidate(&CacheKind::HOLDINGS).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_serves_until_invalidated() {
+        let cache = ResponseCache::new(&CacheSettings::default());
+        let fetched = cache
+            .get_or_fetch(CacheKind::Balance, "asset_id=true", || async {
+                Ok::<_, ()>(serde_json::json!({"balance": 1}))
+            })
+            .await
+            .unwrap();
+        let cached = cache
+            .get_or_fetch(CacheKind::Balance, "asset_id=true", || async { Err(()) })
+            .await
+            .unwrap();
+        assert_eq!(fetched, cached);
+
+        cache.insert(CacheKind::Info, "", serde_json::json!({"version": "0.6"})).await;
+        cache.invalidate_holdings().await;
+        assert!(cache.get(CacheKind::Balance, "asset_id=true").await.is_none());
+        assert!(cache.get(CacheKind::Info, "").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables_caching() {
+        let cache = ResponseCache::disabled();
+        cache.insert(CacheKind::Assets, "", serde_json::json!({"assets": []})).await;
+        assert!(cache.get(CacheKind::Assets, "").await.is_none());
+    }
+}
@@ -5,14 +5,29 @@ use axum::{
     routing::{post, get},
     Router,
 };
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, instrument};
+use tracing::{info, info_span, instrument, warn, Instrument};
 
 use axum::extract::ws::{WebSocket, WebSocketUpgrade, Message};
 use axum::response::IntoResponse;
 
+use super::correlation::{new_correlation_id, with_correlation_id};
+use super::events::drain_stream_messages;
+use super::lnd::{display_txid, lnd_client, LndClient};
+use super::lnurl::{self, LightningAddress};
+use super::rfq;
+use super::rfq_analytics::accepted_price;
+use super::upstream::SendUpstream;
 use crate::error::AppError;
+use crate::storage::invoices::InvoiceRecord;
+use crate::storage::payments::{
+    PaymentOutcome, PaymentQuery, PaymentRecord, PaymentStatus, PaymentStore,
+};
+use crate::storage::rfq_orders::{RfqOrderKind, RfqOrderQuery, RfqOrderRecord, RfqOrderStore};
 use crate::types::AppState;
 
 // WebSocket proxy handler for streaming
@@ -20,58 +35,612 @@ pub struct WebSocketProxyHandler {
     pub client: Arc<reqwest::Client>,
     pub base_url: String,
     pub macaroon_hex: String,
+    pub payment_store: Arc<dyn PaymentStore>,
 }
 
 impl WebSocketProxyHandler {
-    pub fn new(client: Arc<reqwest::Client>, base_url: String, macaroon_hex: String) -> Self {
+    pub fn new(
+        client: Arc<reqwest::Client>,
+        base_url: String,
+        macaroon_hex: String,
+        payment_store: Arc<dyn PaymentStore>,
+    ) -> Self {
         Self {
             client,
             base_url,
             macaroon_hex,
+            payment_store,
         }
     }
 
     pub async fn handle_websocket(
         self: Arc<Self>,
         ws: WebSocketUpgrade,
-        _backend_endpoint: String,
-        _enable_correlation: bool,
+        backend_endpoint: String,
+        enable_correlation: bool,
     ) -> impl IntoResponse {
-        ws.on_upgrade(|socket| self.handle_socket(socket))
+        let correlation_id = enable_correlation.then(new_correlation_id);
+        let span = info_span!(
+            "ws_proxy",
+            correlation_id = correlation_id.as_deref().unwrap_or("-")
+        );
+        ws.on_upgrade(|socket| {
+            self.handle_socket(socket, backend_endpoint, correlation_id)
+                .instrument(span)
+        })
     }
 
+    /// Waits for the client's send-payment request, then relays tapd's streamed
+    /// updates until the payment settles or either side goes away
     async fn handle_socket(
         self: Arc<Self>,
         mut socket: WebSocket,
+        backend_endpoint: String,
+        correlation_id: Option<String>,
     ) {
-        // For now, we'll implement a basic WebSocket proxy
-        // In a full implementation, you'd connect to the backend WebSocket
-        // and proxy messages between the client and backend
-        
-        while let Some(msg) = socket.recv().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    info!("Received WebSocket message: {}", text);
-                    // Echo back for now - replace with actual backend communication
-                    if let Err(e) = socket.send(Message::Text(text)).await {
-                        info!("Failed to send WebSocket message: {}", e);
-                        break;
+        let request = match socket.recv().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str::<SendPaymentStreamRequest>(&text)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid send-payment request: {e}")))
+                .and_then(|mut request| {
+                    check_fields(&mut request)
+                        .map_err(|errors| AppError::InvalidInput(describe_field_errors(&errors)))?;
+                    Ok(request)
+                }),
+            Some(Ok(Message::Close(_))) | None => {
+                info!("WebSocket connection closed before a request was sent");
+                return;
+            }
+            Some(Ok(_)) => Err(AppError::InvalidInput(
+                "Expected a JSON send-payment request".to_string(),
+            )),
+            Some(Err(e)) => {
+                info!("WebSocket error: {}", e);
+                return;
+            }
+        };
+
+        let result = match request {
+            Ok(request) => {
+                let correlation_id = correlation_id.as_deref();
+                self.relay_payment(&mut socket, &backend_endpoint, &request, correlation_id)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("Send-payment stream failed: {}", e);
+            let frame = serde_json::json!({"error": e.to_string()});
+            let frame = match &correlation_id {
+                Some(id) => with_correlation_id(&frame, id),
+                None => frame,
+            };
+            let _ = socket.send(Message::Text(frame.to_string())).await;
+        }
+        let _ = socket.send(Message::Close(None)).await;
+    }
+
+    /// Records the payment in history around the proxied stream; a stream that
+    /// ends before tapd reports the result leaves the payment marked failed
+    async fn relay_payment(
+        &self,
+        socket: &mut WebSocket,
+        backend_endpoint: &str,
+        request: &SendPaymentStreamRequest,
+        correlation_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let record = PaymentRecord::in_flight(
+            request.asset_id.clone(),
+            request.asset_amount,
+            payment_request_string(&request.payment_request),
+            (!request.rfq_id.is_empty()).then(|| request.rfq_id.clone()),
+        );
+        record_payment(self.payment_store.as_ref(), &record).await;
+
+        let mut progress = PaymentProgress::default();
+        let result = self
+            .proxy_stream(socket, backend_endpoint, request, correlation_id, &mut progress)
+            .await;
+        let outcome = progress.final_outcome(result.as_ref().err());
+        complete_payment(self.payment_store.as_ref(), &record, &outcome).await;
+        result
+    }
+
+    async fn proxy_stream(
+        &self,
+        socket: &mut WebSocket,
+        backend_endpoint: &str,
+        request: &SendPaymentStreamRequest,
+        correlation_id: Option<&str>,
+        progress: &mut PaymentProgress,
+    ) -> Result<(), AppError> {
+        info!("Opening send-payment stream for asset ID: {}", request.asset_id);
+        let url = format!("{}{}", self.base_url, backend_endpoint);
+        let mut response = self
+            .client
+            .post(&url)
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .json(&request.upstream_body()?)
+            .send_upstream()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AppError::from_response(response).await);
+        }
+
+        let mut buffer = String::new();
+        loop {
+            tokio::select! {
+                chunk = response.chunk() => {
+                    let Some(chunk) = chunk? else {
+                        info!("Send-payment stream ended");
+                        return Ok(());
+                    };
+                    buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                    for message in drain_stream_messages(&mut buffer) {
+                        let update = parse_stream_update(message)?;
+                        progress.apply(&update);
+                        let frame = serde_json::to_value(&update)?;
+                        let frame = match correlation_id {
+                            Some(id) => with_correlation_id(&frame, id),
+                            None => frame,
+                        };
+                        if let Err(e) = socket.send(Message::Text(frame.to_string())).await {
+                            info!("Failed to send WebSocket message: {}", e);
+                            return Ok(());
+                        }
                     }
                 }
-                Ok(Message::Close(_)) => {
-                    info!("WebSocket connection closed");
-                    break;
-                }
-                Err(e) => {
-                    info!("WebSocket error: {}", e);
-                    break;
+                msg = socket.recv() => match msg {
+                    // Dropping the upstream response cancels the stream
+                    Some(Ok(Message::Close(_))) | None => {
+                        info!("WebSocket connection closed");
+                        return Ok(());
+                    }
+                    Some(Err(e)) => {
+                        info!("WebSocket error: {}", e);
+                        return Ok(());
+                    }
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+}
+
+/// Converts one streamed send-payment message into the update forwarded to the client
+fn parse_stream_update(
+    message: Result<serde_json::Value, serde_json::Value>,
+) -> Result<SendPaymentStreamResponse, AppError> {
+    match message {
+        Ok(update) => serde_json::from_value(update)
+            .map_err(|e| AppError::RequestError(format!("Unexpected send-payment update: {e}"))),
+        Err(error) => {
+            let reason = error
+                .get("message")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            Err(AppError::RequestError(format!("Send-payment failed: {reason}")))
+        }
+    }
+}
+
+/// Tracks a send-payment stream until tapd reports the final payment result
+#[derive(Debug, Default)]
+struct PaymentProgress {
+    rfq_id: Option<String>,
+    outcome: Option<PaymentOutcome>,
+}
+
+impl PaymentProgress {
+    fn apply(&mut self, update: &SendPaymentStreamResponse) {
+        let quote_id = update
+            .accepted_sell_order
+            .as_ref()
+            .and_then(|order| order.get("id"))
+            .and_then(serde_json::Value::as_str);
+        if let Some(id) = quote_id {
+            self.rfq_id = Some(id.to_string());
+        }
+
+        let Some(result) = &update.payment_result else {
+            return;
+        };
+        let field = |name: &str| {
+            result
+                .get(name)
+                .and_then(serde_json::Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let succeeded = match field("status").as_deref() {
+            Some("SUCCEEDED") => true,
+            Some("FAILED") => false,
+            _ => return,
+        };
+
+        self.outcome = Some(PaymentOutcome {
+            succeeded,
+            payment_hash: field("payment_hash"),
+            rfq_id: self.rfq_id.clone(),
+            value_msat: field("value_msat").and_then(|value| value.parse().ok()).unwrap_or(0),
+            fee_msat: field("fee_msat").and_then(|fee| fee.parse().ok()).unwrap_or(0),
+            failure_reason: field("failure_reason").filter(|r| r != "FAILURE_REASON_NONE"),
+        });
+    }
+
+    /// The outcome to store once the stream is over. Dropping the stream early
+    /// cancels it, so a payment without a final result is not left in flight.
+    fn final_outcome(self, error: Option<&AppError>) -> PaymentOutcome {
+        match (error, self.outcome) {
+            (Some(e), _) => PaymentOutcome::failed(e.to_string()),
+            (None, Some(outcome)) => outcome,
+            (None, None) => PaymentOutcome {
+                rfq_id: self.rfq_id,
+                ..PaymentOutcome::failed(
+                    "Stream closed before tapd reported a final status".to_string(),
+                )
+            },
+        }
+    }
+}
+
+/// Extracts the BOLT 11 invoice from an LND SendPaymentRequest body
+fn payment_request_string(payment_request: &serde_json::Value) -> Option<String> {
+    payment_request
+        .get("payment_request")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+}
+
+/// Payment history is best effort: a storage failure is logged rather than
+/// failing a payment that has already been handed to tapd or LND
+async fn record_payment(store: &dyn PaymentStore, record: &PaymentRecord) {
+    if let Err(e) = store.record(record).await {
+        warn!("Failed to record payment {}: {}", record.id, e);
+    }
+}
+
+async fn complete_payment(
+    store: &dyn PaymentStore,
+    record: &PaymentRecord,
+    outcome: &PaymentOutcome,
+) {
+    match store.complete(record.id, outcome).await {
+        Ok(true) => {}
+        Ok(false) => warn!("Payment {} missing from history", record.id),
+        Err(e) => warn!("Failed to store result of payment {}: {}", record.id, e),
+    }
+}
+
+/// Default page size for the payment history
+pub const PAYMENT_HISTORY_DEFAULT_LIMIT: usize = 100;
+/// Largest page of payment history returned at once
+pub const PAYMENT_HISTORY_MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PaymentHistoryParams {
+    pub status: Option<PaymentStatus>,
+    pub asset_id: Option<String>,
+    /// Inclusive lower bound on `created_at`, in unix seconds
+    pub from: Option<i64>,
+    /// Inclusive upper bound on `created_at`, in unix seconds
+    pub to: Option<i64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InvoiceListParams {
+    pub limit: Option<usize>,
+}
+
+/// The stored RFQ order whose quote a payment used
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkedQuote {
+    pub order_id: uuid::Uuid,
+    pub kind: RfqOrderKind,
+    pub peer: Option<String>,
+    /// Quoted price in asset units per BTC
+    pub units_per_btc: Option<f64>,
+}
+
+/// What a settled payment actually cost, fees included
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveRate {
+    /// Asset units spent; derived from the quote when the invoice set the amount
+    pub asset_units: f64,
+    pub msat_spent: u64,
+    pub msat_per_unit: f64,
+    pub units_per_btc: f64,
+}
+
+const MSAT_PER_BTC: f64 = 100_000_000_000.0;
+
+impl EffectiveRate {
+    pub fn new(payment: &PaymentRecord, quoted_units_per_btc: Option<f64>) -> Option<Self> {
+        if payment.status != PaymentStatus::Succeeded || payment.value_msat == 0 {
+            return None;
+        }
+        let msat_spent = payment.value_msat + payment.fee_msat;
+        let asset_units = if payment.asset_amount > 0 {
+            payment.asset_amount as f64
+        } else {
+            quoted_units_per_btc? * payment.value_msat as f64 / MSAT_PER_BTC
+        };
+        (asset_units > 0.0).then(|| Self {
+            asset_units,
+            msat_spent,
+            msat_per_unit: msat_spent as f64 / asset_units,
+            units_per_btc: asset_units * MSAT_PER_BTC / msat_spent as f64,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentHistoryEntry {
+    #[serde(flatten)]
+    pub payment: PaymentRecord,
+    pub quote: Option<LinkedQuote>,
+    pub effective_rate: Option<EffectiveRate>,
+}
+
+impl PaymentHistoryEntry {
+    fn new(payment: PaymentRecord, order: Option<&RfqOrderRecord>) -> Self {
+        let quote = order.map(|order| LinkedQuote {
+            order_id: order.id,
+            kind: order.kind,
+            peer: order.peer.clone(),
+            units_per_btc: accepted_price(order),
+        });
+        let quoted_rate = quote.as_ref().and_then(|quote| quote.units_per_btc);
+        Self {
+            effective_rate: EffectiveRate::new(&payment, quoted_rate),
+            payment,
+            quote,
+        }
+    }
+}
+
+/// Looks up the stored orders behind the quotes a page of payments used;
+/// payments on quotes negotiated inside tapd's own send flow have none
+async fn linked_orders(
+    orders: &dyn RfqOrderStore,
+    payments: &[PaymentRecord],
+) -> HashMap<String, RfqOrderRecord> {
+    let mut linked = HashMap::new();
+    for rfq_id in payments.iter().filter_map(|p| p.rfq_id.as_ref()) {
+        if linked.contains_key(rfq_id) {
+            continue;
+        }
+        let query = RfqOrderQuery {
+            rfq_id: Some(rfq_id.clone()),
+            limit: 1,
+            ..Default::default()
+        };
+        match orders.list(&query).await {
+            Ok(found) => {
+                if let Some(order) = found.into_iter().next() {
+                    linked.insert(rfq_id.clone(), order);
                 }
-                _ => {}
             }
+            Err(e) => warn!("Failed to look up RFQ order for quote {}: {}", rfq_id, e),
+        }
+    }
+    linked
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentHistoryResponse {
+    pub payments: Vec<PaymentHistoryEntry>,
+    /// Offset of the next page, absent on the last page
+    pub next_offset: Option<usize>,
+}
+
+/// Returns one page of the outgoing payment history, newest first, with the
+/// RFQ quote each payment used
+pub async fn payment_history(
+    store: &dyn PaymentStore,
+    orders: &dyn RfqOrderStore,
+    params: PaymentHistoryParams,
+) -> Result<PaymentHistoryResponse, AppError> {
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err(AppError::InvalidInput("from must not be after to".to_string()));
+        }
+    }
+    let limit = params
+        .limit
+        .unwrap_or(PAYMENT_HISTORY_DEFAULT_LIMIT)
+        .clamp(1, PAYMENT_HISTORY_MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+
+    // One extra row tells whether another page follows
+    let mut payments = store
+        .list(&PaymentQuery {
+            status: params.status,
+            asset_id: params.asset_id,
+            from: params.from,
+            to: params.to,
+            limit: limit + 1,
+            offset,
+        })
+        .await?;
+    let next_offset = (payments.len() > limit).then_some(offset + limit);
+    payments.truncate(limit);
+
+    let linked = linked_orders(orders, &payments).await;
+    let payments = payments
+        .into_iter()
+        .map(|payment| {
+            let order = payment.rfq_id.as_ref().and_then(|id| linked.get(id));
+            PaymentHistoryEntry::new(payment, order)
+        })
+        .collect();
+
+    Ok(PaymentHistoryResponse {
+        payments,
+        next_offset,
+    })
+}
+
+/// Serde adapter for uint64 amounts: tapd's REST gateway encodes them as JSON
+/// strings, while clients may send either strings or numbers
+mod amount {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(u64),
+        Text(String),
+    }
+
+    fn parse<E: Error>(raw: Raw) -> Result<u64, E> {
+        match raw {
+            Raw::Number(value) => Ok(value),
+            Raw::Text(text) => text
+                .trim()
+                .parse()
+                .map_err(|_| E::custom(format!("invalid amount: {text:?}"))),
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        parse(Raw::deserialize(deserializer)?)
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<u64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => serializer.collect_str(value),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<u64>, D::Error> {
+            Option::<super::Raw>::deserialize(deserializer)?
+                .map(super::parse)
+                .transpose()
+        }
+    }
+}
+
+const ASSET_ID_BYTES: usize = 32;
+const PUBKEY_BYTES: usize = 33;
+const RFQ_ID_BYTES: usize = 32;
+
+/// One rejected request field, reported back in the 400 response
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
         }
     }
 }
 
+/// Channel request bodies checked field by field before anything is sent upstream
+trait ValidatedRequest {
+    /// Records every invalid field and rewrites hex byte fields to the base64
+    /// encoding tapd's REST gateway expects
+    fn normalize(&mut self, errors: &mut Vec<FieldError>);
+}
+
+fn check_fields<T: ValidatedRequest>(request: &mut T) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    request.normalize(&mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn describe_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{}: {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Decodes a fixed-length byte field given as hex or base64, returning base64
+fn normalize_bytes(value: &str, len: usize) -> Option<String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let hex = (value.len() == len * 2).then(|| hex::decode(value).ok()).flatten();
+    let bytes = hex
+        .or_else(|| engine.decode(value).ok())
+        .or_else(|| base64::engine::general_purpose::URL_SAFE.decode(value).ok())?;
+    (bytes.len() == len).then(|| engine.encode(bytes))
+}
+
+fn check_bytes(errors: &mut Vec<FieldError>, field: &'static str, value: &mut String, len: usize) {
+    match normalize_bytes(value, len) {
+        Some(encoded) => *value = encoded,
+        None => {
+            errors.push(FieldError::new(field, format!("must be {len} bytes of hex or base64")))
+        }
+    }
+}
+
+/// Like [`check_bytes`], but an empty value means the field was left unset
+fn check_optional_bytes(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: &mut String,
+    len: usize,
+) {
+    if !value.is_empty() {
+        check_bytes(errors, field, value, len);
+    }
+}
+
+/// tapd identifies the asset by ID or, for grouped assets, by group key
+fn check_asset(
+    errors: &mut Vec<FieldError>,
+    asset_id: &mut String,
+    group_key: &mut Option<String>,
+) {
+    let group_key = group_key.as_mut().filter(|key| !key.is_empty());
+    if asset_id.is_empty() && group_key.is_none() {
+        errors.push(FieldError::new("asset_id", "required unless group_key is set"));
+    }
+    check_optional_bytes(errors, "asset_id", asset_id, ASSET_ID_BYTES);
+    if let Some(group_key) = group_key {
+        check_bytes(errors, "group_key", group_key, PUBKEY_BYTES);
+    }
+}
+
+fn check_object(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: Option<&serde_json::Value>,
+) {
+    if value.is_some_and(|value| !value.is_object()) {
+        errors.push(FieldError::new(field, "must be a JSON object"));
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EncodeCustomDataRequest {
     pub router_send_payment: serde_json::Value,
@@ -79,22 +648,58 @@ pub struct EncodeCustomDataRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FundChannelRequest {
-    pub asset_amount: String,
+    #[serde(with = "amount")]
+    pub asset_amount: u64,
     pub asset_id: String,
     pub peer_pubkey: String,
     pub fee_rate_sat_per_vbyte: u32,
-    pub push_sat: Option<String>,
+    #[serde(default, with = "amount::option")]
+    pub push_sat: Option<u64>,
     pub group_key: Option<String>,
 }
 
+impl ValidatedRequest for FundChannelRequest {
+    fn normalize(&mut self, errors: &mut Vec<FieldError>) {
+        if self.asset_amount == 0 {
+            errors.push(FieldError::new("asset_amount", "must be greater than 0"));
+        }
+        check_asset(errors, &mut self.asset_id, &mut self.group_key);
+        check_bytes(errors, "peer_pubkey", &mut self.peer_pubkey, PUBKEY_BYTES);
+        if self.fee_rate_sat_per_vbyte == 0 {
+            errors.push(FieldError::new("fee_rate_sat_per_vbyte", "must be greater than 0"));
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InvoiceRequest {
     pub asset_id: String,
-    pub asset_amount: String,
+    #[serde(with = "amount")]
+    pub asset_amount: u64,
+    /// Optional; tapd picks the channel peer when empty
+    #[serde(default)]
     pub peer_pubkey: String,
     pub invoice_request: Option<serde_json::Value>,
     pub hodl_invoice: Option<serde_json::Value>,
     pub group_key: Option<String>,
+    /// Kept with the transaction record only; tapd never sees it
+    #[serde(default, skip_serializing)]
+    pub label: Option<String>,
+}
+
+impl ValidatedRequest for InvoiceRequest {
+    fn normalize(&mut self, errors: &mut Vec<FieldError>) {
+        if self.asset_amount == 0 {
+            errors.push(FieldError::new("asset_amount", "must be greater than 0"));
+        }
+        if let Some(message) = crate::types::check_label(self.label.as_deref()) {
+            errors.push(FieldError::new("label", message));
+        }
+        check_asset(errors, &mut self.asset_id, &mut self.group_key);
+        check_optional_bytes(errors, "peer_pubkey", &mut self.peer_pubkey, PUBKEY_BYTES);
+        check_object(errors, "invoice_request", self.invoice_request.as_ref());
+        check_object(errors, "hodl_invoice", self.hodl_invoice.as_ref());
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -104,26 +709,365 @@ pub struct DecodeInvoiceRequest {
     pub group_key: Option<String>,
 }
 
+impl ValidatedRequest for DecodeInvoiceRequest {
+    fn normalize(&mut self, errors: &mut Vec<FieldError>) {
+        check_asset(errors, &mut self.asset_id, &mut self.group_key);
+        if self.pay_req_string.trim().is_empty() {
+            errors.push(FieldError::new("pay_req_string", "must not be empty"));
+        }
+    }
+}
+
+/// Pays a Lightning Address (`user@domain`) in the given asset
+#[derive(Debug, Deserialize)]
+pub struct PayAddressRequest {
+    pub address: String,
+    /// Amount requested from the LNURL service, in millisatoshis
+    pub amount_msat: u64,
+    pub asset_id: String,
+    pub peer_pubkey: Option<String>,
+    pub group_key: Option<String>,
+    pub comment: Option<String>,
+    pub fee_limit_sat: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayAddressResponse {
+    pub address: String,
+    pub invoice: String,
+    /// LUD-09 action the wallet should show once paid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_action: Option<serde_json::Value>,
+    pub payment: SendPaymentStreamResponse,
+}
+
+/// TLV type LND reads the keysend preimage from
+const KEYSEND_PREIMAGE_RECORD: u64 = 5482373484;
+/// TLV type wallets conventionally use for a keysend text message
+const KEYSEND_MESSAGE_RECORD: u64 = 34349334;
+/// Satoshis carried by the HTLC of an asset payment, matching tapd's default
+const ASSET_HTLC_CARRIER_SAT: u64 = 354;
+const DEFAULT_KEYSEND_TIMEOUT_SECONDS: u32 = 60;
+/// Largest keysend message accepted, well under the onion payload limit
+const MAX_KEYSEND_MESSAGE_BYTES: usize = 512;
+
+/// Sends `asset_amount` units to a channel peer without an invoice
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysendRequest {
+    /// Hex-encoded asset ID
+    pub asset_id: String,
+    pub asset_amount: u64,
+    /// Hex-encoded compressed public key of the receiving peer
+    pub peer_pubkey: String,
+    /// Optional UTF-8 message delivered in the keysend message record
+    pub message: Option<String>,
+    pub fee_limit_sat: Option<u64>,
+    pub timeout_seconds: Option<u32>,
+}
+
+impl KeysendRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        if hex::decode(&self.asset_id).map(|id| id.len()) != Ok(32) {
+            return Err(AppError::InvalidInput("asset_id must be 32 bytes of hex".to_string()));
+        }
+        if hex::decode(&self.peer_pubkey).map(|key| key.len()) != Ok(33) {
+            return Err(AppError::InvalidInput(
+                "peer_pubkey must be a 33-byte hex public key".to_string(),
+            ));
+        }
+        if self.asset_amount == 0 {
+            return Err(AppError::InvalidInput("asset_amount must be greater than 0".to_string()));
+        }
+        if self.message.as_ref().is_some_and(|m| m.len() > MAX_KEYSEND_MESSAGE_BYTES) {
+            return Err(AppError::InvalidInput(format!(
+                "message exceeds {MAX_KEYSEND_MESSAGE_BYTES} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Builds LND's SendPaymentV2 request for a keysend carrying the asset
+    /// custom records returned by encode-custom-data
+    fn lnd_payment(
+        &self,
+        preimage: &[u8; 32],
+        first_hop_custom_records: serde_json::Value,
+    ) -> serde_json::Value {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let peer_pubkey = hex::decode(&self.peer_pubkey).unwrap_or_default();
+
+        let mut records = serde_json::Map::new();
+        records.insert(KEYSEND_PREIMAGE_RECORD.to_string(), base64.encode(preimage).into());
+        if let Some(message) = &self.message {
+            records.insert(KEYSEND_MESSAGE_RECORD.to_string(), base64.encode(message).into());
+        }
+
+        serde_json::json!({
+            "dest": base64.encode(peer_pubkey),
+            "amt": ASSET_HTLC_CARRIER_SAT.to_string(),
+            "payment_hash": base64.encode(Sha256::digest(preimage)),
+            "dest_custom_records": records,
+            "first_hop_custom_records": first_hop_custom_records,
+            "timeout_seconds": self.timeout_seconds.unwrap_or(DEFAULT_KEYSEND_TIMEOUT_SECONDS),
+            "fee_limit_sat": self.fee_limit_sat.unwrap_or_default().to_string(),
+            "no_inflight_updates": true,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysendResponse {
+    pub payment_hash: String,
+    pub preimage: String,
+    /// SUCCEEDED or FAILED
+    pub status: String,
+    pub fee_msat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// Settles an accepted hold invoice by revealing its hex-encoded preimage
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettleInvoiceRequest {
+    pub preimage: String,
+}
+
+/// Cancels a hold invoice by its hex-encoded payment hash, failing any held HTLCs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelInvoiceRequest {
+    pub payment_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InvoiceLookupQuery {
+    pub payment_hash: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+pub struct HodlInvoiceResponse {
+    pub payment_hash: String,
+    pub state: String,
+}
+
+/// LND's Invoice message; hashes are base64 and 64-bit values are strings
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndInvoice {
+    memo: String,
+    r_hash: String,
+    value_msat: String,
+    amt_paid_msat: String,
+    state: String,
+    creation_date: String,
+    settle_date: String,
+    expiry: String,
+    payment_request: String,
+}
+
+/// The parts of an invoice a hold-invoice flow needs to decide whether to settle
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceLookupResponse {
+    pub payment_hash: String,
+    /// OPEN, ACCEPTED (HTLCs held, ready to settle), SETTLED or CANCELED
+    pub state: String,
+    pub memo: String,
+    pub value_msat: u64,
+    pub amt_paid_msat: u64,
+    pub creation_date: i64,
+    pub settle_date: i64,
+    pub expiry: i64,
+    pub payment_request: String,
+}
+
+impl From<LndInvoice> for InvoiceLookupResponse {
+    fn from(invoice: LndInvoice) -> Self {
+        let payment_hash = base64::engine::general_purpose::STANDARD
+            .decode(&invoice.r_hash)
+            .map(hex::encode)
+            .unwrap_or(invoice.r_hash);
+        Self {
+            payment_hash,
+            state: invoice.state,
+            memo: invoice.memo,
+            value_msat: invoice.value_msat.parse().unwrap_or(0),
+            amt_paid_msat: invoice.amt_paid_msat.parse().unwrap_or(0),
+            creation_date: invoice.creation_date.parse().unwrap_or(0),
+            settle_date: invoice.settle_date.parse().unwrap_or(0),
+            expiry: invoice.expiry.parse().unwrap_or(0),
+            payment_request: invoice.payment_request,
+        }
+    }
+}
+
+/// Decodes a hex-encoded 32-byte preimage or payment hash
+fn decode_hash32(field: &str, value: &str) -> Result<Vec<u8>, AppError> {
+    hex::decode(value)
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| AppError::InvalidInput(format!("{field} must be 32 bytes of hex")))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendPaymentRequest {
     pub asset_id: String,
-    pub asset_amount: String,
+    /// May be 0 when the invoice carries the amount
+    #[serde(with = "amount")]
+    pub asset_amount: u64,
+    #[serde(default)]
     pub peer_pubkey: String,
     pub payment_request: Option<serde_json::Value>,
     pub rfq_id: Option<String>,
     pub allow_overpay: bool,
     pub group_key: Option<String>,
+    #[serde(flatten)]
+    pub tuning: PaymentTuning,
+}
+
+/// Retry and multi-part options for LND's router, accepted next to the
+/// payment and merged into its `payment_request` before it goes to tapd
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentTuning {
+    /// Most HTLCs the payment may be split into; 1 disables multi-part payments
+    #[serde(default, skip_serializing)]
+    pub max_parts: Option<u32>,
+    /// Seconds LND keeps retrying routes before giving up
+    #[serde(default, skip_serializing)]
+    pub timeout_seconds: Option<u32>,
+    #[serde(default, skip_serializing)]
+    pub fee_limit_sat: Option<u64>,
+    /// Short channel IDs the first hop is restricted to
+    #[serde(default, skip_serializing)]
+    pub outgoing_chan_ids: Vec<u64>,
 }
 
+/// Upper bound on `max_parts`, well above what a single payment needs
+const MAX_PAYMENT_PARTS: u32 = 64;
+/// Upper bound on `timeout_seconds`
+const MAX_PAYMENT_TIMEOUT_SECONDS: u32 = 3600;
+/// Upper bound on the number of `outgoing_chan_ids`
+const MAX_OUTGOING_CHANNELS: usize = 32;
+
+impl PaymentTuning {
+    fn is_empty(&self) -> bool {
+        self.max_parts.is_none()
+            && self.timeout_seconds.is_none()
+            && self.fee_limit_sat.is_none()
+            && self.outgoing_chan_ids.is_empty()
+    }
+
+    fn check(&self, errors: &mut Vec<FieldError>, has_payment_request: bool) {
+        if self.max_parts.is_some_and(|parts| !(1..=MAX_PAYMENT_PARTS).contains(&parts)) {
+            errors.push(FieldError::new(
+                "max_parts",
+                format!("must be between 1 and {MAX_PAYMENT_PARTS}"),
+            ));
+        }
+        if self
+            .timeout_seconds
+            .is_some_and(|timeout| !(1..=MAX_PAYMENT_TIMEOUT_SECONDS).contains(&timeout))
+        {
+            errors.push(FieldError::new(
+                "timeout_seconds",
+                format!("must be between 1 and {MAX_PAYMENT_TIMEOUT_SECONDS}"),
+            ));
+        }
+        if self.outgoing_chan_ids.len() > MAX_OUTGOING_CHANNELS {
+            errors.push(FieldError::new(
+                "outgoing_chan_ids",
+                format!("at most {MAX_OUTGOING_CHANNELS} channels are allowed"),
+            ));
+        }
+        if self.outgoing_chan_ids.contains(&0) {
+            errors.push(FieldError::new("outgoing_chan_ids", "must be non-zero"));
+        }
+        if !self.is_empty() && !has_payment_request {
+            errors.push(FieldError::new("payment_request", "required for payment tuning"));
+        }
+    }
+
+    /// Merges the options into the request `body` bound for tapd, inside LND's
+    /// SendPaymentRequest where the router reads them
+    fn apply(&self, body: &mut serde_json::Value) {
+        let Some(payment_request) = body
+            .get_mut("payment_request")
+            .and_then(serde_json::Value::as_object_mut)
+        else {
+            return;
+        };
+
+        if let Some(max_parts) = self.max_parts {
+            payment_request.insert("max_parts".to_string(), max_parts.into());
+        }
+        if let Some(timeout_seconds) = self.timeout_seconds {
+            payment_request.insert("timeout_seconds".to_string(), timeout_seconds.into());
+        }
+        if let Some(fee_limit_sat) = self.fee_limit_sat {
+            // LND rejects requests carrying both fee limit units
+            payment_request.remove("fee_limit_msat");
+            payment_request.insert("fee_limit_sat".to_string(), fee_limit_sat.to_string().into());
+        }
+        if !self.outgoing_chan_ids.is_empty() {
+            let ids: Vec<String> = self.outgoing_chan_ids.iter().map(u64::to_string).collect();
+            payment_request.insert("outgoing_chan_ids".to_string(), ids.into());
+        }
+    }
+}
+
+impl SendPaymentRequest {
+    /// Body forwarded to tapd, with the payment tuning merged in
+    fn upstream_body(&self) -> Result<serde_json::Value, AppError> {
+        let mut body = serde_json::to_value(self)?;
+        self.tuning.apply(&mut body);
+        Ok(body)
+    }
+}
+
+impl ValidatedRequest for SendPaymentRequest {
+    fn normalize(&mut self, errors: &mut Vec<FieldError>) {
+        check_asset(errors, &mut self.asset_id, &mut self.group_key);
+        check_optional_bytes(errors, "peer_pubkey", &mut self.peer_pubkey, PUBKEY_BYTES);
+        if let Some(rfq_id) = &mut self.rfq_id {
+            check_optional_bytes(errors, "rfq_id", rfq_id, RFQ_ID_BYTES);
+        }
+        check_object(errors, "payment_request", self.payment_request.as_ref());
+        self.tuning.check(errors, self.payment_request.is_some());
+    }
+}
+
+/// Byte fields are accepted as hex or base64 and forwarded as base64
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SendPaymentStreamRequest {
-    pub asset_id: String, // base64 encoded bytes
-    pub asset_amount: String,
-    pub peer_pubkey: String, // base64 encoded bytes
+    pub asset_id: String,
+    #[serde(with = "amount")]
+    pub asset_amount: u64,
+    #[serde(default)]
+    pub peer_pubkey: String,
     pub payment_request: serde_json::Value,
-    pub rfq_id: String, // base64 encoded bytes
+    #[serde(default)]
+    pub rfq_id: String,
     pub allow_overpay: bool,
-    pub group_key: Option<String>, // base64 encoded bytes
+    pub group_key: Option<String>,
+    #[serde(flatten)]
+    pub tuning: PaymentTuning,
+}
+
+impl SendPaymentStreamRequest {
+    /// Body forwarded to tapd, with the payment tuning merged in
+    fn upstream_body(&self) -> Result<serde_json::Value, AppError> {
+        let mut body = serde_json::to_value(self)?;
+        self.tuning.apply(&mut body);
+        Ok(body)
+    }
+}
+
+impl ValidatedRequest for SendPaymentStreamRequest {
+    fn normalize(&mut self, errors: &mut Vec<FieldError>) {
+        check_asset(errors, &mut self.asset_id, &mut self.group_key);
+        check_optional_bytes(errors, "peer_pubkey", &mut self.peer_pubkey, PUBKEY_BYTES);
+        check_optional_bytes(errors, "rfq_id", &mut self.rfq_id, RFQ_ID_BYTES);
+        check_object(errors, "payment_request", Some(&self.payment_request));
+        self.tuning.check(errors, true);
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -134,10 +1078,340 @@ pub struct SendPaymentStreamResponse {
     pub payment_result: Option<serde_json::Value>, // Payment
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct QueryParams {
-    pub method: Option<String>,
-    pub stream: Option<String>,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryParams {
+    pub method: Option<String>,
+    pub stream: Option<String>,
+}
+
+/// A channel from LND's listchannels; 64-bit values arrive as strings
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LndChannel {
+    pub active: bool,
+    pub remote_pubkey: String,
+    pub channel_point: String,
+    pub chan_id: String,
+    pub capacity: String,
+    pub local_balance: String,
+    pub remote_balance: String,
+    /// Base64-encoded JSON written by tapd for taproot asset channels
+    pub custom_channel_data: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndChannels {
+    channels: Vec<LndChannel>,
+}
+
+/// tapd's JSON view of an asset channel, as carried in `custom_channel_data`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssetChannelData {
+    pub funding_assets: Vec<FundingAsset>,
+    pub local_assets: Vec<AssetTranche>,
+    pub remote_assets: Vec<AssetTranche>,
+    pub capacity: u64,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FundingAsset {
+    pub asset_genesis: FundingAssetGenesis,
+    pub amount: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FundingAssetGenesis {
+    pub asset_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AssetTranche {
+    pub asset_id: String,
+    pub amount: u64,
+}
+
+/// One asset's share of a channel, in asset units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelAssetBalance {
+    pub asset_id: String,
+    pub name: String,
+    pub capacity: u64,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+}
+
+/// A taproot asset channel with its satoshi and per-asset balances
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetChannel {
+    pub chan_id: String,
+    pub channel_point: String,
+    pub remote_pubkey: String,
+    pub active: bool,
+    pub capacity_sat: u64,
+    pub local_balance_sat: u64,
+    pub remote_balance_sat: u64,
+    pub assets: Vec<ChannelAssetBalance>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetChannelsResponse {
+    pub channels: Vec<AssetChannel>,
+}
+
+/// One asset's balances summed across every channel holding it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetBalanceSummary {
+    pub asset_id: String,
+    pub name: String,
+    pub channel_count: usize,
+    pub capacity: u64,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelBalanceResponse {
+    /// Per-asset totals, ordered by asset ID
+    pub assets: Vec<AssetBalanceSummary>,
+    pub local_balance_sat: u64,
+    pub remote_balance_sat: u64,
+}
+
+/// Aggregates per-asset balances over all asset channels
+pub fn summarize_channel_balances(channels: &[AssetChannel]) -> ChannelBalanceResponse {
+    let mut totals: std::collections::BTreeMap<&str, AssetBalanceSummary> = Default::default();
+    for asset in channels.iter().flat_map(|c| &c.assets) {
+        let summary = totals
+            .entry(&asset.asset_id)
+            .or_insert_with(|| AssetBalanceSummary {
+                asset_id: asset.asset_id.clone(),
+                name: asset.name.clone(),
+                channel_count: 0,
+                capacity: 0,
+                local_balance: 0,
+                remote_balance: 0,
+            });
+        summary.channel_count += 1;
+        summary.capacity += asset.capacity;
+        summary.local_balance += asset.local_balance;
+        summary.remote_balance += asset.remote_balance;
+    }
+
+    ChannelBalanceResponse {
+        assets: totals.into_values().collect(),
+        local_balance_sat: channels.iter().map(|c| c.local_balance_sat).sum(),
+        remote_balance_sat: channels.iter().map(|c| c.remote_balance_sat).sum(),
+    }
+}
+
+/// Closes a channel identified by its funding outpoint (`txid:index`).
+/// Cooperative closes may pick a fee rate or a confirmation target; force
+/// closes use the commitment transaction's pre-agreed fee
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseChannelRequest {
+    pub channel_point: String,
+    #[serde(default)]
+    pub force: bool,
+    pub sat_per_vbyte: Option<u64>,
+    pub target_conf: Option<u32>,
+    pub delivery_address: Option<String>,
+}
+
+impl CloseChannelRequest {
+    /// Validates the request and builds LND's CloseChannel REST path
+    pub fn lnd_path(&self) -> Result<String, AppError> {
+        let (txid, index) = self
+            .channel_point
+            .split_once(':')
+            .filter(|(txid, _)| txid.len() == 64 && txid.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| {
+                AppError::InvalidInput("channel_point must be formatted as txid:index".to_string())
+            })?;
+        let index: u32 = index
+            .parse()
+            .map_err(|_| AppError::InvalidInput(format!("Invalid output index: {index}")))?;
+
+        if self.force && (self.sat_per_vbyte.is_some() || self.target_conf.is_some()) {
+            return Err(AppError::InvalidInput(
+                "Fee selection is not supported for force closes".to_string(),
+            ));
+        }
+        if self.sat_per_vbyte.is_some() && self.target_conf.is_some() {
+            return Err(AppError::InvalidInput(
+                "Set either sat_per_vbyte or target_conf, not both".to_string(),
+            ));
+        }
+
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if self.force {
+            query.append_pair("force", "true");
+        }
+        if let Some(rate) = self.sat_per_vbyte {
+            query.append_pair("sat_per_vbyte", &rate.to_string());
+        }
+        if let Some(target) = self.target_conf {
+            query.append_pair("target_conf", &target.to_string());
+        }
+        if let Some(address) = &self.delivery_address {
+            query.append_pair("delivery_address", address);
+        }
+        let query = query.finish();
+
+        let path = format!("/v1/channels/{txid}/{index}");
+        Ok(if query.is_empty() { path } else { format!("{path}?{query}") })
+    }
+}
+
+/// Progress of a channel close as reported to the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CloseChannelStatus {
+    /// The closing transaction was broadcast
+    Pending {
+        closing_txid: String,
+        output_index: u32,
+    },
+    /// The closing transaction confirmed
+    Closed {
+        closing_txid: String,
+        success: bool,
+    },
+}
+
+/// LND's CloseStatusUpdate; hashes are base64 in internal byte order
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndCloseStatusUpdate {
+    close_pending: Option<LndPendingUpdate>,
+    chan_close: Option<LndChannelCloseUpdate>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndPendingUpdate {
+    txid: String,
+    output_index: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndChannelCloseUpdate {
+    closing_txid: String,
+    success: bool,
+}
+
+/// Converts one streamed close update; updates carrying neither stage are skipped
+fn parse_close_status(
+    message: Result<serde_json::Value, serde_json::Value>,
+) -> Result<Option<CloseChannelStatus>, AppError> {
+    let update: LndCloseStatusUpdate = match message {
+        Ok(update) => serde_json::from_value(update)?,
+        Err(error) => {
+            let reason = error
+                .get("message")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            return Err(AppError::RequestError(format!("Channel close failed: {reason}")));
+        }
+    };
+
+    let txid = |encoded: &str| display_txid(encoded).unwrap_or_else(|| encoded.to_string());
+    Ok(match (update.close_pending, update.chan_close) {
+        (_, Some(closed)) => Some(CloseChannelStatus::Closed {
+            closing_txid: txid(&closed.closing_txid),
+            success: closed.success,
+        }),
+        (Some(pending), None) => Some(CloseChannelStatus::Pending {
+            closing_txid: txid(&pending.txid),
+            output_index: pending.output_index,
+        }),
+        (None, None) => None,
+    })
+}
+
+/// Reads the close stream until the next status update; `None` once LND ends it
+async fn next_close_status(
+    response: &mut reqwest::Response,
+    buffer: &mut String,
+    pending: &mut std::collections::VecDeque<CloseChannelStatus>,
+) -> Result<Option<CloseChannelStatus>, AppError> {
+    loop {
+        if let Some(status) = pending.pop_front() {
+            return Ok(Some(status));
+        }
+        let Some(chunk) = response.chunk().await? else {
+            return Ok(None);
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        for message in drain_stream_messages(buffer) {
+            if let Some(status) = parse_close_status(message)? {
+                pending.push_back(status);
+            }
+        }
+    }
+}
+
+/// Decodes a channel's custom data; `None` for plain BTC channels
+pub fn decode_asset_channel_data(custom_channel_data: &str) -> Option<AssetChannelData> {
+    if custom_channel_data.is_empty() {
+        return None;
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(custom_channel_data)
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Keeps the taproot asset channels and splits their balances per asset
+pub fn asset_channels(channels: Vec<LndChannel>) -> Vec<AssetChannel> {
+    channels
+        .into_iter()
+        .filter_map(|channel| {
+            let data = decode_asset_channel_data(&channel.custom_channel_data)?;
+            let sum = |tranches: &[AssetTranche], asset_id: &str| -> u64 {
+                tranches
+                    .iter()
+                    .filter(|t| t.asset_id == asset_id)
+                    .map(|t| t.amount)
+                    .sum()
+            };
+
+            let assets = data
+                .funding_assets
+                .iter()
+                .map(|funding| {
+                    let asset_id = &funding.asset_genesis.asset_id;
+                    ChannelAssetBalance {
+                        asset_id: asset_id.clone(),
+                        name: funding.asset_genesis.name.clone(),
+                        capacity: funding.amount,
+                        local_balance: sum(&data.local_assets, asset_id),
+                        remote_balance: sum(&data.remote_assets, asset_id),
+                    }
+                })
+                .collect();
+
+            Some(AssetChannel {
+                chan_id: channel.chan_id,
+                channel_point: channel.channel_point,
+                remote_pubkey: channel.remote_pubkey,
+                active: channel.active,
+                capacity_sat: channel.capacity.parse().unwrap_or(0),
+                local_balance_sat: channel.local_balance.parse().unwrap_or(0),
+                remote_balance_sat: channel.remote_balance.parse().unwrap_or(0),
+                assets,
+            })
+        })
+        .collect()
 }
 
 #[instrument(skip(client, macaroon_hex, request))]
@@ -153,7 +1427,7 @@ pub async fn encode_custom_data(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -175,7 +1449,7 @@ pub async fn fund_channel(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -197,7 +1471,7 @@ pub async fn create_invoice(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -219,7 +1493,7 @@ pub async fn decode_invoice(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -233,21 +1507,109 @@ pub async fn send_payment(
     client: &reqwest::Client,
     base_url: &str,
     macaroon_hex: &str,
-    request: SendPaymentRequest,
-) -> Result<serde_json::Value, AppError> {
+    request: &SendPaymentRequest,
+) -> Result<SendPaymentStreamResponse, AppError> {
     info!("Sending payment for asset ID: {}", request.asset_id);
     let url = format!("{base_url}/v1/taproot-assets/channels/send-payment");
     let response = client
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .json(&request)
-        .send()
+        .json(&request.upstream_body()?)
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
-    response
-        .json::<serde_json::Value>()
+
+    let status = response.status();
+    let mut body = response
+        .text()
         .await
-        .map_err(|e| AppError::RequestError(e.to_string()))
+        .map_err(|e| AppError::RequestError(e.to_string()))?;
+    if !status.is_success() {
+        return Err(AppError::RequestError(format!(
+            "Send-payment failed with status {status}: {body}"
+        )));
+    }
+
+    // The call is server-streaming, so fold every update into the final state
+    body.push('\n');
+    let mut merged = SendPaymentStreamResponse {
+        accepted_sell_order: None,
+        payment_result: None,
+    };
+    for message in drain_stream_messages(&mut body) {
+        let update = parse_stream_update(message)?;
+        merged.accepted_sell_order = update.accepted_sell_order.or(merged.accepted_sell_order);
+        merged.payment_result = update.payment_result.or(merged.payment_result);
+    }
+    Ok(merged)
+}
+
+/// Sends a keysend asset payment: tapd encodes the asset amount into first-hop
+/// custom records, then LND routes the payment to the peer
+#[instrument(skip(state, request))]
+pub async fn keysend(
+    state: &AppState,
+    request: KeysendRequest,
+) -> Result<KeysendResponse, AppError> {
+    request.validate()?;
+    let lnd = lnd_client(state)?;
+    info!("Sending keysend payment of asset ID: {}", request.asset_id);
+
+    let record =
+        PaymentRecord::in_flight(request.asset_id.clone(), request.asset_amount, None, None);
+    record_payment(state.payment_store.as_ref(), &record).await;
+
+    let result = send_keysend(state, &lnd, &request).await;
+    let outcome = match &result {
+        Ok(response) => PaymentOutcome {
+            succeeded: response.status == "SUCCEEDED",
+            payment_hash: Some(response.payment_hash.clone()),
+            rfq_id: None,
+            value_msat: 0,
+            fee_msat: response.fee_msat,
+            failure_reason: response.failure_reason.clone(),
+        },
+        Err(e) => PaymentOutcome::failed(e.to_string()),
+    };
+    complete_payment(state.payment_store.as_ref(), &record, &outcome).await;
+    result
+}
+
+async fn send_keysend(
+    state: &AppState,
+    lnd: &LndClient,
+    request: &KeysendRequest,
+) -> Result<KeysendResponse, AppError> {
+    let encoded = encode_custom_data(
+        &state.http_client,
+        &state.base_url.0,
+        &state.macaroon_hex.0,
+        EncodeCustomDataRequest {
+            router_send_payment: serde_json::json!({
+                "asset_amounts": {request.asset_id.clone(): request.asset_amount.to_string()}
+            }),
+        },
+    )
+    .await?;
+    let custom_records = encoded
+        .get("custom_records")
+        .cloned()
+        .ok_or_else(|| {
+            AppError::RequestError("encode-custom-data returned no custom_records".to_string())
+        })?;
+
+    let preimage: [u8; 32] = secp256k1::rand::random();
+    let payment = lnd
+        .send_payment(&request.lnd_payment(&preimage, custom_records))
+        .await?;
+
+    Ok(KeysendResponse {
+        fee_msat: payment.fee_msat(),
+        failure_reason: payment.failure_reason(),
+        payment_hash: payment.payment_hash,
+        preimage: hex::encode(preimage),
+        status: payment.status,
+    })
 }
 
 // Axum handlers
@@ -262,14 +1624,15 @@ async fn encode_custom_data_handler(
         req,
     )
     .await
-    .map_err(|e| error_response(e))?;
+    .map_err(error_response)?;
     Ok(Json(result))
 }
 
 async fn fund_handler(
     State(state): State<AppState>,
-    Json(req): Json<FundChannelRequest>,
+    Json(mut req): Json<FundChannelRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    check_fields(&mut req).map_err(field_errors_response)?;
     let result = fund_channel(
         &state.http_client,
         &state.base_url.0,
@@ -277,14 +1640,16 @@ async fn fund_handler(
         req,
     )
     .await
-    .map_err(|e| error_response(e))?;
+    .map_err(error_response)?;
     Ok(Json(result))
 }
 
 async fn create_invoice_handler(
     State(state): State<AppState>,
-    Json(req): Json<InvoiceRequest>,
+    Json(mut req): Json<InvoiceRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    check_fields(&mut req).map_err(field_errors_response)?;
+    let (asset_id, amount, label) = (req.asset_id.clone(), req.asset_amount, req.label.clone());
     let result = create_invoice(
         &state.http_client,
         &state.base_url.0,
@@ -292,14 +1657,82 @@ async fn create_invoice_handler(
         req,
     )
     .await
-    .map_err(|e| error_response(e))?;
+    .map_err(error_response)?;
+
+    // Recorded as a pending receive so the invoice and its label show in history
+    let payment_request = result["invoice_result"]["payment_request"].as_str();
+    if let Some(payment_request) = payment_request.filter(|request| !request.is_empty()) {
+        let now = chrono::Utc::now();
+        let created_at = now.timestamp();
+        let invoice = invoice_record(&result, &asset_id, amount, label.as_deref(), created_at);
+        if let Some(invoice) = invoice {
+            if let Err(e) = state.invoice_repo.insert(&invoice).await {
+                warn!("Failed to record invoice {}: {}", invoice.payment_hash, e);
+            }
+        }
+        let transaction = crate::types::Transaction {
+            id: uuid::Uuid::new_v4(),
+            tx_type: crate::types::TransactionType::Receive,
+            asset_id: Some(asset_id),
+            amount,
+            status: crate::types::TransactionStatus::Pending,
+            created_at: now,
+            updated_at: now,
+            label,
+        };
+        if let Err(e) = state
+            .transaction_store
+            .insert(transaction, Some(payment_request.to_string()))
+            .await
+        {
+            warn!("Failed to record invoice transaction: {}", e);
+        }
+    }
     Ok(Json(result))
 }
 
+/// The invoice tapd created, keyed by its hex payment hash
+fn invoice_record(
+    result: &serde_json::Value,
+    asset_id: &str,
+    asset_amount: u64,
+    label: Option<&str>,
+    created_at: i64,
+) -> Option<InvoiceRecord> {
+    let invoice = &result["invoice_result"];
+    let r_hash = invoice["r_hash"].as_str()?;
+    let payment_hash = base64::engine::general_purpose::STANDARD
+        .decode(r_hash)
+        .ok()
+        .filter(|hash| hash.len() == 32)?;
+    Some(InvoiceRecord {
+        payment_hash: hex::encode(payment_hash),
+        payment_request: invoice["payment_request"].as_str()?.to_string(),
+        asset_id: asset_id.to_string(),
+        asset_amount,
+        label: label.map(str::to_string),
+        created_at,
+    })
+}
+
+/// Invoices created through the gateway, newest first
+async fn list_invoices_handler(
+    State(state): State<AppState>,
+    Query(params): Query<InvoiceListParams>,
+) -> Result<Json<Vec<InvoiceRecord>>, (StatusCode, Json<serde_json::Value>)> {
+    let limit = params
+        .limit
+        .unwrap_or(PAYMENT_HISTORY_DEFAULT_LIMIT)
+        .clamp(1, PAYMENT_HISTORY_MAX_LIMIT);
+    let invoices = state.invoice_repo.list(limit).await.map_err(error_response)?;
+    Ok(Json(invoices))
+}
+
 async fn decode_invoice_handler(
     State(state): State<AppState>,
-    Json(req): Json<DecodeInvoiceRequest>,
+    Json(mut req): Json<DecodeInvoiceRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    check_fields(&mut req).map_err(field_errors_response)?;
     let result = decode_invoice(
         &state.http_client,
         &state.base_url.0,
@@ -307,22 +1740,157 @@ async fn decode_invoice_handler(
         req,
     )
     .await
-    .map_err(|e| error_response(e))?;
+    .map_err(error_response)?;
     Ok(Json(result))
 }
 
 async fn send_payment_handler(
     State(state): State<AppState>,
-    Json(req): Json<SendPaymentRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    Json(mut req): Json<SendPaymentRequest>,
+) -> Result<Json<SendPaymentStreamResponse>, (StatusCode, Json<serde_json::Value>)> {
+    check_fields(&mut req).map_err(field_errors_response)?;
+    let result = send_recorded_payment(&state, &req).await.map_err(error_response)?;
+    Ok(Json(result))
+}
+
+/// Sends a validated payment through tapd, keeping the payment history in step
+async fn send_recorded_payment(
+    state: &AppState,
+    req: &SendPaymentRequest,
+) -> Result<SendPaymentStreamResponse, AppError> {
+    // Swap in a fresh quote if the requested one is about to expire
+    let refreshed;
+    let req = match req.rfq_id.as_deref() {
+        Some(rfq_id) => match rfq::refresh_expiring_quote(state, rfq_id).await {
+            Ok(Some(new_rfq_id)) => {
+                refreshed = SendPaymentRequest {
+                    rfq_id: Some(new_rfq_id),
+                    ..req.clone()
+                };
+                &refreshed
+            }
+            Ok(None) => req,
+            Err(e) => {
+                warn!("Could not refresh quote {}, paying with it as is: {}", rfq_id, e);
+                req
+            }
+        },
+        None => req,
+    };
+
+    let record = PaymentRecord::in_flight(
+        req.asset_id.clone(),
+        req.asset_amount,
+        req.payment_request.as_ref().and_then(payment_request_string),
+        req.rfq_id.clone(),
+    );
+    record_payment(state.payment_store.as_ref(), &record).await;
+
     let result = send_payment(
         &state.http_client,
         &state.base_url.0,
         &state.macaroon_hex.0,
         req,
     )
+    .await;
+    let outcome = match &result {
+        Ok(response) => {
+            let mut progress = PaymentProgress::default();
+            progress.apply(response);
+            progress.outcome
+        }
+        Err(e) => Some(PaymentOutcome::failed(e.to_string())),
+    };
+    if let Some(outcome) = outcome {
+        complete_payment(state.payment_store.as_ref(), &record, &outcome).await;
+    }
+    result
+}
+
+/// Pays a Lightning Address: LNURL-pay discovery, invoice fetch and checks
+/// happen here so thin clients only supply the address and amount
+#[instrument(skip(state, req))]
+async fn pay_address_handler(
+    State(state): State<AppState>,
+    Json(req): Json<PayAddressRequest>,
+) -> Result<Json<PayAddressResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let address = LightningAddress::parse(&req.address).map_err(error_response)?;
+    if req.amount_msat == 0 {
+        return Err(error_response(AppError::InvalidInput(
+            "amount_msat must be greater than 0".to_string(),
+        )));
+    }
+    info!("Paying Lightning Address {}", address);
+
+    let pay_request = lnurl::fetch_pay_request(&state.http_client, &address)
+        .await
+        .map_err(error_response)?;
+    let invoice = lnurl::fetch_invoice(
+        &state.http_client,
+        &pay_request,
+        req.amount_msat,
+        req.comment.as_deref(),
+    )
+    .await
+    .map_err(error_response)?;
+
+    let mut decode = DecodeInvoiceRequest {
+        asset_id: req.asset_id.clone(),
+        pay_req_string: invoice.pr.clone(),
+        group_key: req.group_key.clone(),
+    };
+    check_fields(&mut decode).map_err(field_errors_response)?;
+    let decoded = decode_invoice(
+        &state.http_client,
+        &state.base_url.0,
+        &state.macaroon_hex.0,
+        decode,
+    )
     .await
-    .map_err(|e| error_response(e))?;
+    .map_err(error_response)?;
+    let pay_req = decoded.get("pay_req").ok_or_else(|| {
+        error_response(AppError::RequestError(format!(
+            "Could not decode the invoice from {address}"
+        )))
+    })?;
+    lnurl::check_invoice(pay_req, &pay_request, req.amount_msat, chrono::Utc::now().timestamp())
+        .map_err(error_response)?;
+
+    let mut send = SendPaymentRequest {
+        asset_id: req.asset_id,
+        asset_amount: 0,
+        peer_pubkey: req.peer_pubkey.unwrap_or_default(),
+        payment_request: Some(serde_json::json!({ "payment_request": invoice.pr })),
+        rfq_id: None,
+        allow_overpay: false,
+        group_key: req.group_key,
+        tuning: PaymentTuning {
+            fee_limit_sat: req.fee_limit_sat,
+            ..PaymentTuning::default()
+        },
+    };
+    check_fields(&mut send).map_err(field_errors_response)?;
+    let payment = send_recorded_payment(&state, &send).await.map_err(error_response)?;
+
+    Ok(Json(PayAddressResponse {
+        address: address.to_string(),
+        invoice: invoice.pr,
+        success_action: invoice.success_action,
+        payment,
+    }))
+}
+
+async fn payment_history_handler(
+    State(state): State<AppState>,
+    Query(params): Query<PaymentHistoryParams>,
+) -> Result<Json<PaymentHistoryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let result = payment_history(
+        state.payment_store.as_ref(),
+        state.rfq_order_store.as_ref(),
+        params,
+    )
+        .await
+        .map_err(error_response)?;
     Ok(Json(result))
 }
 
@@ -348,15 +1916,190 @@ async fn send_payment_websocket_handler(
         state.http_client,
         state.base_url.0,
         state.macaroon_hex.0,
+        state.payment_store,
     ));
 
-    // Define the backend WebSocket endpoint for streaming send-payment
-    let backend_endpoint = "/v1/taproot-assets/channels/send-payment?stream=true".to_string();
+    // tapd streams send-payment updates as newline-delimited JSON
+    let backend_endpoint = "/v1/taproot-assets/channels/send-payment".to_string();
 
     // Handle the WebSocket connection with correlation tracking enabled
     ws_handler.handle_websocket(ws, backend_endpoint, true).await.into_response()
 }
 
+async fn fetch_asset_channels(state: &AppState) -> Result<Vec<AssetChannel>, AppError> {
+    let listing: LndChannels = lnd_client(state)?.get("/v1/channels").await?;
+    Ok(asset_channels(listing.channels))
+}
+
+async fn list_asset_channels_handler(
+    State(state): State<AppState>,
+) -> Result<Json<AssetChannelsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let channels = fetch_asset_channels(&state).await.map_err(error_response)?;
+    Ok(Json(AssetChannelsResponse { channels }))
+}
+
+async fn channel_balance_handler(
+    State(state): State<AppState>,
+) -> Result<Json<ChannelBalanceResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let channels = fetch_asset_channels(&state).await.map_err(error_response)?;
+    Ok(Json(summarize_channel_balances(&channels)))
+}
+
+async fn settle_invoice_handler(
+    State(state): State<AppState>,
+    Json(req): Json<SettleInvoiceRequest>,
+) -> Result<Json<HodlInvoiceResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let preimage = decode_hash32("preimage", &req.preimage).map_err(error_response)?;
+    let payment_hash = hex::encode(Sha256::digest(&preimage));
+    let lnd = lnd_client(&state).map_err(error_response)?;
+
+    info!("Settling hold invoice {}", payment_hash);
+    let body = serde_json::json!({
+        "preimage": base64::engine::general_purpose::STANDARD.encode(&preimage)
+    });
+    let _: serde_json::Value = lnd
+        .post("/v2/invoices/settle", &body)
+        .await
+        .map_err(error_response)?;
+
+    Ok(Json(HodlInvoiceResponse {
+        payment_hash,
+        state: "SETTLED".to_string(),
+    }))
+}
+
+async fn cancel_invoice_handler(
+    State(state): State<AppState>,
+    Json(req): Json<CancelInvoiceRequest>,
+) -> Result<Json<HodlInvoiceResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let payment_hash = decode_hash32("payment_hash", &req.payment_hash).map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+
+    info!("Canceling hold invoice {}", req.payment_hash);
+    let body = serde_json::json!({
+        "payment_hash": base64::engine::general_purpose::STANDARD.encode(&payment_hash)
+    });
+    let _: serde_json::Value = lnd
+        .post("/v2/invoices/cancel", &body)
+        .await
+        .map_err(error_response)?;
+
+    Ok(Json(HodlInvoiceResponse {
+        payment_hash: hex::encode(payment_hash),
+        state: "CANCELED".to_string(),
+    }))
+}
+
+async fn lookup_invoice_handler(
+    State(state): State<AppState>,
+    Query(query): Query<InvoiceLookupQuery>,
+) -> Result<Json<InvoiceLookupResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let payment_hash = decode_hash32("payment_hash", &query.payment_hash).map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+
+    let invoice: LndInvoice = lnd
+        .get(&format!("/v1/invoice/{}", hex::encode(payment_hash)))
+        .await
+        .map_err(error_response)?;
+    Ok(Json(invoice.into()))
+}
+
+async fn keysend_handler(
+    State(state): State<AppState>,
+    Json(req): Json<KeysendRequest>,
+) -> Result<Json<KeysendResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let result = keysend(&state, req).await.map_err(error_response)?;
+    Ok(Json(result))
+}
+
+/// Starts a close and returns once LND reports the closing transaction
+async fn close_channel_handler(
+    State(state): State<AppState>,
+    Json(req): Json<CloseChannelRequest>,
+) -> Result<Json<CloseChannelStatus>, (StatusCode, Json<serde_json::Value>)> {
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    let path = req.lnd_path().map_err(error_response)?;
+    info!("Closing channel {} (force: {})", req.channel_point, req.force);
+
+    let mut response = lnd.delete_stream(&path).await.map_err(error_response)?;
+    let status = next_close_status(&mut response, &mut String::new(), &mut Default::default())
+        .await
+        .map_err(error_response)?
+        .ok_or_else(|| {
+            error_response(AppError::RequestError(
+                "LND ended the close stream without an update".to_string(),
+            ))
+        })?;
+    Ok(Json(status))
+}
+
+/// Streams close progress; the client sends a `CloseChannelRequest` as its first message
+async fn close_channel_websocket_handler(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> axum::response::Response {
+    let lnd = match lnd_client(&state) {
+        Ok(lnd) => lnd,
+        Err(e) => return error_response(e).into_response(),
+    };
+    let correlation_id = new_correlation_id();
+    let span = info_span!("channel_close", correlation_id = %correlation_id);
+    ws.on_upgrade(move |socket| stream_channel_close(socket, lnd, correlation_id).instrument(span))
+}
+
+async fn stream_channel_close(mut socket: WebSocket, lnd: Arc<LndClient>, correlation_id: String) {
+    let result = match socket.recv().await {
+        Some(Ok(Message::Text(text))) => match serde_json::from_str::<CloseChannelRequest>(&text) {
+            Ok(request) => relay_close_updates(&mut socket, &lnd, &request, &correlation_id).await,
+            Err(e) => Err(AppError::InvalidInput(format!("Invalid close request: {e}"))),
+        },
+        Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+        Some(Ok(_)) => Err(AppError::InvalidInput(
+            "Expected a JSON close request".to_string(),
+        )),
+    };
+
+    if let Err(e) = result {
+        warn!("Channel close stream failed: {}", e);
+        let frame = with_correlation_id(&serde_json::json!({"error": e.to_string()}), &correlation_id);
+        let _ = socket.send(Message::Text(frame.to_string())).await;
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+async fn relay_close_updates(
+    socket: &mut WebSocket,
+    lnd: &LndClient,
+    request: &CloseChannelRequest,
+    correlation_id: &str,
+) -> Result<(), AppError> {
+    let path = request.lnd_path()?;
+    info!("Closing channel {} (force: {})", request.channel_point, request.force);
+    let mut response = lnd.delete_stream(&path).await?;
+    let mut buffer = String::new();
+    let mut pending = Default::default();
+
+    loop {
+        tokio::select! {
+            status = next_close_status(&mut response, &mut buffer, &mut pending) => {
+                let Some(status) = status? else {
+                    return Ok(());
+                };
+                let finished = matches!(status, CloseChannelStatus::Closed { .. });
+                let frame = with_correlation_id(&serde_json::to_value(&status)?, correlation_id);
+                if socket.send(Message::Text(frame.to_string())).await.is_err() || finished {
+                    return Ok(());
+                }
+            }
+            msg = socket.recv() => match msg {
+                // The close itself continues in LND; only the updates stop
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return Ok(()),
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
 // Error response helper
 fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
     let status = error.status_code();
@@ -367,18 +2110,38 @@ fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
     (status, Json(error_json))
 }
 
+/// A 400 response that also lists every rejected field
+fn field_errors_response(errors: Vec<FieldError>) -> (StatusCode, Json<serde_json::Value>) {
+    let (status, Json(mut error_json)) =
+        error_response(AppError::InvalidInput(describe_field_errors(&errors)));
+    error_json["fields"] = serde_json::json!(errors);
+    (status, Json(error_json))
+}
+
 // Create the channels router
 pub fn create_channels_routes() -> Router<AppState> {
     Router::new()
+        .route("/", get(list_asset_channels_handler))
         .route("/channels/encode-custom-data", post(encode_custom_data_handler))
         .route("/channels/fund", post(fund_handler))
         .route("/channels/invoice", post(create_invoice_handler))
         .route("/channels/invoice/decode", post(decode_invoice_handler))
+        .route("/channels/invoice/settle", post(settle_invoice_handler))
+        .route("/channels/invoice/cancel", post(cancel_invoice_handler))
+        .route("/channels/invoice/lookup", get(lookup_invoice_handler))
+        .route("/channels/invoices", get(list_invoices_handler))
         .route("/channels/send-payment", post(send_payment_handler))
+        .route("/channels/keysend", post(keysend_handler))
+        .route("/channels/pay-address", post(pay_address_handler))
+        .route("/channels/payments", get(payment_history_handler))
         .route("/channels/send-payment", get(send_payment_websocket_handler))
+        .route("/channels/balance", get(channel_balance_handler))
+        .route("/channels/close", post(close_channel_handler))
+        .route("/channels/close", get(close_channel_websocket_handler))
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -398,12 +2161,13 @@ mod tests {
     fn test_send_payment_stream_request_serialization() {
         let request = SendPaymentStreamRequest {
             asset_id: "test_asset_id".to_string(),
-            asset_amount: "1000".to_string(),
+            asset_amount: 1000,
             peer_pubkey: "test_pubkey".to_string(),
             payment_request: serde_json::json!({"invoice": "test_invoice"}),
             rfq_id: "test_rfq_id".to_string(),
             allow_overpay: true,
             group_key: Some("test_group_key".to_string()),
+            tuning: PaymentTuning::default(),
         };
 
         let serialized = serde_json::to_string(&request).unwrap();
@@ -500,7 +2264,7 @@ mod tests {
 
         let request = parsed.unwrap();
         assert_eq!(request.asset_id, "YXNzZXRfaWQ=");
-        assert_eq!(request.asset_amount, "1000");
+        assert_eq!(request.asset_amount, 1000);
         assert_eq!(request.peer_pubkey, "cGVlcl9wdWJrZXk=");
         assert_eq!(request.rfq_id, "cmZxX2lk");
         assert_eq!(request.allow_overpay, false);
@@ -549,4 +2313,529 @@ mod tests {
             assert!(payment.get("status").is_some());
         }
     }
+
+    #[test]
+    fn test_parse_stream_update() {
+        let mut buffer = concat!(
+            r#"{"result":{"accepted_sell_order":{"id":"cXVvdGU="}}}"#,
+            "\n",
+            r#"{"result":{"payment_result":{"status":"SUCCEEDED"}}}"#,
+            "\n",
+            r#"{"error":{"code":2,"message":"no route"}}"#,
+            "\n",
+        )
+        .to_string();
+        let mut messages = drain_stream_messages(&mut buffer).into_iter();
+
+        let order = parse_stream_update(messages.next().unwrap()).unwrap();
+        assert!(order.accepted_sell_order.is_some());
+        assert!(order.payment_result.is_none());
+
+        let payment = parse_stream_update(messages.next().unwrap()).unwrap();
+        assert_eq!(payment.payment_result.unwrap()["status"], "SUCCEEDED");
+
+        let error = parse_stream_update(messages.next().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("no route"));
+    }
+
+    #[test]
+    fn test_asset_channels_decodes_custom_data() {
+        let custom_data = serde_json::json!({
+            "funding_assets": [{
+                "asset_genesis": {"asset_id": "abc123", "name": "USDT"},
+                "amount": 100000
+            }],
+            "local_assets": [{"asset_id": "abc123", "amount": 60000}],
+            "remote_assets": [{"asset_id": "abc123", "amount": 40000}],
+            "capacity": 100000,
+            "local_balance": 60000,
+            "remote_balance": 40000
+        });
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(custom_data.to_string());
+
+        let channels = asset_channels(vec![
+            LndChannel {
+                active: true,
+                chan_id: "1".to_string(),
+                capacity: "100000".to_string(),
+                local_balance: "1000".to_string(),
+                custom_channel_data: encoded,
+                ..Default::default()
+            },
+            // Plain BTC channels are left out
+            LndChannel {
+                chan_id: "2".to_string(),
+                capacity: "500000".to_string(),
+                ..Default::default()
+            },
+        ]);
+
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].capacity_sat, 100000);
+        assert_eq!(channels[0].local_balance_sat, 1000);
+        assert_eq!(
+            channels[0].assets,
+            vec![ChannelAssetBalance {
+                asset_id: "abc123".to_string(),
+                name: "USDT".to_string(),
+                capacity: 100000,
+                local_balance: 60000,
+                remote_balance: 40000,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_close_channel_request_paths() {
+        let txid = "a".repeat(64);
+        let request = |force: bool, sat_per_vbyte: Option<u64>, target_conf: Option<u32>| {
+            CloseChannelRequest {
+                channel_point: format!("{txid}:1"),
+                force,
+                sat_per_vbyte,
+                target_conf,
+                delivery_address: None,
+            }
+        };
+
+        assert_eq!(
+            request(false, Some(12), None).lnd_path().unwrap(),
+            format!("/v1/channels/{txid}/1?sat_per_vbyte=12")
+        );
+        assert_eq!(
+            request(true, None, None).lnd_path().unwrap(),
+            format!("/v1/channels/{txid}/1?force=true")
+        );
+        assert_eq!(request(false, None, None).lnd_path().unwrap(), format!("/v1/channels/{txid}/1"));
+        assert!(request(true, Some(12), None).lnd_path().is_err());
+        assert!(request(false, Some(12), Some(6)).lnd_path().is_err());
+
+        let mut bad_point = request(false, None, None);
+        bad_point.channel_point = "not-an-outpoint".to_string();
+        assert!(bad_point.lnd_path().is_err());
+    }
+
+    #[test]
+    fn test_parse_close_status() {
+        let internal = [0x11u8; 32];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(internal);
+
+        let pending = parse_close_status(Ok(serde_json::json!({
+            "close_pending": {"txid": encoded, "output_index": 0}
+        })))
+        .unwrap();
+        assert_eq!(
+            pending,
+            Some(CloseChannelStatus::Pending {
+                closing_txid: "11".repeat(32),
+                output_index: 0
+            })
+        );
+
+        let closed = parse_close_status(Ok(serde_json::json!({
+            "chan_close": {"closing_txid": encoded, "success": true}
+        })))
+        .unwrap();
+        assert!(matches!(closed, Some(CloseChannelStatus::Closed { success: true, .. })));
+
+        assert_eq!(parse_close_status(Ok(serde_json::json!({}))).unwrap(), None);
+        assert!(parse_close_status(Err(serde_json::json!({"message": "channel not found"}))).is_err());
+    }
+
+    #[test]
+    fn test_summarize_channel_balances() {
+        let balance = |asset_id: &str, local: u64, remote: u64| ChannelAssetBalance {
+            asset_id: asset_id.to_string(),
+            name: asset_id.to_uppercase(),
+            capacity: local + remote,
+            local_balance: local,
+            remote_balance: remote,
+        };
+        let channel = |assets: Vec<ChannelAssetBalance>| AssetChannel {
+            chan_id: String::new(),
+            channel_point: String::new(),
+            remote_pubkey: String::new(),
+            active: true,
+            capacity_sat: 100_000,
+            local_balance_sat: 1_000,
+            remote_balance_sat: 500,
+            assets,
+        };
+
+        let summary = summarize_channel_balances(&[
+            channel(vec![balance("usdt", 60, 40)]),
+            channel(vec![balance("usdt", 10, 90), balance("eur", 5, 5)]),
+        ]);
+
+        assert_eq!(summary.local_balance_sat, 2_000);
+        assert_eq!(summary.remote_balance_sat, 1_000);
+        assert_eq!(summary.assets.len(), 2);
+        assert_eq!(summary.assets[0].asset_id, "eur");
+        let usdt = &summary.assets[1];
+        assert_eq!(usdt.channel_count, 2);
+        assert_eq!((usdt.capacity, usdt.local_balance, usdt.remote_balance), (200, 70, 130));
+    }
+
+    #[test]
+    fn test_hold_invoice_inputs_and_lookup_conversion() {
+        assert!(decode_hash32("preimage", &"ab".repeat(32)).is_ok());
+        assert!(decode_hash32("preimage", &"ab".repeat(31)).is_err());
+        assert!(decode_hash32("preimage", "not hex").is_err());
+
+        let lookup: InvoiceLookupResponse = LndInvoice {
+            r_hash: base64::engine::general_purpose::STANDARD.encode([0xcd; 32]),
+            state: "ACCEPTED".to_string(),
+            value_msat: "250000".to_string(),
+            amt_paid_msat: "250000".to_string(),
+            expiry: "3600".to_string(),
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(lookup.payment_hash, "cd".repeat(32));
+        assert_eq!(lookup.state, "ACCEPTED");
+        assert_eq!(lookup.value_msat, 250000);
+        assert_eq!(lookup.expiry, 3600);
+    }
+
+    #[test]
+    fn test_keysend_request_builds_lnd_payment() {
+        let request = KeysendRequest {
+            asset_id: "ab".repeat(32),
+            asset_amount: 500,
+            peer_pubkey: format!("02{}", "11".repeat(32)),
+            message: Some("thanks".to_string()),
+            fee_limit_sat: Some(10),
+            timeout_seconds: None,
+        };
+        assert!(request.validate().is_ok());
+
+        let preimage = [7u8; 32];
+        let records = serde_json::json!({"65536": "AQI="});
+        let payment = request.lnd_payment(&preimage, records.clone());
+        let base64 = base64::engine::general_purpose::STANDARD;
+
+        assert_eq!(payment["first_hop_custom_records"], records);
+        assert_eq!(payment["dest_custom_records"]["5482373484"], base64.encode(preimage));
+        assert_eq!(payment["dest_custom_records"]["34349334"], base64.encode("thanks"));
+        assert_eq!(payment["payment_hash"], base64.encode(Sha256::digest(preimage)));
+        assert_eq!(payment["amt"], "354");
+        assert_eq!(payment["timeout_seconds"], 60);
+
+        let invalid = KeysendRequest {
+            peer_pubkey: "02abcd".to_string(),
+            ..request
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_payment_progress_tracks_final_result() {
+        let mut progress = PaymentProgress::default();
+        progress.apply(&SendPaymentStreamResponse {
+            accepted_sell_order: Some(serde_json::json!({"id": "quote-id"})),
+            payment_result: None,
+        });
+        progress.apply(&SendPaymentStreamResponse {
+            accepted_sell_order: None,
+            payment_result: Some(serde_json::json!({"status": "IN_FLIGHT"})),
+        });
+        assert!(progress.outcome.is_none());
+
+        progress.apply(&SendPaymentStreamResponse {
+            accepted_sell_order: None,
+            payment_result: Some(serde_json::json!({
+                "status": "SUCCEEDED",
+                "payment_hash": "abcd",
+                "fee_msat": "2000",
+                "failure_reason": "FAILURE_REASON_NONE"
+            })),
+        });
+        let outcome = progress.outcome.unwrap();
+        assert!(outcome.succeeded);
+        assert_eq!(outcome.payment_hash.as_deref(), Some("abcd"));
+        assert_eq!(outcome.rfq_id.as_deref(), Some("quote-id"));
+        assert_eq!(outcome.fee_msat, 2000);
+        assert_eq!(outcome.failure_reason, None);
+    }
+
+    #[test]
+    fn test_unfinished_stream_is_not_left_in_flight() {
+        let mut progress = PaymentProgress::default();
+        progress.apply(&SendPaymentStreamResponse {
+            accepted_sell_order: Some(serde_json::json!({"id": "quote-id"})),
+            payment_result: Some(serde_json::json!({"status": "IN_FLIGHT"})),
+        });
+        let outcome = progress.final_outcome(None);
+        assert!(!outcome.succeeded);
+        assert_eq!(outcome.rfq_id.as_deref(), Some("quote-id"));
+        assert!(outcome.failure_reason.unwrap().contains("final status"));
+
+        let error = AppError::RequestError("Send-payment failed: no route".to_string());
+        let outcome = PaymentProgress::default().final_outcome(Some(&error));
+        assert!(outcome.failure_reason.unwrap().contains("no route"));
+    }
+
+    #[test]
+    fn test_send_payment_rejects_unparseable_amounts() {
+        let body = |amount: serde_json::Value| {
+            serde_json::json!({
+                "asset_id": "abcd",
+                "asset_amount": amount,
+                "payment_request": {"payment_request": "lnbc1"},
+                "rfq_id": null,
+                "allow_overpay": false,
+                "group_key": null
+            })
+        };
+        for amount in [serde_json::json!("ten"), serde_json::json!(""), serde_json::json!(-5)] {
+            let request = serde_json::from_value::<SendPaymentRequest>(body(amount.clone()));
+            assert!(request.is_err(), "{amount} was accepted");
+            let stream = serde_json::from_value::<SendPaymentStreamRequest>(body(amount.clone()));
+            assert!(stream.is_err(), "{amount} was accepted over the WebSocket");
+        }
+        let request = serde_json::from_value::<SendPaymentRequest>(body("42".into())).unwrap();
+        assert_eq!(request.asset_amount, 42);
+    }
+
+    #[tokio::test]
+    async fn test_payment_history_pages() {
+        use crate::storage::payments::InMemoryPaymentStore;
+        use crate::storage::rfq_orders::InMemoryRfqOrderStore;
+
+        let store = InMemoryPaymentStore::new();
+        let orders = InMemoryRfqOrderStore::new();
+        for created_at in 1..=3 {
+            let mut record = PaymentRecord::in_flight("usd".to_string(), 10, None, None);
+            record.created_at = created_at;
+            store.record(&record).await.unwrap();
+        }
+
+        let params = |offset| PaymentHistoryParams {
+            limit: Some(2),
+            offset: Some(offset),
+            ..Default::default()
+        };
+        let first = payment_history(&store, &orders, params(0)).await.unwrap();
+        assert_eq!(first.payments.len(), 2);
+        assert_eq!(first.payments[0].payment.created_at, 3);
+        assert_eq!(first.next_offset, Some(2));
+
+        let last = payment_history(&store, &orders, params(2)).await.unwrap();
+        assert_eq!(last.payments.len(), 1);
+        assert_eq!(last.next_offset, None);
+
+        let backwards = PaymentHistoryParams {
+            from: Some(5),
+            to: Some(1),
+            ..Default::default()
+        };
+        assert!(payment_history(&store, &orders, backwards).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_payment_history_links_quotes() {
+        use crate::storage::payments::InMemoryPaymentStore;
+        use crate::storage::rfq_orders::InMemoryRfqOrderStore;
+
+        let orders = InMemoryRfqOrderStore::new();
+        let response = serde_json::json!({"accepted_quote": {
+            "id": "cXVvdGU=",
+            "peer": "02aa",
+            "bid_asset_rate": {"coefficient": "5000000", "scale": 2}
+        }});
+        let order = RfqOrderRecord::new(
+            RfqOrderKind::SellOrder,
+            "usd".to_string(),
+            serde_json::json!({}),
+            Ok(&response),
+        );
+        orders.record(&order).await.unwrap();
+
+        let store = InMemoryPaymentStore::new();
+        let quoted = Some("cXVvdGU=".to_string());
+        let invoice_payment = PaymentRecord::in_flight("usd".to_string(), 0, None, quoted);
+        let unquoted = PaymentRecord::in_flight("usd".to_string(), 10, None, None);
+        for payment in [&invoice_payment, &unquoted] {
+            store.record(payment).await.unwrap();
+        }
+        let outcome = |value_msat| PaymentOutcome {
+            succeeded: true,
+            value_msat,
+            fee_msat: 1_000,
+            ..Default::default()
+        };
+        store.complete(invoice_payment.id, &outcome(200_000_000)).await.unwrap();
+        store.complete(unquoted.id, &outcome(9_000)).await.unwrap();
+
+        let history = payment_history(&store, &orders, PaymentHistoryParams::default())
+            .await
+            .unwrap();
+        let entry = |id| history.payments.iter().find(|e| e.payment.id == id).unwrap();
+
+        let linked = entry(invoice_payment.id);
+        let quote = linked.quote.as_ref().unwrap();
+        assert_eq!(quote.order_id, order.id);
+        assert_eq!(quote.units_per_btc, Some(50_000.0));
+        // 0.002 BTC at 50,000 units per BTC buys 100 units
+        let rate = linked.effective_rate.as_ref().unwrap();
+        assert_eq!(rate.asset_units, 100.0);
+        assert_eq!(rate.msat_spent, 200_001_000);
+        assert_eq!(rate.msat_per_unit, 2_000_010.0);
+
+        let direct = entry(unquoted.id);
+        assert!(direct.quote.is_none());
+        let rate = direct.effective_rate.as_ref().unwrap();
+        assert_eq!((rate.asset_units, rate.msat_per_unit), (10.0, 1_000.0));
+    }
+
+    #[test]
+    fn test_send_payment_tuning_is_forwarded_to_lnd_request() {
+        let request: SendPaymentRequest = serde_json::from_value(serde_json::json!({
+            "asset_id": "abcd",
+            "asset_amount": "100",
+            "peer_pubkey": "02ab",
+            "payment_request": {"payment_request": "lnbc1", "fee_limit_msat": "5000"},
+            "rfq_id": null,
+            "allow_overpay": false,
+            "group_key": null,
+            "max_parts": 4,
+            "timeout_seconds": 90,
+            "fee_limit_sat": 10,
+            "outgoing_chan_ids": [123456789]
+        }))
+        .unwrap();
+
+        let body = request.upstream_body().unwrap();
+        assert!(body.get("max_parts").is_none());
+        assert_eq!(
+            body["payment_request"],
+            serde_json::json!({
+                "payment_request": "lnbc1",
+                "max_parts": 4,
+                "timeout_seconds": 90,
+                "fee_limit_sat": "10",
+                "outgoing_chan_ids": ["123456789"]
+            })
+        );
+
+        let mut zero_parts = SendPaymentRequest {
+            asset_id: hex::encode([1u8; 32]),
+            peer_pubkey: String::new(),
+            tuning: PaymentTuning {
+                max_parts: Some(0),
+                ..request.tuning.clone()
+            },
+            ..request
+        };
+        let errors = check_fields(&mut zero_parts).unwrap_err();
+        assert_eq!(errors, vec![FieldError::new("max_parts", "must be between 1 and 64")]);
+
+        let mut without_invoice = SendPaymentRequest {
+            tuning: PaymentTuning {
+                max_parts: None,
+                ..zero_parts.tuning.clone()
+            },
+            payment_request: None,
+            ..zero_parts
+        };
+        let errors = check_fields(&mut without_invoice).unwrap_err();
+        assert_eq!(errors[0].field, "payment_request");
+    }
+
+    #[test]
+    fn test_send_payment_stream_takes_the_same_tuning() {
+        let mut request: SendPaymentStreamRequest = serde_json::from_value(serde_json::json!({
+            "asset_id": hex::encode([1u8; 32]),
+            "asset_amount": "100",
+            "payment_request": {"payment_request": "lnbc1", "fee_limit_msat": "5000"},
+            "allow_overpay": false,
+            "group_key": null,
+            "max_parts": 2,
+            "timeout_seconds": 30,
+            "fee_limit_sat": 7,
+            "outgoing_chan_ids": [42]
+        }))
+        .unwrap();
+        check_fields(&mut request).unwrap();
+
+        let body = request.upstream_body().unwrap();
+        assert!(body.get("timeout_seconds").is_none());
+        assert_eq!(
+            body["payment_request"],
+            serde_json::json!({
+                "payment_request": "lnbc1",
+                "max_parts": 2,
+                "timeout_seconds": 30,
+                "fee_limit_sat": "7",
+                "outgoing_chan_ids": ["42"]
+            })
+        );
+
+        request.tuning.timeout_seconds = Some(0);
+        request.tuning.outgoing_chan_ids = vec![0];
+        let errors = check_fields(&mut request).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|error| error.field).collect();
+        assert_eq!(fields, ["timeout_seconds", "outgoing_chan_ids"]);
+    }
+
+    #[test]
+    fn test_amounts_accept_strings_and_numbers() {
+        let fund: FundChannelRequest = serde_json::from_value(serde_json::json!({
+            "asset_amount": 250,
+            "asset_id": "",
+            "peer_pubkey": "",
+            "fee_rate_sat_per_vbyte": 5,
+            "push_sat": "1000",
+            "group_key": null
+        }))
+        .unwrap();
+        assert_eq!(fund.asset_amount, 250);
+        assert_eq!(fund.push_sat, Some(1000));
+
+        let body = serde_json::to_value(&fund).unwrap();
+        assert_eq!(body["asset_amount"], "250");
+        assert_eq!(body["push_sat"], "1000");
+
+        let invalid = serde_json::from_value::<FundChannelRequest>(serde_json::json!({
+            "asset_amount": "ten",
+            "asset_id": "",
+            "peer_pubkey": "",
+            "fee_rate_sat_per_vbyte": 5,
+            "group_key": null
+        }));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_channel_requests_report_every_invalid_field() {
+        let asset_id = [7u8; 32];
+        let mut request = FundChannelRequest {
+            asset_amount: 0,
+            asset_id: hex::encode(asset_id),
+            peer_pubkey: "not-a-key".to_string(),
+            fee_rate_sat_per_vbyte: 0,
+            push_sat: None,
+            group_key: None,
+        };
+        let errors = check_fields(&mut request).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|error| error.field).collect();
+        assert_eq!(fields, ["asset_amount", "peer_pubkey", "fee_rate_sat_per_vbyte"]);
+
+        // Hex byte fields are forwarded in tapd's base64 encoding
+        let engine = base64::engine::general_purpose::STANDARD;
+        assert_eq!(request.asset_id, engine.encode(asset_id));
+
+        let mut decode = DecodeInvoiceRequest {
+            asset_id: String::new(),
+            pay_req_string: String::new(),
+            group_key: Some(engine.encode([2u8; 33])),
+        };
+        let errors = check_fields(&mut decode).unwrap_err();
+        assert_eq!(errors, vec![FieldError::new("pay_req_string", "must not be empty")]);
+
+        let (status, Json(body)) = field_errors_response(errors);
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "pay_req_string");
+    }
 }
@@ -0,0 +1,41 @@
+use serde_json::Value;
+
+/// Field added to forwarded frames so clients can quote it when reporting issues
+pub const CORRELATION_FIELD: &str = "correlation_id";
+
+/// Generates a short random ID identifying one upstream subscription or proxied
+/// connection in the gateway logs
+pub fn new_correlation_id() -> String {
+    hex::encode(secp256k1::rand::random::<[u8; 8]>())
+}
+
+/// Tags a JSON object frame with `correlation_id`; other JSON values are
+/// returned unchanged since there is nowhere to put the field
+pub fn with_correlation_id(frame: &Value, correlation_id: &str) -> Value {
+    let mut frame = frame.clone();
+    if let Value::Object(map) = &mut frame {
+        map.insert(CORRELATION_FIELD.to_string(), Value::from(correlation_id));
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_correlation_ids_are_random_hex() {
+        let id = new_correlation_id();
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, new_correlation_id());
+    }
+
+    #[test]
+    fn test_with_correlation_id_only_tags_objects() {
+        let tagged = with_correlation_id(&json!({"amount": "5"}), "abc");
+        assert_eq!(tagged, json!({"amount": "5", "correlation_id": "abc"}));
+        assert_eq!(with_correlation_id(&json!([1, 2]), "abc"), json!([1, 2]));
+    }
+}
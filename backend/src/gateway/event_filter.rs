@@ -0,0 +1,341 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Longest filter expression accepted from a client
+const MAX_FILTER_LENGTH: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    String(String),
+    Number(f64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Compare {
+        field: Vec<String>,
+        op: CompareOp,
+        value: Literal,
+    },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// A parsed event filter such as `asset_id == "abc" && amount > 1000`.
+///
+/// Fields are dotted paths into the event (`address.asset_id`); a bare name
+/// that is not a top-level field matches the first nested field of that name.
+/// Numeric comparisons accept tapd's string-encoded 64-bit integers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventFilter {
+    expr: Expr,
+}
+
+impl EventFilter {
+    pub fn parse(input: &str) -> Result<Self, AppError> {
+        if input.len() > MAX_FILTER_LENGTH {
+            return Err(AppError::InvalidInput(format!(
+                "Filter expression exceeds {MAX_FILTER_LENGTH} characters"
+            )));
+        }
+
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(invalid_filter("unexpected trailing input"));
+        }
+        Ok(Self { expr })
+    }
+
+    pub fn matches(&self, event: &Value) -> bool {
+        evaluate(&self.expr, event)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn invalid_filter(reason: &str) -> AppError {
+    AppError::InvalidInput(format!("Invalid filter expression: {reason}"))
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, AppError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Eq));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '>' | '<' => {
+                let inclusive = next == Some('=');
+                let op = match (c, inclusive) {
+                    ('>', false) => CompareOp::Gt,
+                    ('>', true) => CompareOp::Ge,
+                    ('<', false) => CompareOp::Lt,
+                    _ => CompareOp::Le,
+                };
+                tokens.push(Token::Op(op));
+                i += if inclusive { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| invalid_filter("unterminated string"))?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text
+                    .parse::<f64>()
+                    .map_err(|_| invalid_filter(&format!("bad number '{text}'")))?;
+                tokens.push(Token::Num(number));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            other => return Err(invalid_filter(&format!("unexpected character '{other}'"))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, AppError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, AppError> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, AppError> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err(invalid_filter("missing ')'")),
+                }
+            }
+            Some(Token::Ident(field)) => {
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    _ => return Err(invalid_filter(&format!("expected operator after '{field}'"))),
+                };
+                let value = match self.next() {
+                    Some(Token::Str(s)) => Literal::String(s),
+                    Some(Token::Num(n)) => Literal::Number(n),
+                    Some(Token::Ident(word)) if word == "true" => Literal::Bool(true),
+                    Some(Token::Ident(word)) if word == "false" => Literal::Bool(false),
+                    _ => return Err(invalid_filter(&format!("expected value after '{field}'"))),
+                };
+                Ok(Expr::Compare {
+                    field: field.split('.').map(str::to_string).collect(),
+                    op,
+                    value,
+                })
+            }
+            _ => Err(invalid_filter("expected a comparison")),
+        }
+    }
+}
+
+fn evaluate(expr: &Expr, event: &Value) -> bool {
+    match expr {
+        Expr::Compare { field, op, value } => {
+            lookup(event, field).is_some_and(|actual| compare(actual, *op, value))
+        }
+        Expr::Not(inner) => !evaluate(inner, event),
+        Expr::And(a, b) => evaluate(a, event) && evaluate(b, event),
+        Expr::Or(a, b) => evaluate(a, event) || evaluate(b, event),
+    }
+}
+
+fn lookup<'a>(event: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let direct = path.iter().try_fold(event, |value, key| value.get(key));
+    match (direct, path) {
+        (Some(value), _) => Some(value),
+        (None, [name]) => find_nested(event, name),
+        _ => None,
+    }
+}
+
+fn find_nested<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map
+            .get(name)
+            .or_else(|| map.values().find_map(|v| find_nested(v, name))),
+        Value::Array(items) => items.iter().find_map(|v| find_nested(v, name)),
+        _ => None,
+    }
+}
+
+fn compare(actual: &Value, op: CompareOp, expected: &Literal) -> bool {
+    let ordering = match (actual, expected) {
+        (Value::Bool(a), Literal::Bool(b)) => a.partial_cmp(b),
+        (Value::String(a), Literal::String(b)) => a.partial_cmp(b),
+        (Value::Number(a), Literal::Number(b)) => a.as_f64().and_then(|a| a.partial_cmp(b)),
+        (Value::String(a), Literal::Number(b)) => {
+            a.parse::<f64>().ok().and_then(|a| a.partial_cmp(b))
+        }
+        (Value::Number(a), Literal::String(b)) => {
+            b.parse::<f64>().ok().and_then(|b| a.as_f64()?.partial_cmp(&b))
+        }
+        _ => None,
+    };
+
+    let Some(ordering) = ordering else {
+        return op == CompareOp::Ne;
+    };
+    match op {
+        CompareOp::Eq => ordering == Ordering::Equal,
+        CompareOp::Ne => ordering != Ordering::Equal,
+        CompareOp::Gt => ordering == Ordering::Greater,
+        CompareOp::Ge => ordering != Ordering::Less,
+        CompareOp::Lt => ordering == Ordering::Less,
+        CompareOp::Le => ordering != Ordering::Greater,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn receive_event() -> Value {
+        json!({
+            "timestamp": "1700000000",
+            "address": {"encoded": "taprt1abc", "asset_id": "abc123", "amount": "1500"},
+            "status": "ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED",
+            "confirmation_height": 100
+        })
+    }
+
+    #[test]
+    fn test_matches_nested_fields_and_string_numbers() {
+        let event = receive_event();
+        let filter = EventFilter::parse(r#"asset_id == "abc123" && amount > 1000"#).unwrap();
+        assert!(filter.matches(&event));
+
+        let filter = EventFilter::parse("address.amount >= 2000").unwrap();
+        assert!(!filter.matches(&event));
+
+        let filter = EventFilter::parse("missing_field == 'x'").unwrap();
+        assert!(!filter.matches(&event));
+    }
+
+    #[test]
+    fn test_operator_precedence_and_negation() {
+        let event = receive_event();
+        let filter = EventFilter::parse(
+            r#"confirmation_height < 50 || status == "ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED" && !(asset_id != "abc123")"#,
+        )
+        .unwrap();
+        assert!(filter.matches(&event));
+    }
+
+    #[test]
+    fn test_rejects_malformed_expressions() {
+        for input in ["asset_id ==", "amount > 5 &&", "(amount > 5", "asset_id = 'x'", "'x' == asset_id"] {
+            assert!(EventFilter::parse(input).is_err(), "accepted: {input}");
+        }
+        assert!(EventFilter::parse(&"a".repeat(MAX_FILTER_LENGTH + 1)).is_err());
+    }
+}
@@ -1,18 +1,34 @@
+use super::admin;
+use super::blocks;
+use super::correlation::{new_correlation_id, with_correlation_id};
+use super::event_filter::EventFilter;
+use super::metrics::EventMetrics;
+use super::upstream::{client_builder, SendUpstream};
+use crate::config::HttpClientSettings;
 use crate::error::AppError;
+use crate::storage::events::{EventStore, StoredEvent};
+use crate::taproot::grpc::TapdGrpcClient;
 use crate::types::AppState;
 use axum::{
     extract::{Query, State, WebSocketUpgrade},
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::post,
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{get, post},
     Router,
 };
+use futures::{stream, Stream, StreamExt};
 use axum::extract::ws::{Message, WebSocket};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tracing::{info, instrument, warn};
+use tokio::sync::broadcast;
+use tracing::{debug, info, info_span, instrument, warn, Instrument};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DebugLevelRequest {
@@ -20,6 +36,55 @@ pub struct DebugLevelRequest {
     pub level_spec: String,
 }
 
+/// tapd's reply; `sub_systems` lists the available subsystems when `show` is set
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugLevelResponse {
+    pub sub_systems: String,
+}
+
+/// Log levels accepted in a `level_spec`
+const DEBUG_LEVELS: [&str; 7] = ["trace", "debug", "info", "warn", "error", "critical", "off"];
+/// tapd subsystems that may be targeted with `SUBSYSTEM=level`
+const DEBUG_SUBSYSTEMS: [&str; 12] = [
+    "TAPD", "ADDR", "FRTR", "GRDN", "PROF", "RFQS", "RPCS", "TADB", "TCHN", "UNIV", "SRVR", "CONF",
+];
+
+impl DebugLevelRequest {
+    /// Accepts a global level, `SUBSYSTEM=level` pairs, or both, comma-separated
+    /// (e.g. `info,TADB=debug`); an empty spec is only allowed with `show`
+    pub fn validate(&self) -> Result<(), AppError> {
+        let spec = self.level_spec.trim();
+        if spec.is_empty() {
+            return if self.show {
+                Ok(())
+            } else {
+                Err(AppError::InvalidInput("level_spec is required".to_string()))
+            };
+        }
+
+        for part in spec.split(',') {
+            let (subsystem, level) = match part.split_once('=') {
+                Some((subsystem, level)) => (Some(subsystem.trim()), level.trim()),
+                None => (None, part.trim()),
+            };
+            if !DEBUG_LEVELS.contains(&level) {
+                return Err(AppError::InvalidInput(format!(
+                    "Unknown log level '{level}'. Expected one of {}",
+                    DEBUG_LEVELS.join(", ")
+                )));
+            }
+            if let Some(subsystem) = subsystem.filter(|s| !DEBUG_SUBSYSTEMS.contains(s)) {
+                return Err(AppError::InvalidInput(format!(
+                    "Unknown subsystem '{subsystem}'. Expected one of {}",
+                    DEBUG_SUBSYSTEMS.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AssetMintRequest {
     pub short_response: bool,
@@ -37,14 +102,8 @@ pub struct AssetSendRequest {
     pub filter_label: Option<String>,
 }
 
-// Create a separate client for event subscriptions with longer timeout
-fn create_event_client() -> Result<Client, AppError> {
-    Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(Duration::from_secs(300)) // 5 minute timeout for event subscriptions
-        .build()
-        .map_err(|e| AppError::ValidationError(format!("Failed to create event client: {e}")))
-}
+/// How long a one-shot event subscription collects events before returning
+const EVENT_COLLECT_TIMEOUT: Duration = Duration::from_secs(300);
 
 #[instrument(skip(client, macaroon_hex, request))]
 pub async fn set_debug_level(
@@ -52,296 +111,669 @@ pub async fn set_debug_level(
     base_url: &str,
     macaroon_hex: &str,
     request: DebugLevelRequest,
-) -> Result<serde_json::Value, AppError> {
+) -> Result<DebugLevelResponse, AppError> {
     info!("Setting debug level: {}", request.level_spec);
     let url = format!("{base_url}/v1/taproot-assets/debuglevel");
     let response = client
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::from_response(response).await);
+    }
     response
-        .json::<serde_json::Value>()
+        .json::<DebugLevelResponse>()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))
 }
 
-#[instrument(skip(macaroon_hex, request))]
-pub async fn asset_mint_events(
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MintBatch {
+    #[serde(default)]
+    pub batch_key: String,
+    #[serde(default)]
+    pub batch_txid: String,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assets: Option<serde_json::Value>,
+}
+
+/// A minting batch state change, from `events/asset-mint`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AssetMintEvent {
+    pub timestamp: String,
+    pub batch_state: String,
+    pub batch: Option<MintBatch>,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ReceiveAddress {
+    pub encoded: String,
+    pub asset_id: String,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An inbound transfer to one of our addresses, from `events/asset-receive`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AssetReceiveEvent {
+    pub timestamp: String,
+    pub address: Option<ReceiveAddress>,
+    pub outpoint: String,
+    pub status: String,
+    pub confirmation_height: u32,
+    pub error: String,
+}
+
+/// A state transition of an outbound transfer, from `events/asset-send`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AssetSendEvent {
+    pub timestamp: String,
+    pub send_state: String,
+    pub parcel_type: String,
+    pub addresses: Vec<serde_json::Value>,
+    pub virtual_packets: Vec<String>,
+    pub passive_virtual_packets: Vec<String>,
+    pub anchor_transaction: Option<serde_json::Value>,
+    pub transfer: Option<serde_json::Value>,
+    pub error: String,
+    pub transfer_label: String,
+    pub next_send_state: String,
+}
+
+/// Events collected from a one-shot subscription request
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventSubscriptionResponse<T> {
+    pub events: Vec<T>,
+    pub timeout: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl<T> EventSubscriptionResponse<T> {
+    fn timed_out() -> Self {
+        Self {
+            events: Vec::new(),
+            timeout: true,
+            message: Some("No events received within timeout period".to_string()),
+        }
+    }
+}
+
+/// Converts an upstream stream message, with or without its `result`
+/// envelope, into a typed event
+pub fn parse_upstream_event<T: DeserializeOwned>(
+    mut payload: serde_json::Value,
+) -> Result<T, AppError> {
+    let event = match payload.get_mut("result") {
+        Some(result) => result.take(),
+        None => payload,
+    };
+    serde_json::from_value(event)
+        .map_err(|e| AppError::RequestError(format!("Unexpected event payload: {e}")))
+}
+
+async fn collect_events<T: DeserializeOwned>(
+    client: &Client,
     base_url: &str,
     macaroon_hex: &str,
-    request: AssetMintRequest,
-) -> Result<serde_json::Value, AppError> {
-    info!("Subscribing to asset mint events");
-    let event_client = create_event_client()?;
-    let url = format!("{base_url}/v1/taproot-assets/events/asset-mint");
+    event_type: &str,
+    request: &impl Serialize,
+) -> Result<EventSubscriptionResponse<T>, AppError> {
+    let url = format!("{base_url}/v1/taproot-assets/events/{event_type}");
 
-    let response = event_client
+    let response = client
         .post(&url)
+        .timeout(EVENT_COLLECT_TIMEOUT)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .json(&request)
+        .json(request)
         .send()
         .await;
 
-    match response {
-        Ok(resp) => {
-            let status = resp.status();
-            if status.is_success() {
-                resp.json::<serde_json::Value>()
-                    .await
-                    .map_err(|e| AppError::RequestError(e.to_string()))
-            } else {
-                let error_text = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-                Err(AppError::ValidationError(format!(
-                    "Event subscription failed with status {status}: {error_text}"
-                )))
-            }
-        }
+    let resp = match response {
+        Ok(resp) => resp,
         Err(e) if e.is_timeout() => {
-            warn!("Asset mint event subscription timed out");
-            Ok(serde_json::json!({
-                "events": [],
-                "timeout": true,
-                "message": "No events received within timeout period"
-            }))
+            warn!("Asset {} event subscription timed out", event_type);
+            return Ok(EventSubscriptionResponse::timed_out());
         }
-        Err(e) => Err(AppError::RequestError(e.to_string())),
+        Err(e) => return Err(AppError::RequestError(e.to_string())),
+    };
+
+    let status = resp.status();
+    if !status.is_success() {
+        let error_text = resp
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(AppError::ValidationError(format!(
+            "Event subscription failed with status {status}: {error_text}"
+        )));
     }
+
+    let mut body = match resp.text().await {
+        Ok(body) => body,
+        Err(e) if e.is_timeout() => {
+            warn!("Asset {} event subscription timed out", event_type);
+            return Ok(EventSubscriptionResponse::timed_out());
+        }
+        Err(e) => return Err(AppError::RequestError(e.to_string())),
+    };
+    body.push('\n');
+
+    let events = drain_stream_events(&mut body)
+        .into_iter()
+        .map(parse_upstream_event)
+        .collect::<Result<Vec<T>, AppError>>()?;
+
+    Ok(EventSubscriptionResponse {
+        events,
+        timeout: false,
+        message: None,
+    })
 }
 
-#[instrument(skip(macaroon_hex, request))]
+#[instrument(skip(client, macaroon_hex, request))]
+pub async fn asset_mint_events(
+    client: &Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    request: AssetMintRequest,
+) -> Result<EventSubscriptionResponse<AssetMintEvent>, AppError> {
+    info!("Subscribing to asset mint events");
+    collect_events(client, base_url, macaroon_hex, "asset-mint", &request).await
+}
+
+#[instrument(skip(client, macaroon_hex, request))]
 pub async fn asset_receive_events(
+    client: &Client,
     base_url: &str,
     macaroon_hex: &str,
     request: AssetReceiveRequest,
-) -> Result<serde_json::Value, AppError> {
+) -> Result<EventSubscriptionResponse<AssetReceiveEvent>, AppError> {
     info!("Subscribing to asset receive events");
-    let event_client = create_event_client()?;
-    let url = format!("{base_url}/v1/taproot-assets/events/asset-receive");
-
-    let response = event_client
-        .post(&url)
-        .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .json(&request)
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) => {
-            let status = resp.status();
-            if status.is_success() {
-                resp.json::<serde_json::Value>()
-                    .await
-                    .map_err(|e| AppError::RequestError(e.to_string()))
-            } else {
-                let error_text = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-                Err(AppError::ValidationError(format!(
-                    "Event subscription failed with status {status}: {error_text}"
-                )))
-            }
-        }
-        Err(e) if e.is_timeout() => {
-            warn!("Asset receive event subscription timed out");
-            Ok(serde_json::json!({
-                "events": [],
-                "timeout": true,
-                "message": "No events received within timeout period"
-            }))
-        }
-        Err(e) => Err(AppError::RequestError(e.to_string())),
-    }
+    collect_events(client, base_url, macaroon_hex, "asset-receive", &request).await
 }
 
-#[instrument(skip(macaroon_hex, request))]
+#[instrument(skip(client, macaroon_hex, request))]
 pub async fn asset_send_events(
+    client: &Client,
     base_url: &str,
     macaroon_hex: &str,
     request: AssetSendRequest,
-) -> Result<serde_json::Value, AppError> {
+) -> Result<EventSubscriptionResponse<AssetSendEvent>, AppError> {
     info!("Subscribing to asset send events");
-    let event_client = create_event_client()?;
-    let url = format!("{base_url}/v1/taproot-assets/events/asset-send");
-
-    let response = event_client
-        .post(&url)
-        .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .json(&request)
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) => {
-            let status = resp.status();
-            if status.is_success() {
-                resp.json::<serde_json::Value>()
-                    .await
-                    .map_err(|e| AppError::RequestError(e.to_string()))
-            } else {
-                let error_text = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-                Err(AppError::ValidationError(format!(
-                    "Event subscription failed with status {status}: {error_text}"
-                )))
-            }
-        }
-        Err(e) if e.is_timeout() => {
-            warn!("Asset send event subscription timed out");
-            Ok(serde_json::json!({
-                "events": [],
-                "timeout": true,
-                "message": "No events received within timeout period"
-            }))
-        }
-        Err(e) => Err(AppError::RequestError(e.to_string())),
-    }
+    collect_events(client, base_url, macaroon_hex, "asset-send", &request).await
 }
 
-// WebSocket proxy handler for events
-pub struct EventWebSocketProxyHandler {
-    pub client: Arc<reqwest::Client>,
-    pub base_url: String,
-    pub macaroon_hex: String,
+#[derive(Debug, Default, Deserialize)]
+pub struct EventQueryParams {
+    pub method: Option<String>,
+    pub short_response: Option<bool>,
+    pub filter_addr: Option<String>,
+    pub start_timestamp: Option<String>,
+    pub filter_script_key: Option<String>,
+    pub filter_label: Option<String>,
+    /// Expression evaluated against each event before it is forwarded,
+    /// e.g. `asset_id == "..." && amount > 1000`
+    pub filter: Option<String>,
 }
 
-impl EventWebSocketProxyHandler {
-    pub fn new(client: Arc<reqwest::Client>, base_url: String, macaroon_hex: String) -> Self {
-        Self {
-            client,
-            base_url,
-            macaroon_hex,
-        }
+impl EventQueryParams {
+    fn event_filter(&self) -> Result<Option<EventFilter>, AppError> {
+        self.filter
+            .as_deref()
+            .filter(|f| !f.trim().is_empty())
+            .map(EventFilter::parse)
+            .transpose()
     }
 
-    pub async fn handle_websocket(
-        self: Arc<Self>,
-        ws: WebSocketUpgrade,
-        backend_endpoint: String,
-        _enable_correlation: bool,
-    ) -> impl IntoResponse {
-        ws.on_upgrade(|socket| self.handle_socket(socket, backend_endpoint))
+    /// Builds the upstream subscription body for `event_type` from the query parameters
+    pub fn subscription_request(&self, event_type: &str) -> serde_json::Value {
+        let request = match event_type {
+            "asset-mint" => serde_json::to_value(AssetMintRequest {
+                short_response: self.short_response.unwrap_or(false),
+            }),
+            "asset-receive" => serde_json::to_value(AssetReceiveRequest {
+                filter_addr: self.filter_addr.clone(),
+                start_timestamp: self.start_timestamp.clone(),
+            }),
+            "asset-send" => serde_json::to_value(AssetSendRequest {
+                filter_script_key: self.filter_script_key.clone(),
+                filter_label: self.filter_label.clone(),
+            }),
+            _ => Ok(serde_json::json!({})),
+        };
+        request.unwrap_or_default()
     }
+}
 
-    async fn handle_socket(
-        self: Arc<Self>,
-        mut socket: WebSocket,
-        _backend_endpoint: String,
-    ) {
-        // For now, we'll implement a basic WebSocket proxy
-        // In a full implementation, you'd connect to the backend WebSocket
-        // and proxy messages between the client and backend
-        
-        while let Some(msg) = socket.recv().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    info!("Received WebSocket message: {}", text);
-                    // Echo back for now - replace with actual backend communication
-                    if let Err(e) = socket.send(Message::Text(text)).await {
-                        info!("Failed to send WebSocket message: {}", e);
+async fn generic_event_websocket_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EventQueryParams>,
+    ws: WebSocketUpgrade,
+    event_type: &str,
+) -> Response {
+    info!("Handling WebSocket connection for {} events", event_type);
+
+    let filter = match params.event_filter() {
+        Ok(filter) => filter,
+        Err(e) => return error_response(e).into_response(),
+    };
+    let request = params.subscription_request(event_type);
+    let events = state.event_broker.subscribe(event_type, request);
+    let event_type = event_type.to_string();
+
+    let broker = Arc::clone(&state.event_broker);
+    ws.on_upgrade(move |socket| forward_events(socket, broker, events, event_type, filter))
+}
+
+/// Relays broker events to a client socket until either side goes away
+async fn forward_events(
+    mut socket: WebSocket,
+    broker: Arc<EventBroker>,
+    mut events: broadcast::Receiver<EventMessage>,
+    event_type: String,
+    filter: Option<EventFilter>,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if !event.passes(filter.as_ref()) {
+                        continue;
+                    }
+                    if let Err(e) = socket.send(Message::Text(event.frame().to_string())).await {
+                        info!(
+                            "Failed to send WebSocket message (correlation_id={}): {}",
+                            event.correlation_id, e
+                        );
                         break;
                     }
                 }
-                Ok(Message::Close(_)) => {
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    broker.record_lag(&event_type, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    info!("Upstream {} event subscription ended", event_type);
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | None => {
                     info!("WebSocket connection closed");
                     break;
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     info!("WebSocket error: {}", e);
                     break;
                 }
-                _ => {}
-            }
+                Some(Ok(_)) => {}
+            },
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct EventQueryParams {
-    pub method: Option<String>,
-    pub short_response: Option<bool>,
-    pub filter_addr: Option<String>,
-    pub start_timestamp: Option<String>,
-    pub filter_script_key: Option<String>,
-    pub filter_label: Option<String>,
+async fn asset_mint_websocket_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EventQueryParams>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    generic_event_websocket_handler(State(state), Query(params), ws, "asset-mint").await
 }
 
-async fn generic_event_websocket_handler(
+async fn asset_receive_websocket_handler(
     State(state): State<AppState>,
     Query(params): Query<EventQueryParams>,
     ws: WebSocketUpgrade,
-    event_type: &str,
 ) -> impl IntoResponse {
-    info!("Handling WebSocket connection for {} events", event_type);
+    generic_event_websocket_handler(State(state), Query(params), ws, "asset-receive").await
+}
 
-    // Extract query parameters and forward them to the backend
-    let mut query_params = Vec::new();
-    query_params.push("method=POST".to_string());
-    
-    if let Some(short_response) = params.short_response {
-        query_params.push(format!("short_response={}", short_response));
-    }
-    if let Some(filter_addr) = params.filter_addr {
-        query_params.push(format!("filter_addr={}", filter_addr));
-    }
-    if let Some(start_timestamp) = params.start_timestamp {
-        query_params.push(format!("start_timestamp={}", start_timestamp));
-    }
-    if let Some(filter_script_key) = params.filter_script_key {
-        query_params.push(format!("filter_script_key={}", filter_script_key));
-    }
-    if let Some(filter_label) = params.filter_label {
-        query_params.push(format!("filter_label={}", filter_label));
-    }
+async fn asset_send_websocket_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EventQueryParams>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    generic_event_websocket_handler(State(state), Query(params), ws, "asset-send").await
+}
 
-    let query_string = query_params.join("&");
-    let endpoint = format!("/v1/taproot-assets/events/{event_type}?{}", query_string);
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
 
-    let ws_handler = Arc::new(EventWebSocketProxyHandler::new(
-        state.http_client.clone(),
-        state.base_url.0.clone(),
-        state.macaroon_hex.0.clone(),
-    ));
+async fn generic_event_sse_handler(
+    state: AppState,
+    params: EventQueryParams,
+    headers: HeaderMap,
+    event_type: &'static str,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, Json<serde_json::Value>)>
+{
+    let filter = params.event_filter().map_err(error_response)?;
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    info!(
+        "Handling SSE connection for {} events (last event id: {:?})",
+        event_type, last_event_id
+    );
 
-    ws_handler.handle_websocket(ws, endpoint, false).await
+    let request = params.subscription_request(event_type);
+    let (missed, events) = state
+        .event_broker
+        .subscribe_from(event_type, request, last_event_id);
+
+    let broker = Arc::clone(&state.event_broker);
+    let live = stream::unfold(events, move |mut events| {
+        let broker = Arc::clone(&broker);
+        async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => return Some((event, events)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        broker.record_lag(event_type, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+
+    let events = stream::iter(missed)
+        .chain(live)
+        .filter(move |event| std::future::ready(event.passes(filter.as_ref())))
+        .map(move |event| {
+            let name = if event.is_marker() { "resubscribed" } else { event_type };
+            Event::default()
+                .id(event.id.to_string())
+                .event(name)
+                .json_data(event.frame())
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
 
-async fn asset_mint_websocket_handler(
+async fn asset_mint_sse_handler(
     State(state): State<AppState>,
     Query(params): Query<EventQueryParams>,
-    ws: WebSocketUpgrade,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    generic_event_websocket_handler(State(state), Query(params), ws, "asset-mint").await
+    generic_event_sse_handler(state, params, headers, "asset-mint").await
 }
 
-async fn asset_receive_websocket_handler(
+async fn asset_receive_sse_handler(
     State(state): State<AppState>,
     Query(params): Query<EventQueryParams>,
-    ws: WebSocketUpgrade,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    generic_event_websocket_handler(State(state), Query(params), ws, "asset-receive").await
+    generic_event_sse_handler(state, params, headers, "asset-receive").await
 }
 
-async fn asset_send_websocket_handler(
+async fn asset_send_sse_handler(
     State(state): State<AppState>,
     Query(params): Query<EventQueryParams>,
-    ws: WebSocketUpgrade,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    generic_event_websocket_handler(State(state), Query(params), ws, "asset-send").await
+    generic_event_sse_handler(state, params, headers, "asset-send").await
+}
+
+const EVENT_TYPES: [&str; 3] = ["asset-mint", "asset-receive", "asset-send"];
+/// Event types available on the multiplexed `/events/stream` socket
+const STREAM_EVENT_TYPES: [&str; 4] = ["asset-mint", "asset-receive", "asset-send", "rfq"];
+
+/// Control messages a client sends on the multiplexed event stream
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum StreamControl {
+    Subscribe {
+        #[serde(rename = "type")]
+        event_type: String,
+        #[serde(flatten)]
+        params: EventQueryParams,
+    },
+    Unsubscribe {
+        #[serde(rename = "type")]
+        event_type: String,
+    },
+}
+
+/// An event as delivered on the multiplexed stream
+#[derive(Debug, Serialize)]
+struct StreamEvent<'a> {
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    id: u64,
+    correlation_id: &'a str,
+    event: &'a serde_json::Value,
+}
+
+async fn event_stream_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(|socket| handle_event_stream(socket, state))
+}
+
+/// Serves one socket carrying any mix of event types, each added or removed
+/// with subscribe/unsubscribe control messages
+async fn handle_event_stream(mut socket: WebSocket, state: AppState) {
+    info!("Opening multiplexed event stream");
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let mut subscriptions: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            outgoing = rx.recv() => {
+                let Some(text) = outgoing else { break };
+                if let Err(e) = socket.send(Message::Text(text)).await {
+                    info!("Failed to send WebSocket message: {}", e);
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = match serde_json::from_str::<StreamControl>(&text) {
+                        Ok(control) => apply_stream_control(&state, control, &mut subscriptions, &tx),
+                        Err(e) => Err(AppError::InvalidInput(format!("Invalid control message: {e}"))),
+                    };
+                    let reply = reply.unwrap_or_else(|e| {
+                        serde_json::json!({"type": "error", "error": e.to_string()})
+                    });
+                    if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("Multiplexed event stream closed");
+                    break;
+                }
+                Some(Err(e)) => {
+                    info!("WebSocket error: {}", e);
+                    break;
+                }
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    for (_, task) in subscriptions {
+        task.abort();
+    }
+}
+
+fn apply_stream_control(
+    state: &AppState,
+    control: StreamControl,
+    subscriptions: &mut HashMap<String, tokio::task::JoinHandle<()>>,
+    tx: &tokio::sync::mpsc::UnboundedSender<String>,
+) -> Result<serde_json::Value, AppError> {
+    match control {
+        StreamControl::Subscribe { event_type, params } => {
+            let Some(&event_type) = STREAM_EVENT_TYPES.iter().find(|t| **t == event_type) else {
+                return Err(AppError::InvalidInput(format!(
+                    "Unknown event type: {event_type}. Expected one of {}",
+                    STREAM_EVENT_TYPES.join(", ")
+                )));
+            };
+
+            let filter = params.event_filter()?;
+            let request = params.subscription_request(event_type);
+            let broker = Arc::clone(&state.event_broker);
+            let mut events = broker.subscribe(event_type, request);
+            let tx = tx.clone();
+
+            let task = tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            if !event.passes(filter.as_ref()) {
+                                continue;
+                            }
+                            let text = if event.is_marker() {
+                                event.frame().to_string()
+                            } else {
+                                let frame = StreamEvent {
+                                    event_type,
+                                    id: event.id,
+                                    correlation_id: &event.correlation_id,
+                                    event: &event.payload,
+                                };
+                                let Ok(text) = serde_json::to_string(&frame) else {
+                                    continue;
+                                };
+                                text
+                            };
+                            if tx.send(text).is_err() {
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            broker.record_lag(event_type, skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            let ended = serde_json::json!({"type": "ended", "event_type": event_type});
+                            let _ = tx.send(ended.to_string());
+                            break;
+                        }
+                    }
+                }
+            });
+
+            // Resubscribing replaces the previous filter for this type
+            if let Some(previous) = subscriptions.insert(event_type.to_string(), task) {
+                previous.abort();
+            }
+            Ok(serde_json::json!({"type": "subscribed", "event_type": event_type}))
+        }
+        StreamControl::Unsubscribe { event_type } => match subscriptions.remove(&event_type) {
+            Some(task) => {
+                task.abort();
+                Ok(serde_json::json!({"type": "unsubscribed", "event_type": event_type}))
+            }
+            None => Err(AppError::NotFound(format!(
+                "Not subscribed to {event_type} events"
+            ))),
+        },
+    }
+}
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+const MAX_HISTORY_LIMIT: usize = 1000;
+const RECORDER_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize)]
+pub struct EventHistoryQuery {
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub after_cursor: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventHistoryResponse {
+    pub events: Vec<StoredEvent>,
+    /// Cursor to pass as `after_cursor` to fetch the next page
+    pub next_cursor: i64,
+}
+
+/// Keeps an unfiltered subscription open for every event type and records
+/// each event to `store`, resubscribing whenever the upstream stream ends
+pub fn spawn_event_recorder(broker: Arc<EventBroker>, store: Arc<dyn EventStore>) {
+    for event_type in EVENT_TYPES {
+        let broker = Arc::clone(&broker);
+        let store = Arc::clone(&store);
+        tokio::spawn(async move {
+            let request = EventQueryParams::default().subscription_request(event_type);
+            loop {
+                let mut events = broker.subscribe(event_type, request.clone());
+                loop {
+                    match events.recv().await {
+                        Ok(event) if event.is_marker() => {}
+                        Ok(event) => {
+                            if let Err(e) = store.append(event_type, &event.payload).await {
+                                warn!("Failed to record {} event: {}", event_type, e);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            broker.record_lag(event_type, skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+                tokio::time::sleep(RECORDER_RETRY_DELAY).await;
+            }
+        });
+    }
+}
+
+async fn event_history_handler(
+    State(state): State<AppState>,
+    Query(query): Query<EventHistoryQuery>,
+) -> Result<Json<EventHistoryResponse>, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(event_type) = query.event_type.as_deref() {
+        if !EVENT_TYPES.contains(&event_type) {
+            return Err(error_response(AppError::InvalidInput(format!(
+                "Unknown event type: {event_type}. Expected one of {}",
+                EVENT_TYPES.join(", ")
+            ))));
+        }
+    }
+
+    let after_cursor = query.after_cursor.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+
+    let events = state
+        .event_store
+        .list(query.event_type.as_deref(), after_cursor, limit)
+        .await
+        .map_err(error_response)?;
+    let next_cursor = events.last().map_or(after_cursor, |e| e.cursor);
+
+    Ok(Json(EventHistoryResponse {
+        events,
+        next_cursor,
+    }))
 }
 
 async fn set_debug_level_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<DebugLevelRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<DebugLevelResponse>, (StatusCode, Json<serde_json::Value>)> {
+    // Changing daemon log levels is an operator action
+    admin::authorize(&state, &headers).map_err(error_response)?;
+    req.validate().map_err(error_response)?;
+
     match set_debug_level(
         &state.http_client,
         &state.base_url.0,
@@ -358,8 +790,9 @@ async fn set_debug_level_handler(
 async fn asset_mint_handler(
     State(state): State<AppState>,
     Json(req): Json<AssetMintRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<EventSubscriptionResponse<AssetMintEvent>>, (StatusCode, Json<serde_json::Value>)> {
     match asset_mint_events(
+        &state.http_client,
         &state.base_url.0,
         &state.macaroon_hex.0,
         req,
@@ -374,8 +807,9 @@ async fn asset_mint_handler(
 async fn asset_receive_handler(
     State(state): State<AppState>,
     Json(req): Json<AssetReceiveRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<EventSubscriptionResponse<AssetReceiveEvent>>, (StatusCode, Json<serde_json::Value>)> {
     match asset_receive_events(
+        &state.http_client,
         &state.base_url.0,
         &state.macaroon_hex.0,
         req,
@@ -390,8 +824,9 @@ async fn asset_receive_handler(
 async fn asset_send_handler(
     State(state): State<AppState>,
     Json(req): Json<AssetSendRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<EventSubscriptionResponse<AssetSendEvent>>, (StatusCode, Json<serde_json::Value>)> {
     match asset_send_events(
+        &state.http_client,
         &state.base_url.0,
         &state.macaroon_hex.0,
         req,
@@ -403,6 +838,460 @@ async fn asset_send_handler(
     }
 }
 
+/// An event received from an upstream subscription, tagged with a broker-wide
+/// sequence number that SSE clients echo back as `Last-Event-ID`
+#[derive(Debug)]
+pub struct BrokerEvent {
+    pub id: u64,
+    pub kind: BrokerEventKind,
+    pub payload: serde_json::Value,
+    /// Identifies the upstream connection the event arrived on
+    pub correlation_id: Arc<str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrokerEventKind {
+    /// An event received from tapd
+    Event,
+    /// Marks that the upstream subscription dropped and was re-established;
+    /// events emitted while it was down may have been missed
+    Resubscribed,
+}
+
+impl BrokerEvent {
+    pub fn is_marker(&self) -> bool {
+        self.kind != BrokerEventKind::Event
+    }
+
+    /// Whether a subscriber with `filter` should receive this event; markers
+    /// are always delivered
+    fn passes(&self, filter: Option<&EventFilter>) -> bool {
+        self.is_marker() || filter.is_none_or(|f| f.matches(&self.payload))
+    }
+
+    /// The payload as forwarded to clients, tagged with its correlation ID
+    pub fn frame(&self) -> serde_json::Value {
+        with_correlation_id(&self.payload, &self.correlation_id)
+    }
+}
+
+/// A broker event shared by all subscribers
+pub type EventMessage = Arc<BrokerEvent>;
+
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+/// Recent events kept per topic so reconnecting clients can resume
+const REPLAY_BUFFER_SIZE: usize = 256;
+/// Topics without subscribers are torn down on the next check, which also
+/// gives briefly disconnected clients time to resume
+const IDLE_TOPIC_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Why an upstream stream stopped without an error
+enum UpstreamEnd {
+    /// Every subscriber has gone away
+    Idle,
+    /// tapd closed the stream
+    Closed,
+}
+
+struct Topic {
+    event_type: String,
+    sender: broadcast::Sender<EventMessage>,
+    recent: VecDeque<EventMessage>,
+}
+
+/// Maintains one upstream subscription per event type and filter, fanning
+/// events out to any number of local subscribers
+pub struct EventBroker {
+    client: Client,
+    base_url: String,
+    macaroon_hex: String,
+    grpc: Option<TapdGrpcClient>,
+    topics: Mutex<HashMap<String, Topic>>,
+    next_event_id: AtomicU64,
+    metrics: Arc<EventMetrics>,
+}
+
+impl EventBroker {
+    pub fn new(
+        base_url: String,
+        macaroon_hex: String,
+        metrics: Arc<EventMetrics>,
+        transport: &HttpClientSettings,
+    ) -> Result<Self, AppError> {
+        // Subscriptions are long-lived, so only the connect phase is bounded
+        let client = client_builder(transport, "tapd event subscriptions")?
+            .build()
+            .map_err(|e| AppError::ValidationError(format!("Failed to create event client: {e}")))?;
+
+        Ok(Self {
+            client,
+            base_url,
+            macaroon_hex,
+            grpc: None,
+            topics: Mutex::new(HashMap::new()),
+            next_event_id: AtomicU64::new(1),
+            metrics,
+        })
+    }
+
+    /// Streams receive events over tapd's gRPC port instead of the REST
+    /// gateway's chunked JSON
+    pub fn with_grpc(mut self, grpc: Option<TapdGrpcClient>) -> Self {
+        self.grpc = grpc;
+        self
+    }
+
+    /// Subscribes to `event_type` events matching `request`, starting an upstream
+    /// subscription only if no other subscriber already shares it
+    pub fn subscribe(
+        self: &Arc<Self>,
+        event_type: &str,
+        request: serde_json::Value,
+    ) -> broadcast::Receiver<EventMessage> {
+        self.subscribe_from(event_type, request, None).1
+    }
+
+    /// Like [`EventBroker::subscribe`], but also returns buffered events newer
+    /// than `last_event_id` so a reconnecting client can catch up without gaps
+    pub fn subscribe_from(
+        self: &Arc<Self>,
+        event_type: &str,
+        request: serde_json::Value,
+        last_event_id: Option<u64>,
+    ) -> (Vec<EventMessage>, broadcast::Receiver<EventMessage>) {
+        let topic = format!("{event_type}:{request}");
+        let mut topics = self.topics.lock().unwrap();
+
+        if let Some(existing) = topics.get(&topic) {
+            debug!("Joining existing {} subscription", topic);
+            let missed = match last_event_id {
+                Some(last_id) => existing
+                    .recent
+                    .iter()
+                    .filter(|event| event.id > last_id)
+                    .cloned()
+                    .collect(),
+                None => Vec::new(),
+            };
+            let receiver = existing.sender.subscribe();
+            self.refresh_metrics(&topics);
+            return (missed, receiver);
+        }
+
+        let (sender, receiver) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        topics.insert(
+            topic.clone(),
+            Topic {
+                event_type: event_type.to_string(),
+                sender: sender.clone(),
+                recent: VecDeque::with_capacity(REPLAY_BUFFER_SIZE),
+            },
+        );
+        self.refresh_metrics(&topics);
+
+        let broker = Arc::clone(self);
+        let event_type = event_type.to_string();
+        tokio::spawn(async move {
+            broker.run_topic(&event_type, &topic, &request, &sender).await;
+            broker.remove_topic(&topic, &sender);
+        });
+
+        (Vec::new(), receiver)
+    }
+
+    pub fn topic_count(&self) -> usize {
+        self.topics.lock().unwrap().len()
+    }
+
+    /// Keeps the upstream subscription for a topic alive while it has
+    /// subscribers, reconnecting with jittered exponential backoff
+    async fn run_topic(
+        &self,
+        event_type: &str,
+        topic: &str,
+        request: &serde_json::Value,
+        sender: &broadcast::Sender<EventMessage>,
+    ) {
+        let mut attempt = 0u32;
+
+        loop {
+            // Each upstream connection gets its own ID, tagged on its log lines and events
+            let correlation_id: Arc<str> = new_correlation_id().into();
+            let span = info_span!("upstream", correlation_id = %correlation_id, event_type);
+            let result = self
+                .run_upstream(event_type, topic, request, sender, &correlation_id, attempt > 0)
+                .instrument(span)
+                .await;
+
+            match result {
+                Ok(UpstreamEnd::Idle) => {
+                    info!(
+                        "No subscribers left for {} events (correlation_id={})",
+                        event_type, correlation_id
+                    );
+                    return;
+                }
+                Ok(UpstreamEnd::Closed) => {
+                    info!(
+                        "Upstream {} event stream ended (correlation_id={})",
+                        event_type, correlation_id
+                    );
+                    attempt = 0;
+                }
+                Err(e) => warn!(
+                    "Upstream {} subscription failed (correlation_id={}): {}",
+                    event_type, correlation_id, e
+                ),
+            }
+
+            attempt += 1;
+            let delay = reconnect_delay(attempt);
+            if sender.receiver_count() == 0 {
+                return;
+            }
+            info!(
+                "Resubscribing to {} events in {:?} (attempt {})",
+                event_type, delay, attempt
+            );
+            tokio::time::sleep(delay).await;
+            if sender.receiver_count() == 0 {
+                return;
+            }
+        }
+    }
+
+    /// Streams newline-delimited JSON events from tapd until the stream ends or
+    /// no subscribers remain
+    async fn run_upstream(
+        &self,
+        event_type: &str,
+        topic: &str,
+        request: &serde_json::Value,
+        sender: &broadcast::Sender<EventMessage>,
+        correlation_id: &Arc<str>,
+        resubscribing: bool,
+    ) -> Result<UpstreamEnd, AppError> {
+        info!("Opening upstream {} event subscription", event_type);
+        if let (Some(grpc), "asset-receive") = (&self.grpc, event_type) {
+            let events = grpc.subscribe_receive_events(request).await?;
+            return self
+                .forward_grpc_events(events, topic, sender, correlation_id, resubscribing)
+                .await;
+        }
+        let url = format!("{}/v1/taproot-assets/{}", self.base_url, upstream_path(event_type));
+
+        let mut response = self
+            .client
+            .post(&url)
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .json(request)
+            .send_upstream()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AppError::ValidationError(format!(
+                "Event subscription failed with status {status}: {error_text}"
+            )));
+        }
+
+        if resubscribing {
+            let marker = serde_json::json!({"type": "resubscribed", "event_type": event_type});
+            self.publish(topic, BrokerEventKind::Resubscribed, marker, correlation_id);
+        }
+
+        let mut buffer = String::new();
+        let mut idle_check = tokio::time::interval_at(
+            tokio::time::Instant::now() + IDLE_TOPIC_CHECK_INTERVAL,
+            IDLE_TOPIC_CHECK_INTERVAL,
+        );
+
+        loop {
+            tokio::select! {
+                chunk = response.chunk() => {
+                    let Some(chunk) = chunk? else {
+                        return Ok(UpstreamEnd::Closed);
+                    };
+                    buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                    for event in drain_stream_events(&mut buffer) {
+                        self.publish(topic, BrokerEventKind::Event, event, correlation_id);
+                    }
+                }
+                _ = idle_check.tick() => {
+                    self.refresh_metrics(&self.topics.lock().unwrap());
+                    if sender.receiver_count() == 0 {
+                        return Ok(UpstreamEnd::Idle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Publishes events from a native gRPC stream until it ends or no
+    /// subscribers remain
+    async fn forward_grpc_events(
+        &self,
+        events: impl Stream<Item = Result<serde_json::Value, AppError>>,
+        topic: &str,
+        sender: &broadcast::Sender<EventMessage>,
+        correlation_id: &Arc<str>,
+        resubscribing: bool,
+    ) -> Result<UpstreamEnd, AppError> {
+        if resubscribing {
+            let marker = serde_json::json!({"type": "resubscribed", "event_type": "asset-receive"});
+            self.publish(topic, BrokerEventKind::Resubscribed, marker, correlation_id);
+        }
+
+        let mut events = std::pin::pin!(events);
+        let mut idle_check = tokio::time::interval_at(
+            tokio::time::Instant::now() + IDLE_TOPIC_CHECK_INTERVAL,
+            IDLE_TOPIC_CHECK_INTERVAL,
+        );
+
+        loop {
+            tokio::select! {
+                event = events.next() => {
+                    let Some(event) = event else {
+                        return Ok(UpstreamEnd::Closed);
+                    };
+                    self.publish(topic, BrokerEventKind::Event, event?, correlation_id);
+                }
+                _ = idle_check.tick() => {
+                    self.refresh_metrics(&self.topics.lock().unwrap());
+                    if sender.receiver_count() == 0 {
+                        return Ok(UpstreamEnd::Idle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Numbers an event, records it for replay and sends it to current subscribers
+    fn publish(
+        &self,
+        topic: &str,
+        kind: BrokerEventKind,
+        payload: serde_json::Value,
+        correlation_id: &Arc<str>,
+    ) {
+        let mut topics = self.topics.lock().unwrap();
+        let Some(topic) = topics.get_mut(topic) else {
+            return;
+        };
+
+        let event = Arc::new(BrokerEvent {
+            id: self.next_event_id.fetch_add(1, Ordering::Relaxed),
+            kind,
+            payload,
+            correlation_id: Arc::clone(correlation_id),
+        });
+        if topic.recent.len() == REPLAY_BUFFER_SIZE {
+            topic.recent.pop_front();
+        }
+        topic.recent.push_back(Arc::clone(&event));
+        // No receivers is fine; the idle check decides when to stop
+        let deliveries = topic.sender.send(event).unwrap_or(0);
+        if kind == BrokerEventKind::Event {
+            self.metrics.record_received(&topic.event_type, deliveries);
+        }
+        self.refresh_metrics(&topics);
+    }
+
+    /// Counts events a lagging subscriber skipped
+    pub fn record_lag(&self, event_type: &str, skipped: u64) {
+        warn!("{} event subscriber lagged, skipped {} events", event_type, skipped);
+        self.metrics.record_dropped(event_type, skipped);
+    }
+
+    fn remove_topic(&self, topic: &str, sender: &broadcast::Sender<EventMessage>) {
+        let mut topics = self.topics.lock().unwrap();
+        if topics.get(topic).is_some_and(|t| t.sender.same_channel(sender)) {
+            topics.remove(topic);
+        }
+        self.refresh_metrics(&topics);
+    }
+
+    fn refresh_metrics(&self, topics: &HashMap<String, Topic>) {
+        for event_type in STREAM_EVENT_TYPES {
+            let subscribers = topics
+                .values()
+                .filter(|t| t.event_type == event_type)
+                .map(|t| t.sender.receiver_count())
+                .sum();
+            self.metrics.set_subscribers(event_type, subscribers);
+        }
+        self.metrics.set_upstream_subscriptions(topics.len());
+    }
+}
+
+/// Exponential backoff with jitter, so many topics reconnecting after a tapd
+/// restart do not all retry at the same instant
+fn reconnect_delay(attempt: u32) -> Duration {
+    let base = RECONNECT_INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RECONNECT_MAX_BACKOFF);
+    base.mul_f64(0.5 + secp256k1::rand::random::<f64>() / 2.0)
+}
+
+/// Maps a broker event type to its tapd REST streaming path
+fn upstream_path(event_type: &str) -> String {
+    match event_type {
+        "rfq" => "rfq/ntfs".to_string(),
+        other => format!("events/{other}"),
+    }
+}
+
+/// Removes every complete line from `buffer` and parses it as a grpc-gateway
+/// stream message, unwrapping the `result` envelope
+fn drain_stream_events(buffer: &mut String) -> Vec<serde_json::Value> {
+    drain_stream_messages(buffer)
+        .into_iter()
+        .filter_map(|message| match message {
+            Ok(event) => Some(event),
+            Err(error) => {
+                warn!("Upstream event stream error: {}", error);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Removes every complete line from `buffer`, yielding each message's `result`
+/// or, for grpc-gateway error messages, its `error`
+pub(crate) fn drain_stream_messages(
+    buffer: &mut String,
+) -> Vec<Result<serde_json::Value, serde_json::Value>> {
+    let Some(last_newline) = buffer.rfind('\n') else {
+        return Vec::new();
+    };
+    let complete: String = buffer.drain(..=last_newline).collect();
+
+    complete
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(mut message) => {
+                if let Some(error) = message.get_mut("error") {
+                    return Some(Err(error.take()));
+                }
+                Some(Ok(message.get_mut("result").map(serde_json::Value::take).unwrap_or(message)))
+            }
+            Err(e) => {
+                warn!("Skipping malformed upstream message: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
 fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
     let status = error.status_code();
     (
@@ -417,24 +1306,46 @@ fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
 pub fn create_events_routes() -> Router<AppState> {
     Router::new()
         .route("/debuglevel", post(set_debug_level_handler))
+        .route("/history", get(event_history_handler))
+        .route("/stream", get(event_stream_handler))
+        .route("/blocks", get(blocks::blocks_ws_handler))
         .route(
-            "/events/asset-mint",
+            "/asset-mint",
             post(asset_mint_handler).get(asset_mint_websocket_handler),
         )
+        .route("/asset-mint/sse", get(asset_mint_sse_handler))
         .route(
-            "/events/asset-receive",
+            "/asset-receive",
             post(asset_receive_handler).get(asset_receive_websocket_handler),
         )
+        .route("/asset-receive/sse", get(asset_receive_sse_handler))
         .route(
-            "/events/asset-send",
+            "/asset-send",
             post(asset_send_handler).get(asset_send_websocket_handler),
         )
+        .route("/asset-send/sse", get(asset_send_sse_handler))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_metrics() -> Arc<EventMetrics> {
+        Arc::new(EventMetrics::new(&prometheus::Registry::new()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_broker_verifies_tapd_certificate() {
+        let url = crate::gateway::upstream::self_signed::spawn_server();
+        let broker = |tls_verify: bool| {
+            let transport = HttpClientSettings { tls_verify, ..HttpClientSettings::default() };
+            EventBroker::new(url.clone(), String::new(), test_metrics(), &transport).unwrap()
+        };
+
+        assert!(broker(true).client.get(&url).send().await.is_err());
+        assert!(broker(false).client.get(&url).send().await.is_ok());
+    }
+
     #[test]
     fn test_websocket_url_format_asset_mint() {
         let base_url = "wss://localhost:8080";
@@ -538,6 +1449,185 @@ mod tests {
         assert!(serialized.contains("label456"));
     }
 
+    #[test]
+    fn test_drain_stream_events_handles_partial_lines() {
+        let mut buffer = String::from(
+            "{\"result\": {\"timestamp\": \"1\"}}\n{\"error\": {\"code\": 2}}\n{\"result\": {\"times",
+        );
+
+        let events = drain_stream_events(&mut buffer);
+        assert_eq!(events, vec![serde_json::json!({"timestamp": "1"})]);
+        assert_eq!(buffer, "{\"result\": {\"times");
+
+        buffer.push_str("tamp\": \"2\"}}\n");
+        let events = drain_stream_events(&mut buffer);
+        assert_eq!(events, vec![serde_json::json!({"timestamp": "2"})]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_subscription_request_from_query() {
+        let params = EventQueryParams {
+            method: None,
+            short_response: Some(true),
+            filter_addr: Some("addr123".to_string()),
+            start_timestamp: None,
+            filter_script_key: None,
+            filter_label: None,
+            filter: Some(r#"asset_id == "abc""#.to_string()),
+        };
+
+        assert_eq!(
+            params.subscription_request("asset-mint"),
+            serde_json::json!({"short_response": true})
+        );
+        assert_eq!(
+            params.subscription_request("asset-receive"),
+            serde_json::json!({"filter_addr": "addr123", "start_timestamp": null})
+        );
+        assert!(params.event_filter().unwrap().is_some());
+
+        let invalid = EventQueryParams {
+            filter: Some("asset_id ==".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.event_filter().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broker_shares_subscription_per_topic() {
+        // Port 9 (discard) refuses connections, so upstream tasks end quickly
+        let broker = Arc::new(
+            EventBroker::new(
+                "http://127.0.0.1:9".to_string(),
+                String::new(),
+                test_metrics(),
+                &HttpClientSettings::default(),
+            )
+            .unwrap(),
+        );
+        let _a = broker.subscribe("asset-mint", serde_json::json!({"short_response": true}));
+        let _b = broker.subscribe("asset-mint", serde_json::json!({"short_response": true}));
+        let _c = broker.subscribe("asset-send", serde_json::json!({}));
+        assert!(broker.topic_count() <= 2);
+    }
+
+    #[test]
+    fn test_stream_control_deserialization() {
+        let control: StreamControl = serde_json::from_str(
+            r#"{"action": "subscribe", "type": "asset-receive", "filter_addr": "addr123", "filter": "amount > 5"}"#,
+        )
+        .unwrap();
+        match control {
+            StreamControl::Subscribe { event_type, params } => {
+                assert_eq!(event_type, "asset-receive");
+                assert_eq!(params.filter_addr.as_deref(), Some("addr123"));
+                assert!(params.event_filter().unwrap().is_some());
+            }
+            other => panic!("unexpected control message: {other:?}"),
+        }
+
+        let control: StreamControl =
+            serde_json::from_str(r#"{"action": "unsubscribe", "type": "rfq"}"#).unwrap();
+        assert!(matches!(control, StreamControl::Unsubscribe { event_type } if event_type == "rfq"));
+
+        assert!(serde_json::from_str::<StreamControl>(r#"{"action": "pause"}"#).is_err());
+    }
+
+    #[test]
+    fn test_reconnect_delay_is_jittered_and_capped() {
+        for attempt in 1..10 {
+            let delay = reconnect_delay(attempt);
+            let base = RECONNECT_INITIAL_BACKOFF
+                .saturating_mul(2u32.pow(attempt - 1))
+                .min(RECONNECT_MAX_BACKOFF);
+            assert!(delay >= base / 2 && delay <= base);
+        }
+        assert!(reconnect_delay(40) <= RECONNECT_MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_markers_bypass_filters() {
+        let filter = EventFilter::parse("amount > 100").unwrap();
+        let marker = BrokerEvent {
+            id: 1,
+            kind: BrokerEventKind::Resubscribed,
+            payload: serde_json::json!({"type": "resubscribed"}),
+            correlation_id: Arc::from("abc"),
+        };
+        let event = BrokerEvent {
+            id: 2,
+            kind: BrokerEventKind::Event,
+            payload: serde_json::json!({"amount": "50"}),
+            correlation_id: Arc::from("abc"),
+        };
+        assert!(marker.passes(Some(&filter)));
+        assert!(!event.passes(Some(&filter)));
+        assert!(event.passes(None));
+        assert_eq!(event.frame()["correlation_id"], "abc");
+        assert_eq!(marker.frame()["correlation_id"], "abc");
+    }
+
+    #[test]
+    fn test_debug_level_spec_allowlist() {
+        let request = |show: bool, level_spec: &str| DebugLevelRequest {
+            show,
+            level_spec: level_spec.to_string(),
+        };
+
+        assert!(request(false, "debug").validate().is_ok());
+        assert!(request(false, "info,TADB=debug, RFQS=trace").validate().is_ok());
+        assert!(request(true, "").validate().is_ok());
+        assert!(request(false, "").validate().is_err());
+        assert!(request(false, "verbose").validate().is_err());
+        assert!(request(false, "NOPE=debug").validate().is_err());
+        assert!(request(false, "TADB=debug;rm -rf").validate().is_err());
+    }
+
+    #[test]
+    fn test_upstream_path() {
+        assert_eq!(upstream_path("asset-mint"), "events/asset-mint");
+        assert_eq!(upstream_path("rfq"), "rfq/ntfs");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_from_replays_missed_events() {
+        let broker = Arc::new(
+            EventBroker::new(
+                "http://127.0.0.1:9".to_string(),
+                String::new(),
+                test_metrics(),
+                &HttpClientSettings::default(),
+            )
+            .unwrap(),
+        );
+        let request = serde_json::json!({"short_response": false});
+        let topic = format!("asset-mint:{request}");
+        // Seed the topic directly so no upstream task races the assertions
+        let (sender, _live) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        broker.topics.lock().unwrap().insert(
+            topic.clone(),
+            Topic {
+                event_type: "asset-mint".to_string(),
+                sender,
+                recent: VecDeque::new(),
+            },
+        );
+
+        let correlation_id: Arc<str> = Arc::from("test");
+        for n in 0..3 {
+            broker.publish(&topic, BrokerEventKind::Event, serde_json::json!({"n": n}), &correlation_id);
+        }
+
+        let (missed, _rx) = broker.subscribe_from("asset-mint", request.clone(), Some(1));
+        let ids: Vec<u64> = missed.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(missed[0].payload["n"], 1);
+
+        let (missed, _rx) = broker.subscribe_from("asset-mint", request, None);
+        assert!(missed.is_empty());
+    }
+
     #[test]
     fn test_event_schema_validation() {
         // Validate that expected response fields match the documented schemas
@@ -591,5 +1681,73 @@ mod tests {
         assert!(send_event.get("send_state").is_some());
         assert!(send_event.get("parcel_type").is_some());
         assert!(send_event.get("addresses").is_some());
+
+        let mint: AssetMintEvent = serde_json::from_value(mint_event).unwrap();
+        assert_eq!(mint.batch.unwrap().batch_txid, "txid123");
+        let receive: AssetReceiveEvent = serde_json::from_value(receive_event).unwrap();
+        assert_eq!(receive.confirmation_height, 100);
+        let send: AssetSendEvent = serde_json::from_value(send_event).unwrap();
+        assert_eq!(send.next_send_state, "SEND_STATE_COMPLETED");
+    }
+
+    #[test]
+    fn test_typed_events_round_trip() {
+        let mint = AssetMintEvent {
+            timestamp: "1234567890".to_string(),
+            batch_state: "BATCH_STATE_FINALIZED".to_string(),
+            batch: Some(MintBatch {
+                batch_key: "key123".to_string(),
+                batch_txid: "txid123".to_string(),
+                ..Default::default()
+            }),
+            error: String::new(),
+        };
+        let json = serde_json::to_value(&mint).unwrap();
+        assert_eq!(serde_json::from_value::<AssetMintEvent>(json).unwrap(), mint);
+
+        let mut extra = serde_json::Map::new();
+        extra.insert("amount".to_string(), serde_json::json!("100"));
+        let receive = AssetReceiveEvent {
+            timestamp: "1234567890".to_string(),
+            address: Some(ReceiveAddress {
+                encoded: "addr123".to_string(),
+                asset_id: "asset123".to_string(),
+                extra,
+            }),
+            outpoint: "txid:0".to_string(),
+            status: "ADDR_EVENT_STATUS_COMPLETED".to_string(),
+            confirmation_height: 812,
+            error: String::new(),
+        };
+        let json = serde_json::to_value(&receive).unwrap();
+        assert_eq!(json["address"]["amount"], "100");
+        assert_eq!(serde_json::from_value::<AssetReceiveEvent>(json).unwrap(), receive);
+
+        let send = AssetSendEvent {
+            timestamp: "1234567890".to_string(),
+            send_state: "SEND_STATE_BROADCAST".to_string(),
+            virtual_packets: vec!["cHNidP8=".to_string()],
+            transfer_label: "label123".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&send).unwrap();
+        assert_eq!(serde_json::from_value::<AssetSendEvent>(json).unwrap(), send);
+    }
+
+    #[test]
+    fn test_parse_upstream_event_unwraps_result() {
+        let wrapped = serde_json::json!({
+            "result": {"timestamp": "1", "batch_state": "BATCH_STATE_PENDING"}
+        });
+        let event: AssetMintEvent = parse_upstream_event(wrapped).unwrap();
+        assert_eq!(event.batch_state, "BATCH_STATE_PENDING");
+        assert!(event.batch.is_none());
+
+        let bare = serde_json::json!({"timestamp": "2", "confirmation_height": 5});
+        let event: AssetReceiveEvent = parse_upstream_event(bare).unwrap();
+        assert_eq!(event.confirmation_height, 5);
+
+        let invalid = serde_json::json!({"confirmation_height": "not a number"});
+        assert!(parse_upstream_event::<AssetReceiveEvent>(invalid).is_err());
     }
 }
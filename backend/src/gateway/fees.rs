@@ -0,0 +1,283 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::lnd::LndClient;
+use super::upstream::SendUpstream;
+use crate::config::FeeSettings;
+use crate::error::AppError;
+use crate::types::AppState;
+
+pub const DEFAULT_TARGET_CONF: u32 = 6;
+/// LND's wallet estimator accepts targets up to a week of blocks
+const MAX_TARGET_CONF: u32 = 1008;
+/// Confirmation targets behind the fastest/half-hour/hour/economy tiers
+const TIER_TARGETS: [u32; 4] = [1, 3, 6, 144];
+const MEMPOOL_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+pub struct FeeEstimateParams {
+    pub target_conf: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeSource {
+    Lnd,
+    Mempool,
+}
+
+/// Fee rates in sat/vB, rounded up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeRecommendations {
+    pub fastest: u64,
+    pub half_hour: u64,
+    pub hour: u64,
+    pub economy: u64,
+    pub minimum: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeEstimateResponse {
+    pub target_conf: u32,
+    /// Rate for `target_conf`, ready for `fee_rate_sat_per_vbyte`
+    pub sat_per_vbyte: u64,
+    pub recommendations: FeeRecommendations,
+    pub source: FeeSource,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndFeeEstimate {
+    sat_per_kw: String,
+    min_relay_fee_sat_per_kw: String,
+}
+
+/// mempool.space `/api/v1/fees/recommended`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MempoolFees {
+    fastest_fee: f64,
+    half_hour_fee: f64,
+    hour_fee: f64,
+    economy_fee: f64,
+    minimum_fee: f64,
+}
+
+/// Converts sat per kiloweight (LND's and tapd's unit) to sat/vB, rounding up
+pub fn sat_per_kw_to_vbyte(sat_per_kw: u64) -> u64 {
+    (sat_per_kw * 4).div_ceil(1000).max(1)
+}
+
+fn sat_per_vbyte(sat_per_kw: &str) -> Option<u64> {
+    sat_per_kw.parse().ok().map(sat_per_kw_to_vbyte)
+}
+
+fn round_rate(rate: f64) -> u64 {
+    (rate.ceil() as u64).max(1)
+}
+
+impl MempoolFees {
+    fn into_response(self, target_conf: u32) -> FeeEstimateResponse {
+        let recommendations = FeeRecommendations {
+            fastest: round_rate(self.fastest_fee),
+            half_hour: round_rate(self.half_hour_fee),
+            hour: round_rate(self.hour_fee),
+            economy: round_rate(self.economy_fee),
+            minimum: round_rate(self.minimum_fee),
+        };
+        let sat_per_vbyte = match target_conf {
+            1 => recommendations.fastest,
+            2..=3 => recommendations.half_hour,
+            4..=6 => recommendations.hour,
+            _ => recommendations.economy,
+        };
+        FeeEstimateResponse {
+            target_conf,
+            sat_per_vbyte,
+            recommendations,
+            source: FeeSource::Mempool,
+        }
+    }
+}
+
+/// Fee rate recommendations from LND's wallet estimator, falling back to a
+/// mempool.space-compatible API when LND is unset or unreachable
+pub struct FeeEstimator {
+    lnd: Option<Arc<LndClient>>,
+    client: reqwest::Client,
+    mempool_url: Option<String>,
+}
+
+impl FeeEstimator {
+    pub fn new(
+        lnd: Option<Arc<LndClient>>,
+        client: reqwest::Client,
+        settings: &FeeSettings,
+    ) -> Self {
+        Self {
+            lnd,
+            client,
+            mempool_url: settings
+                .mempool_url
+                .as_ref()
+                .map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+
+    pub async fn estimate(&self, target_conf: u32) -> Result<FeeEstimateResponse, AppError> {
+        if !(1..=MAX_TARGET_CONF).contains(&target_conf) {
+            return Err(AppError::InvalidInput(format!(
+                "target_conf must be between 1 and {MAX_TARGET_CONF}"
+            )));
+        }
+
+        let lnd_error = match &self.lnd {
+            Some(lnd) => match Self::estimate_lnd(lnd, target_conf).await {
+                Ok(estimate) => return Ok(estimate),
+                Err(e) => Some(e),
+            },
+            None => None,
+        };
+
+        match (&self.mempool_url, lnd_error) {
+            (Some(url), lnd_error) => {
+                if let Some(e) = lnd_error {
+                    warn!("LND fee estimate failed, using mempool fallback: {}", e);
+                }
+                self.estimate_mempool(url, target_conf).await
+            }
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(AppError::ServiceUnavailable(
+                "No fee source configured: set LND_REST_URL or FEE_ESTIMATE_MEMPOOL_URL"
+                    .to_string(),
+            )),
+        }
+    }
+
+    async fn estimate_lnd(
+        lnd: &LndClient,
+        target_conf: u32,
+    ) -> Result<FeeEstimateResponse, AppError> {
+        let fetch = |conf: u32| async move {
+            lnd.get::<LndFeeEstimate>(&format!("/v2/wallet/estimatefee/{conf}")).await
+        };
+        let (target, fastest, half_hour, hour, economy) = futures::try_join!(
+            fetch(target_conf),
+            fetch(TIER_TARGETS[0]),
+            fetch(TIER_TARGETS[1]),
+            fetch(TIER_TARGETS[2]),
+            fetch(TIER_TARGETS[3]),
+        )?;
+
+        let rate = |estimate: &LndFeeEstimate| {
+            sat_per_vbyte(&estimate.sat_per_kw).ok_or_else(|| {
+                AppError::RequestError(format!(
+                    "Unexpected LND fee estimate: {:?}",
+                    estimate.sat_per_kw
+                ))
+            })
+        };
+        Ok(FeeEstimateResponse {
+            target_conf,
+            sat_per_vbyte: rate(&target)?,
+            recommendations: FeeRecommendations {
+                fastest: rate(&fastest)?,
+                half_hour: rate(&half_hour)?,
+                hour: rate(&hour)?,
+                economy: rate(&economy)?,
+                minimum: sat_per_vbyte(&target.min_relay_fee_sat_per_kw).unwrap_or(1),
+            },
+            source: FeeSource::Lnd,
+        })
+    }
+
+    async fn estimate_mempool(
+        &self,
+        base_url: &str,
+        target_conf: u32,
+    ) -> Result<FeeEstimateResponse, AppError> {
+        let fees: MempoolFees = self
+            .client
+            .get(format!("{base_url}/api/v1/fees/recommended"))
+            .timeout(MEMPOOL_TIMEOUT)
+            .send_upstream()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(fees.into_response(target_conf))
+    }
+}
+
+pub async fn estimate_fees_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FeeEstimateParams>,
+) -> Result<Json<FeeEstimateResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let target_conf = params.target_conf.unwrap_or(DEFAULT_TARGET_CONF);
+    let estimate = state
+        .fee_estimator
+        .estimate(target_conf)
+        .await
+        .map_err(error_response)?;
+    Ok(Json(estimate))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sat_per_kw_conversion_rounds_up() {
+        assert_eq!(sat_per_vbyte("253"), Some(2));
+        assert_eq!(sat_per_vbyte("2500"), Some(10));
+        assert_eq!(sat_per_vbyte("0"), Some(1));
+        assert_eq!(sat_per_vbyte("fast"), None);
+    }
+
+    #[test]
+    fn test_mempool_fees_map_target_to_tier() {
+        let fees = || MempoolFees {
+            fastest_fee: 20.0,
+            half_hour_fee: 15.5,
+            hour_fee: 10.0,
+            economy_fee: 4.0,
+            minimum_fee: 1.0,
+        };
+        let estimate = fees().into_response(3);
+        assert_eq!(estimate.sat_per_vbyte, 16);
+        assert_eq!(estimate.source, FeeSource::Mempool);
+        assert_eq!(fees().into_response(1).sat_per_vbyte, 20);
+        assert_eq!(fees().into_response(6).sat_per_vbyte, 10);
+        assert_eq!(fees().into_response(144).sat_per_vbyte, 4);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_without_sources() {
+        let estimator = FeeEstimator::new(None, reqwest::Client::new(), &FeeSettings::default());
+        assert!(matches!(
+            estimator.estimate(6).await,
+            Err(AppError::ServiceUnavailable(_))
+        ));
+        assert!(matches!(
+            estimator.estimate(0).await,
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
@@ -1,7 +1,14 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
 use axum::{response::Json, http::StatusCode};
+use serde::Serialize;
 use serde_json::Value;
 use crate::types::AppState;
 
+/// How long one dependency check may take before it counts as down
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub async fn health() -> Json<Value> {
     Json(serde_json::json!({
         "status": "healthy",
@@ -9,15 +16,125 @@ pub async fn health() -> Json<Value> {
     }))
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    Up,
+    Down,
+    NotConfigured,
+}
+
+/// The outcome of checking one dependency
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCheck {
+    pub status: DependencyStatus,
+    /// Readiness fails when a required dependency is down
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DependencyCheck {
+    fn not_configured() -> Self {
+        Self {
+            status: DependencyStatus::NotConfigured,
+            required: false,
+            latency_ms: None,
+            error: None,
+        }
+    }
+
+    fn is_failing(&self) -> bool {
+        self.required && self.status == DependencyStatus::Down
+    }
+}
+
+/// Times `check`, counting an error or a timeout as down
+async fn check_dependency<F, E>(required: bool, check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let started = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("no answer within {}s", CHECK_TIMEOUT.as_secs())),
+    };
+    DependencyCheck {
+        status: if result.is_ok() { DependencyStatus::Up } else { DependencyStatus::Down },
+        required,
+        latency_ms: Some(started.elapsed().as_millis() as u64),
+        error: result.err(),
+    }
+}
+
+/// Checks tapd, the database and LND concurrently. tapd is always required
+/// and the database is required once `DATABASE_URL` is set; LND is only
+/// reported, since asset endpoints work without it.
 pub async fn readiness(
     axum::extract::State(state): axum::extract::State<AppState>
-) -> Result<Json<Value>, StatusCode> {
-    // Simple readiness check - you can enhance this based on your needs
-    match state.tapd_client.get_info().await {
-        Ok(_) => Ok(Json(serde_json::json!({
-            "status": "ready",
-            "services": {"taproot_assets": "up"}
-        }))),
-        Err(_) => Err(StatusCode::SERVICE_UNAVAILABLE),
+) -> (StatusCode, Json<Value>) {
+    let taproot_assets = check_dependency(true, async {
+        state.tapd_client.get_info_uncached().await.map(|_| ())
+    });
+    let database = async {
+        match &state.storage {
+            Some(storage) => check_dependency(true, storage.ping()).await,
+            None => DependencyCheck::not_configured(),
+        }
+    };
+    let lnd = async {
+        match &state.lnd_client {
+            Some(lnd) => {
+                check_dependency(false, async {
+                    lnd.get::<Value>("/v1/getinfo").await.map(|_| ())
+                })
+                .await
+            }
+            None => DependencyCheck::not_configured(),
+        }
+    };
+    let (taproot_assets, database, lnd) = tokio::join!(taproot_assets, database, lnd);
+
+    let ready = ![&taproot_assets, &database, &lnd].iter().any(|check| check.is_failing());
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "services": {
+                "taproot_assets": taproot_assets,
+                "database": database,
+                "lnd": lnd,
+            }
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+
+    #[tokio::test]
+    async fn test_check_dependency() {
+        let up = check_dependency(true, async { Ok::<_, AppError>(()) }).await;
+        assert_eq!(up.status, DependencyStatus::Up);
+        assert!(up.latency_ms.is_some());
+        assert!(!up.is_failing());
+
+        let optional = check_dependency(false, async {
+            Err(AppError::ServiceUnavailable("LND is down".to_string()))
+        })
+        .await;
+        assert_eq!(optional.status, DependencyStatus::Down);
+        assert_eq!(optional.error.as_deref(), Some("Service unavailable: LND is down"));
+        assert!(!optional.is_failing());
+
+        let required = DependencyCheck { required: true, ..optional };
+        assert!(required.is_failing());
+        assert!(!DependencyCheck::not_configured().is_failing());
     }
-}
\ No newline at end of file
+}
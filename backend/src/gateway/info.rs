@@ -1,5 +1,6 @@
 use axum::{response::Json, http::StatusCode, extract::State};
 use serde_json::Value;
+use super::upstream;
 use crate::types::AppState;
 
 pub async fn get_info(
@@ -7,6 +8,6 @@ pub async fn get_info(
 ) -> Result<Json<Value>, StatusCode> {
     match state.tapd_client.get_info().await {
         Ok(info) => Ok(Json(info)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(upstream::error_status(&e)),
     }
 }
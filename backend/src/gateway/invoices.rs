@@ -0,0 +1,426 @@
+use axum::{http::StatusCode, response::Json};
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Message, PublicKey, Secp256k1,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_GROUPS: usize = 6;
+const TIMESTAMP_GROUPS: usize = 7;
+/// 64-byte compact signature plus recovery ID, in 5-bit groups
+const SIGNATURE_GROUPS: usize = 104;
+
+const TAG_PAYMENT_HASH: u8 = 1;
+const TAG_ROUTE_HINT: u8 = 3;
+const TAG_FEATURES: u8 = 5;
+const TAG_EXPIRY: u8 = 6;
+const TAG_DESCRIPTION: u8 = 13;
+const TAG_PAYMENT_SECRET: u8 = 16;
+const TAG_PAYEE: u8 = 19;
+const TAG_DESCRIPTION_HASH: u8 = 23;
+const TAG_MIN_FINAL_CLTV: u8 = 24;
+
+/// Defaults BOLT11 applies when the `x` and `c` fields are absent
+const DEFAULT_EXPIRY_SECONDS: u64 = 3600;
+const DEFAULT_MIN_FINAL_CLTV_EXPIRY: u64 = 18;
+const ROUTE_HINT_HOP_BYTES: usize = 51;
+
+/// One private-channel hop the payer may route through to reach the payee
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouteHintHop {
+    pub node_id: String,
+    /// `block x tx x output`
+    pub short_channel_id: String,
+    pub fee_base_msat: u32,
+    pub fee_proportional_millionths: u32,
+    pub cltv_expiry_delta: u16,
+}
+
+/// A BOLT11 invoice decoded without any asset or node context
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedInvoice {
+    /// bitcoin, testnet, signet or regtest
+    pub network: String,
+    /// Absent for any-amount invoices
+    pub amount_msat: Option<u64>,
+    pub payment_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_hash: Option<String>,
+    /// Hex public key of the payee, recovered from the signature when not stated
+    pub payee: String,
+    /// Unix seconds
+    pub timestamp: u64,
+    pub expiry_seconds: u64,
+    pub expires_at: u64,
+    pub expired: bool,
+    pub min_final_cltv_expiry: u64,
+    pub route_hints: Vec<Vec<RouteHintHop>>,
+    /// Feature bits set in the invoice
+    pub features: Vec<u32>,
+}
+
+fn invalid(reason: impl Into<String>) -> AppError {
+    AppError::InvalidInput(format!("Invalid invoice: {}", reason.into()))
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 25;
+        let mut checksum = ((checksum & 0x1ff_ffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+        checksum
+    })
+}
+
+/// Splits a bech32 string into its lowercase HRP and 5-bit data, verifying the
+/// checksum; unlike segwit addresses, invoices have no length limit
+fn bech32_decode(encoded: &str) -> Result<(String, Vec<u8>), AppError> {
+    let lowercase = encoded.to_ascii_lowercase();
+    if encoded != lowercase && encoded != encoded.to_ascii_uppercase() {
+        return Err(invalid("mixed case"));
+    }
+    let (hrp, data) = lowercase
+        .rsplit_once('1')
+        .ok_or_else(|| invalid("missing separator"))?;
+    if hrp.is_empty() || data.len() < CHECKSUM_GROUPS {
+        return Err(invalid("too short"));
+    }
+
+    let data = data
+        .chars()
+        .map(|c| BECH32_CHARSET.find(c).map(|value| value as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("invalid character"))?;
+    let expanded = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 31));
+    if polymod(expanded.chain(data.iter().copied())) != 1 {
+        return Err(invalid("bad checksum"));
+    }
+
+    let groups = data.len() - CHECKSUM_GROUPS;
+    Ok((hrp.to_string(), data[..groups].to_vec()))
+}
+
+/// Packs 5-bit groups into bytes; `pad` keeps a final partial byte
+fn groups_to_bytes(groups: &[u8], pad: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(groups.len() * 5 / 8 + 1);
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for group in groups {
+        acc = (acc << 5) | *group as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    if pad && bits > 0 {
+        bytes.push((acc << (8 - bits)) as u8);
+    }
+    bytes
+}
+
+fn groups_to_u64(groups: &[u8]) -> Result<u64, AppError> {
+    if groups.len() > 12 {
+        return Err(invalid("integer field too long"));
+    }
+    Ok(groups.iter().fold(0, |acc, group| (acc << 5) | *group as u64))
+}
+
+/// Network and amount from an HRP such as `lnbc2500u` or `lntb1m`
+fn parse_hrp(hrp: &str) -> Result<(&'static str, Option<u64>), AppError> {
+    let rest = hrp.strip_prefix("ln").ok_or_else(|| invalid("not a lightning invoice"))?;
+    let (network, amount) = [
+        ("bcrt", "regtest"),
+        ("bc", "bitcoin"),
+        ("tbs", "signet"),
+        ("tb", "testnet"),
+    ]
+    .iter()
+    .find_map(|(prefix, network)| rest.strip_prefix(prefix).map(|amount| (*network, amount)))
+    .ok_or_else(|| invalid("unknown currency prefix"))?;
+    if amount.is_empty() {
+        return Ok((network, None));
+    }
+
+    let (digits, multiplier) = match amount.char_indices().last() {
+        Some((index, c)) if c.is_ascii_alphabetic() => (&amount[..index], Some(c)),
+        _ => (amount, None),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid("malformed amount"));
+    }
+    let value: u64 = digits.parse().map_err(|_| invalid("amount too large"))?;
+    let amount_msat = match multiplier {
+        None => value.checked_mul(100_000_000_000),
+        Some('m') => value.checked_mul(100_000_000),
+        Some('u') => value.checked_mul(100_000),
+        Some('n') => value.checked_mul(100),
+        Some('p') if value.is_multiple_of(10) => Some(value / 10),
+        Some('p') => return Err(invalid("sub-millisatoshi amount")),
+        Some(_) => return Err(invalid("unknown amount multiplier")),
+    }
+    .ok_or_else(|| invalid("amount too large"))?;
+    Ok((network, Some(amount_msat)))
+}
+
+fn route_hint(bytes: &[u8]) -> Result<Vec<RouteHintHop>, AppError> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(ROUTE_HINT_HOP_BYTES) {
+        return Err(invalid("malformed route hint"));
+    }
+    Ok(bytes
+        .chunks(ROUTE_HINT_HOP_BYTES)
+        .map(|hop| {
+            let scid = u64::from_be_bytes(hop[33..41].try_into().unwrap());
+            RouteHintHop {
+                node_id: hex::encode(&hop[..33]),
+                short_channel_id: format!(
+                    "{}x{}x{}",
+                    scid >> 40,
+                    (scid >> 16) & 0xff_ffff,
+                    scid & 0xffff
+                ),
+                fee_base_msat: u32::from_be_bytes(hop[41..45].try_into().unwrap()),
+                fee_proportional_millionths: u32::from_be_bytes(hop[45..49].try_into().unwrap()),
+                cltv_expiry_delta: u16::from_be_bytes(hop[49..51].try_into().unwrap()),
+            }
+        })
+        .collect())
+}
+
+/// Feature bits are numbered from the least significant bit of the last group
+fn feature_bits(groups: &[u8]) -> Vec<u32> {
+    let mut bits = Vec::new();
+    for (position, group) in groups.iter().rev().enumerate() {
+        for bit in 0..5 {
+            if group & (1 << bit) != 0 {
+                bits.push(position as u32 * 5 + bit);
+            }
+        }
+    }
+    bits
+}
+
+/// Decodes a BOLT11 invoice and checks its signature; `now` (unix seconds)
+/// only determines `expired`
+pub fn decode_bolt11(encoded: &str, now: u64) -> Result<DecodedInvoice, AppError> {
+    let encoded = encoded.trim();
+    let encoded = encoded
+        .get(..10)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("lightning:"))
+        .map_or(encoded, |_| &encoded[10..]);
+
+    let (hrp, data) = bech32_decode(encoded)?;
+    let (network, amount_msat) = parse_hrp(&hrp)?;
+    if data.len() < TIMESTAMP_GROUPS + SIGNATURE_GROUPS {
+        return Err(invalid("too short"));
+    }
+    let (signed, signature) = data.split_at(data.len() - SIGNATURE_GROUPS);
+    let timestamp = groups_to_u64(&signed[..TIMESTAMP_GROUPS])?;
+
+    let mut invoice = DecodedInvoice {
+        network: network.to_string(),
+        amount_msat,
+        payment_hash: String::new(),
+        payment_secret: None,
+        description: None,
+        description_hash: None,
+        payee: String::new(),
+        timestamp,
+        expiry_seconds: DEFAULT_EXPIRY_SECONDS,
+        expires_at: 0,
+        expired: false,
+        min_final_cltv_expiry: DEFAULT_MIN_FINAL_CLTV_EXPIRY,
+        route_hints: Vec::new(),
+        features: Vec::new(),
+    };
+    let mut stated_payee = None;
+
+    let mut fields = &signed[TIMESTAMP_GROUPS..];
+    while !fields.is_empty() {
+        if fields.len() < 3 {
+            return Err(invalid("truncated field"));
+        }
+        let tag = fields[0];
+        let len = (fields[1] as usize) << 5 | fields[2] as usize;
+        let value = fields.get(3..3 + len).ok_or_else(|| invalid("truncated field"))?;
+        fields = &fields[3 + len..];
+
+        // Fields of an unexpected length are skipped, as BOLT11 requires
+        match (tag, len) {
+            (TAG_PAYMENT_HASH, 52) => {
+                invoice.payment_hash = hex::encode(groups_to_bytes(value, false))
+            }
+            (TAG_PAYMENT_SECRET, 52) => {
+                invoice.payment_secret = Some(hex::encode(groups_to_bytes(value, false)))
+            }
+            (TAG_DESCRIPTION_HASH, 52) => {
+                invoice.description_hash = Some(hex::encode(groups_to_bytes(value, false)))
+            }
+            (TAG_PAYEE, 53) => stated_payee = Some(groups_to_bytes(value, false)),
+            (TAG_DESCRIPTION, _) => {
+                let description = String::from_utf8(groups_to_bytes(value, false))
+                    .map_err(|_| invalid("description is not UTF-8"))?;
+                invoice.description = Some(description);
+            }
+            (TAG_EXPIRY, _) => invoice.expiry_seconds = groups_to_u64(value)?,
+            (TAG_MIN_FINAL_CLTV, _) => invoice.min_final_cltv_expiry = groups_to_u64(value)?,
+            (TAG_ROUTE_HINT, _) => invoice
+                .route_hints
+                .push(route_hint(&groups_to_bytes(value, false))?),
+            (TAG_FEATURES, _) => invoice.features = feature_bits(value),
+            _ => {}
+        }
+    }
+    if invoice.payment_hash.is_empty() {
+        return Err(invalid("missing payment hash"));
+    }
+
+    let mut preimage = hrp.into_bytes();
+    preimage.extend(groups_to_bytes(signed, true));
+    let digest = Message::from_digest(Sha256::digest(&preimage).into());
+    let signature = groups_to_bytes(signature, false);
+    let recovery_id = RecoveryId::from_i32(signature[64] as i32)
+        .map_err(|_| invalid("bad signature recovery ID"))?;
+    let payee = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .and_then(|signature| Secp256k1::verification_only().recover_ecdsa(&digest, &signature))
+        .map_err(|_| invalid("bad signature"))?;
+    if let Some(stated) = stated_payee {
+        let stated = PublicKey::from_slice(&stated).map_err(|_| invalid("bad payee key"))?;
+        if stated != payee {
+            return Err(invalid("signature does not match the payee"));
+        }
+    }
+    invoice.payee = payee.to_string();
+
+    invoice.expires_at = invoice.timestamp.saturating_add(invoice.expiry_seconds);
+    invoice.expired = now >= invoice.expires_at;
+    Ok(invoice)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeBolt11Request {
+    pub invoice: String,
+}
+
+/// Decodes any BOLT11 invoice locally so the UI can preview it before picking
+/// an asset or RFQ path to pay with
+pub async fn decode_bolt11_handler(
+    Json(request): Json<DecodeBolt11Request>,
+) -> Result<Json<DecodedInvoice>, (StatusCode, Json<serde_json::Value>)> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let invoice = decode_bolt11(&request.invoice, now).map_err(error_response)?;
+    Ok(Json(invoice))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from the BOLT11 specification
+    const DONATION: &str = "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vjjsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql";
+    const COFFEE: &str = "lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rh";
+    const HASHED: &str = "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqhp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqs9qrsgq7ea976txfraylvgzuxs8kgcw23ezlrszfnh8r6qtfpr6cxga50aj6txm9rxrydzd06dfeawfk6swupvz4erwnyutnjq7x39ymw6j38gp7ynn44";
+    const PAYEE: &str = "03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad";
+    const PAYMENT_HASH: &str = "0001020304050607080900010203040506070809000102030405060708090102";
+
+    #[test]
+    fn test_decode_any_amount_invoice() {
+        let invoice = decode_bolt11(DONATION, 0).unwrap();
+        assert_eq!(invoice.network, "bitcoin");
+        assert_eq!(invoice.amount_msat, None);
+        assert_eq!(invoice.payment_hash, PAYMENT_HASH);
+        assert_eq!(invoice.payee, PAYEE);
+        assert_eq!(
+            invoice.description.as_deref(),
+            Some("Please consider supporting this project")
+        );
+        assert_eq!(invoice.timestamp, 1_496_314_658);
+        assert_eq!(invoice.expiry_seconds, DEFAULT_EXPIRY_SECONDS);
+        assert_eq!(invoice.payment_secret, Some("11".repeat(32)));
+        assert_eq!(invoice.features, vec![8, 14]);
+        assert!(!invoice.expired);
+    }
+
+    #[test]
+    fn test_decode_amount_and_expiry() {
+        let invoice = decode_bolt11(&format!("lightning:{}", COFFEE.to_uppercase()), 0).unwrap();
+        assert_eq!(invoice.amount_msat, Some(250_000_000));
+        assert_eq!(invoice.description.as_deref(), Some("1 cup coffee"));
+        assert_eq!(invoice.expiry_seconds, 60);
+        assert_eq!(invoice.payee, PAYEE);
+
+        let later = decode_bolt11(COFFEE, invoice.timestamp + 60).unwrap();
+        assert!(later.expired);
+    }
+
+    #[test]
+    fn test_decode_description_hash() {
+        let invoice = decode_bolt11(HASHED, 0).unwrap();
+        assert_eq!(invoice.amount_msat, Some(2_000_000_000));
+        assert_eq!(invoice.description, None);
+        assert_eq!(
+            invoice.description_hash.as_deref(),
+            Some("3925b6f67e2c340036ed12093dd44e0368df1b6ea26c53dbe4811f58fd5db8c1")
+        );
+    }
+
+    #[test]
+    fn test_parse_hrp_amounts() {
+        assert_eq!(parse_hrp("lntb1m").unwrap(), ("testnet", Some(100_000_000)));
+        assert_eq!(parse_hrp("lnbcrt10n").unwrap(), ("regtest", Some(1_000)));
+        assert_eq!(parse_hrp("lntbs20p").unwrap(), ("signet", Some(2)));
+        assert!(parse_hrp("lnbc25p").is_err());
+        assert!(parse_hrp("lnbc1x").is_err());
+        assert!(parse_hrp("lnxy").is_err());
+    }
+
+    #[test]
+    fn test_rejects_corrupted_invoices() {
+        let mut tampered = COFFEE.to_string();
+        tampered.replace_range(20..21, "q");
+        assert!(decode_bolt11(&tampered, 0).is_err());
+        assert!(decode_bolt11("lnbc1qqqqqq", 0).is_err());
+        assert!(decode_bolt11("", 0).is_err());
+    }
+
+    #[test]
+    fn test_route_hint_and_feature_bits() {
+        let mut hop = vec![2u8; 33];
+        hop.extend_from_slice(&((600_000u64 << 40) | (12 << 16) | 1).to_be_bytes());
+        hop.extend_from_slice(&1_000u32.to_be_bytes());
+        hop.extend_from_slice(&100u32.to_be_bytes());
+        hop.extend_from_slice(&40u16.to_be_bytes());
+        let hint = route_hint(&hop).unwrap();
+        assert_eq!(hint[0].short_channel_id, "600000x12x1");
+        assert_eq!(hint[0].fee_base_msat, 1_000);
+        assert_eq!(hint[0].cltv_expiry_delta, 40);
+
+        assert_eq!(feature_bits(&[1, 0]), vec![5]);
+    }
+}
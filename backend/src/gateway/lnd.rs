@@ -0,0 +1,556 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use base64::Engine;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use super::events::drain_stream_messages;
+use super::upstream::{client_builder, SendUpstream};
+use crate::config::{HttpClientSettings, LndSettings};
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// Bound on unary calls; streaming calls stay open until LND ends them
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default route-finding budget for payments that do not set one
+const DEFAULT_PAYMENT_TIMEOUT_SECONDS: u32 = 60;
+/// Default and largest page of the BTC payment history
+const PAYMENTS_DEFAULT_LIMIT: u64 = 100;
+const PAYMENTS_MAX_LIMIT: u64 = 1000;
+
+/// Minimal LND REST client for the chain, channel and BTC wallet endpoints the gateway uses
+pub struct LndClient {
+    client: Client,
+    rest_url: String,
+    macaroon_hex: String,
+}
+
+impl LndClient {
+    /// Returns `None` when LND is not configured. Certificates are checked as
+    /// `transport` says, trusting LND's own `tls.cert` from `LND_TLS_CERT_PATH`.
+    pub fn new(
+        settings: &LndSettings,
+        transport: &HttpClientSettings,
+    ) -> Result<Option<Self>, AppError> {
+        let Some(rest_url) = settings.rest_url.clone() else {
+            return Ok(None);
+        };
+
+        let transport = HttpClientSettings {
+            ca_cert_path: settings.tls_cert_path.clone(),
+            ..transport.clone()
+        };
+        let client = client_builder(&transport, "LND")?
+            .build()
+            .map_err(|e| AppError::ValidationError(format!("Failed to create LND client: {e}")))?;
+
+        Ok(Some(Self {
+            client,
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+            macaroon_hex: settings.macaroon_hex.clone(),
+        }))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, AppError> {
+        let request = self
+            .client
+            .get(format!("{}{path}", self.rest_url))
+            .timeout(REQUEST_TIMEOUT);
+        let response = self.send(request).await?;
+        Ok(response.json::<T>().await?)
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, AppError> {
+        let request = self
+            .client
+            .post(format!("{}{path}", self.rest_url))
+            .timeout(REQUEST_TIMEOUT)
+            .json(body);
+        let response = self.send(request).await?;
+        Ok(response.json::<T>().await?)
+    }
+
+    /// Starts a server-streaming POST call, returning the open response whose
+    /// body carries newline-delimited updates
+    pub async fn post_stream<B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<reqwest::Response, AppError> {
+        let request = self.client.post(format!("{}{path}", self.rest_url)).json(body);
+        self.send(request).await
+    }
+
+    /// Starts a server-streaming DELETE call, returning the open response whose
+    /// body carries newline-delimited updates
+    pub async fn delete_stream(&self, path: &str) -> Result<reqwest::Response, AppError> {
+        let request = self.client.delete(format!("{}{path}", self.rest_url));
+        self.send(request).await
+    }
+
+    /// Sends a payment through the router and waits for LND to report it
+    /// SUCCEEDED or FAILED
+    pub async fn send_payment<B: Serialize>(&self, body: &B) -> Result<LndPayment, AppError> {
+        let mut response = self.post_stream("/v2/router/send", body).await?;
+
+        let mut buffer = String::new();
+        while let Some(chunk) = response.chunk().await? {
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+            for message in drain_stream_messages(&mut buffer) {
+                let payment: LndPayment = match message {
+                    Ok(payment) => serde_json::from_value(payment)?,
+                    Err(error) => {
+                        return Err(AppError::RequestError(format!("Payment failed: {error}")));
+                    }
+                };
+                if payment.is_final() {
+                    return Ok(payment);
+                }
+            }
+        }
+
+        Err(AppError::RequestError(
+            "LND ended the payment stream before the payment completed".to_string(),
+        ))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, AppError> {
+        let response = request
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .send_upstream()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::from_response(response).await);
+        }
+        Ok(response)
+    }
+}
+
+/// Returns the configured client, or 503 when LND is not set up
+pub(crate) fn lnd_client(state: &AppState) -> Result<Arc<LndClient>, AppError> {
+    state
+        .lnd_client
+        .clone()
+        .ok_or_else(|| AppError::ServiceUnavailable("LND is not configured".to_string()))
+}
+
+/// LND's Payment message, as streamed by SendPaymentV2 and listed by ListPayments
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LndPayment {
+    pub payment_hash: String,
+    pub payment_preimage: String,
+    pub payment_request: String,
+    pub status: String,
+    pub value_sat: String,
+    pub fee_msat: String,
+    pub creation_time_ns: String,
+    pub payment_index: String,
+    pub failure_reason: String,
+}
+
+impl LndPayment {
+    pub fn is_final(&self) -> bool {
+        self.status == "SUCCEEDED" || self.status == "FAILED"
+    }
+
+    pub fn fee_msat(&self) -> u64 {
+        self.fee_msat.parse().unwrap_or(0)
+    }
+
+    /// `None` unless LND reported an actual failure
+    pub fn failure_reason(&self) -> Option<String> {
+        (!self.failure_reason.is_empty() && self.failure_reason != "FAILURE_REASON_NONE")
+            .then(|| self.failure_reason.clone())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LndInfo {
+    pub identity_pubkey: String,
+    pub alias: String,
+    pub version: String,
+    pub block_height: u32,
+    pub synced_to_chain: bool,
+    pub num_active_channels: u32,
+    pub num_peers: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBtcInvoiceRequest {
+    pub amount_sat: u64,
+    pub memo: Option<String>,
+    pub expiry_seconds: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndAddInvoiceResponse {
+    r_hash: String,
+    payment_request: String,
+    add_index: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BtcInvoice {
+    pub payment_request: String,
+    /// Hex-encoded payment hash
+    pub payment_hash: String,
+    pub add_index: u64,
+}
+
+impl From<LndAddInvoiceResponse> for BtcInvoice {
+    fn from(response: LndAddInvoiceResponse) -> Self {
+        let payment_hash = base64::engine::general_purpose::STANDARD
+            .decode(&response.r_hash)
+            .map(hex::encode)
+            .unwrap_or_default();
+        Self {
+            payment_request: response.payment_request,
+            payment_hash,
+            add_index: response.add_index.parse().unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayBtcInvoiceRequest {
+    pub payment_request: String,
+    /// Required only for invoices that do not carry an amount
+    pub amount_sat: Option<u64>,
+    pub fee_limit_sat: Option<u64>,
+    pub timeout_seconds: Option<u32>,
+}
+
+impl PayBtcInvoiceRequest {
+    fn lnd_payment(&self) -> Result<serde_json::Value, AppError> {
+        if self.payment_request.trim().is_empty() {
+            return Err(AppError::InvalidInput("payment_request must not be empty".to_string()));
+        }
+        let mut body = serde_json::json!({
+            "payment_request": self.payment_request.trim(),
+            "timeout_seconds": self.timeout_seconds.unwrap_or(DEFAULT_PAYMENT_TIMEOUT_SECONDS),
+            "fee_limit_sat": self.fee_limit_sat.unwrap_or_default().to_string(),
+            "no_inflight_updates": true,
+        });
+        if let Some(amount_sat) = self.amount_sat {
+            body["amt"] = amount_sat.to_string().into();
+        }
+        Ok(body)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BtcPayment {
+    pub payment_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_request: Option<String>,
+    /// IN_FLIGHT, SUCCEEDED or FAILED
+    pub status: String,
+    pub value_sat: u64,
+    pub fee_msat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// Unix seconds
+    pub created_at: i64,
+    pub payment_index: u64,
+}
+
+impl From<LndPayment> for BtcPayment {
+    fn from(payment: LndPayment) -> Self {
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+        let preimage = Some(payment.payment_preimage.clone())
+            .filter(|p| !p.is_empty() && p.bytes().any(|b| b != b'0'));
+        Self {
+            fee_msat: payment.fee_msat(),
+            failure_reason: payment.failure_reason(),
+            value_sat: payment.value_sat.parse().unwrap_or(0),
+            created_at: payment.creation_time_ns.parse::<i64>().unwrap_or(0) / 1_000_000_000,
+            payment_index: payment.payment_index.parse().unwrap_or(0),
+            payment_request: non_empty(payment.payment_request),
+            preimage,
+            payment_hash: payment.payment_hash,
+            status: payment.status,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListBtcPaymentsParams {
+    pub limit: Option<u64>,
+    /// `next_offset` from the previous page
+    pub offset: Option<u64>,
+}
+
+impl ListBtcPaymentsParams {
+    fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(PAYMENTS_DEFAULT_LIMIT)
+            .clamp(1, PAYMENTS_MAX_LIMIT)
+    }
+
+    /// Newest first; LND treats the index offset as exclusive when reversed
+    fn lnd_path(&self) -> String {
+        let mut path = format!(
+            "/v1/payments?include_incomplete=true&reversed=true&max_payments={}",
+            self.limit()
+        );
+        if let Some(offset) = self.offset {
+            path.push_str(&format!("&index_offset={offset}"));
+        }
+        path
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LndPayments {
+    payments: Vec<LndPayment>,
+    first_index_offset: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BtcPaymentsResponse {
+    pub payments: Vec<BtcPayment>,
+    /// Offset of the next (older) page, absent on the last page
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BtcAddressType {
+    #[default]
+    P2tr,
+    P2wkh,
+}
+
+impl BtcAddressType {
+    fn lnd_type(&self) -> &'static str {
+        match self {
+            BtcAddressType::P2tr => "TAPROOT_PUBKEY",
+            BtcAddressType::P2wkh => "WITNESS_PUBKEY_HASH",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NewBtcAddressRequest {
+    #[serde(default)]
+    pub address_type: BtcAddressType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BtcAddress {
+    pub address: String,
+}
+
+async fn info_handler(
+    State(state): State<AppState>,
+) -> Result<Json<LndInfo>, (StatusCode, Json<serde_json::Value>)> {
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    let info = lnd.get("/v1/getinfo").await.map_err(error_response)?;
+    Ok(Json(info))
+}
+
+#[instrument(skip(state, request))]
+async fn create_invoice_handler(
+    State(state): State<AppState>,
+    Json(request): Json<CreateBtcInvoiceRequest>,
+) -> Result<Json<BtcInvoice>, (StatusCode, Json<serde_json::Value>)> {
+    if request.amount_sat == 0 {
+        return Err(error_response(AppError::InvalidInput(
+            "amount_sat must be greater than 0".to_string(),
+        )));
+    }
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    info!("Creating BTC invoice for {} sat", request.amount_sat);
+
+    let mut body = serde_json::json!({"value": request.amount_sat.to_string()});
+    if let Some(memo) = &request.memo {
+        body["memo"] = memo.clone().into();
+    }
+    if let Some(expiry) = request.expiry_seconds {
+        body["expiry"] = expiry.to_string().into();
+    }
+    let invoice: LndAddInvoiceResponse =
+        lnd.post("/v1/invoices", &body).await.map_err(error_response)?;
+    Ok(Json(invoice.into()))
+}
+
+#[instrument(skip(state, request))]
+async fn pay_invoice_handler(
+    State(state): State<AppState>,
+    Json(request): Json<PayBtcInvoiceRequest>,
+) -> Result<Json<BtcPayment>, (StatusCode, Json<serde_json::Value>)> {
+    let body = request.lnd_payment().map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    info!("Paying BTC invoice");
+
+    let payment = lnd.send_payment(&body).await.map_err(error_response)?;
+    Ok(Json(payment.into()))
+}
+
+async fn list_payments_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ListBtcPaymentsParams>,
+) -> Result<Json<BtcPaymentsResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    let listing: LndPayments = lnd.get(&params.lnd_path()).await.map_err(error_response)?;
+
+    let full_page = listing.payments.len() as u64 == params.limit();
+    let next_offset = listing
+        .first_index_offset
+        .parse::<u64>()
+        .ok()
+        .filter(|offset| full_page && *offset > 1);
+    Ok(Json(BtcPaymentsResponse {
+        payments: listing.payments.into_iter().map(BtcPayment::from).collect(),
+        next_offset,
+    }))
+}
+
+async fn new_address_handler(
+    State(state): State<AppState>,
+    request: Option<Json<NewBtcAddressRequest>>,
+) -> Result<Json<BtcAddress>, (StatusCode, Json<serde_json::Value>)> {
+    let address_type = request.map(|Json(r)| r.address_type).unwrap_or_default();
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    let address = lnd
+        .get(&format!("/v1/newaddress?type={}", address_type.lnd_type()))
+        .await
+        .map_err(error_response)?;
+    Ok(Json(address))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+/// Plain BTC wallet endpoints served straight from LND, mounted at `/v1/lnd`
+pub fn create_lnd_routes() -> Router<AppState> {
+    Router::new()
+        .route("/info", get(info_handler))
+        .route("/invoices", post(create_invoice_handler))
+        .route("/payments", post(pay_invoice_handler).get(list_payments_handler))
+        .route("/addresses", post(new_address_handler))
+}
+
+/// Converts a transaction hash from LND's REST encoding (base64 bytes in
+/// internal byte order) to the usual reversed-hex txid
+pub fn display_txid(encoded: &str) -> Option<String> {
+    let mut hash = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    if hash.len() != 32 {
+        return None;
+    }
+    hash.reverse();
+    Some(hex::encode(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::upstream::self_signed;
+
+    #[tokio::test]
+    async fn test_client_verifies_lnd_certificate() {
+        let lnd = |tls_verify: bool, tls_cert_path: Option<&str>| {
+            let settings = LndSettings {
+                rest_url: Some(self_signed::spawn_server()),
+                macaroon_hex: String::new(),
+                tls_cert_path: tls_cert_path.map(str::to_string),
+            };
+            let transport = HttpClientSettings { tls_verify, ..HttpClientSettings::default() };
+            LndClient::new(&settings, &transport).unwrap().unwrap()
+        };
+
+        let verifying = lnd(true, None).get::<serde_json::Value>("/v1/getinfo").await;
+        assert!(verifying.is_err());
+        let trusting = lnd(true, Some(self_signed::CERT_PATH));
+        assert!(trusting.get::<serde_json::Value>("/v1/getinfo").await.is_ok());
+        let unverified = lnd(false, None);
+        assert!(unverified.get::<serde_json::Value>("/v1/getinfo").await.is_ok());
+    }
+
+    #[test]
+    fn test_btc_invoice_hash_is_hex() {
+        let invoice = BtcInvoice::from(LndAddInvoiceResponse {
+            r_hash: base64::engine::general_purpose::STANDARD.encode([0xab; 32]),
+            payment_request: "lnbc1".to_string(),
+            add_index: "7".to_string(),
+        });
+        assert_eq!(invoice.payment_hash, "ab".repeat(32));
+        assert_eq!(invoice.add_index, 7);
+    }
+
+    #[test]
+    fn test_btc_payment_conversion() {
+        let payment = BtcPayment::from(LndPayment {
+            payment_hash: "aa".repeat(32),
+            payment_preimage: "0".repeat(64),
+            status: "FAILED".to_string(),
+            value_sat: "1500".to_string(),
+            fee_msat: "0".to_string(),
+            creation_time_ns: "1700000000123456789".to_string(),
+            payment_index: "12".to_string(),
+            failure_reason: "FAILURE_REASON_NO_ROUTE".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(payment.preimage, None);
+        assert_eq!(payment.payment_request, None);
+        assert_eq!(payment.value_sat, 1500);
+        assert_eq!(payment.created_at, 1_700_000_000);
+        assert_eq!(payment.failure_reason.as_deref(), Some("FAILURE_REASON_NO_ROUTE"));
+    }
+
+    #[test]
+    fn test_btc_request_shapes() {
+        let pay = PayBtcInvoiceRequest {
+            payment_request: " lnbc1 ".to_string(),
+            amount_sat: Some(21),
+            fee_limit_sat: None,
+            timeout_seconds: None,
+        };
+        let body = pay.lnd_payment().unwrap();
+        assert_eq!(body["payment_request"], "lnbc1");
+        assert_eq!(body["amt"], "21");
+        assert_eq!(body["timeout_seconds"], DEFAULT_PAYMENT_TIMEOUT_SECONDS);
+
+        let params = ListBtcPaymentsParams {
+            limit: Some(5000),
+            offset: Some(40),
+        };
+        assert_eq!(
+            params.lnd_path(),
+            "/v1/payments?include_incomplete=true&reversed=true&max_payments=1000&index_offset=40"
+        );
+
+        let request: NewBtcAddressRequest =
+            serde_json::from_value(serde_json::json!({"address_type": "p2wkh"})).unwrap();
+        assert_eq!(request.address_type.lnd_type(), "WITNESS_PUBKEY_HASH");
+    }
+}
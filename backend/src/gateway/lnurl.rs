@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+/// Bound on each LNURL round trip to the recipient's domain
+const LNURL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A LUD-16 Lightning Address such as `alice@example.com`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightningAddress {
+    pub user: String,
+    pub domain: String,
+}
+
+impl LightningAddress {
+    pub fn parse(address: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::InvalidInput(format!("Invalid Lightning Address: {address}"));
+        let (user, domain) = address.trim().split_once('@').ok_or_else(invalid)?;
+        let user = user.to_ascii_lowercase();
+        let domain = domain.to_ascii_lowercase();
+
+        let user_ok = !user.is_empty()
+            && user
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'));
+        let domain_ok = domain.contains('.')
+            && url::Host::parse(&domain).is_ok_and(|host| matches!(host, url::Host::Domain(_)));
+        if !user_ok || !domain_ok {
+            return Err(invalid());
+        }
+        Ok(Self { user, domain })
+    }
+
+    /// LUD-16 well-known endpoint serving the LNURL-pay parameters
+    pub fn lnurlp_url(&self) -> String {
+        format!("https://{}/.well-known/lnurlp/{}", self.domain, self.user)
+    }
+}
+
+impl std::fmt::Display for LightningAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.user, self.domain)
+    }
+}
+
+/// LUD-06 payRequest parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayRequest {
+    pub callback: String,
+    pub min_sendable: u64,
+    pub max_sendable: u64,
+    /// JSON-encoded metadata whose SHA-256 the invoice must commit to
+    pub metadata: String,
+    #[serde(default)]
+    pub comment_allowed: usize,
+}
+
+/// LUD-06 callback response carrying the invoice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceResponse {
+    pub pr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_action: Option<serde_json::Value>,
+}
+
+/// LNURL services report failures as `{"status": "ERROR", "reason": ...}`
+fn service_error(body: &serde_json::Value) -> Option<AppError> {
+    (body.get("status").and_then(serde_json::Value::as_str) == Some("ERROR")).then(|| {
+        let reason = body
+            .get("reason")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown error");
+        AppError::RequestError(format!("LNURL service error: {reason}"))
+    })
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Result<serde_json::Value, AppError> {
+    let response = client.get(url).timeout(LNURL_TIMEOUT).send().await?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| AppError::RequestError(format!("Invalid LNURL response from {url}: {e}")))?;
+    if let Some(error) = service_error(&body) {
+        return Err(error);
+    }
+    if !status.is_success() {
+        return Err(AppError::RequestError(format!("LNURL request to {url} failed: {status}")));
+    }
+    Ok(body)
+}
+
+/// Resolves a Lightning Address to its payRequest parameters
+pub async fn fetch_pay_request(
+    client: &reqwest::Client,
+    address: &LightningAddress,
+) -> Result<PayRequest, AppError> {
+    let body = get_json(client, &address.lnurlp_url()).await?;
+    let not_pay_request =
+        || AppError::RequestError(format!("{address} did not return an LNURL payRequest"));
+    if body.get("tag").and_then(serde_json::Value::as_str) != Some("payRequest") {
+        return Err(not_pay_request());
+    }
+    serde_json::from_value(body).map_err(|_| not_pay_request())
+}
+
+impl PayRequest {
+    /// Callback URL requesting an invoice for `amount_msat`
+    pub fn callback_url(
+        &self,
+        amount_msat: u64,
+        comment: Option<&str>,
+    ) -> Result<String, AppError> {
+        if !(self.min_sendable..=self.max_sendable).contains(&amount_msat) {
+            return Err(AppError::InvalidInput(format!(
+                "Amount must be between {} and {} msat for this address",
+                self.min_sendable, self.max_sendable
+            )));
+        }
+        let mut url = url::Url::parse(&self.callback)
+            .map_err(|e| AppError::RequestError(format!("Invalid LNURL callback: {e}")))?;
+        if url.scheme() != "https" {
+            return Err(AppError::RequestError("LNURL callback must use https".to_string()));
+        }
+        url.query_pairs_mut().append_pair("amount", &amount_msat.to_string());
+        if let Some(comment) = comment.filter(|comment| !comment.is_empty()) {
+            if comment.chars().count() > self.comment_allowed {
+                return Err(AppError::InvalidInput(format!(
+                    "Comment exceeds the {} characters this address accepts",
+                    self.comment_allowed
+                )));
+            }
+            url.query_pairs_mut().append_pair("comment", comment);
+        }
+        Ok(url.into())
+    }
+
+    /// Hex SHA-256 of the metadata, which the invoice's description hash must match
+    pub fn metadata_hash(&self) -> String {
+        hex::encode(Sha256::digest(self.metadata.as_bytes()))
+    }
+}
+
+/// Requests an invoice from the payRequest callback
+pub async fn fetch_invoice(
+    client: &reqwest::Client,
+    pay_request: &PayRequest,
+    amount_msat: u64,
+    comment: Option<&str>,
+) -> Result<InvoiceResponse, AppError> {
+    let url = pay_request.callback_url(amount_msat, comment)?;
+    let body = get_json(client, &url).await?;
+    Ok(serde_json::from_value(body)?)
+}
+
+/// Checks a decoded invoice (LND's PayReq) against what was requested, so a
+/// misbehaving service cannot substitute a different amount or payee metadata
+pub fn check_invoice(
+    pay_req: &serde_json::Value,
+    pay_request: &PayRequest,
+    amount_msat: u64,
+    now: i64,
+) -> Result<(), AppError> {
+    let number = |field: &str| {
+        pay_req
+            .get(field)
+            .and_then(|value| match value {
+                serde_json::Value::String(text) => text.parse::<i64>().ok(),
+                other => other.as_i64(),
+            })
+            .unwrap_or(0)
+    };
+
+    if number("num_msat") != amount_msat as i64 {
+        return Err(AppError::RequestError(format!(
+            "Invoice amount {} msat does not match the requested {amount_msat} msat",
+            number("num_msat")
+        )));
+    }
+    let description_hash = pay_req
+        .get("description_hash")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    if !description_hash.eq_ignore_ascii_case(&pay_request.metadata_hash()) {
+        return Err(AppError::RequestError(
+            "Invoice description hash does not match the LNURL metadata".to_string(),
+        ));
+    }
+    if number("timestamp") + number("expiry") <= now {
+        return Err(AppError::RequestError("Invoice has already expired".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pay_request() -> PayRequest {
+        PayRequest {
+            callback: "https://example.com/lnurlp/alice/callback?token=1".to_string(),
+            min_sendable: 1_000,
+            max_sendable: 10_000,
+            metadata: r#"[["text/plain","Pay alice"]]"#.to_string(),
+            comment_allowed: 5,
+        }
+    }
+
+    #[test]
+    fn test_parse_lightning_address() {
+        let address = LightningAddress::parse(" Alice.B@Example.com ").unwrap();
+        assert_eq!(address.to_string(), "alice.b@example.com");
+        assert_eq!(
+            address.lnurlp_url(),
+            "https://example.com/.well-known/lnurlp/alice.b"
+        );
+
+        assert!(LightningAddress::parse("alice").is_err());
+        assert!(LightningAddress::parse("@example.com").is_err());
+        assert!(LightningAddress::parse("al ice@example.com").is_err());
+        assert!(LightningAddress::parse("alice@localhost").is_err());
+        assert!(LightningAddress::parse("alice@10.0.0.1").is_err());
+    }
+
+    #[test]
+    fn test_callback_url_checks_amount_and_comment() {
+        let params = pay_request();
+        assert_eq!(
+            params.callback_url(2_000, Some("hi")).unwrap(),
+            "https://example.com/lnurlp/alice/callback?token=1&amount=2000&comment=hi"
+        );
+        assert!(params.callback_url(500, None).is_err());
+        assert!(params.callback_url(2_000, Some("too long")).is_err());
+    }
+
+    #[test]
+    fn test_check_invoice() {
+        let params = pay_request();
+        let pay_req = serde_json::json!({
+            "num_msat": "2000",
+            "description_hash": params.metadata_hash(),
+            "timestamp": "1000",
+            "expiry": "3600"
+        });
+        assert!(check_invoice(&pay_req, &params, 2_000, 2_000).is_ok());
+        assert!(check_invoice(&pay_req, &params, 3_000, 2_000).is_err());
+        assert!(check_invoice(&pay_req, &params, 2_000, 5_000).is_err());
+
+        let mut other_metadata = pay_req.clone();
+        other_metadata["description_hash"] = "00".repeat(32).into();
+        assert!(check_invoice(&other_metadata, &params, 2_000, 2_000).is_err());
+    }
+
+    #[test]
+    fn test_service_error() {
+        let body = serde_json::json!({"status": "ERROR", "reason": "no route"});
+        let error = service_error(&body).unwrap();
+        assert!(error.to_string().contains("no route"));
+        assert!(service_error(&serde_json::json!({"pr": "lnbc1"})).is_none());
+    }
+}
@@ -1,29 +1,81 @@
 use axum::{
-    response::{Json, IntoResponse},
-    http::StatusCode,
-    extract::{State, WebSocketUpgrade, ws::WebSocket, ws::Message},
-    response::Response,
+    response::Json,
+    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Path, State, WebSocketUpgrade, ws::CloseFrame, ws::WebSocket, ws::Message},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Mutex;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 use chrono::Utc;
 use base64::Engine;
 use bitcoin::bech32;
-use lazy_static::lazy_static;
 
 use crate::types::AppState;
 use crate::error::AppError;
+use crate::storage::challenges::{ChallengeData, ChallengeStore};
+use crate::storage::receivers::ReceiverRepo;
+use super::admin::is_admin;
+use super::mailbox_chunks::{split_into_chunks, ChunkAssembler, ChunkFrame};
+use super::mailbox_limits::MailboxLimiter;
+use super::mailbox_registry::MailboxRegistry;
+use super::mailbox_webhooks::{generate_webhook_secret, validate_webhook_url, WebhookPayload};
+use super::upstream::SendUpstream;
 use crate::crypto::{
-    derive_public_key_from_receiver_id, verify_schnorr_signature, verify_signature,
+    derive_public_key_from_receiver_id, ecies_decrypt, ecies_encrypt, verify_schnorr_signature,
+    verify_signature,
 };
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterReceiverRequest {
+    pub receiver_id: String,
+    pub public_key: String,
+    pub address: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub webhook_url: Option<String>,
+}
+
+/// Proof that a request comes from the holder of a receiver's registered
+/// key: its signature over a challenge from
+/// `POST /mailbox/receivers/:id/challenge`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiverProof {
+    pub challenge_id: String,
+    /// Schnorr for x-only keys, ECDSA otherwise; hex or base64
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptRequest {
+    pub receiver_id: String,
+    /// Base64 encoded plaintext
+    pub plaintext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecryptRequest {
+    /// Hex encoded secret key of the receiver
+    pub private_key: String,
+    /// Base64 encoded ECIES payload, as returned by the encrypt endpoint
+    pub encrypted_payload: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisterReceiverResponse {
+    #[serde(flatten)]
+    pub receiver: ReceiverInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_secret: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReceiveRequest {
     pub init: serde_json::Value,
@@ -52,23 +104,10 @@ struct ConnectionLimits {
     last_reset: Instant,
 }
 
-#[derive(Debug, Clone)]
-struct ChallengeData {
-    challenge_id: String,
-    timestamp: i64,
-    nonce: String,
-    issued_at: Instant,
-}
-
-lazy_static! {
-    static ref ACTIVE_CHALLENGES: Mutex<HashMap<String, ChallengeData>> = Mutex::new(HashMap::new());
-}
-
 const IDLE_TIMEOUT_SECS: u64 = 300; // 5 minutes
-const RATE_LIMIT_MESSAGES_PER_MINUTE: u32 = 60;
-const MAX_MESSAGE_SIZE_BYTES: usize = 64 * 1024; // 64KB
 const CHALLENGE_EXPIRY_SECS: u64 = 300; // 5 minutes
 const TIMESTAMP_TOLERANCE_SECS: i64 = 30; // 30 seconds tolerance for clock skew
+const CHUNK_ENVELOPE_BYTES: usize = 256;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct WebSocketMailboxMessage {
@@ -76,6 +115,16 @@ struct WebSocketMailboxMessage {
     init: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     auth_sig: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ack: Option<MailboxAck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunk: Option<ChunkFrame>,
+}
+
+/// Message IDs acknowledged by a receiver, echoed back once marked delivered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct MailboxAck {
+    message_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,9 +137,12 @@ struct MailboxResponse {
     messages: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     eos: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ack: Option<MailboxAck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunk: Option<ChunkFrame>,
 }
 
-// Database types (simplified for now)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReceiverInfo {
     pub receiver_id: String,
@@ -100,18 +152,17 @@ pub struct ReceiverInfo {
     pub last_seen: i64,
     pub is_active: bool,
     pub metadata: Option<serde_json::Value>,
-}
-
-// Simplified database trait
-#[async_trait::async_trait]
-pub trait Database {
-    async fn store_receiver_info(&self, info: &ReceiverInfo) -> Result<(), AppError>;
-    async fn get_receiver_info(&self, receiver_id: &str) -> Result<Option<ReceiverInfo>, AppError>;
+    /// HTTPS callback that receives mail while the receiver has no live connection
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// HMAC key for webhook signatures; only revealed once, at registration
+    #[serde(default, skip_serializing)]
+    pub webhook_secret: Option<String>,
 }
 
 // Simplified monitoring trait
 #[async_trait::async_trait]
-pub trait Monitoring {
+pub trait Monitoring: Send + Sync {
     async fn record_connection(&self, connection_id: String, remote_addr: String);
     async fn record_connection_closed(&self, connection_id: &str);
     async fn record_message_received(&self, connection_id: &str, size: usize);
@@ -132,7 +183,7 @@ pub async fn get_mailbox_info(
     let response = client
         .get(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -154,7 +205,7 @@ pub async fn receive_mail(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -176,7 +227,7 @@ pub async fn send_mail(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| AppError::RequestError(e.to_string()))?;
     response
@@ -229,7 +280,29 @@ pub async fn receive_handler(
 pub async fn send_handler(
     State(state): State<AppState>,
     Json(request): Json<SendRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Response {
+    if let Err(exceeded) = state
+        .mailbox_limiter
+        .check_message(&request.receiver_id, request.encrypted_payload.len())
+    {
+        warn!("Rejecting mail for receiver {}: {}", request.receiver_id, exceeded.reason);
+        return exceeded.into_response();
+    }
+
+    // Receivers without a live connection get the mail pushed to their webhook
+    let webhook = offline_webhook_target(&state, &request.receiver_id)
+        .await
+        .map(|(url, secret)| {
+            let payload = WebhookPayload {
+                receiver_id: request.receiver_id.clone(),
+                encrypted_payload: request.encrypted_payload.clone(),
+                tx_proof: request.tx_proof.clone(),
+                expiry_block_height: request.expiry_block_height,
+                delivered_at: Utc::now().timestamp(),
+            };
+            (url, secret, payload)
+        });
+
     let result = send_mail(
         &state.http_client,
         &state.base_url.0,
@@ -239,26 +312,68 @@ pub async fn send_handler(
     .await;
     
     match result {
-        Ok(value) => Ok(Json(value)),
+        Ok(value) => {
+            if let Some((url, secret, payload)) = webhook {
+                state.mailbox_webhooks.dispatch(url, secret, payload);
+            }
+            Json(value).into_response()
+        }
         Err(e) => {
             error!("Failed to send mail: {}", e);
-            Err(e.status_code())
+            e.status_code().into_response()
+        }
+    }
+}
+
+/// Returns the webhook URL and secret of a receiver that has no live mailbox connection
+async fn offline_webhook_target(state: &AppState, receiver_id: &str) -> Option<(String, String)> {
+    if state.mailbox_registry.connection_count(receiver_id) > 0 {
+        return None;
+    }
+
+    let database = state.mailbox_receivers.as_deref()?;
+    match database.get_receiver_info(receiver_id).await {
+        Ok(Some(ReceiverInfo {
+            webhook_url: Some(url),
+            webhook_secret: Some(secret),
+            is_active: true,
+            ..
+        })) => Some((url, secret)),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to look up webhook for receiver {}: {}", receiver_id, e);
+            None
         }
     }
 }
 
 pub async fn websocket_handler(
-    _ws: WebSocketUpgrade,
-    State(_state): State<AppState>,
+    ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    State(state): State<AppState>,
 ) -> Response {
-    // TODO: Fix threading issues with Database and Monitoring traits
-    // ws.on_upgrade(|socket| handle_websocket(socket, state))
-    axum::http::StatusCode::NOT_IMPLEMENTED.into_response()
+    ws.on_upgrade(move |socket| handle_websocket(socket, state, remote_addr))
 }
 
-async fn handle_websocket(socket: WebSocket, state: AppState) {
+async fn handle_websocket(socket: WebSocket, state: AppState, remote_addr: SocketAddr) {
     let connection_id = Uuid::new_v4().to_string();
-    info!("Mailbox WebSocket connection established: {}", connection_id);
+    info!(
+        "Mailbox WebSocket connection established: {} from {}",
+        connection_id, remote_addr
+    );
+
+    let database = state.mailbox_receivers.as_deref();
+    let monitoring = state.mailbox_monitoring.as_deref();
+
+    if let Some(monitoring) = monitoring {
+        monitoring
+            .record_connection(connection_id.clone(), remote_addr.to_string())
+            .await;
+    }
+
+    let registration = state
+        .mailbox_registry
+        .register(&connection_id, &remote_addr.to_string());
 
     let (mut sender, mut receiver) = socket.split();
     let mut mailbox_state = MailboxState::AwaitingInit;
@@ -267,41 +382,69 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
         message_count: 0,
         last_reset: Instant::now(),
     };
+    let settings = state.mailbox_limiter.settings().clone();
+    let mut assembler = ChunkAssembler::new(settings.max_payload_size_bytes);
+    let idle_timeout = Duration::from_secs(IDLE_TIMEOUT_SECS);
 
-    while let Some(msg) = receiver.next().await {
-        let msg = match msg {
-            Ok(msg) => msg,
-            Err(e) => {
-                error!("WebSocket error: {}", e);
+    loop {
+        let next = tokio::select! {
+            next = tokio::time::timeout(idle_timeout, receiver.next()) => next,
+            _ = registration.closed() => {
+                info!("Mailbox WebSocket {} closed by operator", connection_id);
+                let _ = sender.send(policy_violation("Closed by operator")).await;
                 break;
             }
         };
 
-        // Check rate limiting
-        if !check_rate_limit(&mut limits) {
-            warn!("Rate limit exceeded, closing connection");
-            let _ = sender.send(Message::Close(None)).await;
-            break;
-        }
+        let msg = match next {
+            Ok(Some(Ok(msg))) => msg,
+            Ok(Some(Err(e))) => {
+                error!("WebSocket error: {}", e);
+                break;
+            }
+            Ok(None) => break,
+            Err(_) => {
+                info!("Mailbox WebSocket idle for {} seconds, closing", IDLE_TIMEOUT_SECS);
+                let _ = sender.send(Message::Close(None)).await;
+                break;
+            }
+        };
 
         match msg {
             Message::Text(text) => {
                 // Validate message size
-                if text.len() > MAX_MESSAGE_SIZE_BYTES {
-                    warn!(
-                        "Message too large: {} bytes, max: {} bytes",
-                        text.len(),
-                        MAX_MESSAGE_SIZE_BYTES
-                    );
-                    let _ = sender.send(Message::Close(None)).await;
+                if let Err(exceeded) = state.mailbox_limiter.check_message_size(text.len()) {
+                    warn!("{}", exceeded.reason);
+                    let _ = sender.send(policy_violation("Message too large")).await;
                     break;
                 }
 
                 info!("Received mailbox WebSocket message: {}", text);
+                state.mailbox_registry.record_received(&connection_id);
+                if let Some(monitoring) = monitoring {
+                    monitoring.record_message_received(&connection_id, text.len()).await;
+                }
 
-                let parsed_msg: Result<WebSocketMailboxMessage, _> = serde_json::from_str(&text);
+                // Chunk frames are bounded by the assembler's payload limit; the
+                // rate limit applies once the complete message has been reassembled
+                let parsed_msg = serde_json::from_str::<WebSocketMailboxMessage>(&text)
+                    .map_err(AppError::from)
+                    .and_then(|ws_msg| reassemble_message(&mut assembler, ws_msg));
                 match parsed_msg {
-                    Ok(ws_msg) => {
+                    Ok(None) => continue,
+                    Ok(Some(ws_msg)) => {
+                        if !enforce_rate_limit(
+                            &mut limits,
+                            settings.messages_per_minute,
+                            &mut sender,
+                            monitoring,
+                            &connection_id,
+                        )
+                        .await
+                        {
+                            break;
+                        }
+
                         match handle_mailbox_message(
                             &mut mailbox_state,
                             ws_msg,
@@ -310,8 +453,12 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
                             &state.base_url.0,
                             &state.macaroon_hex.0,
                             &mut sender,
-                            None, // database
-                            None, // monitoring
+                            &mut receiver,
+                            state.challenge_store.as_ref(),
+                            &state.mailbox_limiter,
+                            &state.mailbox_registry,
+                            database,
+                            monitoring,
                             &connection_id,
                         )
                         .await
@@ -328,6 +475,8 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
                                     auth_success: Some(false),
                                     messages: None,
                                     eos: None,
+                                    ack: None,
+                                    chunk: None,
                                 };
                                 if let Ok(error_json) = serde_json::to_string(&error_response) {
                                     let _ = sender.send(Message::Text(error_json)).await;
@@ -347,6 +496,17 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
                 break;
             }
             Message::Ping(bytes) => {
+                if !enforce_rate_limit(
+                    &mut limits,
+                    settings.messages_per_minute,
+                    &mut sender,
+                    monitoring,
+                    &connection_id,
+                )
+                .await
+                {
+                    break;
+                }
                 if let Err(e) = sender.send(Message::Pong(bytes)).await {
                     error!("Failed to send pong: {}", e);
                     break;
@@ -356,10 +516,14 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
         }
     }
 
+    if let Some(monitoring) = monitoring {
+        monitoring.record_connection_closed(&connection_id).await;
+    }
+
     info!("Mailbox WebSocket connection handler finished: {}", connection_id);
 }
 
-fn check_rate_limit(limits: &mut ConnectionLimits) -> bool {
+fn check_rate_limit(limits: &mut ConnectionLimits, max_per_minute: u32) -> bool {
     let now = Instant::now();
 
     // Reset counter every minute
@@ -369,7 +533,99 @@ fn check_rate_limit(limits: &mut ConnectionLimits) -> bool {
     }
 
     limits.message_count += 1;
-    limits.message_count <= RATE_LIMIT_MESSAGES_PER_MINUTE
+    limits.message_count <= max_per_minute
+}
+
+/// Applies the per-connection message rate limit, closing the socket when it is exceeded
+async fn enforce_rate_limit(
+    limits: &mut ConnectionLimits,
+    max_per_minute: u32,
+    sender: &mut SplitSink<WebSocket, Message>,
+    monitoring: Option<&dyn Monitoring>,
+    connection_id: &str,
+) -> bool {
+    if check_rate_limit(limits, max_per_minute) {
+        return true;
+    }
+
+    warn!("Rate limit exceeded, closing connection");
+    if let Some(monitoring) = monitoring {
+        monitoring.record_rate_limit_hit(connection_id).await;
+    }
+    let _ = sender
+        .send(policy_violation("Message rate limit exceeded"))
+        .await;
+    false
+}
+
+/// Feeds chunk frames to the assembler; returns the message once it is complete
+fn reassemble_message(
+    assembler: &mut ChunkAssembler,
+    msg: WebSocketMailboxMessage,
+) -> Result<Option<WebSocketMailboxMessage>, AppError> {
+    let Some(frame) = msg.chunk else {
+        return Ok(Some(msg));
+    };
+
+    match assembler.accept(frame)? {
+        Some(payload) => {
+            let msg: WebSocketMailboxMessage = serde_json::from_str(&payload)?;
+            if msg.chunk.is_some() {
+                return Err(AppError::InvalidInput(
+                    "Chunked payloads cannot be nested".to_string(),
+                ));
+            }
+            Ok(Some(msg))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Sends a response, splitting it into chunk frames when it exceeds `max_frame_bytes`;
+/// returns the number of bytes written
+async fn send_mailbox_response(
+    sender: &mut SplitSink<WebSocket, Message>,
+    response: &MailboxResponse,
+    max_frame_bytes: usize,
+) -> Result<usize, AppError> {
+    let response_json = serde_json::to_string(response)?;
+    if response_json.len() <= max_frame_bytes {
+        let size = response_json.len();
+        sender
+            .send(Message::Text(response_json))
+            .await
+            .map_err(|e| AppError::RequestError(e.to_string()))?;
+        return Ok(size);
+    }
+
+    // Leave room for the frame envelope; escaping the embedded JSON can double its size
+    let chunk_size = max_frame_bytes.saturating_sub(CHUNK_ENVELOPE_BYTES) / 2;
+    let mut total = 0;
+    for frame in split_into_chunks(&response_json, chunk_size) {
+        let chunk_response = MailboxResponse {
+            challenge: None,
+            auth_success: None,
+            messages: None,
+            eos: None,
+            ack: None,
+            chunk: Some(frame),
+        };
+        let chunk_json = serde_json::to_string(&chunk_response)?;
+        total += chunk_json.len();
+        sender
+            .send(Message::Text(chunk_json))
+            .await
+            .map_err(|e| AppError::RequestError(e.to_string()))?;
+    }
+    Ok(total)
+}
+
+/// Close frame (RFC 6455 code 1008) sent when a connection breaks mailbox limits
+fn policy_violation(reason: &str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code: axum::extract::ws::close_code::POLICY,
+        reason: reason.to_string().into(),
+    }))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -380,8 +636,12 @@ async fn handle_mailbox_message(
     client: &reqwest::Client,
     base_url: &str,
     macaroon_hex: &str,
-    sender: &mut futures_util::stream::SplitSink<axum::extract::ws::WebSocket, Message>,
-    database: Option<&dyn Database>,
+    sender: &mut SplitSink<WebSocket, Message>,
+    incoming: &mut SplitStream<WebSocket>,
+    challenge_store: &dyn ChallengeStore,
+    limiter: &Arc<MailboxLimiter>,
+    registry: &Arc<MailboxRegistry>,
+    database: Option<&dyn ReceiverRepo>,
     monitoring: Option<&dyn Monitoring>,
     connection_id: &str,
 ) -> Result<bool, AppError> {
@@ -392,12 +652,14 @@ async fn handle_mailbox_message(
                 *pending_init = Some(init);
                 *state = MailboxState::ChallengeSent;
 
-                let challenge_response = generate_challenge().await?;
+                let challenge_response = generate_challenge(challenge_store).await?;
                 let response = MailboxResponse {
                     challenge: Some(challenge_response),
                     auth_success: None,
                     messages: None,
                     eos: None,
+                    ack: None,
+                    chunk: None,
                 };
 
                 let response_json = serde_json::to_string(&response)
@@ -425,6 +687,7 @@ async fn handle_mailbox_message(
                         client,
                         base_url,
                         macaroon_hex,
+                        challenge_store,
                         database,
                     )
                     .await?;
@@ -434,6 +697,8 @@ async fn handle_mailbox_message(
                         auth_success: Some(auth_result),
                         messages: None,
                         eos: None,
+                        ack: None,
+                        chunk: None,
                     };
 
                     let response_json = serde_json::to_string(&response)
@@ -447,14 +712,38 @@ async fn handle_mailbox_message(
                     if auth_result {
                         *state = MailboxState::Authenticated;
 
+                        let receiver_id = init
+                            .get("receiver_id")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default();
+
+                        // Hold a per-receiver connection slot for as long as we stream
+                        let _connection_guard = match limiter.acquire_connection(receiver_id) {
+                            Ok(guard) => guard,
+                            Err(exceeded) => {
+                                warn!("{}", exceeded.reason);
+                                if let Some(monitoring) = monitoring {
+                                    monitoring.record_rate_limit_hit(connection_id).await;
+                                }
+                                let _ = sender
+                                    .send(policy_violation("Too many connections for receiver"))
+                                    .await;
+                                return Ok(false);
+                            }
+                        };
+
                         stream_mailbox_messages(
                             client,
                             base_url,
                             macaroon_hex,
                             sender,
+                            incoming,
                             state,
                             &init,
                             &auth_sig,
+                            registry,
+                            limiter.settings().max_message_size_bytes,
+                            database,
                             monitoring,
                             connection_id,
                         )
@@ -462,6 +751,9 @@ async fn handle_mailbox_message(
                         Ok(false)
                     } else {
                         warn!("Authentication failed");
+                        if let Some(monitoring) = monitoring {
+                            monitoring.record_auth_failure(connection_id).await;
+                        }
                         Ok(false)
                     }
                 } else {
@@ -479,7 +771,9 @@ async fn handle_mailbox_message(
     }
 }
 
-async fn generate_challenge() -> Result<serde_json::Value, AppError> {
+async fn generate_challenge(
+    challenge_store: &dyn ChallengeStore,
+) -> Result<serde_json::Value, AppError> {
     let challenge_id = Uuid::new_v4().to_string();
     let timestamp = Utc::now().timestamp();
     let nonce = base64::engine::general_purpose::STANDARD.encode(Uuid::new_v4().as_bytes());
@@ -489,17 +783,11 @@ async fn generate_challenge() -> Result<serde_json::Value, AppError> {
         challenge_id: challenge_id.clone(),
         timestamp,
         nonce: nonce.clone(),
-        issued_at: Instant::now(),
     };
 
-    {
-        let mut challenges = ACTIVE_CHALLENGES.lock().unwrap();
-
-        // Clean up expired challenges
-        challenges.retain(|_, data| data.issued_at.elapsed().as_secs() < CHALLENGE_EXPIRY_SECS);
-
-        challenges.insert(challenge_id.clone(), challenge_data);
-    }
+    challenge_store
+        .insert(challenge_data, Duration::from_secs(CHALLENGE_EXPIRY_SECS))
+        .await?;
 
     Ok(serde_json::json!({
         "challenge_id": challenge_id,
@@ -509,13 +797,47 @@ async fn generate_challenge() -> Result<serde_json::Value, AppError> {
     }))
 }
 
+/// Checks `proof` against the key `receiver` registered with. The challenge
+/// is used up either way, so a signature cannot be replayed.
+async fn verify_receiver_proof(
+    challenge_store: &dyn ChallengeStore,
+    receiver: &ReceiverInfo,
+    proof: &ReceiverProof,
+) -> Result<(), AppError> {
+    let challenge = challenge_store
+        .take(&proof.challenge_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid or expired challenge".to_string()))?;
+    if Utc::now().timestamp() - challenge.timestamp > CHALLENGE_EXPIRY_SECS as i64 {
+        return Err(AppError::Unauthorized("Invalid or expired challenge".to_string()));
+    }
+
+    let message = format!(
+        "Sign this challenge: {}-{}-{}",
+        challenge.challenge_id, challenge.timestamp, challenge.nonce
+    );
+    let verified = if receiver.public_key.len() == 64 {
+        verify_schnorr_signature(&message, &proof.signature, &receiver.public_key)
+    } else {
+        verify_signature(&message, &proof.signature, &receiver.public_key)
+    };
+    match verified {
+        Ok(true) => Ok(()),
+        _ => Err(AppError::Unauthorized(format!(
+            "Signature does not match the key registered for {}",
+            receiver.receiver_id
+        ))),
+    }
+}
+
 async fn validate_authentication(
     init: &serde_json::Value,
     auth_sig: &serde_json::Value,
     client: &reqwest::Client,
     base_url: &str,
     macaroon_hex: &str,
-    database: Option<&dyn Database>,
+    challenge_store: &dyn ChallengeStore,
+    database: Option<&dyn ReceiverRepo>,
 ) -> Result<bool, AppError> {
     // Extract required fields from init data
     let receiver_id = init
@@ -560,26 +882,21 @@ async fn validate_authentication(
         return Ok(false);
     }
 
-    // 1. Verify challenge exists and is valid
-    let challenge_data = {
-        let mut challenges = ACTIVE_CHALLENGES.lock().unwrap();
-        let data = challenges
-            .get(challenge_id)
-            .ok_or_else(|| {
-                warn!("Challenge not found: {}", challenge_id);
-                AppError::InvalidInput("Invalid or expired challenge".to_string())
-            })?
-            .clone();
-
-        // Check if challenge has expired
-        if data.issued_at.elapsed().as_secs() > CHALLENGE_EXPIRY_SECS {
-            warn!("Challenge expired: {}", challenge_id);
-            challenges.remove(challenge_id);
-            return Ok(false);
-        }
+    // 1. Consume the challenge before anything else, so a concurrent replay of
+    // the same signature finds it gone whether or not this attempt succeeds
+    let challenge_data = challenge_store
+        .take(challenge_id)
+        .await?
+        .ok_or_else(|| {
+            warn!("Challenge not found: {}", challenge_id);
+            AppError::InvalidInput("Invalid or expired challenge".to_string())
+        })?;
 
-        data
-    };
+    // Check if challenge has expired
+    if Utc::now().timestamp() - challenge_data.timestamp > CHALLENGE_EXPIRY_SECS as i64 {
+        warn!("Challenge expired: {}", challenge_id);
+        return Ok(false);
+    }
 
     // 2. Validate timestamp to prevent replay attacks
     let current_time = SystemTime::now()
@@ -629,43 +946,14 @@ async fn validate_authentication(
         return Ok(false);
     }
 
-    // Remove used challenge to prevent replay
-    {
-        let mut challenges = ACTIVE_CHALLENGES.lock().unwrap();
-        challenges.remove(challenge_id);
-    }
-
-    // Store receiver info in database if available
+    // Refresh last_seen for receivers registered through the REST API
     if let Some(db) = database {
-        // Try to extract public key from auth_sig or receiver_id
-        let public_key = if let Some(pk) = auth_sig.get("public_key").and_then(|v| v.as_str()) {
-            pk.to_string()
-        } else if let Some(pk) = derive_public_key_from_receiver_id(receiver_id)? {
-            pk
-        } else {
-            // Generate a placeholder if we can't determine the actual public key
-            format!("unknown_{receiver_id}")
-        };
-
-        let receiver_info = ReceiverInfo {
-            receiver_id: receiver_id.to_string(),
-            public_key,
-            address: init
-                .get("address")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-            created_at: Utc::now().timestamp(),
-            last_seen: Utc::now().timestamp(),
-            is_active: true,
-            metadata: Some(serde_json::json!({
-                "auth_method": "mailbox",
-                "last_challenge_id": challenge_id,
-            })),
-        };
-
-        if let Err(e) = db.store_receiver_info(&receiver_info).await {
-            warn!("Failed to store receiver info in database: {}", e);
-            // Don't fail authentication if we can't store in database
+        if let Some(mut receiver_info) = db.get_receiver_info(receiver_id).await? {
+            receiver_info.last_seen = Utc::now().timestamp();
+            if let Err(e) = db.store_receiver_info(&receiver_info).await {
+                warn!("Failed to update receiver last_seen in database: {}", e);
+                // Don't fail authentication if we can't store in database
+            }
         }
     }
 
@@ -680,7 +968,7 @@ async fn verify_signature_with_receiver(
     message: &str,
     signature: &str,
     receiver_id: &str,
-    database: Option<&dyn Database>,
+    database: Option<&dyn ReceiverRepo>,
 ) -> Result<bool, AppError> {
     // First check if receiver_id is directly a public key
     if let Some(public_key) = derive_public_key_from_receiver_id(receiver_id)? {
@@ -723,7 +1011,7 @@ async fn validate_macaroon_permissions(
         .get(&info_url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .timeout(Duration::from_secs(5))
-        .send()
+        .send_upstream()
         .await
         .map_err(|e| {
             error!("Failed to validate macaroon with backend: {}", e);
@@ -779,24 +1067,18 @@ fn validate_taproot_address_format(address: &str) -> Result<bool, AppError> {
     // Attempt to decode using bech32
     match bech32::decode(address) {
         Ok((hrp, data)) => {
-            // Verify it's a taproot address with correct HRP
-            Ok(hrp.as_str() == "taprt1" && !data.is_empty())
+            // Verify it's a taproot address with correct HRP (the "1" is the separator)
+            Ok(hrp.as_str() == "taprt" && !data.is_empty())
         }
         Err(_) => Ok(false),
     }
 }
 
-async fn validate_receiver_id(
-    receiver_id: &str,
-    _client: &reqwest::Client,
-    _base_url: &str,
-    _macaroon_hex: &str,
-    database: Option<&dyn Database>,
-) -> Result<bool, AppError> {
+fn is_valid_receiver_id_format(receiver_id: &str) -> bool {
     // Basic format validation
     if receiver_id.len() < 8 {
         warn!("Receiver ID too short: {}", receiver_id);
-        return Ok(false);
+        return false;
     }
 
     // First check if it's a potential Taproot address - validate Bech32 characters
@@ -807,10 +1089,30 @@ async fn validate_receiver_id(
             .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
         {
             warn!("Receiver ID contains invalid characters: {}", receiver_id);
-            return Ok(false);
+            return false;
         }
     }
 
+    true
+}
+
+async fn validate_receiver_id(
+    receiver_id: &str,
+    _client: &reqwest::Client,
+    _base_url: &str,
+    _macaroon_hex: &str,
+    database: Option<&dyn ReceiverRepo>,
+) -> Result<bool, AppError> {
+    if !is_valid_receiver_id_format(receiver_id) {
+        return Ok(false);
+    }
+
+    // Taproot asset addresses must decode cleanly
+    if receiver_id.starts_with("taprt1") && !validate_taproot_address_format(receiver_id)? {
+        warn!("Receiver ID is not a valid taproot address: {}", receiver_id);
+        return Ok(false);
+    }
+
     // Check if it's a public key format
     if derive_public_key_from_receiver_id(receiver_id)?.is_some() {
         // If it's a valid public key, it's valid
@@ -844,10 +1146,14 @@ async fn stream_mailbox_messages(
     client: &reqwest::Client,
     base_url: &str,
     macaroon_hex: &str,
-    sender: &mut futures_util::stream::SplitSink<axum::extract::ws::WebSocket, Message>,
+    sender: &mut SplitSink<WebSocket, Message>,
+    incoming: &mut SplitStream<WebSocket>,
     state: &mut MailboxState,
     init: &serde_json::Value,
     auth_sig: &serde_json::Value,
+    registry: &Arc<MailboxRegistry>,
+    max_frame_bytes: usize,
+    database: Option<&dyn ReceiverRepo>,
     monitoring: Option<&dyn Monitoring>,
     connection_id: &str,
 ) -> Result<(), AppError> {
@@ -863,6 +1169,16 @@ async fn stream_mailbox_messages(
         receiver_id
     );
 
+    if let Some(monitoring) = monitoring {
+        monitoring
+            .update_receiver_id(connection_id, receiver_id.to_string())
+            .await;
+    }
+
+    let mut fanout = registry.subscribe(connection_id, receiver_id);
+    let close_signal = registry.close_signal(connection_id).unwrap_or_default();
+    let mut sent_message_ids: HashSet<String> = HashSet::new();
+
     // Create a loop to continuously poll for new messages
     let mut message_count = 0;
     let mut last_message_id: Option<String> = None;
@@ -901,34 +1217,20 @@ async fn stream_mailbox_messages(
                     vec![]
                 };
 
-                if !messages.is_empty() {
-                    empty_polls = 0; // Reset empty poll counter
-                    message_count += messages.len();
-
-                    // Update last_message_id for pagination
-                    if let Some(last_msg) = messages.last() {
-                        if let Some(msg_id) = last_msg.get("id").and_then(|v| v.as_str()) {
-                            last_message_id = Some(msg_id.to_string());
-                        }
+                // Update last_message_id for pagination before dropping acknowledged messages
+                if let Some(last_msg) = messages.last() {
+                    if let Some(msg_id) = last_msg.get("id").and_then(|v| v.as_str()) {
+                        last_message_id = Some(msg_id.to_string());
                     }
+                }
 
-                    // Send messages to client
-                    let response = MailboxResponse {
-                        challenge: None,
-                        auth_success: None,
-                        messages: Some(serde_json::Value::Array(messages.clone())),
-                        eos: None,
-                    };
-
-                    let response_json = serde_json::to_string(&response)
-                        .map_err(|e| AppError::RequestError(e.to_string()))?;
+                let messages =
+                    exclude_delivered_messages(database, receiver_id, messages).await?;
 
-                    if let Err(e) = sender.send(Message::Text(response_json)).await {
-                        warn!("Failed to send messages to client: {}", e);
-                        break;
-                    }
-
-                    debug!("Sent {} new messages to client", messages.len());
+                if !messages.is_empty() {
+                    // Fan out to every device of this receiver, including this connection
+                    let devices = registry.broadcast(receiver_id, messages);
+                    debug!("Fanned out new messages to {} devices", devices);
                 } else {
                     empty_polls += 1;
 
@@ -966,6 +1268,8 @@ async fn stream_mailbox_messages(
                         "error": e.to_string(),
                         "completed": false
                     })),
+                    ack: None,
+                    chunk: None,
                 };
 
                 if let Ok(error_json) = serde_json::to_string(&error_response) {
@@ -976,8 +1280,99 @@ async fn stream_mailbox_messages(
             }
         }
 
-        // Wait before next poll
-        tokio::time::sleep(poll_interval).await;
+        // Wait before next poll, handling acknowledgments from the client meanwhile
+        let poll_deadline = tokio::time::sleep(poll_interval);
+        tokio::pin!(poll_deadline);
+        let mut client_closed = false;
+
+        loop {
+            tokio::select! {
+                _ = &mut poll_deadline => break,
+                _ = close_signal.notified() => {
+                    info!("Mailbox stream {} closed by operator", connection_id);
+                    let _ = sender.send(policy_violation("Closed by operator")).await;
+                    client_closed = true;
+                    break;
+                }
+                Some(batch) = fanout.recv() => {
+                    // Another device's poll may already have delivered these to us
+                    let messages: Vec<serde_json::Value> = batch
+                        .iter()
+                        .filter(|m| match m.get("id").and_then(|v| v.as_str()) {
+                            Some(id) => sent_message_ids.insert(id.to_string()),
+                            None => true,
+                        })
+                        .cloned()
+                        .collect();
+
+                    if messages.is_empty() {
+                        continue;
+                    }
+
+                    empty_polls = 0; // Reset empty poll counter
+                    let sent_count = messages.len();
+                    message_count += sent_count;
+
+                    let response = MailboxResponse {
+                        challenge: None,
+                        auth_success: None,
+                        messages: Some(serde_json::Value::Array(messages)),
+                        eos: None,
+                        ack: None,
+                        chunk: None,
+                    };
+
+                    let response_size =
+                        match send_mailbox_response(sender, &response, max_frame_bytes).await {
+                            Ok(size) => size,
+                            Err(e) => {
+                                warn!("Failed to send messages to client: {}", e);
+                                client_closed = true;
+                                break;
+                            }
+                        };
+                    registry.record_sent(connection_id, sent_count);
+                    if let Some(monitoring) = monitoring {
+                        monitoring.record_message_sent(connection_id, response_size).await;
+                    }
+
+                    debug!("Sent {} new messages to client {}", sent_count, connection_id);
+                }
+                incoming_msg = incoming.next() => match incoming_msg {
+                    Some(Ok(Message::Text(text))) => {
+                        registry.record_received(connection_id);
+                        if let Some(monitoring) = monitoring {
+                            monitoring.record_message_received(connection_id, text.len()).await;
+                        }
+                        match serde_json::from_str::<WebSocketMailboxMessage>(&text) {
+                            Ok(WebSocketMailboxMessage { ack: Some(ack), .. }) => {
+                                acknowledge_messages(database, receiver_id, ack, sender).await?;
+                            }
+                            Ok(_) => warn!("Ignoring non-ack message while streaming"),
+                            Err(e) => warn!("Failed to parse message while streaming: {}", e),
+                        }
+                    }
+                    Some(Ok(Message::Ping(bytes))) => {
+                        let _ = sender.send(Message::Pong(bytes)).await;
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        client_closed = true;
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        warn!("WebSocket error while streaming: {}", e);
+                        client_closed = true;
+                        break;
+                    }
+                },
+            }
+        }
+
+        if client_closed {
+            info!("Client closed mailbox stream for receiver: {}", receiver_id);
+            break;
+        }
     }
 
     // Send end-of-stream message
@@ -990,6 +1385,8 @@ async fn stream_mailbox_messages(
             "message_count": message_count,
             "duration_seconds": empty_polls + (message_count as u32)
         })),
+        ack: None,
+        chunk: None,
     };
 
     let eos_json = serde_json::to_string(&eos_response)
@@ -1005,6 +1402,309 @@ async fn stream_mailbox_messages(
     Ok(())
 }
 
+/// Drops messages the receiver has already acknowledged so each is delivered once
+async fn exclude_delivered_messages(
+    database: Option<&dyn ReceiverRepo>,
+    receiver_id: &str,
+    messages: Vec<serde_json::Value>,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let Some(db) = database else {
+        return Ok(messages);
+    };
+
+    let message_ids: Vec<String> = messages
+        .iter()
+        .filter_map(|m| m.get("id").and_then(|v| v.as_str()).map(str::to_string))
+        .collect();
+    if message_ids.is_empty() {
+        return Ok(messages);
+    }
+
+    let delivered = db.get_delivered_message_ids(receiver_id, &message_ids).await?;
+    Ok(messages
+        .into_iter()
+        .filter(|m| {
+            m.get("id")
+                .and_then(|v| v.as_str())
+                .is_none_or(|id| !delivered.contains(id))
+        })
+        .collect())
+}
+
+/// Marks acknowledged messages as delivered and confirms the ack to the client
+async fn acknowledge_messages(
+    database: Option<&dyn ReceiverRepo>,
+    receiver_id: &str,
+    ack: MailboxAck,
+    sender: &mut SplitSink<WebSocket, Message>,
+) -> Result<(), AppError> {
+    match database {
+        Some(db) => {
+            let newly_delivered = db
+                .mark_messages_delivered(receiver_id, &ack.message_ids)
+                .await?;
+            debug!(
+                "Receiver {} acknowledged {} messages ({} new)",
+                receiver_id,
+                ack.message_ids.len(),
+                newly_delivered
+            );
+        }
+        None => warn!("No mailbox database configured, acknowledgment not persisted"),
+    }
+
+    let response = MailboxResponse {
+        challenge: None,
+        auth_success: None,
+        messages: None,
+        eos: None,
+        ack: Some(ack),
+        chunk: None,
+    };
+
+    let response_json = serde_json::to_string(&response)
+        .map_err(|e| AppError::RequestError(e.to_string()))?;
+
+    sender
+        .send(Message::Text(response_json))
+        .await
+        .map_err(|e| AppError::RequestError(e.to_string()))
+}
+
+fn validate_register_request(request: &RegisterReceiverRequest) -> Result<(), AppError> {
+    if !is_valid_receiver_id_format(&request.receiver_id) {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid receiver_id: {}",
+            request.receiver_id
+        )));
+    }
+
+    if derive_public_key_from_receiver_id(&request.public_key)?.is_none() {
+        return Err(AppError::InvalidInput(
+            "public_key must be a hex encoded compressed, uncompressed or x-only secp256k1 key"
+                .to_string(),
+        ));
+    }
+
+    if let Some(address) = &request.address {
+        if !validate_taproot_address_format(address)? {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid taproot address: {address}"
+            )));
+        }
+    }
+
+    if let Some(webhook_url) = &request.webhook_url {
+        validate_webhook_url(webhook_url)?;
+    }
+
+    Ok(())
+}
+
+fn receiver_repo(state: &AppState) -> Result<&dyn ReceiverRepo, AppError> {
+    state.mailbox_receivers.as_deref().ok_or_else(|| {
+        AppError::ServiceUnavailable("Receiver storage is not configured".to_string())
+    })
+}
+
+pub async fn register_receiver_handler(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterReceiverRequest>,
+) -> Result<(StatusCode, Json<RegisterReceiverResponse>), (StatusCode, Json<serde_json::Value>)> {
+    validate_register_request(&request).map_err(error_response)?;
+    let database = receiver_repo(&state).map_err(error_response)?;
+
+    if database
+        .get_receiver_info(&request.receiver_id)
+        .await
+        .map_err(error_response)?
+        .is_some()
+    {
+        return Err(error_response(AppError::Conflict(format!(
+            "Receiver already registered: {}",
+            request.receiver_id
+        ))));
+    }
+
+    let now = Utc::now().timestamp();
+    let receiver_info = ReceiverInfo {
+        receiver_id: request.receiver_id,
+        public_key: request.public_key,
+        address: request.address,
+        created_at: now,
+        last_seen: now,
+        is_active: true,
+        metadata: request.metadata,
+        webhook_secret: request.webhook_url.as_ref().map(|_| generate_webhook_secret()),
+        webhook_url: request.webhook_url,
+    };
+
+    database
+        .store_receiver_info(&receiver_info)
+        .await
+        .map_err(error_response)?;
+
+    info!("Registered mailbox receiver: {}", receiver_info.receiver_id);
+    Ok((
+        StatusCode::CREATED,
+        Json(RegisterReceiverResponse {
+            webhook_secret: receiver_info.webhook_secret.clone(),
+            receiver: receiver_info,
+        }),
+    ))
+}
+
+/// Resolves the public key mail for `receiver_id` should be encrypted to
+async fn resolve_receiver_public_key(
+    state: &AppState,
+    receiver_id: &str,
+) -> Result<String, AppError> {
+    if let Some(public_key) = derive_public_key_from_receiver_id(receiver_id)? {
+        return Ok(public_key);
+    }
+
+    receiver_repo(state)?
+        .get_receiver_info(receiver_id)
+        .await?
+        .map(|info| info.public_key)
+        .ok_or_else(|| AppError::NotFound(format!("Receiver not found: {receiver_id}")))
+}
+
+pub async fn encrypt_handler(
+    State(state): State<AppState>,
+    Json(request): Json<EncryptRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let plaintext = base64::engine::general_purpose::STANDARD
+        .decode(&request.plaintext)
+        .map_err(|e| {
+            error_response(AppError::InvalidInput(format!("Invalid base64 plaintext: {e}")))
+        })?;
+
+    let public_key = resolve_receiver_public_key(&state, &request.receiver_id)
+        .await
+        .map_err(error_response)?;
+    let encrypted = ecies_encrypt(&public_key, &plaintext).map_err(error_response)?;
+
+    Ok(Json(serde_json::json!({
+        "receiver_id": request.receiver_id,
+        "public_key": public_key,
+        "encrypted_payload": base64::engine::general_purpose::STANDARD.encode(encrypted),
+    })))
+}
+
+pub async fn decrypt_handler(
+    Json(request): Json<DecryptRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let encrypted = base64::engine::general_purpose::STANDARD
+        .decode(&request.encrypted_payload)
+        .map_err(|e| {
+            error_response(AppError::InvalidInput(format!("Invalid base64 payload: {e}")))
+        })?;
+
+    let plaintext = ecies_decrypt(&request.private_key, &encrypted).map_err(error_response)?;
+
+    Ok(Json(serde_json::json!({
+        "plaintext": base64::engine::general_purpose::STANDARD.encode(plaintext),
+    })))
+}
+
+pub async fn get_receiver_handler(
+    State(state): State<AppState>,
+    Path(receiver_id): Path<String>,
+) -> Result<Json<ReceiverInfo>, (StatusCode, Json<serde_json::Value>)> {
+    let database = receiver_repo(&state).map_err(error_response)?;
+
+    database
+        .get_receiver_info(&receiver_id)
+        .await
+        .map_err(error_response)?
+        .map(Json)
+        .ok_or_else(|| {
+            error_response(AppError::NotFound(format!(
+                "Receiver not found: {receiver_id}"
+            )))
+        })
+}
+
+/// Issues a challenge for the holder of `receiver_id`'s key to sign before
+/// deleting it
+pub async fn receiver_challenge_handler(
+    State(state): State<AppState>,
+    Path(receiver_id): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let database = receiver_repo(&state).map_err(error_response)?;
+    if database
+        .get_receiver_info(&receiver_id)
+        .await
+        .map_err(error_response)?
+        .is_none()
+    {
+        return Err(error_response(AppError::NotFound(format!(
+            "Receiver not found: {receiver_id}"
+        ))));
+    }
+
+    generate_challenge(state.challenge_store.as_ref())
+        .await
+        .map(Json)
+        .map_err(error_response)
+}
+
+/// Deletes a receiver for the holder of its key, who sends a [`ReceiverProof`]
+/// as the body, or for an operator with the admin token
+pub async fn delete_receiver_handler(
+    State(state): State<AppState>,
+    Path(receiver_id): Path<String>,
+    headers: HeaderMap,
+    proof: Option<Json<ReceiverProof>>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let database = receiver_repo(&state).map_err(error_response)?;
+    let receiver = database
+        .get_receiver_info(&receiver_id)
+        .await
+        .map_err(error_response)?
+        .ok_or_else(|| {
+            error_response(AppError::NotFound(format!("Receiver not found: {receiver_id}")))
+        })?;
+
+    if !is_admin(&state, &headers) {
+        let Some(Json(proof)) = proof else {
+            return Err(error_response(AppError::Unauthorized(
+                "Deleting a receiver needs a signed challenge from \
+                 POST /mailbox/receivers/:id/challenge"
+                    .to_string(),
+            )));
+        };
+        verify_receiver_proof(state.challenge_store.as_ref(), &receiver, &proof)
+            .await
+            .map_err(error_response)?;
+    }
+
+    if database
+        .delete_receiver_info(&receiver_id)
+        .await
+        .map_err(error_response)?
+    {
+        info!("Deleted mailbox receiver: {}", receiver_id);
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(error_response(AppError::NotFound(format!(
+            "Receiver not found: {receiver_id}"
+        ))))
+    }
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    (
+        status,
+        Json(serde_json::json!({
+            "error": error.to_string(),
+            "type": format!("{:?}", error)
+        })),
+    )
+}
+
 // Router configuration
 pub fn create_mailbox_router() -> Router<AppState> {
     Router::new()
@@ -1012,11 +1712,20 @@ pub fn create_mailbox_router() -> Router<AppState> {
         .route("/mailbox/receive", post(receive_handler))
         .route("/mailbox/receive", get(websocket_handler))
         .route("/mailbox/send", post(send_handler))
+        .route("/mailbox/encrypt", post(encrypt_handler))
+        .route("/mailbox/decrypt", post(decrypt_handler))
+        .route("/mailbox/receivers", post(register_receiver_handler))
+        .route(
+            "/mailbox/receivers/:id",
+            get(get_receiver_handler).delete(delete_receiver_handler),
+        )
+        .route("/mailbox/receivers/:id/challenge", post(receiver_challenge_handler))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::challenges::InMemoryChallengeStore;
     use serde_json::json;
 
     #[test]
@@ -1024,6 +1733,8 @@ mod tests {
         let init_msg = WebSocketMailboxMessage {
             init: Some(json!({"receiver_id": "test"})),
             auth_sig: None,
+            ack: None,
+            chunk: None,
         };
 
         let serialized = serde_json::to_string(&init_msg).unwrap();
@@ -1040,6 +1751,78 @@ mod tests {
         assert!(msg.auth_sig.is_some());
     }
 
+    #[test]
+    fn test_ack_message_deserialization() {
+        let json_str = r#"{"ack": {"message_ids": ["msg_1", "msg_2"]}}"#;
+        let msg: WebSocketMailboxMessage = serde_json::from_str(json_str).unwrap();
+
+        assert!(msg.init.is_none());
+        assert_eq!(msg.ack.unwrap().message_ids, vec!["msg_1", "msg_2"]);
+    }
+
+    #[tokio::test]
+    async fn test_exclude_delivered_messages() {
+        let store = crate::storage::receivers::InMemoryReceiverRepo::new();
+        store
+            .mark_messages_delivered("receiver_1", &["msg_1".to_string()])
+            .await
+            .unwrap();
+
+        let messages = vec![json!({"id": "msg_1"}), json!({"id": "msg_2"}), json!({"data": "no id"})];
+        let remaining = exclude_delivered_messages(Some(&store), "receiver_1", messages)
+            .await
+            .unwrap();
+
+        assert_eq!(remaining, vec![json!({"id": "msg_2"}), json!({"data": "no id"})]);
+    }
+
+    #[tokio::test]
+    async fn test_retention_does_not_redeliver_acknowledged_messages() {
+        use crate::config::RetentionSettings;
+        use crate::gateway::metrics::PromMonitoring;
+        use crate::gateway::retention::Pruner;
+        use crate::storage::events::InMemoryEventStore;
+
+        let store = crate::storage::receivers::InMemoryReceiverRepo::new();
+        store
+            .mark_messages_delivered("receiver_1", &["msg_1".to_string()])
+            .await
+            .unwrap();
+        let settings = RetentionSettings {
+            interval_secs: 3600,
+            event_days: 1,
+        };
+        let monitoring = PromMonitoring::new().unwrap();
+        let pruner = Pruner::new(
+            settings,
+            Arc::new(InMemoryEventStore::new()),
+            monitoring.retention_metrics(),
+        );
+        pruner.run().await;
+
+        let messages = vec![json!({"id": "msg_1"})];
+        let remaining = exclude_delivered_messages(Some(&store), "receiver_1", messages)
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn test_reassemble_chunked_message() {
+        let full = r#"{"init": {"receiver_id": "chunked_receiver"}}"#;
+        let mut assembler = ChunkAssembler::new(1024);
+        let mut result = None;
+
+        for frame in split_into_chunks(full, 8) {
+            let frame_json = serde_json::to_string(&json!({ "chunk": frame })).unwrap();
+            let msg: WebSocketMailboxMessage = serde_json::from_str(&frame_json).unwrap();
+            result = reassemble_message(&mut assembler, msg).unwrap();
+        }
+
+        let msg = result.expect("message should be complete after the last chunk");
+        assert_eq!(msg.init.unwrap()["receiver_id"], "chunked_receiver");
+    }
+
     #[test]
     fn test_mailbox_response_serialization() {
         let response = MailboxResponse {
@@ -1047,6 +1830,8 @@ mod tests {
             auth_success: None,
             messages: None,
             eos: None,
+            ack: None,
+            chunk: None,
         };
 
         let serialized = serde_json::to_string(&response).unwrap();
@@ -1093,7 +1878,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_challenge() {
-        let challenge = generate_challenge().await.unwrap();
+        let store = InMemoryChallengeStore::new();
+        let challenge = generate_challenge(&store).await.unwrap();
 
         assert!(challenge.get("challenge_id").is_some());
         assert!(challenge.get("timestamp").is_some());
@@ -1107,6 +1893,31 @@ mod tests {
 
         let nonce = challenge.get("nonce").unwrap().as_str().unwrap();
         assert!(!nonce.is_empty());
+
+        let stored = store.get(challenge_id).await.unwrap().unwrap();
+        assert_eq!(stored.nonce, nonce);
+        assert_eq!(stored.timestamp, timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_failed_authentication_consumes_the_challenge() {
+        let store = InMemoryChallengeStore::new();
+        let challenge = generate_challenge(&store).await.unwrap();
+        let init = serde_json::json!({"receiver_id": "unknown-receiver"});
+        let auth_sig = serde_json::json!({
+            "signature": "ab".repeat(64),
+            "challenge_id": challenge["challenge_id"],
+            "timestamp": challenge["timestamp"],
+        });
+        let client = reqwest::Client::new();
+        let base_url = "http://127.0.0.1:9";
+        let authenticate =
+            || validate_authentication(&init, &auth_sig, &client, base_url, "", &store, None);
+
+        assert!(!authenticate().await.unwrap());
+        let challenge_id = challenge["challenge_id"].as_str().unwrap();
+        assert_eq!(store.get(challenge_id).await.unwrap(), None);
+        assert!(matches!(authenticate().await, Err(AppError::InvalidInput(_))));
     }
 
     #[test]
@@ -1118,11 +1929,62 @@ mod tests {
 
         // Should allow messages within limit
         for i in 0..60 {
-            assert!(check_rate_limit(&mut limits));
+            assert!(check_rate_limit(&mut limits, 60));
             assert_eq!(limits.message_count, i + 1);
         }
 
         // Should reject messages over limit
-        assert!(!check_rate_limit(&mut limits));
+        assert!(!check_rate_limit(&mut limits, 60));
+    }
+
+    #[test]
+    fn test_validate_register_request() {
+        let valid = RegisterReceiverRequest {
+            receiver_id: "receiver_123".to_string(),
+            public_key: "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+                .to_string(),
+            address: None,
+            metadata: None,
+            webhook_url: Some("https://example.com/mailbox".to_string()),
+        };
+        assert!(validate_register_request(&valid).is_ok());
+
+        let bad_webhook = RegisterReceiverRequest {
+            webhook_url: Some("http://example.com/mailbox".to_string()),
+            receiver_id: valid.receiver_id.clone(),
+            public_key: valid.public_key.clone(),
+            address: None,
+            metadata: None,
+        };
+        assert!(validate_register_request(&bad_webhook).is_err());
+
+        let bad_key = RegisterReceiverRequest {
+            public_key: "not-a-key".to_string(),
+            ..valid
+        };
+        assert!(matches!(
+            validate_register_request(&bad_key).unwrap_err(),
+            AppError::InvalidInput(_)
+        ));
+
+        let bad_id = RegisterReceiverRequest {
+            receiver_id: "short".to_string(),
+            public_key: "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+                .to_string(),
+            address: Some("taprt1bogus".to_string()),
+            metadata: None,
+            webhook_url: None,
+        };
+        assert!(validate_register_request(&bad_id).is_err());
+    }
+
+    #[test]
+    fn test_validate_taproot_address_format() {
+        let hrp = bech32::Hrp::parse("taprt").unwrap();
+        let address = bech32::encode::<bech32::Bech32m>(hrp, &[1u8; 40]).unwrap();
+
+        assert!(validate_taproot_address_format(&address).unwrap());
+        assert!(!validate_taproot_address_format("bc1qexample").unwrap());
+        assert!(!validate_taproot_address_format("taprt1invalid!chars").unwrap());
     }
 }
@@ -0,0 +1,185 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Maximum number of partially received payloads a single connection may hold
+const MAX_PENDING_PAYLOADS: usize = 4;
+/// Partial payloads that stop receiving chunks are discarded after this long
+const PENDING_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// One piece of a payload too large to fit in a single WebSocket frame
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChunkFrame {
+    pub payload_id: String,
+    /// Zero-based position of this chunk
+    pub seq: u32,
+    /// Total number of chunks making up the payload
+    pub total: u32,
+    pub data: String,
+}
+
+/// Splits `payload` into chunks of at most `chunk_size` bytes, never splitting a UTF-8 character
+pub fn split_into_chunks(payload: &str, chunk_size: usize) -> Vec<ChunkFrame> {
+    let chunk_size = chunk_size.max(4);
+    let mut pieces = Vec::new();
+    let mut rest = payload;
+
+    while !rest.is_empty() {
+        let mut end = chunk_size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece.to_string());
+        rest = tail;
+    }
+
+    let payload_id = Uuid::new_v4().to_string();
+    let total = pieces.len() as u32;
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(seq, data)| ChunkFrame {
+            payload_id: payload_id.clone(),
+            seq: seq as u32,
+            total,
+            data,
+        })
+        .collect()
+}
+
+struct PendingPayload {
+    total: u32,
+    bytes: usize,
+    chunks: BTreeMap<u32, String>,
+    last_update: Instant,
+}
+
+/// Reassembles chunked payloads received on one connection, enforcing size limits
+pub struct ChunkAssembler {
+    max_payload_bytes: usize,
+    pending: HashMap<String, PendingPayload>,
+}
+
+impl ChunkAssembler {
+    pub fn new(max_payload_bytes: usize) -> Self {
+        Self {
+            max_payload_bytes,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Accepts a chunk; returns the full payload once its last missing chunk arrives
+    pub fn accept(&mut self, frame: ChunkFrame) -> Result<Option<String>, AppError> {
+        if frame.total == 0 || frame.seq >= frame.total {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid chunk sequence {}/{}",
+                frame.seq, frame.total
+            )));
+        }
+
+        let now = Instant::now();
+        self.pending
+            .retain(|_, p| now.duration_since(p.last_update) < PENDING_PAYLOAD_TIMEOUT);
+
+        if !self.pending.contains_key(&frame.payload_id)
+            && self.pending.len() >= MAX_PENDING_PAYLOADS
+        {
+            return Err(AppError::InvalidInput(
+                "Too many chunked payloads in progress".to_string(),
+            ));
+        }
+
+        let pending = self
+            .pending
+            .entry(frame.payload_id.clone())
+            .or_insert_with(|| PendingPayload {
+                total: frame.total,
+                bytes: 0,
+                chunks: BTreeMap::new(),
+                last_update: now,
+            });
+
+        if pending.total != frame.total {
+            self.pending.remove(&frame.payload_id);
+            return Err(AppError::InvalidInput(
+                "Chunk total changed mid-payload".to_string(),
+            ));
+        }
+        if pending.chunks.contains_key(&frame.seq) {
+            return Err(AppError::InvalidInput(format!(
+                "Duplicate chunk {} for payload {}",
+                frame.seq, frame.payload_id
+            )));
+        }
+        if pending.bytes + frame.data.len() > self.max_payload_bytes {
+            self.pending.remove(&frame.payload_id);
+            return Err(AppError::InvalidInput(format!(
+                "Chunked payload exceeds max size of {} bytes",
+                self.max_payload_bytes
+            )));
+        }
+
+        pending.bytes += frame.data.len();
+        pending.last_update = now;
+        pending.chunks.insert(frame.seq, frame.data);
+
+        if pending.chunks.len() < pending.total as usize {
+            return Ok(None);
+        }
+
+        let complete = self.pending.remove(&frame.payload_id).unwrap();
+        Ok(Some(complete.chunks.into_values().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_reassemble_out_of_order() {
+        let payload = "héllo wörld, this is a chunked payload".repeat(10);
+        let mut chunks = split_into_chunks(&payload, 16);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.data.len() <= 16));
+
+        chunks.reverse();
+        let mut assembler = ChunkAssembler::new(1024);
+        let last = chunks.pop().unwrap();
+        for chunk in chunks {
+            assert_eq!(assembler.accept(chunk).unwrap(), None);
+        }
+        assert_eq!(assembler.accept(last).unwrap(), Some(payload));
+    }
+
+    #[test]
+    fn test_rejects_oversized_payload() {
+        let chunks = split_into_chunks(&"a".repeat(100), 40);
+        let mut assembler = ChunkAssembler::new(60);
+        let mut results = chunks.into_iter().map(|c| assembler.accept(c));
+        assert!(results.next().unwrap().is_ok());
+        assert!(results.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_and_duplicate_chunks() {
+        let mut assembler = ChunkAssembler::new(1024);
+        let frame = |seq, total| ChunkFrame {
+            payload_id: "p1".to_string(),
+            seq,
+            total,
+            data: "x".to_string(),
+        };
+
+        assert!(assembler.accept(frame(2, 2)).is_err());
+        assert!(assembler.accept(frame(0, 0)).is_err());
+        assert!(assembler.accept(frame(0, 2)).unwrap().is_none());
+        assert!(assembler.accept(frame(0, 2)).is_err());
+        assert!(assembler.accept(frame(1, 3)).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::config::MailboxSettings;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// A mailbox limit was exceeded; renders as a structured error with Retry-After when applicable
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitExceeded {
+    pub status: StatusCode,
+    pub reason: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl LimitExceeded {
+    fn rate_limited(reason: String, retry_after: Duration) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            reason,
+            retry_after: Some(retry_after),
+        }
+    }
+
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        // Round up so clients never retry before the window actually resets
+        self.retry_after.map(|d| d.as_secs() + u64::from(d.subsec_nanos() > 0))
+    }
+}
+
+impl IntoResponse for LimitExceeded {
+    fn into_response(self) -> Response {
+        let retry_after_secs = self.retry_after_secs();
+        let body = Json(serde_json::json!({
+            "error": self.reason,
+            "type": "RateLimited",
+            "retry_after_secs": retry_after_secs,
+        }));
+
+        match retry_after_secs {
+            Some(secs) => {
+                (self.status, [(header::RETRY_AFTER, secs.to_string())], body).into_response()
+            }
+            None => (self.status, body).into_response(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ReceiverUsage {
+    window_start: Instant,
+    messages: u32,
+    bytes: usize,
+    connections: usize,
+}
+
+impl ReceiverUsage {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            messages: 0,
+            bytes: 0,
+            connections: 0,
+        }
+    }
+
+    fn roll_window(&mut self, now: Instant) {
+        if now.duration_since(self.window_start) >= WINDOW {
+            self.window_start = now;
+            self.messages = 0;
+            self.bytes = 0;
+        }
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        self.connections == 0 && now.duration_since(self.window_start) >= WINDOW
+    }
+}
+
+/// Tracks message rate, byte quota and live connections per mailbox receiver
+pub struct MailboxLimiter {
+    settings: MailboxSettings,
+    usage: Mutex<HashMap<String, ReceiverUsage>>,
+}
+
+impl MailboxLimiter {
+    pub fn new(settings: MailboxSettings) -> Self {
+        Self {
+            settings,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn settings(&self) -> &MailboxSettings {
+        &self.settings
+    }
+
+    /// Rejects single WebSocket frames above the configured per-message size limit
+    pub fn check_message_size(&self, size: usize) -> Result<(), LimitExceeded> {
+        Self::check_size(size, self.settings.max_message_size_bytes, "Message")
+    }
+
+    /// Rejects relayed payloads, which may span several chunked frames, above the payload limit
+    pub fn check_payload_size(&self, size: usize) -> Result<(), LimitExceeded> {
+        Self::check_size(size, self.settings.max_payload_size_bytes, "Payload")
+    }
+
+    fn check_size(size: usize, max: usize, what: &str) -> Result<(), LimitExceeded> {
+        if size > max {
+            return Err(LimitExceeded {
+                status: StatusCode::PAYLOAD_TOO_LARGE,
+                reason: format!("{} too large: {} bytes, max: {} bytes", what, size, max),
+                retry_after: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Accounts a payload of `size` bytes against the receiver's rate and byte quotas
+    pub fn check_message(&self, receiver_id: &str, size: usize) -> Result<(), LimitExceeded> {
+        self.check_payload_size(size)?;
+
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        usage.retain(|_, u| !u.is_idle(now));
+
+        let entry = usage
+            .entry(receiver_id.to_string())
+            .or_insert_with(|| ReceiverUsage::new(now));
+        entry.roll_window(now);
+
+        let retry_after = WINDOW.saturating_sub(now.duration_since(entry.window_start));
+
+        if entry.messages >= self.settings.messages_per_minute {
+            warn!("Message rate limit exceeded for receiver: {}", receiver_id);
+            return Err(LimitExceeded::rate_limited(
+                format!(
+                    "Message rate limit of {} per minute exceeded",
+                    self.settings.messages_per_minute
+                ),
+                retry_after,
+            ));
+        }
+
+        if entry.bytes + size > self.settings.byte_quota_per_minute {
+            warn!("Byte quota exceeded for receiver: {}", receiver_id);
+            return Err(LimitExceeded::rate_limited(
+                format!(
+                    "Byte quota of {} bytes per minute exceeded",
+                    self.settings.byte_quota_per_minute
+                ),
+                retry_after,
+            ));
+        }
+
+        entry.messages += 1;
+        entry.bytes += size;
+        Ok(())
+    }
+
+    /// Reserves a connection slot for the receiver; the slot is released when the guard drops
+    pub fn acquire_connection(
+        self: &Arc<Self>,
+        receiver_id: &str,
+    ) -> Result<ConnectionGuard, LimitExceeded> {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        let entry = usage
+            .entry(receiver_id.to_string())
+            .or_insert_with(|| ReceiverUsage::new(now));
+
+        if entry.connections >= self.settings.max_connections_per_receiver {
+            warn!("Connection limit exceeded for receiver: {}", receiver_id);
+            return Err(LimitExceeded::rate_limited(
+                format!(
+                    "Maximum of {} concurrent connections per receiver exceeded",
+                    self.settings.max_connections_per_receiver
+                ),
+                WINDOW,
+            ));
+        }
+
+        entry.connections += 1;
+        Ok(ConnectionGuard {
+            limiter: Arc::clone(self),
+            receiver_id: receiver_id.to_string(),
+        })
+    }
+
+    fn release_connection(&self, receiver_id: &str) {
+        let mut usage = self.usage.lock().unwrap();
+        if let Some(entry) = usage.get_mut(receiver_id) {
+            entry.connections = entry.connections.saturating_sub(1);
+        }
+    }
+}
+
+/// Holds a receiver connection slot for the lifetime of a mailbox WebSocket
+pub struct ConnectionGuard {
+    limiter: Arc<MailboxLimiter>,
+    receiver_id: String,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.limiter.release_connection(&self.receiver_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_settings() -> MailboxSettings {
+        MailboxSettings {
+            messages_per_minute: 3,
+            byte_quota_per_minute: 100,
+            max_message_size_bytes: 20,
+            max_payload_size_bytes: 60,
+            max_connections_per_receiver: 2,
+            webhook_max_attempts: 1,
+            webhook_initial_backoff_ms: 10,
+            admin_token: None,
+        }
+    }
+
+    #[test]
+    fn test_message_rate_limit() {
+        let limiter = MailboxLimiter::new(test_settings());
+        for _ in 0..3 {
+            assert!(limiter.check_message("receiver_a", 1).is_ok());
+        }
+
+        let err = limiter.check_message("receiver_a", 1).unwrap_err();
+        assert_eq!(err.status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(err.retry_after_secs().unwrap() <= 60);
+
+        // Other receivers are tracked independently
+        assert!(limiter.check_message("receiver_b", 1).is_ok());
+    }
+
+    #[test]
+    fn test_byte_quota() {
+        let limiter = MailboxLimiter::new(test_settings());
+        assert!(limiter.check_message("receiver_a", 50).is_ok());
+        let err = limiter.check_message("receiver_a", 51).unwrap_err();
+        assert_eq!(err.status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_message_size_limit() {
+        let limiter = MailboxLimiter::new(test_settings());
+        let err = limiter.check_message("receiver_a", 61).unwrap_err();
+        assert_eq!(err.status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(err.retry_after, None);
+
+        // Frames are capped separately from whole (possibly chunked) payloads
+        assert!(limiter.check_message_size(21).is_err());
+        assert!(limiter.check_payload_size(21).is_ok());
+    }
+
+    #[test]
+    fn test_connection_limit_released_on_drop() {
+        let limiter = Arc::new(MailboxLimiter::new(test_settings()));
+        let first = limiter.acquire_connection("receiver_a").unwrap();
+        let _second = limiter.acquire_connection("receiver_a").unwrap();
+        assert!(limiter.acquire_connection("receiver_a").is_err());
+
+        drop(first);
+        assert!(limiter.acquire_connection("receiver_a").is_ok());
+    }
+
+    #[test]
+    fn test_limit_exceeded_response_has_retry_after() {
+        let response =
+            LimitExceeded::rate_limited("slow down".to_string(), Duration::from_millis(1500))
+                .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "2");
+    }
+}
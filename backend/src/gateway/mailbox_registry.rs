@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use serde::Serialize;
+use tokio::sync::{mpsc, Notify};
+use tracing::debug;
+
+/// A batch of mailbox messages fanned out to every device of a receiver
+pub type MessageBatch = Arc<Vec<serde_json::Value>>;
+
+struct ConnectionEntry {
+    remote_addr: String,
+    receiver_id: Option<String>,
+    connected_at: i64,
+    last_activity: Instant,
+    messages_received: u64,
+    messages_sent: u64,
+    close: Arc<Notify>,
+    fanout: Option<mpsc::UnboundedSender<MessageBatch>>,
+}
+
+/// Point-in-time view of a live mailbox connection, as reported to operators
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSummary {
+    pub connection_id: String,
+    pub remote_addr: String,
+    pub receiver_id: Option<String>,
+    pub connected_at: i64,
+    pub idle_secs: u64,
+    pub messages_received: u64,
+    pub messages_sent: u64,
+}
+
+/// Tracks live mailbox connections: per-connection activity for operators, and
+/// per-receiver fan-out so new messages reach all of a receiver's devices,
+/// whichever connection fetched them
+#[derive(Default)]
+pub struct MailboxRegistry {
+    connections: RwLock<HashMap<String, ConnectionEntry>>,
+}
+
+impl MailboxRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new connection; it is removed when the returned guard drops
+    pub fn register(self: &Arc<Self>, connection_id: &str, remote_addr: &str) -> RegistrationGuard {
+        let close = Arc::new(Notify::new());
+        self.connections.write().unwrap().insert(
+            connection_id.to_string(),
+            ConnectionEntry {
+                remote_addr: remote_addr.to_string(),
+                receiver_id: None,
+                connected_at: chrono::Utc::now().timestamp(),
+                last_activity: Instant::now(),
+                messages_received: 0,
+                messages_sent: 0,
+                close: Arc::clone(&close),
+                fanout: None,
+            },
+        );
+
+        RegistrationGuard {
+            registry: Arc::clone(self),
+            connection_id: connection_id.to_string(),
+            close,
+        }
+    }
+
+    /// Binds an authenticated connection to its receiver and returns its fan-out feed
+    pub fn subscribe(
+        &self,
+        connection_id: &str,
+        receiver_id: &str,
+    ) -> mpsc::UnboundedReceiver<MessageBatch> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Some(entry) = self.connections.write().unwrap().get_mut(connection_id) {
+            entry.receiver_id = Some(receiver_id.to_string());
+            entry.fanout = Some(tx);
+        }
+
+        debug!(
+            "Subscribed mailbox connection {} for receiver {}",
+            connection_id, receiver_id
+        );
+        rx
+    }
+
+    /// Sends a batch to every connection of the receiver; returns how many were reached
+    pub fn broadcast(&self, receiver_id: &str, messages: Vec<serde_json::Value>) -> usize {
+        let batch: MessageBatch = Arc::new(messages);
+        self.connections
+            .read()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.receiver_id.as_deref() == Some(receiver_id))
+            .filter_map(|entry| entry.fanout.as_ref())
+            .filter(|tx| tx.send(Arc::clone(&batch)).is_ok())
+            .count()
+    }
+
+    pub fn connection_count(&self, receiver_id: &str) -> usize {
+        self.connections
+            .read()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.receiver_id.as_deref() == Some(receiver_id))
+            .count()
+    }
+
+    pub fn record_received(&self, connection_id: &str) {
+        if let Some(entry) = self.connections.write().unwrap().get_mut(connection_id) {
+            entry.messages_received += 1;
+            entry.last_activity = Instant::now();
+        }
+    }
+
+    pub fn record_sent(&self, connection_id: &str, count: usize) {
+        if let Some(entry) = self.connections.write().unwrap().get_mut(connection_id) {
+            entry.messages_sent += count as u64;
+            entry.last_activity = Instant::now();
+        }
+    }
+
+    /// Returns the close signal of a connection, if it is still registered
+    pub fn close_signal(&self, connection_id: &str) -> Option<Arc<Notify>> {
+        self.connections
+            .read()
+            .unwrap()
+            .get(connection_id)
+            .map(|entry| Arc::clone(&entry.close))
+    }
+
+    /// Asks a connection to shut down; returns false if it is not registered
+    pub fn close(&self, connection_id: &str) -> bool {
+        match self.close_signal(connection_id) {
+            Some(close) => {
+                close.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ConnectionSummary> {
+        let now = Instant::now();
+        let mut connections: Vec<ConnectionSummary> = self
+            .connections
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(connection_id, entry)| ConnectionSummary {
+                connection_id: connection_id.clone(),
+                remote_addr: entry.remote_addr.clone(),
+                receiver_id: entry.receiver_id.clone(),
+                connected_at: entry.connected_at,
+                idle_secs: now.duration_since(entry.last_activity).as_secs(),
+                messages_received: entry.messages_received,
+                messages_sent: entry.messages_sent,
+            })
+            .collect();
+        connections.sort_by_key(|c| c.connected_at);
+        connections
+    }
+
+    fn unregister(&self, connection_id: &str) {
+        self.connections.write().unwrap().remove(connection_id);
+    }
+}
+
+/// Keeps a connection registered with the [`MailboxRegistry`] while it is alive
+pub struct RegistrationGuard {
+    registry: Arc<MailboxRegistry>,
+    connection_id: String,
+    close: Arc<Notify>,
+}
+
+impl RegistrationGuard {
+    /// Resolves once an operator asks for this connection to be closed
+    pub async fn closed(&self) {
+        self.close.notified().await
+    }
+}
+
+impl Drop for RegistrationGuard {
+    fn drop(&mut self) {
+        self.registry.unregister(&self.connection_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_all_devices() {
+        let registry = Arc::new(MailboxRegistry::new());
+        let _guard_a = registry.register("conn_a", "127.0.0.1:1");
+        let _guard_b = registry.register("conn_b", "127.0.0.1:2");
+        let _guard_c = registry.register("conn_c", "127.0.0.1:3");
+        let mut rx_a = registry.subscribe("conn_a", "receiver_1");
+        let mut rx_b = registry.subscribe("conn_b", "receiver_1");
+        let mut rx_c = registry.subscribe("conn_c", "receiver_2");
+
+        assert_eq!(registry.broadcast("receiver_1", vec![json!({"id": "msg_1"})]), 2);
+
+        assert_eq!(rx_a.recv().await.unwrap()[0]["id"], "msg_1");
+        assert_eq!(rx_b.recv().await.unwrap()[0]["id"], "msg_1");
+        assert!(rx_c.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_guard_unregisters_connection() {
+        let registry = Arc::new(MailboxRegistry::new());
+        let guard = registry.register("conn_a", "127.0.0.1:1");
+        let _rx = registry.subscribe("conn_a", "receiver_1");
+        assert_eq!(registry.connection_count("receiver_1"), 1);
+
+        drop(guard);
+        assert_eq!(registry.connection_count("receiver_1"), 0);
+        assert_eq!(registry.broadcast("receiver_1", vec![]), 0);
+        assert!(registry.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_close() {
+        let registry = Arc::new(MailboxRegistry::new());
+        let guard = registry.register("conn_a", "127.0.0.1:1");
+        registry.record_received("conn_a");
+        registry.record_sent("conn_a", 3);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].remote_addr, "127.0.0.1:1");
+        assert_eq!(snapshot[0].messages_received, 1);
+        assert_eq!(snapshot[0].messages_sent, 3);
+
+        assert!(registry.close("conn_a"));
+        assert!(!registry.close("unknown"));
+        // The close request is retained until the connection task observes it
+        tokio::time::timeout(std::time::Duration::from_secs(1), guard.closed())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use crate::config::MailboxSettings;
+use crate::crypto::hmac_sha256_hex;
+use crate::error::AppError;
+
+pub const SIGNATURE_HEADER: &str = "X-Mailbox-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Mailbox-Timestamp";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Body POSTed to a receiver's webhook when mail arrives while it is offline
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub receiver_id: String,
+    pub encrypted_payload: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_proof: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_block_height: Option<u32>,
+    pub delivered_at: i64,
+}
+
+/// Delivers mailbox messages to receiver webhooks with HMAC signatures and retries
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    max_attempts: u32,
+    initial_backoff: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(client: reqwest::Client, settings: &MailboxSettings) -> Self {
+        Self {
+            client,
+            max_attempts: settings.webhook_max_attempts,
+            initial_backoff: Duration::from_millis(settings.webhook_initial_backoff_ms),
+        }
+    }
+
+    /// Delivers the payload in the background, retrying with exponential backoff
+    pub fn dispatch(&self, url: String, secret: String, payload: WebhookPayload) {
+        let client = self.client.clone();
+        let max_attempts = self.max_attempts;
+        let initial_backoff = self.initial_backoff;
+
+        tokio::spawn(async move {
+            for attempt in 1..=max_attempts {
+                match deliver(&client, &url, &secret, &payload).await {
+                    Ok(()) => {
+                        info!(
+                            "Delivered mailbox webhook for receiver {} on attempt {}",
+                            payload.receiver_id, attempt
+                        );
+                        return;
+                    }
+                    Err(e) if attempt < max_attempts => {
+                        let delay = backoff_delay(initial_backoff, attempt);
+                        warn!(
+                            "Mailbox webhook attempt {} for receiver {} failed: {}; retrying in {:?}",
+                            attempt, payload.receiver_id, e, delay
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    Err(e) => {
+                        error!(
+                            "Giving up on mailbox webhook for receiver {} after {} attempts: {}",
+                            payload.receiver_id, attempt, e
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    payload: &WebhookPayload,
+) -> Result<(), AppError> {
+    check_webhook_target(url).await?;
+    let body = serde_json::to_string(payload)?;
+    let timestamp = Utc::now().timestamp().to_string();
+
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, &timestamp)
+        .header(
+            SIGNATURE_HEADER,
+            format!("sha256={}", sign_payload(secret, &timestamp, &body)),
+        )
+        .body(body)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(AppError::RequestError(format!(
+            "Webhook responded with {}",
+            response.status()
+        )))
+    }
+}
+
+/// Signs `"{timestamp}.{body}"` so receivers can reject replayed deliveries
+pub fn sign_payload(secret: &str, timestamp: &str, body: &str) -> String {
+    hmac_sha256_hex(secret.as_bytes(), format!("{timestamp}.{body}").as_bytes())
+}
+
+pub fn backoff_delay(initial: Duration, attempt: u32) -> Duration {
+    initial
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Generates a random per-receiver webhook signing secret
+pub fn generate_webhook_secret() -> String {
+    hex::encode(secp256k1::rand::random::<[u8; 32]>())
+}
+
+/// Webhook callbacks must be absolute HTTPS URLs
+pub fn validate_webhook_url(webhook_url: &str) -> Result<(), AppError> {
+    let parsed = url::Url::parse(webhook_url)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid webhook_url: {e}")))?;
+
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err(AppError::InvalidInput(
+            "webhook_url must be an https:// URL".to_string(),
+        ));
+    }
+    let internal = match parsed.host() {
+        Some(url::Host::Ipv4(ip)) => !is_public_ip(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => !is_public_ip(IpAddr::V6(ip)),
+        Some(url::Host::Domain(domain)) => {
+            domain.eq_ignore_ascii_case("localhost") || domain.ends_with(".localhost")
+        }
+        None => false,
+    };
+    if internal {
+        return Err(AppError::InvalidInput(
+            "webhook_url must not point at an internal host".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Resolves a webhook URL right before sending and refuses loopback,
+/// private and link-local targets, so callbacks can't probe internal hosts
+pub async fn check_webhook_target(webhook_url: &str) -> Result<(), AppError> {
+    validate_webhook_url(webhook_url)?;
+    let parsed = url::Url::parse(webhook_url)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid webhook_url: {e}")))?;
+    let host = parsed.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| AppError::RequestError(format!("Could not resolve {host}: {e}")))?;
+    for addr in addrs {
+        if !is_public_ip(addr.ip()) {
+            return Err(AppError::InvalidInput(format!(
+                "Webhook host {host} resolves to internal address {}",
+                addr.ip()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 is carrier-grade NAT space
+    let shared = a == 100 && (b & 0xc0) == 64;
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || shared
+        || a == 0)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_doubles_and_caps() {
+        let initial = Duration::from_millis(500);
+        assert_eq!(backoff_delay(initial, 1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(initial, 2), Duration::from_millis(1000));
+        assert_eq!(backoff_delay(initial, 4), Duration::from_millis(4000));
+        assert_eq!(backoff_delay(initial, 40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_validate_webhook_url() {
+        assert!(validate_webhook_url("https://example.com/hooks/mailbox").is_ok());
+        assert!(validate_webhook_url("http://example.com/hooks/mailbox").is_err());
+        assert!(validate_webhook_url("not a url").is_err());
+        assert!(validate_webhook_url("https://localhost/hooks").is_err());
+        assert!(validate_webhook_url("https://127.0.0.1/hooks").is_err());
+        assert!(validate_webhook_url("https://169.254.169.254/latest").is_err());
+        assert!(validate_webhook_url("https://10.0.0.5:8443/hooks").is_err());
+        assert!(validate_webhook_url("https://[::1]/hooks").is_err());
+        assert!(validate_webhook_url("https://[::ffff:192.168.1.1]/hooks").is_err());
+        assert!(validate_webhook_url("https://93.184.216.34/hooks").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_webhook_target_rejects_internal_addresses() {
+        assert!(check_webhook_target("https://127.0.0.1:9/hooks").await.is_err());
+        assert!(check_webhook_target("https://[fe80::1]/hooks").await.is_err());
+        assert!(check_webhook_target("https://100.64.0.1/hooks").await.is_err());
+        assert!(check_webhook_target("https://93.184.216.34/hooks").await.is_ok());
+    }
+
+    #[test]
+    fn test_sign_payload_binds_timestamp() {
+        let body = r#"{"receiver_id":"r"}"#;
+        let signature = sign_payload("secret", "1700000000", body);
+        assert_eq!(signature.len(), 64);
+        assert_ne!(signature, sign_payload("secret", "1700000001", body));
+        assert_ne!(signature, sign_payload("other", "1700000000", body));
+    }
+
+    #[test]
+    fn test_generate_webhook_secret_is_random() {
+        let secret = generate_webhook_secret();
+        assert_eq!(secret.len(), 64);
+        assert_ne!(secret, generate_webhook_secret());
+    }
+}
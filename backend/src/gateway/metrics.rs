@@ -0,0 +1,333 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use prometheus::{
+    Encoder, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error};
+
+use crate::types::AppState;
+use super::mailbox::Monitoring;
+
+/// Prometheus-backed mailbox monitoring, exported on `/metrics`
+pub struct PromMonitoring {
+    registry: Registry,
+    connections_total: IntCounter,
+    active_connections: IntGauge,
+    streaming_connections: IntGauge,
+    messages_received_total: IntCounter,
+    bytes_received_total: IntCounter,
+    messages_sent_total: IntCounter,
+    bytes_sent_total: IntCounter,
+    auth_failures_total: IntCounter,
+    rate_limit_hits_total: IntCounter,
+    // connection_id -> receiver_id, once the connection has authenticated
+    connections: Mutex<HashMap<String, Option<String>>>,
+    events: Arc<EventMetrics>,
+    retention: Arc<RetentionMetrics>,
+}
+
+impl PromMonitoring {
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+
+        let counter = |name: &str, help: &str| -> Result<IntCounter, prometheus::Error> {
+            let counter = IntCounter::new(name, help)?;
+            registry.register(Box::new(counter.clone()))?;
+            Ok(counter)
+        };
+        let gauge = |name: &str, help: &str| -> Result<IntGauge, prometheus::Error> {
+            let gauge = IntGauge::new(name, help)?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        Ok(Self {
+            connections_total: counter(
+                "mailbox_connections_total",
+                "Total mailbox WebSocket connections accepted",
+            )?,
+            active_connections: gauge(
+                "mailbox_active_connections",
+                "Currently open mailbox WebSocket connections",
+            )?,
+            streaming_connections: gauge(
+                "mailbox_streaming_connections",
+                "Authenticated mailbox connections currently streaming messages",
+            )?,
+            messages_received_total: counter(
+                "mailbox_messages_received_total",
+                "Messages received from mailbox clients",
+            )?,
+            bytes_received_total: counter(
+                "mailbox_bytes_received_total",
+                "Bytes received from mailbox clients",
+            )?,
+            messages_sent_total: counter(
+                "mailbox_messages_sent_total",
+                "Messages sent to mailbox clients",
+            )?,
+            bytes_sent_total: counter(
+                "mailbox_bytes_sent_total",
+                "Bytes sent to mailbox clients",
+            )?,
+            auth_failures_total: counter(
+                "mailbox_auth_failures_total",
+                "Failed mailbox authentication attempts",
+            )?,
+            rate_limit_hits_total: counter(
+                "mailbox_rate_limit_hits_total",
+                "Mailbox connections rejected or closed by rate limits",
+            )?,
+            connections: Mutex::new(HashMap::new()),
+            events: Arc::new(EventMetrics::new(&registry)?),
+            retention: Arc::new(RetentionMetrics::new(&registry)?),
+            registry,
+        })
+    }
+
+    /// Metrics for the asset event broker, registered in the same registry
+    pub fn event_metrics(&self) -> Arc<EventMetrics> {
+        Arc::clone(&self.events)
+    }
+
+    /// Metrics for the retention job, registered in the same registry
+    pub fn retention_metrics(&self) -> Arc<RetentionMetrics> {
+        Arc::clone(&self.retention)
+    }
+
+    /// Renders all registered metrics in the Prometheus text exposition format
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+/// Asset event broker metrics, labelled by event type
+pub struct EventMetrics {
+    received_total: IntCounterVec,
+    deliveries_total: IntCounterVec,
+    dropped_total: IntCounterVec,
+    subscribers: IntGaugeVec,
+    upstream_subscriptions: IntGauge,
+}
+
+impl EventMetrics {
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let counter = |name: &str, help: &str| -> Result<IntCounterVec, prometheus::Error> {
+            let counter = IntCounterVec::new(Opts::new(name, help), &["type"])?;
+            registry.register(Box::new(counter.clone()))?;
+            Ok(counter)
+        };
+
+        let subscribers = IntGaugeVec::new(
+            Opts::new("event_subscribers", "Local subscribers per event type"),
+            &["type"],
+        )?;
+        registry.register(Box::new(subscribers.clone()))?;
+        let upstream_subscriptions = IntGauge::new(
+            "event_upstream_subscriptions",
+            "Open upstream event subscriptions to tapd",
+        )?;
+        registry.register(Box::new(upstream_subscriptions.clone()))?;
+
+        Ok(Self {
+            received_total: counter("events_received_total", "Events received from tapd")?,
+            deliveries_total: counter(
+                "event_deliveries_total",
+                "Events fanned out to local subscribers",
+            )?,
+            dropped_total: counter(
+                "event_subscriber_dropped_total",
+                "Events skipped by subscribers that lagged behind the broadcast channel",
+            )?,
+            subscribers,
+            upstream_subscriptions,
+        })
+    }
+
+    pub fn record_received(&self, event_type: &str, deliveries: usize) {
+        self.received_total.with_label_values(&[event_type]).inc();
+        self.deliveries_total
+            .with_label_values(&[event_type])
+            .inc_by(deliveries as u64);
+    }
+
+    pub fn record_dropped(&self, event_type: &str, skipped: u64) {
+        self.dropped_total
+            .with_label_values(&[event_type])
+            .inc_by(skipped);
+    }
+
+    pub fn set_subscribers(&self, event_type: &str, count: usize) {
+        self.subscribers
+            .with_label_values(&[event_type])
+            .set(count as i64);
+    }
+
+    pub fn set_upstream_subscriptions(&self, count: usize) {
+        self.upstream_subscriptions.set(count as i64);
+    }
+}
+
+/// Retention job metrics, labelled by policy
+pub struct RetentionMetrics {
+    pruned_total: IntCounterVec,
+    failures_total: IntCounterVec,
+    last_run: IntGauge,
+}
+
+impl RetentionMetrics {
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let counter = |name: &str, help: &str| -> Result<IntCounterVec, prometheus::Error> {
+            let counter = IntCounterVec::new(Opts::new(name, help), &["policy"])?;
+            registry.register(Box::new(counter.clone()))?;
+            Ok(counter)
+        };
+
+        let last_run = IntGauge::new(
+            "retention_last_run_timestamp_seconds",
+            "Unix time the retention job last ran",
+        )?;
+        registry.register(Box::new(last_run.clone()))?;
+
+        Ok(Self {
+            pruned_total: counter("retention_pruned_rows_total", "Rows deleted by retention")?,
+            failures_total: counter(
+                "retention_failures_total",
+                "Retention passes that failed to prune",
+            )?,
+            last_run,
+        })
+    }
+
+    pub fn record_pruned(&self, policy: &str, rows: u64) {
+        self.pruned_total.with_label_values(&[policy]).inc_by(rows);
+    }
+
+    pub fn record_failure(&self, policy: &str) {
+        self.failures_total.with_label_values(&[policy]).inc();
+    }
+
+    pub fn set_last_run(&self, at: i64) {
+        self.last_run.set(at);
+    }
+}
+
+#[async_trait::async_trait]
+impl Monitoring for PromMonitoring {
+    async fn record_connection(&self, connection_id: String, remote_addr: String) {
+        debug!("Mailbox connection {} from {}", connection_id, remote_addr);
+        self.connections.lock().unwrap().insert(connection_id, None);
+        self.connections_total.inc();
+        self.active_connections.inc();
+    }
+
+    async fn record_connection_closed(&self, connection_id: &str) {
+        if let Some(receiver_id) = self.connections.lock().unwrap().remove(connection_id) {
+            self.active_connections.dec();
+            if receiver_id.is_some() {
+                self.streaming_connections.dec();
+            }
+        }
+    }
+
+    async fn record_message_received(&self, _connection_id: &str, size: usize) {
+        self.messages_received_total.inc();
+        self.bytes_received_total.inc_by(size as u64);
+    }
+
+    async fn record_message_sent(&self, _connection_id: &str, size: usize) {
+        self.messages_sent_total.inc();
+        self.bytes_sent_total.inc_by(size as u64);
+    }
+
+    async fn record_rate_limit_hit(&self, _connection_id: &str) {
+        self.rate_limit_hits_total.inc();
+    }
+
+    async fn record_auth_failure(&self, _connection_id: &str) {
+        self.auth_failures_total.inc();
+    }
+
+    async fn update_receiver_id(&self, connection_id: &str, receiver_id: String) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(slot) = connections.get_mut(connection_id) {
+            if slot.replace(receiver_id).is_none() {
+                self.streaming_connections.inc();
+            }
+        }
+    }
+}
+
+pub async fn metrics_handler(State(state): State<AppState>) -> Response {
+    match state.metrics.encode() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            error!("Failed to encode metrics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connection_lifecycle_gauges() {
+        let monitoring = PromMonitoring::new().unwrap();
+        monitoring
+            .record_connection("conn_1".to_string(), "127.0.0.1:1234".to_string())
+            .await;
+        monitoring
+            .update_receiver_id("conn_1", "receiver_1".to_string())
+            .await;
+
+        assert_eq!(monitoring.connections_total.get(), 1);
+        assert_eq!(monitoring.active_connections.get(), 1);
+        assert_eq!(monitoring.streaming_connections.get(), 1);
+
+        monitoring.record_connection_closed("conn_1").await;
+        assert_eq!(monitoring.active_connections.get(), 0);
+        assert_eq!(monitoring.streaming_connections.get(), 0);
+
+        // Closing twice must not drive gauges negative
+        monitoring.record_connection_closed("conn_1").await;
+        assert_eq!(monitoring.active_connections.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_encode_includes_counters() {
+        let monitoring = PromMonitoring::new().unwrap();
+        monitoring.record_message_received("conn_1", 42).await;
+        monitoring.record_auth_failure("conn_1").await;
+
+        let output = monitoring.encode().unwrap();
+        assert!(output.contains("mailbox_messages_received_total 1"));
+        assert!(output.contains("mailbox_bytes_received_total 42"));
+        assert!(output.contains("mailbox_auth_failures_total 1"));
+    }
+
+    #[test]
+    fn test_event_metrics_are_exported() {
+        let monitoring = PromMonitoring::new().unwrap();
+        let events = monitoring.event_metrics();
+        events.record_received("asset-send", 3);
+        events.record_dropped("asset-send", 2);
+        events.set_subscribers("asset-send", 3);
+        events.set_upstream_subscriptions(1);
+
+        let output = monitoring.encode().unwrap();
+        assert!(output.contains(r#"events_received_total{type="asset-send"} 1"#));
+        assert!(output.contains(r#"event_deliveries_total{type="asset-send"} 3"#));
+        assert!(output.contains(r#"event_subscriber_dropped_total{type="asset-send"} 2"#));
+        assert!(output.contains(r#"event_subscribers{type="asset-send"} 3"#));
+        assert!(output.contains("event_upstream_subscriptions 1"));
+    }
+}
@@ -0,0 +1,357 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Json,
+    },
+};
+use base64::Engine;
+use futures::{stream, Stream};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use super::events::{AssetMintRequest, EventMessage};
+use super::upstream::SendUpstream;
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// Batch lookups back up the event stream, which may miss transitions while
+/// it reconnects
+const MINT_LOOKUP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Coarse progress of a minting batch, in the order a batch moves through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MintPhase {
+    /// Collecting seedlings, frozen or committed but not yet broadcast
+    Pending,
+    Broadcast,
+    Confirmed,
+    /// Proofs are written and the assets are spendable
+    Finalized,
+    Cancelled,
+}
+
+impl MintPhase {
+    /// Maps tapd's `BatchState`; unrecognised states are ignored
+    pub fn from_batch_state(state: &str) -> Option<Self> {
+        match state {
+            "BATCH_STATE_PENDING" | "BATCH_STATE_FROZEN" | "BATCH_STATE_COMMITTED" => {
+                Some(MintPhase::Pending)
+            }
+            "BATCH_STATE_BROADCAST" => Some(MintPhase::Broadcast),
+            "BATCH_STATE_CONFIRMED" => Some(MintPhase::Confirmed),
+            "BATCH_STATE_FINALIZED" => Some(MintPhase::Finalized),
+            "BATCH_STATE_SEEDLING_CANCELLED" | "BATCH_STATE_SPROUT_CANCELLED" => {
+                Some(MintPhase::Cancelled)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, MintPhase::Finalized | MintPhase::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionSource {
+    Event,
+    Lookup,
+}
+
+/// A batch moving to a later phase
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MintTransition {
+    /// Hex
+    pub batch_key: String,
+    pub from: Option<MintPhase>,
+    pub to: MintPhase,
+    /// tapd's state, e.g. `BATCH_STATE_BROADCAST`
+    pub batch_state: String,
+    pub batch_txid: Option<String>,
+    pub error: Option<String>,
+    pub source: TransitionSource,
+}
+
+/// Batch keys arrive as base64 from tapd's REST API; clients may use hex
+pub fn batch_key_hex(key: &str) -> Option<String> {
+    let key = key.trim();
+    if key.len() == 66 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(key.to_ascii_lowercase());
+    }
+    let engines = [
+        base64::engine::general_purpose::STANDARD,
+        base64::engine::general_purpose::URL_SAFE,
+    ];
+    engines
+        .iter()
+        .find_map(|engine| engine.decode(key).ok())
+        .filter(|bytes| bytes.len() == 33)
+        .map(hex::encode)
+}
+
+/// Follows one batch, turning the batches it is shown into forward-only transitions
+#[derive(Debug)]
+pub struct MintTracker {
+    batch_key: String,
+    phase: Option<MintPhase>,
+}
+
+impl MintTracker {
+    pub fn new(batch_key_hex: String) -> Self {
+        Self {
+            batch_key: batch_key_hex,
+            phase: None,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase.is_some_and(|phase| phase.is_terminal())
+    }
+
+    /// Returns a transition if `batch` is this tracker's batch and has moved
+    /// past the last phase seen; stale lookups never move it backwards
+    pub fn observe(
+        &mut self,
+        batch: &Value,
+        error: Option<&str>,
+        source: TransitionSource,
+    ) -> Option<MintTransition> {
+        let key = batch["batch_key"].as_str().and_then(batch_key_hex)?;
+        if key != self.batch_key {
+            return None;
+        }
+        let batch_state = batch["state"].as_str()?;
+        let phase = MintPhase::from_batch_state(batch_state)?;
+        if self.phase.is_some_and(|current| phase <= current) {
+            return None;
+        }
+
+        let from = self.phase.replace(phase);
+        Some(MintTransition {
+            batch_key: key,
+            from,
+            to: phase,
+            batch_state: batch_state.to_string(),
+            batch_txid: batch["batch_txid"]
+                .as_str()
+                .filter(|txid| !txid.is_empty())
+                .map(str::to_string),
+            error: error.filter(|e| !e.is_empty()).map(str::to_string),
+            source,
+        })
+    }
+}
+
+/// Looks a batch up through `ListBatches`; `None` if tapd does not know it
+pub async fn lookup_batch(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    batch_key_hex: &str,
+) -> Result<Option<Value>, AppError> {
+    let key = hex::decode(batch_key_hex)
+        .map_err(|_| AppError::InvalidInput("batch_key must be hex".to_string()))?;
+    let key = base64::engine::general_purpose::URL_SAFE.encode(key);
+    let url = format!("{base_url}/v1/taproot-assets/assets/mint/batches/{key}");
+    let response = client
+        .get(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .send_upstream()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    let body = response.json::<Value>().await?;
+    // Newer tapd versions wrap each batch in a verbose envelope
+    Ok(body["batches"]
+        .as_array()
+        .and_then(|batches| batches.first())
+        .map(|batch| batch.get("batch").unwrap_or(batch).clone()))
+}
+
+struct MintWatch {
+    state: AppState,
+    tracker: MintTracker,
+    events: Option<broadcast::Receiver<EventMessage>>,
+    lookups: tokio::time::Interval,
+    finished: bool,
+}
+
+impl MintWatch {
+    async fn next_event(&mut self) -> Option<Event> {
+        while !self.finished {
+            let transition = tokio::select! {
+                event = recv_event(&mut self.events) => match event {
+                    Ok(event) if !event.is_marker() => {
+                        let payload = &event.payload;
+                        let error = payload["error"].as_str();
+                        self.tracker.observe(&payload["batch"], error, TransitionSource::Event)
+                    }
+                    Ok(_) => {
+                        // The subscription reconnected; check what was missed
+                        self.lookups.reset_immediately();
+                        None
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        self.events = None;
+                        None
+                    }
+                },
+                _ = self.lookups.tick() => {
+                    let batch = lookup_batch(
+                        &self.state.http_client,
+                        &self.state.base_url.0,
+                        &self.state.macaroon_hex.0,
+                        &self.tracker.batch_key,
+                    )
+                    .await;
+                    match batch {
+                        Ok(Some(batch)) => {
+                            self.tracker.observe(&batch, None, TransitionSource::Lookup)
+                        }
+                        Ok(None) => {
+                            self.finished = true;
+                            let error = format!("Unknown mint batch {}", self.tracker.batch_key);
+                            return Some(error_event(&error));
+                        }
+                        Err(e) => {
+                            warn!("Mint batch lookup failed: {}", e);
+                            None
+                        }
+                    }
+                }
+            };
+
+            if let Some(transition) = transition {
+                self.finished = self.tracker.is_done();
+                return Event::default().event("transition").json_data(&transition).ok();
+            }
+        }
+        None
+    }
+}
+
+async fn recv_event(
+    events: &mut Option<broadcast::Receiver<EventMessage>>,
+) -> Result<EventMessage, broadcast::error::RecvError> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn error_event(error: &str) -> Event {
+    Event::default()
+        .event("error")
+        .json_data(serde_json::json!({ "error": error }))
+        .unwrap_or_default()
+}
+
+/// Streams a mint batch's phase transitions as server-sent events until it is
+/// finalized or cancelled
+pub async fn mint_batch_status_handler(
+    State(state): State<AppState>,
+    Path(batch_key): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, Json<Value>)> {
+    let key = batch_key_hex(&batch_key).ok_or_else(|| {
+        error_response(AppError::InvalidInput(
+            "batch_key must be 33 bytes of hex or base64".to_string(),
+        ))
+    })?;
+    info!("Following mint batch {}", key);
+
+    let request = serde_json::to_value(AssetMintRequest {
+        short_response: true,
+    })
+    .unwrap_or_default();
+    let events = state.event_broker.subscribe("asset-mint", request);
+    let watch = MintWatch {
+        state,
+        tracker: MintTracker::new(key),
+        events: Some(events),
+        lookups: tokio::time::interval(MINT_LOOKUP_INTERVAL),
+        finished: false,
+    };
+
+    let transitions = stream::unfold(watch, |mut watch| async move {
+        let event = watch.next_event().await?;
+        Some((Ok(event), watch))
+    });
+    Ok(Sse::new(transitions).keep_alive(KeepAlive::default()))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(key: &str, state: &str) -> Value {
+        serde_json::json!({"batch_key": key, "state": state, "batch_txid": ""})
+    }
+
+    #[test]
+    fn test_tracker_moves_forward_only() {
+        let key_bytes = [0x02; 33];
+        let base64_key = base64::engine::general_purpose::STANDARD.encode(key_bytes);
+        let hex_key = hex::encode(key_bytes);
+        let mut tracker = MintTracker::new(hex_key.clone());
+
+        let first = tracker
+            .observe(&batch(&base64_key, "BATCH_STATE_FROZEN"), None, TransitionSource::Lookup)
+            .unwrap();
+        assert_eq!((first.from, first.to), (None, MintPhase::Pending));
+        assert_eq!(first.batch_key, hex_key);
+        assert_eq!(first.batch_txid, None);
+
+        // Same phase, another batch, and a stale state are all ignored
+        let committed = batch(&base64_key, "BATCH_STATE_COMMITTED");
+        assert!(tracker.observe(&committed, None, TransitionSource::Event).is_none());
+        let other = batch(&hex::encode([0x03; 33]), "BATCH_STATE_BROADCAST");
+        assert!(tracker.observe(&other, None, TransitionSource::Event).is_none());
+
+        let mut broadcast = batch(&hex_key, "BATCH_STATE_BROADCAST");
+        broadcast["batch_txid"] = "ab".repeat(32).into();
+        let second = tracker.observe(&broadcast, None, TransitionSource::Event).unwrap();
+        assert_eq!((second.from, second.to), (Some(MintPhase::Pending), MintPhase::Broadcast));
+        assert_eq!(second.batch_txid, Some("ab".repeat(32)));
+        assert!(!tracker.is_done());
+
+        let stale = batch(&hex_key, "BATCH_STATE_PENDING");
+        assert!(tracker.observe(&stale, None, TransitionSource::Lookup).is_none());
+
+        let done = batch(&hex_key, "BATCH_STATE_FINALIZED");
+        let last = tracker.observe(&done, Some(""), TransitionSource::Event).unwrap();
+        assert_eq!(last.to, MintPhase::Finalized);
+        assert_eq!(last.error, None);
+        assert!(tracker.is_done());
+    }
+
+    #[test]
+    fn test_batch_key_hex() {
+        let hex_key = hex::encode([0x02; 33]);
+        let url_safe = base64::engine::general_purpose::URL_SAFE.encode([0xfb; 33]);
+        assert_eq!(batch_key_hex(&hex_key.to_uppercase()), Some(hex_key));
+        assert_eq!(batch_key_hex(&url_safe), Some(hex::encode([0xfb; 33])));
+        assert_eq!(batch_key_hex("02abcd"), None);
+        assert_eq!(MintPhase::from_batch_state("BATCH_STATE_UNKNOWN"), None);
+    }
+}
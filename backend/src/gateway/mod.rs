@@ -2,10 +2,44 @@ pub mod health;
 pub mod assets;
 pub mod addresses;
 pub mod info;
+pub mod invoices;
+pub mod lnd;
+pub mod lnurl;
 pub mod wallet;
+pub mod balance_history;
 pub mod burn;
+pub mod cache;
+pub mod blocks;
 pub mod channels;
+pub mod correlation;
 pub mod events;
+pub mod fees;
+pub mod event_filter;
 pub mod rfq;
+pub mod rfq_analytics;
+pub mod rfq_sim;
 pub mod routes;
-pub mod mailbox;
\ No newline at end of file
+pub mod mailbox;
+pub mod mailbox_chunks;
+pub mod mailbox_limits;
+pub mod mailbox_registry;
+pub mod mailbox_webhooks;
+pub mod metrics;
+pub mod mint_status;
+pub mod notifications;
+pub mod offers;
+pub mod ownership;
+pub mod price_alerts;
+pub mod price_oracle;
+pub mod qr;
+pub mod reconcile;
+pub mod retention;
+pub mod admin;
+pub mod audit;
+pub mod backup;
+pub mod transaction_events;
+pub mod transfers;
+pub mod universe;
+pub mod upstream;
+pub mod utxos;
+pub mod webhooks;
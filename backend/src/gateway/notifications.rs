@@ -0,0 +1,437 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use super::events::{EventBroker, EventQueryParams};
+use super::transaction_events::transaction_updates;
+use super::upstream::SendUpstream;
+use crate::config::PushSettings;
+use crate::error::AppError;
+use crate::storage::devices::{DeviceRegistration, DeviceStore, PushPlatform};
+use crate::types::{AppState, TransactionStatus};
+
+/// Most addresses a single device may watch
+const MAX_ADDRESSES_PER_DEVICE: usize = 100;
+/// FCM and APNs tokens are well under this; anything longer is not a push token
+const MAX_TOKEN_LENGTH: usize = 4096;
+/// Receives remembered so repeated confirmation states notify only once
+const NOTIFIED_HISTORY: usize = 1024;
+
+/// A platform-neutral push message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PushNotification {
+    pub title: String,
+    pub body: String,
+    pub data: Value,
+}
+
+/// Delivers push notifications to devices; implementations wrap FCM, APNs or a relay
+#[async_trait::async_trait]
+pub trait PushProvider: Send + Sync {
+    async fn send(
+        &self,
+        device: &DeviceRegistration,
+        notification: &PushNotification,
+    ) -> Result<(), AppError>;
+}
+
+/// Logs notifications instead of sending them, for deployments without a push relay
+pub struct LogPushProvider;
+
+#[async_trait::async_trait]
+impl PushProvider for LogPushProvider {
+    async fn send(
+        &self,
+        device: &DeviceRegistration,
+        notification: &PushNotification,
+    ) -> Result<(), AppError> {
+        info!(
+            "Push to {:?} device {}: {}",
+            device.platform,
+            redact_token(&device.token),
+            notification.body
+        );
+        Ok(())
+    }
+}
+
+/// POSTs notifications to a relay service that holds the FCM/APNs credentials
+pub struct RelayPushProvider {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl RelayPushProvider {
+    pub fn new(url: String, token: Option<String>) -> Result<Self, AppError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| AppError::ValidationError(format!("Failed to create push client: {e}")))?;
+
+        Ok(Self { client, url, token })
+    }
+}
+
+#[async_trait::async_trait]
+impl PushProvider for RelayPushProvider {
+    async fn send(
+        &self,
+        device: &DeviceRegistration,
+        notification: &PushNotification,
+    ) -> Result<(), AppError> {
+        let mut request = self.client.post(&self.url).json(&json!({
+            "platform": device.platform,
+            "token": device.token,
+            "notification": notification,
+        }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send_upstream().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(AppError::RequestError(format!(
+                "Push relay responded with {}",
+                response.status()
+            )))
+        }
+    }
+}
+
+pub fn create_push_provider(settings: &PushSettings) -> Result<Arc<dyn PushProvider>, AppError> {
+    match &settings.relay_url {
+        Some(url) => {
+            info!("Sending push notifications through relay at {}", url);
+            Ok(Arc::new(RelayPushProvider::new(
+                url.clone(),
+                settings.relay_token.clone(),
+            )?))
+        }
+        None => {
+            info!("PUSH_RELAY_URL not set, push notifications will only be logged");
+            Ok(Arc::new(LogPushProvider))
+        }
+    }
+}
+
+/// Pushes to every device watching an address once an incoming transfer to it confirms
+pub fn spawn_push_notifier(
+    broker: Arc<EventBroker>,
+    devices: Arc<dyn DeviceStore>,
+    provider: Arc<dyn PushProvider>,
+) {
+    let request = EventQueryParams::default().subscription_request("asset-receive");
+    let mut events = broker.subscribe("asset-receive", request);
+
+    tokio::spawn(async move {
+        let mut notified = NotifiedReceives::default();
+        loop {
+            match events.recv().await {
+                Ok(event) if event.is_marker() => {}
+                Ok(event) => {
+                    let Some((address, key, notification)) = receive_notification(&event.payload)
+                    else {
+                        continue;
+                    };
+                    if !notified.insert(key) {
+                        continue;
+                    }
+                    notify_devices(devices.as_ref(), provider.as_ref(), &address, &notification)
+                        .await;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    broker.record_lag("asset-receive", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+async fn notify_devices(
+    devices: &dyn DeviceStore,
+    provider: &dyn PushProvider,
+    address: &str,
+    notification: &PushNotification,
+) {
+    let targets = match devices.devices_for_address(address).await {
+        Ok(targets) => targets,
+        Err(e) => {
+            warn!("Failed to look up devices for {}: {}", address, e);
+            return;
+        }
+    };
+
+    for device in targets {
+        if let Err(e) = provider.send(&device, notification).await {
+            warn!(
+                "Failed to push to {:?} device {}: {}",
+                device.platform,
+                redact_token(&device.token),
+                e
+            );
+        }
+    }
+}
+
+/// Builds the notification for a confirmed asset-receive event, returning the
+/// receiving address and a key identifying the transfer
+fn receive_notification(event: &Value) -> Option<(String, String, PushNotification)> {
+    let update = transaction_updates("asset-receive", event)
+        .into_iter()
+        .find(|u| u.status == TransactionStatus::Confirmed)?;
+    let outpoint = event
+        .get("outpoint")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let asset_id = update.asset_id.unwrap_or_default();
+
+    let notification = PushNotification {
+        title: "Assets received".to_string(),
+        body: format!(
+            "Received {} units of asset {}",
+            update.amount,
+            asset_id.get(..8).unwrap_or(&asset_id)
+        ),
+        data: json!({
+            "type": "asset_received",
+            "address": update.destination,
+            "asset_id": asset_id,
+            "amount": update.amount.to_string(),
+            "outpoint": outpoint,
+        }),
+    };
+    let key = format!("{}:{}", update.destination, outpoint);
+    Some((update.destination, key, notification))
+}
+
+#[derive(Default)]
+struct NotifiedReceives {
+    order: VecDeque<String>,
+    seen: HashSet<String>,
+}
+
+impl NotifiedReceives {
+    /// Returns false if the key was already notified
+    fn insert(&mut self, key: String) -> bool {
+        if !self.seen.insert(key.clone()) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > NOTIFIED_HISTORY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+fn redact_token(token: &str) -> String {
+    format!("{}…", token.get(..8).unwrap_or(token))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterDeviceRequest {
+    pub token: String,
+    pub platform: PushPlatform,
+    pub addresses: Vec<String>,
+}
+
+impl RegisterDeviceRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.token.trim().is_empty() || self.token.len() > MAX_TOKEN_LENGTH {
+            return Err(AppError::InvalidInput("Invalid device token".to_string()));
+        }
+        if self.addresses.is_empty() || self.addresses.len() > MAX_ADDRESSES_PER_DEVICE {
+            return Err(AppError::InvalidInput(format!(
+                "Between 1 and {MAX_ADDRESSES_PER_DEVICE} addresses are required"
+            )));
+        }
+        if let Some(address) = self.addresses.iter().find(|a| !a.starts_with("tap")) {
+            return Err(AppError::InvalidInput(format!(
+                "Not a Taproot Assets address: {address}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub async fn register_device_handler(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceRegistration>), (StatusCode, Json<Value>)> {
+    request.validate().map_err(error_response)?;
+
+    let device = DeviceRegistration {
+        token: request.token,
+        platform: request.platform,
+        addresses: request.addresses,
+        registered_at: chrono::Utc::now().timestamp(),
+    };
+    state
+        .device_store
+        .register(&device)
+        .await
+        .map_err(error_response)?;
+
+    info!(
+        "Registered {:?} device {} for {} addresses",
+        device.platform,
+        redact_token(&device.token),
+        device.addresses.len()
+    );
+    Ok((StatusCode::CREATED, Json(device)))
+}
+
+pub async fn unregister_device_handler(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    if state
+        .device_store
+        .unregister(&token)
+        .await
+        .map_err(error_response)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(error_response(AppError::NotFound(
+            "Device not registered".to_string(),
+        )))
+    }
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    (
+        status,
+        Json(json!({
+            "error": error.to_string(),
+            "type": format!("{:?}", error)
+        })),
+    )
+}
+
+pub fn create_notifications_router() -> Router<AppState> {
+    Router::new()
+        .route("/notifications/devices", post(register_device_handler))
+        .route(
+            "/notifications/devices/:token",
+            delete(unregister_device_handler),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::devices::InMemoryDeviceStore;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingProvider {
+        sent: Mutex<Vec<(String, PushNotification)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PushProvider for RecordingProvider {
+        async fn send(
+            &self,
+            device: &DeviceRegistration,
+            notification: &PushNotification,
+        ) -> Result<(), AppError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((device.token.clone(), notification.clone()));
+            Ok(())
+        }
+    }
+
+    fn receive_event(status: &str) -> Value {
+        json!({
+            "status": status,
+            "outpoint": "abcd:0",
+            "address": {"encoded": "taprt1me", "asset_id": "0123456789abcdef", "amount": "42"}
+        })
+    }
+
+    #[test]
+    fn test_only_confirmed_receives_notify() {
+        assert!(receive_notification(&receive_event("ADDR_EVENT_STATUS_TRANSACTION_DETECTED")).is_none());
+
+        let (address, key, notification) =
+            receive_notification(&receive_event("ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED")).unwrap();
+        assert_eq!(address, "taprt1me");
+        assert_eq!(key, "taprt1me:abcd:0");
+        assert_eq!(notification.body, "Received 42 units of asset 01234567");
+        assert_eq!(notification.data["amount"], "42");
+    }
+
+    #[test]
+    fn test_notified_receives_are_deduplicated_and_bounded() {
+        let mut notified = NotifiedReceives::default();
+        assert!(notified.insert("a".to_string()));
+        assert!(!notified.insert("a".to_string()));
+
+        for i in 0..NOTIFIED_HISTORY {
+            notified.insert(i.to_string());
+        }
+        assert!(notified.insert("a".to_string()));
+        assert_eq!(notified.seen.len(), NOTIFIED_HISTORY);
+    }
+
+    #[tokio::test]
+    async fn test_notify_devices_targets_watchers_only() {
+        let store = InMemoryDeviceStore::new();
+        for (token, address) in [("watcher", "taprt1me"), ("other", "taprt1else")] {
+            store
+                .register(&DeviceRegistration {
+                    token: token.to_string(),
+                    platform: PushPlatform::Apns,
+                    addresses: vec![address.to_string()],
+                    registered_at: 0,
+                })
+                .await
+                .unwrap();
+        }
+        let provider = RecordingProvider::default();
+
+        let (address, _, notification) =
+            receive_notification(&receive_event("ADDR_EVENT_STATUS_COMPLETED")).unwrap();
+        notify_devices(&store, &provider, &address, &notification).await;
+
+        let sent = provider.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "watcher");
+    }
+
+    #[test]
+    fn test_register_request_validation() {
+        let request = |token: &str, addresses: Vec<&str>| RegisterDeviceRequest {
+            token: token.to_string(),
+            platform: PushPlatform::Fcm,
+            addresses: addresses.into_iter().map(str::to_string).collect(),
+        };
+
+        assert!(request("fcm-token", vec!["taprt1me"]).validate().is_ok());
+        assert!(request("", vec!["taprt1me"]).validate().is_err());
+        assert!(request("fcm-token", vec![]).validate().is_err());
+        assert!(request("fcm-token", vec!["bc1qnotassets"]).validate().is_err());
+    }
+}
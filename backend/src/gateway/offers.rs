@@ -0,0 +1,450 @@
+use axum::{
+    http::StatusCode,
+    response::Json,
+    routing::post,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// Human-readable part of a BOLT12 offer string
+const OFFER_HRP: &str = "lno";
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const OFFER_CHAINS: u64 = 2;
+const OFFER_METADATA: u64 = 4;
+const OFFER_CURRENCY: u64 = 6;
+const OFFER_AMOUNT: u64 = 8;
+const OFFER_DESCRIPTION: u64 = 10;
+const OFFER_FEATURES: u64 = 12;
+const OFFER_ABSOLUTE_EXPIRY: u64 = 14;
+const OFFER_PATHS: u64 = 16;
+const OFFER_ISSUER: u64 = 18;
+const OFFER_QUANTITY_MAX: u64 = 20;
+const OFFER_ISSUER_ID: u64 = 22;
+
+/// A blinded path the offer can be reached through
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OfferPath {
+    /// Hex node ID, or `direction:short_channel_id` for compact introductions
+    pub introduction_node: String,
+    pub hops: usize,
+}
+
+/// A decoded BOLT12 offer
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Offer {
+    /// Hex chain hashes; empty means bitcoin mainnet
+    pub chains: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    /// ISO 4217 code when the amount is denominated in fiat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// Millisatoshis, or minor units of `currency`; absent for any-amount offers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+    /// Unix seconds after which the offer must not be paid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_expiry: Option<u64>,
+    pub paths: Vec<OfferPath>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// 0 means unlimited quantity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity_max: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_id: Option<String>,
+}
+
+impl Offer {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.absolute_expiry.is_some_and(|expiry| now >= expiry)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> AppError {
+    AppError::InvalidInput(format!("Invalid offer: {}", reason.into()))
+}
+
+/// Bech32 data without a checksum, as BOLT12 uses it; `+` joins split strings
+fn offer_bytes(encoded: &str) -> Result<Vec<u8>, AppError> {
+    let mut joined = String::with_capacity(encoded.len());
+    let mut parts = encoded.trim().split('+');
+    joined.push_str(parts.next().unwrap_or_default());
+    for part in parts {
+        let part = part.trim_start();
+        if part.is_empty() || joined.is_empty() {
+            return Err(invalid("misplaced '+'"));
+        }
+        joined.push_str(part);
+    }
+
+    let lowercase = joined.to_ascii_lowercase();
+    if joined != lowercase && joined != joined.to_ascii_uppercase() {
+        return Err(invalid("mixed case"));
+    }
+    let data = lowercase
+        .strip_prefix(OFFER_HRP)
+        .and_then(|rest| rest.strip_prefix('1'))
+        .ok_or_else(|| invalid("expected an lno1 string"))?;
+
+    let mut bytes = Vec::with_capacity(data.len() * 5 / 8);
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for c in data.chars() {
+        let value = BECH32_CHARSET
+            .find(c)
+            .ok_or_else(|| invalid(format!("invalid character {c:?}")))?;
+        acc = (acc << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(invalid("non-zero padding"));
+    }
+    Ok(bytes)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AppError> {
+        if self.bytes.len() < len {
+            return Err(invalid("truncated field"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, AppError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// BOLT 1 BigSize, which must be minimally encoded
+    fn big_size(&mut self) -> Result<u64, AppError> {
+        let (value, min) = match self.byte()? {
+            0xff => (u64::from_be_bytes(self.take(8)?.try_into().unwrap()), 0x1_0000_0000),
+            0xfe => (u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64, 0x1_0000),
+            0xfd => (u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64, 0xfd),
+            small => return Ok(small as u64),
+        };
+        if value < min {
+            return Err(invalid("non-minimal BigSize"));
+        }
+        Ok(value)
+    }
+}
+
+/// Truncated big-endian integer without leading zeros
+fn tu64(value: &[u8]) -> Result<u64, AppError> {
+    if value.len() > 8 || value.first() == Some(&0) {
+        return Err(invalid("non-minimal integer"));
+    }
+    Ok(value.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64))
+}
+
+fn utf8(value: &[u8]) -> Result<String, AppError> {
+    String::from_utf8(value.to_vec()).map_err(|_| invalid("text field is not UTF-8"))
+}
+
+fn point(value: &[u8]) -> Result<String, AppError> {
+    secp256k1::PublicKey::from_slice(value)
+        .map(|key| key.to_string())
+        .map_err(|_| invalid("invalid public key"))
+}
+
+fn blinded_paths(value: &[u8]) -> Result<Vec<OfferPath>, AppError> {
+    let mut reader = Reader { bytes: value };
+    let mut paths = Vec::new();
+    while !reader.bytes.is_empty() {
+        let introduction_node = match reader.byte()? {
+            direction @ (0 | 1) => {
+                let scid = u64::from_be_bytes(reader.take(8)?.try_into().unwrap());
+                format!("{direction}:{}x{}x{}", scid >> 40, (scid >> 16) & 0xff_ffff, scid & 0xffff)
+            }
+            prefix => {
+                let mut key = vec![prefix];
+                key.extend_from_slice(reader.take(32)?);
+                point(&key)?
+            }
+        };
+        reader.take(33)?; // path key
+        let hops = reader.byte()? as usize;
+        if hops == 0 {
+            return Err(invalid("blinded path without hops"));
+        }
+        for _ in 0..hops {
+            reader.take(33)?;
+            let len = u16::from_be_bytes(reader.take(2)?.try_into().unwrap()) as usize;
+            reader.take(len)?;
+        }
+        paths.push(OfferPath {
+            introduction_node,
+            hops,
+        });
+    }
+    Ok(paths)
+}
+
+/// Decodes and checks an `lno1…` string against the BOLT12 reader requirements
+pub fn decode_offer(encoded: &str) -> Result<Offer, AppError> {
+    let bytes = offer_bytes(encoded)?;
+    let mut reader = Reader { bytes: &bytes };
+    let mut offer = Offer::default();
+    let mut last_type = None;
+
+    while !reader.bytes.is_empty() {
+        let tlv_type = reader.big_size()?;
+        if last_type.is_some_and(|last| tlv_type <= last) {
+            return Err(invalid("TLV records out of order"));
+        }
+        last_type = Some(tlv_type);
+        let len = usize::try_from(reader.big_size()?).map_err(|_| invalid("oversized record"))?;
+        let value = reader.take(len)?;
+
+        match tlv_type {
+            OFFER_CHAINS => {
+                if value.is_empty() || value.len() % 32 != 0 {
+                    return Err(invalid("offer_chains"));
+                }
+                offer.chains = value.chunks(32).map(hex::encode).collect();
+            }
+            OFFER_METADATA => offer.metadata = Some(hex::encode(value)),
+            OFFER_CURRENCY => {
+                let currency = utf8(value)?;
+                if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+                    return Err(invalid("offer_currency must be an ISO 4217 code"));
+                }
+                offer.currency = Some(currency);
+            }
+            OFFER_AMOUNT => offer.amount = Some(tu64(value)?),
+            OFFER_DESCRIPTION => offer.description = Some(utf8(value)?),
+            OFFER_FEATURES => offer.features = Some(hex::encode(value)),
+            OFFER_ABSOLUTE_EXPIRY => offer.absolute_expiry = Some(tu64(value)?),
+            OFFER_PATHS => {
+                offer.paths = blinded_paths(value)?;
+                if offer.paths.is_empty() {
+                    return Err(invalid("offer_paths is empty"));
+                }
+            }
+            OFFER_ISSUER => offer.issuer = Some(utf8(value)?),
+            OFFER_QUANTITY_MAX => offer.quantity_max = Some(tu64(value)?),
+            OFFER_ISSUER_ID => offer.issuer_id = Some(point(value)?),
+            // Only offer fields (1-79 and 1000000000-1999999999) may appear
+            t if !(1..80).contains(&t) && !(1_000_000_000..2_000_000_000).contains(&t) => {
+                return Err(invalid(format!("unexpected TLV type {t}")));
+            }
+            t if t % 2 == 0 => return Err(invalid(format!("unknown required TLV type {t}"))),
+            _ => {}
+        }
+    }
+
+    if offer.amount == Some(0) {
+        return Err(invalid("offer_amount must not be zero"));
+    }
+    if offer.amount.is_some() && offer.description.is_none() {
+        return Err(invalid("offer_amount requires offer_description"));
+    }
+    if offer.currency.is_some() && offer.amount.is_none() {
+        return Err(invalid("offer_currency requires offer_amount"));
+    }
+    if offer.issuer_id.is_none() && offer.paths.is_empty() {
+        return Err(invalid("offer needs offer_issuer_id or offer_paths"));
+    }
+    Ok(offer)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeOfferRequest {
+    pub offer: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOfferRequest {
+    /// Millisatoshis; omit for an any-amount offer
+    pub amount_msat: Option<u64>,
+    pub description: String,
+    pub issuer: Option<String>,
+    pub absolute_expiry: Option<u64>,
+    pub quantity_max: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayOfferRequest {
+    pub offer: String,
+    /// Required when the offer leaves the amount to the payer
+    pub amount_msat: Option<u64>,
+    pub quantity: Option<u64>,
+    pub payer_note: Option<String>,
+}
+
+impl PayOfferRequest {
+    /// Checks the request against the offer it pays
+    fn validate(&self, offer: &Offer, now: u64) -> Result<(), AppError> {
+        if offer.is_expired(now) {
+            return Err(AppError::InvalidInput("Offer has expired".to_string()));
+        }
+        if offer.amount.is_none() && self.amount_msat.is_none_or(|amount| amount == 0) {
+            return Err(AppError::InvalidInput(
+                "amount_msat is required for offers without an amount".to_string(),
+            ));
+        }
+        match (offer.quantity_max, self.quantity) {
+            (None, Some(_)) => Err(AppError::InvalidInput(
+                "quantity is not accepted by this offer".to_string(),
+            )),
+            (Some(_), None) | (Some(_), Some(0)) => Err(AppError::InvalidInput(
+                "quantity must be at least 1 for this offer".to_string(),
+            )),
+            (Some(max), Some(quantity)) if max != 0 && quantity > max => Err(
+                AppError::InvalidInput(format!("quantity exceeds the offer maximum of {max}")),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// LND exposes no BOLT12 RPCs, so creating and paying offers needs an
+/// offers-capable upstream that this gateway does not yet connect to
+fn offers_unsupported() -> AppError {
+    AppError::NotImplemented(
+        "BOLT12 offers are not supported by the connected LND node".to_string(),
+    )
+}
+
+async fn decode_offer_handler(
+    Json(request): Json<DecodeOfferRequest>,
+) -> Result<Json<Offer>, (StatusCode, Json<serde_json::Value>)> {
+    let offer = decode_offer(&request.offer).map_err(error_response)?;
+    Ok(Json(offer))
+}
+
+async fn create_offer_handler(
+    Json(request): Json<CreateOfferRequest>,
+) -> Result<Json<Offer>, (StatusCode, Json<serde_json::Value>)> {
+    if request.description.trim().is_empty() {
+        return Err(error_response(AppError::InvalidInput(
+            "description must not be empty".to_string(),
+        )));
+    }
+    if request.amount_msat == Some(0) {
+        return Err(error_response(AppError::InvalidInput(
+            "amount_msat must be greater than 0".to_string(),
+        )));
+    }
+    Err(error_response(offers_unsupported()))
+}
+
+async fn pay_offer_handler(
+    Json(request): Json<PayOfferRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let offer = decode_offer(&request.offer).map_err(error_response)?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    request.validate(&offer, now).map_err(error_response)?;
+    info!("Rejecting BOLT12 payment: no offers-capable upstream");
+    Err(error_response(offers_unsupported()))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+/// BOLT12 offer endpoints, mounted at `/v1/lnd/offers`
+pub fn create_offers_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_offer_handler))
+        .route("/decode", post(decode_offer_handler))
+        .route("/pay", post(pay_offer_handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER_ID: &str = "02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619";
+
+    #[test]
+    fn test_decode_minimal_offer() {
+        let offer = decode_offer("lno1zcss9mk8y3wkklfvevcrszlmu23kfrxh49px20665dqwmn4p72pksese")
+            .unwrap();
+        assert_eq!(offer.issuer_id.as_deref(), Some(ISSUER_ID));
+        assert_eq!(offer.amount, None);
+        assert!(offer.chains.is_empty());
+
+        // Split strings are joined on '+', and upper case is accepted
+        let split = "LNO1ZCSS9MK8Y3WKKLFVEVCRSZLMU23KFRXH49+  PX20665DQWMN4P72PKSESE";
+        assert_eq!(decode_offer(split).unwrap(), offer);
+    }
+
+    #[test]
+    fn test_decode_offer_with_amount() {
+        let offer = decode_offer(
+            "lno1pqpp8zq2qe3k7enxv4jsuprhxk2qq93pqthvwfzadd7jejes8q9lhc4rvjxd022zv5l44g6qah82ru5rdpnpj",
+        )
+        .unwrap();
+        assert_eq!(offer.amount, Some(5000));
+        assert_eq!(offer.description.as_deref(), Some("coffee"));
+        assert_eq!(offer.absolute_expiry, Some(2_000_000_000));
+        assert!(offer.is_expired(2_000_000_000));
+        assert!(!offer.is_expired(1_999_999_999));
+    }
+
+    #[test]
+    fn test_decode_offer_rejects_invalid_input() {
+        // Unknown even (required) TLV type
+        assert!(decode_offer("lno1zcss9mk8y3wkklfvevcrszlmu23kfrxh49px20665dqwmn4p72pksesegcqsq")
+            .is_err());
+        assert!(decode_offer("lnbc1zcss9mk8y3wkklfvevcrszlmu23kfrxh49px20665dqwmn4p72pksese")
+            .is_err());
+        assert!(decode_offer("lno1zcss9mk8y3wkklfvevcrszlmu23kfrxh49px20665dqwmn4p72pkseb")
+            .is_err());
+        assert!(decode_offer("lno1").is_err());
+    }
+
+    #[test]
+    fn test_pay_offer_request_validation() {
+        let offer = Offer {
+            issuer_id: Some(ISSUER_ID.to_string()),
+            quantity_max: Some(3),
+            ..Default::default()
+        };
+        let request = PayOfferRequest {
+            offer: String::new(),
+            amount_msat: Some(1_000),
+            quantity: Some(2),
+            payer_note: None,
+        };
+        assert!(request.validate(&offer, 0).is_ok());
+
+        let too_many = PayOfferRequest {
+            quantity: Some(4),
+            ..request
+        };
+        assert!(too_many.validate(&offer, 0).is_err());
+
+        let without_amount = PayOfferRequest {
+            amount_msat: None,
+            quantity: Some(1),
+            ..too_many
+        };
+        assert!(without_amount.validate(&offer, 0).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, instrument, warn};
+
+use super::upstream::SendUpstream;
+use super::utxos::{parse_managed_utxos, parse_outpoint, AssetInput};
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// tapd binds ownership proofs to a challenge of exactly this many bytes
+pub const CHALLENGE_BYTES: usize = 32;
+
+fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>, AppError> {
+    hex::decode(value.trim())
+        .map_err(|_| AppError::InvalidInput(format!("{field} must be hex")))
+}
+
+/// The counterparty's challenge; without one a proof could be replayed
+fn parse_challenge(challenge: &str) -> Result<Vec<u8>, AppError> {
+    let bytes = decode_hex(challenge, "challenge")?;
+    if bytes.len() != CHALLENGE_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "challenge must be {CHALLENGE_BYTES} bytes of hex"
+        )));
+    }
+    Ok(bytes)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProveOwnershipRequest {
+    /// Hex, chosen by the party asking for the proof
+    pub challenge: String,
+    /// `txid:vout` of one anchor output; every leaf of the asset otherwise
+    pub outpoint: Option<String>,
+}
+
+/// A proof that the wallet can spend one leaf of the asset
+#[derive(Debug, Serialize)]
+pub struct OwnershipProof {
+    /// `txid:vout` of the anchor output
+    pub outpoint: String,
+    /// Hex
+    pub script_key: String,
+    pub amount: u64,
+    /// Hex proof with witness, as tapd's `VerifyAssetOwnership` takes it
+    pub proof: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProveOwnershipResponse {
+    pub asset_id: String,
+    pub challenge: String,
+    /// Sum of the proven leaves
+    pub total_amount: u64,
+    pub proofs: Vec<OwnershipProof>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyOwnershipRequest {
+    /// Hex; the challenge the proofs were made for
+    pub challenge: String,
+    /// Hex proofs from `prove-ownership`
+    pub proofs: Vec<String>,
+}
+
+/// tapd's verdict on one proof, with the asset it claims decoded
+#[derive(Debug, PartialEq, Serialize)]
+pub struct VerifiedProof {
+    pub valid: bool,
+    /// Hex
+    pub asset_id: Option<String>,
+    pub amount: u64,
+    /// `txid:vout` of the anchor output
+    pub outpoint: Option<String>,
+    pub block_hash: Option<String>,
+    pub block_height: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyOwnershipResponse {
+    /// Every proof is valid
+    pub valid: bool,
+    /// Amount behind the valid proofs, per hex asset ID
+    pub verified_amounts: std::collections::BTreeMap<String, u64>,
+    pub proofs: Vec<VerifiedProof>,
+}
+
+impl VerifyOwnershipRequest {
+    /// Returns the challenge and proofs as bytes
+    pub fn validate(&self) -> Result<(Vec<u8>, Vec<Vec<u8>>), AppError> {
+        let challenge = parse_challenge(&self.challenge)?;
+        if self.proofs.is_empty() {
+            return Err(AppError::InvalidInput("proofs must not be empty".to_string()));
+        }
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|proof| decode_hex(proof, "proofs"))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((challenge, proofs))
+    }
+}
+
+fn number(value: &Value) -> u64 {
+    match value {
+        Value::String(text) => text.parse().unwrap_or(0),
+        other => other.as_u64().unwrap_or(0),
+    }
+}
+
+/// The leaves of `asset_id` in tapd's `ListUtxos` response, narrowed to one
+/// anchor output when the request names it
+pub fn ownership_leaves(
+    listing: &Value,
+    asset_id: &str,
+    outpoint: Option<&str>,
+) -> Result<Vec<AssetInput>, AppError> {
+    let outpoint = outpoint.map(parse_outpoint).transpose()?;
+    let leaves: Vec<AssetInput> = parse_managed_utxos(listing, chrono::Utc::now().timestamp())
+        .into_iter()
+        .filter(|utxo| {
+            outpoint
+                .as_ref()
+                .is_none_or(|(txid, vout)| utxo.outpoint == format!("{txid}:{vout}"))
+        })
+        .flat_map(|utxo| {
+            let outpoint = utxo.outpoint;
+            utxo.assets
+                .into_iter()
+                .filter(|asset| asset.asset_id == asset_id)
+                .map(move |asset| AssetInput {
+                    outpoint: outpoint.clone(),
+                    asset,
+                })
+        })
+        .collect();
+    if leaves.is_empty() {
+        return Err(AppError::NotFound(format!("The wallet holds no leaves of asset {asset_id}")));
+    }
+    Ok(leaves)
+}
+
+/// tapd's `ProveAssetOwnershipRequest` for one leaf
+pub fn prove_request(leaf: &AssetInput, challenge: &[u8]) -> Result<Value, AppError> {
+    let prev_id = leaf.prev_id()?;
+    Ok(serde_json::json!({
+        "asset_id": prev_id["id"],
+        "script_key": prev_id["script_key"],
+        "outpoint": prev_id["anchor_point"],
+        "challenge": base64::engine::general_purpose::STANDARD.encode(challenge),
+    }))
+}
+
+/// Merges tapd's `VerifyAssetOwnership` and `DecodeProof` responses. Amounts
+/// only count when the proof is valid, so a bad proof claims nothing.
+pub fn verified_proof(verification: &Value, decoded: Option<&Value>) -> VerifiedProof {
+    let valid = verification["valid_proof"].as_bool().unwrap_or(false);
+    let asset = decoded.map(|decoded| &decoded["decoded_proof"]["asset"]);
+    let text = |value: &Value| value.as_str().filter(|text| !text.is_empty()).map(str::to_string);
+    VerifiedProof {
+        valid,
+        asset_id: asset.and_then(|asset| {
+            let id = asset["asset_genesis"]["asset_id"].as_str()?;
+            let bytes = base64::engine::general_purpose::STANDARD.decode(id).ok()?;
+            Some(hex::encode(bytes))
+        }),
+        amount: asset.filter(|_| valid).map_or(0, |asset| number(&asset["amount"])),
+        outpoint: text(&verification["outpoint_str"]),
+        block_hash: text(&verification["block_hash_str"]),
+        block_height: valid.then(|| number(&verification["block_height"]) as u32),
+    }
+}
+
+async fn tapd_request(
+    state: &AppState,
+    method: reqwest::Method,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<Value, AppError> {
+    let url = format!("{}/v1/taproot-assets/{path}", state.base_url.0);
+    let mut request = state
+        .http_client
+        .request(method, &url)
+        .header("Grpc-Metadata-macaroon", &state.macaroon_hex.0);
+    if let Some(body) = body {
+        request = request.json(body);
+    }
+    let response = request.send_upstream().await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+async fn tapd_post(state: &AppState, path: &str, body: &Value) -> Result<Value, AppError> {
+    tapd_request(state, reqwest::Method::POST, path, Some(body)).await
+}
+
+/// Proves through tapd's `ProveAssetOwnership` that the wallet controls its
+/// leaves of an asset, bound to a challenge from the counterparty
+#[instrument(skip(state, request))]
+pub async fn prove_ownership_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+    Json(request): Json<ProveOwnershipRequest>,
+) -> Result<Json<ProveOwnershipResponse>, (StatusCode, Json<Value>)> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error_response(AppError::InvalidInput(
+            "asset_id must be 32 bytes of hex".to_string(),
+        )));
+    }
+    let challenge = parse_challenge(&request.challenge).map_err(error_response)?;
+
+    let listing = tapd_request(&state, reqwest::Method::GET, "assets/utxos?include_leased=true", None)
+        .await
+        .map_err(error_response)?;
+    let leaves = ownership_leaves(&listing, &asset_id, request.outpoint.as_deref())
+        .map_err(error_response)?;
+    info!("Proving ownership of {} leaves of {}", leaves.len(), asset_id);
+
+    let mut proofs = Vec::with_capacity(leaves.len());
+    for leaf in leaves {
+        let body = prove_request(&leaf, &challenge).map_err(error_response)?;
+        let proven = tapd_post(&state, "wallet/ownership/prove", &body)
+            .await
+            .map_err(error_response)?;
+        let proof = proven["proof_with_witness"]
+            .as_str()
+            .and_then(|proof| base64::engine::general_purpose::STANDARD.decode(proof).ok())
+            .ok_or_else(|| {
+                error_response(AppError::RequestError(
+                    "tapd returned no ownership proof".to_string(),
+                ))
+            })?;
+        proofs.push(OwnershipProof {
+            outpoint: leaf.outpoint,
+            script_key: leaf.asset.script_key,
+            amount: leaf.asset.amount,
+            proof: hex::encode(proof),
+        });
+    }
+
+    Ok(Json(ProveOwnershipResponse {
+        asset_id,
+        challenge: hex::encode(challenge),
+        total_amount: proofs.iter().map(|proof| proof.amount).sum(),
+        proofs,
+    }))
+}
+
+/// Checks a counterparty's ownership proofs against the challenge we gave
+/// them, decoding each proof to report which asset and amount it covers
+#[instrument(skip(state, request))]
+pub async fn verify_ownership_handler(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyOwnershipRequest>,
+) -> Result<Json<VerifyOwnershipResponse>, (StatusCode, Json<Value>)> {
+    let (challenge, proofs) = request.validate().map_err(error_response)?;
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let challenge = base64.encode(challenge);
+
+    let mut verified = Vec::with_capacity(proofs.len());
+    for proof in proofs {
+        let proof = base64.encode(proof);
+        let body = serde_json::json!({"proof_with_witness": proof, "challenge": challenge});
+        let verification = tapd_post(&state, "wallet/ownership/verify", &body)
+            .await
+            .map_err(error_response)?;
+        let decoded = tapd_post(&state, "proofs/decode", &serde_json::json!({"raw_proof": proof}))
+            .await
+            .inspect_err(|e| warn!("Decoding ownership proof failed: {}", e))
+            .ok();
+        verified.push(verified_proof(&verification, decoded.as_ref()));
+    }
+
+    let mut verified_amounts = std::collections::BTreeMap::new();
+    for proof in verified.iter().filter(|proof| proof.valid) {
+        if let Some(asset_id) = &proof.asset_id {
+            *verified_amounts.entry(asset_id.clone()).or_insert(0) += proof.amount;
+        }
+    }
+    Ok(Json(VerifyOwnershipResponse {
+        valid: verified.iter().all(|proof| proof.valid),
+        verified_amounts,
+        proofs: verified,
+    }))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_must_be_32_bytes() {
+        assert!(parse_challenge(&"ab".repeat(32)).is_ok());
+        assert!(parse_challenge(&"ab".repeat(16)).is_err());
+        assert!(parse_challenge("not hex").is_err());
+
+        let request = VerifyOwnershipRequest {
+            challenge: "ab".repeat(32),
+            proofs: vec![],
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_verified_proof_only_counts_valid_amounts() {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let decoded = serde_json::json!({"decoded_proof": {"asset": {
+            "asset_genesis": {"asset_id": base64.encode([0xcd; 32])},
+            "amount": "500",
+        }}});
+        let verification = serde_json::json!({
+            "valid_proof": true,
+            "outpoint_str": format!("{}:1", "ab".repeat(32)),
+            "block_hash_str": "00".repeat(32),
+            "block_height": 840000,
+        });
+
+        let proof = verified_proof(&verification, Some(&decoded));
+        assert_eq!(proof.asset_id, Some("cd".repeat(32)));
+        assert_eq!(proof.amount, 500);
+        assert_eq!(proof.block_height, Some(840000));
+
+        let rejected = verified_proof(&serde_json::json!({"valid_proof": false}), Some(&decoded));
+        assert!(!rejected.valid);
+        assert_eq!((rejected.amount, rejected.outpoint), (0, None));
+    }
+}
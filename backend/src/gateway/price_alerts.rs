@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Path, Query, State, WebSocketUpgrade,
+    },
+    http::StatusCode,
+    response::{Json, Response},
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::mailbox_webhooks::{
+    check_webhook_target, generate_webhook_secret, sign_payload, validate_webhook_url,
+};
+use super::price_oracle::{AssetRate, PriceOracle};
+use crate::error::AppError;
+use crate::storage::price_alerts::{AlertDirection, PriceAlert, PriceAlertStore};
+use crate::types::AppState;
+
+pub const SIGNATURE_HEADER: &str = "X-Alert-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Alert-Timestamp";
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const ALERT_CHANNEL_CAPACITY: usize = 64;
+/// Pending alerts a single asset may have
+const MAX_ALERTS_PER_ASSET: usize = 100;
+
+/// Sent to WebSocket subscribers and webhooks when an alert fires
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceAlertEvent {
+    pub alert: PriceAlert,
+    pub rate: AssetRate,
+}
+
+/// Polls the price oracle for assets with pending alerts and fires those
+/// whose threshold has been reached
+pub struct PriceAlertMonitor {
+    store: Arc<dyn PriceAlertStore>,
+    oracle: Arc<dyn PriceOracle>,
+    client: reqwest::Client,
+    events: broadcast::Sender<PriceAlertEvent>,
+}
+
+impl PriceAlertMonitor {
+    pub fn new(
+        store: Arc<dyn PriceAlertStore>,
+        oracle: Arc<dyn PriceOracle>,
+        client: reqwest::Client,
+    ) -> Self {
+        let (events, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self {
+            store,
+            oracle,
+            client,
+            events,
+        }
+    }
+
+    pub fn store(&self) -> &dyn PriceAlertStore {
+        self.store.as_ref()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PriceAlertEvent> {
+        self.events.subscribe()
+    }
+
+    /// Checks pending alerts every `period`
+    pub fn spawn(self: &Arc<Self>, period: Duration) {
+        let monitor = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = monitor.check().await {
+                    warn!("Price alert check failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Fetches one rate per asset with pending alerts and fires every alert
+    /// that is met; returns how many fired
+    pub async fn check(&self) -> Result<usize, AppError> {
+        let mut by_asset: HashMap<String, Vec<PriceAlert>> = HashMap::new();
+        for alert in self.store.pending().await? {
+            by_asset.entry(alert.asset_id.clone()).or_default().push(alert);
+        }
+
+        let mut fired = 0;
+        for (asset_id, alerts) in by_asset {
+            let rate = match self.oracle.rate(&asset_id).await {
+                Ok(rate) => rate,
+                Err(e) => {
+                    warn!("No rate for price alerts on {}: {}", asset_id, e);
+                    continue;
+                }
+            };
+
+            let now = Utc::now().timestamp();
+            for mut alert in alerts {
+                if !alert.direction.is_met(rate.units_per_btc, alert.threshold)
+                    || !self.store.mark_triggered(alert.id, now, rate.units_per_btc).await?
+                {
+                    continue;
+                }
+                alert.triggered_at = Some(now);
+                alert.triggered_rate = Some(rate.units_per_btc);
+                info!(
+                    "Price alert {} fired: {} at {} units/BTC",
+                    alert.id, asset_id, rate.units_per_btc
+                );
+
+                if let (Some(url), Some(secret)) = (&alert.webhook_url, &alert.webhook_secret) {
+                    self.deliver_webhook(url.clone(), secret.clone(), &alert, &rate);
+                }
+                // No WebSocket subscribers is fine
+                let _ = self.events.send(PriceAlertEvent {
+                    alert,
+                    rate: rate.clone(),
+                });
+                fired += 1;
+            }
+        }
+        Ok(fired)
+    }
+
+    /// POSTs the fired alert in the background; alerts are one-shot, so a
+    /// failed delivery is only logged
+    fn deliver_webhook(&self, url: String, secret: String, alert: &PriceAlert, rate: &AssetRate) {
+        let event = PriceAlertEvent {
+            alert: alert.clone(),
+            rate: rate.clone(),
+        };
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = check_webhook_target(&url).await {
+                return warn!("Price alert {} webhook refused: {}", event.alert.id, e);
+            }
+            let body = match serde_json::to_string(&event) {
+                Ok(body) => body,
+                Err(e) => return warn!("Failed to encode price alert {}: {}", event.alert.id, e),
+            };
+            let timestamp = Utc::now().timestamp().to_string();
+            let result = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(TIMESTAMP_HEADER, &timestamp)
+                .header(
+                    SIGNATURE_HEADER,
+                    format!("sha256={}", sign_payload(&secret, &timestamp, &body)),
+                )
+                .body(body)
+                .timeout(WEBHOOK_TIMEOUT)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                warn!("Price alert {} webhook failed: {}", event.alert.id, e);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePriceAlertRequest {
+    pub asset_id: String,
+    pub direction: AlertDirection,
+    /// Asset units per BTC, as reported by `/rfq/rates/:asset_id`
+    pub threshold: f64,
+    pub webhook_url: Option<String>,
+}
+
+impl CreatePriceAlertRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.asset_id.len() != 64 || !self.asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AppError::InvalidInput(
+                "asset_id must be 32 bytes of hex".to_string(),
+            ));
+        }
+        if !self.threshold.is_finite() || self.threshold <= 0.0 {
+            return Err(AppError::InvalidInput(
+                "threshold must be a positive number".to_string(),
+            ));
+        }
+        if let Some(url) = &self.webhook_url {
+            validate_webhook_url(url)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatePriceAlertResponse {
+    #[serde(flatten)]
+    pub alert: PriceAlert,
+    /// Verifies the `X-Alert-Signature` header; shown only once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_secret: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PriceAlertParams {
+    pub asset_id: Option<String>,
+}
+
+pub async fn create_alert_handler(
+    State(state): State<AppState>,
+    Json(request): Json<CreatePriceAlertRequest>,
+) -> Result<(StatusCode, Json<CreatePriceAlertResponse>), (StatusCode, Json<Value>)> {
+    request.validate().map_err(error_response)?;
+    let asset_id = request.asset_id.to_ascii_lowercase();
+
+    let store = state.price_alerts.store();
+    let pending = store
+        .list(Some(&asset_id))
+        .await
+        .map_err(error_response)?
+        .iter()
+        .filter(|alert| alert.triggered_at.is_none())
+        .count();
+    if pending >= MAX_ALERTS_PER_ASSET {
+        return Err(error_response(AppError::Conflict(format!(
+            "At most {MAX_ALERTS_PER_ASSET} pending alerts per asset"
+        ))));
+    }
+
+    let webhook_secret = request.webhook_url.as_ref().map(|_| generate_webhook_secret());
+    let alert = PriceAlert {
+        id: Uuid::new_v4(),
+        asset_id,
+        direction: request.direction,
+        threshold: request.threshold,
+        webhook_url: request.webhook_url,
+        webhook_secret: webhook_secret.clone(),
+        created_at: Utc::now().timestamp(),
+        triggered_at: None,
+        triggered_rate: None,
+    };
+    store.create(&alert).await.map_err(error_response)?;
+
+    info!(
+        "Registered {:?} price alert {} for {} at {}",
+        alert.direction, alert.id, alert.asset_id, alert.threshold
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatePriceAlertResponse {
+            alert,
+            webhook_secret,
+        }),
+    ))
+}
+
+pub async fn list_alerts_handler(
+    State(state): State<AppState>,
+    Query(params): Query<PriceAlertParams>,
+) -> Result<Json<Vec<PriceAlert>>, (StatusCode, Json<Value>)> {
+    let asset_id = params.asset_id.map(|id| id.to_ascii_lowercase());
+    let alerts = state
+        .price_alerts
+        .store()
+        .list(asset_id.as_deref())
+        .await
+        .map_err(error_response)?;
+    Ok(Json(alerts))
+}
+
+pub async fn delete_alert_handler(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    if state
+        .price_alerts
+        .store()
+        .delete(id)
+        .await
+        .map_err(error_response)?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(error_response(AppError::NotFound(
+            "Price alert not found".to_string(),
+        )))
+    }
+}
+
+/// Streams fired alerts, optionally for one asset
+pub async fn alerts_ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<PriceAlertParams>,
+) -> Response {
+    let events = state.price_alerts.subscribe();
+    let asset_id = params.asset_id.map(|id| id.to_ascii_lowercase());
+    ws.on_upgrade(move |socket| stream_alerts(socket, events, asset_id))
+}
+
+async fn stream_alerts(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<PriceAlertEvent>,
+    asset_id: Option<String>,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if asset_id.as_ref().is_some_and(|id| id != &event.alert.asset_id) {
+                        continue;
+                    }
+                    let Ok(frame) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(frame)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Price alert subscriber lagged, skipped {} alerts", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::price_alerts::InMemoryPriceAlertStore;
+
+    const ASSET_ID: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    struct FixedOracle(f64);
+
+    #[async_trait::async_trait]
+    impl PriceOracle for FixedOracle {
+        async fn rate(&self, asset_id: &str) -> Result<AssetRate, AppError> {
+            Ok(AssetRate {
+                asset_id: asset_id.to_string(),
+                coefficient: self.0.to_string(),
+                scale: 0,
+                units_per_btc: self.0,
+                source: "test",
+                observed_at: 0,
+                expires_at: None,
+            })
+        }
+    }
+
+    fn alert(direction: AlertDirection, threshold: f64) -> PriceAlert {
+        PriceAlert {
+            id: Uuid::new_v4(),
+            asset_id: ASSET_ID.to_string(),
+            direction,
+            threshold,
+            webhook_url: None,
+            webhook_secret: None,
+            created_at: 0,
+            triggered_at: None,
+            triggered_rate: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_fires_met_alerts_once() {
+        let store = Arc::new(InMemoryPriceAlertStore::new());
+        let above = alert(AlertDirection::Above, 60_000.0);
+        let below = alert(AlertDirection::Below, 60_000.0);
+        store.create(&above).await.unwrap();
+        store.create(&below).await.unwrap();
+
+        let monitor = PriceAlertMonitor::new(
+            store.clone(),
+            Arc::new(FixedOracle(64_000.0)),
+            reqwest::Client::new(),
+        );
+        let mut events = monitor.subscribe();
+
+        assert_eq!(monitor.check().await.unwrap(), 1);
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.alert.id, above.id);
+        assert_eq!(event.alert.triggered_rate, Some(64_000.0));
+
+        assert_eq!(monitor.check().await.unwrap(), 0);
+        let pending = store.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, below.id);
+    }
+
+    #[test]
+    fn test_create_request_validation() {
+        let request = |threshold: f64, webhook_url: Option<&str>| CreatePriceAlertRequest {
+            asset_id: ASSET_ID.to_string(),
+            direction: AlertDirection::Below,
+            threshold,
+            webhook_url: webhook_url.map(str::to_string),
+        };
+        assert!(request(50_000.0, Some("https://example.com/hook")).validate().is_ok());
+        assert!(request(0.0, None).validate().is_err());
+        assert!(request(f64::NAN, None).validate().is_err());
+        assert!(request(1.0, Some("http://example.com/hook")).validate().is_err());
+
+        let mut bad_asset = request(1.0, None);
+        bad_asset.asset_id = "usd".to_string();
+        assert!(bad_asset.validate().is_err());
+    }
+
+    #[test]
+    fn test_create_response_reveals_secret_once() {
+        let mut created = alert(AlertDirection::Above, 1.0);
+        created.webhook_url = Some("https://example.com/hook".to_string());
+        created.webhook_secret = Some("secret".to_string());
+
+        let listed = serde_json::to_value(&created).unwrap();
+        assert!(listed.get("webhook_secret").is_none());
+
+        let response = serde_json::to_value(CreatePriceAlertResponse {
+            webhook_secret: created.webhook_secret.clone(),
+            alert: created,
+        })
+        .unwrap();
+        assert_eq!(response["webhook_secret"], "secret");
+        assert_eq!(response["direction"], "above");
+    }
+}
@@ -0,0 +1,366 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::info;
+
+use super::upstream::SendUpstream;
+use crate::config::{PriceOracleBackend, PriceOracleSettings};
+use crate::error::AppError;
+use crate::types::AppState;
+
+const ORACLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Asset ID tapd's oracle uses for BTC as the payment asset
+const BTC_ASSET_ID: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// An asset's price as `coefficient / 10^scale` asset units per BTC, matching
+/// tapd's fixed-point rates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssetRate {
+    pub asset_id: String,
+    pub coefficient: String,
+    pub scale: u32,
+    /// Convenience float of the fixed-point rate; may lose precision
+    pub units_per_btc: f64,
+    /// Backend that produced the rate
+    pub source: &'static str,
+    /// Unix seconds at which the source priced the asset
+    pub observed_at: i64,
+    /// Unix seconds after which the source no longer stands by the rate
+    pub expires_at: Option<i64>,
+}
+
+impl AssetRate {
+    fn new(
+        asset_id: &str,
+        coefficient: String,
+        scale: u32,
+        source: &'static str,
+        observed_at: i64,
+        expires_at: Option<i64>,
+    ) -> Result<Self, AppError> {
+        let value = coefficient.parse::<f64>().map_err(|_| {
+            AppError::RequestError(format!("Invalid rate coefficient: {coefficient}"))
+        })?;
+        let units_per_btc = value / 10f64.powi(scale as i32);
+        Ok(Self {
+            asset_id: asset_id.to_string(),
+            coefficient,
+            scale,
+            units_per_btc,
+            source,
+            observed_at,
+            expires_at,
+        })
+    }
+}
+
+/// Source of asset prices for RFQ quotes
+#[async_trait::async_trait]
+pub trait PriceOracle: Send + Sync {
+    async fn rate(&self, asset_id: &str) -> Result<AssetRate, AppError>;
+}
+
+/// Rates from tapd's configured price oracle
+pub struct TapdPriceOracle {
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    macaroon_hex: String,
+}
+
+impl TapdPriceOracle {
+    pub fn new(client: Arc<reqwest::Client>, base_url: String, macaroon_hex: String) -> Self {
+        Self {
+            client,
+            base_url,
+            macaroon_hex,
+        }
+    }
+}
+
+/// Reads a uint64 that tapd may encode as a string or a number
+fn json_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(text) => text.parse().ok(),
+        other => other.as_u64(),
+    }
+}
+
+/// Extracts the subject asset rate from a `QueryAssetRates` response
+fn parse_tapd_rate(asset_id: &str, response: &Value, now: i64) -> Result<AssetRate, AppError> {
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(AppError::RequestError(format!("Price oracle error: {message}")));
+    }
+
+    let rates = &response["ok"]["asset_rates"];
+    let rate = &rates["subject_asset_rate"];
+    let coefficient = rate["coefficient"]
+        .as_str()
+        .filter(|coefficient| !coefficient.is_empty())
+        .ok_or_else(|| {
+            AppError::RequestError(format!("Price oracle returned no rate for {asset_id}"))
+        })?;
+    let scale = json_u64(&rate["scale"]).unwrap_or(0) as u32;
+    let expires_at = json_u64(&rates["expiry_timestamp"]).map(|expiry| expiry as i64);
+    AssetRate::new(asset_id, coefficient.to_string(), scale, "tapd", now, expires_at)
+}
+
+#[async_trait::async_trait]
+impl PriceOracle for TapdPriceOracle {
+    async fn rate(&self, asset_id: &str) -> Result<AssetRate, AppError> {
+        info!("Querying tapd price oracle for asset ID: {}", asset_id);
+        let url = format!("{}/v1/taproot-assets/rfq/priceoracle/assetrates", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .query(&[
+                ("transaction_type", "PURCHASE"),
+                ("subject_asset.asset_id_str", asset_id),
+                ("payment_asset.asset_id_str", BTC_ASSET_ID),
+            ])
+            .timeout(ORACLE_TIMEOUT)
+            .send_upstream()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::from_response(response).await);
+        }
+
+        let body = response.json::<Value>().await?;
+        parse_tapd_rate(asset_id, &body, chrono::Utc::now().timestamp())
+    }
+}
+
+/// Rates from an operator-run HTTP feed; `{asset_id}` in the URL is replaced
+/// with the requested asset. The feed answers with
+/// `{"units_per_btc": "<decimal>", "timestamp": <unix seconds>}`.
+pub struct HttpPriceOracle {
+    client: Arc<reqwest::Client>,
+    url_template: String,
+}
+
+impl HttpPriceOracle {
+    pub fn new(client: Arc<reqwest::Client>, url_template: String) -> Self {
+        Self {
+            client,
+            url_template,
+        }
+    }
+}
+
+/// Splits a non-negative decimal such as `"64250.5"` into a fixed-point
+/// coefficient and scale
+pub(crate) fn fixed_point(decimal: &str) -> Option<(String, u32)> {
+    let (whole, fraction) = decimal.trim().split_once('.').unwrap_or((decimal.trim(), ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let digits = format!("{whole}{fraction}");
+    let coefficient = digits.trim_start_matches('0');
+    let coefficient = if coefficient.is_empty() { "0" } else { coefficient };
+    Some((coefficient.to_string(), fraction.len() as u32))
+}
+
+fn parse_feed_rate(asset_id: &str, body: &Value, now: i64) -> Result<AssetRate, AppError> {
+    let rate = match &body["units_per_btc"] {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    };
+    let (coefficient, scale) = rate.as_deref().and_then(fixed_point).ok_or_else(|| {
+        AppError::RequestError(format!("Price feed returned no valid rate for {asset_id}"))
+    })?;
+    let observed_at = body["timestamp"].as_i64().unwrap_or(now);
+    let expires_at = body["expires_at"].as_i64();
+    AssetRate::new(asset_id, coefficient, scale, "http", observed_at, expires_at)
+}
+
+#[async_trait::async_trait]
+impl PriceOracle for HttpPriceOracle {
+    async fn rate(&self, asset_id: &str) -> Result<AssetRate, AppError> {
+        let url = self.url_template.replace("{asset_id}", asset_id);
+        info!("Querying price feed for asset ID: {}", asset_id);
+        let body: Value = self
+            .client
+            .get(&url)
+            .timeout(ORACLE_TIMEOUT)
+            .send_upstream()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        parse_feed_rate(asset_id, &body, chrono::Utc::now().timestamp())
+    }
+}
+
+/// Builds the price oracle selected by the configured backend
+pub fn create_price_oracle(
+    settings: &PriceOracleSettings,
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    macaroon_hex: String,
+) -> Result<Arc<dyn PriceOracle>, AppError> {
+    info!("Using {:?} price oracle", settings.backend);
+
+    let oracle: Arc<dyn PriceOracle> = match settings.backend {
+        PriceOracleBackend::Tapd => Arc::new(TapdPriceOracle::new(client, base_url, macaroon_hex)),
+        PriceOracleBackend::Http => {
+            let url = settings.feed_url.clone().ok_or_else(|| {
+                AppError::ValidationError(
+                    "PRICE_ORACLE_FEED_URL must be set when PRICE_ORACLE_BACKEND=http".to_string(),
+                )
+            })?;
+            Arc::new(HttpPriceOracle::new(client, url))
+        }
+    };
+
+    Ok(oracle)
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetRateResponse {
+    #[serde(flatten)]
+    pub rate: AssetRate,
+    pub age_seconds: i64,
+    /// Past its expiry, or older than the configured maximum age
+    pub stale: bool,
+}
+
+impl AssetRateResponse {
+    fn new(rate: AssetRate, now: i64, max_age_secs: u64) -> Self {
+        let age_seconds = (now - rate.observed_at).max(0);
+        let stale = age_seconds as u64 > max_age_secs
+            || rate.expires_at.is_some_and(|expires_at| now >= expires_at);
+        Self {
+            rate,
+            age_seconds,
+            stale,
+        }
+    }
+}
+
+pub async fn asset_rate_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<AssetRateResponse>, (StatusCode, Json<Value>)> {
+    let asset_id = asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error_response(AppError::InvalidInput(
+            "asset_id must be 32 bytes of hex".to_string(),
+        )));
+    }
+
+    let rate = state
+        .price_oracle
+        .rate(&asset_id)
+        .await
+        .map_err(error_response)?;
+    let now = chrono::Utc::now().timestamp();
+    Ok(Json(AssetRateResponse::new(
+        rate,
+        now,
+        state.price_oracle_max_age_secs,
+    )))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET_ID: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    #[test]
+    fn test_parse_tapd_rate() {
+        let response = serde_json::json!({
+            "ok": {"asset_rates": {
+                "subject_asset_rate": {"coefficient": "6425050", "scale": 2},
+                "payment_asset_rate": {"coefficient": "100000000000", "scale": 0},
+                "expiry_timestamp": "1700000600"
+            }}
+        });
+        let rate = parse_tapd_rate(ASSET_ID, &response, 1_700_000_000).unwrap();
+        assert_eq!(rate.coefficient, "6425050");
+        assert_eq!(rate.scale, 2);
+        assert_eq!(rate.units_per_btc, 64250.5);
+        assert_eq!(rate.expires_at, Some(1_700_000_600));
+        assert_eq!(rate.source, "tapd");
+
+        let error = serde_json::json!({"error": {"message": "unsupported asset", "code": 1}});
+        assert!(parse_tapd_rate(ASSET_ID, &error, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_feed_rate() {
+        let body = serde_json::json!({"units_per_btc": "64250.50", "timestamp": 1_700_000_000});
+        let rate = parse_feed_rate(ASSET_ID, &body, 1_700_000_100).unwrap();
+        assert_eq!((rate.coefficient.as_str(), rate.scale), ("6425050", 2));
+        assert_eq!(rate.observed_at, 1_700_000_000);
+        assert_eq!(rate.source, "http");
+
+        let numeric = serde_json::json!({"units_per_btc": 95000});
+        let rate = parse_feed_rate(ASSET_ID, &numeric, 5).unwrap();
+        assert_eq!((rate.coefficient.as_str(), rate.scale), ("95000", 0));
+        assert_eq!(rate.observed_at, 5);
+
+        assert!(parse_feed_rate(ASSET_ID, &serde_json::json!({"units_per_btc": "-1"}), 0).is_err());
+        assert!(parse_feed_rate(ASSET_ID, &serde_json::json!({}), 0).is_err());
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        assert_eq!(fixed_point("0.0015"), Some(("15".to_string(), 4)));
+        assert_eq!(fixed_point("100"), Some(("100".to_string(), 0)));
+        assert_eq!(fixed_point("0"), Some(("0".to_string(), 0)));
+        assert_eq!(fixed_point("1e5"), None);
+        assert_eq!(fixed_point("."), None);
+    }
+
+    #[test]
+    fn test_staleness() {
+        let rate = AssetRate::new(ASSET_ID, "1".to_string(), 0, "http", 1_000, None).unwrap();
+        assert!(!AssetRateResponse::new(rate.clone(), 1_200, 300).stale);
+        let old = AssetRateResponse::new(rate.clone(), 1_400, 300);
+        assert!(old.stale);
+        assert_eq!(old.age_seconds, 400);
+
+        let expiring = AssetRate {
+            expires_at: Some(1_100),
+            ..rate
+        };
+        assert!(AssetRateResponse::new(expiring, 1_100, 300).stale);
+    }
+
+    #[test]
+    fn test_http_backend_requires_feed_url() {
+        let settings = PriceOracleSettings {
+            backend: PriceOracleBackend::Http,
+            ..PriceOracleSettings::default()
+        };
+        let client = Arc::new(reqwest::Client::new());
+        let result = create_price_oracle(&settings, client, String::new(), String::new());
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
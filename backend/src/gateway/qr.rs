@@ -0,0 +1,174 @@
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use qrcode::{render::svg, Color, EcLevel, QrCode};
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+/// Longest payload accepted; invoices with route hints stay well below this
+const MAX_QR_DATA_BYTES: usize = 2048;
+/// Default and largest rendered edge length in pixels
+const DEFAULT_QR_SIZE: u32 = 256;
+const MAX_QR_SIZE: u32 = 2048;
+/// Light modules around the symbol, as the QR spec requires
+const QUIET_ZONE_MODULES: u32 = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QrParams {
+    /// Invoice, taproot asset address or any other text to encode
+    pub data: String,
+    #[serde(default)]
+    pub format: QrFormat,
+    /// Minimum edge length in pixels
+    pub size: Option<u32>,
+}
+
+fn encode(data: &str) -> Result<QrCode, AppError> {
+    if data.is_empty() {
+        return Err(AppError::InvalidInput("data must not be empty".to_string()));
+    }
+    if data.len() > MAX_QR_DATA_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "data exceeds {MAX_QR_DATA_BYTES} bytes"
+        )));
+    }
+    // Bech32 strings are case-insensitive, so upper case lets invoices and
+    // addresses use the denser alphanumeric mode
+    let data = if is_bech32(data) {
+        data.to_ascii_uppercase()
+    } else {
+        data.to_string()
+    };
+    QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M)
+        .map_err(|e| AppError::InvalidInput(format!("Cannot encode QR code: {e}")))
+}
+
+/// Invoices (`lnbc…`), offers and taproot asset addresses (`taprt…`)
+fn is_bech32(data: &str) -> bool {
+    let single_case = data.to_ascii_lowercase() == data || data.to_ascii_uppercase() == data;
+    single_case
+        && data.chars().all(|c| c.is_ascii_alphanumeric())
+        && data.rfind('1').is_some_and(|separator| separator > 0 && separator < data.len() - 1)
+}
+
+pub fn render_svg(data: &str, size: u32) -> Result<String, AppError> {
+    let code = encode(data)?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(size, size)
+        .quiet_zone(true)
+        .build())
+}
+
+pub fn render_png(data: &str, size: u32) -> Result<Vec<u8>, AppError> {
+    let code = encode(data)?;
+    let width = code.width() as u32;
+    let modules = width + 2 * QUIET_ZONE_MODULES;
+    let scale = size.div_ceil(modules).max(1);
+    let edge = modules * scale;
+
+    let colors = code.to_colors();
+    let mut pixels = vec![0xffu8; (edge * edge) as usize];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x = (index as u32 % width + QUIET_ZONE_MODULES) * scale;
+        let y = (index as u32 / width + QUIET_ZONE_MODULES) * scale;
+        for row in y..y + scale {
+            let start = (row * edge + x) as usize;
+            pixels[start..start + scale as usize].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, edge, edge);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|e| AppError::RequestError(format!("Failed to encode PNG: {e}")))?;
+    Ok(png)
+}
+
+/// Renders `data` as an SVG or PNG QR code so clients need no QR library
+pub async fn qr_handler(Query(params): Query<QrParams>) -> Response {
+    let size = params.size.unwrap_or(DEFAULT_QR_SIZE).clamp(1, MAX_QR_SIZE);
+    let rendered = match params.format {
+        QrFormat::Svg => {
+            render_svg(&params.data, size).map(|svg| ("image/svg+xml", svg.into_bytes()))
+        }
+        QrFormat::Png => render_png(&params.data, size).map(|png| ("image/png", png)),
+    };
+
+    match rendered {
+        Ok((content_type, body)) => (
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CACHE_CONTROL, "public, max-age=86400"),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(error) => error_response(error).into_response(),
+    }
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_svg() {
+        let svg = render_svg("taprt1qqqsqqspqqzzq", 200).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("<svg"));
+    }
+
+    #[test]
+    fn test_render_png_scales_to_size() {
+        let png = render_png("lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyq", 300).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!(info.width, info.height);
+        assert!(info.width >= 300);
+    }
+
+    #[test]
+    fn test_bech32_data_is_detected() {
+        assert!(is_bech32("lnbc10u1pvjluez"));
+        assert!(is_bech32("TAPRT1QQQSQQ"));
+        assert!(!is_bech32("bitcoin:bc1q?amount=1"));
+        assert!(!is_bech32("lnBC1abc"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_data() {
+        assert!(render_svg("", 100).is_err());
+        assert!(render_svg(&"a".repeat(MAX_QR_DATA_BYTES + 1), 100).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::HeaderMap, http::StatusCode, response::Json};
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use super::addresses::{address_receives, decode_address, parse_decoded_address};
+use super::admin::authorize;
+use super::transaction_events::{apply_update, transaction_updates, TransactionUpdate};
+use super::upstream::SendUpstream;
+use crate::error::AppError;
+use crate::storage::transactions::TransactionRepo;
+use crate::taproot::grpc::TapdGrpcClient;
+use crate::types::{AppState, TransactionStatus, TransactionType};
+
+/// What one reconciliation pass changed
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ReconcileReport {
+    /// Receives tapd recorded that were missing locally
+    pub receives_inserted: usize,
+    /// Pending transactions moved to the status tapd reports
+    pub statuses_fixed: usize,
+}
+
+/// Brings persisted transactions in line with tapd, so a missed or dropped
+/// event subscription cannot leave history out of date.
+///
+/// Receives are keyed by address in tapd's receive log and are inserted when
+/// missing. Sends are confirmed once a confirmed transfer pays their address's
+/// script key; transfers made outside the gateway are not imported, since
+/// tapd's transfer log does not name the address they paid.
+pub struct Reconciler {
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    macaroon_hex: String,
+    store: Arc<dyn TransactionRepo>,
+    grpc: Option<TapdGrpcClient>,
+}
+
+impl Reconciler {
+    pub fn new(
+        client: Arc<reqwest::Client>,
+        base_url: String,
+        macaroon_hex: String,
+        store: Arc<dyn TransactionRepo>,
+    ) -> Self {
+        Self {
+            client,
+            base_url,
+            macaroon_hex,
+            store,
+            grpc: None,
+        }
+    }
+
+    /// Lists transfers over tapd's gRPC port; receives and address decoding
+    /// still go to the REST gateway
+    pub fn with_grpc(mut self, grpc: Option<TapdGrpcClient>) -> Self {
+        self.grpc = grpc;
+        self
+    }
+
+    /// Runs a pass every `period`, starting right away
+    pub fn spawn(self: &Arc<Self>, period: Duration) {
+        let reconciler = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = reconciler.run().await {
+                    warn!("Transaction reconciliation failed: {}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn run(&self) -> Result<ReconcileReport, AppError> {
+        let pending = self.store.pending().await?;
+        let mut report = ReconcileReport::default();
+
+        let receives = address_receives(&self.client, &self.base_url, &self.macaroon_hex, None)
+            .await?;
+        let pending_receives: HashSet<&str> = pending
+            .iter()
+            .filter(|(tx, _)| tx.tx_type == TransactionType::Receive)
+            .map(|(_, destination)| destination.as_str())
+            .collect();
+        for update in receive_updates(&receives) {
+            let fixed = update.status != TransactionStatus::Pending
+                && pending_receives.contains(update.destination.as_str());
+            if apply_update(self.store.as_ref(), update).await? {
+                report.receives_inserted += 1;
+            } else if fixed {
+                report.statuses_fixed += 1;
+            }
+        }
+
+        let pending_sends: Vec<&str> = pending
+            .iter()
+            .filter(|(tx, _)| tx.tx_type == TransactionType::Send)
+            .map(|(_, destination)| destination.as_str())
+            .collect();
+        if !pending_sends.is_empty() {
+            let confirmed = confirmed_script_keys(&self.list_transfers().await?);
+            for destination in pending_sends {
+                if self.pays_script_key(destination, &confirmed).await {
+                    let status = TransactionStatus::Confirmed;
+                    self.store.update_status(TransactionType::Send, destination, status).await?;
+                    report.statuses_fixed += 1;
+                }
+            }
+        }
+
+        if report != ReconcileReport::default() {
+            info!(
+                "Reconciled transactions with tapd: {} receives inserted, {} statuses fixed",
+                report.receives_inserted, report.statuses_fixed
+            );
+        }
+        Ok(report)
+    }
+
+    async fn list_transfers(&self) -> Result<Value, AppError> {
+        if let Some(grpc) = &self.grpc {
+            return grpc.list_transfers(None).await;
+        }
+        let url = format!("{}/v1/taproot-assets/assets/transfers", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+            .send_upstream()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::from_response(response).await);
+        }
+        Ok(response.json::<Value>().await?)
+    }
+
+    /// Whether `destination` decodes to one of `script_keys`; addresses tapd
+    /// cannot decode are left pending
+    async fn pays_script_key(&self, destination: &str, script_keys: &HashSet<String>) -> bool {
+        let decoded =
+            decode_address(&self.client, &self.base_url, &self.macaroon_hex, destination).await;
+        match decoded {
+            Ok(addr) => script_keys.contains(&parse_decoded_address("", &addr).script_key),
+            Err(e) => {
+                warn!("Could not decode {} for reconciliation: {}", destination, e);
+                false
+            }
+        }
+    }
+}
+
+/// Reads tapd's `AddrReceivesResponse` as the updates its live receive events
+/// would have produced, keeping the latest status per address
+fn receive_updates(response: &Value) -> Vec<TransactionUpdate> {
+    let mut latest: HashMap<String, (i64, TransactionUpdate)> = HashMap::new();
+    for event in response["events"].as_array().into_iter().flatten() {
+        let created_at = match &event["creation_time_unix_seconds"] {
+            Value::String(text) => text.parse().unwrap_or(0),
+            other => other.as_i64().unwrap_or(0),
+        };
+        let receive = serde_json::json!({"address": event["addr"], "status": event["status"]});
+        for update in transaction_updates("asset-receive", &receive) {
+            match latest.get(&update.destination) {
+                Some((seen, _)) if *seen > created_at => {}
+                _ => {
+                    latest.insert(update.destination.clone(), (created_at, update));
+                }
+            }
+        }
+    }
+    let mut updates: Vec<(i64, TransactionUpdate)> = latest.into_values().collect();
+    updates.sort_by(|a, b| (a.0, &a.1.destination).cmp(&(b.0, &b.1.destination)));
+    updates.into_iter().map(|(_, update)| update).collect()
+}
+
+/// Hex script keys of remote outputs in transfers whose anchor transaction
+/// has confirmed
+fn confirmed_script_keys(transfers: &Value) -> HashSet<String> {
+    transfers["transfers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|transfer| {
+            transfer["anchor_tx_block_hash"]["hash_str"]
+                .as_str()
+                .is_some_and(|hash| !hash.is_empty())
+        })
+        .flat_map(|transfer| transfer["outputs"].as_array().into_iter().flatten())
+        .filter(|output| !output["script_key_is_local"].as_bool().unwrap_or(false))
+        .filter_map(|output| {
+            let key = output["script_key"].as_str()?;
+            base64::engine::general_purpose::STANDARD.decode(key).ok().map(hex::encode)
+        })
+        .collect()
+}
+
+/// Runs a reconciliation pass now and reports what it changed
+pub async fn reconcile_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ReconcileReport>, (StatusCode, Json<Value>)> {
+    authorize(&state, &headers).map_err(error_response)?;
+    let report = state.reconciler.run().await.map_err(error_response)?;
+    Ok(Json(report))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    (
+        status,
+        Json(serde_json::json!({
+            "error": error.to_string(),
+            "type": format!("{:?}", error)
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receive_updates_keep_latest_status() {
+        let event = |status: &str, created_at: &str| {
+            serde_json::json!({
+                "creation_time_unix_seconds": created_at,
+                "addr": {"encoded": "taprt1alice", "asset_id": "asset", "amount": "40"},
+                "status": status,
+            })
+        };
+        let response = serde_json::json!({"events": [
+            event("ADDR_EVENT_STATUS_COMPLETED", "20"),
+            event("ADDR_EVENT_STATUS_TRANSACTION_DETECTED", "10"),
+            event("ADDR_EVENT_STATUS_PROOF_RECEIVED", "15"),
+        ]});
+
+        let updates = receive_updates(&response);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].destination, "taprt1alice");
+        assert_eq!(updates[0].status, TransactionStatus::Confirmed);
+        assert_eq!(updates[0].amount, 40);
+    }
+
+    #[test]
+    fn test_confirmed_script_keys() {
+        let key = |byte: u8| base64::engine::general_purpose::STANDARD.encode([byte; 33]);
+        let transfers = serde_json::json!({"transfers": [
+            {
+                "anchor_tx_block_hash": {"hash_str": "00ab"},
+                "outputs": [
+                    {"script_key": key(2), "script_key_is_local": false},
+                    {"script_key": key(3), "script_key_is_local": true},
+                ],
+            },
+            {
+                "anchor_tx_block_hash": null,
+                "outputs": [{"script_key": key(4), "script_key_is_local": false}],
+            },
+        ]});
+
+        let keys = confirmed_script_keys(&transfers);
+        assert_eq!(keys, HashSet::from(["02".repeat(33)]));
+    }
+}
@@ -1,27 +1,83 @@
 use axum::{
-    extract::{Path, State, WebSocketUpgrade, ws::{WebSocket, Message}},
+    extract::{Path, Query, State, WebSocketUpgrade, ws::{WebSocket, Message}},
     response::{Response, Json},
     http::StatusCode,
 };
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::{interval, Duration};
-use tracing::{info, error, instrument};
+use tracing::{info, error, instrument, warn};
+use super::upstream::SendUpstream;
 use crate::{
     error::AppError,
+    gateway::events::drain_stream_messages,
+    storage::rfq_orders::{
+        RfqOrderKind, RfqOrderQuery, RfqOrderRecord, RfqOrderStatus, RfqOrderStore,
+    },
     types::AppState,
 };
 
+/// The asset an RFQ request refers to, in tapd's `AssetSpecifier` oneof
+/// shape: `{"asset_id_str": "<hex>"}` and so on. The bytes variants are
+/// base64 as in tapd's REST API, the `_str` variants hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetSpecifier {
+    AssetId(String),
+    AssetIdStr(String),
+    GroupKey(String),
+    GroupKeyStr(String),
+}
+
+impl AssetSpecifier {
+    /// Checks that the specifier decodes to a 32-byte asset ID or a 33-byte
+    /// compressed group key
+    pub fn validate(&self) -> Result<(), AppError> {
+        use base64::Engine;
+
+        let base64 = |value: &str| base64::engine::general_purpose::STANDARD.decode(value).ok();
+        let (bytes, is_group_key, encoding) = match self {
+            AssetSpecifier::AssetId(value) => (base64(value), false, "base64"),
+            AssetSpecifier::AssetIdStr(value) => (hex::decode(value).ok(), false, "hex"),
+            AssetSpecifier::GroupKey(value) => (base64(value), true, "base64"),
+            AssetSpecifier::GroupKeyStr(value) => (hex::decode(value).ok(), true, "hex"),
+        };
+        let (valid, expected) = if is_group_key {
+            let compressed = bytes.is_some_and(|b| b.len() == 33 && matches!(b[0], 0x02 | 0x03));
+            (compressed, "a 33-byte compressed public key")
+        } else {
+            (bytes.is_some_and(|b| b.len() == 32), "32 bytes")
+        };
+        if valid {
+            return Ok(());
+        }
+        Err(AppError::InvalidInput(format!(
+            "asset_specifier.{} must be {expected} of {encoding}",
+            self.field()
+        )))
+    }
+
+    fn field(&self) -> &'static str {
+        match self {
+            AssetSpecifier::AssetId(_) => "asset_id",
+            AssetSpecifier::AssetIdStr(_) => "asset_id_str",
+            AssetSpecifier::GroupKey(_) => "group_key",
+            AssetSpecifier::GroupKeyStr(_) => "group_key_str",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BuyOfferRequest {
-    pub asset_specifier: serde_json::Value,
+    pub asset_specifier: AssetSpecifier,
     pub max_units: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BuyOrderRequest {
-    pub asset_specifier: serde_json::Value,
+    pub asset_specifier: AssetSpecifier,
     pub asset_max_amt: String,
     pub expiry: String,
     pub peer_pub_key: String,
@@ -31,13 +87,13 @@ pub struct BuyOrderRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SellOfferRequest {
-    pub asset_specifier: serde_json::Value,
+    pub asset_specifier: AssetSpecifier,
     pub max_units: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SellOrderRequest {
-    pub asset_specifier: serde_json::Value,
+    pub asset_specifier: AssetSpecifier,
     pub payment_max_amt: String,
     pub expiry: String,
     pub peer_pub_key: String,
@@ -45,6 +101,105 @@ pub struct SellOrderRequest {
     pub skip_asset_channel_check: bool,
 }
 
+/// Which side of the book a standing offer is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OfferSide {
+    Buy,
+    Sell,
+}
+
+impl OfferSide {
+    fn path(&self) -> &'static str {
+        match self {
+            OfferSide::Buy => "buyoffer",
+            OfferSide::Sell => "selloffer",
+        }
+    }
+}
+
+/// A peer-accepted quote with its remaining lifetime
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PeerQuote {
+    pub side: OfferSide,
+    pub id: String,
+    pub peer: String,
+    /// Unix seconds
+    pub expiry: i64,
+    pub expires_in_secs: i64,
+    /// The quote as tapd returned it
+    pub quote: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ActiveQuotesParams {
+    /// Also drop quotes expiring within this many seconds
+    pub min_remaining_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveQuotesResponse {
+    pub quotes: Vec<PeerQuote>,
+    /// Quotes tapd still lists but that are expired or about to be
+    pub filtered: usize,
+}
+
+/// Flattens a `QueryPeerAcceptedQuotes` response; tapd's `buy_quotes` are the
+/// quotes we asked to buy with, `sell_quotes` those we asked to sell with
+pub fn tracked_quotes(listing: &Value, now: i64) -> Vec<PeerQuote> {
+    let text = |quote: &Value, field: &str| {
+        quote.get(field).and_then(Value::as_str).unwrap_or_default().to_string()
+    };
+    [("buy_quotes", OfferSide::Buy), ("sell_quotes", OfferSide::Sell)]
+        .into_iter()
+        .flat_map(|(field, side)| {
+            listing
+                .get(field)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(move |quote| (side, quote))
+        })
+        .map(|(side, quote)| {
+            let expiry = match &quote["expiry"] {
+                Value::String(expiry) => expiry.parse().unwrap_or(0),
+                other => other.as_i64().unwrap_or(0),
+            };
+            PeerQuote {
+                side,
+                id: text(quote, "id"),
+                peer: text(quote, "peer"),
+                expiry,
+                expires_in_secs: expiry - now,
+                quote: quote.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Quotes with more than `min_remaining_secs` left, soonest expiry first
+pub fn active_quotes(listing: &Value, now: i64, min_remaining_secs: u64) -> ActiveQuotesResponse {
+    let all = tracked_quotes(listing, now);
+    let total = all.len();
+    let mut quotes: Vec<PeerQuote> = all
+        .into_iter()
+        .filter(|quote| quote.expires_in_secs > min_remaining_secs as i64)
+        .collect();
+    quotes.sort_by_key(|quote| quote.expiry);
+    ActiveQuotesResponse {
+        filtered: total - quotes.len(),
+        quotes,
+    }
+}
+
+/// Confirms that a standing offer was withdrawn
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CancelOfferResponse {
+    pub asset_id: String,
+    pub side: OfferSide,
+    pub cancelled: bool,
+}
+
 // Core RFQ functions
 #[instrument(skip(client, macaroon_hex, request))]
 pub async fn buy_offer(
@@ -60,12 +215,11 @@ pub async fn buy_offer(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
@@ -86,18 +240,55 @@ pub async fn buy_order(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
     Ok(result)
 }
 
+/// Removes the standing buy or sell offer for an asset. tapd builds without
+/// offer removal answer 404/405/501, which map to `NotImplemented`.
+#[instrument(skip(client, macaroon_hex))]
+pub async fn cancel_offer(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    side: OfferSide,
+    asset_id: &str,
+) -> Result<CancelOfferResponse, AppError> {
+    info!("Cancelling {:?} offer for asset ID: {}", side, asset_id);
+    let url = format!("{base_url}/v1/taproot-assets/rfq/{}/asset-id/{asset_id}", side.path());
+    let response = client
+        .delete(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .send_upstream()
+        .await?;
+
+    let status = response.status();
+    if matches!(
+        status,
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+    ) {
+        return Err(AppError::NotImplemented(format!(
+            "Upstream RFQ API cannot remove {side:?} offers"
+        )));
+    }
+    if !status.is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(CancelOfferResponse {
+        asset_id: asset_id.to_string(),
+        side,
+        cancelled: true,
+    })
+}
+
 #[instrument(skip(client, macaroon_hex))]
 pub async fn get_notifications(
     client: &reqwest::Client,
@@ -110,12 +301,11 @@ pub async fn get_notifications(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&serde_json::json!({}))
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
@@ -133,12 +323,11 @@ pub async fn get_asset_rates(
     let response = client
         .get(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
@@ -156,12 +345,11 @@ pub async fn get_peer_quotes(
     let response = client
         .get(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
@@ -182,12 +370,11 @@ pub async fn sell_offer(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
@@ -208,31 +395,224 @@ pub async fn sell_order(
         .post(&url)
         .header("Grpc-Metadata-macaroon", macaroon_hex)
         .json(&request)
-        .send()
+        .send_upstream()
         .await?;
     
     if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(AppError::RequestError(error_text));
+        return Err(AppError::from_response(response).await);
     }
     
     let result = response.json::<Value>().await?;
     Ok(result)
 }
 
+/// Stores a submitted offer or order with tapd's answer; history is best effort
+async fn record_order(
+    state: &AppState,
+    kind: RfqOrderKind,
+    asset_id: &str,
+    request: Value,
+    result: &Result<Value, AppError>,
+) -> RfqOrderRecord {
+    let order = RfqOrderRecord::new(kind, asset_id.to_string(), request, result.as_ref());
+    if let Err(e) = state.rfq_order_store.record(&order).await {
+        warn!("Failed to record RFQ {:?} {}: {}", kind, order.id, e);
+    }
+    order
+}
+
+/// Shortest lifetime a refreshed order asks for
+const MIN_REFRESH_EXPIRY_SECS: i64 = 60;
+
+/// Moves an order's `expiry` forward so a resubmission keeps the lifetime
+/// originally requested
+fn refreshed_request(order: &RfqOrderRecord, now: i64) -> Value {
+    let mut request = order.request.clone();
+    let expiry = request["expiry"]
+        .as_str()
+        .and_then(|expiry| expiry.parse::<i64>().ok())
+        .unwrap_or(0);
+    let lifetime = (expiry - order.created_at).max(MIN_REFRESH_EXPIRY_SECS);
+    request["expiry"] = Value::String((now + lifetime).to_string());
+    request
+}
+
+/// Peer-accepted quotes from tapd, or from the simulator when RFQ simulation is on
+async fn peer_quote_listing(state: &AppState) -> Result<Value, AppError> {
+    match &state.rfq_simulator {
+        Some(simulator) => Ok(simulator.peer_quotes(chrono::Utc::now().timestamp())),
+        None => {
+            get_peer_quotes(&state.http_client, &state.base_url.0, &state.macaroon_hex.0).await
+        }
+    }
+}
+
+async fn submit_buy_order(
+    state: &AppState,
+    request: BuyOrderRequest,
+    asset_id: &str,
+) -> Result<Value, AppError> {
+    match &state.rfq_simulator {
+        Some(simulator) => {
+            Ok(simulator.buy_order(asset_id, &request, chrono::Utc::now().timestamp()))
+        }
+        None => {
+            let (client, base_url, macaroon_hex) =
+                (&state.http_client, &state.base_url.0, &state.macaroon_hex.0);
+            buy_order(client, base_url, macaroon_hex, request, asset_id).await
+        }
+    }
+}
+
+async fn submit_sell_order(
+    state: &AppState,
+    request: SellOrderRequest,
+    asset_id: &str,
+) -> Result<Value, AppError> {
+    match &state.rfq_simulator {
+        Some(simulator) => {
+            Ok(simulator.sell_order(asset_id, &request, chrono::Utc::now().timestamp()))
+        }
+        None => {
+            let (client, base_url, macaroon_hex) =
+                (&state.http_client, &state.base_url.0, &state.macaroon_hex.0);
+            sell_order(client, base_url, macaroon_hex, request, asset_id).await
+        }
+    }
+}
+
+/// Replaces a quote that expires within `RFQ_QUOTE_REFRESH_SECS` by
+/// resubmitting the stored order that produced it. Returns the new quote ID,
+/// or `None` when no refresh is needed or possible.
+pub async fn refresh_expiring_quote(
+    state: &AppState,
+    rfq_id: &str,
+) -> Result<Option<String>, AppError> {
+    let margin = state.rfq_settings.quote_refresh_secs;
+    if margin == 0 {
+        return Ok(None);
+    }
+
+    let listing = peer_quote_listing(state).await?;
+    let now = chrono::Utc::now().timestamp();
+    let expiring = tracked_quotes(&listing, now)
+        .into_iter()
+        .any(|quote| quote.id == rfq_id && quote.expires_in_secs <= margin as i64);
+    if !expiring {
+        return Ok(None);
+    }
+
+    let query = RfqOrderQuery {
+        rfq_id: Some(rfq_id.to_string()),
+        limit: 1,
+        ..Default::default()
+    };
+    let Some(order) = state.rfq_order_store.list(&query).await?.into_iter().next() else {
+        return Ok(None);
+    };
+    let request = refreshed_request(&order, now);
+    let result = match order.kind {
+        RfqOrderKind::BuyOrder => {
+            let typed = serde_json::from_value(request.clone())?;
+            submit_buy_order(state, typed, &order.asset_id).await
+        }
+        RfqOrderKind::SellOrder => {
+            let typed = serde_json::from_value(request.clone())?;
+            submit_sell_order(state, typed, &order.asset_id).await
+        }
+        RfqOrderKind::BuyOffer | RfqOrderKind::SellOffer => return Ok(None),
+    };
+
+    let refreshed = record_order(state, order.kind, &order.asset_id, request, &result).await;
+    match refreshed.status {
+        RfqOrderStatus::Accepted => {
+            info!("Refreshed expiring quote {} as {:?}", rfq_id, refreshed.rfq_id);
+            Ok(refreshed.rfq_id)
+        }
+        _ => Err(AppError::RequestError(format!(
+            "Quote refresh was not accepted: {}",
+            refreshed.failure_reason.unwrap_or_default()
+        ))),
+    }
+}
+
+/// Default page size for the RFQ order history
+pub const RFQ_ORDERS_DEFAULT_LIMIT: usize = 100;
+/// Largest page of RFQ order history returned at once
+pub const RFQ_ORDERS_MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RfqOrdersParams {
+    pub kind: Option<RfqOrderKind>,
+    pub status: Option<RfqOrderStatus>,
+    pub asset_id: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RfqOrdersResponse {
+    pub orders: Vec<RfqOrderRecord>,
+    /// Offset of the next page, absent on the last page
+    pub next_offset: Option<usize>,
+}
+
+/// Returns one page of offers and orders submitted through the gateway, newest first
+pub async fn order_history(
+    store: &dyn RfqOrderStore,
+    params: RfqOrdersParams,
+) -> Result<RfqOrdersResponse, AppError> {
+    let limit = params
+        .limit
+        .unwrap_or(RFQ_ORDERS_DEFAULT_LIMIT)
+        .clamp(1, RFQ_ORDERS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+
+    // One extra row tells whether another page follows
+    let mut orders = store
+        .list(&RfqOrderQuery {
+            kind: params.kind,
+            status: params.status,
+            asset_id: params.asset_id,
+            rfq_id: None,
+            since: None,
+            limit: limit + 1,
+            offset,
+        })
+        .await?;
+    let next_offset = (orders.len() > limit).then_some(offset + limit);
+    orders.truncate(limit);
+
+    Ok(RfqOrdersResponse {
+        orders,
+        next_offset,
+    })
+}
+
 // Axum handlers
 pub async fn buy_offer_handler(
     State(state): State<AppState>,
     Path(asset_id): Path<String>,
     Json(request): Json<BuyOfferRequest>,
 ) -> Result<Json<Value>, StatusCode> {
-    match buy_offer(
-        &state.http_client,
-        &state.base_url.0,
-        &state.macaroon_hex.0,
-        request,
-        &asset_id,
-    ).await {
+    if let Err(e) = request.asset_specifier.validate() {
+        warn!("Rejected buy offer request: {}", e);
+        return Err(e.status_code());
+    }
+    let request_json = serde_json::to_value(&request).unwrap_or_default();
+    let result = match &state.rfq_simulator {
+        Some(simulator) => Ok(simulator.offer()),
+        None => buy_offer(
+            &state.http_client,
+            &state.base_url.0,
+            &state.macaroon_hex.0,
+            request,
+            &asset_id,
+        ).await,
+    };
+    record_order(&state, RfqOrderKind::BuyOffer, &asset_id, request_json, &result).await;
+
+    match result {
         Ok(result) => Ok(Json(result)),
         Err(e) => {
             error!("Buy offer failed: {}", e);
@@ -246,13 +626,15 @@ pub async fn buy_order_handler(
     Path(asset_id): Path<String>,
     Json(request): Json<BuyOrderRequest>,
 ) -> Result<Json<Value>, StatusCode> {
-    match buy_order(
-        &state.http_client,
-        &state.base_url.0,
-        &state.macaroon_hex.0,
-        request,
-        &asset_id,
-    ).await {
+    if let Err(e) = request.asset_specifier.validate() {
+        warn!("Rejected buy order request: {}", e);
+        return Err(e.status_code());
+    }
+    let request_json = serde_json::to_value(&request).unwrap_or_default();
+    let result = submit_buy_order(&state, request, &asset_id).await;
+    record_order(&state, RfqOrderKind::BuyOrder, &asset_id, request_json, &result).await;
+
+    match result {
         Ok(result) => Ok(Json(result)),
         Err(e) => {
             error!("Buy order failed: {}", e);
@@ -261,6 +643,19 @@ pub async fn buy_order_handler(
     }
 }
 
+pub async fn orders_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RfqOrdersParams>,
+) -> Result<Json<RfqOrdersResponse>, StatusCode> {
+    match order_history(state.rfq_order_store.as_ref(), params).await {
+        Ok(result) => Ok(Json(result)),
+        Err(e) => {
+            error!("Get RFQ orders failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
 pub async fn notifications_handler(
     State(state): State<AppState>,
 ) -> Result<Json<Value>, StatusCode> {
@@ -296,11 +691,7 @@ pub async fn asset_rates_handler(
 pub async fn peer_quotes_handler(
     State(state): State<AppState>,
 ) -> Result<Json<Value>, StatusCode> {
-    match get_peer_quotes(
-        &state.http_client,
-        &state.base_url.0,
-        &state.macaroon_hex.0,
-    ).await {
+    match peer_quote_listing(&state).await {
         Ok(result) => Ok(Json(result)),
         Err(e) => {
             error!("Get peer quotes failed: {}", e);
@@ -309,18 +700,46 @@ pub async fn peer_quotes_handler(
     }
 }
 
+pub async fn active_quotes_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ActiveQuotesParams>,
+) -> Result<Json<ActiveQuotesResponse>, StatusCode> {
+    match peer_quote_listing(&state).await {
+        Ok(listing) => {
+            let now = chrono::Utc::now().timestamp();
+            let min_remaining = params.min_remaining_secs.unwrap_or(0);
+            Ok(Json(active_quotes(&listing, now, min_remaining)))
+        }
+        Err(e) => {
+            error!("Get active quotes failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
 pub async fn sell_offer_handler(
     State(state): State<AppState>,
     Path(asset_id): Path<String>,
     Json(request): Json<SellOfferRequest>,
 ) -> Result<Json<Value>, StatusCode> {
-    match sell_offer(
-        &state.http_client,
-        &state.base_url.0,
-        &state.macaroon_hex.0,
-        request,
-        &asset_id,
-    ).await {
+    if let Err(e) = request.asset_specifier.validate() {
+        warn!("Rejected sell offer request: {}", e);
+        return Err(e.status_code());
+    }
+    let request_json = serde_json::to_value(&request).unwrap_or_default();
+    let result = match &state.rfq_simulator {
+        Some(simulator) => Ok(simulator.offer()),
+        None => sell_offer(
+            &state.http_client,
+            &state.base_url.0,
+            &state.macaroon_hex.0,
+            request,
+            &asset_id,
+        ).await,
+    };
+    record_order(&state, RfqOrderKind::SellOffer, &asset_id, request_json, &result).await;
+
+    match result {
         Ok(result) => Ok(Json(result)),
         Err(e) => {
             error!("Sell offer failed: {}", e);
@@ -334,21 +753,188 @@ pub async fn sell_order_handler(
     Path(asset_id): Path<String>,
     Json(request): Json<SellOrderRequest>,
 ) -> Result<Json<Value>, StatusCode> {
-    match sell_order(
+    if let Err(e) = request.asset_specifier.validate() {
+        warn!("Rejected sell order request: {}", e);
+        return Err(e.status_code());
+    }
+    let request_json = serde_json::to_value(&request).unwrap_or_default();
+    let result = submit_sell_order(&state, request, &asset_id).await;
+    record_order(&state, RfqOrderKind::SellOrder, &asset_id, request_json, &result).await;
+
+    match result {
+        Ok(result) => Ok(Json(result)),
+        Err(e) => {
+            error!("Sell order failed: {}", e);
+            Err(e.status_code())
+        }
+    }
+}
+
+async fn cancel_offer_handler(
+    state: &AppState,
+    side: OfferSide,
+    asset_id: &str,
+) -> Result<Json<CancelOfferResponse>, StatusCode> {
+    if state.rfq_simulator.is_some() {
+        return Ok(Json(CancelOfferResponse {
+            asset_id: asset_id.to_string(),
+            side,
+            cancelled: true,
+        }));
+    }
+    match cancel_offer(
         &state.http_client,
         &state.base_url.0,
         &state.macaroon_hex.0,
-        request,
-        &asset_id,
+        side,
+        asset_id,
     ).await {
         Ok(result) => Ok(Json(result)),
         Err(e) => {
-            error!("Sell order failed: {}", e);
+            error!("Cancel {:?} offer failed: {}", side, e);
             Err(e.status_code())
         }
     }
 }
 
+pub async fn cancel_buy_offer_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<CancelOfferResponse>, StatusCode> {
+    cancel_offer_handler(&state, OfferSide::Buy, &asset_id).await
+}
+
+pub async fn cancel_sell_offer_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<CancelOfferResponse>, StatusCode> {
+    cancel_offer_handler(&state, OfferSide::Sell, &asset_id).await
+}
+
+const RFQ_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// Why a streaming RFQ subscription stopped without an error
+enum RfqStreamEnd {
+    /// The WebSocket side went away
+    ClientGone,
+    /// tapd closed or dropped the stream
+    Closed,
+}
+
+/// Converts streamed `/rfq/ntfs` messages into WebSocket frames; upstream
+/// errors use the same frame as failed polls
+fn notification_frames(buffer: &mut String) -> Vec<String> {
+    drain_stream_messages(buffer)
+        .into_iter()
+        .map(|message| match message {
+            Ok(event) => event.to_string(),
+            Err(error) => serde_json::json!({
+                "error": error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string()),
+                "type": "rfq_notification_error"
+            })
+            .to_string(),
+        })
+        .collect()
+}
+
+/// Holds a streaming subscription to `/rfq/ntfs` open, forwarding each event as
+/// it arrives; errors only when the subscription cannot be opened
+async fn stream_notifications(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    tx: &UnboundedSender<String>,
+) -> Result<RfqStreamEnd, AppError> {
+    info!("Opening RFQ notification stream");
+    let url = format!("{base_url}/v1/taproot-assets/rfq/ntfs");
+    let mut response = client
+        .post(&url)
+        .header("Grpc-Metadata-macaroon", macaroon_hex)
+        .json(&serde_json::json!({}))
+        .send_upstream()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(AppError::RequestError(format!(
+            "RFQ notification stream failed with status {status}: {error_text}"
+        )));
+    }
+
+    let mut buffer = String::new();
+    loop {
+        tokio::select! {
+            chunk = response.chunk() => {
+                let chunk = match chunk {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => return Ok(RfqStreamEnd::Closed),
+                    Err(e) => {
+                        warn!("RFQ notification stream dropped: {}", e);
+                        return Ok(RfqStreamEnd::Closed);
+                    }
+                };
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                for frame in notification_frames(&mut buffer) {
+                    if tx.send(frame).is_err() {
+                        return Ok(RfqStreamEnd::ClientGone);
+                    }
+                }
+            }
+            // Dropping the upstream response cancels the subscription
+            _ = tx.closed() => return Ok(RfqStreamEnd::ClientGone),
+        }
+    }
+}
+
+/// Fallback for tapd builds that cannot stream notifications
+async fn poll_notifications(
+    client: &reqwest::Client,
+    base_url: &str,
+    macaroon_hex: &str,
+    tx: &UnboundedSender<String>,
+    period: Duration,
+) {
+    let mut poll_interval = interval(period);
+
+    loop {
+        poll_interval.tick().await;
+
+        match get_notifications(client, base_url, macaroon_hex).await {
+            Ok(events) => {
+                let event_json = serde_json::to_string(&events)
+                    .unwrap_or_else(|_| "{}".to_string());
+
+                if tx.send(event_json).is_err() {
+                    error!("Failed to send RFQ event to channel");
+                    break;
+                }
+            }
+            Err(e) => {
+                error!("Failed to fetch RFQ notifications: {}", e);
+
+                let error_msg = serde_json::json!({
+                    "error": e.to_string(),
+                    "type": "rfq_notification_error"
+                });
+
+                if tx.send(error_msg.to_string()).is_err() {
+                    error!("Failed to send error message to channel");
+                    break;
+                }
+            }
+        }
+    }
+}
+
 // WebSocket handler for RFQ events
 pub async fn rfq_events_ws_handler(
     ws: WebSocketUpgrade,
@@ -371,46 +957,32 @@ async fn handle_rfq_websocket(socket: WebSocket, state: AppState) {
     let client = state.http_client.clone();
     let base_url = state.base_url.0.clone();
     let macaroon_hex = state.macaroon_hex.0.clone();
+    let settings = state.rfq_settings.clone();
     
-    // Create a channel for communication between polling task and main handler
+    // Create a channel for communication between the notification task and main handler
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
-    
-    // Create polling task
-    let poll_task = tokio::spawn(async move {
-        let mut poll_interval = interval(Duration::from_secs(5)); // Default 5 seconds
-        
+
+    // Forward streamed notifications, polling only if tapd cannot stream them
+    let notification_task = tokio::spawn(async move {
         loop {
-            poll_interval.tick().await;
-            
-            match get_notifications(&client, &base_url, &macaroon_hex).await {
-                Ok(events) => {
-                    let event_json = serde_json::to_string(&events)
-                        .unwrap_or_else(|_| "{}".to_string());
-                    
-                    if tx.send(event_json).is_err() {
-                        error!("Failed to send RFQ event to channel");
-                        break;
-                    }
+            match stream_notifications(&client, &base_url, &macaroon_hex, &tx).await {
+                Ok(RfqStreamEnd::ClientGone) => return,
+                Ok(RfqStreamEnd::Closed) => {
+                    info!("RFQ notification stream ended, resubscribing");
+                    tokio::time::sleep(RFQ_RESUBSCRIBE_DELAY).await;
                 }
                 Err(e) => {
-                    error!("Failed to fetch RFQ notifications: {}", e);
-                    
-                    let error_msg = serde_json::json!({
-                        "error": e.to_string(),
-                        "type": "rfq_notification_error"
-                    });
-                    
-                    if tx.send(error_msg.to_string()).is_err() {
-                        error!("Failed to send error message to channel");
-                        break;
-                    }
+                    warn!("RFQ notification streaming unavailable, polling instead: {}", e);
+                    break;
                 }
             }
         }
+        poll_notifications(&client, &base_url, &macaroon_hex, &tx, settings.poll_interval())
+            .await;
     });
-    
+
     // Handle incoming messages and keep connection alive
-    let mut ping_interval = interval(Duration::from_secs(30));
+    let mut ping_interval = interval(state.rfq_settings.ping_interval());
     
     loop {
         tokio::select! {
@@ -461,6 +1033,156 @@ async fn handle_rfq_websocket(socket: WebSocket, state: AppState) {
         }
     }
     
-    // Clean up polling task
-    poll_task.abort();
+    // Clean up notification task
+    notification_task.abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::rfq_orders::InMemoryRfqOrderStore;
+
+    #[test]
+    fn test_notification_frames() {
+        let mut buffer = concat!(
+            r#"{"result": {"peer_accepted_buy_quote": {"id": "cXVvdGU="}}}"#,
+            "\n",
+            r#"{"error": {"code": 2, "message": "rfq manager stopped"}}"#,
+            "\n",
+            r#"{"result": {"#
+        )
+        .to_string();
+
+        let frames = notification_frames(&mut buffer);
+        assert_eq!(frames.len(), 2);
+        let event: Value = serde_json::from_str(&frames[0]).unwrap();
+        assert_eq!(event["peer_accepted_buy_quote"]["id"], "cXVvdGU=");
+        let error: Value = serde_json::from_str(&frames[1]).unwrap();
+        assert_eq!(error["error"], "rfq manager stopped");
+        assert_eq!(error["type"], "rfq_notification_error");
+        assert_eq!(buffer, r#"{"result": {"#);
+    }
+
+    #[test]
+    fn test_asset_specifier_validation() {
+        let asset_id = "a1".repeat(32);
+        let group_key = format!("02{}", "b2".repeat(32));
+        let parsed: BuyOfferRequest = serde_json::from_value(serde_json::json!({
+            "asset_specifier": {"asset_id_str": asset_id},
+            "max_units": "100"
+        }))
+        .unwrap();
+        assert_eq!(parsed.asset_specifier, AssetSpecifier::AssetIdStr(asset_id.clone()));
+        assert_eq!(
+            serde_json::to_value(&parsed.asset_specifier).unwrap(),
+            serde_json::json!({"asset_id_str": asset_id})
+        );
+
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode([7u8; 32]);
+        for valid in [
+            AssetSpecifier::AssetIdStr(asset_id.clone()),
+            AssetSpecifier::AssetId(encoded.clone()),
+            AssetSpecifier::GroupKeyStr(group_key.clone()),
+        ] {
+            assert!(valid.validate().is_ok(), "{valid:?}");
+        }
+        for invalid in [
+            AssetSpecifier::AssetIdStr("zz".repeat(32)),
+            AssetSpecifier::AssetIdStr("a1".repeat(31)),
+            AssetSpecifier::AssetId(asset_id),
+            AssetSpecifier::GroupKeyStr(format!("04{}", "b2".repeat(32))),
+            AssetSpecifier::GroupKey(encoded),
+        ] {
+            let error = invalid.validate().unwrap_err();
+            assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+        }
+
+        let unknown = serde_json::json!({"ticker": "USD"});
+        assert!(serde_json::from_value::<AssetSpecifier>(unknown).is_err());
+    }
+
+    #[test]
+    fn test_active_quotes_filter_expired() {
+        let listing = serde_json::json!({
+            "buy_quotes": [
+                {"id": "b2xk", "peer": "02aa", "expiry": "1000"},
+                {"id": "c29vbg==", "peer": "02aa", "expiry": "1300"}
+            ],
+            "sell_quotes": [{"id": "bGF0ZXI=", "peer": "02bb", "expiry": "1100"}]
+        });
+
+        let active = active_quotes(&listing, 1_050, 0);
+        assert_eq!(active.filtered, 1);
+        let ids: Vec<&str> = active.quotes.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(ids, ["bGF0ZXI=", "c29vbg=="]);
+        assert_eq!(active.quotes[0].side, OfferSide::Sell);
+        assert_eq!(active.quotes[0].expires_in_secs, 50);
+
+        let soon = active_quotes(&listing, 1_050, 100);
+        assert_eq!(soon.quotes.len(), 1);
+        assert_eq!(soon.filtered, 2);
+    }
+
+    #[test]
+    fn test_refreshed_request_keeps_lifetime() {
+        let request = serde_json::json!({"asset_max_amt": "10", "expiry": "1600"});
+        let result = Ok(serde_json::json!({"accepted_quote": {"id": "cQ=="}}));
+        let mut order = RfqOrderRecord::new(
+            RfqOrderKind::BuyOrder,
+            "usd".to_string(),
+            request,
+            result.as_ref(),
+        );
+        order.created_at = 1_000;
+
+        let refreshed = refreshed_request(&order, 5_000);
+        assert_eq!(refreshed["expiry"], "5600");
+        assert_eq!(refreshed["asset_max_amt"], "10");
+
+        order.request["expiry"] = "garbage".into();
+        assert_eq!(refreshed_request(&order, 5_000)["expiry"], "5060");
+    }
+
+    #[test]
+    fn test_cancel_offer_response_shape() {
+        let response = CancelOfferResponse {
+            asset_id: "usd".to_string(),
+            side: OfferSide::Sell,
+            cancelled: true,
+        };
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({"asset_id": "usd", "side": "sell", "cancelled": true})
+        );
+        assert_eq!(OfferSide::Buy.path(), "buyoffer");
+    }
+
+    #[tokio::test]
+    async fn test_order_history_pages() {
+        let store = InMemoryRfqOrderStore::new();
+        let empty = serde_json::json!({});
+        for _ in 0..3 {
+            let kind = RfqOrderKind::BuyOffer;
+            let order = RfqOrderRecord::new(kind, "usd".to_string(), empty.clone(), Ok(&empty));
+            store.record(&order).await.unwrap();
+        }
+
+        let params = RfqOrdersParams {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = order_history(&store, params).await.unwrap();
+        assert_eq!(first.orders.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+
+        let params = RfqOrdersParams {
+            limit: Some(2),
+            offset: first.next_offset,
+            ..Default::default()
+        };
+        let last = order_history(&store, params).await.unwrap();
+        assert_eq!(last.orders.len(), 1);
+        assert_eq!(last.next_offset, None);
+    }
 }
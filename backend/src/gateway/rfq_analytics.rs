@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::error::AppError;
+use crate::storage::rfq_orders::{RfqOrderKind, RfqOrderQuery, RfqOrderRecord, RfqOrderStatus};
+use crate::types::AppState;
+
+const DEFAULT_WINDOW: &str = "24h";
+const MAX_WINDOW_SECS: i64 = 90 * 86_400;
+/// Acceptance is reported over this many equal slices of the window
+const BUCKETS: i64 = 24;
+/// Upper bound on orders aggregated per request
+const MAX_ORDERS: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct RfqAnalyticsParams {
+    pub asset_id: String,
+    /// Lookback such as `30m`, `24h` or `7d`
+    pub window: Option<String>,
+}
+
+/// Accepted quote prices in asset units per BTC
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceSummary {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AcceptanceBucket {
+    /// Unix seconds at which the bucket starts
+    pub start: i64,
+    pub requests: usize,
+    pub accepted: usize,
+    pub acceptance_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeerAnalytics {
+    pub peer: String,
+    pub requests: usize,
+    pub accepted: usize,
+    pub acceptance_ratio: Option<f64>,
+    pub avg_price: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RfqAnalyticsResponse {
+    pub asset_id: String,
+    pub window_secs: i64,
+    pub from: i64,
+    pub to: i64,
+    /// Buy and sell orders; standing offers get no quote and are not counted
+    pub requests: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    pub failed: usize,
+    pub acceptance_ratio: Option<f64>,
+    pub price: Option<PriceSummary>,
+    pub oracle_rate: Option<f64>,
+    /// Average accepted price relative to the oracle rate, in percent
+    pub spread_pct: Option<f64>,
+    pub buckets: Vec<AcceptanceBucket>,
+    pub peers: Vec<PeerAnalytics>,
+}
+
+/// Parses a lookback like `90s`, `30m`, `24h` or `7d` into seconds
+pub fn parse_window(window: &str) -> Result<i64, AppError> {
+    let invalid = || {
+        AppError::InvalidInput(format!(
+            "Invalid window '{window}', expected e.g. 30m, 24h or 7d"
+        ))
+    };
+    let window = window.trim();
+    let split = window.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = window.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return Err(invalid()),
+    };
+    let secs = amount.checked_mul(unit_secs).ok_or_else(invalid)?;
+    if secs <= 0 || secs > MAX_WINDOW_SECS {
+        return Err(AppError::InvalidInput(format!(
+            "window must be between 1s and {}d",
+            MAX_WINDOW_SECS / 86_400
+        )));
+    }
+    Ok(secs)
+}
+
+/// Price of an accepted quote from its fixed-point `ask_asset_rate` (buy
+/// orders) or `bid_asset_rate` (sell orders)
+pub fn accepted_price(order: &RfqOrderRecord) -> Option<f64> {
+    let quote = order.response.as_ref()?.get("accepted_quote")?;
+    let rate = quote.get("ask_asset_rate").or_else(|| quote.get("bid_asset_rate"))?;
+    let number = |value: &Value| match value {
+        Value::String(text) => text.parse::<f64>().ok(),
+        other => other.as_f64(),
+    };
+    let coefficient = number(&rate["coefficient"])?;
+    let scale = number(&rate["scale"]).unwrap_or(0.0);
+    Some(coefficient / 10f64.powf(scale))
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+/// Aggregates the orders of one asset over `[to - window_secs, to]`
+pub fn summarize(
+    asset_id: &str,
+    orders: &[RfqOrderRecord],
+    window_secs: i64,
+    to: i64,
+    oracle_rate: Option<f64>,
+) -> RfqAnalyticsResponse {
+    let from = to - window_secs;
+    let bucket_secs = (window_secs + BUCKETS - 1) / BUCKETS;
+    let mut buckets: Vec<AcceptanceBucket> = (0..BUCKETS)
+        .map(|index| AcceptanceBucket {
+            start: from + index * bucket_secs,
+            requests: 0,
+            accepted: 0,
+            acceptance_ratio: None,
+        })
+        .collect();
+    // (requests, accepted, price sum, priced quotes)
+    let mut peers: BTreeMap<String, (usize, usize, f64, usize)> = BTreeMap::new();
+    let (mut accepted, mut rejected, mut failed) = (0, 0, 0);
+    let mut prices = Vec::new();
+
+    let quoted = orders.iter().filter(|order| {
+        matches!(order.kind, RfqOrderKind::BuyOrder | RfqOrderKind::SellOrder)
+            && order.created_at >= from
+    });
+    let mut requests = 0;
+    for order in quoted {
+        requests += 1;
+        let is_accepted = order.status == RfqOrderStatus::Accepted;
+        match order.status {
+            RfqOrderStatus::Accepted => accepted += 1,
+            RfqOrderStatus::Rejected => rejected += 1,
+            RfqOrderStatus::Failed | RfqOrderStatus::Submitted => failed += 1,
+        }
+
+        let index = ((order.created_at - from) / bucket_secs).clamp(0, BUCKETS - 1);
+        let bucket = &mut buckets[index as usize];
+        bucket.requests += 1;
+        bucket.accepted += usize::from(is_accepted);
+
+        let price = is_accepted.then(|| accepted_price(order)).flatten();
+        if let Some(price) = price {
+            prices.push(price);
+        }
+        if let Some(peer) = &order.peer {
+            let entry = peers.entry(peer.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(is_accepted);
+            if let Some(price) = price {
+                entry.2 += price;
+                entry.3 += 1;
+            }
+        }
+    }
+
+    for bucket in &mut buckets {
+        bucket.acceptance_ratio = ratio(bucket.accepted, bucket.requests);
+    }
+
+    let price = (!prices.is_empty()).then(|| PriceSummary {
+        min: prices.iter().copied().fold(f64::INFINITY, f64::min),
+        max: prices.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        avg: prices.iter().sum::<f64>() / prices.len() as f64,
+    });
+    let spread_pct = match (&price, oracle_rate) {
+        (Some(price), Some(oracle)) if oracle > 0.0 => Some((price.avg - oracle) / oracle * 100.0),
+        _ => None,
+    };
+
+    RfqAnalyticsResponse {
+        asset_id: asset_id.to_string(),
+        window_secs,
+        from,
+        to,
+        requests,
+        accepted,
+        rejected,
+        failed,
+        acceptance_ratio: ratio(accepted, requests),
+        price,
+        oracle_rate,
+        spread_pct,
+        buckets,
+        peers: peers
+            .into_iter()
+            .map(|(peer, (requests, accepted, price_sum, priced))| PeerAnalytics {
+                peer,
+                requests,
+                accepted,
+                acceptance_ratio: ratio(accepted, requests),
+                avg_price: (priced > 0).then(|| price_sum / priced as f64),
+            })
+            .collect(),
+    }
+}
+
+pub async fn rfq_analytics_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RfqAnalyticsParams>,
+) -> Result<Json<RfqAnalyticsResponse>, (StatusCode, Json<Value>)> {
+    let asset_id = params.asset_id.to_ascii_lowercase();
+    if asset_id.len() != 64 || !asset_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error_response(AppError::InvalidInput(
+            "asset_id must be 32 bytes of hex".to_string(),
+        )));
+    }
+    let window_secs = parse_window(params.window.as_deref().unwrap_or(DEFAULT_WINDOW))
+        .map_err(error_response)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let query = RfqOrderQuery {
+        asset_id: Some(asset_id.clone()),
+        since: Some(now - window_secs),
+        limit: MAX_ORDERS,
+        ..Default::default()
+    };
+    let orders = state
+        .rfq_order_store
+        .list(&query)
+        .await
+        .map_err(error_response)?;
+
+    // The spread is optional; analytics stay useful while the oracle is down
+    let oracle_rate = match state.price_oracle.rate(&asset_id).await {
+        Ok(rate) => Some(rate.units_per_btc),
+        Err(e) => {
+            warn!("Price oracle unavailable for RFQ analytics on {}: {}", asset_id, e);
+            None
+        }
+    };
+
+    Ok(Json(summarize(&asset_id, &orders, window_secs, now, oracle_rate)))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(kind: RfqOrderKind, peer: &str, created_at: i64, response: Value) -> RfqOrderRecord {
+        let request = serde_json::json!({"peer_pub_key": peer});
+        let mut order = RfqOrderRecord::new(kind, "usd".to_string(), request, Ok(&response));
+        order.created_at = created_at;
+        order
+    }
+
+    fn accepted(field: &str, coefficient: &str, scale: u32) -> Value {
+        serde_json::json!({"accepted_quote": {
+            "id": "cQ==",
+            field: {"coefficient": coefficient, "scale": scale}
+        }})
+    }
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window("90s").unwrap(), 90);
+        assert_eq!(parse_window("30m").unwrap(), 1_800);
+        assert_eq!(parse_window("24h").unwrap(), 86_400);
+        assert_eq!(parse_window("7d").unwrap(), 604_800);
+        for invalid in ["", "h", "0h", "-1d", "10w", "1000d"] {
+            assert!(parse_window(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_summarize_orders() {
+        let rejected = serde_json::json!({"rejected_quote": {"error_message": "no liquidity"}});
+        let orders = vec![
+            order(RfqOrderKind::BuyOrder, "02aa", 1_450, accepted("ask_asset_rate", "6400000", 2)),
+            order(RfqOrderKind::SellOrder, "02aa", 1_500, accepted("bid_asset_rate", "66000", 0)),
+            order(RfqOrderKind::BuyOrder, "02bb", 3_750, rejected),
+            order(RfqOrderKind::BuyOffer, "02bb", 3_600, serde_json::json!({})),
+            // Outside the window
+            order(RfqOrderKind::BuyOrder, "02bb", 10, accepted("ask_asset_rate", "1", 0)),
+        ];
+
+        let summary = summarize("usd", &orders, 2_400, 3_800, Some(50_000.0));
+        assert_eq!((summary.requests, summary.accepted, summary.rejected), (3, 2, 1));
+        assert_eq!(summary.acceptance_ratio, Some(2.0 / 3.0));
+        let price = summary.price.unwrap();
+        assert_eq!((price.min, price.max, price.avg), (64_000.0, 66_000.0, 65_000.0));
+        assert_eq!(summary.spread_pct, Some(30.0));
+
+        assert_eq!(summary.buckets.len(), BUCKETS as usize);
+        assert_eq!(summary.buckets[0].start, 1_400);
+        assert_eq!(summary.buckets[1].requests, 1);
+        assert_eq!(summary.buckets[23].acceptance_ratio, Some(0.0));
+
+        assert_eq!(summary.peers.len(), 2);
+        assert_eq!(summary.peers[0].peer, "02aa");
+        assert_eq!(summary.peers[0].avg_price, Some(65_000.0));
+        assert_eq!(summary.peers[1].acceptance_ratio, Some(0.0));
+        assert_eq!(summary.peers[1].avg_price, None);
+    }
+}
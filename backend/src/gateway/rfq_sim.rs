@@ -0,0 +1,184 @@
+use std::sync::Mutex;
+
+use base64::Engine;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::price_oracle::fixed_point;
+use super::rfq::{BuyOrderRequest, OfferSide, SellOrderRequest};
+use crate::error::AppError;
+
+/// Stands in for the quoting peer when the request names none
+pub const SIMULATED_PEER: &str =
+    "02000000000000000000000000000000000000000000000000000000000000005e";
+/// Lifetime of a simulated quote whose request carries no usable expiry
+const DEFAULT_QUOTE_LIFETIME_SECS: i64 = 600;
+const MSAT_PER_BTC: f64 = 100_000_000_000.0;
+
+/// Answers RFQ orders locally with quotes at a fixed rate, for building
+/// exchange flows without funded asset channels. Quote IDs are derived from a
+/// counter, so the same sequence of requests yields the same quotes.
+pub struct RfqSimulator {
+    coefficient: String,
+    scale: u32,
+    state: Mutex<SimulatorState>,
+}
+
+#[derive(Default)]
+struct SimulatorState {
+    issued: u64,
+    buy_quotes: Vec<Value>,
+    sell_quotes: Vec<Value>,
+}
+
+impl RfqSimulator {
+    /// `rate` is a decimal number of asset units per BTC
+    pub fn new(rate: &str) -> Result<Self, AppError> {
+        let (coefficient, scale) = fixed_point(rate)
+            .filter(|(coefficient, _)| coefficient != "0")
+            .ok_or_else(|| {
+                AppError::ValidationError(format!(
+                    "RFQ_SIMULATED_RATE must be a positive decimal, got '{rate}'"
+                ))
+            })?;
+        Ok(Self {
+            coefficient,
+            scale,
+            state: Mutex::new(SimulatorState::default()),
+        })
+    }
+
+    fn units_per_btc(&self) -> f64 {
+        self.coefficient.parse::<f64>().unwrap_or(0.0) / 10f64.powi(self.scale as i32)
+    }
+
+    fn rate(&self) -> Value {
+        serde_json::json!({"coefficient": self.coefficient, "scale": self.scale})
+    }
+
+    /// Standing offers are accepted without a quote, as tapd does
+    pub fn offer(&self) -> Value {
+        serde_json::json!({})
+    }
+
+    pub fn buy_order(&self, asset_id: &str, request: &BuyOrderRequest, now: i64) -> Value {
+        let (peer, expiry) = (&request.peer_pub_key, &request.expiry);
+        let mut quote = self.quote(OfferSide::Buy, asset_id, peer, expiry, now);
+        quote["asset_max_amount"] = Value::String(request.asset_max_amt.clone());
+        quote["ask_asset_rate"] = self.rate();
+        self.state.lock().unwrap().buy_quotes.push(quote.clone());
+        serde_json::json!({ "accepted_quote": quote })
+    }
+
+    pub fn sell_order(&self, asset_id: &str, request: &SellOrderRequest, now: i64) -> Value {
+        let (peer, expiry) = (&request.peer_pub_key, &request.expiry);
+        let mut quote = self.quote(OfferSide::Sell, asset_id, peer, expiry, now);
+        // The payment cap is in msat; quote the asset units it buys at the rate
+        let payment_msat = request.payment_max_amt.parse::<f64>().unwrap_or(0.0);
+        let asset_amount = (payment_msat * self.units_per_btc() / MSAT_PER_BTC).floor();
+        quote["asset_amount"] = Value::String((asset_amount as u64).to_string());
+        quote["bid_asset_rate"] = self.rate();
+        self.state.lock().unwrap().sell_quotes.push(quote.clone());
+        serde_json::json!({ "accepted_quote": quote })
+    }
+
+    /// Unexpired simulated quotes in the shape of tapd's peer-accepted listing
+    pub fn peer_quotes(&self, now: i64) -> Value {
+        let mut state = self.state.lock().unwrap();
+        let unexpired = |quote: &Value| {
+            quote["expiry"].as_str().and_then(|e| e.parse::<i64>().ok()).unwrap_or(0) > now
+        };
+        state.buy_quotes.retain(unexpired);
+        state.sell_quotes.retain(unexpired);
+        serde_json::json!({"buy_quotes": state.buy_quotes, "sell_quotes": state.sell_quotes})
+    }
+
+    fn quote(&self, side: OfferSide, asset_id: &str, peer: &str, expiry: &str, now: i64) -> Value {
+        let issued = {
+            let mut state = self.state.lock().unwrap();
+            state.issued += 1;
+            state.issued
+        };
+        let id: [u8; 32] = Sha256::new()
+            .chain_update(format!("{side:?}:{asset_id}:{issued}"))
+            .finalize()
+            .into();
+        // tapd derives the quote's short channel ID from the last 8 bytes of its ID
+        let scid = u64::from_be_bytes(id[24..].try_into().unwrap());
+        let expiry = expiry
+            .parse::<i64>()
+            .ok()
+            .filter(|expiry| *expiry > now)
+            .unwrap_or(now + DEFAULT_QUOTE_LIFETIME_SECS);
+        let peer = if peer.is_empty() { SIMULATED_PEER } else { peer };
+        serde_json::json!({
+            "peer": peer,
+            "id": base64::engine::general_purpose::STANDARD.encode(id),
+            "scid": scid.to_string(),
+            "expiry": expiry.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::rfq::{active_quotes, AssetSpecifier};
+
+    fn buy_request(expiry: &str) -> BuyOrderRequest {
+        BuyOrderRequest {
+            asset_specifier: AssetSpecifier::AssetIdStr("a1".repeat(32)),
+            asset_max_amt: "500".to_string(),
+            expiry: expiry.to_string(),
+            peer_pub_key: String::new(),
+            timeout_seconds: 30,
+            skip_asset_channel_check: true,
+        }
+    }
+
+    #[test]
+    fn test_simulated_quotes_are_deterministic() {
+        let first = RfqSimulator::new("64250.5").unwrap();
+        let second = RfqSimulator::new("64250.5").unwrap();
+        let quote = first.buy_order("usd", &buy_request("2000"), 1_000);
+        assert_eq!(quote, second.buy_order("usd", &buy_request("2000"), 1_000));
+
+        let accepted = &quote["accepted_quote"];
+        assert_eq!(accepted["ask_asset_rate"]["coefficient"], "642505");
+        assert_eq!(accepted["ask_asset_rate"]["scale"], 1);
+        assert_eq!(accepted["peer"], SIMULATED_PEER);
+        assert_eq!(accepted["expiry"], "2000");
+        let next = first.buy_order("usd", &buy_request("0"), 1_000);
+        assert_ne!(next["accepted_quote"]["id"], accepted["id"]);
+        assert_eq!(next["accepted_quote"]["expiry"], "1600");
+    }
+
+    #[test]
+    fn test_simulated_quotes_are_listed_until_expiry() {
+        let simulator = RfqSimulator::new("100000").unwrap();
+        simulator.buy_order("usd", &buy_request("1500"), 1_000);
+        let sell = SellOrderRequest {
+            asset_specifier: AssetSpecifier::AssetIdStr("a1".repeat(32)),
+            payment_max_amt: "200000000".to_string(),
+            expiry: "3000".to_string(),
+            peer_pub_key: "03ab".to_string(),
+            timeout_seconds: 30,
+            skip_asset_channel_check: true,
+        };
+        simulator.sell_order("usd", &sell, 1_000);
+
+        let listing = simulator.peer_quotes(1_000);
+        assert_eq!(active_quotes(&listing, 1_000, 0).quotes.len(), 2);
+        let later = simulator.peer_quotes(2_000);
+        assert_eq!(later["buy_quotes"].as_array().unwrap().len(), 0);
+        assert_eq!(later["sell_quotes"][0]["peer"], "03ab");
+        assert_eq!(later["sell_quotes"][0]["asset_amount"], "200");
+    }
+
+    #[test]
+    fn test_rate_must_be_positive() {
+        for invalid in ["", "0", "0.00", "-5", "abc"] {
+            assert!(RfqSimulator::new(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -1,16 +1,23 @@
 use axum::{
-    routing::{get, post, any},
+    routing::{get, post, any, delete},
     Router,
 };
 use crate::types::AppState;
 
-use super::{health, assets, addresses, info, wallet, burn, channels, events, rfq};
+use super::{health, assets, addresses, info, invoices, fees, lnd, offers, ownership, price_alerts, price_oracle, qr, wallet, mint_status, universe, utxos, transfers, burn, channels, events, rfq, rfq_analytics, mailbox, metrics, admin, notifications};
 
 pub fn create_taproot_routes() -> Router<AppState> {
     Router::new()
         // Health endpoints
         .route("/health", get(health::health))
         .route("/readiness", get(health::readiness))
+        .route("/metrics", get(metrics::metrics_handler))
+        // QR codes for invoices and addresses
+        .route("/qr", get(qr::qr_handler))
+        // Fee rate recommendations for funding and on-chain sends
+        .route("/fees/estimate", get(fees::estimate_fees_handler))
+        // Local BOLT11 decoding, independent of any asset
+        .route("/invoices/decode", post(invoices::decode_bolt11_handler))
         
         // Taproot Assets API endpoints under /v1/taproot-assets
         .nest("/v1/taproot-assets", 
@@ -18,25 +25,94 @@ pub fn create_taproot_routes() -> Router<AppState> {
                 // Core endpoints - these will be implemented as needed
                 .route("/assets/list", get(assets::list_assets))
                 .route("/assets/mint", post(assets::mint_asset))
+                .route(
+                    "/assets/mint/batches/:batch_key/events",
+                    get(mint_status::mint_batch_status_handler),
+                )
+                .route("/assets/:asset_id", get(assets::asset_detail_handler))
+                .route("/assets/:asset_id/meta", get(assets::asset_meta_handler))
+                .route("/assets/:asset_id/stats", get(assets::asset_stats_handler))
+                .route("/assets/:asset_id/image", get(assets::asset_image_handler))
+                .route("/assets/images", post(assets::upload_image_handler))
+                // Proofs that the wallet controls an asset, for OTC counterparties
+                .route(
+                    "/assets/:asset_id/prove-ownership",
+                    post(ownership::prove_ownership_handler),
+                )
+                .route("/assets/ownership/verify", post(ownership::verify_ownership_handler))
                 .route("/addresses/new", post(addresses::new_address))
+                // Universe roots, leaves and proof sync
+                .route("/universe/roots", get(universe::roots_handler))
+                .route("/universe/roots/asset-id/:asset_id", get(universe::asset_root_handler))
+                .route("/universe/roots/group-key/:group_key", get(universe::group_root_handler))
+                .route("/universe/leaves/asset-id/:asset_id", get(universe::asset_leaves_handler))
+                .route(
+                    "/universe/leaves/group-key/:group_key",
+                    get(universe::group_leaves_handler),
+                )
+                .route("/universe/sync", post(universe::sync_handler))
+                .route(
+                    "/universe/federation",
+                    get(universe::list_federation_handler).post(universe::add_federation_handler),
+                )
+                .route(
+                    "/universe/federation/:host",
+                    delete(universe::remove_federation_handler),
+                )
                 .route("/addresses/list", get(addresses::list_addresses))
+                .route("/addresses/decode", post(addresses::decode_address_handler))
+                .route("/addresses/:addr/events", get(addresses::address_events_handler))
                 .route("/info", get(info::get_info))
                 .route("/wallet/balance", get(wallet::get_balance))
+                // Anchor outputs and manual coin locking
+                .route("/wallet/utxos", get(utxos::list_utxos_handler))
+                .route("/wallet/utxos/lease", post(utxos::lease_utxo_handler))
+                .route("/wallet/utxos/release", post(utxos::release_utxo_handler))
+                // Fee bumps for stuck anchor transactions
+                .route(
+                    "/transfers/:txid/bump-fee",
+                    post(transfers::bump_fee_handler).get(transfers::bump_status_handler),
+                )
                 .route("/burn", post(burn::burn))
                 .route("/burns", get(burn::list))
                 // Channel endpoints
                 .nest("/channels", channels::create_channels_routes())
                 // Add more routes as needed...
                 // RFQ endpoints
-                .route("/rfq/buyoffer/asset-id/:asset_id", post(rfq::buy_offer_handler))
+                .route(
+                    "/rfq/buyoffer/asset-id/:asset_id",
+                    post(rfq::buy_offer_handler).delete(rfq::cancel_buy_offer_handler),
+                )
                 .route("/rfq/buyorder/asset-id/:asset_id", post(rfq::buy_order_handler))
-                .route("/rfq/selloffer/asset-id/:asset_id", post(rfq::sell_offer_handler))
+                .route(
+                    "/rfq/selloffer/asset-id/:asset_id",
+                    post(rfq::sell_offer_handler).delete(rfq::cancel_sell_offer_handler),
+                )
                 .route("/rfq/sellorder/asset-id/:asset_id", post(rfq::sell_order_handler))
                 .route("/rfq/ntfs", post(rfq::notifications_handler))
+                .route("/rfq/orders", get(rfq::orders_handler))
+                .route("/rfq/analytics", get(rfq_analytics::rfq_analytics_handler))
+                .route("/rfq/rates/:asset_id", get(price_oracle::asset_rate_handler))
+                .route(
+                    "/rfq/alerts",
+                    post(price_alerts::create_alert_handler).get(price_alerts::list_alerts_handler),
+                )
+                .route("/rfq/alerts/ws", any(price_alerts::alerts_ws_handler))
+                .route("/rfq/alerts/:id", delete(price_alerts::delete_alert_handler))
                 .route("/rfq/priceoracle/assetrates", get(rfq::asset_rates_handler))
                 .route("/rfq/quotes/peeraccepted", get(rfq::peer_quotes_handler))
+                .route("/rfq/quotes/active", get(rfq::active_quotes_handler))
                 .route("/rfq/events", any(rfq::rfq_events_ws_handler))
+                // Mailbox endpoints
+                .merge(mailbox::create_mailbox_router())
         )
+        // Plain BTC wallet endpoints backed by LND
+        .nest("/v1/lnd", lnd::create_lnd_routes())
+        .nest("/v1/lnd/offers", offers::create_offers_routes())
         // Event endpoints (top level)
         .nest("/events", events::create_events_routes())
+        // Mobile push notification registration
+        .merge(notifications::create_notifications_router())
+        // Operator endpoints
+        .merge(admin::create_admin_router())
 }
\ No newline at end of file
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::events::{EventBroker, EventQueryParams};
+use crate::storage::transactions::TransactionRepo;
+use crate::types::{Transaction, TransactionStatus, TransactionType};
+
+const SEND_STATE_COMPLETED: &str = "SEND_STATE_COMPLETED";
+const RECEIVE_STATUS_DETECTED: &str = "ADDR_EVENT_STATUS_TRANSACTION_DETECTED";
+const RECEIVE_CONFIRMED_STATUSES: [&str; 2] = [
+    "ADDR_EVENT_STATUS_TRANSACTION_CONFIRMED",
+    "ADDR_EVENT_STATUS_COMPLETED",
+];
+
+/// A status change derived from an asset event, addressed by destination address
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionUpdate {
+    pub tx_type: TransactionType,
+    pub destination: String,
+    pub status: TransactionStatus,
+    pub asset_id: Option<String>,
+    pub amount: u64,
+}
+
+/// Updates persisted transactions as asset send and receive events arrive
+pub fn spawn_transaction_updater(broker: Arc<EventBroker>, store: Arc<dyn TransactionRepo>) {
+    for event_type in ["asset-send", "asset-receive"] {
+        let store = Arc::clone(&store);
+        let request = EventQueryParams::default().subscription_request(event_type);
+        let mut events = broker.subscribe(event_type, request);
+        let broker = Arc::clone(&broker);
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if event.is_marker() => {}
+                    Ok(event) => {
+                        for update in transaction_updates(event_type, &event.payload) {
+                            if let Err(e) = apply_update(store.as_ref(), update).await {
+                                warn!("Failed to update transaction from {} event: {}", event_type, e);
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        broker.record_lag(event_type, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+/// Applies an update; receives seen for the first time are recorded as new
+/// transactions, reported by returning true
+pub(crate) async fn apply_update(
+    store: &dyn TransactionRepo,
+    update: TransactionUpdate,
+) -> Result<bool, crate::error::AppError> {
+    let matched = store
+        .update_status(update.tx_type, &update.destination, update.status)
+        .await?;
+    if matched || update.tx_type != TransactionType::Receive {
+        return Ok(false);
+    }
+
+    debug!("Recording incoming transfer to {}", update.destination);
+    let now = Utc::now();
+    store
+        .insert(
+            Transaction {
+                id: Uuid::new_v4(),
+                tx_type: update.tx_type,
+                asset_id: update.asset_id,
+                amount: update.amount,
+                status: update.status,
+                created_at: now,
+                updated_at: now,
+                label: None,
+            },
+            Some(update.destination),
+        )
+        .await?;
+    Ok(true)
+}
+
+/// Maps an asset event to the transaction status changes it implies
+pub fn transaction_updates(event_type: &str, event: &Value) -> Vec<TransactionUpdate> {
+    let text = |field: &str| event.get(field).and_then(Value::as_str).unwrap_or_default();
+
+    match event_type {
+        "asset-send" => {
+            let status = if !text("error").is_empty() {
+                TransactionStatus::Failed
+            } else if text("send_state") == SEND_STATE_COMPLETED {
+                TransactionStatus::Confirmed
+            } else {
+                return Vec::new();
+            };
+
+            event
+                .get("addresses")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|addr| addr.get("encoded").and_then(Value::as_str))
+                .map(|encoded| TransactionUpdate {
+                    tx_type: TransactionType::Send,
+                    destination: encoded.to_string(),
+                    status,
+                    asset_id: None,
+                    amount: 0,
+                })
+                .collect()
+        }
+        "asset-receive" => {
+            let status = match text("status") {
+                s if RECEIVE_CONFIRMED_STATUSES.contains(&s) => TransactionStatus::Confirmed,
+                RECEIVE_STATUS_DETECTED => TransactionStatus::Pending,
+                _ => return Vec::new(),
+            };
+            let Some(address) = event.get("address") else {
+                return Vec::new();
+            };
+            let Some(encoded) = address.get("encoded").and_then(Value::as_str) else {
+                return Vec::new();
+            };
+
+            // tapd encodes 64-bit amounts as strings
+            let amount = match address.get("amount") {
+                Some(Value::String(s)) => s.parse().unwrap_or(0),
+                Some(Value::Number(n)) => n.as_u64().unwrap_or(0),
+                _ => 0,
+            };
+
+            vec![TransactionUpdate {
+                tx_type: TransactionType::Receive,
+                destination: encoded.to_string(),
+                status,
+                asset_id: address
+                    .get("asset_id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                amount,
+            }]
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::transactions::InMemoryTransactionRepo;
+    use serde_json::json;
+
+    #[test]
+    fn test_send_updates() {
+        let completed = json!({
+            "send_state": "SEND_STATE_COMPLETED",
+            "addresses": [{"encoded": "taprt1a"}, {"encoded": "taprt1b"}],
+            "error": ""
+        });
+        let updates = transaction_updates("asset-send", &completed);
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| u.status == TransactionStatus::Confirmed));
+
+        let failed = json!({"send_state": "SEND_STATE_BROADCAST", "addresses": [{"encoded": "taprt1a"}], "error": "boom"});
+        assert_eq!(transaction_updates("asset-send", &failed)[0].status, TransactionStatus::Failed);
+
+        let in_progress = json!({"send_state": "SEND_STATE_BROADCAST", "addresses": [{"encoded": "taprt1a"}]});
+        assert!(transaction_updates("asset-send", &in_progress).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_receive_is_recorded_then_confirmed() {
+        let store = InMemoryTransactionRepo::new();
+        let event = |status: &str| {
+            json!({
+                "status": status,
+                "address": {"encoded": "taprt1me", "asset_id": "asset123", "amount": "250"}
+            })
+        };
+
+        for update in transaction_updates("asset-receive", &event(RECEIVE_STATUS_DETECTED)) {
+            apply_update(&store, update).await.unwrap();
+        }
+        for update in transaction_updates("asset-receive", &event("ADDR_EVENT_STATUS_COMPLETED")) {
+            apply_update(&store, update).await.unwrap();
+        }
+
+        let transactions = store.list(10).await.unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].tx_type, TransactionType::Receive);
+        assert_eq!(transactions[0].status, TransactionStatus::Confirmed);
+        assert_eq!(transactions[0].amount, 250);
+    }
+}
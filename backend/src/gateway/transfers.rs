@@ -0,0 +1,316 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, instrument};
+
+use super::lnd::{display_txid, lnd_client};
+use super::upstream::SendUpstream;
+use super::utxos::parse_outpoint;
+use crate::error::AppError;
+use crate::types::AppState;
+
+/// Guards against fee rates entered in the wrong unit
+pub const MAX_BUMP_SAT_PER_VBYTE: u64 = 1_000;
+
+#[derive(Debug, Deserialize)]
+pub struct BumpFeeRequest {
+    /// Fee rate the bumped package should reach
+    pub sat_per_vbyte: u64,
+}
+
+impl BumpFeeRequest {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.sat_per_vbyte == 0 || self.sat_per_vbyte > MAX_BUMP_SAT_PER_VBYTE {
+            return Err(AppError::InvalidInput(format!(
+                "sat_per_vbyte must be between 1 and {MAX_BUMP_SAT_PER_VBYTE}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BumpMethod {
+    /// A new child transaction spends one of the anchor's wallet outputs
+    Cpfp,
+    /// An earlier child is replaced at the higher rate
+    Rbf,
+}
+
+/// A wallet output of the anchor transaction in LND's sweeper
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PendingBump {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub amount_sat: u64,
+    pub requested_sat_per_vbyte: u64,
+    /// Rate of the latest broadcast sweep; 0 before the first broadcast
+    pub current_sat_per_vbyte: u64,
+    pub broadcast_attempts: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BumpFeeResponse {
+    pub txid: String,
+    /// Output the fee bump spends
+    pub outpoint: String,
+    pub sat_per_vbyte: u64,
+    pub method: BumpMethod,
+    /// LND's description of the request
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BumpStatusResponse {
+    pub txid: String,
+    /// Empty once the anchor confirms or if it was never bumped
+    pub pending: Vec<PendingBump>,
+}
+
+fn number(value: &Value) -> u64 {
+    match value {
+        Value::String(text) => text.parse().unwrap_or(0),
+        other => other.as_u64().unwrap_or(0),
+    }
+}
+
+fn outpoint_string(outpoint: &Value) -> Option<String> {
+    let txid = outpoint["txid_str"].as_str()?;
+    Some(format!("{}:{}", txid.to_ascii_lowercase(), number(&outpoint["output_index"])))
+}
+
+/// Whether tapd's `ListTransfers` response has a transfer anchored in `txid`
+pub fn has_transfer(transfers: &Value, txid: &str) -> bool {
+    transfers["transfers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|transfer| transfer["anchor_tx_hash"].as_str().and_then(display_txid))
+        .any(|anchor| anchor == txid)
+}
+
+/// Picks the largest unconfirmed wallet output of `txid` from LND's
+/// `ListUnspent`, so the child has the most value to pay fees from
+pub fn bump_candidate(unspent: &Value, txid: &str) -> Option<String> {
+    unspent["utxos"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|utxo| number(&utxo["confirmations"]) == 0)
+        .filter_map(|utxo| Some((outpoint_string(&utxo["outpoint"])?, number(&utxo["amount_sat"]))))
+        .filter(|(outpoint, _)| outpoint.starts_with(&format!("{txid}:")))
+        .max_by_key(|(_, amount)| *amount)
+        .map(|(outpoint, _)| outpoint)
+}
+
+/// Reads LND's `PendingSweeps` for the outputs of `txid`
+pub fn pending_bumps(sweeps: &Value, txid: &str) -> Vec<PendingBump> {
+    sweeps["pending_sweeps"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|sweep| {
+            let outpoint = outpoint_string(&sweep["outpoint"])?;
+            outpoint.starts_with(&format!("{txid}:")).then(|| PendingBump {
+                outpoint,
+                amount_sat: number(&sweep["amount_sat"]),
+                requested_sat_per_vbyte: number(&sweep["requested_sat_per_vbyte"]),
+                current_sat_per_vbyte: number(&sweep["sat_per_vbyte"]),
+                broadcast_attempts: number(&sweep["broadcast_attempts"]) as u32,
+            })
+        })
+        .collect()
+}
+
+fn validate_txid(txid: &str) -> Result<String, AppError> {
+    if txid.len() != 64 || !txid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput("txid must be 32 bytes of hex".to_string()));
+    }
+    Ok(txid.to_ascii_lowercase())
+}
+
+async fn find_transfer(state: &AppState, txid: &str) -> Result<(), AppError> {
+    let transfers = match state.tapd_client.grpc_client() {
+        Some(grpc) => grpc.list_transfers(Some(txid)).await?,
+        None => rest_transfers(state, txid).await?,
+    };
+    if !has_transfer(&transfers, txid) {
+        return Err(AppError::NotFound(format!("No asset transfer is anchored in {txid}")));
+    }
+    Ok(())
+}
+
+async fn rest_transfers(state: &AppState, txid: &str) -> Result<Value, AppError> {
+    let url = format!(
+        "{}/v1/taproot-assets/assets/transfers?anchor_txid={txid}",
+        state.base_url.0
+    );
+    let response = state
+        .http_client
+        .get(&url)
+        .header("Grpc-Metadata-macaroon", &state.macaroon_hex.0)
+        .send_upstream()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+/// Accelerates a stuck anchor transaction through LND's sweeper: the first
+/// bump spends one of its wallet outputs in a child transaction, and later
+/// bumps replace that child at the higher rate
+#[instrument(skip(state))]
+pub async fn bump_fee_handler(
+    State(state): State<AppState>,
+    Path(txid): Path<String>,
+    Json(request): Json<BumpFeeRequest>,
+) -> Result<Json<BumpFeeResponse>, (StatusCode, Json<Value>)> {
+    let txid = validate_txid(&txid).map_err(error_response)?;
+    request.validate().map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    find_transfer(&state, &txid).await.map_err(error_response)?;
+
+    let sweeps: Value = lnd.get("/v2/wallet/sweeps/pending").await.map_err(error_response)?;
+    let pending = pending_bumps(&sweeps, &txid);
+    let (outpoint, method) = match pending.first() {
+        Some(bump) if request.sat_per_vbyte <= bump.requested_sat_per_vbyte => {
+            return Err(error_response(AppError::InvalidInput(format!(
+                "sat_per_vbyte must exceed the pending bump of {} sat/vB",
+                bump.requested_sat_per_vbyte
+            ))));
+        }
+        Some(bump) => (bump.outpoint.clone(), BumpMethod::Rbf),
+        None => {
+            let body = serde_json::json!({"min_confs": 0, "max_confs": 0});
+            let unspent: Value = lnd.post("/v2/wallet/utxos", &body).await.map_err(error_response)?;
+            let outpoint = bump_candidate(&unspent, &txid).ok_or_else(|| {
+                error_response(AppError::Conflict(format!(
+                    "{txid} has no unconfirmed wallet output to bump; it may already be confirmed"
+                )))
+            })?;
+            (outpoint, BumpMethod::Cpfp)
+        }
+    };
+
+    info!("Bumping {} to {} sat/vB via {:?}", outpoint, request.sat_per_vbyte, method);
+    let (outpoint_txid, output_index) = parse_outpoint(&outpoint).map_err(error_response)?;
+    let body = serde_json::json!({
+        "outpoint": {"txid_str": outpoint_txid, "output_index": output_index},
+        "sat_per_vbyte": request.sat_per_vbyte.to_string(),
+        "immediate": true,
+    });
+    let bumped: Value = lnd.post("/v2/wallet/bumpfee", &body).await.map_err(error_response)?;
+
+    Ok(Json(BumpFeeResponse {
+        txid,
+        outpoint,
+        sat_per_vbyte: request.sat_per_vbyte,
+        method,
+        status: bumped["status"].as_str().unwrap_or_default().to_string(),
+    }))
+}
+
+/// Reports fee bumps of an anchor transaction that are still in the sweeper
+pub async fn bump_status_handler(
+    State(state): State<AppState>,
+    Path(txid): Path<String>,
+) -> Result<Json<BumpStatusResponse>, (StatusCode, Json<Value>)> {
+    let txid = validate_txid(&txid).map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    let sweeps: Value = lnd.get("/v2/wallet/sweeps/pending").await.map_err(error_response)?;
+    Ok(Json(BumpStatusResponse {
+        pending: pending_bumps(&sweeps, &txid),
+        txid,
+    }))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    #[test]
+    fn test_bump_candidate_prefers_largest_unconfirmed_output() {
+        let txid = "ab".repeat(32);
+        let utxo = |txid: &str, vout: u32, amount: &str, confirmations: &str| {
+            serde_json::json!({
+                "outpoint": {"txid_str": txid, "output_index": vout},
+                "amount_sat": amount,
+                "confirmations": confirmations,
+            })
+        };
+        let unspent = serde_json::json!({"utxos": [
+            utxo(&txid, 0, "1000", "0"),
+            utxo(&txid, 2, "50000", "0"),
+            utxo(&"cd".repeat(32), 0, "90000", "0"),
+            utxo(&txid, 3, "99999", "1"),
+        ]});
+        assert_eq!(bump_candidate(&unspent, &txid), Some(format!("{txid}:2")));
+        assert_eq!(bump_candidate(&unspent, &"ef".repeat(32)), None);
+    }
+
+    #[test]
+    fn test_pending_bumps() {
+        let txid = "ab".repeat(32);
+        let sweeps = serde_json::json!({"pending_sweeps": [
+            {
+                "outpoint": {"txid_str": txid, "output_index": 1},
+                "amount_sat": 40000,
+                "sat_per_vbyte": "12",
+                "requested_sat_per_vbyte": "20",
+                "broadcast_attempts": 2,
+            },
+            {"outpoint": {"txid_str": "cd".repeat(32), "output_index": 0}},
+        ]});
+        assert_eq!(
+            pending_bumps(&sweeps, &txid),
+            vec![PendingBump {
+                outpoint: format!("{txid}:1"),
+                amount_sat: 40000,
+                requested_sat_per_vbyte: 20,
+                current_sat_per_vbyte: 12,
+                broadcast_attempts: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_has_transfer_matches_display_txid() {
+        let internal = [0x01u8; 31].into_iter().chain([0x02]).collect::<Vec<u8>>();
+        let mut display = internal.clone();
+        display.reverse();
+        let transfers = serde_json::json!({"transfers": [{
+            "anchor_tx_hash": base64::engine::general_purpose::STANDARD.encode(&internal)
+        }]});
+        assert!(has_transfer(&transfers, &hex::encode(display)));
+        assert!(!has_transfer(&transfers, &hex::encode(internal)));
+    }
+
+    #[test]
+    fn test_bump_fee_request_validation() {
+        assert!(BumpFeeRequest { sat_per_vbyte: 25 }.validate().is_ok());
+        assert!(BumpFeeRequest { sat_per_vbyte: 0 }.validate().is_err());
+        let too_high = BumpFeeRequest {
+            sat_per_vbyte: MAX_BUMP_SAT_PER_VBYTE + 1,
+        };
+        assert!(too_high.validate().is_err());
+        assert!(validate_txid("abc").is_err());
+    }
+}
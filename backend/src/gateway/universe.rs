@@ -1,10 +1,581 @@
-use axum::{response::Json, http::StatusCode, extract::State};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use base64::Engine;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::{info, warn};
+
+use super::cache::CacheKind;
+use super::upstream::SendUpstream;
+use crate::error::AppError;
 use crate::types::AppState;
 
-// Placeholder functions - implement as needed
-pub async fn placeholder(
-    State(_state): State<AppState>
-) -> Result<Json<Value>, StatusCode> {
-    Ok(Json(serde_json::json!({"message": "Not implemented yet"})))
+/// tapd's default universe RPC port
+pub const DEFAULT_UNIVERSE_PORT: u16 = 10029;
+
+/// Which proofs of an asset a universe tree holds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofType {
+    #[default]
+    Issuance,
+    Transfer,
+}
+
+impl ProofType {
+    fn upstream(&self) -> &'static str {
+        match self {
+            ProofType::Issuance => "PROOF_TYPE_ISSUANCE",
+            ProofType::Transfer => "PROOF_TYPE_TRANSFER",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Only issuance proofs, as tapd syncs by default
+    #[default]
+    IssuanceOnly,
+    /// Issuance and transfer proofs
+    Full,
+}
+
+impl SyncMode {
+    fn upstream(&self) -> &'static str {
+        match self {
+            SyncMode::IssuanceOnly => "SYNC_ISSUANCE_ONLY",
+            SyncMode::Full => "SYNC_FULL",
+        }
+    }
+}
+
+/// Checks a universe server address of the form `host[:port]`, where host is
+/// a DNS name or IP address. Returns it with tapd's default port filled in.
+pub fn validate_universe_host(host: &str) -> Result<String, AppError> {
+    let invalid =
+        |reason: &str| AppError::InvalidInput(format!("Invalid universe host '{host}': {reason}"));
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(invalid("empty"));
+    }
+    if host.contains("://") || host.contains('/') {
+        return Err(invalid("expected host[:port] without a scheme or path"));
+    }
+
+    let parsed = url::Url::parse(&format!("tcp://{host}")).map_err(|e| invalid(&e.to_string()))?;
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(invalid("credentials are not allowed"));
+    }
+    let name = match parsed.host() {
+        Some(url::Host::Domain(domain)) => {
+            let labels_ok = domain.split('.').all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+            if !labels_ok || domain.len() > 253 {
+                return Err(invalid("not a valid hostname"));
+            }
+            domain.to_string()
+        }
+        Some(url::Host::Ipv4(ip)) => ip.to_string(),
+        Some(url::Host::Ipv6(ip)) => format!("[{ip}]"),
+        None => return Err(invalid("missing host")),
+    };
+    // `Url` drops a port that is explicitly 0
+    if host.ends_with(":0") {
+        return Err(invalid("port must be between 1 and 65535"));
+    }
+    Ok(format!("{name}:{}", parsed.port().unwrap_or(DEFAULT_UNIVERSE_PORT)))
+}
+
+fn validate_hex_id(value: &str, bytes: usize, name: &str) -> Result<String, AppError> {
+    let value = value.to_ascii_lowercase();
+    if value.len() != bytes * 2 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput(format!(
+            "{name} must be {bytes} bytes of hex"
+        )));
+    }
+    Ok(value)
+}
+
+/// Identifies one universe tree, by asset ID or group key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UniverseId {
+    /// Hex
+    AssetId(String),
+    /// Hex
+    GroupKey(String),
+}
+
+impl UniverseId {
+    fn validated(&self) -> Result<Self, AppError> {
+        match self {
+            UniverseId::AssetId(id) => validate_hex_id(id, 32, "asset_id").map(UniverseId::AssetId),
+            UniverseId::GroupKey(key) => {
+                validate_hex_id(key, 33, "group_key").map(UniverseId::GroupKey)
+            }
+        }
+    }
+
+    /// Path segment for tapd's `{id.asset_id_str}` or `{id.group_key_str}` routes
+    fn path(&self) -> String {
+        match self {
+            UniverseId::AssetId(id) => format!("asset-id/{id}"),
+            UniverseId::GroupKey(key) => format!("group-key/{key}"),
+        }
+    }
+
+    fn upstream(&self, proof_type: ProofType) -> Value {
+        match self {
+            UniverseId::AssetId(id) => {
+                serde_json::json!({"asset_id_str": id, "proof_type": proof_type.upstream()})
+            }
+            UniverseId::GroupKey(key) => {
+                serde_json::json!({"group_key_str": key, "proof_type": proof_type.upstream()})
+            }
+        }
+    }
+
+    /// Reads tapd's `ID` message, whose byte fields are base64
+    fn from_upstream(id: &Value) -> Option<Self> {
+        let bytes_hex = |field: &str| {
+            let value = id[field].as_str()?;
+            base64::engine::general_purpose::STANDARD
+                .decode(value)
+                .ok()
+                .map(hex::encode)
+        };
+        let text = |field: &str| id[field].as_str().filter(|value| !value.is_empty());
+        let asset_id = bytes_hex("asset_id").or_else(|| text("asset_id_str").map(str::to_string));
+        if let Some(asset_id) = asset_id {
+            return Some(UniverseId::AssetId(asset_id));
+        }
+        bytes_hex("group_key")
+            .or_else(|| text("group_key_str").map(str::to_string))
+            .map(UniverseId::GroupKey)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UniverseRootsParams {
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UniverseLeavesParams {
+    #[serde(default)]
+    pub proof_type: ProofType,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UniverseSyncRequest {
+    pub universe_host: String,
+    #[serde(default)]
+    pub sync_mode: SyncMode,
+    /// Trees to sync; every tree the server knows when empty
+    #[serde(default)]
+    pub targets: Vec<UniverseId>,
+    #[serde(default)]
+    pub proof_type: ProofType,
+}
+
+/// One tree that changed during a sync
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncedUniverse {
+    pub id: Option<UniverseId>,
+    pub old_root_sum: Option<u64>,
+    pub new_root_sum: Option<u64>,
+    pub new_leaves: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UniverseSyncResponse {
+    pub universe_host: String,
+    pub synced_universes: Vec<SyncedUniverse>,
+}
+
+/// Summarizes tapd's `SyncResponse`
+pub fn summarize_sync(universe_host: String, response: &Value) -> UniverseSyncResponse {
+    let root_sum = |root: &Value| match &root["mssmt_root"]["root_sum"] {
+        Value::String(sum) => sum.parse().ok(),
+        sum => sum.as_u64(),
+    };
+    let synced_universes = response["synced_universes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|synced| {
+            let (old, new) = (&synced["old_asset_root"], &synced["new_asset_root"]);
+            SyncedUniverse {
+                id: UniverseId::from_upstream(&new["id"])
+                    .or_else(|| UniverseId::from_upstream(&old["id"])),
+                old_root_sum: root_sum(old),
+                new_root_sum: root_sum(new),
+                new_leaves: synced["new_asset_leaves"].as_array().map_or(0, Vec::len),
+            }
+        })
+        .collect();
+    UniverseSyncResponse {
+        universe_host,
+        synced_universes,
+    }
+}
+
+pub(crate) async fn universe_request(
+    state: &AppState,
+    method: Method,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<Value, AppError> {
+    let url = format!("{}/v1/taproot-assets/universe/{path}", state.base_url.0);
+    let mut request = state
+        .http_client
+        .request(method, &url)
+        .header("Grpc-Metadata-macaroon", &state.macaroon_hex.0);
+    if let Some(body) = body {
+        request = request.json(body);
+    }
+    let response = request.send_upstream().await?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(AppError::NotFound(format!("Universe {path} not found")));
+    }
+    if !status.is_success() {
+        return Err(AppError::from_response(response).await);
+    }
+
+    Ok(response.json::<Value>().await?)
+}
+
+/// Lists the roots of every universe tree the node knows
+pub async fn roots_handler(
+    State(state): State<AppState>,
+    Query(params): Query<UniverseRootsParams>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let query: Vec<String> = [("offset", params.offset), ("limit", params.limit)]
+        .into_iter()
+        .filter_map(|(key, value)| Some(format!("{key}={}", value?)))
+        .collect();
+    let path = format!("roots?{}", query.join("&"));
+    let roots = state
+        .response_cache
+        .get_or_fetch(CacheKind::UniverseRoots, &path, || {
+            universe_request(&state, Method::GET, &path, None)
+        })
+        .await
+        .map_err(error_response)?;
+    Ok(Json(roots))
+}
+
+async fn tree_request(
+    state: &AppState,
+    id: UniverseId,
+    tree: &str,
+    proof_type: Option<ProofType>,
+) -> Result<Value, AppError> {
+    let id = id.validated()?;
+    let mut path = format!("{tree}/{}", id.path());
+    if let Some(proof_type) = proof_type {
+        path.push_str(&format!("?proof_type={}", proof_type.upstream()));
+    }
+    universe_request(state, Method::GET, &path, None).await
+}
+
+pub async fn asset_root_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let root = tree_request(&state, UniverseId::AssetId(asset_id), "roots", None)
+        .await
+        .map_err(error_response)?;
+    Ok(Json(root))
+}
+
+pub async fn group_root_handler(
+    State(state): State<AppState>,
+    Path(group_key): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let root = tree_request(&state, UniverseId::GroupKey(group_key), "roots", None)
+        .await
+        .map_err(error_response)?;
+    Ok(Json(root))
+}
+
+/// Lists the leaves, each an asset with its proof, of one asset's tree
+pub async fn asset_leaves_handler(
+    State(state): State<AppState>,
+    Path(asset_id): Path<String>,
+    Query(params): Query<UniverseLeavesParams>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let id = UniverseId::AssetId(asset_id);
+    let leaves = tree_request(&state, id, "leaves", Some(params.proof_type))
+        .await
+        .map_err(error_response)?;
+    Ok(Json(leaves))
+}
+
+pub async fn group_leaves_handler(
+    State(state): State<AppState>,
+    Path(group_key): Path<String>,
+    Query(params): Query<UniverseLeavesParams>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let id = UniverseId::GroupKey(group_key);
+    let leaves = tree_request(&state, id, "leaves", Some(params.proof_type))
+        .await
+        .map_err(error_response)?;
+    Ok(Json(leaves))
+}
+
+/// Pulls proofs from a universe server into the node's own universe
+pub async fn sync_handler(
+    State(state): State<AppState>,
+    Json(request): Json<UniverseSyncRequest>,
+) -> Result<Json<UniverseSyncResponse>, (StatusCode, Json<Value>)> {
+    let universe_host = validate_universe_host(&request.universe_host).map_err(error_response)?;
+    let targets = request
+        .targets
+        .iter()
+        .map(|target| {
+            let id = target.validated()?;
+            Ok(serde_json::json!({ "id": id.upstream(request.proof_type) }))
+        })
+        .collect::<Result<Vec<_>, AppError>>()
+        .map_err(error_response)?;
+
+    info!(
+        "Syncing {} universe tree(s) from {}",
+        if targets.is_empty() { "all".to_string() } else { targets.len().to_string() },
+        universe_host
+    );
+    let body = serde_json::json!({
+        "universe_host": universe_host,
+        "sync_mode": request.sync_mode.upstream(),
+        "sync_targets": targets,
+    });
+    let response = universe_request(&state, Method::POST, "sync", Some(&body))
+        .await
+        .map_err(|e| {
+            warn!("Universe sync from {} failed: {}", universe_host, e);
+            error_response(e)
+        })?;
+
+    Ok(Json(summarize_sync(universe_host, &response)))
+}
+
+/// A universe server the node syncs from and pushes proofs to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FederationServer {
+    /// `host:port`
+    pub host: String,
+    pub id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FederationListResponse {
+    pub servers: Vec<FederationServer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddFederationServerRequest {
+    pub host: String,
+}
+
+/// Reads tapd's `ListFederationServersResponse`
+pub fn parse_federation_servers(response: &Value) -> Vec<FederationServer> {
+    response["servers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|server| {
+            Some(FederationServer {
+                host: server["host"].as_str()?.to_string(),
+                id: match &server["id"] {
+                    Value::String(id) => id.parse().ok(),
+                    id => id.as_i64(),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The listed server with the same address as `host`, which has already been
+/// validated; tapd stores hosts as they were added, with or without a port
+fn find_server<'a>(servers: &'a [FederationServer], host: &str) -> Option<&'a FederationServer> {
+    servers.iter().find(|server| {
+        validate_universe_host(&server.host).is_ok_and(|listed| listed.eq_ignore_ascii_case(host))
+    })
+}
+
+async fn federation_servers(state: &AppState) -> Result<Vec<FederationServer>, AppError> {
+    let response = universe_request(state, Method::GET, "federation", None).await?;
+    Ok(parse_federation_servers(&response))
+}
+
+pub async fn list_federation_handler(
+    State(state): State<AppState>,
+) -> Result<Json<FederationListResponse>, (StatusCode, Json<Value>)> {
+    let servers = federation_servers(&state).await.map_err(error_response)?;
+    Ok(Json(FederationListResponse { servers }))
+}
+
+pub async fn add_federation_handler(
+    State(state): State<AppState>,
+    Json(request): Json<AddFederationServerRequest>,
+) -> Result<(StatusCode, Json<FederationServer>), (StatusCode, Json<Value>)> {
+    let host = validate_universe_host(&request.host).map_err(error_response)?;
+    let servers = federation_servers(&state).await.map_err(error_response)?;
+    if find_server(&servers, &host).is_some() {
+        return Err(error_response(AppError::Conflict(format!(
+            "{host} is already a federation server"
+        ))));
+    }
+
+    let body = serde_json::json!({ "servers": [{ "host": host }] });
+    universe_request(&state, Method::POST, "federation", Some(&body))
+        .await
+        .map_err(error_response)?;
+    info!("Added universe federation server {}", host);
+
+    // tapd assigns the ID; report it when the listing already shows it
+    let added = federation_servers(&state)
+        .await
+        .ok()
+        .and_then(|servers| find_server(&servers, &host).cloned())
+        .unwrap_or(FederationServer { host, id: None });
+    Ok((StatusCode::CREATED, Json(added)))
+}
+
+pub async fn remove_federation_handler(
+    State(state): State<AppState>,
+    Path(host): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let host = validate_universe_host(&host).map_err(error_response)?;
+    let servers = federation_servers(&state).await.map_err(error_response)?;
+    let Some(server) = find_server(&servers, &host) else {
+        return Err(error_response(AppError::NotFound(format!(
+            "{host} is not a federation server"
+        ))));
+    };
+
+    // Delete by the host exactly as tapd stored it
+    let path = format!(
+        "federation?servers.host={}",
+        urlencoding::encode(&server.host)
+    );
+    universe_request(&state, Method::DELETE, &path, None)
+        .await
+        .map_err(error_response)?;
+    info!("Removed universe federation server {}", server.host);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_universe_host() {
+        let valid = [
+            ("universe.lightning.finance", "universe.lightning.finance:10029"),
+            ("testnet.universe.example.com:10029", "testnet.universe.example.com:10029"),
+            ("10.0.0.5:8443", "10.0.0.5:8443"),
+            ("[::1]:10029", "[::1]:10029"),
+        ];
+        for (host, expected) in valid {
+            assert_eq!(validate_universe_host(host).unwrap(), expected);
+        }
+        let invalid = [
+            "",
+            "https://universe.lightning.finance",
+            "host/path",
+            "user:pass@host",
+            "bad_host",
+            "-host.com",
+            "host:0",
+            "host:99999",
+        ];
+        for host in invalid {
+            assert!(validate_universe_host(host).is_err(), "{host}");
+        }
+    }
+
+    #[test]
+    fn test_sync_request_targets() {
+        let request: UniverseSyncRequest = serde_json::from_value(serde_json::json!({
+            "universe_host": "universe.example.com",
+            "sync_mode": "full",
+            "targets": [{"asset_id": "AB".repeat(32)}, {"group_key": "02"}],
+        }))
+        .unwrap();
+        assert_eq!(request.sync_mode, SyncMode::Full);
+        assert_eq!(request.proof_type, ProofType::Issuance);
+
+        let asset = request.targets[0].validated().unwrap();
+        let upstream = asset.upstream(ProofType::Transfer);
+        assert_eq!(upstream["asset_id_str"], "ab".repeat(32));
+        assert_eq!(upstream["proof_type"], "PROOF_TYPE_TRANSFER");
+        assert_eq!(asset.path(), format!("asset-id/{}", "ab".repeat(32)));
+        assert!(request.targets[1].validated().is_err());
+    }
+
+    #[test]
+    fn test_federation_servers() {
+        let response = serde_json::json!({"servers": [
+            {"host": "universe.example.com", "id": 1},
+            {"host": "10.0.0.5:8443", "id": "2"},
+            {"id": 3}
+        ]});
+        let servers = parse_federation_servers(&response);
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[1].id, Some(2));
+
+        let default_port = find_server(&servers, "universe.example.com:10029").unwrap();
+        assert_eq!(default_port.host, "universe.example.com");
+        assert!(find_server(&servers, "10.0.0.5:8443").is_some());
+        assert!(find_server(&servers, "10.0.0.5:10029").is_none());
+    }
+
+    #[test]
+    fn test_summarize_sync() {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let id = serde_json::json!({"asset_id": base64.encode([0xab; 32])});
+        let response = serde_json::json!({"synced_universes": [{
+            "old_asset_root": {"id": id, "mssmt_root": {"root_sum": "100"}},
+            "new_asset_root": {"id": id, "mssmt_root": {"root_sum": "150"}},
+            "new_asset_leaves": [{}, {}]
+        }]});
+        let summary = summarize_sync("host:10029".to_string(), &response);
+        assert_eq!(
+            summary.synced_universes,
+            vec![SyncedUniverse {
+                id: Some(UniverseId::AssetId("ab".repeat(32))),
+                old_root_sum: Some(100),
+                new_root_sum: Some(150),
+                new_leaves: 2,
+            }]
+        );
+        assert!(summarize_sync(String::new(), &serde_json::json!({}))
+            .synced_universes
+            .is_empty());
+    }
 }
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use reqwest::{Certificate, ClientBuilder, Proxy, RequestBuilder, Response};
+use tracing::{info, warn};
+
+use crate::config::{HttpClientSettings, UpstreamSettings};
+use crate::error::AppError;
+
+static POLICY: OnceLock<UpstreamPolicy> = OnceLock::new();
+
+/// Installs the retry and circuit breaker settings; calls made before this
+/// use the defaults
+pub fn configure(settings: UpstreamSettings) {
+    if POLICY.set(UpstreamPolicy::new(settings)).is_err() {
+        warn!("Upstream policy is already configured");
+    }
+}
+
+fn policy() -> &'static UpstreamPolicy {
+    POLICY.get_or_init(|| UpstreamPolicy::new(UpstreamSettings::default()))
+}
+
+/// A client builder for `service` applying the transport settings:
+/// certificate checks, a trusted CA, the connect timeout and a SOCKS5 proxy.
+/// Only connects are bounded, since clients are shared with streaming calls.
+pub fn client_builder(
+    settings: &HttpClientSettings,
+    service: &str,
+) -> Result<ClientBuilder, AppError> {
+    let mut builder = reqwest::Client::builder().connect_timeout(settings.connect_timeout());
+    if !settings.tls_verify {
+        warn!("TLS verification is disabled for {}", service);
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some(path) = &settings.ca_cert_path {
+        let pem = std::fs::read(path).map_err(|e| {
+            AppError::ValidationError(format!("Failed to read CA certificate at {path}: {e}"))
+        })?;
+        let cert = Certificate::from_pem(&pem).map_err(|e| {
+            AppError::ValidationError(format!("Invalid CA certificate at {path}: {e}"))
+        })?;
+        builder = builder.add_root_certificate(cert);
+    }
+    if let Some(proxy) = &settings.socks5_proxy {
+        info!("Reaching {} through SOCKS5 proxy {}", service, proxy);
+        let proxy = Proxy::all(proxy)
+            .map_err(|e| AppError::ValidationError(format!("Invalid SOCKS5 proxy: {e}")))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// The status a handler should answer with when a `TapdClient` call fails
+pub fn error_status(error: &anyhow::Error) -> StatusCode {
+    error
+        .downcast_ref::<AppError>()
+        .map_or(StatusCode::INTERNAL_SERVER_ERROR, AppError::status_code)
+}
+
+/// Sends a request under the process-wide retry policy and the circuit
+/// breaker of its host
+#[async_trait]
+pub trait SendUpstream {
+    async fn send_upstream(self) -> Result<Response, AppError>;
+}
+
+#[async_trait]
+impl SendUpstream for RequestBuilder {
+    async fn send_upstream(self) -> Result<Response, AppError> {
+        policy().send(self).await
+    }
+}
+
+/// tapd's and LND's REST proxies report most application errors as 500, so
+/// only gateway and availability failures are worth another try
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Exponential backoff with full jitter: a random delay up to the doubled
+/// base, so callers that failed together do not retry together
+pub fn backoff_delay(settings: &UpstreamSettings, attempt: u32, random: u64) -> Duration {
+    let ceiling = settings
+        .initial_backoff_ms
+        .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
+        .min(settings.max_backoff_ms);
+    Duration::from_millis(random % (ceiling + 1))
+}
+
+/// Consecutive-failure circuit breaker for one upstream. Once open it fails
+/// fast until the cooldown ends, then lets a single trial call through;
+/// other calls keep failing fast until that trial succeeds.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Errors with the time left while the circuit is open
+    pub fn admit(&mut self, now: Instant, open_for: Duration) -> Result<(), Duration> {
+        match self.open_until {
+            Some(until) if now < until => Err(until - now),
+            Some(_) => {
+                // Half-open: hold others back while the trial runs
+                self.open_until = Some(now + open_for);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns whether this result opened the circuit
+    pub fn record(
+        &mut self,
+        success: bool,
+        now: Instant,
+        threshold: u32,
+        open_for: Duration,
+    ) -> bool {
+        if success {
+            self.failures = 0;
+            self.open_until = None;
+            return false;
+        }
+        self.failures = self.failures.saturating_add(1);
+        let was_open = self.open_until.is_some();
+        if was_open || self.failures >= threshold {
+            self.open_until = Some(now + open_for);
+        }
+        !was_open && self.open_until.is_some()
+    }
+}
+
+pub struct UpstreamPolicy {
+    settings: UpstreamSettings,
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+}
+
+impl UpstreamPolicy {
+    pub fn new(settings: UpstreamSettings) -> Self {
+        Self {
+            settings,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn open_for(&self) -> Duration {
+        Duration::from_secs(self.settings.breaker_open_secs)
+    }
+
+    /// Connection failures are retried for every method, since the request
+    /// never arrived. Timeouts and 502/503/504 are only retried for
+    /// idempotent methods, so a send or mint is never submitted twice.
+    pub async fn send(&self, builder: RequestBuilder) -> Result<Response, AppError> {
+        let (client, request) = builder.build_split();
+        let mut request = request?;
+        let url = request.url();
+        let upstream = format!(
+            "{}:{}",
+            url.host_str().unwrap_or_default(),
+            url.port_or_known_default().unwrap_or_default()
+        );
+        let idempotent = request.method().is_idempotent();
+
+        self.breaker(&upstream, |breaker| breaker.admit(Instant::now(), self.open_for()))
+            .map_err(|remaining| {
+                AppError::UpstreamUnavailable(format!(
+                    "{upstream} is failing; retry in {}s",
+                    remaining.as_secs().max(1)
+                ))
+            })?;
+
+        let mut attempt = 1;
+        loop {
+            // Bodies that cannot be cloned, such as streams, are sent once
+            let retry = (attempt < self.settings.max_attempts)
+                .then(|| request.try_clone())
+                .flatten();
+            let result = client.execute(request).await;
+            let transient = match &result {
+                Ok(response) => is_transient_status(response.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            let retryable = match &result {
+                Err(e) if e.is_connect() => true,
+                _ => transient && idempotent,
+            };
+
+            match retry {
+                Some(next) if retryable => {
+                    let delay = backoff_delay(&self.settings, attempt, secp256k1::rand::random());
+                    warn!(
+                        "Upstream {} attempt {} failed transiently; retrying in {:?}",
+                        upstream, attempt, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    request = next;
+                    attempt += 1;
+                }
+                _ => {
+                    let opened = self.breaker(&upstream, |breaker| {
+                        breaker.record(
+                            !transient,
+                            Instant::now(),
+                            self.settings.breaker_failure_threshold,
+                            self.open_for(),
+                        )
+                    });
+                    if opened {
+                        warn!("Opened circuit for upstream {} after repeated failures", upstream);
+                    } else if !transient && attempt > 1 {
+                        info!("Upstream {} recovered on attempt {}", upstream, attempt);
+                    }
+                    return Ok(result?);
+                }
+            }
+        }
+    }
+
+    fn breaker<T>(&self, upstream: &str, f: impl FnOnce(&mut CircuitBreaker) -> T) -> T {
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        f(breakers.entry(upstream.to_string()).or_default())
+    }
+}
+
+/// An HTTPS server on localhost with a self-signed certificate, for checking
+/// that clients verify certificates unless told not to
+#[cfg(test)]
+pub(crate) mod self_signed {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    pub const CERT_PATH: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/self_signed.cert");
+    const KEY_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/self_signed.key");
+
+    /// Answers every request with `200 {}`; returns the server's base URL
+    pub fn spawn_server() -> String {
+        let cert = std::fs::read(CERT_PATH).unwrap();
+        let key = std::fs::read(KEY_PATH).unwrap();
+        let identity = native_tls::Identity::from_pkcs8(&cert, &key).unwrap();
+        let acceptor = native_tls::TlsAcceptor::new(identity).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://127.0.0.1:{}", listener.local_addr().unwrap().port());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // Clients that reject the certificate fail the handshake
+                let Ok(mut stream) = acceptor.accept(stream) else {
+                    continue;
+                };
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request);
+                let response = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                                content-length: 2\r\nconnection: close\r\n\r\n{}";
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_is_jittered_and_capped() {
+        let settings = UpstreamSettings::default();
+        assert_eq!(backoff_delay(&settings, 1, 0), Duration::ZERO);
+        assert_eq!(backoff_delay(&settings, 1, 200), Duration::from_millis(200));
+        assert_eq!(backoff_delay(&settings, 1, 201), Duration::ZERO);
+        assert_eq!(backoff_delay(&settings, 2, 400), Duration::from_millis(400));
+        assert!(backoff_delay(&settings, 30, u64::MAX) <= Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_transient_statuses() {
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_transient_status(StatusCode::GATEWAY_TIMEOUT));
+        assert!(!is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens_and_recovers() {
+        let open_for = Duration::from_secs(30);
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::default();
+
+        assert!(!breaker.record(false, start, 2, open_for));
+        assert!(breaker.admit(start, open_for).is_ok());
+        assert!(breaker.record(false, start, 2, open_for));
+        assert!(breaker.admit(start + Duration::from_secs(10), open_for).is_err());
+
+        // One trial after the cooldown; the rest wait for its outcome
+        let later = start + open_for;
+        assert!(breaker.admit(later, open_for).is_ok());
+        assert!(breaker.admit(later, open_for).is_err());
+        assert!(!breaker.record(true, later, 2, open_for));
+        assert!(breaker.admit(later, open_for).is_ok());
+    }
+
+    #[test]
+    fn test_error_status_reads_app_errors() {
+        let unavailable = anyhow::Error::new(AppError::UpstreamUnavailable("tapd".to_string()));
+        assert_eq!(error_status(&unavailable), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error_status(&anyhow::anyhow!("boom")), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_client_builder_verifies_certificates_when_asked() {
+        let url = self_signed::spawn_server();
+        let get = |settings: HttpClientSettings| {
+            let url = url.clone();
+            async move {
+                let client = client_builder(&settings, "test").unwrap().build().unwrap();
+                client.get(&url).send().await
+            }
+        };
+
+        let verifying = HttpClientSettings { tls_verify: true, ..HttpClientSettings::default() };
+        assert!(get(verifying.clone()).await.is_err());
+        let trusting = HttpClientSettings {
+            ca_cert_path: Some(self_signed::CERT_PATH.to_string()),
+            ..verifying
+        };
+        assert!(get(trusting).await.unwrap().status().is_success());
+        let unverified = HttpClientSettings { tls_verify: false, ..HttpClientSettings::default() };
+        assert!(get(unverified).await.unwrap().status().is_success());
+    }
+}
@@ -0,0 +1,498 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument};
+
+use super::lnd::lnd_client;
+use super::upstream::SendUpstream;
+use crate::error::AppError;
+use crate::types::{AppState, CoinSelection};
+
+/// Leases last ten minutes unless the request asks otherwise, as in LND
+const DEFAULT_LEASE_SECONDS: u64 = 600;
+const MAX_LEASE_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// The lease ID the gateway locks outputs under, so it only ever releases its
+/// own leases and never tapd's or LND's internal ones
+pub fn gateway_lease_id() -> [u8; 32] {
+    Sha256::digest(b"taproot-gateway/utxo-lease").into()
+}
+
+/// Splits a `txid:vout` outpoint, normalising the txid to lowercase hex
+pub fn parse_outpoint(outpoint: &str) -> Result<(String, u32), AppError> {
+    let invalid = || {
+        AppError::InvalidInput(format!("Invalid outpoint '{outpoint}', expected txid:vout"))
+    };
+    let (txid, vout) = outpoint.trim().split_once(':').ok_or_else(invalid)?;
+    if txid.len() != 64 || !txid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let vout = vout.parse::<u32>().map_err(|_| invalid())?;
+    Ok((txid.to_ascii_lowercase(), vout))
+}
+
+/// An asset committed to an anchor output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnchoredAsset {
+    /// Hex
+    pub asset_id: String,
+    pub name: String,
+    pub amount: u64,
+    /// Hex
+    pub script_key: String,
+}
+
+/// A BTC output managed by tapd and the assets anchored in it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnchorUtxo {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub amount_sat: u64,
+    pub assets: Vec<AnchoredAsset>,
+    /// Hex lease ID while the output is locked
+    pub lease_owner: Option<String>,
+    /// Unix seconds
+    pub lease_expiry: Option<i64>,
+    /// Locked through `/wallet/utxos/lease` rather than by a pending transfer
+    pub leased_by_gateway: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UtxoListResponse {
+    pub utxos: Vec<AnchorUtxo>,
+}
+
+fn number(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(text) => text.parse().ok(),
+        other => other.as_i64(),
+    }
+}
+
+fn base64_hex(value: &Value) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(value.as_str()?).ok()?;
+    (!bytes.is_empty()).then(|| hex::encode(bytes))
+}
+
+/// Reads tapd's `ListUtxosResponse`, ordered by outpoint
+pub fn parse_managed_utxos(response: &Value, now: i64) -> Vec<AnchorUtxo> {
+    let gateway_lease = hex::encode(gateway_lease_id());
+    let mut utxos: Vec<AnchorUtxo> = response["managed_utxos"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, utxo)| {
+            let assets = utxo["assets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|asset| {
+                    let genesis = &asset["asset_genesis"];
+                    Some(AnchoredAsset {
+                        asset_id: base64_hex(&genesis["asset_id"])?,
+                        name: genesis["name"].as_str().unwrap_or_default().to_string(),
+                        amount: number(&asset["amount"]).unwrap_or(0).max(0) as u64,
+                        script_key: base64_hex(&asset["script_key"]).unwrap_or_default(),
+                    })
+                })
+                .collect();
+            // Expired leases linger in tapd's listing until the output is reused
+            let lease_expiry = number(&utxo["lease_expiry_unix"]).filter(|expiry| *expiry > now);
+            let lease_owner = lease_expiry.and_then(|_| base64_hex(&utxo["lease_owner"]));
+            AnchorUtxo {
+                outpoint: utxo["out_point"].as_str().unwrap_or(key).to_string(),
+                amount_sat: number(&utxo["amt_sat"]).unwrap_or(0).max(0) as u64,
+                assets,
+                leased_by_gateway: lease_owner.as_deref() == Some(gateway_lease.as_str()),
+                lease_expiry: lease_owner.as_ref().and(lease_expiry),
+                lease_owner,
+            }
+        })
+        .collect();
+    utxos.sort_by(|a, b| a.outpoint.cmp(&b.outpoint));
+    utxos
+}
+
+/// An asset leaf chosen to fund a send
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetInput {
+    /// `txid:vout` of the anchor output
+    pub outpoint: String,
+    pub asset: AnchoredAsset,
+}
+
+impl AssetInput {
+    /// tapd's `PrevId`, with the anchor txid in internal byte order
+    pub fn prev_id(&self) -> Result<Value, AppError> {
+        let (txid, vout) = parse_outpoint(&self.outpoint)?;
+        let mut txid = hex::decode(txid).unwrap_or_default();
+        txid.reverse();
+        let bytes = |hex_value: &str| {
+            hex::decode(hex_value)
+                .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+                .map_err(|_| AppError::InvalidInput(format!("Invalid hex '{hex_value}'")))
+        };
+        Ok(serde_json::json!({
+            "anchor_point": {
+                "txid": base64::engine::general_purpose::STANDARD.encode(txid),
+                "output_index": vout,
+            },
+            "id": bytes(&self.asset.asset_id)?,
+            "script_key": bytes(&self.asset.script_key)?,
+        }))
+    }
+}
+
+/// Chooses leaves of `asset_id` worth at least `amount`. The ordered
+/// strategies skip leased outputs; named outpoints may be ones the gateway
+/// leased, since that is how they are held back for manual sends.
+pub fn select_asset_inputs(
+    utxos: &[AnchorUtxo],
+    asset_id: &str,
+    amount: u64,
+    selection: &CoinSelection,
+) -> Result<Vec<AssetInput>, AppError> {
+    let asset_id = asset_id.trim().to_ascii_lowercase();
+    let leaves = |utxo: &AnchorUtxo| -> Vec<AssetInput> {
+        utxo.assets
+            .iter()
+            .filter(|asset| asset.asset_id == asset_id)
+            .map(|asset| AssetInput {
+                outpoint: utxo.outpoint.clone(),
+                asset: asset.clone(),
+            })
+            .collect()
+    };
+
+    let candidates: Vec<AssetInput> = match selection {
+        CoinSelection::Auto => return Ok(Vec::new()),
+        CoinSelection::LargestFirst | CoinSelection::SmallestFirst => {
+            let mut candidates: Vec<AssetInput> = utxos
+                .iter()
+                .filter(|utxo| utxo.lease_owner.is_none())
+                .flat_map(leaves)
+                .collect();
+            candidates.sort_by_key(|input| input.asset.amount);
+            if *selection == CoinSelection::LargestFirst {
+                candidates.reverse();
+            }
+            candidates
+        }
+        CoinSelection::Outpoints { outpoints } => {
+            let mut candidates = Vec::new();
+            for outpoint in outpoints {
+                let (txid, vout) = parse_outpoint(outpoint)?;
+                let outpoint = format!("{txid}:{vout}");
+                let utxo = utxos
+                    .iter()
+                    .find(|utxo| utxo.outpoint.eq_ignore_ascii_case(&outpoint))
+                    .ok_or_else(|| AppError::NotFound(format!("No anchor output {outpoint}")))?;
+                if utxo.lease_owner.is_some() && !utxo.leased_by_gateway {
+                    return Err(AppError::Conflict(format!(
+                        "{outpoint} is locked by a pending transfer"
+                    )));
+                }
+                let found = leaves(utxo);
+                if found.is_empty() {
+                    return Err(AppError::InvalidInput(format!(
+                        "{outpoint} holds none of asset {asset_id}"
+                    )));
+                }
+                candidates.extend(found);
+            }
+            // Every named outpoint is spent, not just enough of them
+            let total: u64 = candidates.iter().map(|input| input.asset.amount).sum();
+            if total < amount {
+                return Err(AppError::InvalidInput(format!(
+                    "Selected outpoints hold {total} units, {amount} needed"
+                )));
+            }
+            return Ok(candidates);
+        }
+    };
+
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+    for input in candidates {
+        if total >= amount {
+            break;
+        }
+        total += input.asset.amount;
+        selected.push(input);
+    }
+    if total < amount {
+        return Err(AppError::InvalidInput(format!(
+            "Unleased outputs hold {total} units of {asset_id}, {amount} needed"
+        )));
+    }
+    Ok(selected)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeaseUtxoRequest {
+    /// `txid:vout`
+    pub outpoint: String,
+    pub expiration_seconds: Option<u64>,
+}
+
+impl LeaseUtxoRequest {
+    /// LND's `LeaseOutputRequest` for this lease
+    pub fn lnd_request(&self) -> Result<Value, AppError> {
+        let seconds = self.expiration_seconds.unwrap_or(DEFAULT_LEASE_SECONDS);
+        if seconds == 0 || seconds > MAX_LEASE_SECONDS {
+            return Err(AppError::InvalidInput(format!(
+                "expiration_seconds must be between 1 and {MAX_LEASE_SECONDS}"
+            )));
+        }
+        let mut request = release_request(&self.outpoint)?;
+        request["expiration_seconds"] = seconds.to_string().into();
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseUtxoRequest {
+    /// `txid:vout`
+    pub outpoint: String,
+}
+
+/// LND's `ReleaseOutputRequest` for the gateway's lease on `outpoint`
+fn release_request(outpoint: &str) -> Result<Value, AppError> {
+    let (txid, vout) = parse_outpoint(outpoint)?;
+    Ok(serde_json::json!({
+        "id": base64::engine::general_purpose::STANDARD.encode(gateway_lease_id()),
+        "outpoint": {"txid_str": txid, "output_index": vout},
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct UtxoLeaseResponse {
+    pub outpoint: String,
+    /// Unix seconds; `None` once released
+    pub expires_at: Option<i64>,
+}
+
+/// Lists tapd's anchor outputs, including leased ones
+#[instrument(skip(state))]
+pub async fn list_utxos_handler(
+    State(state): State<AppState>,
+) -> Result<Json<UtxoListResponse>, (StatusCode, Json<Value>)> {
+    let url = format!("{}/v1/taproot-assets/assets/utxos?include_leased=true", state.base_url.0);
+    let response = state
+        .http_client
+        .get(&url)
+        .header("Grpc-Metadata-macaroon", &state.macaroon_hex.0)
+        .send_upstream()
+        .await
+        .map_err(error_response)?;
+
+    if !response.status().is_success() {
+        return Err(error_response(AppError::from_response(response).await));
+    }
+
+    let body = response.json::<Value>().await.map_err(|e| error_response(e.into()))?;
+    let now = chrono::Utc::now().timestamp();
+    Ok(Json(UtxoListResponse {
+        utxos: parse_managed_utxos(&body, now),
+    }))
+}
+
+/// Locks an output in LND's wallet so neither tapd nor LND spends it
+#[instrument(skip(state))]
+pub async fn lease_utxo_handler(
+    State(state): State<AppState>,
+    Json(request): Json<LeaseUtxoRequest>,
+) -> Result<Json<UtxoLeaseResponse>, (StatusCode, Json<Value>)> {
+    let body = request.lnd_request().map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    info!("Leasing UTXO {}", request.outpoint);
+
+    let leased: Value = lnd
+        .post("/v2/wallet/utxos/lease", &body)
+        .await
+        .map_err(error_response)?;
+    Ok(Json(UtxoLeaseResponse {
+        outpoint: request.outpoint.trim().to_ascii_lowercase(),
+        expires_at: number(&leased["expiration"]),
+    }))
+}
+
+/// Unlocks an output the gateway leased earlier
+#[instrument(skip(state))]
+pub async fn release_utxo_handler(
+    State(state): State<AppState>,
+    Json(request): Json<ReleaseUtxoRequest>,
+) -> Result<Json<UtxoLeaseResponse>, (StatusCode, Json<Value>)> {
+    let body = release_request(&request.outpoint).map_err(error_response)?;
+    let lnd = lnd_client(&state).map_err(error_response)?;
+    info!("Releasing UTXO {}", request.outpoint);
+
+    let _: Value = lnd
+        .post("/v2/wallet/utxos/release", &body)
+        .await
+        .map_err(error_response)?;
+    Ok(Json(UtxoLeaseResponse {
+        outpoint: request.outpoint.trim().to_ascii_lowercase(),
+        expires_at: None,
+    }))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_outpoint() {
+        let txid = "AB".repeat(32);
+        assert_eq!(parse_outpoint(&format!("{txid}:1")).unwrap(), ("ab".repeat(32), 1));
+        let rejected = ["".to_string(), "ab:1".to_string(), txid.clone(), format!("{txid}:-1")];
+        for invalid in rejected {
+            assert!(matches!(parse_outpoint(&invalid), Err(AppError::InvalidInput(_))), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_managed_utxos() {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        let first = format!("{}:0", "aa".repeat(32));
+        let second = format!("{}:1", "bb".repeat(32));
+        let response = serde_json::json!({"managed_utxos": {
+            second.clone(): {
+                "out_point": second,
+                "amt_sat": "1000",
+                "assets": [],
+                "lease_owner": encode(&[0x01; 32]),
+                "lease_expiry_unix": "500",
+            },
+            first.clone(): {
+                "out_point": first,
+                "amt_sat": "1000",
+                "assets": [{
+                    "asset_genesis": {"asset_id": encode(&[0xcd; 32]), "name": "beefbux"},
+                    "amount": "250",
+                }],
+                "lease_owner": encode(&gateway_lease_id()),
+                "lease_expiry_unix": "2000",
+            },
+        }});
+
+        let utxos = parse_managed_utxos(&response, 1_000);
+        assert_eq!(utxos.len(), 2);
+        assert_eq!(utxos[0].outpoint, first);
+        assert_eq!(
+            utxos[0].assets,
+            vec![AnchoredAsset {
+                asset_id: "cd".repeat(32),
+                name: "beefbux".to_string(),
+                amount: 250,
+                script_key: String::new(),
+            }]
+        );
+        assert!(utxos[0].leased_by_gateway);
+        assert_eq!(utxos[0].lease_expiry, Some(2000));
+        // An expired lease no longer locks the output
+        assert_eq!(utxos[1].lease_owner, None);
+        assert_eq!(utxos[1].lease_expiry, None);
+        assert!(!utxos[1].leased_by_gateway);
+    }
+
+    #[test]
+    fn test_select_asset_inputs() {
+        let utxo = |seed: &str, amounts: &[u64], lease: Option<bool>| AnchorUtxo {
+            outpoint: format!("{}:0", seed.repeat(32)),
+            amount_sat: 1000,
+            assets: amounts
+                .iter()
+                .map(|amount| AnchoredAsset {
+                    asset_id: "cd".repeat(32),
+                    name: "beefbux".to_string(),
+                    amount: *amount,
+                    script_key: "02".repeat(33),
+                })
+                .collect(),
+            lease_owner: lease.map(|_| "01".repeat(32)),
+            lease_expiry: lease.map(|_| 2000),
+            leased_by_gateway: lease == Some(true),
+        };
+        let utxos = vec![
+            utxo("aa", &[5, 40], None),
+            utxo("bb", &[20], None),
+            utxo("cc", &[100], Some(true)),
+            utxo("dd", &[100], Some(false)),
+        ];
+        let asset_id = "cd".repeat(32);
+        let amounts = |inputs: Vec<AssetInput>| -> Vec<u64> {
+            inputs.iter().map(|input| input.asset.amount).collect()
+        };
+
+        let largest = select_asset_inputs(&utxos, &asset_id, 50, &CoinSelection::LargestFirst);
+        assert_eq!(amounts(largest.unwrap()), vec![40, 20]);
+        let smallest = select_asset_inputs(&utxos, &asset_id, 50, &CoinSelection::SmallestFirst);
+        assert_eq!(amounts(smallest.unwrap()), vec![5, 20, 40]);
+        assert!(select_asset_inputs(&utxos, &asset_id, 66, &CoinSelection::LargestFirst).is_err());
+        let auto = select_asset_inputs(&utxos, &asset_id, 1, &CoinSelection::Auto);
+        assert!(auto.unwrap().is_empty());
+
+        let named = |seeds: &[&str]| CoinSelection::Outpoints {
+            outpoints: seeds.iter().map(|seed| format!("{}:0", seed.repeat(32))).collect(),
+        };
+        let chosen = select_asset_inputs(&utxos, &asset_id, 10, &named(&["aa", "cc"])).unwrap();
+        assert_eq!(amounts(chosen), vec![5, 40, 100]);
+        let locked = select_asset_inputs(&utxos, &asset_id, 10, &named(&["dd"]));
+        assert!(matches!(locked, Err(AppError::Conflict(_))));
+        let short = select_asset_inputs(&utxos, &asset_id, 30, &named(&["bb"]));
+        assert!(matches!(short, Err(AppError::InvalidInput(_))));
+        let unknown = select_asset_inputs(&utxos, &asset_id, 1, &named(&["ee"]));
+        assert!(matches!(unknown, Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_prev_id_uses_internal_txid_order() {
+        let input = AssetInput {
+            outpoint: format!("{}01:3", "00".repeat(31)),
+            asset: AnchoredAsset {
+                asset_id: "cd".repeat(32),
+                name: String::new(),
+                amount: 1,
+                script_key: "02".repeat(33),
+            },
+        };
+        let prev_id = input.prev_id().unwrap();
+        let txid = base64::engine::general_purpose::STANDARD
+            .decode(prev_id["anchor_point"]["txid"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(txid[0], 0x01);
+        assert_eq!(prev_id["anchor_point"]["output_index"], 3);
+    }
+
+    #[test]
+    fn test_lease_request() {
+        let outpoint = format!("{}:2", "ef".repeat(32));
+        let request = LeaseUtxoRequest {
+            outpoint: outpoint.clone(),
+            expiration_seconds: None,
+        };
+        let body = request.lnd_request().unwrap();
+        assert_eq!(body["outpoint"]["txid_str"], "ef".repeat(32));
+        assert_eq!(body["outpoint"]["output_index"], 2);
+        assert_eq!(body["expiration_seconds"], "600");
+        assert_eq!(body["id"], release_request(&outpoint).unwrap()["id"]);
+
+        let too_long = LeaseUtxoRequest {
+            outpoint,
+            expiration_seconds: Some(MAX_LEASE_SECONDS + 1),
+        };
+        assert!(too_long.lnd_request().is_err());
+    }
+}
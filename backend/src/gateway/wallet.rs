@@ -1,12 +1,214 @@
-use axum::{response::Json, http::StatusCode, extract::State};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use crate::types::AppState;
+use tracing::warn;
 
+use super::price_oracle::AssetRate;
+use crate::error::AppError;
+use crate::types::{AppState, BalanceGrouping};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BalanceParams {
+    #[serde(default)]
+    pub group_by: BalanceGrouping,
+}
+
+/// One asset's or one group's spendable balance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssetBalance {
+    /// Hex; set when grouped by asset ID
+    pub asset_id: Option<String>,
+    /// Hex; set when grouped by group key
+    pub group_key: Option<String>,
+    pub name: Option<String>,
+    /// Base units
+    pub balance: u64,
+    /// Value in `fiat_currency`; priced per asset ID, so never set for groups
+    pub fiat_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletBalanceResponse {
+    pub group_by: BalanceGrouping,
+    /// Currency of each `fiat_value`; `None` when no fiat reference is configured
+    pub fiat_currency: Option<String>,
+    pub balances: Vec<AssetBalance>,
+}
+
+fn base64_hex(value: &Value) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(value.as_str()?).ok()?;
+    Some(hex::encode(bytes))
+}
+
+fn string_u64(value: &Value) -> u64 {
+    match value {
+        Value::String(text) => text.parse().unwrap_or(0),
+        other => other.as_u64().unwrap_or(0),
+    }
+}
+
+/// Reads tapd's `ListBalancesResponse` for either grouping, ordered by key
+pub fn parse_balances(response: &Value, group_by: BalanceGrouping) -> Vec<AssetBalance> {
+    let section = match group_by {
+        BalanceGrouping::AssetId => "asset_balances",
+        BalanceGrouping::GroupKey => "asset_group_balances",
+    };
+    let mut balances: Vec<AssetBalance> = response[section]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, entry)| {
+            let key = key.to_ascii_lowercase();
+            let (asset_id, group_key) = match group_by {
+                BalanceGrouping::AssetId => (Some(key), None),
+                BalanceGrouping::GroupKey => {
+                    (None, Some(base64_hex(&entry["group_key"]).unwrap_or(key)))
+                }
+            };
+            AssetBalance {
+                asset_id,
+                group_key,
+                name: entry["asset_genesis"]["name"].as_str().map(str::to_string),
+                balance: string_u64(&entry["balance"]),
+                fiat_value: None,
+            }
+        })
+        .collect();
+    balances.sort_by(|a, b| (&a.asset_id, &a.group_key).cmp(&(&b.asset_id, &b.group_key)));
+    balances
+}
+
+/// Converts base units to the fiat currency through BTC, rounded to cents
+pub fn fiat_value(balance: u64, asset_rate: &AssetRate, fiat_rate: &AssetRate) -> Option<f64> {
+    if asset_rate.units_per_btc <= 0.0 {
+        return None;
+    }
+    let btc = balance as f64 / asset_rate.units_per_btc;
+    Some((btc * fiat_rate.units_per_btc * 100.0).round() / 100.0)
+}
+
+/// Prices each balance in the configured fiat currency; assets the oracle
+/// cannot price are left without a value
+async fn value_balances(state: &AppState, asset_id: &str, balances: &mut [AssetBalance]) {
+    let fiat_rate = match state.price_oracle.rate(asset_id).await {
+        Ok(rate) => rate,
+        Err(e) => {
+            warn!("Fiat reference rate unavailable: {}", e);
+            return;
+        }
+    };
+    let rates = futures::future::join_all(balances.iter().map(|entry| async {
+        let asset_id = entry.asset_id.as_deref()?;
+        match state.price_oracle.rate(asset_id).await {
+            Ok(rate) => Some(rate),
+            Err(e) => {
+                warn!("No rate for asset {}: {}", asset_id, e);
+                None
+            }
+        }
+    }))
+    .await;
+    for (entry, rate) in balances.iter_mut().zip(rates) {
+        entry.fiat_value = rate.and_then(|rate| fiat_value(entry.balance, &rate, &fiat_rate));
+    }
+}
+
+/// Asset balances grouped by asset ID (the default) or group key, with fiat
+/// values when a fiat reference is configured
 pub async fn get_balance(
-    State(state): State<AppState>
-) -> Result<Json<Value>, StatusCode> {
-    match state.tapd_client.get_balance().await {
-        Ok(balance) => Ok(Json(balance)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    State(state): State<AppState>,
+    Query(params): Query<BalanceParams>,
+) -> Result<Json<WalletBalanceResponse>, (StatusCode, Json<Value>)> {
+    let response = state
+        .tapd_client
+        .get_balance(params.group_by)
+        .await
+        .map_err(|e| error_response(AppError::RequestError(e.to_string())))?;
+    let mut balances = parse_balances(&response, params.group_by);
+
+    let fiat = state.fiat_reference.as_ref();
+    if let Some(fiat) = fiat.filter(|_| params.group_by == BalanceGrouping::AssetId) {
+        value_balances(&state, &fiat.asset_id, &mut balances).await;
+    }
+    Ok(Json(WalletBalanceResponse {
+        group_by: params.group_by,
+        fiat_currency: fiat.map(|fiat| fiat.currency.clone()),
+        balances,
+    }))
+}
+
+fn error_response(error: AppError) -> (StatusCode, Json<Value>) {
+    let status = error.status_code();
+    let error_json = serde_json::json!({
+        "error": error.to_string(),
+        "type": format!("{:?}", error)
+    });
+    (status, Json(error_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(units_per_btc: f64) -> AssetRate {
+        AssetRate {
+            asset_id: String::new(),
+            coefficient: units_per_btc.to_string(),
+            scale: 0,
+            units_per_btc,
+            source: "http",
+            observed_at: 0,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_balances_by_asset_id() {
+        let response = serde_json::json!({"asset_balances": {
+            "BB".repeat(32): {"asset_genesis": {"name": "beefbux"}, "balance": "250"},
+            "aa".repeat(32): {"asset_genesis": {"name": "usd"}, "balance": "1000"},
+        }});
+        let balances = parse_balances(&response, BalanceGrouping::AssetId);
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[0].asset_id, Some("aa".repeat(32)));
+        assert_eq!(balances[0].name.as_deref(), Some("usd"));
+        assert_eq!(balances[1].asset_id, Some("bb".repeat(32)));
+        assert_eq!(balances[1].balance, 250);
+        assert!(parse_balances(&response, BalanceGrouping::GroupKey).is_empty());
+    }
+
+    #[test]
+    fn test_parse_balances_by_group_key() {
+        let group_key = base64::engine::general_purpose::STANDARD.encode([0x02; 33]);
+        let response = serde_json::json!({"asset_group_balances": {
+            "02".repeat(33): {"group_key": group_key, "balance": "75"},
+        }});
+        let balances = parse_balances(&response, BalanceGrouping::GroupKey);
+        assert_eq!(
+            balances,
+            vec![AssetBalance {
+                asset_id: None,
+                group_key: Some("02".repeat(33)),
+                name: None,
+                balance: 75,
+                fiat_value: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fiat_value() {
+        // 50,000 cents of a stablecoin at 6,000,000 cents per BTC, with BTC at 60,000 USD
+        assert_eq!(fiat_value(50_000, &rate(6_000_000.0), &rate(60_000.0)), Some(500.0));
+        assert_eq!(fiat_value(1, &rate(3.0), &rate(1.0)), Some(0.33));
+        assert_eq!(fiat_value(1, &rate(0.0), &rate(60_000.0)), None);
+
+        let params: BalanceParams = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(params.group_by, BalanceGrouping::AssetId);
     }
 }
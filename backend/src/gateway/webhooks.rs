@@ -0,0 +1,259 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::events::{EventBroker, EventQueryParams};
+use super::mailbox_webhooks::{backoff_delay, check_webhook_target, sign_payload};
+use super::transaction_events::{transaction_updates, TransactionUpdate};
+use crate::config::WebhookSettings;
+use crate::error::AppError;
+use crate::storage::webhooks::{
+    DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent, WebhookStore,
+};
+use crate::types::TransactionStatus;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries attempted per poll
+const DELIVERY_BATCH_SIZE: usize = 50;
+
+/// Body POSTed to a webhook; `id` stays the same across retries so
+/// receivers can drop duplicates
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookMessage<'a> {
+    pub id: Uuid,
+    pub event: WebhookEvent,
+    pub created_at: i64,
+    pub data: &'a serde_json::Value,
+}
+
+/// Queues events for subscribed webhooks and delivers them in the
+/// background, retrying failures with exponential backoff
+pub struct WebhookWorker {
+    store: Arc<dyn WebhookStore>,
+    client: reqwest::Client,
+    max_attempts: u32,
+    initial_backoff: Duration,
+}
+
+impl WebhookWorker {
+    pub fn new(
+        store: Arc<dyn WebhookStore>,
+        client: reqwest::Client,
+        settings: &WebhookSettings,
+    ) -> Self {
+        Self {
+            store,
+            client,
+            max_attempts: settings.max_attempts,
+            initial_backoff: Duration::from_millis(settings.initial_backoff_ms),
+        }
+    }
+
+    pub fn store(&self) -> &dyn WebhookStore {
+        self.store.as_ref()
+    }
+
+    /// Queues events from asset sends and receives, and delivers due
+    /// deliveries every `period`
+    pub fn spawn(self: &Arc<Self>, broker: &Arc<EventBroker>, period: Duration) {
+        for event_type in ["asset-send", "asset-receive"] {
+            let worker = Arc::clone(self);
+            let request = EventQueryParams::default().subscription_request(event_type);
+            let mut events = broker.subscribe(event_type, request);
+            let broker = Arc::clone(broker);
+
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) if event.is_marker() => {}
+                        Ok(event) => {
+                            for update in transaction_updates(event_type, &event.payload) {
+                                if let Err(e) = worker.enqueue_update(&update).await {
+                                    warn!("Failed to queue webhooks for {}: {}", event_type, e);
+                                }
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            broker.record_lag(event_type, skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
+        let worker = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = worker.deliver_due().await {
+                    warn!("Webhook delivery failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Confirmed transfers are both a confirmation and a balance change
+    async fn enqueue_update(&self, update: &TransactionUpdate) -> Result<usize, AppError> {
+        if update.status != TransactionStatus::Confirmed {
+            return Ok(0);
+        }
+        let transaction = json!({
+            "type": update.tx_type,
+            "destination": update.destination,
+            "status": update.status,
+            "asset_id": update.asset_id,
+            "amount": update.amount,
+        });
+        let balance = json!({
+            "asset_id": update.asset_id,
+            "direction": update.tx_type,
+            "amount": update.amount,
+        });
+        let confirmed = self.enqueue(WebhookEvent::TransactionConfirmed, transaction).await?;
+        Ok(confirmed + self.enqueue(WebhookEvent::BalanceChanged, balance).await?)
+    }
+
+    /// Queues `data` for every webhook subscribed to `event`; returns how many
+    pub async fn enqueue(
+        &self,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<usize, AppError> {
+        let subscribers = self.store.subscribers(event).await?;
+        let now = Utc::now().timestamp();
+        for webhook in &subscribers {
+            let delivery = WebhookDelivery::new(webhook.id, event, data.clone(), now);
+            self.store.enqueue(&delivery).await?;
+        }
+        Ok(subscribers.len())
+    }
+
+    /// Attempts every due delivery once; returns how many succeeded
+    pub async fn deliver_due(&self) -> Result<usize, AppError> {
+        let now = Utc::now().timestamp();
+        let mut delivered = 0;
+        for mut delivery in self.store.due(now, DELIVERY_BATCH_SIZE).await? {
+            let Some(webhook) = self.store.get(delivery.webhook_id).await? else {
+                continue;
+            };
+            let message = WebhookMessage {
+                id: delivery.id,
+                event: delivery.event,
+                created_at: delivery.created_at,
+                data: &delivery.payload,
+            };
+            let result = self.send(&webhook, &message).await;
+
+            delivery.attempts += 1;
+            match result {
+                Ok(_) => {
+                    delivery.status = DeliveryStatus::Delivered;
+                    delivery.delivered_at = Some(Utc::now().timestamp());
+                    delivery.last_error = None;
+                    delivered += 1;
+                }
+                Err(e) if delivery.attempts < self.max_attempts => {
+                    let delay = backoff_delay(self.initial_backoff, delivery.attempts);
+                    delivery.next_attempt_at = now.saturating_add(delay.as_secs() as i64);
+                    delivery.last_error = Some(e.to_string());
+                }
+                Err(e) => {
+                    warn!(
+                        "Giving up on webhook {} delivery {} after {} attempts: {}",
+                        webhook.id, delivery.id, delivery.attempts, e
+                    );
+                    delivery.status = DeliveryStatus::Failed;
+                    delivery.last_error = Some(e.to_string());
+                }
+            }
+            self.store.update_delivery(&delivery).await?;
+        }
+        if delivered > 0 {
+            info!("Delivered {} webhook events", delivered);
+        }
+        Ok(delivered)
+    }
+
+    /// POSTs one signed message; returns the receiver's status on success
+    pub async fn send(
+        &self,
+        webhook: &Webhook,
+        message: &WebhookMessage<'_>,
+    ) -> Result<u16, AppError> {
+        check_webhook_target(&webhook.url).await?;
+        let body = serde_json::to_string(message)?;
+        let timestamp = Utc::now().timestamp().to_string();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, message.event.as_str())
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                format!("sha256={}", sign_payload(&webhook.secret, &timestamp, &body)),
+            )
+            .body(body)
+            .timeout(WEBHOOK_TIMEOUT)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(AppError::RequestError(format!("Webhook responded with {status}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::webhooks::InMemoryWebhookStore;
+    use crate::types::TransactionType;
+
+    #[tokio::test]
+    async fn test_confirmed_updates_queue_both_events() {
+        let store = Arc::new(InMemoryWebhookStore::new());
+        let webhook = Webhook {
+            id: Uuid::new_v4(),
+            url: "https://example.com/hooks".to_string(),
+            events: vec![WebhookEvent::TransactionConfirmed, WebhookEvent::BalanceChanged],
+            secret: "secret".to_string(),
+            created_at: 0,
+        };
+        store.create(&webhook).await.unwrap();
+        let worker =
+            WebhookWorker::new(store.clone(), reqwest::Client::new(), &WebhookSettings::default());
+
+        let mut update = TransactionUpdate {
+            tx_type: TransactionType::Receive,
+            destination: "taprt1me".to_string(),
+            status: TransactionStatus::Pending,
+            asset_id: Some("asset123".to_string()),
+            amount: 250,
+        };
+        assert_eq!(worker.enqueue_update(&update).await.unwrap(), 0);
+
+        update.status = TransactionStatus::Confirmed;
+        assert_eq!(worker.enqueue_update(&update).await.unwrap(), 2);
+        let due = store.due(i64::MAX, 10).await.unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].payload["destination"], "taprt1me");
+        assert_eq!(due[1].event, WebhookEvent::BalanceChanged);
+        assert_eq!(due[1].payload["direction"], "Receive");
+    }
+}
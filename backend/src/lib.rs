@@ -3,6 +3,7 @@ pub mod config;
 pub mod crypto;
 pub mod error;
 pub mod gateway;
+pub mod secrets;
 pub mod storage;
 pub mod taproot;
 pub mod types;
@@ -1,12 +1,62 @@
-use axum::Router;
+use axum::{middleware, Router};
 use tower_http::cors::CorsLayer;
 use tracing::info;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 // Use the lib module structure
 use taproot_backend::{
     api::routes,
-    taproot::client::TapdClient,
+    config::{
+        BurnSettings, CacheSettings, ChallengeStoreSettings, HttpClientSettings, EventStoreSettings, FeeSettings, ImageStoreSettings,
+        AssetPreferenceStoreSettings, BalanceSnapshotSettings, IdempotencyStoreSettings,
+        AuditLogSettings, DatabasePoolSettings,
+        LndSettings, MailboxSettings, MAX_ASSET_META_BYTES,
+        PaymentStoreSettings, PriceAlertSettings, PriceOracleSettings, PushSettings,
+        ReconcileSettings, RetentionSettings,
+        RfqOrderStoreSettings,
+        RfqSettings, SettingsStoreSettings, TapdSettings, TapdTransport, UpstreamSettings,
+        WebhookSettings,
+    },
+    gateway::{
+        audit::record_audit_entry,
+        balance_history::BalanceRecorder,
+        blocks::ChainWatcher,
+        cache::ResponseCache,
+        events::{spawn_event_recorder, EventBroker},
+        fees::FeeEstimator,
+        lnd::LndClient,
+        mailbox_limits::MailboxLimiter,
+        mailbox_registry::MailboxRegistry,
+        mailbox_webhooks::WebhookDispatcher,
+        metrics::PromMonitoring,
+        notifications::{create_push_provider, spawn_push_notifier},
+        price_alerts::PriceAlertMonitor,
+        price_oracle::create_price_oracle,
+        reconcile::Reconciler,
+        retention::Pruner,
+        rfq_sim::RfqSimulator,
+        transaction_events::spawn_transaction_updater,
+        upstream,
+        webhooks::WebhookWorker,
+    },
+    storage::{
+        self, asset_preferences::create_asset_preference_store,
+        audit::create_audit_log,
+        balance_snapshots::create_balance_snapshot_store,
+        challenges::create_challenge_store,
+        devices::InMemoryDeviceStore,
+        events::create_event_store, idempotency::create_idempotency_store,
+        images::create_image_store, invoices::InMemoryInvoiceRepo,
+        payments::create_payment_store,
+        price_alerts::create_price_alert_store,
+        receivers::InMemoryReceiverRepo,
+        rfq_orders::create_rfq_order_store,
+        settings::create_settings_store,
+        transactions::InMemoryTransactionRepo,
+        webhooks::create_webhook_store,
+    },
+    taproot::{client::TapdClient, grpc::TapdGrpcClient},
     types::*,
 };
 
@@ -18,33 +68,271 @@ async fn main() -> anyhow::Result<()> {
     // Load environment variables
     dotenv::dotenv().ok();
 
+    // Retry transient upstream failures and fail fast while an upstream is down
+    upstream::configure(UpstreamSettings::from_env());
+
     // Initialize Taproot Assets client
-    let gateway_url = std::env::var("TAPROOT_GATEWAY_URL")
-        .unwrap_or_else(|_| "http://127.0.0.1:8080".to_string());
-    let tapd_client = Arc::new(TapdClient::new(gateway_url.clone()));
-    
+    let tapd_settings = TapdSettings::from_env()?;
+    let gateway_url = tapd_settings.gateway_url();
+    if tapd_settings.macaroon().is_none() {
+        info!("No tapd macaroon configured, sending unauthenticated requests");
+    }
+    // Short-lived cache for the reads dashboards poll
+    let response_cache = Arc::new(ResponseCache::new(&CacheSettings::from_env()));
+    let http_client_settings = HttpClientSettings::from_env()?;
+    let tapd_grpc = match tapd_settings.transport {
+        TapdTransport::Grpc => Some(TapdGrpcClient::connect(
+            &tapd_settings.grpc_url,
+            &http_client_settings,
+            tapd_settings.macaroon(),
+        )?),
+        TapdTransport::Rest => None,
+    };
+    let tapd_client = TapdClient::new(gateway_url.clone())
+        .with_transport(&http_client_settings)?
+        .with_macaroon(tapd_settings.macaroon())
+        .with_cache(response_cache.clone())
+        .with_grpc(tapd_grpc);
+
     info!("Connecting to Taproot Assets gateway");
 
-    // Initialize HTTP client and configuration
-    let http_client = Arc::new(reqwest::Client::new());
+    // Handlers reach tapd over the same transport as the client
+    let http_client = Arc::new(tapd_client.http_client().clone());
+    let tapd_client = Arc::new(tapd_client);
     let base_url = BaseUrl(gateway_url.clone());
-    let macaroon_hex = MacaroonHex(
-        std::env::var("TAPROOT_MACAROON_HEX")
-            .unwrap_or_else(|_| "".to_string())
+    let macaroon_hex = MacaroonHex(tapd_settings.macaroon().unwrap_or_default().to_string());
+
+    // Postgres or SQLite by DATABASE_URL scheme; stores stay in memory when it is unset
+    let storage = storage::database::connect_storage(&DatabasePoolSettings::from_env()).await?;
+
+    // Initialize mailbox challenge store
+    let challenge_store =
+        create_challenge_store(&ChallengeStoreSettings::from_env()?, storage.as_deref()).await?;
+
+    let mailbox_settings = MailboxSettings::from_env();
+
+    // Initialize mailbox metrics
+    let metrics = Arc::new(PromMonitoring::new()?);
+
+    // Initialize shared upstream event subscriptions
+    let event_broker = Arc::new(
+        EventBroker::new(
+            gateway_url.clone(),
+            macaroon_hex.0.clone(),
+            metrics.event_metrics(),
+            &http_client_settings,
+        )?
+        .with_grpc(tapd_client.grpc_client().cloned()),
+    );
+
+    // Record asset events for history replay
+    let event_store =
+        create_event_store(&EventStoreSettings::from_env()?, storage.as_deref()).await?;
+    spawn_event_recorder(event_broker.clone(), event_store.clone());
+
+    // Keep transaction history in step with asset send/receive events
+    let transaction_store: Arc<dyn storage::transactions::TransactionRepo> = match &storage {
+        Some(storage) => storage.transactions(),
+        None => Arc::new(InMemoryTransactionRepo::new()),
+    };
+    spawn_transaction_updater(event_broker.clone(), transaction_store.clone());
+
+    // Catch up on receives and send confirmations the event stream missed
+    let reconciler = Arc::new(
+        Reconciler::new(
+            http_client.clone(),
+            gateway_url.clone(),
+            macaroon_hex.0.clone(),
+            transaction_store.clone(),
+        )
+        .with_grpc(tapd_client.grpc_client().cloned()),
+    );
+    match ReconcileSettings::from_env().interval() {
+        Some(interval) => reconciler.spawn(interval),
+        None => info!("RECONCILE_INTERVAL_SECS is 0, background reconciliation disabled"),
+    }
+
+    // Invoices created through the gateway
+    let invoice_repo: Arc<dyn storage::invoices::InvoiceRepo> = match &storage {
+        Some(storage) => storage.invoices(),
+        None => Arc::new(InMemoryInvoiceRepo::new()),
+    };
+
+    // Push confirmed incoming transfers to registered devices
+    let device_store: Arc<dyn storage::devices::DeviceStore> = match &storage {
+        Some(storage) => storage.devices(),
+        None => Arc::new(InMemoryDeviceStore::new()),
+    };
+    spawn_push_notifier(
+        event_broker.clone(),
+        device_store.clone(),
+        create_push_provider(&PushSettings::from_env())?,
     );
 
+    // Persist outgoing payment history
+    let payment_store =
+        create_payment_store(&PaymentStoreSettings::from_env()?, storage.as_deref()).await?;
+
+    // Persist RFQ offers and orders with their upstream results
+    let rfq_order_store =
+        create_rfq_order_store(&RfqOrderStoreSettings::from_env()?, storage.as_deref()).await?;
+
+    // Remember send, mint and burn results so retried requests are replayed
+    let idempotency_settings = IdempotencyStoreSettings::from_env()?;
+    let idempotency_store =
+        create_idempotency_store(&idempotency_settings, storage.as_deref()).await?;
+
+    // Favorite and hidden assets, applied to asset listings
+    let asset_preference_settings = AssetPreferenceStoreSettings::from_env()?;
+    let asset_preferences =
+        create_asset_preference_store(&asset_preference_settings, storage.as_deref()).await?;
+
+    // App and user preferences kept server-side
+    let settings =
+        create_settings_store(&SettingsStoreSettings::from_env()?, storage.as_deref()).await?;
+
+    // Append-only record of sends, mints, burns, channel changes and admin actions
+    let audit_log = create_audit_log(&AuditLogSettings::from_env()?, storage.as_deref()).await?;
+
+    // Sample per-asset balances into hourly and daily history
+    let balance_snapshot_settings = BalanceSnapshotSettings::from_env()?;
+    let balance_snapshots =
+        create_balance_snapshot_store(&balance_snapshot_settings, storage.as_deref()).await?;
+    Arc::new(BalanceRecorder::new(tapd_client.clone(), balance_snapshots.clone()))
+        .spawn(balance_snapshot_settings.interval());
+
+    // Deliver transaction and balance events to integrator webhooks
+    let webhook_settings = WebhookSettings::from_env()?;
+    let webhooks = Arc::new(WebhookWorker::new(
+        create_webhook_store(&webhook_settings, storage.as_deref()).await?,
+        reqwest::Client::new(),
+        &webhook_settings,
+    ));
+    webhooks.spawn(&event_broker, webhook_settings.poll_interval());
+
+    // Follow the chain through LND when configured
+    let lnd_settings = LndSettings::from_env()?;
+    let lnd_client = LndClient::new(&lnd_settings, &http_client_settings)?.map(Arc::new);
+    let chain_watcher = lnd_client
+        .clone()
+        .map(|lnd| Arc::new(ChainWatcher::new(lnd)));
+    match &chain_watcher {
+        Some(watcher) => watcher.spawn(&event_broker),
+        None => info!("LND_REST_URL not set, block events disabled"),
+    }
+
+    // Fee estimates from LND, with an optional mempool.space fallback
+    let fee_estimator = Arc::new(FeeEstimator::new(
+        lnd_client.clone(),
+        reqwest::Client::new(),
+        &FeeSettings::from_env(),
+    ));
+
+    // Asset prices for RFQ rates, from tapd's oracle or an operator feed
+    let price_oracle_settings = PriceOracleSettings::from_env()?;
+    let price_oracle = create_price_oracle(
+        &price_oracle_settings,
+        http_client.clone(),
+        gateway_url.clone(),
+        macaroon_hex.0.clone(),
+    )?;
+
+    // Fire registered price alerts as oracle rates move
+    let price_alert_settings = PriceAlertSettings::from_env()?;
+    let price_alerts = Arc::new(PriceAlertMonitor::new(
+        create_price_alert_store(&price_alert_settings, storage.as_deref()).await?,
+        price_oracle.clone(),
+        reqwest::Client::new(),
+    ));
+    price_alerts.spawn(price_alert_settings.poll_interval());
+
+    // Simulated RFQ quotes for frontend development without asset channels
+    let rfq_settings = RfqSettings::from_env();
+    let rfq_simulator = if rfq_settings.simulate {
+        info!("RFQ simulation enabled at {} units per BTC", rfq_settings.simulated_rate);
+        Some(Arc::new(RfqSimulator::new(&rfq_settings.simulated_rate)?))
+    } else {
+        None
+    };
+
+    // Collectible images uploaded for minting
+    let image_store_settings = ImageStoreSettings::from_env()?;
+    let image_store =
+        create_image_store(&image_store_settings, reqwest::Client::new()).await?;
+
+    // Operator cap on the size of a single burn
+    let burn_settings = BurnSettings::from_env();
+
+    // Registered mailbox receivers and their delivered messages
+    let mailbox_receivers: Arc<dyn storage::receivers::ReceiverRepo> = match &storage {
+        Some(storage) => storage.receivers(),
+        None => Arc::new(InMemoryReceiverRepo::new()),
+    };
+
+    // Prune asset events past their retention
+    let retention_settings = RetentionSettings::from_env();
+    let pruner = Arc::new(Pruner::new(
+        retention_settings.clone(),
+        event_store.clone(),
+        metrics.retention_metrics(),
+    ));
+    match retention_settings.interval() {
+        Some(interval) => pruner.spawn(interval),
+        None => info!("RETENTION_INTERVAL_SECS is 0, data retention disabled"),
+    }
+
     // Create application state
     let app_state = AppState {
         tapd_client,
         http_client,
+        storage,
         base_url,
         macaroon_hex,
+        mailbox_receivers: Some(mailbox_receivers),
+        mailbox_monitoring: Some(metrics.clone()),
+        challenge_store,
+        mailbox_limiter: Arc::new(MailboxLimiter::new(mailbox_settings.clone())),
+        mailbox_registry: Arc::new(MailboxRegistry::new()),
+        metrics,
+        mailbox_webhooks: Arc::new(WebhookDispatcher::new(
+            reqwest::Client::new(),
+            &mailbox_settings,
+        )),
+        event_broker,
+        event_store,
+        lnd_client,
+        chain_watcher,
+        transaction_store,
+        reconciler,
+        invoice_repo,
+        device_store,
+        payment_store,
+        rfq_order_store,
+        fee_estimator,
+        rfq_settings,
+        rfq_simulator,
+        price_oracle,
+        price_oracle_max_age_secs: price_oracle_settings.max_age_secs,
+        fiat_reference: price_oracle_settings.fiat_reference(),
+        price_alerts,
+        image_store,
+        image_max_bytes: image_store_settings.max_bytes.min(MAX_ASSET_META_BYTES),
+        burn_settings,
+        response_cache,
+        idempotency_store,
+        idempotency_ttl_secs: idempotency_settings.ttl_secs,
+        asset_preferences,
+        settings,
+        balance_snapshots,
+        webhooks,
+        audit_log,
     };
 
     // Build application
     let app = Router::new()
         .nest("/api", routes::create_routes())
         .merge(taproot_backend::gateway::routes::create_taproot_routes())
+        .layer(middleware::from_fn_with_state(app_state.clone(), record_audit_entry))
         .layer(CorsLayer::permissive())
         .with_state(app_state);
 
@@ -56,7 +344,11 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting server on {}", addr);
     
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
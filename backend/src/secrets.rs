@@ -0,0 +1,268 @@
+//! At-rest encryption for secrets kept in the database: webhook signing
+//! secrets and mobile push tokens. tapd and LND macaroons are not stored by
+//! the gateway, but may be configured sealed (see the `seal-macaroon` binary)
+//! and are opened with the same keys at startup.
+//!
+//! Values are sealed with AES-256-GCM under a key derived with HKDF-SHA256
+//! from `SECRETS_MASTER_KEY` and stored as `enc:v1:<key id>:<base64>`, where
+//! the base64 holds `nonce (12) || ciphertext || tag (16)`. Rotating the
+//! master key moves the old one to `SECRETS_PREVIOUS_MASTER_KEYS`; values
+//! sealed under it stay readable and are resealed under the new key.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use hkdf::Hkdf;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::config::SecretsSettings;
+use crate::crypto::hmac_sha256_hex;
+use crate::error::AppError;
+
+const SEALED_PREFIX: &str = "enc:v1:";
+const LOOKUP_PREFIX: &str = "hmac:v1:";
+const HKDF_SALT: &[u8] = b"taproot-backend secrets";
+const NONCE_LEN: usize = 12;
+pub const MIN_MASTER_KEY_LEN: usize = 32;
+
+/// Keys derived from one master key
+struct SecretKey {
+    /// Short fingerprint naming the key that sealed a value
+    id: String,
+    cipher: Aes256Gcm,
+    lookup_key: [u8; 32],
+}
+
+impl SecretKey {
+    fn derive(master_key: &str) -> Result<Self, AppError> {
+        if master_key.len() < MIN_MASTER_KEY_LEN {
+            return Err(AppError::ValidationError(format!(
+                "Secrets master keys must be at least {MIN_MASTER_KEY_LEN} characters"
+            )));
+        }
+
+        let hkdf = Hkdf::<Sha256>::new(Some(HKDF_SALT), master_key.as_bytes());
+        let expand = |info: &[u8]| {
+            let mut okm = [0u8; 32];
+            hkdf.expand(info, &mut okm)
+                .expect("32 bytes is a valid HKDF-SHA256 output length");
+            okm
+        };
+
+        Ok(Self {
+            id: hex::encode(&expand(b"key id")[..4]),
+            cipher: Aes256Gcm::new(&expand(b"encryption").into()),
+            lookup_key: expand(b"lookup"),
+        })
+    }
+
+    fn lookup(&self, value: &str) -> String {
+        let mac = hmac_sha256_hex(&self.lookup_key, value.as_bytes());
+        format!("{LOOKUP_PREFIX}{}:{mac}", self.id)
+    }
+}
+
+/// Seals and opens stored secrets. Without a master key values are stored
+/// as given, and values read back without the sealed prefix are taken as
+/// plaintext written before encryption was enabled.
+pub struct SecretBox {
+    /// The current key first, then earlier keys still accepted for reading
+    keys: Vec<SecretKey>,
+}
+
+impl SecretBox {
+    pub fn new(master_key: &str, previous_master_keys: &[String]) -> Result<Self, AppError> {
+        let keys = std::iter::once(master_key)
+            .chain(previous_master_keys.iter().map(String::as_str))
+            .map(SecretKey::derive)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { keys })
+    }
+
+    /// Stores secrets unencrypted
+    pub fn plaintext() -> Self {
+        Self { keys: Vec::new() }
+    }
+
+    pub fn from_settings(settings: &SecretsSettings) -> Result<Self, AppError> {
+        match &settings.master_key {
+            Some(master_key) => Self::new(master_key, &settings.previous_master_keys),
+            None => {
+                if !settings.previous_master_keys.is_empty() {
+                    return Err(AppError::ValidationError(
+                        "SECRETS_PREVIOUS_MASTER_KEYS requires SECRETS_MASTER_KEY".to_string(),
+                    ));
+                }
+                warn!("SECRETS_MASTER_KEY not set, storing webhook secrets and push tokens as is");
+                Ok(Self::plaintext())
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Whether `stored` came from [`SecretBox::seal`] with a key set
+    pub fn is_sealed(stored: &str) -> bool {
+        stored.starts_with(SEALED_PREFIX)
+    }
+
+    /// Encrypts `plaintext` under the current key
+    pub fn seal(&self, plaintext: &str) -> Result<String, AppError> {
+        let Some(key) = self.keys.first() else {
+            return Ok(plaintext.to_string());
+        };
+
+        let nonce: [u8; NONCE_LEN] = secp256k1::rand::random();
+        let ciphertext = key
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+            .map_err(|_| AppError::StorageError("Failed to seal secret".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(sealed);
+        Ok(format!("{SEALED_PREFIX}{}:{encoded}", key.id))
+    }
+
+    /// Decrypts a value from [`SecretBox::seal`] with whichever configured
+    /// key sealed it; unsealed values are returned as they are
+    pub fn open(&self, stored: &str) -> Result<String, AppError> {
+        let Some(sealed) = stored.strip_prefix(SEALED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let (key_id, encoded) = sealed
+            .split_once(':')
+            .ok_or_else(|| AppError::StorageError("Malformed sealed secret".to_string()))?;
+        let key = self.keys.iter().find(|key| key.id == key_id).ok_or_else(|| {
+            AppError::StorageError(format!(
+                "Secret sealed with unknown key {key_id}; is its master key configured?"
+            ))
+        })?;
+
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| AppError::StorageError("Malformed sealed secret".to_string()))?;
+        if data.len() < NONCE_LEN {
+            return Err(AppError::StorageError("Malformed sealed secret".to_string()));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                AppError::StorageError(format!("Sealed secret failed to open with key {key_id}"))
+            })?;
+        String::from_utf8(plaintext)
+            .map_err(|_| AppError::StorageError("Sealed secret is not UTF-8".to_string()))
+    }
+
+    pub fn seal_optional(&self, plaintext: Option<&str>) -> Result<Option<String>, AppError> {
+        plaintext.map(|value| self.seal(value)).transpose()
+    }
+
+    pub fn open_optional(&self, stored: Option<&str>) -> Result<Option<String>, AppError> {
+        stored.map(|value| self.open(value)).transpose()
+    }
+
+    /// Whether a stored value should be rewritten: it is plaintext while
+    /// encryption is enabled, or was sealed under a previous key
+    pub fn needs_reseal(&self, stored: &str) -> bool {
+        match self.keys.first() {
+            Some(key) => !stored.starts_with(&format!("{SEALED_PREFIX}{}:", key.id)),
+            None => false,
+        }
+    }
+
+    /// Seals the plaintext of `stored` under the current key
+    pub fn reseal(&self, stored: &str) -> Result<String, AppError> {
+        self.seal(&self.open(stored)?)
+    }
+
+    /// Deterministic stand-in for a secret that has to be looked up by
+    /// value, such as a push token keyed by itself; the value itself when
+    /// encryption is disabled
+    pub fn lookup_key(&self, value: &str) -> String {
+        match self.keys.first() {
+            Some(key) => key.lookup(value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Every stored form `value` may have been keyed by: under the current
+    /// key, each previous key, and as plaintext
+    pub fn lookup_candidates(&self, value: &str) -> Vec<String> {
+        self.keys
+            .iter()
+            .map(|key| key.lookup(value))
+            .chain(std::iter::once(value.to_string()))
+            .collect()
+    }
+}
+
+impl Default for SecretBox {
+    fn default() -> Self {
+        Self::plaintext()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = "current-master-key-with-at-least-32-chars";
+    const PREVIOUS: &str = "previous-master-key-with-at-least-32-chars";
+
+    #[test]
+    fn test_seal_round_trip() {
+        let secrets = SecretBox::new(CURRENT, &[]).unwrap();
+        let sealed = secrets.seal("whsec_123").unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert!(!sealed.contains("whsec_123"));
+        assert_ne!(sealed, secrets.seal("whsec_123").unwrap());
+        assert_eq!(secrets.open(&sealed).unwrap(), "whsec_123");
+        assert!(!secrets.needs_reseal(&sealed));
+    }
+
+    #[test]
+    fn test_legacy_plaintext_and_disabled_encryption() {
+        let secrets = SecretBox::new(CURRENT, &[]).unwrap();
+        assert_eq!(secrets.open("whsec_123").unwrap(), "whsec_123");
+        assert!(secrets.needs_reseal("whsec_123"));
+
+        let plaintext = SecretBox::plaintext();
+        assert_eq!(plaintext.seal("whsec_123").unwrap(), "whsec_123");
+        assert_eq!(plaintext.lookup_key("token"), "token");
+        assert!(!plaintext.needs_reseal("whsec_123"));
+        assert!(plaintext.open(&secrets.seal("whsec_123").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_values_readable() {
+        let old = SecretBox::new(PREVIOUS, &[]).unwrap();
+        let sealed = old.seal("whsec_123").unwrap();
+
+        let rotated = SecretBox::new(CURRENT, &[PREVIOUS.to_string()]).unwrap();
+        assert_eq!(rotated.open(&sealed).unwrap(), "whsec_123");
+        assert!(rotated.needs_reseal(&sealed));
+        assert!(SecretBox::new(CURRENT, &[]).unwrap().open(&sealed).is_err());
+
+        let candidates = rotated.lookup_candidates("token");
+        assert_eq!(candidates[0], rotated.lookup_key("token"));
+        assert_eq!(candidates[1], old.lookup_key("token"));
+        assert_eq!(candidates[2], "token");
+    }
+
+    #[test]
+    fn test_rejects_short_keys_and_tampering() {
+        assert!(SecretBox::new("short", &[]).is_err());
+
+        let secrets = SecretBox::new(CURRENT, &[]).unwrap();
+        let mut sealed = secrets.seal("whsec_123").unwrap();
+        let last = sealed.pop().unwrap();
+        sealed.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(secrets.open(&sealed).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{AssetPreferenceStoreBackend, AssetPreferenceStoreSettings};
+use crate::error::AppError;
+
+/// How one profile wants an asset shown in asset listings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetPreference {
+    /// Hex asset ID
+    pub asset_id: String,
+    pub favorite: bool,
+    /// Left out of asset listings unless `include_hidden=true`
+    pub hidden: bool,
+    pub updated_at: i64,
+}
+
+/// Favorite and hidden flags, kept per profile
+#[async_trait::async_trait]
+pub trait AssetPreferenceStore: Send + Sync {
+    /// The profile's preferences, by asset ID
+    async fn list(&self, profile: &str) -> Result<Vec<AssetPreference>, AppError>;
+    async fn get(
+        &self,
+        profile: &str,
+        asset_id: &str,
+    ) -> Result<Option<AssetPreference>, AppError>;
+    /// Inserts or replaces the preference for its asset
+    async fn save(&self, profile: &str, preference: &AssetPreference) -> Result<(), AppError>;
+}
+
+/// Process-local asset preferences
+#[derive(Default)]
+pub struct InMemoryAssetPreferenceStore {
+    preferences: RwLock<HashMap<(String, String), AssetPreference>>,
+}
+
+impl InMemoryAssetPreferenceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl AssetPreferenceStore for InMemoryAssetPreferenceStore {
+    async fn list(&self, profile: &str) -> Result<Vec<AssetPreference>, AppError> {
+        let mut preferences: Vec<AssetPreference> = self
+            .preferences
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|((owner, _), _)| owner == profile)
+            .map(|(_, preference)| preference.clone())
+            .collect();
+        preferences.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+        Ok(preferences)
+    }
+
+    async fn get(
+        &self,
+        profile: &str,
+        asset_id: &str,
+    ) -> Result<Option<AssetPreference>, AppError> {
+        let key = (profile.to_string(), asset_id.to_string());
+        Ok(self.preferences.read().unwrap().get(&key).cloned())
+    }
+
+    async fn save(&self, profile: &str, preference: &AssetPreference) -> Result<(), AppError> {
+        let key = (profile.to_string(), preference.asset_id.clone());
+        self.preferences.write().unwrap().insert(key, preference.clone());
+        Ok(())
+    }
+}
+
+/// Postgres-backed asset preferences using the `asset_preferences` table
+pub struct PostgresAssetPreferenceStore {
+    pool: PgPool,
+}
+
+impl PostgresAssetPreferenceStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+type PreferenceRow = (String, bool, bool, i64);
+
+fn preference_from_row(row: PreferenceRow) -> AssetPreference {
+    let (asset_id, favorite, hidden, updated_at) = row;
+    AssetPreference {
+        asset_id,
+        favorite,
+        hidden,
+        updated_at,
+    }
+}
+
+#[async_trait::async_trait]
+impl AssetPreferenceStore for PostgresAssetPreferenceStore {
+    async fn list(&self, profile: &str) -> Result<Vec<AssetPreference>, AppError> {
+        let rows = sqlx::query_as::<_, PreferenceRow>(
+            "SELECT asset_id, favorite, hidden, updated_at FROM asset_preferences
+             WHERE profile = $1 ORDER BY asset_id",
+        )
+        .bind(profile)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(preference_from_row).collect())
+    }
+
+    async fn get(
+        &self,
+        profile: &str,
+        asset_id: &str,
+    ) -> Result<Option<AssetPreference>, AppError> {
+        let row = sqlx::query_as::<_, PreferenceRow>(
+            "SELECT asset_id, favorite, hidden, updated_at FROM asset_preferences
+             WHERE profile = $1 AND asset_id = $2",
+        )
+        .bind(profile)
+        .bind(asset_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(preference_from_row))
+    }
+
+    async fn save(&self, profile: &str, preference: &AssetPreference) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO asset_preferences (profile, asset_id, favorite, hidden, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (profile, asset_id) DO UPDATE
+                SET favorite = EXCLUDED.favorite,
+                    hidden = EXCLUDED.hidden,
+                    updated_at = EXCLUDED.updated_at",
+        )
+        .bind(profile)
+        .bind(&preference.asset_id)
+        .bind(preference.favorite)
+        .bind(preference.hidden)
+        .bind(preference.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// SQLite-backed asset preferences using the `asset_preferences` table
+pub struct SqliteAssetPreferenceStore {
+    pool: SqlitePool,
+}
+
+impl SqliteAssetPreferenceStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AssetPreferenceStore for SqliteAssetPreferenceStore {
+    async fn list(&self, profile: &str) -> Result<Vec<AssetPreference>, AppError> {
+        let rows = sqlx::query_as::<_, PreferenceRow>(
+            "SELECT asset_id, favorite, hidden, updated_at FROM asset_preferences
+             WHERE profile = $1 ORDER BY asset_id",
+        )
+        .bind(profile)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(preference_from_row).collect())
+    }
+
+    async fn get(
+        &self,
+        profile: &str,
+        asset_id: &str,
+    ) -> Result<Option<AssetPreference>, AppError> {
+        let row = sqlx::query_as::<_, PreferenceRow>(
+            "SELECT asset_id, favorite, hidden, updated_at FROM asset_preferences
+             WHERE profile = $1 AND asset_id = $2",
+        )
+        .bind(profile)
+        .bind(asset_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(preference_from_row))
+    }
+
+    async fn save(&self, profile: &str, preference: &AssetPreference) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO asset_preferences (profile, asset_id, favorite, hidden, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (profile, asset_id) DO UPDATE
+                SET favorite = excluded.favorite,
+                    hidden = excluded.hidden,
+                    updated_at = excluded.updated_at",
+        )
+        .bind(profile)
+        .bind(&preference.asset_id)
+        .bind(preference.favorite)
+        .bind(preference.hidden)
+        .bind(preference.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Builds the asset preference store selected by the configured backend
+pub async fn create_asset_preference_store(
+    settings: &AssetPreferenceStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn AssetPreferenceStore>> {
+    info!("Using {:?} asset preference store", settings.backend);
+
+    let store: Arc<dyn AssetPreferenceStore> = match settings.backend {
+        AssetPreferenceStoreBackend::Memory => Arc::new(InMemoryAssetPreferenceStore::new()),
+        AssetPreferenceStoreBackend::Postgres => {
+            match storage_for(storage, "asset preference") {
+                Some(storage) => storage.asset_preferences(),
+                None => Arc::new(InMemoryAssetPreferenceStore::new()),
+            }
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn assert_preferences_are_kept_per_profile(store: &dyn AssetPreferenceStore) {
+        let mut preference = AssetPreference {
+            asset_id: "ab".repeat(32),
+            favorite: true,
+            hidden: false,
+            updated_at: 1,
+        };
+        store.save("alice", &preference).await.unwrap();
+        preference.hidden = true;
+        store.save("alice", &preference).await.unwrap();
+
+        assert_eq!(store.list("alice").await.unwrap(), vec![preference.clone()]);
+        assert!(store.list("bob").await.unwrap().is_empty());
+        assert_eq!(
+            store.get("alice", &preference.asset_id).await.unwrap(),
+            Some(preference)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preferences_are_kept_per_profile() {
+        assert_preferences_are_kept_per_profile(&InMemoryAssetPreferenceStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_preferences_are_kept_per_profile() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_preferences_are_kept_per_profile(storage.asset_preferences().as_ref()).await;
+    }
+}
@@ -0,0 +1,433 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+use super::database::{storage_for, Storage};
+use crate::config::{AuditLogBackend, AuditLogSettings};
+use crate::error::AppError;
+
+/// Kinds of sensitive operation the audit log records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    #[serde(rename = "asset.send")]
+    AssetSend,
+    #[serde(rename = "asset.mint")]
+    AssetMint,
+    #[serde(rename = "asset.burn")]
+    AssetBurn,
+    #[serde(rename = "channel.fund")]
+    ChannelFund,
+    #[serde(rename = "channel.close")]
+    ChannelClose,
+    /// A Lightning payment, in assets or BTC
+    #[serde(rename = "payment.send")]
+    PaymentSend,
+    /// A mutating request to an operator endpoint
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::AssetSend => "asset.send",
+            AuditAction::AssetMint => "asset.mint",
+            AuditAction::AssetBurn => "asset.burn",
+            AuditAction::ChannelFund => "channel.fund",
+            AuditAction::ChannelClose => "channel.close",
+            AuditAction::PaymentSend => "payment.send",
+            AuditAction::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "asset.send" => Ok(AuditAction::AssetSend),
+            "asset.mint" => Ok(AuditAction::AssetMint),
+            "asset.burn" => Ok(AuditAction::AssetBurn),
+            "channel.fund" => Ok(AuditAction::ChannelFund),
+            "channel.close" => Ok(AuditAction::ChannelClose),
+            "payment.send" => Ok(AuditAction::PaymentSend),
+            "admin" => Ok(AuditAction::Admin),
+            other => Err(AppError::InvalidInput(format!("Unknown audit action: {other}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditResult {
+    /// Answered with a 2xx status
+    Success,
+    Failure,
+}
+
+impl AuditResult {
+    pub fn from_status(status: u16) -> Self {
+        if (200..300).contains(&status) {
+            AuditResult::Success
+        } else {
+            AuditResult::Failure
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditResult::Success => "success",
+            AuditResult::Failure => "failure",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "success" => Ok(AuditResult::Success),
+            "failure" => Ok(AuditResult::Failure),
+            other => Err(AppError::StorageError(format!("Unknown audit result: {other}"))),
+        }
+    }
+}
+
+/// One sensitive request and how it was answered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub occurred_at: i64,
+    /// `admin` when the request carried the admin token, otherwise `anonymous`
+    pub actor: String,
+    /// Peer address of the connection
+    pub ip: Option<String>,
+    /// `X-Forwarded-For` as sent by the client or proxy; not verified
+    pub forwarded_for: Option<String>,
+    pub action: AuditAction,
+    pub method: String,
+    pub path: String,
+    /// The JSON request body with secrets redacted and long values shortened
+    pub summary: serde_json::Value,
+    pub status: u16,
+    pub result: AuditResult,
+}
+
+/// Filters for reading the audit log
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub result: Option<AuditResult>,
+    /// Inclusive bounds on `occurred_at`, in unix seconds
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: usize,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor.as_ref().is_none_or(|actor| *actor == entry.actor)
+            && self.action.is_none_or(|action| action == entry.action)
+            && self.result.is_none_or(|result| result == entry.result)
+            && self.from.is_none_or(|from| entry.occurred_at >= from)
+            && self.to.is_none_or(|to| entry.occurred_at <= to)
+    }
+}
+
+/// Append-only record of sends, mints, burns, channel changes and admin actions
+#[async_trait::async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), AppError>;
+    /// Matching entries, newest first
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, AppError>;
+}
+
+/// Process-local audit log
+#[derive(Default)]
+pub struct InMemoryAuditLog {
+    entries: RwLock<Vec<AuditEntry>>,
+}
+
+impl InMemoryAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLog for InMemoryAuditLog {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), AppError> {
+        self.entries.write().unwrap().push(entry.clone());
+        Ok(())
+    }
+
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, AppError> {
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|entry| query.matches(entry))
+            .take(query.limit)
+            .cloned()
+            .collect())
+    }
+}
+
+type AuditRow = (
+    Uuid,
+    i64,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    String,
+    Json<serde_json::Value>,
+    i32,
+    String,
+);
+
+fn entry_from_row(row: AuditRow) -> Result<AuditEntry, AppError> {
+    let (
+        id,
+        occurred_at,
+        actor,
+        ip,
+        forwarded_for,
+        action,
+        method,
+        path,
+        Json(summary),
+        status,
+        result,
+    ) = row;
+    Ok(AuditEntry {
+        id,
+        occurred_at,
+        actor,
+        ip,
+        forwarded_for,
+        action: AuditAction::parse(&action)
+            .map_err(|e| AppError::StorageError(e.to_string()))?,
+        method,
+        path,
+        summary,
+        status: status as u16,
+        result: AuditResult::parse(&result)?,
+    })
+}
+
+const AUDIT_INSERT: &str = "INSERT INTO audit_log
+        (id, occurred_at, actor, ip, forwarded_for, action, method, path, summary, status, result)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)";
+
+const AUDIT_COLUMNS: &str =
+    "id, occurred_at, actor, ip, forwarded_for, action, method, path, summary, status, result";
+
+/// Postgres-backed audit log using the `audit_log` table, which rejects
+/// updates and deletes
+pub struct PostgresAuditLog {
+    pool: PgPool,
+}
+
+impl PostgresAuditLog {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLog for PostgresAuditLog {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), AppError> {
+        sqlx::query(AUDIT_INSERT)
+            .bind(entry.id)
+            .bind(entry.occurred_at)
+            .bind(&entry.actor)
+            .bind(&entry.ip)
+            .bind(&entry.forwarded_for)
+            .bind(entry.action.as_str())
+            .bind(&entry.method)
+            .bind(&entry.path)
+            .bind(Json(&entry.summary))
+            .bind(entry.status as i32)
+            .bind(entry.result.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, AppError> {
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {AUDIT_COLUMNS} FROM audit_log
+             WHERE ($1::TEXT IS NULL OR actor = $1)
+               AND ($2::TEXT IS NULL OR action = $2)
+               AND ($3::TEXT IS NULL OR result = $3)
+               AND ($4::BIGINT IS NULL OR occurred_at >= $4)
+               AND ($5::BIGINT IS NULL OR occurred_at <= $5)
+             ORDER BY occurred_at DESC, id DESC LIMIT $6"
+        ))
+        .bind(&query.actor)
+        .bind(query.action.map(|action| action.as_str()))
+        .bind(query.result.map(|result| result.as_str()))
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(entry_from_row).collect()
+    }
+}
+
+/// SQLite-backed audit log using the `audit_log` table, which rejects
+/// updates and deletes
+pub struct SqliteAuditLog {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditLog {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLog for SqliteAuditLog {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), AppError> {
+        sqlx::query(AUDIT_INSERT)
+            .bind(entry.id)
+            .bind(entry.occurred_at)
+            .bind(&entry.actor)
+            .bind(&entry.ip)
+            .bind(&entry.forwarded_for)
+            .bind(entry.action.as_str())
+            .bind(&entry.method)
+            .bind(&entry.path)
+            .bind(Json(&entry.summary))
+            .bind(entry.status as i32)
+            .bind(entry.result.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, AppError> {
+        // Rows are only ever appended, so insertion order breaks timestamp ties
+        let rows = sqlx::query_as::<_, AuditRow>(&format!(
+            "SELECT {AUDIT_COLUMNS} FROM audit_log
+             WHERE ($1 IS NULL OR actor = $1)
+               AND ($2 IS NULL OR action = $2)
+               AND ($3 IS NULL OR result = $3)
+               AND ($4 IS NULL OR occurred_at >= $4)
+               AND ($5 IS NULL OR occurred_at <= $5)
+             ORDER BY occurred_at DESC, rowid DESC LIMIT $6"
+        ))
+        .bind(&query.actor)
+        .bind(query.action.map(|action| action.as_str()))
+        .bind(query.result.map(|result| result.as_str()))
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(entry_from_row).collect()
+    }
+}
+
+/// Builds the audit log selected by the configured backend
+pub async fn create_audit_log(
+    settings: &AuditLogSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn AuditLog>> {
+    info!("Using {:?} audit log", settings.backend);
+
+    let log: Arc<dyn AuditLog> = match settings.backend {
+        AuditLogBackend::Memory => Arc::new(InMemoryAuditLog::new()),
+        AuditLogBackend::Postgres => match storage_for(storage, "audit log") {
+            Some(storage) => storage.audit_log(),
+            None => Arc::new(InMemoryAuditLog::new()),
+        },
+    };
+
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: AuditAction, occurred_at: i64, status: u16) -> AuditEntry {
+        AuditEntry {
+            id: Uuid::new_v4(),
+            occurred_at,
+            actor: "anonymous".to_string(),
+            ip: Some("127.0.0.1".to_string()),
+            forwarded_for: None,
+            action,
+            method: "POST".to_string(),
+            path: "/api/assets/send".to_string(),
+            summary: serde_json::json!({"amount": 10}),
+            status,
+            result: AuditResult::from_status(status),
+        }
+    }
+
+    fn query() -> AuditQuery {
+        AuditQuery {
+            actor: None,
+            action: None,
+            result: None,
+            from: None,
+            to: None,
+            limit: 10,
+        }
+    }
+
+    async fn assert_filters_newest_first(log: &dyn AuditLog) {
+        let send = entry(AuditAction::AssetSend, 10, 200);
+        let failed_burn = entry(AuditAction::AssetBurn, 20, 502);
+        let later_send = entry(AuditAction::AssetSend, 30, 201);
+        for entry in [&send, &failed_burn, &later_send] {
+            log.append(entry).await.unwrap();
+        }
+
+        let all = log.list(&query()).await.unwrap();
+        assert_eq!(all, vec![later_send.clone(), failed_burn.clone(), send.clone()]);
+
+        let sends = AuditQuery { action: Some(AuditAction::AssetSend), limit: 1, ..query() };
+        assert_eq!(log.list(&sends).await.unwrap(), vec![later_send]);
+        let failures = AuditQuery { result: Some(AuditResult::Failure), ..query() };
+        assert_eq!(log.list(&failures).await.unwrap(), vec![failed_burn]);
+        let window = AuditQuery { from: Some(5), to: Some(15), ..query() };
+        assert_eq!(log.list(&window).await.unwrap(), vec![send]);
+        let admin = AuditQuery { actor: Some("admin".to_string()), ..query() };
+        assert!(log.list(&admin).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filters_newest_first() {
+        assert_filters_newest_first(&InMemoryAuditLog::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_filters_newest_first() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_filters_newest_first(storage.audit_log().as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_rejects_updates_and_deletes() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await.unwrap();
+        SqliteAuditLog::new(pool.clone())
+            .append(&entry(AuditAction::Admin, 10, 200))
+            .await
+            .unwrap();
+
+        assert!(sqlx::query("UPDATE audit_log SET status = 500").execute(&pool).await.is_err());
+        assert!(sqlx::query("DELETE FROM audit_log").execute(&pool).await.is_err());
+    }
+}
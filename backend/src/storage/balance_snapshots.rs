@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{BalanceSnapshotSettings, BalanceSnapshotStoreBackend};
+use crate::error::AppError;
+
+/// Width of the buckets balances are sampled into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotGranularity {
+    #[default]
+    Hourly,
+    Daily,
+}
+
+impl SnapshotGranularity {
+    pub const ALL: [SnapshotGranularity; 2] =
+        [SnapshotGranularity::Hourly, SnapshotGranularity::Daily];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotGranularity::Hourly => "hourly",
+            SnapshotGranularity::Daily => "daily",
+        }
+    }
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            SnapshotGranularity::Hourly => 3_600,
+            SnapshotGranularity::Daily => 86_400,
+        }
+    }
+
+    /// Start of the UTC hour or day containing `timestamp`
+    pub fn bucket_start(&self, timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(self.seconds())
+    }
+}
+
+/// One asset's balance at the end of an hour or day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    /// Hex asset ID
+    pub asset_id: String,
+    pub granularity: SnapshotGranularity,
+    /// Unix seconds at the start of the bucket
+    pub bucket_start: i64,
+    /// Base units at the latest sample in the bucket
+    pub balance: u64,
+    pub recorded_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceHistoryQuery {
+    pub asset_id: String,
+    pub granularity: SnapshotGranularity,
+    /// Inclusive bounds on `bucket_start`, in unix seconds
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Most recent buckets kept when more match
+    pub limit: usize,
+}
+
+/// Sampled balances, one per asset and bucket
+#[async_trait::async_trait]
+pub trait BalanceSnapshotStore: Send + Sync {
+    /// Inserts each snapshot or replaces the one already in its bucket
+    async fn record(&self, snapshots: &[BalanceSnapshot]) -> Result<(), AppError>;
+    /// Matching snapshots, oldest first
+    async fn history(&self, query: &BalanceHistoryQuery) -> Result<Vec<BalanceSnapshot>, AppError>;
+}
+
+type SnapshotKey = (String, SnapshotGranularity, i64);
+
+/// Process-local balance history
+#[derive(Default)]
+pub struct InMemoryBalanceSnapshotStore {
+    snapshots: RwLock<BTreeMap<SnapshotKey, BalanceSnapshot>>,
+}
+
+impl InMemoryBalanceSnapshotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl BalanceSnapshotStore for InMemoryBalanceSnapshotStore {
+    async fn record(&self, snapshots: &[BalanceSnapshot]) -> Result<(), AppError> {
+        let mut stored = self.snapshots.write().unwrap();
+        for snapshot in snapshots {
+            let key = (snapshot.asset_id.clone(), snapshot.granularity, snapshot.bucket_start);
+            stored.insert(key, snapshot.clone());
+        }
+        Ok(())
+    }
+
+    async fn history(&self, query: &BalanceHistoryQuery) -> Result<Vec<BalanceSnapshot>, AppError> {
+        let start = (
+            query.asset_id.clone(),
+            query.granularity,
+            query.from.unwrap_or(i64::MIN),
+        );
+        let end = (query.asset_id.clone(), query.granularity, query.to.unwrap_or(i64::MAX));
+        let stored = self.snapshots.read().unwrap();
+        let mut snapshots: Vec<BalanceSnapshot> = stored
+            .range(start..=end)
+            .rev()
+            .take(query.limit)
+            .map(|(_, snapshot)| snapshot.clone())
+            .collect();
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+}
+
+type SnapshotRow = (i64, i64, i64);
+
+fn snapshots_from_rows(
+    query: &BalanceHistoryQuery,
+    rows: Vec<SnapshotRow>,
+) -> Vec<BalanceSnapshot> {
+    // Rows come newest first so the limit keeps the most recent buckets
+    rows.into_iter()
+        .rev()
+        .map(|(bucket_start, balance, recorded_at)| BalanceSnapshot {
+            asset_id: query.asset_id.clone(),
+            granularity: query.granularity,
+            bucket_start,
+            balance: balance.max(0) as u64,
+            recorded_at,
+        })
+        .collect()
+}
+
+/// Postgres-backed balance history using the `balance_snapshots` table
+pub struct PostgresBalanceSnapshotStore {
+    pool: PgPool,
+}
+
+impl PostgresBalanceSnapshotStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl BalanceSnapshotStore for PostgresBalanceSnapshotStore {
+    async fn record(&self, snapshots: &[BalanceSnapshot]) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        for snapshot in snapshots {
+            sqlx::query(
+                "INSERT INTO balance_snapshots
+                    (asset_id, granularity, bucket_start, balance, recorded_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (asset_id, granularity, bucket_start) DO UPDATE
+                    SET balance = EXCLUDED.balance,
+                        recorded_at = EXCLUDED.recorded_at",
+            )
+            .bind(&snapshot.asset_id)
+            .bind(snapshot.granularity.as_str())
+            .bind(snapshot.bucket_start)
+            .bind(snapshot.balance as i64)
+            .bind(snapshot.recorded_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn history(&self, query: &BalanceHistoryQuery) -> Result<Vec<BalanceSnapshot>, AppError> {
+        let rows = sqlx::query_as::<_, SnapshotRow>(
+            "SELECT bucket_start, balance, recorded_at FROM balance_snapshots
+             WHERE asset_id = $1 AND granularity = $2
+               AND ($3::BIGINT IS NULL OR bucket_start >= $3)
+               AND ($4::BIGINT IS NULL OR bucket_start <= $4)
+             ORDER BY bucket_start DESC LIMIT $5",
+        )
+        .bind(&query.asset_id)
+        .bind(query.granularity.as_str())
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(snapshots_from_rows(query, rows))
+    }
+}
+
+/// SQLite-backed balance history using the `balance_snapshots` table
+pub struct SqliteBalanceSnapshotStore {
+    pool: SqlitePool,
+}
+
+impl SqliteBalanceSnapshotStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl BalanceSnapshotStore for SqliteBalanceSnapshotStore {
+    async fn record(&self, snapshots: &[BalanceSnapshot]) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        for snapshot in snapshots {
+            sqlx::query(
+                "INSERT INTO balance_snapshots
+                    (asset_id, granularity, bucket_start, balance, recorded_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (asset_id, granularity, bucket_start) DO UPDATE
+                    SET balance = excluded.balance,
+                        recorded_at = excluded.recorded_at",
+            )
+            .bind(&snapshot.asset_id)
+            .bind(snapshot.granularity.as_str())
+            .bind(snapshot.bucket_start)
+            .bind(snapshot.balance as i64)
+            .bind(snapshot.recorded_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn history(&self, query: &BalanceHistoryQuery) -> Result<Vec<BalanceSnapshot>, AppError> {
+        let rows = sqlx::query_as::<_, SnapshotRow>(
+            "SELECT bucket_start, balance, recorded_at FROM balance_snapshots
+             WHERE asset_id = $1 AND granularity = $2
+               AND ($3 IS NULL OR bucket_start >= $3)
+               AND ($4 IS NULL OR bucket_start <= $4)
+             ORDER BY bucket_start DESC LIMIT $5",
+        )
+        .bind(&query.asset_id)
+        .bind(query.granularity.as_str())
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(snapshots_from_rows(query, rows))
+    }
+}
+
+/// Builds the balance snapshot store selected by the configured backend
+pub async fn create_balance_snapshot_store(
+    settings: &BalanceSnapshotSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn BalanceSnapshotStore>> {
+    info!("Using {:?} balance snapshot store", settings.backend);
+
+    let store: Arc<dyn BalanceSnapshotStore> = match settings.backend {
+        BalanceSnapshotStoreBackend::Memory => Arc::new(InMemoryBalanceSnapshotStore::new()),
+        BalanceSnapshotStoreBackend::Postgres => {
+            match storage_for(storage, "balance snapshot") {
+                Some(storage) => storage.balance_snapshots(),
+                None => Arc::new(InMemoryBalanceSnapshotStore::new()),
+            }
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(
+        granularity: SnapshotGranularity,
+        bucket_start: i64,
+        balance: u64,
+    ) -> BalanceSnapshot {
+        BalanceSnapshot {
+            asset_id: "ab".repeat(32),
+            granularity,
+            bucket_start,
+            balance,
+            recorded_at: bucket_start + 60,
+        }
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        let timestamp = 1_700_003_723;
+        assert_eq!(SnapshotGranularity::Hourly.bucket_start(timestamp), 1_700_002_800);
+        assert_eq!(SnapshotGranularity::Daily.bucket_start(timestamp), 1_699_920_000);
+    }
+
+    async fn assert_history_keeps_latest_sample_per_bucket(store: &dyn BalanceSnapshotStore) {
+        let hourly = SnapshotGranularity::Hourly;
+        store
+            .record(&[
+                snapshot(hourly, 3_600, 10),
+                snapshot(hourly, 7_200, 20),
+                snapshot(hourly, 10_800, 30),
+                snapshot(SnapshotGranularity::Daily, 0, 30),
+            ])
+            .await
+            .unwrap();
+        store.record(&[snapshot(hourly, 7_200, 25)]).await.unwrap();
+
+        let mut query = BalanceHistoryQuery {
+            asset_id: "ab".repeat(32),
+            granularity: hourly,
+            from: None,
+            to: None,
+            limit: 10,
+        };
+        let balances = |snapshots: Vec<BalanceSnapshot>| -> Vec<u64> {
+            snapshots.iter().map(|snapshot| snapshot.balance).collect()
+        };
+        assert_eq!(balances(store.history(&query).await.unwrap()), [10, 25, 30]);
+
+        query.limit = 2;
+        assert_eq!(balances(store.history(&query).await.unwrap()), [25, 30]);
+
+        query.limit = 10;
+        query.from = Some(7_200);
+        query.to = Some(7_200);
+        assert_eq!(store.history(&query).await.unwrap(), vec![snapshot(hourly, 7_200, 25)]);
+
+        query.asset_id = "cd".repeat(32);
+        assert!(store.history(&query).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_keeps_latest_sample_per_bucket() {
+        assert_history_keeps_latest_sample_per_bucket(&InMemoryBalanceSnapshotStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_history_keeps_latest_sample_per_bucket() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_history_keeps_latest_sample_per_bucket(storage.balance_snapshots().as_ref()).await;
+    }
+}
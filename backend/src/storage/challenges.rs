@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{ChallengeStoreBackend, ChallengeStoreSettings};
+use crate::error::AppError;
+
+const REDIS_KEY_PREFIX: &str = "mailbox:challenge:";
+
+/// An authentication challenge issued to a mailbox client
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChallengeData {
+    pub challenge_id: String,
+    pub timestamp: i64,
+    pub nonce: String,
+}
+
+/// Storage for outstanding mailbox auth challenges.
+///
+/// Challenges must be visible to every gateway instance that may receive the
+/// client's signed response, so multi-instance deployments should use a shared
+/// backend (Redis or Postgres) rather than the in-memory store.
+#[async_trait::async_trait]
+pub trait ChallengeStore: Send + Sync {
+    async fn insert(&self, challenge: ChallengeData, ttl: Duration) -> Result<(), AppError>;
+    async fn get(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError>;
+    /// Removes and returns the challenge in one step, so concurrent responses
+    /// to the same challenge cannot both consume it
+    async fn take(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError>;
+}
+
+/// Process-local challenge store, suitable for single-instance deployments
+#[derive(Default)]
+pub struct InMemoryChallengeStore {
+    challenges: Mutex<HashMap<String, (ChallengeData, Instant)>>,
+}
+
+impl InMemoryChallengeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ChallengeStore for InMemoryChallengeStore {
+    async fn insert(&self, challenge: ChallengeData, ttl: Duration) -> Result<(), AppError> {
+        let mut challenges = self.challenges.lock().unwrap();
+        let now = Instant::now();
+
+        // Clean up expired challenges
+        challenges.retain(|_, (_, expires_at)| *expires_at > now);
+
+        challenges.insert(challenge.challenge_id.clone(), (challenge, now + ttl));
+        Ok(())
+    }
+
+    async fn get(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let challenges = self.challenges.lock().unwrap();
+        Ok(challenges
+            .get(challenge_id)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(data, _)| data.clone()))
+    }
+
+    async fn take(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let removed = self.challenges.lock().unwrap().remove(challenge_id);
+        Ok(removed
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(data, _)| data))
+    }
+}
+
+/// Redis-backed challenge store; expiry is delegated to Redis key TTLs
+pub struct RedisChallengeStore {
+    connection: redis::aio::ConnectionManager,
+}
+
+impl RedisChallengeStore {
+    pub async fn connect(redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let connection = redis::aio::ConnectionManager::new(client).await?;
+        Ok(Self { connection })
+    }
+}
+
+#[async_trait::async_trait]
+impl ChallengeStore for RedisChallengeStore {
+    async fn insert(&self, challenge: ChallengeData, ttl: Duration) -> Result<(), AppError> {
+        let key = format!("{REDIS_KEY_PREFIX}{}", challenge.challenge_id);
+        let value = serde_json::to_string(&challenge)?;
+        let mut connection = self.connection.clone();
+        connection
+            .set_ex::<_, _, ()>(key, value, ttl.as_secs().max(1))
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection
+            .get(format!("{REDIS_KEY_PREFIX}{challenge_id}"))
+            .await?;
+        value
+            .map(|v| serde_json::from_str(&v).map_err(AppError::from))
+            .transpose()
+    }
+
+    async fn take(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let mut connection = self.connection.clone();
+        let value: Option<String> = connection
+            .get_del(format!("{REDIS_KEY_PREFIX}{challenge_id}"))
+            .await?;
+        value
+            .map(|v| serde_json::from_str(&v).map_err(AppError::from))
+            .transpose()
+    }
+}
+
+/// Postgres-backed challenge store using the `mailbox_challenges` table
+pub struct PostgresChallengeStore {
+    pool: PgPool,
+}
+
+impl PostgresChallengeStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChallengeStore for PostgresChallengeStore {
+    async fn insert(&self, challenge: ChallengeData, ttl: Duration) -> Result<(), AppError> {
+        // Clean up expired challenges
+        sqlx::query("DELETE FROM mailbox_challenges WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO mailbox_challenges (challenge_id, timestamp, nonce, expires_at)
+             VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))",
+        )
+        .bind(&challenge.challenge_id)
+        .bind(challenge.timestamp)
+        .bind(&challenge.nonce)
+        .bind(ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let row = sqlx::query_as::<_, (String, i64, String)>(
+            "SELECT challenge_id, timestamp, nonce FROM mailbox_challenges
+             WHERE challenge_id = $1 AND expires_at > NOW()",
+        )
+        .bind(challenge_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(challenge_id, timestamp, nonce)| ChallengeData {
+            challenge_id,
+            timestamp,
+            nonce,
+        }))
+    }
+
+    async fn take(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let row = sqlx::query_as::<_, (String, i64, String, bool)>(
+            "DELETE FROM mailbox_challenges WHERE challenge_id = $1
+             RETURNING challenge_id, timestamp, nonce, expires_at > NOW()",
+        )
+        .bind(challenge_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row
+            .filter(|(_, _, _, live)| *live)
+            .map(|(challenge_id, timestamp, nonce, _)| ChallengeData {
+                challenge_id,
+                timestamp,
+                nonce,
+            }))
+    }
+}
+
+/// SQLite-backed challenge store using the `mailbox_challenges` table
+pub struct SqliteChallengeStore {
+    pool: SqlitePool,
+}
+
+impl SqliteChallengeStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChallengeStore for SqliteChallengeStore {
+    async fn insert(&self, challenge: ChallengeData, ttl: Duration) -> Result<(), AppError> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // Clean up expired challenges
+        sqlx::query("DELETE FROM mailbox_challenges WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO mailbox_challenges (challenge_id, timestamp, nonce, expires_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&challenge.challenge_id)
+        .bind(challenge.timestamp)
+        .bind(&challenge.nonce)
+        .bind(now.saturating_add(ttl.as_millis() as i64))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let row = sqlx::query_as::<_, (String, i64, String)>(
+            "SELECT challenge_id, timestamp, nonce FROM mailbox_challenges
+             WHERE challenge_id = $1 AND expires_at > $2",
+        )
+        .bind(challenge_id)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(challenge_id, timestamp, nonce)| ChallengeData {
+            challenge_id,
+            timestamp,
+            nonce,
+        }))
+    }
+
+    async fn take(&self, challenge_id: &str) -> Result<Option<ChallengeData>, AppError> {
+        let row = sqlx::query_as::<_, (String, i64, String, i64)>(
+            "DELETE FROM mailbox_challenges WHERE challenge_id = $1
+             RETURNING challenge_id, timestamp, nonce, expires_at",
+        )
+        .bind(challenge_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        Ok(row
+            .filter(|(_, _, _, expires_at)| *expires_at > now)
+            .map(|(challenge_id, timestamp, nonce, _)| ChallengeData {
+                challenge_id,
+                timestamp,
+                nonce,
+            }))
+    }
+}
+
+/// Builds the challenge store selected by the configured backend
+pub async fn create_challenge_store(
+    settings: &ChallengeStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn ChallengeStore>> {
+    info!("Using {:?} mailbox challenge store", settings.backend);
+
+    let store: Arc<dyn ChallengeStore> = match settings.backend {
+        ChallengeStoreBackend::Memory => Arc::new(InMemoryChallengeStore::new()),
+        ChallengeStoreBackend::Redis => {
+            Arc::new(RedisChallengeStore::connect(&settings.redis_url).await?)
+        }
+        ChallengeStoreBackend::Postgres => {
+            match storage_for(storage, "mailbox challenge") {
+                Some(storage) => storage.challenges(),
+                None => Arc::new(InMemoryChallengeStore::new()),
+            }
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_challenge(id: &str) -> ChallengeData {
+        ChallengeData {
+            challenge_id: id.to_string(),
+            timestamp: 1_700_000_000,
+            nonce: "nonce".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_insert_get_take() {
+        let store = InMemoryChallengeStore::new();
+        store
+            .insert(test_challenge("abc"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("abc").await.unwrap(), Some(test_challenge("abc")));
+
+        assert_eq!(store.take("abc").await.unwrap(), Some(test_challenge("abc")));
+        assert_eq!(store.get("abc").await.unwrap(), None);
+        assert_eq!(store.take("abc").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_takes_consume_a_challenge_once() {
+        let store = Arc::new(InMemoryChallengeStore::new());
+        store
+            .insert(test_challenge("abc"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let takes = (0..8).map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { store.take("abc").await.unwrap() })
+        });
+        let taken = futures::future::join_all(takes).await;
+        assert_eq!(taken.into_iter().filter(|t| t.as_ref().unwrap().is_some()).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_expiry() {
+        let store = InMemoryChallengeStore::new();
+        store
+            .insert(test_challenge("expired"), Duration::from_secs(0))
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("expired").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_cleans_up_expired_on_insert() {
+        let store = InMemoryChallengeStore::new();
+        store
+            .insert(test_challenge("old"), Duration::from_secs(0))
+            .await
+            .unwrap();
+        store
+            .insert(test_challenge("new"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let challenges = store.challenges.lock().unwrap();
+        assert!(!challenges.contains_key("old"));
+        assert!(challenges.contains_key("new"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_insert_get_expire() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        let store = storage.challenges();
+        store
+            .insert(test_challenge("abc"), Duration::from_secs(60))
+            .await
+            .unwrap();
+        store
+            .insert(test_challenge("expired"), Duration::from_secs(0))
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("abc").await.unwrap(), Some(test_challenge("abc")));
+        assert_eq!(store.get("expired").await.unwrap(), None);
+
+        assert_eq!(store.take("expired").await.unwrap(), None);
+        assert_eq!(store.take("abc").await.unwrap(), Some(test_challenge("abc")));
+        assert_eq!(store.take("abc").await.unwrap(), None);
+        assert_eq!(store.get("abc").await.unwrap(), None);
+    }
+}
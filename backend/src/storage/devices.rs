@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool, SqlitePool};
+
+use crate::error::AppError;
+use crate::secrets::SecretBox;
+
+/// Push service a device token belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushPlatform {
+    Fcm,
+    Apns,
+}
+
+impl PushPlatform {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PushPlatform::Fcm => "fcm",
+            PushPlatform::Apns => "apns",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "fcm" => Ok(PushPlatform::Fcm),
+            "apns" => Ok(PushPlatform::Apns),
+            other => Err(AppError::StorageError(format!("Unknown push platform: {other}"))),
+        }
+    }
+}
+
+/// A mobile device to notify about transfers to the Taproot Assets addresses it watches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceRegistration {
+    pub token: String,
+    pub platform: PushPlatform,
+    pub addresses: Vec<String>,
+    pub registered_at: i64,
+}
+
+/// Registered push tokens, looked up by watched address when assets arrive
+#[async_trait::async_trait]
+pub trait DeviceStore: Send + Sync {
+    /// Stores the registration, replacing any earlier one for the same token
+    async fn register(&self, device: &DeviceRegistration) -> Result<(), AppError>;
+    async fn unregister(&self, token: &str) -> Result<bool, AppError>;
+    async fn devices_for_address(&self, address: &str) -> Result<Vec<DeviceRegistration>, AppError>;
+}
+
+/// Process-local device registry
+#[derive(Default)]
+pub struct InMemoryDeviceStore {
+    devices: RwLock<HashMap<String, DeviceRegistration>>,
+}
+
+impl InMemoryDeviceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceStore for InMemoryDeviceStore {
+    async fn register(&self, device: &DeviceRegistration) -> Result<(), AppError> {
+        self.devices
+            .write()
+            .unwrap()
+            .insert(device.token.clone(), device.clone());
+        Ok(())
+    }
+
+    async fn unregister(&self, token: &str) -> Result<bool, AppError> {
+        Ok(self.devices.write().unwrap().remove(token).is_some())
+    }
+
+    async fn devices_for_address(&self, address: &str) -> Result<Vec<DeviceRegistration>, AppError> {
+        Ok(self
+            .devices
+            .read()
+            .unwrap()
+            .values()
+            .filter(|device| device.addresses.iter().any(|a| a == address))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Postgres-backed device registry using the `notification_devices` table.
+/// With encryption enabled, rows are keyed by the token's HMAC and the token
+/// itself is kept in `sealed_token`.
+pub struct PostgresDeviceStore {
+    pool: PgPool,
+    secrets: Arc<SecretBox>,
+}
+
+impl PostgresDeviceStore {
+    pub fn new(pool: PgPool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Rekeys and reseals tokens stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT token, sealed_token FROM notification_devices",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (key, sealed_token) in rows {
+            let Some(token) = token_to_reseal(&self.secrets, &key, sealed_token.as_deref())? else {
+                continue;
+            };
+            sqlx::query(
+                "UPDATE notification_devices SET token = $1, sealed_token = $2 WHERE token = $3",
+            )
+            .bind(self.secrets.lookup_key(&token))
+            .bind(sealed_token_for(&self.secrets, &token)?)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+/// The token to seal when encryption is enabled
+fn sealed_token_for(secrets: &SecretBox, token: &str) -> Result<Option<String>, AppError> {
+    secrets.is_enabled().then(|| secrets.seal(token)).transpose()
+}
+
+/// The push token of a stored row: sealed, or the key itself for rows
+/// written without encryption
+fn token_from_row(
+    secrets: &SecretBox,
+    key: String,
+    sealed_token: Option<&str>,
+) -> Result<String, AppError> {
+    match sealed_token {
+        Some(sealed) => secrets.open(sealed),
+        None => Ok(key),
+    }
+}
+
+/// The plaintext token of a row that is not keyed and sealed under the
+/// current key, or `None` when it already is
+fn token_to_reseal(
+    secrets: &SecretBox,
+    key: &str,
+    sealed_token: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    let current = match sealed_token {
+        Some(sealed) => !secrets.needs_reseal(sealed),
+        None => !secrets.is_enabled(),
+    };
+    if current {
+        return Ok(None);
+    }
+    token_from_row(secrets, key.to_string(), sealed_token).map(Some)
+}
+
+#[async_trait::async_trait]
+impl DeviceStore for PostgresDeviceStore {
+    async fn register(&self, device: &DeviceRegistration) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        // Drop any row for the token keyed under a previous key or as plaintext
+        sqlx::query("DELETE FROM notification_devices WHERE token = ANY($1)")
+            .bind(self.secrets.lookup_candidates(&device.token))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO notification_devices
+                (token, sealed_token, platform, addresses, registered_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(self.secrets.lookup_key(&device.token))
+        .bind(sealed_token_for(&self.secrets, &device.token)?)
+        .bind(device.platform.as_str())
+        .bind(&device.addresses)
+        .bind(device.registered_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn unregister(&self, token: &str) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM notification_devices WHERE token = ANY($1)")
+            .bind(self.secrets.lookup_candidates(token))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn devices_for_address(&self, address: &str) -> Result<Vec<DeviceRegistration>, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, String, Vec<String>, i64)>(
+            "SELECT token, sealed_token, platform, addresses, registered_at
+             FROM notification_devices WHERE $1 = ANY(addresses)",
+        )
+        .bind(address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(key, sealed_token, platform, addresses, registered_at)| {
+                Ok(DeviceRegistration {
+                    token: token_from_row(&self.secrets, key, sealed_token.as_deref())?,
+                    platform: PushPlatform::parse(&platform)?,
+                    addresses,
+                    registered_at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// SQLite-backed device registry using the `notification_devices` table,
+/// with watched addresses kept as a JSON array and tokens keyed as in
+/// [`PostgresDeviceStore`]
+pub struct SqliteDeviceStore {
+    pool: SqlitePool,
+    secrets: Arc<SecretBox>,
+}
+
+impl SqliteDeviceStore {
+    pub fn new(pool: SqlitePool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Rekeys and reseals tokens stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT token, sealed_token FROM notification_devices",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (key, sealed_token) in rows {
+            let Some(token) = token_to_reseal(&self.secrets, &key, sealed_token.as_deref())? else {
+                continue;
+            };
+            sqlx::query(
+                "UPDATE notification_devices SET token = $1, sealed_token = $2 WHERE token = $3",
+            )
+            .bind(self.secrets.lookup_key(&token))
+            .bind(sealed_token_for(&self.secrets, &token)?)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceStore for SqliteDeviceStore {
+    async fn register(&self, device: &DeviceRegistration) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        // Drop any row for the token keyed under a previous key or as plaintext
+        sqlx::query(
+            "DELETE FROM notification_devices
+             WHERE token IN (SELECT value FROM json_each($1))",
+        )
+        .bind(Json(self.secrets.lookup_candidates(&device.token)))
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO notification_devices
+                (token, sealed_token, platform, addresses, registered_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(self.secrets.lookup_key(&device.token))
+        .bind(sealed_token_for(&self.secrets, &device.token)?)
+        .bind(device.platform.as_str())
+        .bind(Json(&device.addresses))
+        .bind(device.registered_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn unregister(&self, token: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "DELETE FROM notification_devices
+             WHERE token IN (SELECT value FROM json_each($1))",
+        )
+        .bind(Json(self.secrets.lookup_candidates(token)))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn devices_for_address(&self, address: &str) -> Result<Vec<DeviceRegistration>, AppError> {
+        let rows = sqlx::query_as::<_, (String, Option<String>, String, Json<Vec<String>>, i64)>(
+            "SELECT token, sealed_token, platform, addresses, registered_at
+             FROM notification_devices
+             WHERE EXISTS (SELECT 1 FROM json_each(addresses) WHERE value = $1)",
+        )
+        .bind(address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(key, sealed_token, platform, Json(addresses), registered_at)| {
+                Ok(DeviceRegistration {
+                    token: token_from_row(&self.secrets, key, sealed_token.as_deref())?,
+                    platform: PushPlatform::parse(&platform)?,
+                    addresses,
+                    registered_at,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(token: &str, addresses: &[&str]) -> DeviceRegistration {
+        DeviceRegistration {
+            token: token.to_string(),
+            platform: PushPlatform::Fcm,
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            registered_at: 1_700_000_000,
+        }
+    }
+
+    async fn assert_lookup_by_address_and_reregistration(store: &dyn DeviceStore) {
+        store.register(&device("phone", &["taprt1a", "taprt1b"])).await.unwrap();
+        store.register(&device("tablet", &["taprt1b"])).await.unwrap();
+
+        assert_eq!(store.devices_for_address("taprt1a").await.unwrap().len(), 1);
+        assert_eq!(store.devices_for_address("taprt1b").await.unwrap().len(), 2);
+
+        // Re-registering a token replaces its watched addresses
+        store.register(&device("phone", &["taprt1c"])).await.unwrap();
+        assert!(store.devices_for_address("taprt1a").await.unwrap().is_empty());
+
+        assert!(store.unregister("tablet").await.unwrap());
+        assert!(!store.unregister("tablet").await.unwrap());
+        assert!(store.devices_for_address("taprt1b").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_address_and_reregistration() {
+        assert_lookup_by_address_and_reregistration(&InMemoryDeviceStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_lookup_by_address_and_reregistration() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_lookup_by_address_and_reregistration(storage.devices().as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_tokens_are_sealed_and_rekeyed_on_rotation() {
+        let old_key = "previous-master-key-with-at-least-32-chars".to_string();
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await.unwrap();
+        let plaintext = SqliteDeviceStore::new(pool.clone(), Arc::new(SecretBox::plaintext()));
+        plaintext.register(&device("phone", &["taprt1a"])).await.unwrap();
+
+        let sealed = SecretBox::new(&old_key, &[]).unwrap();
+        let store = SqliteDeviceStore::new(pool.clone(), Arc::new(sealed));
+        assert_eq!(store.reseal_secrets().await.unwrap(), 1);
+        let (key, sealed_token) = sqlx::query_as::<_, (String, String)>(
+            "SELECT token, sealed_token FROM notification_devices",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(key.starts_with("hmac:v1:"));
+        assert!(!sealed_token.contains("phone"));
+        assert_eq!(store.devices_for_address("taprt1a").await.unwrap()[0].token, "phone");
+
+        let rotated = SecretBox::new("current-master-key-with-at-least-32-chars", &[old_key]);
+        let store = SqliteDeviceStore::new(pool, Arc::new(rotated.unwrap()));
+        assert_eq!(store.devices_for_address("taprt1a").await.unwrap()[0].token, "phone");
+        assert_eq!(store.reseal_secrets().await.unwrap(), 1);
+        assert_eq!(store.reseal_secrets().await.unwrap(), 0);
+        assert!(store.unregister("phone").await.unwrap());
+    }
+}
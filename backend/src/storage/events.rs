@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::types::Json;
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{EventStoreBackend, EventStoreSettings};
+use crate::error::AppError;
+
+/// Events kept by the in-memory store before the oldest are dropped
+const IN_MEMORY_RETENTION: usize = 10_000;
+
+/// An asset event as recorded, addressed by a monotonically increasing cursor
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StoredEvent {
+    pub cursor: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub received_at: i64,
+}
+
+/// Append-only log of asset events used to backfill reconnecting clients
+#[async_trait::async_trait]
+pub trait EventStore: Send + Sync {
+    /// Records an event and returns its cursor
+    async fn append(&self, event_type: &str, payload: &serde_json::Value) -> Result<i64, AppError>;
+    /// Returns up to `limit` events with a cursor greater than `after_cursor`, oldest first
+    async fn list(
+        &self,
+        event_type: Option<&str>,
+        after_cursor: i64,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>, AppError>;
+    /// Deletes events received before `before`, in unix seconds; returns how
+    /// many were removed
+    async fn prune(&self, before: i64) -> Result<u64, AppError>;
+}
+
+/// Process-local event log holding the most recent events only
+#[derive(Default)]
+pub struct InMemoryEventStore {
+    events: Mutex<VecDeque<StoredEvent>>,
+    /// Last cursor handed out; kept apart from `events` so cursors never
+    /// repeat after pruning empties the log
+    last_cursor: AtomicI64,
+}
+
+impl InMemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append(&self, event_type: &str, payload: &serde_json::Value) -> Result<i64, AppError> {
+        let mut events = self.events.lock().unwrap();
+        let cursor = self.last_cursor.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if events.len() == IN_MEMORY_RETENTION {
+            events.pop_front();
+        }
+        events.push_back(StoredEvent {
+            cursor,
+            event_type: event_type.to_string(),
+            payload: payload.clone(),
+            received_at: chrono::Utc::now().timestamp(),
+        });
+
+        Ok(cursor)
+    }
+
+    async fn list(
+        &self,
+        event_type: Option<&str>,
+        after_cursor: i64,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>, AppError> {
+        let events = self.events.lock().unwrap();
+        Ok(events
+            .iter()
+            .filter(|e| e.cursor > after_cursor)
+            .filter(|e| event_type.is_none_or(|t| e.event_type == t))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn prune(&self, before: i64) -> Result<u64, AppError> {
+        let mut events = self.events.lock().unwrap();
+        let count = events.len();
+        events.retain(|e| e.received_at >= before);
+        Ok((count - events.len()) as u64)
+    }
+}
+
+/// Postgres-backed event log using the `asset_events` table
+pub struct PostgresEventStore {
+    pool: PgPool,
+}
+
+impl PostgresEventStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStore for PostgresEventStore {
+    async fn append(&self, event_type: &str, payload: &serde_json::Value) -> Result<i64, AppError> {
+        let (cursor,) = sqlx::query_as::<_, (i64,)>(
+            "INSERT INTO asset_events (event_type, payload, received_at)
+             VALUES ($1, $2, $3) RETURNING cursor",
+        )
+        .bind(event_type)
+        .bind(Json(payload))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(cursor)
+    }
+
+    async fn list(
+        &self,
+        event_type: Option<&str>,
+        after_cursor: i64,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>, AppError> {
+        let rows = sqlx::query_as::<_, (i64, String, Json<serde_json::Value>, i64)>(
+            "SELECT cursor, event_type, payload, received_at FROM asset_events
+             WHERE cursor > $1 AND ($2::TEXT IS NULL OR event_type = $2)
+             ORDER BY cursor ASC LIMIT $3",
+        )
+        .bind(after_cursor)
+        .bind(event_type)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(cursor, event_type, Json(payload), received_at)| StoredEvent {
+                cursor,
+                event_type,
+                payload,
+                received_at,
+            })
+            .collect())
+    }
+
+    async fn prune(&self, before: i64) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM asset_events WHERE received_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// SQLite-backed event log using the `asset_events` table
+pub struct SqliteEventStore {
+    pool: SqlitePool,
+}
+
+impl SqliteEventStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStore for SqliteEventStore {
+    async fn append(&self, event_type: &str, payload: &serde_json::Value) -> Result<i64, AppError> {
+        let (cursor,) = sqlx::query_as::<_, (i64,)>(
+            "INSERT INTO asset_events (event_type, payload, received_at)
+             VALUES ($1, $2, $3) RETURNING cursor",
+        )
+        .bind(event_type)
+        .bind(Json(payload))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(cursor)
+    }
+
+    async fn list(
+        &self,
+        event_type: Option<&str>,
+        after_cursor: i64,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>, AppError> {
+        let rows = sqlx::query_as::<_, (i64, String, Json<serde_json::Value>, i64)>(
+            "SELECT cursor, event_type, payload, received_at FROM asset_events
+             WHERE cursor > $1 AND ($2 IS NULL OR event_type = $2)
+             ORDER BY cursor ASC LIMIT $3",
+        )
+        .bind(after_cursor)
+        .bind(event_type)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(cursor, event_type, Json(payload), received_at)| StoredEvent {
+                cursor,
+                event_type,
+                payload,
+                received_at,
+            })
+            .collect())
+    }
+
+    async fn prune(&self, before: i64) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM asset_events WHERE received_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Builds the event store selected by the configured backend
+pub async fn create_event_store(
+    settings: &EventStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn EventStore>> {
+    info!("Using {:?} asset event store", settings.backend);
+
+    let store: Arc<dyn EventStore> = match settings.backend {
+        EventStoreBackend::Memory => Arc::new(InMemoryEventStore::new()),
+        EventStoreBackend::Postgres => match storage_for(storage, "asset event") {
+            Some(storage) => storage.events(),
+            None => Arc::new(InMemoryEventStore::new()),
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_in_memory_cursors_increase() {
+        let store = InMemoryEventStore::new();
+        let first = store.append("asset-mint", &json!({"n": 1})).await.unwrap();
+        let second = store.append("asset-send", &json!({"n": 2})).await.unwrap();
+        assert!(second > first);
+    }
+
+    async fn assert_list_filters_and_pages(store: &dyn EventStore) {
+        for n in 0..5 {
+            let event_type = if n % 2 == 0 { "asset-mint" } else { "asset-send" };
+            store.append(event_type, &json!({"n": n})).await.unwrap();
+        }
+
+        let mints = store.list(Some("asset-mint"), 0, 10).await.unwrap();
+        assert_eq!(mints.len(), 3);
+        assert!(mints.iter().all(|e| e.event_type == "asset-mint"));
+
+        let page = store.list(None, 2, 2).await.unwrap();
+        let cursors: Vec<i64> = page.iter().map(|e| e.cursor).collect();
+        assert_eq!(cursors, vec![3, 4]);
+        assert_eq!(page[0].payload["n"], 2);
+
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(store.prune(now - 3600).await.unwrap(), 0);
+        assert_eq!(store.prune(now + 1).await.unwrap(), 5);
+        assert!(store.list(None, 0, 10).await.unwrap().is_empty());
+
+        // Cursors keep counting after the log is emptied, so a client
+        // resuming from cursor 5 still sees the next event
+        let cursor = store.append("asset-mint", &json!({"n": 5})).await.unwrap();
+        assert_eq!(cursor, 6);
+        assert_eq!(store.list(None, 5, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_list_filters_and_pages() {
+        assert_list_filters_and_pages(&InMemoryEventStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_list_filters_and_pages() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_list_filters_and_pages(storage.events().as_ref()).await;
+    }
+}
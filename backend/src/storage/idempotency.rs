@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use sqlx::{types::Json, PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{IdempotencyStoreBackend, IdempotencyStoreSettings};
+use crate::error::AppError;
+
+/// What a request carrying an already-seen `Idempotency-Key` should do
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// The key is new, or its record expired; the caller runs the request
+    Claimed,
+    /// Another request with this key has not finished yet
+    InProgress,
+    /// The key was used for a different request body
+    Mismatch,
+    /// The stored result to replay
+    Completed { status: u16, body: serde_json::Value },
+}
+
+impl IdempotencyClaim {
+    fn existing(
+        request_hash: &str,
+        stored_hash: &str,
+        status: Option<u16>,
+        body: Option<serde_json::Value>,
+    ) -> Self {
+        if stored_hash != request_hash {
+            return IdempotencyClaim::Mismatch;
+        }
+        match status {
+            Some(status) => IdempotencyClaim::Completed {
+                status,
+                body: body.unwrap_or_default(),
+            },
+            None => IdempotencyClaim::InProgress,
+        }
+    }
+}
+
+/// Results of mutating requests keyed by the client's `Idempotency-Key`,
+/// scoped per endpoint
+#[async_trait::async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Claims `key` for a request hashing to `request_hash`, unless a record
+    /// created after `expired_before` already holds it
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+        expired_before: i64,
+    ) -> Result<IdempotencyClaim, AppError>;
+    /// Stores the response of a claimed request for replay
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        body: &serde_json::Value,
+    ) -> Result<(), AppError>;
+}
+
+#[derive(Debug, Clone)]
+struct StoredKey {
+    request_hash: String,
+    status: Option<u16>,
+    body: Option<serde_json::Value>,
+    created_at: i64,
+}
+
+/// Process-local idempotency records
+#[derive(Default)]
+pub struct InMemoryIdempotencyStore {
+    keys: RwLock<HashMap<(String, String), StoredKey>>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+        expired_before: i64,
+    ) -> Result<IdempotencyClaim, AppError> {
+        let mut keys = self.keys.write().unwrap();
+        keys.retain(|_, stored| stored.created_at >= expired_before);
+
+        let id = (scope.to_string(), key.to_string());
+        if let Some(stored) = keys.get(&id) {
+            return Ok(IdempotencyClaim::existing(
+                request_hash,
+                &stored.request_hash,
+                stored.status,
+                stored.body.clone(),
+            ));
+        }
+        keys.insert(
+            id,
+            StoredKey {
+                request_hash: request_hash.to_string(),
+                status: None,
+                body: None,
+                created_at: now,
+            },
+        );
+        Ok(IdempotencyClaim::Claimed)
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        body: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let mut keys = self.keys.write().unwrap();
+        if let Some(stored) = keys.get_mut(&(scope.to_string(), key.to_string())) {
+            stored.status = Some(status);
+            stored.body = Some(body.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Postgres-backed idempotency records using the `idempotency_keys` table
+pub struct PostgresIdempotencyStore {
+    pool: PgPool,
+}
+
+impl PostgresIdempotencyStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for PostgresIdempotencyStore {
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+        expired_before: i64,
+    ) -> Result<IdempotencyClaim, AppError> {
+        // Takes the key when it is free or expired, in one statement so two
+        // concurrent retries cannot both claim it
+        let claimed = sqlx::query_scalar::<_, String>(
+            "INSERT INTO idempotency_keys (scope, key, request_hash, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (scope, key) DO UPDATE
+                SET request_hash = EXCLUDED.request_hash,
+                    status = NULL,
+                    response = NULL,
+                    created_at = EXCLUDED.created_at
+                WHERE idempotency_keys.created_at < $5
+             RETURNING key",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(request_hash)
+        .bind(now)
+        .bind(expired_before)
+        .fetch_optional(&self.pool)
+        .await?;
+        if claimed.is_some() {
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let (stored_hash, status, body) =
+            sqlx::query_as::<_, (String, Option<i32>, Option<Json<serde_json::Value>>)>(
+                "SELECT request_hash, status, response FROM idempotency_keys
+                 WHERE scope = $1 AND key = $2",
+            )
+            .bind(scope)
+            .bind(key)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(IdempotencyClaim::existing(
+            request_hash,
+            &stored_hash,
+            status.map(|status| status as u16),
+            body.map(|Json(body)| body),
+        ))
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        body: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE idempotency_keys SET status = $3, response = $4
+             WHERE scope = $1 AND key = $2",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(status as i32)
+        .bind(Json(body))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// SQLite-backed idempotency records using the `idempotency_keys` table
+pub struct SqliteIdempotencyStore {
+    pool: SqlitePool,
+}
+
+impl SqliteIdempotencyStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for SqliteIdempotencyStore {
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+        expired_before: i64,
+    ) -> Result<IdempotencyClaim, AppError> {
+        // SQLite serializes writers, so the upsert claims the key atomically
+        // as it does in Postgres
+        let claimed = sqlx::query_scalar::<_, String>(
+            "INSERT INTO idempotency_keys (scope, key, request_hash, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (scope, key) DO UPDATE
+                SET request_hash = excluded.request_hash,
+                    status = NULL,
+                    response = NULL,
+                    created_at = excluded.created_at
+                WHERE idempotency_keys.created_at < $5
+             RETURNING key",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(request_hash)
+        .bind(now)
+        .bind(expired_before)
+        .fetch_optional(&self.pool)
+        .await?;
+        if claimed.is_some() {
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let (stored_hash, status, body) =
+            sqlx::query_as::<_, (String, Option<i32>, Option<Json<serde_json::Value>>)>(
+                "SELECT request_hash, status, response FROM idempotency_keys
+                 WHERE scope = $1 AND key = $2",
+            )
+            .bind(scope)
+            .bind(key)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(IdempotencyClaim::existing(
+            request_hash,
+            &stored_hash,
+            status.map(|status| status as u16),
+            body.map(|Json(body)| body),
+        ))
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: u16,
+        body: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE idempotency_keys SET status = $3, response = $4
+             WHERE scope = $1 AND key = $2",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(status as i32)
+        .bind(Json(body))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Builds the idempotency store selected by the configured backend
+pub async fn create_idempotency_store(
+    settings: &IdempotencyStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn IdempotencyStore>> {
+    info!("Using {:?} idempotency store", settings.backend);
+
+    let store: Arc<dyn IdempotencyStore> = match settings.backend {
+        IdempotencyStoreBackend::Memory => Arc::new(InMemoryIdempotencyStore::new()),
+        IdempotencyStoreBackend::Postgres => match storage_for(storage, "idempotency") {
+            Some(storage) => storage.idempotency(),
+            None => Arc::new(InMemoryIdempotencyStore::new()),
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn assert_claims_replay_until_expiry(store: &dyn IdempotencyStore) {
+        let claim =
+            |hash: &'static str, now: i64| store.claim("send", "retry-1", hash, now, now - 10);
+
+        assert_eq!(claim("a", 100).await.unwrap(), IdempotencyClaim::Claimed);
+        assert_eq!(claim("a", 101).await.unwrap(), IdempotencyClaim::InProgress);
+
+        let body = serde_json::json!({"success": true});
+        store.complete("send", "retry-1", 200, &body).await.unwrap();
+        assert_eq!(
+            claim("a", 102).await.unwrap(),
+            IdempotencyClaim::Completed { status: 200, body }
+        );
+        assert_eq!(claim("b", 103).await.unwrap(), IdempotencyClaim::Mismatch);
+        // Scopes keep the same key apart
+        assert_eq!(
+            store.claim("mint", "retry-1", "a", 104, 94).await.unwrap(),
+            IdempotencyClaim::Claimed
+        );
+        assert_eq!(claim("b", 200).await.unwrap(), IdempotencyClaim::Claimed);
+    }
+
+    #[tokio::test]
+    async fn test_claims_replay_until_expiry() {
+        assert_claims_replay_until_expiry(&InMemoryIdempotencyStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_claims_replay_until_expiry() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_claims_replay_until_expiry(storage.idempotency().as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_postgres_backend_without_database_stays_in_memory() {
+        let settings = IdempotencyStoreSettings::default();
+        assert_eq!(settings.backend, IdempotencyStoreBackend::Postgres);
+        let store = create_idempotency_store(&settings, None).await.unwrap();
+        assert_eq!(
+            store.claim("send", "key", "a", 100, 0).await.unwrap(),
+            IdempotencyClaim::Claimed
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::config::{ImageStoreBackend, ImageStoreSettings};
+use crate::crypto::hmac_sha256;
+use crate::error::AppError;
+
+/// Detects the image formats accepted for collectibles from their magic bytes
+pub fn image_content_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Hex SHA-256 of an image, which is also the meta hash tapd records when the
+/// image is minted as an opaque meta blob
+pub fn image_hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredImage {
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl StoredImage {
+    /// `None` unless `data` is a supported image
+    pub fn new(data: Vec<u8>) -> Option<Self> {
+        let content_type = image_content_type(&data)?;
+        Some(Self { content_type, data })
+    }
+
+    pub fn hash(&self) -> String {
+        image_hash(&self.data)
+    }
+}
+
+/// Collectible images, content-addressed by their hex SHA-256
+#[async_trait::async_trait]
+pub trait ImageStore: Send + Sync {
+    async fn put(&self, image: &StoredImage) -> Result<String, AppError>;
+    async fn get(&self, hash: &str) -> Result<Option<StoredImage>, AppError>;
+}
+
+fn validate_hash(hash: &str) -> Result<(), AppError> {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::InvalidInput(
+            "Image hash must be 32 bytes of hex".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Process-local images
+#[derive(Default)]
+pub struct InMemoryImageStore {
+    images: std::sync::RwLock<std::collections::HashMap<String, StoredImage>>,
+}
+
+impl InMemoryImageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ImageStore for InMemoryImageStore {
+    async fn put(&self, image: &StoredImage) -> Result<String, AppError> {
+        let hash = image.hash();
+        self.images.write().unwrap().insert(hash.clone(), image.clone());
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &str) -> Result<Option<StoredImage>, AppError> {
+        validate_hash(hash)?;
+        Ok(self.images.read().unwrap().get(&hash.to_ascii_lowercase()).cloned())
+    }
+}
+
+/// One file per image under a local directory
+pub struct FilesystemImageStore {
+    dir: PathBuf,
+}
+
+impl FilesystemImageStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, hash: &str) -> Result<PathBuf, AppError> {
+        validate_hash(hash)?;
+        Ok(self.dir.join(hash.to_ascii_lowercase()))
+    }
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::StorageError(format!("Image storage failed: {e}"))
+}
+
+#[async_trait::async_trait]
+impl ImageStore for FilesystemImageStore {
+    async fn put(&self, image: &StoredImage) -> Result<String, AppError> {
+        let hash = image.hash();
+        let path = self.path(&hash)?;
+        if tokio::fs::try_exists(&path).await.map_err(io_error)? {
+            return Ok(hash);
+        }
+        tokio::fs::create_dir_all(&self.dir).await.map_err(io_error)?;
+        // Readers never see a partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, &image.data).await.map_err(io_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(io_error)?;
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &str) -> Result<Option<StoredImage>, AppError> {
+        match tokio::fs::read(self.path(hash)?).await {
+            Ok(data) => Ok(StoredImage::new(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+}
+
+/// Objects under `images/` in an S3-compatible bucket, addressed path-style
+/// and signed with AWS Signature Version 4
+pub struct S3ImageStore {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3ImageStore {
+    pub fn new(client: reqwest::Client, settings: &ImageStoreSettings) -> Self {
+        let endpoint = settings
+            .s3_endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", settings.s3_region));
+        Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: settings.s3_bucket.clone(),
+            region: settings.s3_region.clone(),
+            access_key: settings.s3_access_key.clone(),
+            secret_key: settings.s3_secret_key.clone(),
+        }
+    }
+
+    fn request(
+        &self,
+        method: reqwest::Method,
+        hash: &str,
+        body: Vec<u8>,
+        now: DateTime<Utc>,
+    ) -> Result<reqwest::RequestBuilder, AppError> {
+        validate_hash(hash)?;
+        let path = format!("/{}/images/{}", self.bucket, hash.to_ascii_lowercase());
+        let url = url::Url::parse(&format!("{}{path}", self.endpoint))
+            .map_err(|e| AppError::StorageError(format!("Invalid IMAGE_S3_ENDPOINT: {e}")))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => {
+                return Err(AppError::StorageError("IMAGE_S3_ENDPOINT has no host".to_string()))
+            }
+        };
+
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let authorization = sigv4_authorization(
+            &SigV4Request {
+                method: method.as_str(),
+                host: &host,
+                path: url.path(),
+                payload_hash: &payload_hash,
+            },
+            &self.access_key,
+            &self.secret_key,
+            &self.region,
+            now,
+        );
+        Ok(self
+            .client
+            .request(method, url)
+            .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body))
+    }
+}
+
+#[async_trait::async_trait]
+impl ImageStore for S3ImageStore {
+    async fn put(&self, image: &StoredImage) -> Result<String, AppError> {
+        let hash = image.hash();
+        let response = self
+            .request(reqwest::Method::PUT, &hash, image.data.clone(), Utc::now())?
+            .header(reqwest::header::CONTENT_TYPE, image.content_type)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(AppError::StorageError(format!("S3 upload failed: {error_text}")));
+        }
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &str) -> Result<Option<StoredImage>, AppError> {
+        let response = self
+            .request(reqwest::Method::GET, hash, Vec::new(), Utc::now())?
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(AppError::StorageError(format!("S3 download failed: {error_text}")));
+        }
+        Ok(StoredImage::new(response.bytes().await?.to_vec()))
+    }
+}
+
+struct SigV4Request<'a> {
+    method: &'a str,
+    host: &'a str,
+    /// Already URI-encoded
+    path: &'a str,
+    payload_hash: &'a str,
+}
+
+/// `kSigning` from the SigV4 key derivation chain
+fn sigv4_signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let key = hmac_sha256(format!("AWS4{secret_key}").as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// `Authorization` header for an S3 request without a query string, signing
+/// the host, payload hash and date headers
+fn sigv4_authorization(
+    request: &SigV4Request,
+    access_key: &str,
+    secret_key: &str,
+    region: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        request.method,
+        request.path,
+        request.host,
+        request.payload_hash,
+        amz_date,
+        signed_headers,
+        request.payload_hash,
+    );
+
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = sigv4_signing_key(secret_key, &date, region, "s3");
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={access_key}/{scope}, \
+         SignedHeaders={signed_headers}, Signature={signature}"
+    )
+}
+
+pub async fn create_image_store(
+    settings: &ImageStoreSettings,
+    client: reqwest::Client,
+) -> Result<Arc<dyn ImageStore>> {
+    info!("Using {:?} image store", settings.backend);
+
+    let store: Arc<dyn ImageStore> = match settings.backend {
+        ImageStoreBackend::Filesystem => {
+            tokio::fs::create_dir_all(&settings.dir).await?;
+            Arc::new(FilesystemImageStore::new(&settings.dir))
+        }
+        ImageStoreBackend::S3 => Arc::new(S3ImageStore::new(client, settings)),
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR";
+
+    #[tokio::test]
+    async fn test_filesystem_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FilesystemImageStore::new(dir.path());
+        let image = StoredImage::new(PNG.to_vec()).unwrap();
+
+        let hash = store.put(&image).await.unwrap();
+        assert_eq!(hash, image_hash(PNG));
+        assert_eq!(store.put(&image).await.unwrap(), hash);
+        assert_eq!(store.get(&hash.to_uppercase()).await.unwrap(), Some(image));
+        assert_eq!(store.get(&"00".repeat(32)).await.unwrap(), None);
+        assert!(store.get("../../etc/passwd").await.is_err());
+    }
+
+    #[test]
+    fn test_only_images_are_stored() {
+        assert_eq!(StoredImage::new(PNG.to_vec()).unwrap().content_type, "image/png");
+        assert_eq!(image_content_type(b"GIF89a..."), Some("image/gif"));
+        assert!(StoredImage::new(b"{\"name\": \"not an image\"}".to_vec()).is_none());
+    }
+
+    #[test]
+    fn test_sigv4_signing() {
+        // Key derivation example from the AWS Signature Version 4 documentation
+        let key = sigv4_signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+
+        let now = DateTime::parse_from_rfc3339("2013-05-24T00:00:00Z").unwrap().to_utc();
+        let request = SigV4Request {
+            method: "GET",
+            host: "s3.us-east-1.amazonaws.com",
+            path: "/bucket/images/ab",
+            payload_hash: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        };
+        let header = sigv4_authorization(&request, "AKID", "secret", "us-east-1", now);
+        assert!(header.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKID/20130524/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        assert_eq!(header.rsplit('=').next().unwrap().len(), 64);
+    }
+}
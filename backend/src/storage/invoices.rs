@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+
+use crate::error::AppError;
+
+/// An asset invoice created through the gateway
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceRecord {
+    /// Hex payment hash
+    pub payment_hash: String,
+    pub payment_request: String,
+    pub asset_id: String,
+    pub asset_amount: u64,
+    pub label: Option<String>,
+    pub created_at: i64,
+}
+
+/// Invoices created through the gateway, by payment hash
+#[async_trait::async_trait]
+pub trait InvoiceRepo: Send + Sync {
+    /// Keeps the first record seen for a payment hash
+    async fn insert(&self, invoice: &InvoiceRecord) -> Result<(), AppError>;
+    async fn get(&self, payment_hash: &str) -> Result<Option<InvoiceRecord>, AppError>;
+    /// Newest first
+    async fn list(&self, limit: usize) -> Result<Vec<InvoiceRecord>, AppError>;
+}
+
+/// Process-local invoices
+#[derive(Default)]
+pub struct InMemoryInvoiceRepo {
+    invoices: RwLock<HashMap<String, InvoiceRecord>>,
+}
+
+impl InMemoryInvoiceRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl InvoiceRepo for InMemoryInvoiceRepo {
+    async fn insert(&self, invoice: &InvoiceRecord) -> Result<(), AppError> {
+        self.invoices
+            .write()
+            .unwrap()
+            .entry(invoice.payment_hash.clone())
+            .or_insert_with(|| invoice.clone());
+        Ok(())
+    }
+
+    async fn get(&self, payment_hash: &str) -> Result<Option<InvoiceRecord>, AppError> {
+        Ok(self.invoices.read().unwrap().get(payment_hash).cloned())
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<InvoiceRecord>, AppError> {
+        let mut invoices: Vec<InvoiceRecord> =
+            self.invoices.read().unwrap().values().cloned().collect();
+        invoices.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.payment_hash.cmp(&b.payment_hash))
+        });
+        invoices.truncate(limit);
+        Ok(invoices)
+    }
+}
+
+type InvoiceRow = (String, String, String, i64, Option<String>, i64);
+
+fn invoice_from_row(row: InvoiceRow) -> InvoiceRecord {
+    let (payment_hash, payment_request, asset_id, asset_amount, label, created_at) = row;
+    InvoiceRecord {
+        payment_hash,
+        payment_request,
+        asset_id,
+        asset_amount: asset_amount.max(0) as u64,
+        label,
+        created_at,
+    }
+}
+
+/// Postgres-backed invoices using the `invoices` table
+pub struct PostgresInvoiceRepo {
+    pool: PgPool,
+}
+
+impl PostgresInvoiceRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl InvoiceRepo for PostgresInvoiceRepo {
+    async fn insert(&self, invoice: &InvoiceRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO invoices
+                (payment_hash, payment_request, asset_id, asset_amount, label, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (payment_hash) DO NOTHING",
+        )
+        .bind(&invoice.payment_hash)
+        .bind(&invoice.payment_request)
+        .bind(&invoice.asset_id)
+        .bind(invoice.asset_amount as i64)
+        .bind(&invoice.label)
+        .bind(invoice.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, payment_hash: &str) -> Result<Option<InvoiceRecord>, AppError> {
+        let row = sqlx::query_as::<_, InvoiceRow>(
+            "SELECT payment_hash, payment_request, asset_id, asset_amount, label, created_at
+             FROM invoices WHERE payment_hash = $1",
+        )
+        .bind(payment_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(invoice_from_row))
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<InvoiceRecord>, AppError> {
+        let rows = sqlx::query_as::<_, InvoiceRow>(
+            "SELECT payment_hash, payment_request, asset_id, asset_amount, label, created_at
+             FROM invoices ORDER BY created_at DESC, payment_hash LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(invoice_from_row).collect())
+    }
+}
+
+/// SQLite-backed invoices using the `invoices` table
+pub struct SqliteInvoiceRepo {
+    pool: SqlitePool,
+}
+
+impl SqliteInvoiceRepo {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl InvoiceRepo for SqliteInvoiceRepo {
+    async fn insert(&self, invoice: &InvoiceRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO invoices
+                (payment_hash, payment_request, asset_id, asset_amount, label, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&invoice.payment_hash)
+        .bind(&invoice.payment_request)
+        .bind(&invoice.asset_id)
+        .bind(invoice.asset_amount as i64)
+        .bind(&invoice.label)
+        .bind(invoice.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, payment_hash: &str) -> Result<Option<InvoiceRecord>, AppError> {
+        let row = sqlx::query_as::<_, InvoiceRow>(
+            "SELECT payment_hash, payment_request, asset_id, asset_amount, label, created_at
+             FROM invoices WHERE payment_hash = $1",
+        )
+        .bind(payment_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(invoice_from_row))
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<InvoiceRecord>, AppError> {
+        let rows = sqlx::query_as::<_, InvoiceRow>(
+            "SELECT payment_hash, payment_request, asset_id, asset_amount, label, created_at
+             FROM invoices ORDER BY created_at DESC, payment_hash LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(invoice_from_row).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoice(payment_hash: &str, created_at: i64) -> InvoiceRecord {
+        InvoiceRecord {
+            payment_hash: payment_hash.repeat(32),
+            payment_request: format!("lnbcrt1{payment_hash}"),
+            asset_id: "cd".repeat(32),
+            asset_amount: 250,
+            label: Some("coffee".to_string()),
+            created_at,
+        }
+    }
+
+    async fn assert_invoices_by_hash_newest_first(repo: &dyn InvoiceRepo) {
+        let older = invoice("aa", 1);
+        let newer = invoice("bb", 2);
+        repo.insert(&older).await.unwrap();
+        repo.insert(&newer).await.unwrap();
+        repo.insert(&InvoiceRecord { label: None, ..older.clone() }).await.unwrap();
+
+        assert_eq!(repo.get(&older.payment_hash).await.unwrap(), Some(older.clone()));
+        assert_eq!(repo.get(&"ff".repeat(32)).await.unwrap(), None);
+        assert_eq!(repo.list(10).await.unwrap(), vec![newer.clone(), older]);
+        assert_eq!(repo.list(1).await.unwrap(), vec![newer]);
+    }
+
+    #[tokio::test]
+    async fn test_invoices_by_hash_newest_first() {
+        assert_invoices_by_hash_newest_first(&InMemoryInvoiceRepo::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_invoices_by_hash_newest_first() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_invoices_by_hash_newest_first(storage.invoices().as_ref()).await;
+    }
+}
@@ -1 +1,17 @@
-pub mod database;
\ No newline at end of file
+pub mod asset_preferences;
+pub mod audit;
+pub mod balance_snapshots;
+pub mod challenges;
+pub mod database;
+pub mod devices;
+pub mod events;
+pub mod idempotency;
+pub mod images;
+pub mod invoices;
+pub mod payments;
+pub mod price_alerts;
+pub mod receivers;
+pub mod rfq_orders;
+pub mod settings;
+pub mod transactions;
+pub mod webhooks;
@@ -0,0 +1,522 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+use super::database::{storage_for, Storage};
+use crate::config::{PaymentStoreBackend, PaymentStoreSettings};
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    InFlight,
+    Succeeded,
+    Failed,
+}
+
+impl PaymentStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::InFlight => "in_flight",
+            PaymentStatus::Succeeded => "succeeded",
+            PaymentStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "in_flight" => Ok(PaymentStatus::InFlight),
+            "succeeded" => Ok(PaymentStatus::Succeeded),
+            "failed" => Ok(PaymentStatus::Failed),
+            other => Err(AppError::StorageError(format!("Unknown payment status: {other}"))),
+        }
+    }
+}
+
+/// An outgoing asset payment as initiated through the gateway
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub id: Uuid,
+    pub payment_request: Option<String>,
+    pub payment_hash: Option<String>,
+    pub asset_id: String,
+    pub asset_amount: u64,
+    pub rfq_id: Option<String>,
+    pub status: PaymentStatus,
+    /// Amount delivered to the payee, excluding fees
+    pub value_msat: u64,
+    pub fee_msat: u64,
+    pub failure_reason: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl PaymentRecord {
+    /// A new in-flight payment, completed later with [`PaymentStore::complete`]
+    pub fn in_flight(
+        asset_id: String,
+        asset_amount: u64,
+        payment_request: Option<String>,
+        rfq_id: Option<String>,
+    ) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: Uuid::new_v4(),
+            payment_request,
+            payment_hash: None,
+            asset_id,
+            asset_amount,
+            rfq_id,
+            status: PaymentStatus::InFlight,
+            value_msat: 0,
+            fee_msat: 0,
+            failure_reason: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// The final result of a payment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaymentOutcome {
+    pub succeeded: bool,
+    pub payment_hash: Option<String>,
+    pub rfq_id: Option<String>,
+    pub value_msat: u64,
+    pub fee_msat: u64,
+    pub failure_reason: Option<String>,
+}
+
+impl PaymentOutcome {
+    pub fn failed(reason: String) -> Self {
+        Self {
+            failure_reason: Some(reason),
+            ..Self::default()
+        }
+    }
+}
+
+/// Filters for listing payments; `from`/`to` are inclusive unix timestamps
+#[derive(Debug, Clone, Default)]
+pub struct PaymentQuery {
+    pub status: Option<PaymentStatus>,
+    pub asset_id: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl PaymentQuery {
+    fn matches(&self, payment: &PaymentRecord) -> bool {
+        self.status.is_none_or(|s| payment.status == s)
+            && self.asset_id.as_ref().is_none_or(|a| &payment.asset_id == a)
+            && self.from.is_none_or(|from| payment.created_at >= from)
+            && self.to.is_none_or(|to| payment.created_at <= to)
+    }
+}
+
+/// History of outgoing payments that survives restarts of the gateway
+#[async_trait::async_trait]
+pub trait PaymentStore: Send + Sync {
+    async fn record(&self, payment: &PaymentRecord) -> Result<(), AppError>;
+    /// Stores the outcome of an in-flight payment; returns false if it is unknown
+    async fn complete(&self, id: Uuid, outcome: &PaymentOutcome) -> Result<bool, AppError>;
+    /// Returns matching payments, newest first
+    async fn list(&self, query: &PaymentQuery) -> Result<Vec<PaymentRecord>, AppError>;
+}
+
+/// Process-local payment history
+#[derive(Default)]
+pub struct InMemoryPaymentStore {
+    payments: RwLock<Vec<PaymentRecord>>,
+}
+
+impl InMemoryPaymentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentStore for InMemoryPaymentStore {
+    async fn record(&self, payment: &PaymentRecord) -> Result<(), AppError> {
+        self.payments.write().unwrap().push(payment.clone());
+        Ok(())
+    }
+
+    async fn complete(&self, id: Uuid, outcome: &PaymentOutcome) -> Result<bool, AppError> {
+        let mut payments = self.payments.write().unwrap();
+        let Some(payment) = payments.iter_mut().find(|p| p.id == id) else {
+            return Ok(false);
+        };
+
+        payment.status = if outcome.succeeded {
+            PaymentStatus::Succeeded
+        } else {
+            PaymentStatus::Failed
+        };
+        payment.payment_hash = outcome.payment_hash.clone().or(payment.payment_hash.take());
+        payment.rfq_id = outcome.rfq_id.clone().or(payment.rfq_id.take());
+        payment.value_msat = outcome.value_msat;
+        payment.fee_msat = outcome.fee_msat;
+        payment.failure_reason = outcome.failure_reason.clone();
+        payment.updated_at = chrono::Utc::now().timestamp();
+        Ok(true)
+    }
+
+    async fn list(&self, query: &PaymentQuery) -> Result<Vec<PaymentRecord>, AppError> {
+        let payments = self.payments.read().unwrap();
+        let mut matching: Vec<PaymentRecord> =
+            payments.iter().filter(|p| query.matches(p)).cloned().collect();
+        matching.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+        Ok(matching
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect())
+    }
+}
+
+/// Postgres-backed payment history using the `payments` table
+pub struct PostgresPaymentStore {
+    pool: PgPool,
+}
+
+impl PostgresPaymentStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+type PaymentRow = (
+    Uuid,
+    Option<String>,
+    Option<String>,
+    String,
+    i64,
+    Option<String>,
+    String,
+    i64,
+    i64,
+    Option<String>,
+    i64,
+    i64,
+);
+
+fn payment_from_row(row: PaymentRow) -> Result<PaymentRecord, AppError> {
+    let (
+        id,
+        payment_request,
+        payment_hash,
+        asset_id,
+        asset_amount,
+        rfq_id,
+        status,
+        value_msat,
+        fee_msat,
+        failure_reason,
+        created_at,
+        updated_at,
+    ) = row;
+    Ok(PaymentRecord {
+        id,
+        payment_request,
+        payment_hash,
+        asset_id,
+        asset_amount: asset_amount.max(0) as u64,
+        rfq_id,
+        status: PaymentStatus::parse(&status)?,
+        value_msat: value_msat.max(0) as u64,
+        fee_msat: fee_msat.max(0) as u64,
+        failure_reason,
+        created_at,
+        updated_at,
+    })
+}
+
+#[async_trait::async_trait]
+impl PaymentStore for PostgresPaymentStore {
+    async fn record(&self, payment: &PaymentRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO payments
+                (id, payment_request, payment_hash, asset_id, asset_amount, rfq_id, status,
+                 value_msat, fee_msat, failure_reason, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(payment.id)
+        .bind(&payment.payment_request)
+        .bind(&payment.payment_hash)
+        .bind(&payment.asset_id)
+        .bind(payment.asset_amount as i64)
+        .bind(&payment.rfq_id)
+        .bind(payment.status.as_str())
+        .bind(payment.value_msat as i64)
+        .bind(payment.fee_msat as i64)
+        .bind(&payment.failure_reason)
+        .bind(payment.created_at)
+        .bind(payment.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn complete(&self, id: Uuid, outcome: &PaymentOutcome) -> Result<bool, AppError> {
+        let status = if outcome.succeeded {
+            PaymentStatus::Succeeded
+        } else {
+            PaymentStatus::Failed
+        };
+
+        let result = sqlx::query(
+            "UPDATE payments SET
+                status = $2,
+                payment_hash = COALESCE($3, payment_hash),
+                rfq_id = COALESCE($4, rfq_id),
+                value_msat = $5,
+                fee_msat = $6,
+                failure_reason = $7,
+                updated_at = $8
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(&outcome.payment_hash)
+        .bind(&outcome.rfq_id)
+        .bind(outcome.value_msat as i64)
+        .bind(outcome.fee_msat as i64)
+        .bind(&outcome.failure_reason)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self, query: &PaymentQuery) -> Result<Vec<PaymentRecord>, AppError> {
+        let rows = sqlx::query_as::<_, PaymentRow>(
+            "SELECT id, payment_request, payment_hash, asset_id, asset_amount, rfq_id, status,
+                    value_msat, fee_msat, failure_reason, created_at, updated_at
+             FROM payments
+             WHERE ($1::TEXT IS NULL OR status = $1)
+               AND ($2::TEXT IS NULL OR asset_id = $2)
+               AND ($3::BIGINT IS NULL OR created_at >= $3)
+               AND ($4::BIGINT IS NULL OR created_at <= $4)
+             ORDER BY created_at DESC
+             LIMIT $5 OFFSET $6",
+        )
+        .bind(query.status.map(|s| s.as_str()))
+        .bind(&query.asset_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .bind(query.offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(payment_from_row).collect()
+    }
+}
+
+/// SQLite-backed payment history using the `payments` table
+pub struct SqlitePaymentStore {
+    pool: SqlitePool,
+}
+
+impl SqlitePaymentStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentStore for SqlitePaymentStore {
+    async fn record(&self, payment: &PaymentRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO payments
+                (id, payment_request, payment_hash, asset_id, asset_amount, rfq_id, status,
+                 value_msat, fee_msat, failure_reason, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(payment.id)
+        .bind(&payment.payment_request)
+        .bind(&payment.payment_hash)
+        .bind(&payment.asset_id)
+        .bind(payment.asset_amount as i64)
+        .bind(&payment.rfq_id)
+        .bind(payment.status.as_str())
+        .bind(payment.value_msat as i64)
+        .bind(payment.fee_msat as i64)
+        .bind(&payment.failure_reason)
+        .bind(payment.created_at)
+        .bind(payment.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn complete(&self, id: Uuid, outcome: &PaymentOutcome) -> Result<bool, AppError> {
+        let status = if outcome.succeeded {
+            PaymentStatus::Succeeded
+        } else {
+            PaymentStatus::Failed
+        };
+
+        let result = sqlx::query(
+            "UPDATE payments SET
+                status = $2,
+                payment_hash = COALESCE($3, payment_hash),
+                rfq_id = COALESCE($4, rfq_id),
+                value_msat = $5,
+                fee_msat = $6,
+                failure_reason = $7,
+                updated_at = $8
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(&outcome.payment_hash)
+        .bind(&outcome.rfq_id)
+        .bind(outcome.value_msat as i64)
+        .bind(outcome.fee_msat as i64)
+        .bind(&outcome.failure_reason)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self, query: &PaymentQuery) -> Result<Vec<PaymentRecord>, AppError> {
+        let rows = sqlx::query_as::<_, PaymentRow>(
+            "SELECT id, payment_request, payment_hash, asset_id, asset_amount, rfq_id, status,
+                    value_msat, fee_msat, failure_reason, created_at, updated_at
+             FROM payments
+             WHERE ($1 IS NULL OR status = $1)
+               AND ($2 IS NULL OR asset_id = $2)
+               AND ($3 IS NULL OR created_at >= $3)
+               AND ($4 IS NULL OR created_at <= $4)
+             ORDER BY created_at DESC
+             LIMIT $5 OFFSET $6",
+        )
+        .bind(query.status.map(|s| s.as_str()))
+        .bind(&query.asset_id)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.limit as i64)
+        .bind(query.offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(payment_from_row).collect()
+    }
+}
+
+/// Builds the payment store selected by the configured backend
+pub async fn create_payment_store(
+    settings: &PaymentStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn PaymentStore>> {
+    info!("Using {:?} payment store", settings.backend);
+
+    let store: Arc<dyn PaymentStore> = match settings.backend {
+        PaymentStoreBackend::Memory => Arc::new(InMemoryPaymentStore::new()),
+        PaymentStoreBackend::Postgres => match storage_for(storage, "payment") {
+            Some(storage) => storage.payments(),
+            None => Arc::new(InMemoryPaymentStore::new()),
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn assert_complete_and_filter_payments(store: &dyn PaymentStore) {
+        let invoice = Some("lnbc1".to_string());
+        let mut usd = PaymentRecord::in_flight("usd".to_string(), 100, invoice, None);
+        usd.created_at = 1_000;
+        let mut eur = PaymentRecord::in_flight("eur".to_string(), 50, None, None);
+        eur.created_at = 2_000;
+        store.record(&usd).await.unwrap();
+        store.record(&eur).await.unwrap();
+
+        let outcome = PaymentOutcome {
+            succeeded: true,
+            payment_hash: Some("hash".to_string()),
+            rfq_id: Some("quote".to_string()),
+            value_msat: 100_000,
+            fee_msat: 1_500,
+            failure_reason: None,
+        };
+        assert!(store.complete(usd.id, &outcome).await.unwrap());
+        assert!(!store.complete(Uuid::new_v4(), &outcome).await.unwrap());
+
+        let all = PaymentQuery {
+            limit: 10,
+            ..Default::default()
+        };
+        let payments = store.list(&all).await.unwrap();
+        assert_eq!(payments[0].asset_id, "eur");
+
+        let succeeded = store
+            .list(&PaymentQuery {
+                status: Some(PaymentStatus::Succeeded),
+                ..all.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(succeeded.len(), 1);
+        assert_eq!(succeeded[0].value_msat, 100_000);
+        assert_eq!(succeeded[0].fee_msat, 1_500);
+        assert_eq!(succeeded[0].rfq_id.as_deref(), Some("quote"));
+
+        let window = store
+            .list(&PaymentQuery {
+                from: Some(1_500),
+                to: Some(2_500),
+                ..all.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(window.len(), 1);
+        assert_eq!(window[0].asset_id, "eur");
+
+        let second_page = store
+            .list(&PaymentQuery {
+                limit: 1,
+                offset: 1,
+                ..all
+            })
+            .await
+            .unwrap();
+        assert_eq!(second_page[0].asset_id, "usd");
+    }
+
+    #[tokio::test]
+    async fn test_complete_and_filter_payments() {
+        assert_complete_and_filter_payments(&InMemoryPaymentStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_complete_and_filter_payments() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_complete_and_filter_payments(storage.payments().as_ref()).await;
+    }
+
+    #[test]
+    fn test_status_round_trips() {
+        for status in [PaymentStatus::InFlight, PaymentStatus::Succeeded, PaymentStatus::Failed] {
+            assert_eq!(PaymentStatus::parse(status.as_str()).unwrap(), status);
+        }
+    }
+}
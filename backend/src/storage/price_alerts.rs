@@ -0,0 +1,487 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+use super::database::{storage_for, Storage};
+use crate::config::{PriceAlertSettings, PriceAlertStoreBackend};
+use crate::error::AppError;
+use crate::secrets::SecretBox;
+
+/// Which side of the threshold fires the alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDirection {
+    /// Fires once the rate is at or above the threshold
+    Above,
+    /// Fires once the rate is at or below the threshold
+    Below,
+}
+
+impl AlertDirection {
+    pub fn is_met(&self, rate: f64, threshold: f64) -> bool {
+        match self {
+            AlertDirection::Above => rate >= threshold,
+            AlertDirection::Below => rate <= threshold,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AlertDirection::Above => "above",
+            AlertDirection::Below => "below",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "above" => Ok(AlertDirection::Above),
+            "below" => Ok(AlertDirection::Below),
+            other => Err(AppError::StorageError(format!("Unknown alert direction: {other}"))),
+        }
+    }
+}
+
+/// A one-shot alert on an asset's oracle rate, in asset units per BTC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceAlert {
+    pub id: Uuid,
+    pub asset_id: String,
+    pub direction: AlertDirection,
+    pub threshold: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// Signs webhook deliveries; only returned when the alert is created
+    #[serde(skip_serializing)]
+    pub webhook_secret: Option<String>,
+    pub created_at: i64,
+    pub triggered_at: Option<i64>,
+    pub triggered_rate: Option<f64>,
+}
+
+/// Registered price alerts, polled by the alert monitor
+#[async_trait::async_trait]
+pub trait PriceAlertStore: Send + Sync {
+    async fn create(&self, alert: &PriceAlert) -> Result<(), AppError>;
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError>;
+    /// Returns alerts, optionally for one asset, oldest first
+    async fn list(&self, asset_id: Option<&str>) -> Result<Vec<PriceAlert>, AppError>;
+    /// Alerts that have not fired yet
+    async fn pending(&self) -> Result<Vec<PriceAlert>, AppError>;
+    /// Marks a pending alert as fired; returns false if it is unknown or already fired
+    async fn mark_triggered(&self, id: Uuid, at: i64, rate: f64) -> Result<bool, AppError>;
+}
+
+/// Process-local alert registry
+#[derive(Default)]
+pub struct InMemoryPriceAlertStore {
+    alerts: RwLock<Vec<PriceAlert>>,
+}
+
+impl InMemoryPriceAlertStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl PriceAlertStore for InMemoryPriceAlertStore {
+    async fn create(&self, alert: &PriceAlert) -> Result<(), AppError> {
+        self.alerts.write().unwrap().push(alert.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let mut alerts = self.alerts.write().unwrap();
+        let before = alerts.len();
+        alerts.retain(|alert| alert.id != id);
+        Ok(alerts.len() != before)
+    }
+
+    async fn list(&self, asset_id: Option<&str>) -> Result<Vec<PriceAlert>, AppError> {
+        Ok(self
+            .alerts
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|alert| asset_id.is_none_or(|a| alert.asset_id == a))
+            .cloned()
+            .collect())
+    }
+
+    async fn pending(&self) -> Result<Vec<PriceAlert>, AppError> {
+        Ok(self
+            .alerts
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|alert| alert.triggered_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_triggered(&self, id: Uuid, at: i64, rate: f64) -> Result<bool, AppError> {
+        let mut alerts = self.alerts.write().unwrap();
+        let Some(alert) = alerts
+            .iter_mut()
+            .find(|alert| alert.id == id && alert.triggered_at.is_none())
+        else {
+            return Ok(false);
+        };
+        alert.triggered_at = Some(at);
+        alert.triggered_rate = Some(rate);
+        Ok(true)
+    }
+}
+
+/// Postgres-backed alerts using the `price_alerts` table
+pub struct PostgresPriceAlertStore {
+    pool: PgPool,
+    secrets: Arc<SecretBox>,
+}
+
+impl PostgresPriceAlertStore {
+    pub fn new(pool: PgPool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals webhook secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, webhook_secret FROM price_alerts WHERE webhook_secret IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE price_alerts SET webhook_secret = $1 WHERE id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+type PriceAlertRow = (
+    Uuid,
+    String,
+    String,
+    f64,
+    Option<String>,
+    Option<String>,
+    i64,
+    Option<i64>,
+    Option<f64>,
+);
+
+const ALERT_COLUMNS: &str = "id, asset_id, direction, threshold, webhook_url, webhook_secret, \
+                             created_at, triggered_at, triggered_rate";
+
+fn alert_from_row(row: PriceAlertRow, secrets: &SecretBox) -> Result<PriceAlert, AppError> {
+    let (
+        id,
+        asset_id,
+        direction,
+        threshold,
+        webhook_url,
+        webhook_secret,
+        created_at,
+        triggered_at,
+        triggered_rate,
+    ) = row;
+    Ok(PriceAlert {
+        id,
+        asset_id,
+        direction: AlertDirection::parse(&direction)?,
+        threshold,
+        webhook_url,
+        webhook_secret: secrets.open_optional(webhook_secret.as_deref())?,
+        created_at,
+        triggered_at,
+        triggered_rate,
+    })
+}
+
+#[async_trait::async_trait]
+impl PriceAlertStore for PostgresPriceAlertStore {
+    async fn create(&self, alert: &PriceAlert) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO price_alerts
+                (id, asset_id, direction, threshold, webhook_url, webhook_secret, created_at,
+                 triggered_at, triggered_rate)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(alert.id)
+        .bind(&alert.asset_id)
+        .bind(alert.direction.as_str())
+        .bind(alert.threshold)
+        .bind(&alert.webhook_url)
+        .bind(self.secrets.seal_optional(alert.webhook_secret.as_deref())?)
+        .bind(alert.created_at)
+        .bind(alert.triggered_at)
+        .bind(alert.triggered_rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM price_alerts WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self, asset_id: Option<&str>) -> Result<Vec<PriceAlert>, AppError> {
+        let rows = sqlx::query_as::<_, PriceAlertRow>(&format!(
+            "SELECT {ALERT_COLUMNS} FROM price_alerts
+             WHERE $1::TEXT IS NULL OR asset_id = $1
+             ORDER BY created_at"
+        ))
+        .bind(asset_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| alert_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn pending(&self) -> Result<Vec<PriceAlert>, AppError> {
+        let rows = sqlx::query_as::<_, PriceAlertRow>(&format!(
+            "SELECT {ALERT_COLUMNS} FROM price_alerts
+             WHERE triggered_at IS NULL
+             ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| alert_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn mark_triggered(&self, id: Uuid, at: i64, rate: f64) -> Result<bool, AppError> {
+        // The IS NULL guard keeps two monitors from firing the same alert
+        let result = sqlx::query(
+            "UPDATE price_alerts SET triggered_at = $2, triggered_rate = $3
+             WHERE id = $1 AND triggered_at IS NULL",
+        )
+        .bind(id)
+        .bind(at)
+        .bind(rate)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// SQLite-backed alerts using the `price_alerts` table
+pub struct SqlitePriceAlertStore {
+    pool: SqlitePool,
+    secrets: Arc<SecretBox>,
+}
+
+impl SqlitePriceAlertStore {
+    pub fn new(pool: SqlitePool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals webhook secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, webhook_secret FROM price_alerts WHERE webhook_secret IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE price_alerts SET webhook_secret = $1 WHERE id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+#[async_trait::async_trait]
+impl PriceAlertStore for SqlitePriceAlertStore {
+    async fn create(&self, alert: &PriceAlert) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO price_alerts
+                (id, asset_id, direction, threshold, webhook_url, webhook_secret, created_at,
+                 triggered_at, triggered_rate)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(alert.id)
+        .bind(&alert.asset_id)
+        .bind(alert.direction.as_str())
+        .bind(alert.threshold)
+        .bind(&alert.webhook_url)
+        .bind(self.secrets.seal_optional(alert.webhook_secret.as_deref())?)
+        .bind(alert.created_at)
+        .bind(alert.triggered_at)
+        .bind(alert.triggered_rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM price_alerts WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(&self, asset_id: Option<&str>) -> Result<Vec<PriceAlert>, AppError> {
+        let rows = sqlx::query_as::<_, PriceAlertRow>(&format!(
+            "SELECT {ALERT_COLUMNS} FROM price_alerts
+             WHERE $1 IS NULL OR asset_id = $1
+             ORDER BY created_at, rowid"
+        ))
+        .bind(asset_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| alert_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn pending(&self) -> Result<Vec<PriceAlert>, AppError> {
+        let rows = sqlx::query_as::<_, PriceAlertRow>(&format!(
+            "SELECT {ALERT_COLUMNS} FROM price_alerts
+             WHERE triggered_at IS NULL
+             ORDER BY created_at, rowid"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| alert_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn mark_triggered(&self, id: Uuid, at: i64, rate: f64) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE price_alerts SET triggered_at = $2, triggered_rate = $3
+             WHERE id = $1 AND triggered_at IS NULL",
+        )
+        .bind(id)
+        .bind(at)
+        .bind(rate)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Builds the price alert store selected by the configured backend
+pub async fn create_price_alert_store(
+    settings: &PriceAlertSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn PriceAlertStore>> {
+    info!("Using {:?} price alert store", settings.backend);
+
+    let store: Arc<dyn PriceAlertStore> = match settings.backend {
+        PriceAlertStoreBackend::Memory => Arc::new(InMemoryPriceAlertStore::new()),
+        PriceAlertStoreBackend::Postgres => match storage_for(storage, "price alert") {
+            Some(storage) => storage.price_alerts(),
+            None => Arc::new(InMemoryPriceAlertStore::new()),
+        },
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(asset_id: &str, created_at: i64) -> PriceAlert {
+        PriceAlert {
+            id: Uuid::new_v4(),
+            asset_id: asset_id.to_string(),
+            direction: AlertDirection::Above,
+            threshold: 100.0,
+            webhook_url: None,
+            webhook_secret: None,
+            created_at,
+            triggered_at: None,
+            triggered_rate: None,
+        }
+    }
+
+    async fn assert_alert_lifecycle(store: &dyn PriceAlertStore) {
+        let usd = alert("usd", 1);
+        let eur = alert("eur", 2);
+        store.create(&usd).await.unwrap();
+        store.create(&eur).await.unwrap();
+
+        assert_eq!(store.list(Some("usd")).await.unwrap(), vec![usd.clone()]);
+        assert!(store.mark_triggered(usd.id, 10, 101.0).await.unwrap());
+        assert!(!store.mark_triggered(usd.id, 11, 102.0).await.unwrap());
+
+        let pending = store.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, eur.id);
+        assert_eq!(store.list(Some("usd")).await.unwrap()[0].triggered_rate, Some(101.0));
+
+        assert!(store.delete(eur.id).await.unwrap());
+        assert!(!store.delete(eur.id).await.unwrap());
+        assert_eq!(store.list(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_alert_lifecycle() {
+        assert_alert_lifecycle(&InMemoryPriceAlertStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_alert_lifecycle() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_alert_lifecycle(storage.price_alerts().as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_webhook_secrets_are_sealed() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await.unwrap();
+        let plaintext = SqlitePriceAlertStore::new(pool.clone(), Arc::new(SecretBox::plaintext()));
+        let mut hooked = alert("usd", 1);
+        hooked.webhook_url = Some("https://example.com/alerts".to_string());
+        hooked.webhook_secret = Some("secret".to_string());
+        plaintext.create(&hooked).await.unwrap();
+        plaintext.create(&alert("eur", 2)).await.unwrap();
+
+        let secrets = SecretBox::new("current-master-key-with-at-least-32-chars", &[]).unwrap();
+        let store = SqlitePriceAlertStore::new(pool.clone(), Arc::new(secrets));
+        assert_eq!(store.reseal_secrets().await.unwrap(), 1);
+        let (stored,) = sqlx::query_as::<_, (String,)>(
+            "SELECT webhook_secret FROM price_alerts WHERE webhook_secret IS NOT NULL",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_ne!(stored, "secret");
+        assert_eq!(store.list(Some("usd")).await.unwrap(), vec![hooked]);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert!(AlertDirection::Above.is_met(100.0, 100.0));
+        assert!(!AlertDirection::Above.is_met(99.0, 100.0));
+        assert!(AlertDirection::Below.is_met(99.0, 100.0));
+        assert!(!AlertDirection::Below.is_met(101.0, 100.0));
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+
+use sqlx::{types::Json, PgPool, SqlitePool};
+
+use crate::error::AppError;
+use crate::gateway::mailbox::ReceiverInfo;
+use crate::secrets::SecretBox;
+
+/// Registered mailbox receivers and the messages they have acknowledged
+#[async_trait::async_trait]
+pub trait ReceiverRepo: Send + Sync {
+    async fn store_receiver_info(&self, info: &ReceiverInfo) -> Result<(), AppError>;
+    async fn get_receiver_info(&self, receiver_id: &str) -> Result<Option<ReceiverInfo>, AppError>;
+    async fn delete_receiver_info(&self, receiver_id: &str) -> Result<bool, AppError>;
+    /// Records acknowledged message IDs; returns how many were newly marked delivered
+    async fn mark_messages_delivered(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<usize, AppError>;
+    /// Returns the subset of `message_ids` the receiver has already acknowledged
+    async fn get_delivered_message_ids(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<HashSet<String>, AppError>;
+}
+
+/// Process-local receiver registry
+#[derive(Default)]
+pub struct InMemoryReceiverRepo {
+    receivers: RwLock<HashMap<String, ReceiverInfo>>,
+    deliveries: RwLock<HashMap<String, HashSet<String>>>,
+}
+
+impl InMemoryReceiverRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl ReceiverRepo for InMemoryReceiverRepo {
+    async fn store_receiver_info(&self, info: &ReceiverInfo) -> Result<(), AppError> {
+        self.receivers
+            .write()
+            .unwrap()
+            .insert(info.receiver_id.clone(), info.clone());
+        Ok(())
+    }
+
+    async fn get_receiver_info(&self, receiver_id: &str) -> Result<Option<ReceiverInfo>, AppError> {
+        Ok(self.receivers.read().unwrap().get(receiver_id).cloned())
+    }
+
+    async fn delete_receiver_info(&self, receiver_id: &str) -> Result<bool, AppError> {
+        Ok(self.receivers.write().unwrap().remove(receiver_id).is_some())
+    }
+
+    async fn mark_messages_delivered(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<usize, AppError> {
+        let mut deliveries = self.deliveries.write().unwrap();
+        let delivered = deliveries.entry(receiver_id.to_string()).or_default();
+        Ok(message_ids
+            .iter()
+            .filter(|id| delivered.insert(id.to_string()))
+            .count())
+    }
+
+    async fn get_delivered_message_ids(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<HashSet<String>, AppError> {
+        let deliveries = self.deliveries.read().unwrap();
+        Ok(deliveries
+            .get(receiver_id)
+            .map(|delivered| {
+                message_ids
+                    .iter()
+                    .filter(|id| delivered.contains(*id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+type ReceiverRow = (
+    String,
+    String,
+    Option<String>,
+    i64,
+    i64,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+fn receiver_from_row(row: ReceiverRow, secrets: &SecretBox) -> Result<ReceiverInfo, AppError> {
+    let (
+        receiver_id,
+        public_key,
+        address,
+        created_at,
+        last_seen,
+        is_active,
+        metadata,
+        webhook_url,
+        webhook_secret,
+    ) = row;
+    Ok(ReceiverInfo {
+        receiver_id,
+        public_key,
+        address,
+        created_at,
+        last_seen,
+        is_active,
+        metadata: metadata.map(|m| serde_json::from_str(&m)).transpose()?,
+        webhook_url,
+        webhook_secret: secrets.open_optional(webhook_secret.as_deref())?,
+    })
+}
+
+/// Postgres-backed receiver registry using the `mailbox_receivers` table
+pub struct PostgresReceiverRepo {
+    pool: PgPool,
+    secrets: Arc<SecretBox>,
+}
+
+impl PostgresReceiverRepo {
+    pub fn new(pool: PgPool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals webhook secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT receiver_id, webhook_secret FROM mailbox_receivers
+             WHERE webhook_secret IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (receiver_id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE mailbox_receivers SET webhook_secret = $1 WHERE receiver_id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(receiver_id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+#[async_trait::async_trait]
+impl ReceiverRepo for PostgresReceiverRepo {
+    async fn store_receiver_info(&self, info: &ReceiverInfo) -> Result<(), AppError> {
+        let metadata = info.metadata.as_ref().map(|m| m.to_string());
+
+        sqlx::query(
+            "INSERT INTO mailbox_receivers
+                (receiver_id, public_key, address, created_at, last_seen, is_active, metadata,
+                 webhook_url, webhook_secret)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (receiver_id) DO UPDATE SET
+                public_key = $2, address = $3, last_seen = $5, is_active = $6, metadata = $7,
+                webhook_url = $8, webhook_secret = $9",
+        )
+        .bind(&info.receiver_id)
+        .bind(&info.public_key)
+        .bind(&info.address)
+        .bind(info.created_at)
+        .bind(info.last_seen)
+        .bind(info.is_active)
+        .bind(metadata)
+        .bind(&info.webhook_url)
+        .bind(self.secrets.seal_optional(info.webhook_secret.as_deref())?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_receiver_info(&self, receiver_id: &str) -> Result<Option<ReceiverInfo>, AppError> {
+        let row = sqlx::query_as::<_, ReceiverRow>(
+            "SELECT receiver_id, public_key, address, created_at, last_seen, is_active, metadata,
+                    webhook_url, webhook_secret
+             FROM mailbox_receivers WHERE receiver_id = $1",
+        )
+        .bind(receiver_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| receiver_from_row(row, &self.secrets)).transpose()
+    }
+
+    async fn delete_receiver_info(&self, receiver_id: &str) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM mailbox_receivers WHERE receiver_id = $1")
+            .bind(receiver_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_messages_delivered(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<usize, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO mailbox_deliveries (receiver_id, message_id, delivered_at)
+             SELECT $1, message_id, $3 FROM UNNEST($2::TEXT[]) AS message_id
+             ON CONFLICT (receiver_id, message_id) DO NOTHING",
+        )
+        .bind(receiver_id)
+        .bind(message_ids)
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn get_delivered_message_ids(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<HashSet<String>, AppError> {
+        let rows = sqlx::query_as::<_, (String,)>(
+            "SELECT message_id FROM mailbox_deliveries
+             WHERE receiver_id = $1 AND message_id = ANY($2)",
+        )
+        .bind(receiver_id)
+        .bind(message_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|(message_id,)| message_id).collect())
+    }
+}
+
+/// SQLite-backed receiver registry using the `mailbox_receivers` table
+pub struct SqliteReceiverRepo {
+    pool: SqlitePool,
+    secrets: Arc<SecretBox>,
+}
+
+impl SqliteReceiverRepo {
+    pub fn new(pool: SqlitePool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals webhook secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT receiver_id, webhook_secret FROM mailbox_receivers
+             WHERE webhook_secret IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut resealed = 0;
+        for (receiver_id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE mailbox_receivers SET webhook_secret = $1 WHERE receiver_id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(receiver_id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+#[async_trait::async_trait]
+impl ReceiverRepo for SqliteReceiverRepo {
+    async fn store_receiver_info(&self, info: &ReceiverInfo) -> Result<(), AppError> {
+        let metadata = info.metadata.as_ref().map(|m| m.to_string());
+
+        sqlx::query(
+            "INSERT INTO mailbox_receivers
+                (receiver_id, public_key, address, created_at, last_seen, is_active, metadata,
+                 webhook_url, webhook_secret)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (receiver_id) DO UPDATE SET
+                public_key = $2, address = $3, last_seen = $5, is_active = $6, metadata = $7,
+                webhook_url = $8, webhook_secret = $9",
+        )
+        .bind(&info.receiver_id)
+        .bind(&info.public_key)
+        .bind(&info.address)
+        .bind(info.created_at)
+        .bind(info.last_seen)
+        .bind(info.is_active)
+        .bind(metadata)
+        .bind(&info.webhook_url)
+        .bind(self.secrets.seal_optional(info.webhook_secret.as_deref())?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_receiver_info(&self, receiver_id: &str) -> Result<Option<ReceiverInfo>, AppError> {
+        let row = sqlx::query_as::<_, ReceiverRow>(
+            "SELECT receiver_id, public_key, address, created_at, last_seen, is_active, metadata,
+                    webhook_url, webhook_secret
+             FROM mailbox_receivers WHERE receiver_id = $1",
+        )
+        .bind(receiver_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| receiver_from_row(row, &self.secrets)).transpose()
+    }
+
+    async fn delete_receiver_info(&self, receiver_id: &str) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM mailbox_receivers WHERE receiver_id = $1")
+            .bind(receiver_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_messages_delivered(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<usize, AppError> {
+        // The IDs are passed as one JSON array in place of Postgres' UNNEST
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO mailbox_deliveries (receiver_id, message_id, delivered_at)
+             SELECT $1, value, $3 FROM json_each($2)",
+        )
+        .bind(receiver_id)
+        .bind(Json(message_ids))
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn get_delivered_message_ids(
+        &self,
+        receiver_id: &str,
+        message_ids: &[String],
+    ) -> Result<HashSet<String>, AppError> {
+        let rows = sqlx::query_as::<_, (String,)>(
+            "SELECT message_id FROM mailbox_deliveries
+             WHERE receiver_id = $1 AND message_id IN (SELECT value FROM json_each($2))",
+        )
+        .bind(receiver_id)
+        .bind(Json(message_ids))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|(message_id,)| message_id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_receiver(receiver_id: &str) -> ReceiverInfo {
+        ReceiverInfo {
+            receiver_id: receiver_id.to_string(),
+            public_key: "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+                .to_string(),
+            address: None,
+            created_at: 1_700_000_000,
+            last_seen: 1_700_000_000,
+            is_active: true,
+            metadata: None,
+            webhook_url: None,
+            webhook_secret: None,
+        }
+    }
+
+    async fn assert_receiver_store_roundtrip(store: &dyn ReceiverRepo) {
+        store
+            .store_receiver_info(&test_receiver("receiver_1"))
+            .await
+            .unwrap();
+
+        let stored = store.get_receiver_info("receiver_1").await.unwrap().unwrap();
+        assert_eq!(stored.receiver_id, "receiver_1");
+        assert!(stored.is_active);
+
+        assert!(store.delete_receiver_info("receiver_1").await.unwrap());
+        assert!(!store.delete_receiver_info("receiver_1").await.unwrap());
+        assert!(store.get_receiver_info("receiver_1").await.unwrap().is_none());
+    }
+
+    async fn assert_delivery_tracking(store: &dyn ReceiverRepo) {
+        let ids = vec!["msg_1".to_string(), "msg_2".to_string()];
+
+        assert_eq!(store.mark_messages_delivered("receiver_1", &ids[..1]).await.unwrap(), 1);
+        // Re-acknowledging is idempotent
+        assert_eq!(store.mark_messages_delivered("receiver_1", &ids).await.unwrap(), 1);
+
+        let delivered = store
+            .get_delivered_message_ids("receiver_1", &ids)
+            .await
+            .unwrap();
+        assert_eq!(delivered.len(), 2);
+
+        // Deliveries are scoped per receiver
+        assert!(store
+            .get_delivered_message_ids("receiver_2", &ids)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_receiver_store_roundtrip() {
+        assert_receiver_store_roundtrip(&InMemoryReceiverRepo::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_delivery_tracking() {
+        assert_delivery_tracking(&InMemoryReceiverRepo::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_receivers_and_deliveries() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_receiver_store_roundtrip(storage.receivers().as_ref()).await;
+        assert_delivery_tracking(storage.receivers().as_ref()).await;
+    }
+}
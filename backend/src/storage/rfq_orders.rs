@@ -0,0 +1,548 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+use super::database::{storage_for, Storage};
+use crate::config::{RfqOrderStoreBackend, RfqOrderStoreSettings};
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RfqOrderKind {
+    BuyOffer,
+    BuyOrder,
+    SellOffer,
+    SellOrder,
+}
+
+impl RfqOrderKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RfqOrderKind::BuyOffer => "buy_offer",
+            RfqOrderKind::BuyOrder => "buy_order",
+            RfqOrderKind::SellOffer => "sell_offer",
+            RfqOrderKind::SellOrder => "sell_order",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "buy_offer" => Ok(RfqOrderKind::BuyOffer),
+            "buy_order" => Ok(RfqOrderKind::BuyOrder),
+            "sell_offer" => Ok(RfqOrderKind::SellOffer),
+            "sell_order" => Ok(RfqOrderKind::SellOrder),
+            other => Err(AppError::StorageError(format!("Unknown RFQ order kind: {other}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RfqOrderStatus {
+    /// A standing offer tapd registered; offers get no quote of their own
+    Submitted,
+    /// The peer accepted the quote request
+    Accepted,
+    /// The peer rejected the request or returned an invalid quote
+    Rejected,
+    /// The upstream call itself failed
+    Failed,
+}
+
+impl RfqOrderStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RfqOrderStatus::Submitted => "submitted",
+            RfqOrderStatus::Accepted => "accepted",
+            RfqOrderStatus::Rejected => "rejected",
+            RfqOrderStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "submitted" => Ok(RfqOrderStatus::Submitted),
+            "accepted" => Ok(RfqOrderStatus::Accepted),
+            "rejected" => Ok(RfqOrderStatus::Rejected),
+            "failed" => Ok(RfqOrderStatus::Failed),
+            other => Err(AppError::StorageError(format!("Unknown RFQ order status: {other}"))),
+        }
+    }
+}
+
+/// An RFQ offer or order submitted through the gateway, with tapd's answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RfqOrderRecord {
+    pub id: Uuid,
+    pub kind: RfqOrderKind,
+    pub asset_id: String,
+    pub status: RfqOrderStatus,
+    /// Quote ID from an accepted, rejected or invalid quote
+    pub rfq_id: Option<String>,
+    pub peer: Option<String>,
+    pub request: serde_json::Value,
+    pub response: Option<serde_json::Value>,
+    pub failure_reason: Option<String>,
+    pub created_at: i64,
+}
+
+impl RfqOrderRecord {
+    /// Records the result of submitting `request`; orders are classified by
+    /// which quote variant tapd answered with
+    pub fn new(
+        kind: RfqOrderKind,
+        asset_id: String,
+        request: serde_json::Value,
+        result: Result<&serde_json::Value, &AppError>,
+    ) -> Self {
+        let requested_peer = request
+            .get("peer_pub_key")
+            .and_then(serde_json::Value::as_str)
+            .filter(|peer| !peer.is_empty())
+            .map(str::to_string);
+        let mut record = Self {
+            id: Uuid::new_v4(),
+            kind,
+            asset_id,
+            status: RfqOrderStatus::Failed,
+            rfq_id: None,
+            peer: requested_peer,
+            request,
+            response: None,
+            failure_reason: None,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                record.failure_reason = Some(e.to_string());
+                return record;
+            }
+        };
+        record.response = Some(response.clone());
+
+        let quote = ["accepted_quote", "rejected_quote", "invalid_quote"]
+            .into_iter()
+            .find_map(|variant| response.get(variant).map(|quote| (variant, quote)));
+        let text = |quote: &serde_json::Value, field: &str| {
+            quote
+                .get(field)
+                .and_then(serde_json::Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        match quote {
+            Some((variant, quote)) => {
+                record.rfq_id = text(quote, "id");
+                record.peer = text(quote, "peer").or(record.peer.take());
+                if variant == "accepted_quote" {
+                    record.status = RfqOrderStatus::Accepted;
+                } else {
+                    record.status = RfqOrderStatus::Rejected;
+                    record.failure_reason = text(quote, "error_message")
+                        .or_else(|| text(quote, "status"))
+                        .or_else(|| Some(variant.replace('_', " ")));
+                }
+            }
+            None => record.status = RfqOrderStatus::Submitted,
+        }
+        record
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RfqOrderQuery {
+    pub kind: Option<RfqOrderKind>,
+    pub status: Option<RfqOrderStatus>,
+    pub asset_id: Option<String>,
+    /// Quote ID an order was answered with
+    pub rfq_id: Option<String>,
+    /// Only orders created at or after this unix timestamp
+    pub since: Option<i64>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl RfqOrderQuery {
+    fn matches(&self, order: &RfqOrderRecord) -> bool {
+        self.kind.is_none_or(|k| order.kind == k)
+            && self.status.is_none_or(|s| order.status == s)
+            && self.asset_id.as_ref().is_none_or(|a| &order.asset_id == a)
+            && self.rfq_id.as_ref().is_none_or(|id| order.rfq_id.as_ref() == Some(id))
+            && self.since.is_none_or(|since| order.created_at >= since)
+    }
+}
+
+/// Quote history kept by the gateway, since tapd does not retain it in a
+/// queryable form
+#[async_trait::async_trait]
+pub trait RfqOrderStore: Send + Sync {
+    async fn record(&self, order: &RfqOrderRecord) -> Result<(), AppError>;
+    /// Returns matching offers and orders, newest first
+    async fn list(&self, query: &RfqOrderQuery) -> Result<Vec<RfqOrderRecord>, AppError>;
+}
+
+/// Process-local RFQ order history
+#[derive(Default)]
+pub struct InMemoryRfqOrderStore {
+    orders: RwLock<Vec<RfqOrderRecord>>,
+}
+
+impl InMemoryRfqOrderStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl RfqOrderStore for InMemoryRfqOrderStore {
+    async fn record(&self, order: &RfqOrderRecord) -> Result<(), AppError> {
+        self.orders.write().unwrap().push(order.clone());
+        Ok(())
+    }
+
+    async fn list(&self, query: &RfqOrderQuery) -> Result<Vec<RfqOrderRecord>, AppError> {
+        let orders = self.orders.read().unwrap();
+        let mut matching: Vec<RfqOrderRecord> =
+            orders.iter().filter(|o| query.matches(o)).cloned().collect();
+        matching.sort_by_key(|o| std::cmp::Reverse(o.created_at));
+        Ok(matching
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect())
+    }
+}
+
+/// Postgres-backed RFQ order history using the `rfq_orders` table
+pub struct PostgresRfqOrderStore {
+    pool: PgPool,
+}
+
+impl PostgresRfqOrderStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+type RfqOrderRow = (
+    Uuid,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Json<serde_json::Value>,
+    Option<Json<serde_json::Value>>,
+    Option<String>,
+    i64,
+);
+
+fn rfq_order_from_row(row: RfqOrderRow) -> Result<RfqOrderRecord, AppError> {
+    let (
+        id,
+        kind,
+        asset_id,
+        status,
+        rfq_id,
+        peer,
+        Json(request),
+        response,
+        failure_reason,
+        created_at,
+    ) = row;
+    Ok(RfqOrderRecord {
+        id,
+        kind: RfqOrderKind::parse(&kind)?,
+        asset_id,
+        status: RfqOrderStatus::parse(&status)?,
+        rfq_id,
+        peer,
+        request,
+        response: response.map(|Json(response)| response),
+        failure_reason,
+        created_at,
+    })
+}
+
+#[async_trait::async_trait]
+impl RfqOrderStore for PostgresRfqOrderStore {
+    async fn record(&self, order: &RfqOrderRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO rfq_orders
+                (id, kind, asset_id, status, rfq_id, peer, request, response, failure_reason,
+                 created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(order.id)
+        .bind(order.kind.as_str())
+        .bind(&order.asset_id)
+        .bind(order.status.as_str())
+        .bind(&order.rfq_id)
+        .bind(&order.peer)
+        .bind(Json(&order.request))
+        .bind(order.response.as_ref().map(Json))
+        .bind(&order.failure_reason)
+        .bind(order.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, query: &RfqOrderQuery) -> Result<Vec<RfqOrderRecord>, AppError> {
+        let rows = sqlx::query_as::<_, RfqOrderRow>(
+            "SELECT id, kind, asset_id, status, rfq_id, peer, request, response, failure_reason,
+                    created_at
+             FROM rfq_orders
+             WHERE ($1::TEXT IS NULL OR kind = $1)
+               AND ($2::TEXT IS NULL OR status = $2)
+               AND ($3::TEXT IS NULL OR asset_id = $3)
+               AND ($4::TEXT IS NULL OR rfq_id = $4)
+               AND ($5::BIGINT IS NULL OR created_at >= $5)
+             ORDER BY created_at DESC
+             LIMIT $6 OFFSET $7",
+        )
+        .bind(query.kind.map(|k| k.as_str()))
+        .bind(query.status.map(|s| s.as_str()))
+        .bind(&query.asset_id)
+        .bind(&query.rfq_id)
+        .bind(query.since)
+        .bind(query.limit as i64)
+        .bind(query.offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(rfq_order_from_row).collect()
+    }
+}
+
+/// SQLite-backed RFQ order history using the `rfq_orders` table
+pub struct SqliteRfqOrderStore {
+    pool: SqlitePool,
+}
+
+impl SqliteRfqOrderStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl RfqOrderStore for SqliteRfqOrderStore {
+    async fn record(&self, order: &RfqOrderRecord) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO rfq_orders
+                (id, kind, asset_id, status, rfq_id, peer, request, response, failure_reason,
+                 created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(order.id)
+        .bind(order.kind.as_str())
+        .bind(&order.asset_id)
+        .bind(order.status.as_str())
+        .bind(&order.rfq_id)
+        .bind(&order.peer)
+        .bind(Json(&order.request))
+        .bind(order.response.as_ref().map(Json))
+        .bind(&order.failure_reason)
+        .bind(order.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, query: &RfqOrderQuery) -> Result<Vec<RfqOrderRecord>, AppError> {
+        let rows = sqlx::query_as::<_, RfqOrderRow>(
+            "SELECT id, kind, asset_id, status, rfq_id, peer, request, response, failure_reason,
+                    created_at
+             FROM rfq_orders
+             WHERE ($1 IS NULL OR kind = $1)
+               AND ($2 IS NULL OR status = $2)
+               AND ($3 IS NULL OR asset_id = $3)
+               AND ($4 IS NULL OR rfq_id = $4)
+               AND ($5 IS NULL OR created_at >= $5)
+             ORDER BY created_at DESC
+             LIMIT $6 OFFSET $7",
+        )
+        .bind(query.kind.map(|k| k.as_str()))
+        .bind(query.status.map(|s| s.as_str()))
+        .bind(&query.asset_id)
+        .bind(&query.rfq_id)
+        .bind(query.since)
+        .bind(query.limit as i64)
+        .bind(query.offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(rfq_order_from_row).collect()
+    }
+}
+
+/// Builds the RFQ order store selected by the configured backend
+pub async fn create_rfq_order_store(
+    settings: &RfqOrderStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn RfqOrderStore>> {
+    info!("Using {:?} RFQ order store", settings.backend);
+
+    let store: Arc<dyn RfqOrderStore> = match settings.backend {
+        RfqOrderStoreBackend::Memory => Arc::new(InMemoryRfqOrderStore::new()),
+        RfqOrderStoreBackend::Postgres => match storage_for(storage, "RFQ order") {
+            Some(storage) => storage.rfq_orders(),
+            None => Arc::new(InMemoryRfqOrderStore::new()),
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_upstream_results() {
+        let request = serde_json::json!({"peer_pub_key": "02aa", "asset_max_amt": "100"});
+
+        let accepted = serde_json::json!({"accepted_quote": {"id": "cXVvdGU=", "peer": "02bb"}});
+        let order = RfqOrderRecord::new(
+            RfqOrderKind::BuyOrder,
+            "usd".to_string(),
+            request.clone(),
+            Ok(&accepted),
+        );
+        assert_eq!(order.status, RfqOrderStatus::Accepted);
+        assert_eq!(order.rfq_id.as_deref(), Some("cXVvdGU="));
+        assert_eq!(order.peer.as_deref(), Some("02bb"));
+
+        let rejected = serde_json::json!({
+            "rejected_quote": {"id": "cXVvdGU=", "error_message": "price oracle unavailable"}
+        });
+        let order = RfqOrderRecord::new(
+            RfqOrderKind::SellOrder,
+            "usd".to_string(),
+            request.clone(),
+            Ok(&rejected),
+        );
+        assert_eq!(order.status, RfqOrderStatus::Rejected);
+        assert_eq!(order.failure_reason.as_deref(), Some("price oracle unavailable"));
+        assert_eq!(order.peer.as_deref(), Some("02aa"));
+
+        let offer = RfqOrderRecord::new(
+            RfqOrderKind::BuyOffer,
+            "usd".to_string(),
+            serde_json::json!({"max_units": "10"}),
+            Ok(&serde_json::json!({})),
+        );
+        assert_eq!(offer.status, RfqOrderStatus::Submitted);
+        assert_eq!(offer.peer, None);
+
+        let error = AppError::RequestError("connection refused".to_string());
+        let failed =
+            RfqOrderRecord::new(RfqOrderKind::SellOffer, "usd".to_string(), request, Err(&error));
+        assert_eq!(failed.status, RfqOrderStatus::Failed);
+        assert_eq!(failed.response, None);
+        assert!(failed.failure_reason.unwrap().contains("connection refused"));
+    }
+
+    async fn assert_filter_and_page_orders(store: &dyn RfqOrderStore) {
+        let empty = serde_json::json!({});
+        for (index, (kind, asset_id)) in [
+            (RfqOrderKind::BuyOffer, "usd"),
+            (RfqOrderKind::BuyOrder, "usd"),
+            (RfqOrderKind::SellOrder, "eur"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut order =
+                RfqOrderRecord::new(kind, asset_id.to_string(), empty.clone(), Ok(&empty));
+            order.created_at = index as i64;
+            store.record(&order).await.unwrap();
+        }
+
+        let all = RfqOrderQuery {
+            limit: 10,
+            ..Default::default()
+        };
+        let orders = store.list(&all).await.unwrap();
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0].asset_id, "eur");
+
+        let usd = store
+            .list(&RfqOrderQuery {
+                asset_id: Some("usd".to_string()),
+                ..all.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(usd.len(), 2);
+
+        let by_quote = store
+            .list(&RfqOrderQuery {
+                rfq_id: Some("missing".to_string()),
+                ..all.clone()
+            })
+            .await
+            .unwrap();
+        assert!(by_quote.is_empty());
+
+        let recent = store
+            .list(&RfqOrderQuery {
+                since: Some(1),
+                ..all.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 2);
+
+        let buy_offers = store
+            .list(&RfqOrderQuery {
+                kind: Some(RfqOrderKind::BuyOffer),
+                status: Some(RfqOrderStatus::Submitted),
+                limit: 1,
+                ..all
+            })
+            .await
+            .unwrap();
+        assert_eq!(buy_offers[0].kind, RfqOrderKind::BuyOffer);
+    }
+
+    #[tokio::test]
+    async fn test_filter_and_page_orders() {
+        assert_filter_and_page_orders(&InMemoryRfqOrderStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_filter_and_page_orders() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_filter_and_page_orders(storage.rfq_orders().as_ref()).await;
+    }
+
+    #[test]
+    fn test_kind_and_status_round_trip() {
+        for kind in [
+            RfqOrderKind::BuyOffer,
+            RfqOrderKind::BuyOrder,
+            RfqOrderKind::SellOffer,
+            RfqOrderKind::SellOrder,
+        ] {
+            assert_eq!(RfqOrderKind::parse(kind.as_str()).unwrap(), kind);
+        }
+        for status in [
+            RfqOrderStatus::Submitted,
+            RfqOrderStatus::Accepted,
+            RfqOrderStatus::Rejected,
+            RfqOrderStatus::Failed,
+        ] {
+            assert_eq!(RfqOrderStatus::parse(status.as_str()).unwrap(), status);
+        }
+    }
+}
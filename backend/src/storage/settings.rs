@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{PgPool, SqlitePool};
+use tracing::info;
+
+use super::database::{storage_for, Storage};
+use crate::config::{SettingsStoreBackend, SettingsStoreSettings};
+use crate::error::AppError;
+
+/// One app or user preference, such as the default fee rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Setting {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub updated_at: i64,
+}
+
+/// Preferences kept server-side, by key
+#[async_trait::async_trait]
+pub trait SettingsStore: Send + Sync {
+    /// Every setting, by key
+    async fn list(&self) -> Result<Vec<Setting>, AppError>;
+    async fn get(&self, key: &str) -> Result<Option<Setting>, AppError>;
+    /// Inserts or replaces the setting for its key
+    async fn put(&self, setting: &Setting) -> Result<(), AppError>;
+}
+
+/// Process-local settings
+#[derive(Default)]
+pub struct InMemorySettingsStore {
+    settings: RwLock<BTreeMap<String, Setting>>,
+}
+
+impl InMemorySettingsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsStore for InMemorySettingsStore {
+    async fn list(&self) -> Result<Vec<Setting>, AppError> {
+        Ok(self.settings.read().unwrap().values().cloned().collect())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Setting>, AppError> {
+        Ok(self.settings.read().unwrap().get(key).cloned())
+    }
+
+    async fn put(&self, setting: &Setting) -> Result<(), AppError> {
+        self.settings.write().unwrap().insert(setting.key.clone(), setting.clone());
+        Ok(())
+    }
+}
+
+type SettingRow = (String, Json<serde_json::Value>, i64);
+
+fn setting_from_row((key, Json(value), updated_at): SettingRow) -> Setting {
+    Setting {
+        key,
+        value,
+        updated_at,
+    }
+}
+
+/// Postgres-backed settings using the `settings` table
+pub struct PostgresSettingsStore {
+    pool: PgPool,
+}
+
+impl PostgresSettingsStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsStore for PostgresSettingsStore {
+    async fn list(&self) -> Result<Vec<Setting>, AppError> {
+        let rows = sqlx::query_as::<_, SettingRow>(
+            "SELECT key, value, updated_at FROM settings ORDER BY key",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(setting_from_row).collect())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Setting>, AppError> {
+        let row = sqlx::query_as::<_, SettingRow>(
+            "SELECT key, value, updated_at FROM settings WHERE key = $1",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(setting_from_row))
+    }
+
+    async fn put(&self, setting: &Setting) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES ($1, $2, $3)
+             ON CONFLICT (key) DO UPDATE
+                SET value = EXCLUDED.value, updated_at = EXCLUDED.updated_at",
+        )
+        .bind(&setting.key)
+        .bind(Json(&setting.value))
+        .bind(setting.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// SQLite-backed settings using the `settings` table
+pub struct SqliteSettingsStore {
+    pool: SqlitePool,
+}
+
+impl SqliteSettingsStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsStore for SqliteSettingsStore {
+    async fn list(&self) -> Result<Vec<Setting>, AppError> {
+        let rows = sqlx::query_as::<_, SettingRow>(
+            "SELECT key, value, updated_at FROM settings ORDER BY key",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(setting_from_row).collect())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Setting>, AppError> {
+        let row = sqlx::query_as::<_, SettingRow>(
+            "SELECT key, value, updated_at FROM settings WHERE key = $1",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(setting_from_row))
+    }
+
+    async fn put(&self, setting: &Setting) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES ($1, $2, $3)
+             ON CONFLICT (key) DO UPDATE
+                SET value = excluded.value, updated_at = excluded.updated_at",
+        )
+        .bind(&setting.key)
+        .bind(Json(&setting.value))
+        .bind(setting.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Builds the settings store selected by the configured backend
+pub async fn create_settings_store(
+    settings: &SettingsStoreSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn SettingsStore>> {
+    info!("Using {:?} settings store", settings.backend);
+
+    let store: Arc<dyn SettingsStore> = match settings.backend {
+        SettingsStoreBackend::Memory => Arc::new(InMemorySettingsStore::new()),
+        SettingsStoreBackend::Postgres => match storage_for(storage, "settings") {
+            Some(storage) => storage.settings(),
+            None => Arc::new(InMemorySettingsStore::new()),
+        },
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn assert_settings_are_replaced_by_key(store: &dyn SettingsStore) {
+        let mut fee_rate = Setting {
+            key: "default_fee_rate".to_string(),
+            value: serde_json::json!(12),
+            updated_at: 1,
+        };
+        let currency = Setting {
+            key: "display_currency".to_string(),
+            value: serde_json::json!({"code": "EUR", "decimals": 2}),
+            updated_at: 1,
+        };
+        store.put(&fee_rate).await.unwrap();
+        store.put(&currency).await.unwrap();
+        fee_rate.value = serde_json::json!(15);
+        fee_rate.updated_at = 2;
+        store.put(&fee_rate).await.unwrap();
+
+        assert_eq!(store.get("default_fee_rate").await.unwrap(), Some(fee_rate.clone()));
+        assert_eq!(store.get("missing").await.unwrap(), None);
+        assert_eq!(store.list().await.unwrap(), vec![fee_rate, currency]);
+    }
+
+    #[tokio::test]
+    async fn test_settings_are_replaced_by_key() {
+        assert_settings_are_replaced_by_key(&InMemorySettingsStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_settings_are_replaced_by_key() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_settings_are_replaced_by_key(storage.settings().as_ref()).await;
+    }
+}
@@ -0,0 +1,783 @@
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Database, Encode, PgPool, QueryBuilder, SqlitePool, Type};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::types::{
+    Transaction, TransactionPage, TransactionQuery, TransactionSort, TransactionStatus,
+    TransactionType,
+};
+
+/// Persisted wallet transactions, keyed for event correlation by the Taproot
+/// Assets address they pay to or were received on
+#[async_trait::async_trait]
+pub trait TransactionRepo: Send + Sync {
+    async fn insert(&self, transaction: Transaction, destination: Option<String>) -> Result<(), AppError>;
+    /// Moves the newest `tx_type` transaction for `destination` to `status` if it
+    /// is still pending; returns false when no such transaction exists
+    async fn update_status(
+        &self,
+        tx_type: TransactionType,
+        destination: &str,
+        status: TransactionStatus,
+    ) -> Result<bool, AppError>;
+    /// Returns up to `limit` transactions, newest first
+    async fn list(&self, limit: usize) -> Result<Vec<Transaction>, AppError>;
+    /// Pending transactions with their destination, oldest first
+    async fn pending(&self) -> Result<Vec<(Transaction, String)>, AppError>;
+    /// Returns one filtered, sorted page with the count of all matches
+    async fn query(&self, query: &TransactionQuery) -> Result<TransactionPage, AppError>;
+    /// Up to `limit` transactions, newest first, whose ID starts with `text`
+    /// or whose label or destination contains it, ignoring case
+    async fn search(&self, text: &str, limit: usize) -> Result<Vec<Transaction>, AppError>;
+    /// Every transaction with its destination, oldest first
+    async fn export(&self) -> Result<Vec<(Transaction, Option<String>)>, AppError>;
+    /// Inserts a transaction unless one with its ID exists; returns whether
+    /// it was inserted
+    async fn import(
+        &self,
+        transaction: Transaction,
+        destination: Option<String>,
+    ) -> Result<bool, AppError>;
+}
+
+/// Process-local transaction history
+#[derive(Default)]
+pub struct InMemoryTransactionRepo {
+    transactions: RwLock<Vec<(Transaction, Option<String>)>>,
+}
+
+impl InMemoryTransactionRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionRepo for InMemoryTransactionRepo {
+    async fn insert(&self, transaction: Transaction, destination: Option<String>) -> Result<(), AppError> {
+        self.transactions
+            .write()
+            .unwrap()
+            .push((transaction, destination));
+        Ok(())
+    }
+
+    async fn update_status(
+        &self,
+        tx_type: TransactionType,
+        destination: &str,
+        status: TransactionStatus,
+    ) -> Result<bool, AppError> {
+        let mut transactions = self.transactions.write().unwrap();
+        let newest = transactions
+            .iter_mut()
+            .filter(|(tx, dest)| tx.tx_type == tx_type && dest.as_deref() == Some(destination))
+            .max_by_key(|(tx, _)| tx.created_at);
+
+        let Some((transaction, _)) = newest else {
+            return Ok(false);
+        };
+        if transaction.status == TransactionStatus::Pending {
+            transaction.status = status;
+            transaction.updated_at = Utc::now();
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        let mut transactions: Vec<Transaction> = self
+            .transactions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(tx, _)| tx.clone())
+            .collect();
+        transactions.sort_by_key(|tx| std::cmp::Reverse(tx.created_at));
+        transactions.truncate(limit);
+        Ok(transactions)
+    }
+
+    async fn pending(&self) -> Result<Vec<(Transaction, String)>, AppError> {
+        let mut pending: Vec<(Transaction, String)> = self
+            .transactions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(tx, _)| tx.status == TransactionStatus::Pending)
+            .filter_map(|(tx, destination)| Some((tx.clone(), destination.clone()?)))
+            .collect();
+        pending.sort_by_key(|(tx, _)| tx.created_at);
+        Ok(pending)
+    }
+
+    async fn query(&self, query: &TransactionQuery) -> Result<TransactionPage, AppError> {
+        let transactions = self
+            .transactions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(tx, _)| tx.clone())
+            .collect();
+        Ok(query.page(transactions))
+    }
+
+    async fn search(&self, text: &str, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        let text = text.to_lowercase();
+        let contains =
+            |value: Option<&str>| value.is_some_and(|v| v.to_lowercase().contains(&text));
+        let mut matches: Vec<Transaction> = self
+            .transactions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(tx, destination)| {
+                tx.id.to_string().starts_with(&text)
+                    || contains(tx.label.as_deref())
+                    || contains(destination.as_deref())
+            })
+            .map(|(tx, _)| tx.clone())
+            .collect();
+        matches.sort_by_key(|tx| std::cmp::Reverse(tx.created_at));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    async fn export(&self) -> Result<Vec<(Transaction, Option<String>)>, AppError> {
+        let mut transactions = self.transactions.read().unwrap().clone();
+        transactions.sort_by_key(|(tx, _)| tx.created_at);
+        Ok(transactions)
+    }
+
+    async fn import(
+        &self,
+        transaction: Transaction,
+        destination: Option<String>,
+    ) -> Result<bool, AppError> {
+        let mut transactions = self.transactions.write().unwrap();
+        if transactions.iter().any(|(tx, _)| tx.id == transaction.id) {
+            return Ok(false);
+        }
+        transactions.push((transaction, destination));
+        Ok(true)
+    }
+}
+
+/// Postgres-backed transaction history using the `transactions` table
+pub struct PostgresTransactionRepo {
+    pool: PgPool,
+}
+
+impl PostgresTransactionRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+type TransactionRow = (
+    Uuid,
+    String,
+    Option<String>,
+    i64,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<String>,
+);
+
+#[async_trait::async_trait]
+impl TransactionRepo for PostgresTransactionRepo {
+    async fn insert(&self, transaction: Transaction, destination: Option<String>) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO transactions
+                (id, tx_type, asset_id, amount, status, destination, created_at, updated_at, label)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(transaction.id)
+        .bind(format!("{:?}", transaction.tx_type))
+        .bind(&transaction.asset_id)
+        .bind(transaction.amount as i64)
+        .bind(format!("{:?}", transaction.status))
+        .bind(destination)
+        .bind(transaction.created_at)
+        .bind(transaction.updated_at)
+        .bind(&transaction.label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_status(
+        &self,
+        tx_type: TransactionType,
+        destination: &str,
+        status: TransactionStatus,
+    ) -> Result<bool, AppError> {
+        let newest = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, status FROM transactions
+             WHERE tx_type = $1 AND destination = $2
+             ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(format!("{tx_type:?}"))
+        .bind(destination)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((id, current)) = newest else {
+            return Ok(false);
+        };
+        if current == format!("{:?}", TransactionStatus::Pending) {
+            sqlx::query("UPDATE transactions SET status = $1, updated_at = NOW() WHERE id = $2")
+                .bind(format!("{status:?}"))
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        let rows = sqlx::query_as::<_, TransactionRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(transaction_from_row).collect()
+    }
+
+    async fn pending(&self) -> Result<Vec<(Transaction, String)>, AppError> {
+        let rows = sqlx::query_as::<_, PendingRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label,
+                    destination
+             FROM transactions WHERE status = $1 AND destination IS NOT NULL
+             ORDER BY created_at",
+        )
+        .bind(format!("{:?}", TransactionStatus::Pending))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(pending_from_row).collect()
+    }
+
+    async fn query(&self, query: &TransactionQuery) -> Result<TransactionPage, AppError> {
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM transactions");
+        push_filters(&mut count, query);
+        let (total,): (i64,) = count.build_query_as().fetch_one(&self.pool).await?;
+
+        let mut select = QueryBuilder::new(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions",
+        );
+        push_filters(&mut select, query);
+        select.push(match query.sort {
+            TransactionSort::Newest => " ORDER BY created_at DESC",
+            TransactionSort::Oldest => " ORDER BY created_at ASC",
+            TransactionSort::AmountDesc => " ORDER BY amount DESC, created_at DESC",
+            TransactionSort::AmountAsc => " ORDER BY amount ASC, created_at DESC",
+        });
+        let (limit, offset) = (query.limit(), query.offset());
+        select.push(" LIMIT ").push_bind(limit as i64);
+        select.push(" OFFSET ").push_bind(offset as i64);
+        let rows: Vec<TransactionRow> = select.build_query_as().fetch_all(&self.pool).await?;
+
+        let total = total.max(0) as usize;
+        Ok(TransactionPage {
+            transactions: rows.into_iter().map(transaction_from_row).collect::<Result<_, _>>()?,
+            total,
+            limit,
+            offset,
+            next_offset: (total > offset + limit).then_some(offset + limit),
+        })
+    }
+
+    async fn search(&self, text: &str, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        // LIKE wildcards in the search text match literally
+        let escaped = text
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let rows = sqlx::query_as::<_, TransactionRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions
+             WHERE id::text LIKE $1 OR label ILIKE $2 OR destination ILIKE $2
+             ORDER BY created_at DESC LIMIT $3",
+        )
+        .bind(format!("{escaped}%"))
+        .bind(format!("%{escaped}%"))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(transaction_from_row).collect()
+    }
+
+    async fn export(&self) -> Result<Vec<(Transaction, Option<String>)>, AppError> {
+        let rows = sqlx::query_as::<_, ExportRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label,
+                    destination
+             FROM transactions ORDER BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(export_from_row).collect()
+    }
+
+    async fn import(
+        &self,
+        transaction: Transaction,
+        destination: Option<String>,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO transactions
+                (id, tx_type, asset_id, amount, status, destination, created_at, updated_at, label)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(transaction.id)
+        .bind(format!("{:?}", transaction.tx_type))
+        .bind(&transaction.asset_id)
+        .bind(transaction.amount as i64)
+        .bind(format!("{:?}", transaction.status))
+        .bind(destination)
+        .bind(transaction.created_at)
+        .bind(transaction.updated_at)
+        .bind(&transaction.label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// SQLite-backed transaction history using the `transactions` table
+pub struct SqliteTransactionRepo {
+    pool: SqlitePool,
+}
+
+impl SqliteTransactionRepo {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionRepo for SqliteTransactionRepo {
+    async fn insert(&self, transaction: Transaction, destination: Option<String>) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO transactions
+                (id, tx_type, asset_id, amount, status, destination, created_at, updated_at, label)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(transaction.id)
+        .bind(format!("{:?}", transaction.tx_type))
+        .bind(&transaction.asset_id)
+        .bind(transaction.amount as i64)
+        .bind(format!("{:?}", transaction.status))
+        .bind(destination)
+        .bind(transaction.created_at)
+        .bind(transaction.updated_at)
+        .bind(&transaction.label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_status(
+        &self,
+        tx_type: TransactionType,
+        destination: &str,
+        status: TransactionStatus,
+    ) -> Result<bool, AppError> {
+        let newest = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, status FROM transactions
+             WHERE tx_type = $1 AND destination = $2
+             ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(format!("{tx_type:?}"))
+        .bind(destination)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((id, current)) = newest else {
+            return Ok(false);
+        };
+        if current == format!("{:?}", TransactionStatus::Pending) {
+            sqlx::query("UPDATE transactions SET status = $1, updated_at = $2 WHERE id = $3")
+                .bind(format!("{status:?}"))
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        let rows = sqlx::query_as::<_, TransactionRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(transaction_from_row).collect()
+    }
+
+    async fn pending(&self) -> Result<Vec<(Transaction, String)>, AppError> {
+        let rows = sqlx::query_as::<_, PendingRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label,
+                    destination
+             FROM transactions WHERE status = $1 AND destination IS NOT NULL
+             ORDER BY created_at",
+        )
+        .bind(format!("{:?}", TransactionStatus::Pending))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(pending_from_row).collect()
+    }
+
+    async fn query(&self, query: &TransactionQuery) -> Result<TransactionPage, AppError> {
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM transactions");
+        push_filters(&mut count, query);
+        let (total,): (i64,) = count.build_query_as().fetch_one(&self.pool).await?;
+
+        let mut select = QueryBuilder::new(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions",
+        );
+        push_filters(&mut select, query);
+        select.push(match query.sort {
+            TransactionSort::Newest => " ORDER BY created_at DESC",
+            TransactionSort::Oldest => " ORDER BY created_at ASC",
+            TransactionSort::AmountDesc => " ORDER BY amount DESC, created_at DESC",
+            TransactionSort::AmountAsc => " ORDER BY amount ASC, created_at DESC",
+        });
+        let (limit, offset) = (query.limit(), query.offset());
+        select.push(" LIMIT ").push_bind(limit as i64);
+        select.push(" OFFSET ").push_bind(offset as i64);
+        let rows: Vec<TransactionRow> = select.build_query_as().fetch_all(&self.pool).await?;
+
+        let total = total.max(0) as usize;
+        Ok(TransactionPage {
+            transactions: rows.into_iter().map(transaction_from_row).collect::<Result<_, _>>()?,
+            total,
+            limit,
+            offset,
+            next_offset: (total > offset + limit).then_some(offset + limit),
+        })
+    }
+
+    async fn search(&self, text: &str, limit: usize) -> Result<Vec<Transaction>, AppError> {
+        // IDs are stored as blobs, so their prefix is matched on the hex
+        // digits without the hyphens
+        let escaped = text
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let rows = sqlx::query_as::<_, TransactionRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label
+             FROM transactions
+             WHERE lower(hex(id)) LIKE $1 ESCAPE '\\'
+                OR lower(label) LIKE $2 ESCAPE '\\'
+                OR lower(destination) LIKE $2 ESCAPE '\\'
+             ORDER BY created_at DESC LIMIT $3",
+        )
+        .bind(format!("{}%", escaped.replace('-', "")))
+        .bind(format!("%{escaped}%"))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(transaction_from_row).collect()
+    }
+
+    async fn export(&self) -> Result<Vec<(Transaction, Option<String>)>, AppError> {
+        let rows = sqlx::query_as::<_, ExportRow>(
+            "SELECT id, tx_type, asset_id, amount, status, created_at, updated_at, label,
+                    destination
+             FROM transactions ORDER BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(export_from_row).collect()
+    }
+
+    async fn import(
+        &self,
+        transaction: Transaction,
+        destination: Option<String>,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO transactions
+                (id, tx_type, asset_id, amount, status, destination, created_at, updated_at, label)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(transaction.id)
+        .bind(format!("{:?}", transaction.tx_type))
+        .bind(&transaction.asset_id)
+        .bind(transaction.amount as i64)
+        .bind(format!("{:?}", transaction.status))
+        .bind(destination)
+        .bind(transaction.created_at)
+        .bind(transaction.updated_at)
+        .bind(&transaction.label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn push_filters<'a, DB>(builder: &mut QueryBuilder<'a, DB>, query: &TransactionQuery)
+where
+    DB: Database,
+    String: Encode<'a, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'a, DB> + Type<DB>,
+{
+    let mut clause = " WHERE ";
+    let mut next = |builder: &mut QueryBuilder<'a, DB>, column: &str| {
+        builder.push(clause).push(column);
+        clause = " AND ";
+    };
+    if let Some(asset_id) = &query.asset_id {
+        next(builder, "asset_id = ");
+        builder.push_bind(asset_id.clone());
+    }
+    if let Some(tx_type) = query.tx_type {
+        next(builder, "tx_type = ");
+        builder.push_bind(format!("{tx_type:?}"));
+    }
+    if let Some(status) = query.status {
+        next(builder, "status = ");
+        builder.push_bind(format!("{status:?}"));
+    }
+    if let Some(from) = query.from {
+        next(builder, "created_at >= ");
+        builder.push_bind(from);
+    }
+    if let Some(to) = query.to {
+        next(builder, "created_at <= ");
+        builder.push_bind(to);
+    }
+    if let Some(label) = &query.label {
+        next(builder, "label = ");
+        builder.push_bind(label.clone());
+    }
+}
+
+type PendingRow = (
+    Uuid,
+    String,
+    Option<String>,
+    i64,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<String>,
+    String,
+);
+
+type ExportRow = (
+    Uuid,
+    String,
+    Option<String>,
+    i64,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<String>,
+    Option<String>,
+);
+
+fn export_from_row(
+    (id, tx_type, asset_id, amount, status, created_at, updated_at, label, destination): ExportRow,
+) -> Result<(Transaction, Option<String>), AppError> {
+    let row = (id, tx_type, asset_id, amount, status, created_at, updated_at, label);
+    Ok((transaction_from_row(row)?, destination))
+}
+
+fn pending_from_row(
+    (id, tx_type, asset_id, amount, status, created_at, updated_at, label, destination): PendingRow,
+) -> Result<(Transaction, String), AppError> {
+    let row = (id, tx_type, asset_id, amount, status, created_at, updated_at, label);
+    Ok((transaction_from_row(row)?, destination))
+}
+
+fn transaction_from_row(
+    (id, tx_type, asset_id, amount, status, created_at, updated_at, label): TransactionRow,
+) -> Result<Transaction, AppError> {
+    Ok(Transaction {
+        id,
+        tx_type: parse_tx_type(&tx_type)?,
+        asset_id,
+        amount: amount.max(0) as u64,
+        status: parse_status(&status)?,
+        created_at,
+        updated_at,
+        label,
+    })
+}
+
+fn parse_tx_type(value: &str) -> Result<TransactionType, AppError> {
+    match value {
+        "Send" => Ok(TransactionType::Send),
+        "Receive" => Ok(TransactionType::Receive),
+        "Issue" => Ok(TransactionType::Issue),
+        other => Err(AppError::StorageError(format!("Unknown transaction type: {other}"))),
+    }
+}
+
+fn parse_status(value: &str) -> Result<TransactionStatus, AppError> {
+    match value {
+        "Pending" => Ok(TransactionStatus::Pending),
+        "Confirmed" => Ok(TransactionStatus::Confirmed),
+        "Failed" => Ok(TransactionStatus::Failed),
+        other => Err(AppError::StorageError(format!("Unknown transaction status: {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(tx_type: TransactionType) -> Transaction {
+        let now = Utc::now();
+        Transaction {
+            id: Uuid::new_v4(),
+            tx_type,
+            asset_id: Some("asset123".to_string()),
+            amount: 100,
+            status: TransactionStatus::Pending,
+            created_at: now,
+            updated_at: now,
+            label: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_status_only_moves_pending() {
+        let store = InMemoryTransactionRepo::new();
+        store
+            .insert(pending(TransactionType::Send), Some("taprt1dest".to_string()))
+            .await
+            .unwrap();
+
+        assert!(!store
+            .update_status(TransactionType::Receive, "taprt1dest", TransactionStatus::Confirmed)
+            .await
+            .unwrap());
+        assert!(store
+            .update_status(TransactionType::Send, "taprt1dest", TransactionStatus::Confirmed)
+            .await
+            .unwrap());
+        // A late failure must not overwrite a completed transfer
+        assert!(store
+            .update_status(TransactionType::Send, "taprt1dest", TransactionStatus::Failed)
+            .await
+            .unwrap());
+
+        let transactions = store.list(10).await.unwrap();
+        assert_eq!(transactions[0].status, TransactionStatus::Confirmed);
+        assert!(store.pending().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_id_label_and_destination() {
+        let store = InMemoryTransactionRepo::new();
+        let labelled = Transaction {
+            label: Some("March Payroll".to_string()),
+            ..pending(TransactionType::Send)
+        };
+        let id = labelled.id.to_string();
+        store.insert(labelled, Some("taprt1alice".to_string())).await.unwrap();
+        store
+            .insert(pending(TransactionType::Receive), Some("taprt1bob".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(store.search("payroll", 10).await.unwrap().len(), 1);
+        assert_eq!(store.search(&id[..8], 10).await.unwrap()[0].id.to_string(), id);
+        assert_eq!(store.search("TAPRT1", 10).await.unwrap().len(), 2);
+        assert_eq!(store.search("taprt1", 1).await.unwrap().len(), 1);
+        assert!(store.search("carol", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_filters_searches_and_updates() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        let store = storage.transactions();
+        let labelled = Transaction {
+            label: Some("March Payroll".to_string()),
+            ..pending(TransactionType::Send)
+        };
+        let id = labelled.id.to_string();
+        store.insert(labelled, Some("taprt1alice".to_string())).await.unwrap();
+        store
+            .insert(pending(TransactionType::Receive), Some("taprt1bob".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(store.search("payroll", 10).await.unwrap().len(), 1);
+        assert_eq!(store.search(&id[..13], 10).await.unwrap()[0].id.to_string(), id);
+        assert_eq!(store.search("TAPRT1", 10).await.unwrap().len(), 2);
+        assert!(store.search("100%", 10).await.unwrap().is_empty());
+
+        assert!(store
+            .update_status(TransactionType::Receive, "taprt1bob", TransactionStatus::Confirmed)
+            .await
+            .unwrap());
+        let pending = store.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!((pending[0].0.id.to_string(), pending[0].1.as_str()), (id, "taprt1alice"));
+        let query = TransactionQuery {
+            status: Some(TransactionStatus::Confirmed),
+            from: Some(Utc::now() - chrono::Duration::minutes(1)),
+            ..Default::default()
+        };
+        let page = store.query(&query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.transactions[0].tx_type, TransactionType::Receive);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_import_skips_existing_ids() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        let store = storage.transactions();
+        let sent = pending(TransactionType::Send);
+        store.insert(sent.clone(), Some("taprt1alice".to_string())).await.unwrap();
+
+        assert!(!store.import(sent.clone(), None).await.unwrap());
+        let received = pending(TransactionType::Receive);
+        assert!(store.import(received.clone(), None).await.unwrap());
+
+        let exported = store.export().await.unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!((exported[0].0.id, exported[0].1.as_deref()), (sent.id, Some("taprt1alice")));
+        assert_eq!((exported[1].0.id, exported[1].1.as_deref()), (received.id, None));
+    }
+
+    #[test]
+    fn test_parse_round_trips_debug_names() {
+        for tx_type in [TransactionType::Send, TransactionType::Receive, TransactionType::Issue] {
+            assert_eq!(parse_tx_type(&format!("{tx_type:?}")).unwrap(), tx_type);
+        }
+        for status in [TransactionStatus::Pending, TransactionStatus::Confirmed, TransactionStatus::Failed] {
+            assert_eq!(parse_status(&format!("{status:?}")).unwrap(), status);
+        }
+        assert!(parse_status("Unknown").is_err());
+    }
+}
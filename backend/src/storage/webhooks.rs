@@ -0,0 +1,683 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgPool, SqlitePool};
+use tracing::info;
+use uuid::Uuid;
+
+use super::database::{storage_for, Storage};
+use crate::config::{WebhookSettings, WebhookStoreBackend};
+use crate::error::AppError;
+use crate::secrets::SecretBox;
+
+/// Events an integrator can subscribe a webhook to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    /// An asset send or receive reached its final confirmed state
+    #[serde(rename = "transaction.confirmed")]
+    TransactionConfirmed,
+    /// A confirmed transfer moved an asset balance in or out of the wallet
+    #[serde(rename = "balance.changed")]
+    BalanceChanged,
+    /// Sent by the test-fire endpoint only; cannot be subscribed to
+    #[serde(rename = "webhook.test")]
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TransactionConfirmed => "transaction.confirmed",
+            WebhookEvent::BalanceChanged => "balance.changed",
+            WebhookEvent::Test => "webhook.test",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "transaction.confirmed" => Ok(WebhookEvent::TransactionConfirmed),
+            "balance.changed" => Ok(WebhookEvent::BalanceChanged),
+            "webhook.test" => Ok(WebhookEvent::Test),
+            other => Err(AppError::StorageError(format!("Unknown webhook event: {other}"))),
+        }
+    }
+}
+
+/// A callback URL registered for a set of events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Signs deliveries; only returned when the webhook is registered
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Every attempt failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "pending" => Ok(DeliveryStatus::Pending),
+            "delivered" => Ok(DeliveryStatus::Delivered),
+            "failed" => Ok(DeliveryStatus::Failed),
+            other => Err(AppError::StorageError(format!("Unknown delivery status: {other}"))),
+        }
+    }
+}
+
+/// One event queued for one webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+impl WebhookDelivery {
+    pub fn new(
+        webhook_id: Uuid,
+        event: WebhookEvent,
+        payload: serde_json::Value,
+        now: i64,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            webhook_id,
+            event,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        }
+    }
+}
+
+/// Registered webhooks and the queue of deliveries owed to them
+#[async_trait::async_trait]
+pub trait WebhookStore: Send + Sync {
+    async fn create(&self, webhook: &Webhook) -> Result<(), AppError>;
+    /// Returns webhooks, oldest first
+    async fn list(&self) -> Result<Vec<Webhook>, AppError>;
+    async fn get(&self, id: Uuid) -> Result<Option<Webhook>, AppError>;
+    /// Removes a webhook along with its queued deliveries
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError>;
+    /// Webhooks subscribed to `event`
+    async fn subscribers(&self, event: WebhookEvent) -> Result<Vec<Webhook>, AppError>;
+    async fn enqueue(&self, delivery: &WebhookDelivery) -> Result<(), AppError>;
+    /// Pending deliveries whose next attempt is due at `now`, oldest first
+    async fn due(&self, now: i64, limit: usize) -> Result<Vec<WebhookDelivery>, AppError>;
+    /// Saves the outcome of a delivery attempt
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError>;
+}
+
+/// Process-local webhooks and deliveries
+#[derive(Default)]
+pub struct InMemoryWebhookStore {
+    webhooks: RwLock<Vec<Webhook>>,
+    deliveries: RwLock<Vec<WebhookDelivery>>,
+}
+
+impl InMemoryWebhookStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for InMemoryWebhookStore {
+    async fn create(&self, webhook: &Webhook) -> Result<(), AppError> {
+        self.webhooks.write().unwrap().push(webhook.clone());
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Webhook>, AppError> {
+        Ok(self.webhooks.read().unwrap().clone())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        Ok(self.webhooks.read().unwrap().iter().find(|w| w.id == id).cloned())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let mut webhooks = self.webhooks.write().unwrap();
+        let before = webhooks.len();
+        webhooks.retain(|webhook| webhook.id != id);
+        self.deliveries.write().unwrap().retain(|d| d.webhook_id != id);
+        Ok(webhooks.len() != before)
+    }
+
+    async fn subscribers(&self, event: WebhookEvent) -> Result<Vec<Webhook>, AppError> {
+        Ok(self
+            .webhooks
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|webhook| webhook.events.contains(&event))
+            .cloned()
+            .collect())
+    }
+
+    async fn enqueue(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        self.deliveries.write().unwrap().push(delivery.clone());
+        Ok(())
+    }
+
+    async fn due(&self, now: i64, limit: usize) -> Result<Vec<WebhookDelivery>, AppError> {
+        let mut due: Vec<WebhookDelivery> = self
+            .deliveries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|d| d.status == DeliveryStatus::Pending && d.next_attempt_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|d| d.next_attempt_at);
+        due.truncate(limit);
+        Ok(due)
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        let mut deliveries = self.deliveries.write().unwrap();
+        if let Some(stored) = deliveries.iter_mut().find(|d| d.id == delivery.id) {
+            *stored = delivery.clone();
+        }
+        Ok(())
+    }
+}
+
+/// Postgres-backed webhooks using the `webhooks` and `webhook_deliveries` tables
+pub struct PostgresWebhookStore {
+    pool: PgPool,
+    secrets: Arc<SecretBox>,
+}
+
+impl PostgresWebhookStore {
+    pub fn new(pool: PgPool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals signing secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>("SELECT id, secret FROM webhooks")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut resealed = 0;
+        for (id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE webhooks SET secret = $1 WHERE id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+type WebhookRow = (Uuid, String, Vec<String>, String, i64);
+
+fn webhook_from_row(row: WebhookRow, secrets: &SecretBox) -> Result<Webhook, AppError> {
+    let (id, url, events, secret, created_at) = row;
+    Ok(Webhook {
+        id,
+        url,
+        events: events
+            .iter()
+            .map(|event| WebhookEvent::parse(event))
+            .collect::<Result<_, _>>()?,
+        secret: secrets.open(&secret)?,
+        created_at,
+    })
+}
+
+type DeliveryRow = (
+    Uuid,
+    Uuid,
+    String,
+    Json<serde_json::Value>,
+    String,
+    i32,
+    i64,
+    Option<String>,
+    i64,
+    Option<i64>,
+);
+
+fn delivery_from_row(row: DeliveryRow) -> Result<WebhookDelivery, AppError> {
+    let (
+        id,
+        webhook_id,
+        event,
+        Json(payload),
+        status,
+        attempts,
+        next_attempt_at,
+        last_error,
+        created_at,
+        delivered_at,
+    ) = row;
+    Ok(WebhookDelivery {
+        id,
+        webhook_id,
+        event: WebhookEvent::parse(&event)?,
+        payload,
+        status: DeliveryStatus::parse(&status)?,
+        attempts: attempts as u32,
+        next_attempt_at,
+        last_error,
+        created_at,
+        delivered_at,
+    })
+}
+
+const WEBHOOK_COLUMNS: &str = "id, url, events, secret, created_at";
+
+#[async_trait::async_trait]
+impl WebhookStore for PostgresWebhookStore {
+    async fn create(&self, webhook: &Webhook) -> Result<(), AppError> {
+        let events: Vec<&str> = webhook.events.iter().map(WebhookEvent::as_str).collect();
+        sqlx::query(
+            "INSERT INTO webhooks (id, url, events, secret, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(webhook.id)
+        .bind(&webhook.url)
+        .bind(events)
+        .bind(self.secrets.seal(&webhook.secret)?)
+        .bind(webhook.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Webhook>, AppError> {
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| webhook_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        let row = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(|row| webhook_from_row(row, &self.secrets)).transpose()
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        // Queued deliveries go with it through ON DELETE CASCADE
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn subscribers(&self, event: WebhookEvent) -> Result<Vec<Webhook>, AppError> {
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE $1 = ANY(events) ORDER BY created_at"
+        ))
+        .bind(event.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| webhook_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn enqueue(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries
+                (id, webhook_id, event, payload, status, attempts, next_attempt_at, last_error,
+                 created_at, delivered_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.event.as_str())
+        .bind(Json(&delivery.payload))
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn due(&self, now: i64, limit: usize) -> Result<Vec<WebhookDelivery>, AppError> {
+        let rows = sqlx::query_as::<_, DeliveryRow>(
+            "SELECT id, webhook_id, event, payload, status, attempts, next_attempt_at, last_error,
+                    created_at, delivered_at
+             FROM webhook_deliveries
+             WHERE status = 'pending' AND next_attempt_at <= $1
+             ORDER BY next_attempt_at
+             LIMIT $2",
+        )
+        .bind(now)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(delivery_from_row).collect()
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = $2, attempts = $3, next_attempt_at = $4, last_error = $5,
+                 delivered_at = $6
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// SQLite-backed webhooks using the `webhooks` and `webhook_deliveries`
+/// tables, with subscribed events kept as a JSON array
+pub struct SqliteWebhookStore {
+    pool: SqlitePool,
+    secrets: Arc<SecretBox>,
+}
+
+impl SqliteWebhookStore {
+    pub fn new(pool: SqlitePool, secrets: Arc<SecretBox>) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// Reseals signing secrets stored as plaintext or under a previous key
+    pub async fn reseal_secrets(&self) -> Result<u64, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>("SELECT id, secret FROM webhooks")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut resealed = 0;
+        for (id, secret) in rows {
+            if !self.secrets.needs_reseal(&secret) {
+                continue;
+            }
+            sqlx::query("UPDATE webhooks SET secret = $1 WHERE id = $2")
+                .bind(self.secrets.reseal(&secret)?)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            resealed += 1;
+        }
+        Ok(resealed)
+    }
+}
+
+type SqliteWebhookRow = (Uuid, String, Json<Vec<String>>, String, i64);
+
+fn sqlite_webhook_from_row(
+    row: SqliteWebhookRow,
+    secrets: &SecretBox,
+) -> Result<Webhook, AppError> {
+    let (id, url, Json(events), secret, created_at) = row;
+    webhook_from_row((id, url, events, secret, created_at), secrets)
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for SqliteWebhookStore {
+    async fn create(&self, webhook: &Webhook) -> Result<(), AppError> {
+        let events: Vec<&str> = webhook.events.iter().map(WebhookEvent::as_str).collect();
+        sqlx::query(
+            "INSERT INTO webhooks (id, url, events, secret, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(webhook.id)
+        .bind(&webhook.url)
+        .bind(Json(events))
+        .bind(self.secrets.seal(&webhook.secret)?)
+        .bind(webhook.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<Webhook>, AppError> {
+        let rows = sqlx::query_as::<_, SqliteWebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| sqlite_webhook_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Webhook>, AppError> {
+        let row = sqlx::query_as::<_, SqliteWebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(|row| sqlite_webhook_from_row(row, &self.secrets)).transpose()
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        // Queued deliveries go with it through ON DELETE CASCADE
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn subscribers(&self, event: WebhookEvent) -> Result<Vec<Webhook>, AppError> {
+        let rows = sqlx::query_as::<_, SqliteWebhookRow>(&format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM webhooks
+             WHERE EXISTS (SELECT 1 FROM json_each(events) WHERE value = $1)
+             ORDER BY created_at"
+        ))
+        .bind(event.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(|row| sqlite_webhook_from_row(row, &self.secrets)).collect()
+    }
+
+    async fn enqueue(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries
+                (id, webhook_id, event, payload, status, attempts, next_attempt_at, last_error,
+                 created_at, delivered_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.event.as_str())
+        .bind(Json(&delivery.payload))
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn due(&self, now: i64, limit: usize) -> Result<Vec<WebhookDelivery>, AppError> {
+        let rows = sqlx::query_as::<_, DeliveryRow>(
+            "SELECT id, webhook_id, event, payload, status, attempts, next_attempt_at, last_error,
+                    created_at, delivered_at
+             FROM webhook_deliveries
+             WHERE status = 'pending' AND next_attempt_at <= $1
+             ORDER BY next_attempt_at
+             LIMIT $2",
+        )
+        .bind(now)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(delivery_from_row).collect()
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = $2, attempts = $3, next_attempt_at = $4, last_error = $5,
+                 delivered_at = $6
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Builds the webhook store selected by the configured backend
+pub async fn create_webhook_store(
+    settings: &WebhookSettings,
+    storage: Option<&dyn Storage>,
+) -> Result<Arc<dyn WebhookStore>> {
+    info!("Using {:?} webhook store", settings.backend);
+
+    let store: Arc<dyn WebhookStore> = match settings.backend {
+        WebhookStoreBackend::Memory => Arc::new(InMemoryWebhookStore::new()),
+        WebhookStoreBackend::Postgres => match storage_for(storage, "webhook") {
+            Some(storage) => storage.webhooks(),
+            None => Arc::new(InMemoryWebhookStore::new()),
+        }
+    };
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: Vec<WebhookEvent>) -> Webhook {
+        Webhook {
+            id: Uuid::new_v4(),
+            url: "https://example.com/hooks".to_string(),
+            events,
+            secret: "secret".to_string(),
+            created_at: 0,
+        }
+    }
+
+    async fn assert_queue_follows_subscriptions_and_deletes(store: &dyn WebhookStore) {
+        let confirmations = webhook(vec![WebhookEvent::TransactionConfirmed]);
+        let balances = webhook(vec![WebhookEvent::BalanceChanged]);
+        store.create(&confirmations).await.unwrap();
+        store.create(&balances).await.unwrap();
+
+        let subscribers = store.subscribers(WebhookEvent::BalanceChanged).await.unwrap();
+        assert_eq!(subscribers, vec![balances.clone()]);
+
+        let event = WebhookEvent::BalanceChanged;
+        let later = WebhookDelivery::new(balances.id, event, serde_json::json!({}), 20);
+        let mut first = WebhookDelivery::new(balances.id, event, serde_json::json!({}), 10);
+        store.enqueue(&later).await.unwrap();
+        store.enqueue(&first).await.unwrap();
+        assert_eq!(store.due(15, 10).await.unwrap(), vec![first.clone()]);
+
+        first.status = DeliveryStatus::Delivered;
+        store.update_delivery(&first).await.unwrap();
+        assert_eq!(store.due(30, 10).await.unwrap(), vec![later]);
+
+        assert!(store.delete(balances.id).await.unwrap());
+        assert!(!store.delete(balances.id).await.unwrap());
+        assert!(store.due(30, 10).await.unwrap().is_empty());
+        assert_eq!(store.list().await.unwrap(), vec![confirmations]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_follows_subscriptions_and_deletes() {
+        assert_queue_follows_subscriptions_and_deletes(&InMemoryWebhookStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_queue_follows_subscriptions_and_deletes() {
+        let storage = crate::storage::database::open_storage("sqlite::memory:").await.unwrap();
+        assert_queue_follows_subscriptions_and_deletes(storage.webhooks().as_ref()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_secrets_are_sealed() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await.unwrap();
+        let plaintext = SqliteWebhookStore::new(pool.clone(), Arc::new(SecretBox::plaintext()));
+        let hook = webhook(vec![WebhookEvent::BalanceChanged]);
+        plaintext.create(&hook).await.unwrap();
+
+        let secrets = SecretBox::new("current-master-key-with-at-least-32-chars", &[]).unwrap();
+        let store = SqliteWebhookStore::new(pool.clone(), Arc::new(secrets));
+        assert_eq!(store.reseal_secrets().await.unwrap(), 1);
+        let (stored,) = sqlx::query_as::<_, (String,)>("SELECT secret FROM webhooks")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, hook.secret);
+        assert_eq!(store.get(hook.id).await.unwrap(), Some(hook));
+    }
+
+    #[test]
+    fn test_listed_webhook_hides_secret() {
+        let listed = serde_json::to_value(webhook(vec![WebhookEvent::TransactionConfirmed]));
+        let listed = listed.unwrap();
+        assert!(listed.get("secret").is_none());
+        assert_eq!(listed["events"][0], "transaction.confirmed");
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Renders a base-unit amount with `decimals` places, e.g. 150 with 2 as "1.50"
+pub fn format_amount(raw: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return raw.to_string();
+    }
+    let digits = format!("{raw:0>width$}", width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    format!("{whole}.{fraction}")
+}
+
+/// Reads an amount written with up to `decimals` places into base units, so
+/// "1.5" with 2 decimals is 150. Negative, exponent and over-precise
+/// amounts are refused rather than rounded.
+pub fn parse_amount(display: &str, decimals: u8) -> Result<u64, AppError> {
+    let invalid =
+        |reason: &str| AppError::InvalidInput(format!("Invalid amount {display:?}: {reason}"));
+    let display = display.trim();
+    let (whole, fraction) = display.split_once('.').unwrap_or((display, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(invalid("expected digits with an optional decimal point"));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(invalid(&format!("the asset has {decimals} decimal places")));
+    }
+
+    let scale = 10u64
+        .checked_pow(decimals as u32)
+        .ok_or_else(|| invalid("too many decimal places"))?;
+    let whole: u64 = match whole {
+        "" => 0,
+        whole => whole.parse().map_err(|_| invalid("too large"))?,
+    };
+    let fraction: u64 = format!("{fraction:0<width$}", width = decimals as usize)
+        .parse()
+        .unwrap_or(0);
+    whole
+        .checked_mul(scale)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(|| invalid("too large"))
+}
+
+/// Adds `decimal_display` and `balance_display` to each entry of a
+/// `ListBalances` response grouped by asset ID whose decimals are known
+pub fn annotate_balances(balances: &mut Value, decimals: &HashMap<String, u8>) {
+    let Some(entries) = balances["asset_balances"].as_object_mut() else {
+        return;
+    };
+    for (asset_id, entry) in entries {
+        let Some(&places) = decimals.get(asset_id) else {
+            continue;
+        };
+        let raw = match &entry["balance"] {
+            Value::String(balance) => balance.parse().ok(),
+            balance => balance.as_u64(),
+        };
+        if let (Some(raw), Some(entry)) = (raw, entry.as_object_mut()) {
+            entry.insert("decimal_display".to_string(), places.into());
+            entry.insert("balance_display".to_string(), format_amount(raw, places).into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amounts_round_trip_through_display_units() {
+        assert_eq!(format_amount(150, 2), "1.50");
+        assert_eq!(format_amount(5, 3), "0.005");
+        assert_eq!(format_amount(42, 0), "42");
+
+        assert_eq!(parse_amount("1.5", 2).unwrap(), 150);
+        assert_eq!(parse_amount(".005", 3).unwrap(), 5);
+        assert_eq!(parse_amount("42", 0).unwrap(), 42);
+        assert!(parse_amount("1.505", 2).is_err());
+        assert!(parse_amount("-1", 2).is_err());
+        assert!(parse_amount("1e3", 2).is_err());
+        assert!(parse_amount(".", 2).is_err());
+        assert!(parse_amount("18446744073709551616", 0).is_err());
+    }
+
+    #[test]
+    fn test_annotate_balances() {
+        let asset_id = "ab".repeat(32);
+        let mut balances = serde_json::json!({"asset_balances": {
+            asset_id.clone(): {"balance": "1500"},
+            "cd".repeat(32): {"balance": "7"}
+        }});
+        annotate_balances(&mut balances, &HashMap::from([(asset_id.clone(), 2)]));
+        assert_eq!(balances["asset_balances"][&asset_id]["balance_display"], "15.00");
+        assert_eq!(balances["asset_balances"][&asset_id]["decimal_display"], 2);
+        assert!(balances["asset_balances"]["cd".repeat(32)].get("balance_display").is_none());
+    }
+}
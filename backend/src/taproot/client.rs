@@ -1,11 +1,26 @@
-use anyhow::Result;
-use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::{Client, Method, RequestBuilder};
 use serde_json::json;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+use crate::config::HttpClientSettings;
+use crate::error::AppError;
+use crate::gateway::cache::{CacheKind, ResponseCache};
+use crate::gateway::upstream::{self, SendUpstream};
+use crate::gateway::utxos;
+use crate::taproot::grpc::TapdGrpcClient;
 
 pub struct TapdClient {
     gateway_url: String,
     client: Client,
+    macaroon_hex: Option<String>,
+    request_timeout: Duration,
+    cache: Arc<ResponseCache>,
+    grpc: Option<TapdGrpcClient>,
 }
 
 impl TapdClient {
@@ -13,54 +28,389 @@ impl TapdClient {
         Self {
             gateway_url,
             client: Client::new(),
+            macaroon_hex: None,
+            request_timeout: HttpClientSettings::default().request_timeout(),
+            cache: Arc::new(ResponseCache::disabled()),
+            grpc: None,
         }
     }
 
-    pub async fn list_assets(&self) -> Result<Vec<crate::types::TaprootAsset>> {
-        info!("Listing assets from gateway at {}", self.gateway_url);
-        
-        let url = format!("{}/v1/taproot-assets/assets", self.gateway_url);
-        let response = self.client.get(&url).send().await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to list assets: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to list assets: {}", error_text));
+    /// Serves reads, addresses, sends, mints and asset groups over tapd's
+    /// gRPC port; calls without a gRPC mapping still go to the REST gateway
+    pub fn with_grpc(mut self, grpc: Option<TapdGrpcClient>) -> Self {
+        self.grpc = grpc;
+        self
+    }
+
+    /// The gRPC client, when tapd is reached over gRPC
+    pub fn grpc_client(&self) -> Option<&TapdGrpcClient> {
+        self.grpc.as_ref()
+    }
+
+    /// Serves assets, balances and info from `cache`, and drops holdings
+    /// from it after sends and mints
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Rebuilds the HTTP client from the transport settings: certificate
+    /// checks, a trusted CA, timeouts and a SOCKS5 proxy
+    pub fn with_transport(mut self, settings: &HttpClientSettings) -> Result<Self> {
+        self.client = upstream::client_builder(settings, "tapd")?
+            .build()
+            .context("Failed to create tapd HTTP client")?;
+        self.request_timeout = settings.request_timeout();
+        Ok(self)
+    }
+
+    /// The underlying HTTP client, for handlers that call tapd directly
+    pub fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Authenticates every request with the hex macaroon; `None` sends none
+    pub fn with_macaroon(mut self, macaroon_hex: Option<&str>) -> Self {
+        self.macaroon_hex = macaroon_hex.map(str::to_string);
+        self
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.client.request(method, url).timeout(self.request_timeout);
+        match &self.macaroon_hex {
+            Some(macaroon) => request.header("Grpc-Metadata-macaroon", macaroon),
+            None => request,
         }
+    }
+
+    /// Lists assets one page at a time; `params` filters the listing, and its
+    /// `include_*` flags are passed through to tapd. Each asset's balance is
+    /// its wallet total, fetched alongside the listing. Assets that cannot be
+    /// read are skipped with a warning rather than failing the page.
+    pub async fn list_assets(
+        &self,
+        params: &crate::types::AssetListParams,
+    ) -> Result<crate::types::AssetPage<crate::types::TaprootAsset>> {
+        use crate::types::{AssetWarning, BalanceGrouping, TaprootAsset};
+
+        info!("Listing assets from gateway at {}", self.gateway_url);
         
-        let json: serde_json::Value = response.json().await?;
-        let empty_vec = vec![];
-        let assets = json["assets"].as_array().unwrap_or(&empty_vec);
-        
+        let (json, balances) = futures::join!(
+            self.asset_listing(params),
+            self.get_balance(BalanceGrouping::AssetId)
+        );
+        let assets = json?["assets"].as_array().cloned().unwrap_or_default();
+        let page = params.page(assets);
+
+        let mut warnings = Vec::new();
+        let balances = balances
+            .map_err(|e| {
+                warn!("Listing assets without wallet totals: {}", e);
+                warnings.push(AssetWarning {
+                    index: None,
+                    asset_id: None,
+                    message: format!("Balances unavailable, showing per-output amounts: {e}"),
+                });
+            })
+            .unwrap_or_default();
         let mut result = Vec::new();
-        for asset in assets {
-            if let Ok(taproot_asset) = serde_json::from_value::<crate::types::TaprootAsset>(asset.clone()) {
-                result.push(taproot_asset);
+        for (index, asset) in page.assets.iter().enumerate() {
+            match TaprootAsset::from_upstream(asset) {
+                Ok((mut taproot_asset, problems)) => {
+                    params.apply_preference(&mut taproot_asset);
+                    let total = &balances["asset_balances"][&taproot_asset.asset_id]["balance"];
+                    if let Some(total) = total.as_str().and_then(|total| total.parse().ok()) {
+                        taproot_asset.balance = total;
+                    }
+                    warnings.extend(problems.into_iter().map(|message| AssetWarning {
+                        index: Some(index),
+                        asset_id: Some(taproot_asset.asset_id.clone()),
+                        message,
+                    }));
+                    result.push(taproot_asset);
+                }
+                Err(message) => {
+                    warn!("Skipping unreadable asset at index {}: {}", index, message);
+                    warnings.push(AssetWarning {
+                        index: Some(index),
+                        asset_id: None,
+                        message,
+                    });
+                }
             }
         }
         
-        Ok(result)
+        Ok(crate::types::AssetPage {
+            assets: result,
+            total: page.total,
+            matched: page.matched,
+            next_offset: page.next_offset,
+            warnings,
+        })
+    }
+
+    /// tapd's `ListAssets` with the `include_*` flags of `params`, cached
+    async fn asset_listing(
+        &self,
+        params: &crate::types::AssetListParams,
+    ) -> Result<serde_json::Value> {
+        let query: Vec<String> = params
+            .upstream_query()
+            .into_iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        let path = format!("/v1/taproot-assets/assets?{}", query.join("&"));
+        match &self.grpc {
+            Some(grpc) => Ok(self
+                .cache
+                .get_or_fetch(CacheKind::Assets, &path, || grpc.list_assets(params))
+                .await?),
+            None => self.cached_get(CacheKind::Assets, &path, "list assets").await,
+        }
+    }
+
+    /// The `decimal_display` of every asset the wallet has held, keyed by
+    /// hex asset ID
+    pub async fn asset_decimals(&self) -> Result<HashMap<String, u8>> {
+        let params = crate::types::AssetListParams {
+            include_spent: Some(true),
+            ..Default::default()
+        };
+        let listing = self.asset_listing(&params).await?;
+        let assets = listing["assets"].as_array().cloned().unwrap_or_default();
+        Ok(assets
+            .iter()
+            .filter_map(|asset| crate::types::TaprootAsset::from_upstream(asset).ok())
+            .map(|(asset, _)| (asset.asset_id, asset.decimals))
+            .collect())
     }
 
     pub async fn send_asset(&self, transfer: &crate::types::AssetTransfer) -> Result<String> {
         info!("Sending asset {} to {} via gateway", transfer.asset_id, transfer.destination);
-        
-        let url = format!("{}/v1/taproot-assets/send", self.gateway_url);
-        let payload = json!({
-            "tap_addrs": [transfer.destination],
-            "fee_rate": transfer.fee_rate.unwrap_or(5)
+        self.send_selected(
+            &[transfer.destination.as_str()],
+            transfer.fee_rate,
+            (&transfer.asset_id, transfer.amount),
+            &transfer.coin_selection,
+            transfer.label.as_deref(),
+        )
+        .await
+    }
+
+    /// Pays every recipient from one anchor transaction. tapd sends all of
+    /// the addresses or none, so the outcome is shared.
+    pub async fn send_batch(
+        &self,
+        transfer: &crate::types::BatchTransfer,
+    ) -> crate::types::BatchTransferResult {
+        use crate::types::{BatchTransferResult, RecipientStatus};
+
+        info!("Sending to {} addresses in one anchor transaction", transfer.recipients.len());
+        let addresses: Vec<&str> = transfer
+            .recipients
+            .iter()
+            .map(|recipient| recipient.destination.trim())
+            .collect();
+        // validate() ensures selected inputs only ever fund one asset
+        let asset_id = transfer.recipients[0].asset_id.as_str();
+        let amount = transfer.recipients.iter().map(|recipient| recipient.amount).sum();
+        let selection = &transfer.coin_selection;
+        let sent = self
+            .send_selected(
+                &addresses,
+                transfer.fee_rate,
+                (asset_id, amount),
+                selection,
+                transfer.label.as_deref(),
+            )
+            .await;
+        match sent {
+            Ok(tx_id) => {
+                BatchTransferResult::uniform(transfer, RecipientStatus::Sent, Some(tx_id), None)
+            }
+            Err(e) => BatchTransferResult::uniform(
+                transfer,
+                RecipientStatus::Failed,
+                None,
+                Some(e.to_string()),
+            ),
+        }
+    }
+
+    /// Sends through tapd's own coin selection unless the caller chose the
+    /// inputs, funding `amount` units of the asset
+    async fn send_selected(
+        &self,
+        tap_addrs: &[&str],
+        fee_rate: Option<u32>,
+        (asset_id, amount): (&str, u64),
+        selection: &crate::types::CoinSelection,
+        label: Option<&str>,
+    ) -> Result<String> {
+        // tapd only labels transfers it funds itself
+        let sent = if *selection == crate::types::CoinSelection::Auto {
+            self.send_to_addresses(tap_addrs, fee_rate, label).await
+        } else {
+            self.send_from_selection(tap_addrs, (asset_id, amount), selection).await
+        };
+        self.cache.invalidate_holdings().await;
+        sent
+    }
+
+    async fn send_from_selection(
+        &self,
+        tap_addrs: &[&str],
+        (asset_id, amount): (&str, u64),
+        selection: &crate::types::CoinSelection,
+    ) -> Result<String> {
+        let listing = match &self.grpc {
+            Some(grpc) => grpc.list_utxos(true).await?,
+            None => {
+                self.get("/v1/taproot-assets/assets/utxos?include_leased=true", "list UTXOs")
+                    .await?
+            }
+        };
+        let now = chrono::Utc::now().timestamp();
+        let utxos = utxos::parse_managed_utxos(&listing, now);
+        let inputs = utxos::select_asset_inputs(&utxos, asset_id, amount, selection)?;
+        info!("Funding send from {} selected asset outputs", inputs.len());
+        self.send_from_inputs(tap_addrs, &inputs).await
+    }
+
+    /// Funds, signs and anchors a virtual transaction spending exactly
+    /// `inputs`. tapd picks the anchor fee rate itself on this path.
+    async fn send_from_inputs(
+        &self,
+        tap_addrs: &[&str],
+        inputs: &[utxos::AssetInput],
+    ) -> Result<String> {
+        let prev_ids = inputs
+            .iter()
+            .map(|input| input.prev_id())
+            .collect::<Result<Vec<_>, _>>()?;
+        // Recipients map to anchor outputs; output 0 takes the change
+        let recipients: serde_json::Map<String, serde_json::Value> = tap_addrs
+            .iter()
+            .enumerate()
+            .map(|(index, addr)| (addr.to_string(), json!((index + 1).to_string())))
+            .collect();
+
+        let fund = json!({"raw": {"inputs": prev_ids, "recipients": recipients}});
+        let funded = match &self.grpc {
+            Some(grpc) => grpc.fund_virtual_psbt(&fund).await?,
+            None => {
+                self.post(
+                    "/v1/taproot-assets/wallet/virtual-psbt/fund",
+                    &fund,
+                    "fund virtual transaction",
+                )
+                .await?
+            }
+        };
+        let sign = json!({"funded_psbt": funded["funded_psbt"]});
+        let signed = match &self.grpc {
+            Some(grpc) => grpc.sign_virtual_psbt(&sign).await?,
+            None => {
+                self.post(
+                    "/v1/taproot-assets/wallet/virtual-psbt/sign",
+                    &sign,
+                    "sign virtual transaction",
+                )
+                .await?
+            }
+        };
+        let mut virtual_psbts = vec![signed["signed_psbt"].clone()];
+        // Other assets sharing the spent anchor outputs move along with them
+        let passive = funded["passive_asset_psbts"].as_array().into_iter().flatten();
+        virtual_psbts.extend(passive.cloned());
+        let anchor = json!({"virtual_psbts": virtual_psbts});
+        let sent = match &self.grpc {
+            Some(grpc) => grpc.anchor_virtual_psbts(&anchor).await?,
+            None => {
+                self.post(
+                    "/v1/taproot-assets/wallet/virtual-psbt/anchor",
+                    &anchor,
+                    "anchor virtual transaction",
+                )
+                .await?
+            }
+        };
+
+        Ok(sent["transfer"]["anchor_tx_hash"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string())
+    }
+
+    /// GETs `path` through the response cache
+    async fn cached_get(
+        &self,
+        kind: CacheKind,
+        path: &str,
+        action: &str,
+    ) -> Result<serde_json::Value> {
+        self.cache.get_or_fetch(kind, path, || self.get(path, action)).await
+    }
+
+    async fn get(&self, path: &str, action: &str) -> Result<serde_json::Value> {
+        let url = format!("{}{path}", self.gateway_url);
+        let response = self.request(Method::GET, &url).send_upstream().await?;
+        Self::json_response(response, action).await
+    }
+
+    async fn post(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+        action: &str,
+    ) -> Result<serde_json::Value> {
+        let response = self
+            .request(Method::POST, &format!("{}{path}", self.gateway_url))
+            .json(body)
+            .send_upstream()
+            .await?;
+        Self::json_response(response, action).await
+    }
+
+    async fn json_response(response: reqwest::Response, action: &str) -> Result<serde_json::Value> {
+        if !response.status().is_success() {
+            let error = AppError::from_response(response).await;
+            error!("Failed to {}: {}", action, error);
+            return Err(error.into());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Returns the anchor transaction hash
+    async fn send_to_addresses(
+        &self,
+        tap_addrs: &[&str],
+        fee_rate: Option<u32>,
+        label: Option<&str>,
+    ) -> Result<String> {
+        let mut payload = json!({
+            "tap_addrs": tap_addrs,
+            "fee_rate": fee_rate.unwrap_or(5)
         });
+        if let Some(label) = label {
+            payload["label"] = json!(label);
+        }
+        if let Some(grpc) = &self.grpc {
+            let json = grpc.send_asset(&payload).await?;
+            return Ok(json["transfer"]["anchor_tx_hash"].as_str().unwrap_or("unknown").to_string());
+        }
         
-        let response = self.client
-            .post(&url)
+        let url = format!("{}/v1/taproot-assets/send", self.gateway_url);
+        let response = self
+            .request(Method::POST, &url)
             .json(&payload)
-            .send()
+            .send_upstream()
             .await?;
         
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to send asset: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to send asset: {}", error_text));
+            let error = AppError::from_response(response).await;
+            error!("Failed to send asset: {}", error);
+            return Err(error.into());
         }
         
         let json: serde_json::Value = response.json().await?;
@@ -75,25 +425,11 @@ impl TapdClient {
     pub async fn create_address(&self, asset_id: &str, amount: u64) -> Result<String> {
         info!("Creating address for asset {} amount {}", asset_id, amount);
         
-        let url = format!("{}/v1/taproot-assets/addrs", self.gateway_url);
         let payload = json!({
             "asset_id": asset_id,
             "amt": amount.to_string()
         });
-        
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to create address: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to create address: {}", error_text));
-        }
-        
-        let json: serde_json::Value = response.json().await?;
+        let json = self.new_address(payload).await?;
         let address = json["encoded"]
             .as_str()
             .unwrap_or("unknown")
@@ -102,32 +438,14 @@ impl TapdClient {
         Ok(address)
     }
 
-    pub async fn mint_asset(&self, name: &str, amount: u64, asset_type: &str) -> Result<String> {
-        info!("Minting asset {} with amount {}", name, amount);
+    pub async fn mint_asset(&self, request: &crate::types::MintAssetRequest) -> Result<String> {
+        info!("Minting asset {} with amount {}", request.name, request.amount);
         
-        let url = format!("{}/v1/taproot-assets/assets", self.gateway_url);
         let payload = json!({
-            "asset": {
-                "asset_type": asset_type,
-                "name": name,
-                "amount": amount.to_string()
-            },
+            "asset": request.upstream_asset(),
             "short_response": true
         });
-        
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to mint asset: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to mint asset: {}", error_text));
-        }
-        
-        let json: serde_json::Value = response.json().await?;
+        let json = self.mint_asset_raw(payload).await?;
         let batch_key = json["pending_batch"]["batch_key"]
             .as_str()
             .unwrap_or("unknown")
@@ -136,48 +454,80 @@ impl TapdClient {
         Ok(batch_key)
     }
 
-    pub async fn get_balance(&self) -> Result<serde_json::Value> {
-        info!("Getting asset balance from gateway");
+    pub async fn list_groups(&self) -> Result<Vec<crate::types::AssetGroup>> {
+        info!("Listing asset groups from gateway at {}", self.gateway_url);
+        if let Some(grpc) = &self.grpc {
+            return Ok(crate::types::parse_asset_groups(&grpc.list_groups().await?));
+        }
         
-        let url = format!("{}/v1/taproot-assets/assets/balance", self.gateway_url);
-        let response = self.client.get(&url).send().await?;
+        let url = format!("{}/v1/taproot-assets/assets/groups", self.gateway_url);
+        let response = self.request(Method::GET, &url).send_upstream().await?;
         
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to get balance: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to get balance: {}", error_text));
+            let error = AppError::from_response(response).await;
+            error!("Failed to list asset groups: {}", error);
+            return Err(error.into());
         }
         
         let json: serde_json::Value = response.json().await?;
-        Ok(json)
+        Ok(crate::types::parse_asset_groups(&json))
+    }
+
+    /// tapd's `ListBalances`, keyed by hex asset ID or group key
+    pub async fn get_balance(
+        &self,
+        group_by: crate::types::BalanceGrouping,
+    ) -> Result<serde_json::Value> {
+        info!("Getting asset balance from gateway");
+        
+        let path = format!("/v1/taproot-assets/assets/balance?{}", group_by.upstream_query());
+        match &self.grpc {
+            Some(grpc) => {
+                let balances = self
+                    .cache
+                    .get_or_fetch(CacheKind::Balance, &path, || grpc.list_balances(group_by))
+                    .await?;
+                Ok(balances)
+            }
+            None => self.cached_get(CacheKind::Balance, &path, "get balance").await,
+        }
     }
 
     pub async fn get_info(&self) -> Result<serde_json::Value> {
         info!("Getting taproot assets info from gateway");
         
-        let url = format!("{}/v1/taproot-assets/info", self.gateway_url);
-        let response = self.client.get(&url).send().await?;
-        
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to get info: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to get info: {}", error_text));
+        let path = "/v1/taproot-assets/info";
+        match &self.grpc {
+            Some(grpc) => {
+                let info = self.cache.get_or_fetch(CacheKind::Info, path, || grpc.get_info()).await?;
+                Ok(info)
+            }
+            None => self.cached_get(CacheKind::Info, path, "get info").await,
+        }
+    }
+
+    /// Asks tapd for its info without the response cache, for health checks
+    /// that must reflect whether tapd answers right now
+    pub async fn get_info_uncached(&self) -> Result<serde_json::Value> {
+        match &self.grpc {
+            Some(grpc) => Ok(grpc.get_info().await?),
+            None => self.get("/v1/taproot-assets/info", "get info").await,
         }
-        
-        let json: serde_json::Value = response.json().await?;
-        Ok(json)
     }
 
     pub async fn list_addresses(&self) -> Result<serde_json::Value> {
         info!("Listing addresses from gateway");
+        if let Some(grpc) = &self.grpc {
+            return Ok(grpc.query_addrs().await?);
+        }
         
         let url = format!("{}/v1/taproot-assets/addrs", self.gateway_url);
-        let response = self.client.get(&url).send().await?;
+        let response = self.request(Method::GET, &url).send_upstream().await?;
         
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to list addresses: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to list addresses: {}", error_text));
+            let error = AppError::from_response(response).await;
+            error!("Failed to list addresses: {}", error);
+            return Err(error.into());
         }
         
         let json: serde_json::Value = response.json().await?;
@@ -186,18 +536,21 @@ impl TapdClient {
 
     pub async fn new_address(&self, payload: serde_json::Value) -> Result<serde_json::Value> {
         info!("Creating new address via gateway");
+        if let Some(grpc) = &self.grpc {
+            return Ok(grpc.new_addr(&payload).await?);
+        }
         
         let url = format!("{}/v1/taproot-assets/addrs", self.gateway_url);
-        let response = self.client
-            .post(&url)
+        let response = self
+            .request(Method::POST, &url)
             .json(&payload)
-            .send()
+            .send_upstream()
             .await?;
         
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to create new address: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to create new address: {}", error_text));
+            let error = AppError::from_response(response).await;
+            error!("Failed to create new address: {}", error);
+            return Err(error.into());
         }
         
         let json: serde_json::Value = response.json().await?;
@@ -206,21 +559,77 @@ impl TapdClient {
 
     pub async fn mint_asset_raw(&self, payload: serde_json::Value) -> Result<serde_json::Value> {
         info!("Minting asset via gateway with raw payload");
+        if let Some(grpc) = &self.grpc {
+            let json = grpc.mint_asset(&payload).await?;
+            self.cache.invalidate_holdings().await;
+            return Ok(json);
+        }
         
         let url = format!("{}/v1/taproot-assets/assets", self.gateway_url);
-        let response = self.client
-            .post(&url)
+        let response = self
+            .request(Method::POST, &url)
             .json(&payload)
-            .send()
+            .send_upstream()
             .await?;
         
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("Failed to mint asset: {}", error_text);
-            return Err(anyhow::anyhow!("Failed to mint asset: {}", error_text));
+            let error = AppError::from_response(response).await;
+            error!("Failed to mint asset: {}", error);
+            return Err(error.into());
         }
         
         let json: serde_json::Value = response.json().await?;
+        self.cache.invalidate_holdings().await;
         Ok(json)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_carry_macaroon_when_configured() {
+        let url = "http://127.0.0.1:8080/v1/taproot-assets/info";
+        let client =
+            TapdClient::new("http://127.0.0.1:8080".to_string()).with_macaroon(Some("0201"));
+        let request = client.request(Method::GET, url).build().unwrap();
+        assert_eq!(request.headers()["Grpc-Metadata-macaroon"], "0201");
+
+        let anonymous = TapdClient::new("http://127.0.0.1:8080".to_string());
+        let request = anonymous.request(Method::POST, url).build().unwrap();
+        assert!(request.headers().get("Grpc-Metadata-macaroon").is_none());
+    }
+
+    #[test]
+    fn test_transport_settings() {
+        let settings = HttpClientSettings {
+            request_timeout_secs: 5,
+            socks5_proxy: Some("socks5h://127.0.0.1:9050".to_string()),
+            ..HttpClientSettings::default()
+        };
+        let client = TapdClient::new("http://127.0.0.1:8080".to_string())
+            .with_transport(&settings)
+            .unwrap();
+        let request = client.request(Method::GET, "http://127.0.0.1:8080/v1/taproot-assets/info");
+        assert_eq!(request.build().unwrap().timeout(), Some(&Duration::from_secs(5)));
+
+        let missing_ca = HttpClientSettings {
+            ca_cert_path: Some("/nonexistent/tls.cert".to_string()),
+            ..HttpClientSettings::default()
+        };
+        assert!(TapdClient::new(String::new()).with_transport(&missing_ca).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_uncached_info_skips_the_response_cache() {
+        let cache = Arc::new(ResponseCache::new(&crate::config::CacheSettings::default()));
+        let path = "/v1/taproot-assets/info";
+        cache.insert(CacheKind::Info, path, json!({"version": "0.6.0"})).await;
+        // Nothing listens on the discard port, so only the cache can answer
+        let client = TapdClient::new("http://127.0.0.1:9".to_string()).with_cache(cache);
+
+        assert_eq!(client.get_info().await.unwrap()["version"], "0.6.0");
+        assert!(client.get_info_uncached().await.is_err());
+    }
+}
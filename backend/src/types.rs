@@ -2,12 +2,183 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::api::extract::{FieldError, Validate};
+
 #[derive(Clone)]
 pub struct AppState {
     pub tapd_client: std::sync::Arc<crate::taproot::client::TapdClient>,
     pub http_client: std::sync::Arc<reqwest::Client>,
+    /// Postgres or SQLite, set when `DATABASE_URL` is configured
+    pub storage: Option<std::sync::Arc<dyn crate::storage::database::Storage>>,
     pub base_url: BaseUrl,
     pub macaroon_hex: MacaroonHex,
+    pub mailbox_receivers: Option<std::sync::Arc<dyn crate::storage::receivers::ReceiverRepo>>,
+    pub mailbox_monitoring: Option<std::sync::Arc<dyn crate::gateway::mailbox::Monitoring>>,
+    pub challenge_store: std::sync::Arc<dyn crate::storage::challenges::ChallengeStore>,
+    pub mailbox_limiter: std::sync::Arc<crate::gateway::mailbox_limits::MailboxLimiter>,
+    pub mailbox_registry: std::sync::Arc<crate::gateway::mailbox_registry::MailboxRegistry>,
+    pub metrics: std::sync::Arc<crate::gateway::metrics::PromMonitoring>,
+    pub mailbox_webhooks: std::sync::Arc<crate::gateway::mailbox_webhooks::WebhookDispatcher>,
+    pub event_broker: std::sync::Arc<crate::gateway::events::EventBroker>,
+    pub event_store: std::sync::Arc<dyn crate::storage::events::EventStore>,
+    pub lnd_client: Option<std::sync::Arc<crate::gateway::lnd::LndClient>>,
+    pub chain_watcher: Option<std::sync::Arc<crate::gateway::blocks::ChainWatcher>>,
+    pub transaction_store: std::sync::Arc<dyn crate::storage::transactions::TransactionRepo>,
+    /// Reconciles `transaction_store` against tapd's receives and transfers
+    pub reconciler: std::sync::Arc<crate::gateway::reconcile::Reconciler>,
+    /// Invoices created through `/channels/invoice`
+    pub invoice_repo: std::sync::Arc<dyn crate::storage::invoices::InvoiceRepo>,
+    pub device_store: std::sync::Arc<dyn crate::storage::devices::DeviceStore>,
+    pub payment_store: std::sync::Arc<dyn crate::storage::payments::PaymentStore>,
+    pub rfq_order_store: std::sync::Arc<dyn crate::storage::rfq_orders::RfqOrderStore>,
+    pub fee_estimator: std::sync::Arc<crate::gateway::fees::FeeEstimator>,
+    pub rfq_settings: crate::config::RfqSettings,
+    /// Answers RFQ requests locally instead of through tapd when set
+    pub rfq_simulator: Option<std::sync::Arc<crate::gateway::rfq_sim::RfqSimulator>>,
+    pub price_oracle: std::sync::Arc<dyn crate::gateway::price_oracle::PriceOracle>,
+    /// Rates older than this are reported as stale
+    pub price_oracle_max_age_secs: u64,
+    pub price_alerts: std::sync::Arc<crate::gateway::price_alerts::PriceAlertMonitor>,
+    pub image_store: std::sync::Arc<dyn crate::storage::images::ImageStore>,
+    /// Largest accepted collectible image upload
+    pub image_max_bytes: usize,
+    /// Currency `/wallet/balance` values assets in, when configured
+    pub fiat_reference: Option<crate::config::FiatReference>,
+    pub burn_settings: crate::config::BurnSettings,
+    /// Shared with `tapd_client`, which drops holdings after sends and mints
+    pub response_cache: std::sync::Arc<crate::gateway::cache::ResponseCache>,
+    /// Results of sends, mints and burns replayed to retries by `Idempotency-Key`
+    pub idempotency_store: std::sync::Arc<dyn crate::storage::idempotency::IdempotencyStore>,
+    pub idempotency_ttl_secs: u64,
+    /// Favorite and hidden assets per profile
+    pub asset_preferences:
+        std::sync::Arc<dyn crate::storage::asset_preferences::AssetPreferenceStore>,
+    /// App and user preferences from `/api/settings`
+    pub settings: std::sync::Arc<dyn crate::storage::settings::SettingsStore>,
+    /// Hourly and daily per-asset balances sampled from tapd
+    pub balance_snapshots:
+        std::sync::Arc<dyn crate::storage::balance_snapshots::BalanceSnapshotStore>,
+    /// Integrator webhooks and their background delivery
+    pub webhooks: std::sync::Arc<crate::gateway::webhooks::WebhookWorker>,
+    /// Sends, mints, burns, channel changes and admin actions
+    pub audit_log: std::sync::Arc<dyn crate::storage::audit::AuditLog>,
+}
+
+impl AppState {
+    /// State with every store in memory and no background tasks, for running
+    /// handlers against `gateway_url` without a database
+    pub fn in_memory(gateway_url: &str) -> Result<Self, crate::error::AppError> {
+        use std::sync::Arc;
+
+        use crate::config::{
+            BurnSettings, CacheSettings, FeeSettings, MailboxSettings, PriceOracleSettings,
+            RfqSettings, WebhookSettings,
+        };
+        use crate::error::AppError;
+        use crate::gateway::{
+            cache, events, fees, mailbox_limits, mailbox_registry, mailbox_webhooks, metrics,
+            price_alerts, price_oracle, reconcile, webhooks,
+        };
+        use crate::storage::{
+            asset_preferences, audit, balance_snapshots, challenges, devices, events as event_log,
+            idempotency, images, invoices, payments, price_alerts as alert_store, receivers,
+            rfq_orders, settings, transactions, webhooks as hooks,
+        };
+
+        let metrics = Arc::new(metrics::PromMonitoring::new().map_err(|e| {
+            AppError::ValidationError(format!("Failed to create metrics: {e}"))
+        })?);
+        let http_client = Arc::new(reqwest::Client::new());
+        let response_cache = Arc::new(cache::ResponseCache::new(&CacheSettings::default()));
+        let tapd_client = crate::taproot::client::TapdClient::new(gateway_url.to_string())
+            .with_cache(response_cache.clone());
+        let event_broker = events::EventBroker::new(
+            gateway_url.to_string(),
+            String::new(),
+            metrics.event_metrics(),
+            &crate::config::HttpClientSettings::default(),
+        )?;
+        let price_oracle_settings = PriceOracleSettings::default();
+        let price_oracle = price_oracle::create_price_oracle(
+            &price_oracle_settings,
+            http_client.clone(),
+            gateway_url.to_string(),
+            String::new(),
+        )?;
+        let mailbox_settings = MailboxSettings::default();
+        let transaction_store: Arc<dyn transactions::TransactionRepo> =
+            Arc::new(transactions::InMemoryTransactionRepo::new());
+        let reconciler = reconcile::Reconciler::new(
+            http_client.clone(),
+            gateway_url.to_string(),
+            String::new(),
+            transaction_store.clone(),
+        );
+
+        Ok(Self {
+            tapd_client: Arc::new(tapd_client),
+            http_client,
+            storage: None,
+            base_url: BaseUrl(gateway_url.to_string()),
+            macaroon_hex: MacaroonHex(String::new()),
+            mailbox_receivers: Some(Arc::new(receivers::InMemoryReceiverRepo::new())),
+            mailbox_monitoring: Some(metrics.clone()),
+            challenge_store: Arc::new(challenges::InMemoryChallengeStore::new()),
+            mailbox_limiter: Arc::new(mailbox_limits::MailboxLimiter::new(
+                mailbox_settings.clone(),
+            )),
+            mailbox_registry: Arc::new(mailbox_registry::MailboxRegistry::new()),
+            metrics,
+            mailbox_webhooks: Arc::new(mailbox_webhooks::WebhookDispatcher::new(
+                reqwest::Client::new(),
+                &mailbox_settings,
+            )),
+            event_broker: Arc::new(event_broker),
+            event_store: Arc::new(event_log::InMemoryEventStore::new()),
+            lnd_client: None,
+            chain_watcher: None,
+            reconciler: Arc::new(reconciler),
+            transaction_store,
+            invoice_repo: Arc::new(invoices::InMemoryInvoiceRepo::new()),
+            device_store: Arc::new(devices::InMemoryDeviceStore::new()),
+            payment_store: Arc::new(payments::InMemoryPaymentStore::new()),
+            rfq_order_store: Arc::new(rfq_orders::InMemoryRfqOrderStore::new()),
+            fee_estimator: Arc::new(fees::FeeEstimator::new(
+                None,
+                reqwest::Client::new(),
+                &FeeSettings::default(),
+            )),
+            rfq_settings: RfqSettings::default(),
+            rfq_simulator: None,
+            price_oracle: price_oracle.clone(),
+            price_oracle_max_age_secs: price_oracle_settings.max_age_secs,
+            price_alerts: Arc::new(price_alerts::PriceAlertMonitor::new(
+                Arc::new(alert_store::InMemoryPriceAlertStore::new()),
+                price_oracle,
+                reqwest::Client::new(),
+            )),
+            image_store: Arc::new(images::InMemoryImageStore::new()),
+            image_max_bytes: crate::config::MAX_ASSET_META_BYTES,
+            fiat_reference: None,
+            burn_settings: BurnSettings::default(),
+            response_cache,
+            idempotency_store: Arc::new(idempotency::InMemoryIdempotencyStore::new()),
+            idempotency_ttl_secs: crate::config::IdempotencyStoreSettings::default().ttl_secs,
+            asset_preferences: Arc::new(
+                asset_preferences::InMemoryAssetPreferenceStore::new(),
+            ),
+            settings: Arc::new(settings::InMemorySettingsStore::new()),
+            balance_snapshots: Arc::new(
+                balance_snapshots::InMemoryBalanceSnapshotStore::new(),
+            ),
+            webhooks: Arc::new(webhooks::WebhookWorker::new(
+                Arc::new(hooks::InMemoryWebhookStore::new()),
+                reqwest::Client::new(),
+                &WebhookSettings::default(),
+            )),
+            audit_log: Arc::new(audit::InMemoryAuditLog::new()),
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,14 +189,421 @@ pub struct TaprootAsset {
     pub decimals: u8,
     pub asset_type: AssetType,
     pub meta_data: Option<AssetMetaData>,
+    /// Set from the listing profile's preferences
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+}
+
+impl TaprootAsset {
+    /// Reads an asset from tapd's listing, or from one already in this
+    /// shape. Only the asset ID is required; other missing or malformed
+    /// fields fall back to defaults and are reported as problems.
+    pub fn from_upstream(asset: &serde_json::Value) -> Result<(Self, Vec<String>), String> {
+        let genesis = &asset["asset_genesis"];
+        let field = |name: &str| genesis.get(name).or_else(|| asset.get(name));
+        let mut problems = Vec::new();
+
+        let asset_id = field("asset_id")
+            .and_then(serde_json::Value::as_str)
+            .and_then(asset_id_hex)
+            .ok_or_else(|| "missing or malformed asset_id".to_string())?;
+        let asset_type = match field("asset_type") {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|_| {
+                problems.push(format!("unknown asset_type {value}, assuming normal"));
+                AssetType::Normal
+            }),
+            None => AssetType::Normal,
+        };
+        let meta_data = match asset.get("meta_data").filter(|meta| !meta.is_null()) {
+            Some(meta) => serde_json::from_value(meta.clone())
+                .map_err(|e| problems.push(format!("ignoring malformed meta_data: {e}")))
+                .ok(),
+            None => None,
+        };
+        let decimals = asset["decimal_display"]["decimal_display"]
+            .as_u64()
+            .or_else(|| asset["decimals"].as_u64())
+            .unwrap_or(0);
+
+        let parsed = TaprootAsset {
+            asset_id,
+            name: field("name").and_then(serde_json::Value::as_str).unwrap_or_default().to_string(),
+            balance: amount(asset),
+            decimals: decimals.min(u8::MAX as u64) as u8,
+            asset_type,
+            meta_data,
+            favorite: false,
+            hidden: false,
+        };
+        Ok((parsed, problems))
+    }
+}
+
+/// Part of an asset listing that could not be read as expected: an asset
+/// that was skipped or listed with defaults, or missing balance totals
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetWarning {
+    /// Position of the asset in this page; absent for page-wide warnings
+    pub index: Option<usize>,
+    pub asset_id: Option<String>,
+    pub message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub enum AssetType {
+    #[default]
+    #[serde(alias = "normal", alias = "NORMAL")]
     Normal,
+    #[serde(alias = "collectible", alias = "COLLECTIBLE")]
     Collectible,
 }
 
+/// Default page size for asset listings
+pub const ASSET_LIST_DEFAULT_LIMIT: usize = 100;
+/// Largest page of assets returned at once
+pub const ASSET_LIST_MAX_LIMIT: usize = 1000;
+
+/// Filters and paging for asset listings. `include_spent`, `include_leased`
+/// and `include_unconfirmed_mints` are tapd's own listing options and are
+/// forwarded to it; the rest apply here.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AssetListParams {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub asset_type: Option<AssetType>,
+    pub min_balance: Option<u64>,
+    /// Tweaked group key, hex
+    pub group_key: Option<String>,
+    pub include_spent: Option<bool>,
+    pub include_leased: Option<bool>,
+    pub include_unconfirmed_mints: Option<bool>,
+    /// Whose favorite and hidden preferences apply; `default` when unset
+    pub profile: Option<String>,
+    /// List assets the profile hid; they are left out by default
+    pub include_hidden: Option<bool>,
+    /// List only the profile's favorites
+    pub favorites_only: Option<bool>,
+    /// The profile's preferences by hex asset ID, loaded by the handler
+    #[serde(skip)]
+    pub preferences:
+        std::collections::HashMap<String, crate::storage::asset_preferences::AssetPreference>,
+}
+
+impl AssetListParams {
+    /// tapd query parameters for the flags that were set
+    pub fn upstream_query(&self) -> Vec<(&'static str, bool)> {
+        [
+            ("include_spent", self.include_spent),
+            ("include_leased", self.include_leased),
+            ("include_unconfirmed_mints", self.include_unconfirmed_mints),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+
+    /// Whether a tapd asset passes the type, balance and group filters
+    pub fn matches(&self, asset: &serde_json::Value) -> bool {
+        let asset_type = asset["asset_genesis"]
+            .get("asset_type")
+            .or_else(|| asset.get("asset_type"))
+            .and_then(|value| serde_json::from_value::<AssetType>(value.clone()).ok());
+        let amount = amount(asset);
+        let preference = asset["asset_genesis"]
+            .get("asset_id")
+            .or_else(|| asset.get("asset_id"))
+            .and_then(serde_json::Value::as_str)
+            .and_then(asset_id_hex)
+            .and_then(|asset_id| self.preferences.get(&asset_id));
+        let hidden = preference.is_some_and(|p| p.hidden);
+        let favorite = preference.is_some_and(|p| p.favorite);
+        (!hidden || self.include_hidden.unwrap_or(false))
+            && (favorite || !self.favorites_only.unwrap_or(false))
+            && self.asset_type.as_ref().is_none_or(|wanted| asset_type.as_ref() == Some(wanted))
+            && self.min_balance.is_none_or(|min| amount >= min)
+            && self.group_key.as_ref().is_none_or(|wanted| {
+                group_key_hex(asset).is_some_and(|key| key.eq_ignore_ascii_case(wanted))
+            })
+    }
+
+    /// Marks an asset with the profile's preference for it
+    pub fn apply_preference(&self, asset: &mut TaprootAsset) {
+        if let Some(preference) = self.preferences.get(&asset.asset_id) {
+            asset.favorite = preference.favorite;
+            asset.hidden = preference.hidden;
+        }
+    }
+
+    /// Filters `assets` and cuts out the requested page
+    pub fn page(&self, assets: Vec<serde_json::Value>) -> AssetPage<serde_json::Value> {
+        let total = assets.len();
+        let matching: Vec<serde_json::Value> =
+            assets.into_iter().filter(|asset| self.matches(asset)).collect();
+        let matched = matching.len();
+        let limit = self
+            .limit
+            .unwrap_or(ASSET_LIST_DEFAULT_LIMIT)
+            .clamp(1, ASSET_LIST_MAX_LIMIT);
+        let offset = self.offset.unwrap_or(0);
+        AssetPage {
+            assets: matching.into_iter().skip(offset).take(limit).collect(),
+            total,
+            matched,
+            next_offset: (matched > offset + limit).then_some(offset + limit),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// tapd's REST API sends 64-bit amounts as strings
+fn amount(asset: &serde_json::Value) -> u64 {
+    match asset.get("amount").or_else(|| asset.get("balance")) {
+        Some(serde_json::Value::String(amount)) => amount.parse().unwrap_or(0),
+        Some(amount) => amount.as_u64().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// Asset IDs arrive as base64 from tapd's REST API and as hex elsewhere
+pub fn asset_id_hex(id: &str) -> Option<String> {
+    use base64::Engine;
+
+    if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(id.to_ascii_lowercase());
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(id)
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .map(hex::encode)
+}
+
+/// tapd's REST API returns the tweaked group key as base64; hex is accepted too
+fn group_key_hex(asset: &serde_json::Value) -> Option<String> {
+    use base64::Engine;
+
+    let key = asset["asset_group"]["tweaked_group_key"].as_str()?;
+    if key.len() == 66 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(key.to_ascii_lowercase());
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(key)
+        .ok()
+        .map(hex::encode)
+}
+
+/// An asset group with the wallet's balance across its members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetGroup {
+    /// Tweaked group key, hex
+    pub group_key: String,
+    pub balance: u64,
+    pub assets: Vec<GroupedAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupedAsset {
+    /// Hex
+    pub asset_id: String,
+    pub tag: String,
+    pub amount: u64,
+    pub asset_type: Option<AssetType>,
+}
+
+/// Reads tapd's `ListGroupsResponse`, whose `groups` map is keyed by hex group
+/// key; asset IDs arrive as base64
+pub fn parse_asset_groups(response: &serde_json::Value) -> Vec<AssetGroup> {
+    use base64::Engine;
+
+    let Some(groups) = response["groups"].as_object() else {
+        return Vec::new();
+    };
+    let mut parsed: Vec<AssetGroup> = groups
+        .iter()
+        .map(|(group_key, group)| {
+            let assets: Vec<GroupedAsset> = group["assets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|asset| GroupedAsset {
+                    asset_id: asset["id"]
+                        .as_str()
+                        .and_then(|id| base64::engine::general_purpose::STANDARD.decode(id).ok())
+                        .map(hex::encode)
+                        .unwrap_or_default(),
+                    tag: asset["tag"].as_str().unwrap_or_default().to_string(),
+                    amount: match &asset["amount"] {
+                        serde_json::Value::String(amount) => amount.parse().unwrap_or(0),
+                        amount => amount.as_u64().unwrap_or(0),
+                    },
+                    asset_type: serde_json::from_value(asset["type"].clone()).ok(),
+                })
+                .collect();
+            AssetGroup {
+                group_key: group_key.clone(),
+                balance: assets.iter().map(|asset| asset.amount).sum(),
+                assets,
+            }
+        })
+        .collect();
+    parsed.sort_by(|a, b| a.group_key.cmp(&b.group_key));
+    parsed
+}
+
+/// Longest asset name tapd accepts
+pub const MAX_ASSET_NAME_LENGTH: usize = 64;
+/// Most decimal places tapd accepts for `decimal_display`
+pub const MAX_DECIMAL_DISPLAY: u32 = 12;
+
+/// A new asset for the next minting batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintAssetRequest {
+    pub name: String,
+    /// In base units, before `decimal_display` is applied
+    pub amount: u64,
+    #[serde(default)]
+    pub asset_type: AssetType,
+    /// Decimal places wallets show, e.g. 2 displays an amount of 150 as 1.50
+    #[serde(default)]
+    pub decimal_display: u32,
+    #[serde(flatten)]
+    pub grouping: MintGrouping,
+}
+
+impl MintAssetRequest {
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        use crate::error::AppError;
+
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_ASSET_NAME_LENGTH {
+            return Err(AppError::InvalidInput(format!(
+                "name must be between 1 and {MAX_ASSET_NAME_LENGTH} bytes"
+            )));
+        }
+        if self.amount == 0 {
+            return Err(AppError::InvalidInput("amount must be greater than 0".to_string()));
+        }
+        if self.decimal_display > MAX_DECIMAL_DISPLAY {
+            return Err(AppError::InvalidInput(format!(
+                "decimal_display must be at most {MAX_DECIMAL_DISPLAY}"
+            )));
+        }
+        if self.asset_type == AssetType::Collectible
+            && (self.amount != 1 || self.decimal_display != 0)
+        {
+            return Err(AppError::InvalidInput(
+                "A collectible has an amount of 1 and no decimal_display".to_string(),
+            ));
+        }
+        self.grouping.validate()
+    }
+
+    /// tapd's `MintAsset` message
+    pub fn upstream_asset(&self) -> serde_json::Value {
+        let asset_type = match self.asset_type {
+            AssetType::Normal => "NORMAL",
+            AssetType::Collectible => "COLLECTIBLE",
+        };
+        let mut asset = serde_json::json!({
+            "asset_type": asset_type,
+            "name": self.name.trim(),
+            "amount": self.amount.to_string(),
+        });
+        if self.decimal_display > 0 {
+            asset["decimal_display"] = self.decimal_display.into();
+        }
+        self.grouping.apply(&mut asset);
+        asset
+    }
+}
+
+impl Validate for MintAssetRequest {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        let message = match self.validate() {
+            Ok(()) => return Vec::new(),
+            Err(crate::error::AppError::InvalidInput(message)) => message,
+            Err(e) => e.to_string(),
+        };
+        vec![FieldError {
+            field: None,
+            message,
+        }]
+    }
+}
+
+/// How a newly minted asset joins an asset group; at most one option may be set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MintGrouping {
+    /// Start a new group that later issuance can join
+    #[serde(default)]
+    pub new_grouped_asset: bool,
+    /// Tweaked key of an existing group to issue more supply into, hex
+    pub group_key: Option<String>,
+    /// Name of another asset in the same pending batch whose new group to join
+    pub group_anchor: Option<String>,
+}
+
+impl MintGrouping {
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        use crate::error::AppError;
+
+        let options = usize::from(self.new_grouped_asset)
+            + usize::from(self.group_key.is_some())
+            + usize::from(self.group_anchor.is_some());
+        if options > 1 {
+            return Err(AppError::InvalidInput(
+                "Only one of new_grouped_asset, group_key and group_anchor may be set".to_string(),
+            ));
+        }
+        if let Some(group_key) = &self.group_key {
+            let compressed = hex::decode(group_key)
+                .is_ok_and(|key| key.len() == 33 && matches!(key[0], 0x02 | 0x03));
+            if !compressed {
+                return Err(AppError::InvalidInput(
+                    "group_key must be a 33-byte compressed public key in hex".to_string(),
+                ));
+            }
+        }
+        if self.group_anchor.as_ref().is_some_and(|anchor| anchor.trim().is_empty()) {
+            return Err(AppError::InvalidInput("group_anchor must not be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Sets tapd's `MintAsset` grouping fields on `asset`
+    pub fn apply(&self, asset: &mut serde_json::Value) {
+        use base64::Engine;
+
+        if self.new_grouped_asset {
+            asset["new_grouped_asset"] = true.into();
+        }
+        if let Some(key) = self.group_key.as_ref().and_then(|key| hex::decode(key).ok()) {
+            asset["grouped_asset"] = true.into();
+            asset["group_key"] = base64::engine::general_purpose::STANDARD.encode(key).into();
+        }
+        if let Some(anchor) = &self.group_anchor {
+            asset["grouped_asset"] = true.into();
+            asset["group_anchor"] = anchor.clone().into();
+        }
+    }
+}
+
+/// One page of an asset listing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetPage<T> {
+    pub assets: Vec<T>,
+    /// Assets tapd returned before filtering
+    pub total: usize,
+    /// Assets that passed the filters
+    pub matched: usize,
+    /// Offset of the next page, absent on the last page
+    pub next_offset: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<AssetWarning>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AssetMetaData {
     pub description: Option<String>,
@@ -33,12 +611,384 @@ pub struct AssetMetaData {
     pub issuer: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How tapd totals balances: per asset ID, or per asset group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceGrouping {
+    #[default]
+    AssetId,
+    GroupKey,
+}
+
+impl BalanceGrouping {
+    /// Query flag selecting the grouping in tapd's `ListBalances`
+    pub fn upstream_query(&self) -> &'static str {
+        match self {
+            BalanceGrouping::AssetId => "asset_id=true",
+            BalanceGrouping::GroupKey => "group_key=true",
+        }
+    }
+}
+
+/// Which asset outputs fund a send
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum CoinSelection {
+    /// tapd picks the inputs
+    #[default]
+    Auto,
+    /// Fewest inputs, leaving small outputs untouched
+    LargestFirst,
+    /// Consolidates small outputs first
+    SmallestFirst,
+    /// Exactly these anchor outputs, as `txid:vout`
+    Outpoints { outpoints: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetTransfer {
     pub asset_id: String,
+    /// In base units; may be left out when `amount_display` is set
+    #[serde(default)]
     pub amount: u64,
+    /// The amount in the asset's display units, e.g. "1.50" for 150 base
+    /// units of an asset with two decimal places
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_display: Option<String>,
     pub destination: String,
     pub fee_rate: Option<u32>,
+    #[serde(default)]
+    pub coin_selection: CoinSelection,
+    /// Kept with the transaction record and passed to tapd's transfer log
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Validate for AssetTransfer {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if asset_id_hex(&self.asset_id).is_none() {
+            errors.push(FieldError::new("asset_id", "must be 32 bytes of hex or base64"));
+        }
+        match &self.amount_display {
+            Some(display) => {
+                let places = MAX_DECIMAL_DISPLAY as u8;
+                match crate::taproot::amounts::parse_amount(display, places) {
+                    Ok(0) => errors.push(FieldError::new("amount_display", "must be above 0")),
+                    Ok(_) => {}
+                    Err(e) => errors.push(FieldError::new("amount_display", e.to_string())),
+                }
+            }
+            None if self.amount == 0 => {
+                errors.push(FieldError::new("amount", "must be greater than 0"));
+            }
+            None => {}
+        }
+        if let Err(e) = crate::gateway::addresses::check_address_encoding(&self.destination) {
+            errors.push(FieldError::new("destination", e.to_string()));
+        }
+        if self.fee_rate == Some(0) {
+            errors.push(FieldError::new("fee_rate", "must be greater than 0 when set"));
+        }
+        if let Some(message) = check_label(self.label.as_deref()) {
+            errors.push(FieldError::new("label", message));
+        }
+        errors
+    }
+}
+
+/// Longest label accepted on sends and invoices
+pub const MAX_LABEL_LENGTH: usize = 128;
+
+/// Why a label cannot be stored, if it cannot
+pub fn check_label(label: Option<&str>) -> Option<String> {
+    let label = label?;
+    if label.chars().count() > MAX_LABEL_LENGTH {
+        return Some(format!("must be at most {MAX_LABEL_LENGTH} characters"));
+    }
+    label.chars().any(char::is_control).then(|| "must not contain control characters".to_string())
+}
+
+/// Query of POST /api/assets/send
+#[derive(Debug, Default, Deserialize)]
+pub struct SendAssetParams {
+    /// Preview the send without broadcasting anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Typical virtual size of a send's anchor transaction: one taproot input
+/// plus the recipient's and the change outputs
+pub const SEND_ANCHOR_VBYTES: u64 = 154;
+
+/// The anchor fee a send would pay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SendFeePreview {
+    pub sat_per_vbyte: u64,
+    /// `sat_per_vbyte` over `SEND_ANCHOR_VBYTES`
+    pub estimated_fee_sat: u64,
+    /// Absent when the request set `fee_rate`
+    pub source: Option<crate::gateway::fees::FeeSource>,
+}
+
+impl SendFeePreview {
+    pub fn new(sat_per_vbyte: u64, source: Option<crate::gateway::fees::FeeSource>) -> Self {
+        Self {
+            sat_per_vbyte,
+            estimated_fee_sat: sat_per_vbyte * SEND_ANCHOR_VBYTES,
+            source,
+        }
+    }
+}
+
+/// What `POST /api/assets/send?dry_run=true` found; nothing is sent
+#[derive(Debug, Clone, Serialize)]
+pub struct SendPreview {
+    pub destination: crate::gateway::addresses::DecodedAddress,
+    /// Spendable balance of the asset
+    pub balance: u64,
+    /// Balance left once the address is paid
+    pub remaining_balance: u64,
+    /// Absent when no fee source could be reached
+    pub fee: Option<SendFeePreview>,
+    /// Reasons the send would fail; empty when it would go through
+    pub problems: Vec<String>,
+    pub would_succeed: bool,
+}
+
+impl SendPreview {
+    /// Compares the request with its decoded destination and the balance.
+    /// The address fixes the asset and amount paid, so both must match.
+    pub fn new(
+        transfer: &AssetTransfer,
+        destination: crate::gateway::addresses::DecodedAddress,
+        balance: u64,
+        fee: Option<SendFeePreview>,
+    ) -> Self {
+        let mut problems = Vec::new();
+        if asset_id_hex(&transfer.asset_id).as_deref() != Some(destination.asset_id.as_str()) {
+            problems.push(format!(
+                "destination pays asset {}, not {}",
+                destination.asset_id, transfer.asset_id
+            ));
+        }
+        if destination.amount != transfer.amount {
+            problems.push(format!(
+                "destination requests {}, not {}",
+                destination.amount, transfer.amount
+            ));
+        }
+        if destination.amount > balance {
+            problems.push(format!(
+                "destination requests {} but the balance is {balance}",
+                destination.amount
+            ));
+        }
+        Self {
+            remaining_balance: balance.saturating_sub(destination.amount),
+            balance,
+            fee,
+            would_succeed: problems.is_empty(),
+            problems,
+            destination,
+        }
+    }
+}
+
+/// Body of POST /api/assets/address
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAddressRequest {
+    pub asset_id: String,
+    pub amount: u64,
+}
+
+impl Validate for CreateAddressRequest {
+    fn validate_fields(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if asset_id_hex(&self.asset_id).is_none() {
+            errors.push(FieldError::new("asset_id", "must be 32 bytes of hex or base64"));
+        }
+        if self.amount == 0 {
+            errors.push(FieldError::new("amount", "must be greater than 0"));
+        }
+        errors
+    }
+}
+
+/// Most addresses paid by one anchor transaction
+pub const MAX_BATCH_RECIPIENTS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecipient {
+    pub asset_id: String,
+    pub amount: u64,
+    /// Taproot Assets address
+    pub destination: String,
+}
+
+/// Pays several addresses from one anchor transaction, sharing its fee
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchTransfer {
+    pub recipients: Vec<TransferRecipient>,
+    pub fee_rate: Option<u32>,
+    #[serde(default)]
+    pub coin_selection: CoinSelection,
+    /// Applies to every recipient's transaction record
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl BatchTransfer {
+    /// Why each recipient cannot be sent, in order; all `None` when the batch
+    /// can go out. tapd rejects the whole batch for any bad address, so a
+    /// batch with rejections is not sent at all.
+    pub fn check_recipients(&self) -> Vec<Option<String>> {
+        let mut seen = std::collections::HashSet::new();
+        self.recipients
+            .iter()
+            .map(|recipient| {
+                let destination = recipient.destination.trim();
+                if let Err(e) = crate::gateway::addresses::check_address_encoding(destination) {
+                    return Some(e.to_string());
+                }
+                if !seen.insert(destination) {
+                    return Some("Duplicate destination in batch".to_string());
+                }
+                None
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        if let Some(message) = check_label(self.label.as_deref()) {
+            return Err(crate::error::AppError::InvalidInput(format!("label {message}")));
+        }
+        if self.recipients.is_empty() || self.recipients.len() > MAX_BATCH_RECIPIENTS {
+            return Err(crate::error::AppError::InvalidInput(format!(
+                "A batch needs between 1 and {MAX_BATCH_RECIPIENTS} recipients"
+            )));
+        }
+        // Selected inputs fund a single asset's virtual transaction
+        let first_asset = &self.recipients[0].asset_id;
+        if self.coin_selection != CoinSelection::Auto
+            && self.recipients.iter().any(|recipient| &recipient.asset_id != first_asset)
+        {
+            return Err(crate::error::AppError::InvalidInput(
+                "coin_selection requires every recipient to receive the same asset".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientStatus {
+    /// Included in the anchor transaction
+    Sent,
+    /// Failed local checks; the batch was not sent
+    Rejected,
+    /// Not paid because the batch was refused upstream or by another recipient
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipientOutcome {
+    pub destination: String,
+    pub status: RecipientStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTransferResult {
+    pub anchor_tx_hash: Option<String>,
+    pub recipients: Vec<RecipientOutcome>,
+}
+
+impl BatchTransferResult {
+    /// Every recipient shares the outcome of the single anchor transaction
+    pub fn uniform(
+        transfer: &BatchTransfer,
+        status: RecipientStatus,
+        anchor_tx_hash: Option<String>,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            anchor_tx_hash,
+            recipients: transfer
+                .recipients
+                .iter()
+                .map(|recipient| RecipientOutcome {
+                    destination: recipient.destination.clone(),
+                    status,
+                    error: error.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Outcomes for a batch that failed local checks
+    pub fn rejected(transfer: &BatchTransfer, errors: Vec<Option<String>>) -> Self {
+        Self {
+            anchor_tx_hash: None,
+            recipients: transfer
+                .recipients
+                .iter()
+                .zip(errors)
+                .map(|(recipient, error)| RecipientOutcome {
+                    destination: recipient.destination.clone(),
+                    status: if error.is_some() {
+                        RecipientStatus::Rejected
+                    } else {
+                        RecipientStatus::Failed
+                    },
+                    error,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Separate sends run at once by POST /api/assets/send-batch
+pub const SEND_BATCH_CONCURRENCY: usize = 4;
+
+/// How one send of a multi-send went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferOutcome {
+    /// Position in the request
+    pub index: usize,
+    pub destination: String,
+    pub success: bool,
+    pub tx_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransferBatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Outcomes of POST /api/assets/send-batch, one send per transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferBatchResult {
+    pub results: Vec<TransferOutcome>,
+    pub summary: TransferBatchSummary,
+}
+
+impl TransferBatchResult {
+    pub fn new(results: Vec<TransferOutcome>) -> Self {
+        let succeeded = results.iter().filter(|outcome| outcome.success).count();
+        Self {
+            summary: TransferBatchSummary {
+                total: results.len(),
+                succeeded,
+                failed: results.len() - succeeded,
+            },
+            results,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,7 +999,7 @@ pub struct AssetInvoice {
     pub expiry: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: Uuid,
     pub tx_type: TransactionType,
@@ -58,22 +1008,262 @@ pub struct Transaction {
     pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Free text the user attached when sending or invoicing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum TransactionType {
+    #[serde(alias = "send")]
     Send,
+    #[serde(alias = "receive")]
     Receive,
+    #[serde(alias = "issue")]
     Issue,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum TransactionStatus {
+    #[serde(alias = "pending")]
     Pending,
+    #[serde(alias = "confirmed")]
     Confirmed,
+    #[serde(alias = "failed")]
     Failed,
 }
 
+/// Default page size for the transaction history
+pub const TRANSACTION_LIST_DEFAULT_LIMIT: usize = 100;
+/// Largest page of transactions returned at once
+pub const TRANSACTION_LIST_MAX_LIMIT: usize = 1000;
+
+/// Order of the transaction history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSort {
+    #[default]
+    Newest,
+    Oldest,
+    AmountDesc,
+    AmountAsc,
+}
+
+/// Filters and paging for the transaction history. `from` and `to` bound
+/// `created_at`, inclusive.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransactionQuery {
+    pub asset_id: Option<String>,
+    pub tx_type: Option<TransactionType>,
+    pub status: Option<TransactionStatus>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Exact label match
+    pub label: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub sort: TransactionSort,
+}
+
+impl TransactionQuery {
+    pub fn validate(&self) -> Result<(), crate::error::AppError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(crate::error::AppError::InvalidInput(
+                    "from must not be after to".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(TRANSACTION_LIST_DEFAULT_LIMIT)
+            .clamp(1, TRANSACTION_LIST_MAX_LIMIT)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+
+    /// Whether a transaction passes every filter that was set
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        self.asset_id
+            .as_ref()
+            .is_none_or(|wanted| transaction.asset_id.as_deref() == Some(wanted.as_str()))
+            && self.tx_type.is_none_or(|wanted| transaction.tx_type == wanted)
+            && self.status.is_none_or(|wanted| transaction.status == wanted)
+            && self.from.is_none_or(|from| transaction.created_at >= from)
+            && self.to.is_none_or(|to| transaction.created_at <= to)
+            && self
+                .label
+                .as_ref()
+                .is_none_or(|wanted| transaction.label.as_deref() == Some(wanted.as_str()))
+    }
+
+    /// Filters, sorts and cuts out the requested page. Ties keep the newest
+    /// first, so pages are stable.
+    pub fn page(&self, transactions: Vec<Transaction>) -> TransactionPage {
+        let mut matching: Vec<Transaction> =
+            transactions.into_iter().filter(|tx| self.matches(tx)).collect();
+        matching.sort_by(|a, b| {
+            let newest_first = b.created_at.cmp(&a.created_at);
+            match self.sort {
+                TransactionSort::Newest => newest_first,
+                TransactionSort::Oldest => a.created_at.cmp(&b.created_at),
+                TransactionSort::AmountDesc => b.amount.cmp(&a.amount).then(newest_first),
+                TransactionSort::AmountAsc => a.amount.cmp(&b.amount).then(newest_first),
+            }
+        });
+        let total = matching.len();
+        let (limit, offset) = (self.limit(), self.offset());
+        TransactionPage {
+            transactions: matching.into_iter().skip(offset).take(limit).collect(),
+            total,
+            limit,
+            offset,
+            next_offset: (total > offset + limit).then_some(offset + limit),
+        }
+    }
+}
+
+/// One page of the transaction history
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionPage<T = Transaction> {
+    pub transactions: Vec<T>,
+    /// Transactions that passed the filters
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    /// Offset of the next page, absent on the last page
+    pub next_offset: Option<usize>,
+}
+
+impl<T> TransactionPage<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> TransactionPage<U> {
+        TransactionPage {
+            transactions: self.transactions.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            next_offset: self.next_offset,
+        }
+    }
+}
+
+/// A transaction with its amount also in the asset's display units
+#[derive(Debug, Serialize)]
+pub struct DisplayedTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    /// Absent when the asset's `decimal_display` is unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_display: Option<String>,
+}
+
+impl DisplayedTransaction {
+    pub fn new(transaction: Transaction, decimals: &std::collections::HashMap<String, u8>) -> Self {
+        let amount_display = transaction
+            .asset_id
+            .as_deref()
+            .and_then(asset_id_hex)
+            .and_then(|asset_id| decimals.get(&asset_id))
+            .map(|&places| crate::taproot::amounts::format_amount(transaction.amount, places));
+        Self {
+            transaction,
+            amount_display,
+        }
+    }
+}
+
+/// Shortest accepted search text
+pub const SEARCH_MIN_QUERY_LENGTH: usize = 2;
+pub const SEARCH_DEFAULT_LIMIT: usize = 10;
+pub const SEARCH_MAX_LIMIT: usize = 50;
+
+/// Query of GET /api/search
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchParams {
+    pub q: String,
+    /// Most results per category
+    pub limit: Option<usize>,
+}
+
+impl SearchParams {
+    /// The trimmed search text, if long enough to search for
+    pub fn text(&self) -> Result<&str, crate::error::AppError> {
+        let text = self.q.trim();
+        if text.chars().count() < SEARCH_MIN_QUERY_LENGTH {
+            return Err(crate::error::AppError::InvalidInput(format!(
+                "q must be at least {SEARCH_MIN_QUERY_LENGTH} characters"
+            )));
+        }
+        Ok(text)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(SEARCH_DEFAULT_LIMIT).clamp(1, SEARCH_MAX_LIMIT)
+    }
+
+    /// Assets whose name contains the text, ignoring case, or whose hex ID
+    /// starts with it
+    pub fn matches_asset(&self, asset: &TaprootAsset) -> bool {
+        let text = self.q.trim().to_lowercase();
+        asset.name.to_lowercase().contains(&text) || asset.asset_id.starts_with(&text)
+    }
+
+    /// Addresses containing the text, or paying an asset whose ID starts
+    /// with it
+    pub fn matches_address(&self, address: &crate::gateway::addresses::DecodedAddress) -> bool {
+        let text = self.q.trim().to_lowercase();
+        address.encoded.to_lowercase().contains(&text) || address.asset_id.starts_with(&text)
+    }
+}
+
+/// Matches for one search, grouped by category. A category whose source
+/// could not be reached is empty and explained in `warnings`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub assets: Vec<TaprootAsset>,
+    pub addresses: Vec<crate::gateway::addresses::DecodedAddress>,
+    pub transactions: Vec<Transaction>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Which gateway build is running and against which nodes
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Absent when the build had no git checkout or `GIT_COMMIT`
+    pub git_commit: Option<&'static str>,
+    pub build_timestamp: Option<DateTime<Utc>>,
+    /// Optional integrations this instance was configured with
+    pub features: Vec<&'static str>,
+    /// Absent when the node could not be reached
+    pub tapd_version: Option<String>,
+    pub lnd_version: Option<String>,
+}
+
+impl VersionInfo {
+    /// The version details fixed at build time
+    pub fn build() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: option_env!("GIT_COMMIT"),
+            build_timestamp: option_env!("BUILD_TIMESTAMP")
+                .and_then(|secs| secs.parse().ok())
+                .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+            features: Vec::new(),
+            tapd_version: None,
+            lnd_version: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
@@ -109,6 +1299,8 @@ mod tests {
                 image_url: Some("https://example.com/image.png".to_string()),
                 issuer: Some("Test Issuer".to_string()),
             }),
+            favorite: false,
+            hidden: false,
         };
 
         let json = serde_json::to_string(&asset).unwrap();
@@ -136,6 +1328,8 @@ mod tests {
             decimals: 8,
             asset_type: AssetType::Collectible,
             meta_data: None,
+            favorite: false,
+            hidden: false,
         };
 
         let json = serde_json::to_string(&asset).unwrap();
@@ -149,13 +1343,236 @@ mod tests {
         assert!(deserialized.meta_data.is_none());
     }
 
+    #[test]
+    fn test_asset_list_filters_and_pages() {
+        use base64::Engine;
+
+        let group_key = format!("02{}", "ab".repeat(32));
+        let encoded_key =
+            base64::engine::general_purpose::STANDARD.encode(hex::decode(&group_key).unwrap());
+        let assets = vec![
+            serde_json::json!({
+                "asset_genesis": {"asset_type": "NORMAL"},
+                "amount": "500",
+                "asset_group": {"tweaked_group_key": encoded_key}
+            }),
+            serde_json::json!({"asset_genesis": {"asset_type": "NORMAL"}, "amount": "50"}),
+            serde_json::json!({"asset_genesis": {"asset_type": "COLLECTIBLE"}, "amount": "1"}),
+        ];
+
+        let normal = AssetListParams {
+            asset_type: Some(AssetType::Normal),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let page = normal.page(assets.clone());
+        assert_eq!((page.total, page.matched, page.assets.len()), (3, 2, 1));
+        assert_eq!(page.next_offset, Some(1));
+
+        let rich = AssetListParams {
+            min_balance: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(rich.page(assets.clone()).matched, 1);
+
+        let grouped = AssetListParams {
+            group_key: Some(group_key.to_uppercase()),
+            ..Default::default()
+        };
+        let page = grouped.page(assets);
+        assert_eq!(page.matched, 1);
+        assert_eq!(page.assets[0]["amount"], "500");
+        assert_eq!(page.next_offset, None);
+
+        let flags = AssetListParams {
+            include_spent: Some(true),
+            include_leased: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            flags.upstream_query(),
+            vec![("include_spent", true), ("include_leased", false)]
+        );
+    }
+
+    #[test]
+    fn test_asset_list_respects_preferences() {
+        use crate::storage::asset_preferences::AssetPreference;
+
+        let asset = |id: &str| serde_json::json!({"asset_genesis": {"asset_id": id.repeat(32)}});
+        let assets = vec![asset("aa"), asset("bb"), asset("cc")];
+        let preference = |id: &str, favorite: bool, hidden: bool| {
+            let preference = AssetPreference {
+                asset_id: id.repeat(32),
+                favorite,
+                hidden,
+                updated_at: 0,
+            };
+            (preference.asset_id.clone(), preference)
+        };
+        let mut params = AssetListParams {
+            preferences: [preference("aa", true, false), preference("bb", false, true)].into(),
+            ..Default::default()
+        };
+        assert_eq!(params.page(assets.clone()).matched, 2);
+
+        params.include_hidden = Some(true);
+        assert_eq!(params.page(assets.clone()).matched, 3);
+
+        params.favorites_only = Some(true);
+        let page = params.page(assets);
+        assert_eq!(page.matched, 1);
+        let (mut favorite, _) = TaprootAsset::from_upstream(&page.assets[0]).unwrap();
+        params.apply_preference(&mut favorite);
+        assert!(favorite.favorite && !favorite.hidden);
+    }
+
+    #[test]
+    fn test_transaction_query_filters_sorts_and_pages() {
+        let start = Utc::now();
+        let transaction = |minutes: i64, tx_type, amount, asset_id: &str| Transaction {
+            id: Uuid::new_v4(),
+            tx_type,
+            asset_id: Some(asset_id.to_string()),
+            amount,
+            status: TransactionStatus::Confirmed,
+            created_at: start + chrono::Duration::minutes(minutes),
+            updated_at: start,
+            label: (amount >= 30).then(|| "payroll".to_string()),
+        };
+        let history = vec![
+            transaction(0, TransactionType::Send, 30, "usdt"),
+            transaction(1, TransactionType::Receive, 10, "usdt"),
+            transaction(2, TransactionType::Send, 20, "usdt"),
+            transaction(3, TransactionType::Send, 50, "other"),
+        ];
+
+        let sends: TransactionQuery = serde_json::from_value(serde_json::json!({
+            "asset_id": "usdt",
+            "tx_type": "send",
+            "sort": "amount_asc",
+            "limit": 1
+        }))
+        .unwrap();
+        let page = sends.page(history.clone());
+        assert_eq!((page.total, page.next_offset), (2, Some(1)));
+        assert_eq!(page.transactions[0].amount, 20);
+
+        let window = TransactionQuery {
+            from: Some(start + chrono::Duration::minutes(1)),
+            to: Some(start + chrono::Duration::minutes(2)),
+            ..Default::default()
+        };
+        let page = window.page(history.clone());
+        assert_eq!(page.total, 2);
+        assert_eq!(page.transactions[0].amount, 20);
+
+        let labelled = TransactionQuery {
+            label: Some("payroll".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(labelled.page(history).total, 2);
+
+        let backwards = TransactionQuery {
+            from: window.to,
+            to: window.from,
+            ..Default::default()
+        };
+        assert!(backwards.validate().is_err());
+    }
+
+    #[test]
+    fn test_taproot_asset_reads_tapd_listing_leniently() {
+        use base64::Engine;
+
+        let asset_id = "cd".repeat(32);
+        let encoded_id =
+            base64::engine::general_purpose::STANDARD.encode(hex::decode(&asset_id).unwrap());
+        let listed = serde_json::json!({
+            "asset_genesis": {"asset_id": encoded_id, "name": "USDT", "asset_type": "NORMAL"},
+            "amount": "1500",
+            "decimal_display": {"decimal_display": 2},
+            "meta_data": "not an object",
+            "unknown_field": true
+        });
+        let (asset, problems) = TaprootAsset::from_upstream(&listed).unwrap();
+        assert_eq!(asset.asset_id, asset_id);
+        assert_eq!((asset.name.as_str(), asset.balance, asset.decimals), ("USDT", 1500, 2));
+        assert!(asset.meta_data.is_none());
+        assert_eq!(problems.len(), 1);
+
+        let flat = serde_json::to_value(&asset).unwrap();
+        assert_eq!(TaprootAsset::from_upstream(&flat).unwrap().0.asset_id, asset_id);
+        assert!(TaprootAsset::from_upstream(&serde_json::json!({"amount": "1"})).is_err());
+    }
+
+    #[test]
+    fn test_parse_asset_groups() {
+        use base64::Engine;
+
+        let id = base64::engine::general_purpose::STANDARD.encode([0x11; 32]);
+        let response = serde_json::json!({"groups": {
+            "03bb": {"assets": [
+                {"id": id, "tag": "usd", "amount": "700", "type": "NORMAL"},
+                {"id": id, "tag": "usd-2", "amount": "300", "type": "NORMAL"}
+            ]},
+            "02aa": {"assets": []}
+        }});
+
+        let groups = parse_asset_groups(&response);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group_key, "02aa");
+        assert_eq!(groups[0].balance, 0);
+        assert_eq!(groups[1].balance, 1_000);
+        assert_eq!(groups[1].assets[0].asset_id, "11".repeat(32));
+        assert_eq!(groups[1].assets[1].asset_type, Some(AssetType::Normal));
+        assert!(parse_asset_groups(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_mint_grouping() {
+        let group_key = format!("03{}", "cd".repeat(32));
+        let existing = MintGrouping {
+            group_key: Some(group_key),
+            ..Default::default()
+        };
+        assert!(existing.validate().is_ok());
+        let mut asset = serde_json::json!({"name": "usd"});
+        existing.apply(&mut asset);
+        assert_eq!(asset["grouped_asset"], true);
+        assert!(asset["group_key"].is_string());
+        assert!(asset.get("new_grouped_asset").is_none());
+
+        let anchored = MintGrouping {
+            group_anchor: Some("usd".to_string()),
+            ..Default::default()
+        };
+        let mut asset = serde_json::json!({});
+        anchored.apply(&mut asset);
+        assert_eq!(asset["group_anchor"], "usd");
+
+        let both = MintGrouping {
+            new_grouped_asset: true,
+            ..anchored
+        };
+        assert!(both.validate().is_err());
+        let bad_key = MintGrouping {
+            group_key: Some("02abcd".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_key.validate().is_err());
+    }
+
     #[test]
     fn test_asset_transfer_serialization() {
         let transfer = AssetTransfer {
             asset_id: "test_asset_id".to_string(),
             amount: 100,
+            amount_display: None,
             destination: "test_destination".to_string(),
             fee_rate: Some(5),
+            coin_selection: CoinSelection::default(),
+            label: None,
         };
 
         let json = serde_json::to_string(&transfer).unwrap();
@@ -172,8 +1589,11 @@ mod tests {
         let transfer = AssetTransfer {
             asset_id: "test_asset_id".to_string(),
             amount: 100,
+            amount_display: None,
             destination: "test_destination".to_string(),
             fee_rate: None,
+            coin_selection: CoinSelection::default(),
+            label: None,
         };
 
         let json = serde_json::to_string(&transfer).unwrap();
@@ -185,6 +1605,260 @@ mod tests {
         assert_eq!(deserialized.fee_rate, None);
     }
 
+    #[test]
+    fn test_send_preview_problems() {
+        let transfer = AssetTransfer {
+            asset_id: "ab".repeat(32),
+            amount: 100,
+            amount_display: None,
+            destination: "taprt1example".to_string(),
+            fee_rate: None,
+            coin_selection: CoinSelection::default(),
+            label: None,
+        };
+        let destination = crate::gateway::addresses::DecodedAddress {
+            encoded: "taprt1example".to_string(),
+            network: "regtest",
+            asset_id: "ab".repeat(32),
+            asset_type: None,
+            amount: 100,
+            group_key: None,
+            script_key: "02".repeat(33),
+            internal_key: "03".repeat(33),
+            proof_courier_addr: None,
+            asset_version: None,
+            address_version: None,
+        };
+        let fee = SendFeePreview::new(2, None);
+        assert_eq!(fee.estimated_fee_sat, 2 * SEND_ANCHOR_VBYTES);
+
+        let preview = SendPreview::new(&transfer, destination.clone(), 250, Some(fee));
+        assert!(preview.would_succeed);
+        assert_eq!(preview.remaining_balance, 150);
+
+        let short = SendPreview::new(&transfer, destination.clone(), 40, None);
+        assert!(!short.would_succeed);
+        assert_eq!(short.remaining_balance, 0);
+
+        let other = crate::gateway::addresses::DecodedAddress {
+            asset_id: "cd".repeat(32),
+            amount: 90,
+            ..destination
+        };
+        assert_eq!(SendPreview::new(&transfer, other, 250, None).problems.len(), 2);
+    }
+
+    #[test]
+    fn test_display_amounts() {
+        let transfer: AssetTransfer = serde_json::from_value(serde_json::json!({
+            "asset_id": "ab".repeat(32),
+            "amount_display": "1.50",
+            "destination": "taprt1example"
+        }))
+        .unwrap();
+        let fields = |transfer: &AssetTransfer| -> Vec<String> {
+            transfer.validate_fields().into_iter().filter_map(|e| e.field).collect()
+        };
+        assert!(!fields(&transfer).iter().any(|field| field.starts_with("amount")));
+
+        let zero = AssetTransfer {
+            amount_display: Some("0.00".to_string()),
+            ..transfer
+        };
+        assert!(fields(&zero).contains(&"amount_display".to_string()));
+
+        let now = Utc::now();
+        let transaction = Transaction {
+            id: Uuid::new_v4(),
+            tx_type: TransactionType::Send,
+            asset_id: Some("ab".repeat(32)),
+            amount: 150,
+            status: TransactionStatus::Pending,
+            created_at: now,
+            updated_at: now,
+            label: None,
+        };
+        let decimals = std::collections::HashMap::from([("ab".repeat(32), 2)]);
+        let shown = DisplayedTransaction::new(transaction, &decimals);
+        let shown = serde_json::to_value(shown).unwrap();
+        assert_eq!(shown["amount"], 150);
+        assert_eq!(shown["amount_display"], "1.50");
+    }
+
+    #[test]
+    fn test_version_info_is_stamped_at_build() {
+        let info = VersionInfo::build();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(info.build_timestamp.is_some());
+        assert!(info.features.is_empty() && info.tapd_version.is_none());
+    }
+
+    #[test]
+    fn test_search_params_match_assets_and_addresses() {
+        let params = |q: &str| SearchParams { q: q.to_string(), limit: Some(500) };
+        assert!(params(" x ").text().is_err());
+        assert_eq!(params(" usd ").text().unwrap(), "usd");
+        assert_eq!(params("usd").limit(), SEARCH_MAX_LIMIT);
+
+        let asset = TaprootAsset {
+            asset_id: "ab".repeat(32),
+            name: "TestUSD".to_string(),
+            balance: 1,
+            decimals: 2,
+            asset_type: AssetType::Normal,
+            meta_data: None,
+            favorite: false,
+            hidden: false,
+        };
+        assert!(params("usd").matches_asset(&asset));
+        assert!(params("ABAB").matches_asset(&asset));
+        assert!(!params("baba").matches_asset(&asset));
+    }
+
+    #[test]
+    fn test_check_label() {
+        assert_eq!(check_label(None), None);
+        assert_eq!(check_label(Some("Rent – March")), None);
+        assert!(check_label(Some("line\nbreak")).is_some());
+        assert!(check_label(Some(&"x".repeat(MAX_LABEL_LENGTH + 1))).is_some());
+    }
+
+    #[test]
+    fn test_transfer_batch_summary() {
+        let outcome = |index: usize, success: bool| TransferOutcome {
+            index,
+            destination: format!("taprt1dest{index}"),
+            success,
+            tx_id: success.then(|| "ab".repeat(32)),
+            error: (!success).then(|| "insufficient funds".to_string()),
+        };
+        let result = TransferBatchResult::new(vec![outcome(0, true), outcome(1, false)]);
+        assert_eq!(
+            result.summary,
+            TransferBatchSummary { total: 2, succeeded: 1, failed: 1 }
+        );
+    }
+
+    #[test]
+    fn test_mint_request_validation() {
+        let request: MintAssetRequest = serde_json::from_value(serde_json::json!({
+            "name": " USD ",
+            "amount": 100_000,
+            "decimal_display": 2,
+            "new_grouped_asset": true
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+        let asset = request.upstream_asset();
+        assert_eq!(asset["asset_type"], "NORMAL");
+        assert_eq!(asset["name"], "USD");
+        assert_eq!(asset["amount"], "100000");
+        assert_eq!(asset["decimal_display"], 2);
+        assert_eq!(asset["new_grouped_asset"], true);
+
+        // Required fields are no longer defaulted
+        let missing = serde_json::json!({"name": "USD"});
+        assert!(serde_json::from_value::<MintAssetRequest>(missing).is_err());
+
+        let invalid = [
+            serde_json::json!({"name": "", "amount": 1}),
+            serde_json::json!({"name": "x".repeat(65), "amount": 1}),
+            serde_json::json!({"name": "USD", "amount": 0}),
+            serde_json::json!({"name": "USD", "amount": 1, "decimal_display": 13}),
+            serde_json::json!({"name": "Punk", "amount": 2, "asset_type": "COLLECTIBLE"}),
+            serde_json::json!({"name": "USD", "amount": 1, "group_key": "02", "group_anchor": "A"}),
+        ];
+        for request in invalid {
+            let parsed: MintAssetRequest = serde_json::from_value(request.clone()).unwrap();
+            assert!(parsed.validate().is_err(), "{request}");
+        }
+    }
+
+    #[test]
+    fn test_batch_transfer_checks() {
+        use bitcoin::bech32;
+
+        let address = |seed: u8| {
+            let hrp = bech32::Hrp::parse("taprt").unwrap();
+            bech32::encode::<bech32::Bech32m>(hrp, &[seed; 100]).unwrap()
+        };
+        let recipient = |destination: String| TransferRecipient {
+            asset_id: "usd".to_string(),
+            amount: 10,
+            destination,
+        };
+        let batch = BatchTransfer {
+            recipients: vec![
+                recipient(address(1)),
+                recipient(address(2)),
+                recipient(address(1)),
+                recipient("bc1qnotanasset".to_string()),
+            ],
+            fee_rate: None,
+            coin_selection: CoinSelection::default(),
+            label: None,
+        };
+        assert!(batch.validate().is_ok());
+        let errors = batch.check_recipients();
+        assert_eq!(errors[..2], [None, None]);
+        assert_eq!(errors[2].as_deref(), Some("Duplicate destination in batch"));
+        assert!(errors[3].is_some());
+
+        let result = BatchTransferResult::rejected(&batch, errors);
+        let statuses: Vec<_> = result.recipients.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                RecipientStatus::Failed,
+                RecipientStatus::Failed,
+                RecipientStatus::Rejected,
+                RecipientStatus::Rejected
+            ]
+        );
+
+        let empty = BatchTransfer {
+            recipients: vec![],
+            fee_rate: None,
+            coin_selection: CoinSelection::default(),
+            label: None,
+        };
+        assert!(empty.validate().is_err());
+
+        let mut mixed = BatchTransfer {
+            recipients: vec![recipient(address(1)), recipient(address(2))],
+            fee_rate: None,
+            coin_selection: CoinSelection::LargestFirst,
+            label: None,
+        };
+        assert!(mixed.validate().is_ok());
+        mixed.recipients[1].asset_id = "eur".to_string();
+        assert!(mixed.validate().is_err());
+    }
+
+    #[test]
+    fn test_coin_selection_deserialization() {
+        let transfer: AssetTransfer = serde_json::from_value(serde_json::json!({
+            "asset_id": "usd",
+            "amount": 10,
+            "destination": "taprt1",
+            "fee_rate": null,
+            "coin_selection": {"strategy": "outpoints", "outpoints": ["ab:0"]}
+        }))
+        .unwrap();
+        assert_eq!(
+            transfer.coin_selection,
+            CoinSelection::Outpoints {
+                outpoints: vec!["ab:0".to_string()]
+            }
+        );
+
+        let default: AssetTransfer = serde_json::from_value(serde_json::json!({
+            "asset_id": "usd", "amount": 10, "destination": "taprt1", "fee_rate": 5
+        }))
+        .unwrap();
+        assert_eq!(default.coin_selection, CoinSelection::Auto);
+    }
+
     #[test]
     fn test_asset_invoice_serialization() {
         let invoice = AssetInvoice {
@@ -232,6 +1906,7 @@ mod tests {
             status: TransactionStatus::Pending,
             created_at: now,
             updated_at: now,
+            label: None,
         };
 
         let json = serde_json::to_string(&transaction).unwrap();
@@ -254,6 +1929,7 @@ mod tests {
             status: TransactionStatus::Confirmed,
             created_at: now,
             updated_at: now,
+            label: None,
         };
 
         let json = serde_json::to_string(&transaction).unwrap();
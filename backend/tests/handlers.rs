@@ -0,0 +1,396 @@
+//! Handler tests against the full router, with every store in memory and
+//! tapd pointed at a closed port
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::{middleware, Router};
+use chrono::Utc;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use taproot_backend::api::routes;
+use taproot_backend::config::MailboxSettings;
+use taproot_backend::gateway::audit::record_audit_entry;
+use taproot_backend::gateway::mailbox_limits::MailboxLimiter;
+use taproot_backend::gateway::routes::create_taproot_routes;
+use taproot_backend::storage::balance_snapshots::{BalanceSnapshot, SnapshotGranularity};
+use taproot_backend::storage::invoices::InvoiceRecord;
+use taproot_backend::types::{AppState, Transaction, TransactionStatus, TransactionType};
+
+/// The secp256k1 generator point, a valid compressed public key
+const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+fn app(state: AppState) -> Router {
+    Router::new()
+        .nest("/api", routes::create_routes())
+        .merge(create_taproot_routes())
+        .layer(middleware::from_fn_with_state(state.clone(), record_audit_entry))
+        .with_state(state)
+}
+
+fn state() -> AppState {
+    AppState::in_memory("http://127.0.0.1:9").unwrap()
+}
+
+/// A state whose admin API accepts `Bearer admin-token`
+fn admin_state() -> AppState {
+    let mut state = state();
+    let settings = MailboxSettings {
+        admin_token: Some("admin-token".to_string()),
+        ..MailboxSettings::default()
+    };
+    state.mailbox_limiter = std::sync::Arc::new(MailboxLimiter::new(settings));
+    state
+}
+
+async fn admin_post(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::post(uri)
+        .header("authorization", "Bearer admin-token")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = app.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+#[tokio::test]
+async fn test_mailbox_receiver_lifecycle() {
+    let app = app(state());
+    let uri = "/v1/taproot-assets/mailbox/receivers";
+    let receiver = json!({"receiver_id": "receiver-01", "public_key": PUBLIC_KEY});
+
+    let (status, body) = call(&app, Method::POST, uri, Some(receiver.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["receiver_id"], "receiver-01");
+    let (status, _) = call(&app, Method::POST, uri, Some(receiver)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let receiver_uri = format!("{uri}/receiver-01");
+    let (status, body) = call(&app, Method::GET, &receiver_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["public_key"], PUBLIC_KEY);
+
+    // Deleting takes a signature from the registered key over a fresh challenge
+    let (status, _) = call(&app, Method::DELETE, &receiver_uri, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let challenge_uri = format!("{receiver_uri}/challenge");
+    let (_, challenge) = call(&app, Method::POST, &challenge_uri, None).await;
+    let forged = json!({"challenge_id": challenge["challenge_id"], "signature": "00".repeat(64)});
+    let (status, _) = call(&app, Method::DELETE, &receiver_uri, Some(forged)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (_, challenge) = call(&app, Method::POST, &challenge_uri, None).await;
+    let proof = json!({
+        "challenge_id": challenge["challenge_id"],
+        "signature": sign(challenge["message"].as_str().unwrap()),
+    });
+    let (status, _) = call(&app, Method::DELETE, &receiver_uri, Some(proof)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = call(&app, Method::GET, &receiver_uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_operators_delete_receivers_with_the_admin_token() {
+    let app = app(admin_state());
+    let uri = "/v1/taproot-assets/mailbox/receivers";
+    let receiver = json!({"receiver_id": "receiver-01", "public_key": PUBLIC_KEY});
+    call(&app, Method::POST, uri, Some(receiver)).await;
+
+    let request = Request::delete(format!("{uri}/receiver-01"))
+        .header("authorization", "Bearer admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+/// ECDSA signature over SHA-256 of `message` by the secret key behind
+/// [`PUBLIC_KEY`], as mailbox clients sign challenges
+fn sign(message: &str) -> String {
+    let mut secret = [0u8; 32];
+    secret[31] = 1;
+    let secret_key = secp256k1::SecretKey::from_slice(&secret).unwrap();
+    let digest: [u8; 32] = <sha2::Sha256 as sha2::Digest>::digest(message.as_bytes()).into();
+    let message = secp256k1::Message::from_digest(digest);
+    let signature = secp256k1::Secp256k1::new().sign_ecdsa(&message, &secret_key);
+    hex::encode(signature.serialize_compact())
+}
+
+#[tokio::test]
+async fn test_transactions_come_from_the_repo() {
+    let state = state();
+    let now = Utc::now();
+    let transaction = Transaction {
+        id: uuid::Uuid::new_v4(),
+        tx_type: TransactionType::Send,
+        asset_id: Some("ab".repeat(32)),
+        amount: 42,
+        status: TransactionStatus::Confirmed,
+        created_at: now,
+        updated_at: now,
+        label: Some("rent".to_string()),
+    };
+    state.transaction_store.insert(transaction.clone(), None).await.unwrap();
+    let app = app(state);
+
+    let (status, body) = call(&app, Method::GET, "/api/transactions", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 1);
+    assert_eq!(body["data"]["transactions"][0]["id"], transaction.id.to_string());
+    assert_eq!(body["data"]["transactions"][0]["label"], "rent");
+
+    let uri = "/api/transactions?from=2024-01-02T00:00:00Z&to=2024-01-01T00:00:00Z";
+    let (status, body) = call(&app, Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+}
+
+#[tokio::test]
+async fn test_invoices_come_from_the_repo() {
+    let state = state();
+    let invoice = InvoiceRecord {
+        payment_hash: "cd".repeat(32),
+        payment_request: "lnbcrt1invoice".to_string(),
+        asset_id: "ab".repeat(32),
+        asset_amount: 250,
+        label: None,
+        created_at: 1_700_000_000,
+    };
+    state.invoice_repo.insert(&invoice).await.unwrap();
+    let app = app(state);
+
+    let uri = "/v1/taproot-assets/channels/channels/invoices";
+    let (status, body) = call(&app, Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!([invoice]));
+}
+
+#[tokio::test]
+async fn test_asset_preferences_round_trip() {
+    let app = app(state());
+    let asset_id = "ab".repeat(32);
+
+    let uri = format!("/api/assets/{asset_id}/preferences?profile=alice");
+    let (status, body) = call(&app, Method::PUT, &uri, Some(json!({"favorite": true}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["favorite"], true);
+    assert_eq!(body["data"]["hidden"], false);
+
+    let uri = "/api/assets/preferences?profile=alice";
+    let (status, body) = call(&app, Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["asset_id"], asset_id);
+    let (_, body) = call(&app, Method::GET, "/api/assets/preferences", None).await;
+    assert_eq!(body["data"], json!([]));
+}
+
+#[tokio::test]
+async fn test_balance_history_comes_from_the_store() {
+    let state = state();
+    let snapshot = BalanceSnapshot {
+        asset_id: "ab".repeat(32),
+        granularity: SnapshotGranularity::Daily,
+        bucket_start: 86_400,
+        balance: 1_000,
+        recorded_at: 90_000,
+    };
+    state.balance_snapshots.record(std::slice::from_ref(&snapshot)).await.unwrap();
+    let app = app(state);
+
+    let uri = format!("/api/assets/balance/history?asset_id={}&granularity=daily", "ab".repeat(32));
+    let (status, body) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!([snapshot]));
+
+    let uri = uri.replace("daily", "hourly");
+    let (_, body) = call(&app, Method::GET, &uri, None).await;
+    assert_eq!(body["data"], json!([]));
+    let (status, _) = call(&app, Method::GET, "/api/assets/balance/history", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_settings_round_trip() {
+    let app = app(state());
+
+    let uri = "/api/settings/display_currency";
+    let (status, _) = call(&app, Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let value = json!({"code": "EUR", "decimals": 2});
+    let (status, body) = call(&app, Method::PUT, uri, Some(json!({"value": value}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["key"], "display_currency");
+
+    let (status, body) = call(&app, Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["value"], value);
+    let (_, body) = call(&app, Method::GET, "/api/settings", None).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+
+    let (status, _) = call(&app, Method::PUT, "/api/settings/Fee%20Rate", Some(json!({"value": 1})))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_send_batch_is_served_at_its_documented_path() {
+    let app = app(state());
+
+    let (status, body) = call(&app, Method::POST, "/api/assets/send-batch", Some(json!([]))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].as_str().unwrap().contains("between 1 and"));
+}
+
+#[tokio::test]
+async fn test_webhooks_require_the_admin_token() {
+    let app = app(admin_state());
+    let hook = json!({"url": "https://example.com/hooks", "events": ["transaction.confirmed"]});
+
+    let (status, _) = call(&app, Method::POST, "/api/webhooks", Some(hook.clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = call(&app, Method::GET, "/api/webhooks", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = admin_post(&app, "/api/webhooks", hook).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = body["data"]["id"].as_str().unwrap();
+    let uri = format!("/api/webhooks/{id}");
+    let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = call(&app, Method::POST, &format!("{uri}/test"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let internal = json!({"url": "https://169.254.169.254/hooks", "events": ["balance.changed"]});
+    let (status, _) = admin_post(&app, "/api/webhooks", internal).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_sensitive_requests_are_audited() {
+    let mut state = state();
+    let settings = MailboxSettings {
+        admin_token: Some("admin-token".to_string()),
+        ..MailboxSettings::default()
+    };
+    state.mailbox_limiter = std::sync::Arc::new(MailboxLimiter::new(settings));
+    let app = app(state);
+
+    let burn = json!({
+        "asset_id": "ab".repeat(32),
+        "amount_to_burn": "5",
+        "confirmation_text": "assets will be destroyed",
+    });
+    let (status, _) = call(&app, Method::POST, "/v1/taproot-assets/burn", Some(burn)).await;
+    assert!(!status.is_success());
+    call(&app, Method::GET, "/api/assets", None).await;
+
+    let request = Request::get("/admin/audit?action=asset.burn")
+        .header("authorization", "Bearer admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let entries: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["actor"], "anonymous");
+    assert_eq!(entries[0]["result"], "failure");
+    assert_eq!(entries[0]["summary"]["amount_to_burn"], "5");
+
+    let (status, _) = call(&app, Method::GET, "/admin/audit", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_event_routes_are_served_under_a_single_prefix() {
+    let app = app(state());
+
+    let (status, body) = call(&app, Method::GET, "/events/history", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["events"], json!([]));
+    assert_eq!(body["next_cursor"], 0);
+
+    // Streams never finish, so only the status is read; sockets reject a plain
+    // GET, which still proves the route exists
+    for uri in ["/events/stream", "/events/blocks", "/events/asset-mint/sse"] {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_ne!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
+
+    let (status, _) = call(&app, Method::GET, "/events/events/history", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_readiness_reports_each_dependency() {
+    let app = app(state());
+
+    let (status, body) = call(&app, Method::GET, "/readiness", None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(body["services"]["taproot_assets"]["status"], "down");
+    assert_eq!(body["services"]["taproot_assets"]["required"], true);
+    assert!(body["services"]["taproot_assets"]["latency_ms"].is_u64());
+    assert_eq!(body["services"]["database"]["status"], "not_configured");
+    assert_eq!(body["services"]["lnd"]["status"], "not_configured");
+}
+
+#[tokio::test]
+async fn test_export_imports_into_another_gateway() {
+    let source = admin_state();
+    let now = Utc::now();
+    let transaction = Transaction {
+        id: uuid::Uuid::new_v4(),
+        tx_type: TransactionType::Receive,
+        asset_id: Some("ab".repeat(32)),
+        amount: 7,
+        status: TransactionStatus::Confirmed,
+        created_at: now,
+        updated_at: now,
+        label: None,
+    };
+    source.transaction_store.insert(transaction.clone(), None).await.unwrap();
+    let source = app(source);
+    let uri = "/api/settings/display_currency";
+    call(&source, Method::PUT, uri, Some(json!({"value": "EUR"}))).await;
+
+    let passphrase = json!({"passphrase": "a passphrase of at least 32 characters"});
+    let (status, _) = call(&source, Method::POST, "/admin/export", Some(passphrase.clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, export) = admin_post(&source, "/admin/export", passphrase.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(export["transactions"], 1);
+
+    let target = app(admin_state());
+    let mut import = passphrase;
+    import["archive"] = export["archive"].clone();
+    let (status, report) = admin_post(&target, "/admin/import", import).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["transactions_imported"], 1);
+    assert_eq!(report["settings_restored"], 1);
+
+    let (_, body) = call(&target, Method::GET, "/api/transactions", None).await;
+    assert_eq!(body["data"]["transactions"][0]["id"], transaction.id.to_string());
+    let (_, body) = call(&target, Method::GET, uri, None).await;
+    assert_eq!(body["data"]["value"], "EUR");
+}
@@ -24,6 +24,13 @@ export interface AssetInvoice {
   expiry?: number;
 }
 
+export interface AssetPage {
+  assets: TaprootAsset[];
+  total: number;
+  matched: number;
+  next_offset?: number | null;
+}
+
 export interface ApiResponse<T> {
   success: boolean;
   data?: T;
@@ -41,10 +48,10 @@ export class TaprootService {
   async listAssets(): Promise<TaprootAsset[]> {
     try {
       const response = await fetch(`${this.baseUrl}/assets`);
-      const result: ApiResponse<TaprootAsset[]> = await response.json();
+      const result: ApiResponse<AssetPage> = await response.json();
       
       if (result.success && result.data) {
-        return result.data;
+        return result.data.assets;
       } else {
         throw new Error(result.error || 'Failed to fetch assets');
       }